@@ -1,8 +1,14 @@
 // This crate was essentially pulled out verbatim from main `zed` crate to avoid having to run RustEmbed macro whenever zed has to be rebuilt. It saves a second or two on an incremental build.
 use anyhow::anyhow;
 
+use collections::HashMap;
 use gpui::{AppContext, AssetSource, Result, SharedString};
+use parking_lot::RwLock;
 use rust_embed::RustEmbed;
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
@@ -16,8 +22,16 @@ use rust_embed::RustEmbed;
 #[exclude = "*.DS_Store"]
 pub struct Assets;
 
+/// The contents of the icons of the user-installed icon themes, by path. They're read ahead
+/// of time, since assets are loaded while rendering.
+static USER_ICONS: LazyLock<RwLock<HashMap<PathBuf, Arc<[u8]>>>> = LazyLock::new(Default::default);
+
 impl AssetSource for Assets {
     fn load(&self, path: &str) -> Result<Option<std::borrow::Cow<'static, [u8]>>> {
+        if let Some(icon) = USER_ICONS.read().get(Path::new(path)) {
+            return Ok(Some(icon.to_vec().into()));
+        }
+
         Self::get(path)
             .map(|f| Some(f.data))
             .ok_or_else(|| anyhow!("could not find asset at path \"{}\"", path))
@@ -37,6 +51,24 @@ impl AssetSource for Assets {
 }
 
 impl Assets {
+    /// Sets the contents of an icon of a user-installed icon theme, or removes it.
+    pub fn set_user_icon(path: PathBuf, content: Option<Vec<u8>>) {
+        let mut user_icons = USER_ICONS.write();
+        match content {
+            Some(content) => {
+                user_icons.insert(path, content.into());
+            }
+            None => {
+                user_icons.remove(&path);
+            }
+        }
+    }
+
+    /// Removes the icons of user-installed icon themes that aren't used anymore.
+    pub fn retain_user_icons(mut used: impl FnMut(&Path) -> bool) {
+        USER_ICONS.write().retain(|path, _| used(path));
+    }
+
     /// Populate the [`TextSystem`] of the given [`AppContext`] with all `.ttf` fonts in the `fonts` directory.
     pub fn load_fonts(&self, cx: &AppContext) -> gpui::Result<()> {
         let font_paths = self.list("fonts")?;
@@ -15,6 +15,7 @@ mod streaming_diff;
 mod terminal_inline_assistant;
 mod workflow;
 
+use anyhow::Result;
 pub use assistant_panel::{AssistantPanel, AssistantPanelEvent};
 use assistant_settings::AssistantSettings;
 use assistant_slash_command::SlashCommandRegistry;
@@ -26,7 +27,9 @@ pub use context_store::*;
 use feature_flags::FeatureFlagAppExt;
 use fs::Fs;
 use gpui::Context as _;
-use gpui::{actions, impl_actions, AppContext, Global, SharedString, UpdateGlobal};
+use gpui::{
+    actions, impl_actions, AppContext, AsyncAppContext, Global, SharedString, Task, UpdateGlobal,
+};
 use indexed_docs::IndexedDocsRegistry;
 pub(crate) use inline_assistant::*;
 use language_model::{
@@ -35,7 +38,10 @@ use language_model::{
 pub(crate) use model_selector::*;
 pub use prompts::PromptBuilder;
 use prompts::PromptLoadingParams;
-use semantic_index::{CloudEmbeddingProvider, SemanticIndex};
+use semantic_index::{
+    EmbeddingProviderName, HttpReranker, Reranker, RerankingSettings, SemanticIndex,
+    SemanticIndexSettings,
+};
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsStore};
 use slash_command::{
@@ -45,7 +51,7 @@ use slash_command::{
 };
 use std::sync::Arc;
 pub(crate) use streaming_diff::*;
-use util::ResultExt;
+use util::{ResultExt, TryFutureExt};
 pub use workflow::*;
 
 use crate::slash_command_settings::SlashCommandSettings;
@@ -190,6 +196,8 @@ pub fn init(
     cx.set_global(Assistant::default());
     AssistantSettings::register(cx);
     SlashCommandSettings::register(cx);
+    SemanticIndexSettings::register(cx);
+    semantic_index::init(cx);
 
     // TODO: remove this when 0.148.0 is released.
     if AssistantSettings::get_global(cx).using_outdated_settings_version {
@@ -201,20 +209,7 @@ pub fn init(
         });
     }
 
-    cx.spawn(|mut cx| {
-        let client = client.clone();
-        async move {
-            let embedding_provider = CloudEmbeddingProvider::new(client.clone());
-            let semantic_index = SemanticIndex::new(
-                paths::embeddings_dir().join("semantic-index-db.0.mdb"),
-                Arc::new(embedding_provider),
-                &mut cx,
-            )
-            .await?;
-            cx.update(|cx| cx.set_global(semantic_index))
-        }
-    })
-    .detach();
+    init_semantic_index(client.clone(), cx);
 
     context_store::init(&client);
     prompt_library::init(cx);
@@ -327,6 +322,102 @@ fn register_context_server_handlers(cx: &mut AppContext) {
     .detach();
 }
 
+/// Loads the semantic index with the embedding provider selected in the settings, and
+/// loads it again when the provider changes.
+fn init_semantic_index(client: Arc<Client>, cx: &mut AppContext) {
+    let mut embedding_settings = None;
+    let mut reranking_settings = None;
+    let mut load_index: Option<Task<Option<()>>> = None;
+    let mut settings_changed = move |cx: &mut AppContext| {
+        let settings = SemanticIndexSettings::get_global(cx).clone();
+        let new_embedding_settings = Some((settings.db_name(), settings.openai.api_url.clone()));
+        let new_reranking_settings = Some(settings.reranking.clone());
+        if new_embedding_settings == embedding_settings
+            && new_reranking_settings == reranking_settings
+        {
+            return;
+        }
+        // Only the reranker is replaced when the embedding provider stays the same, so that
+        // the projects' indices are kept.
+        let reload_index =
+            new_embedding_settings != embedding_settings || !cx.has_global::<SemanticIndex>();
+        embedding_settings = new_embedding_settings;
+        reranking_settings = new_reranking_settings;
+
+        let client = client.clone();
+        load_index.replace(cx.spawn(|mut cx| {
+            async move {
+                let reranker = reranker(&settings.reranking, &client, &mut cx).await?;
+                if !reload_index {
+                    return cx.update(|cx| {
+                        cx.update_global::<SemanticIndex, _>(|semantic_index, _| {
+                            semantic_index.set_reranker(reranker)
+                        })
+                    });
+                }
+
+                let openai_api_key = if settings.embedding_provider == EmbeddingProviderName::OpenAi
+                {
+                    openai_api_key(&settings.openai.api_url, &mut cx)
+                        .await
+                        .log_err()
+                        .flatten()
+                } else {
+                    None
+                };
+                let (embedding_provider, db_name) =
+                    settings.create_embedding_provider(client, openai_api_key);
+                let mut semantic_index = SemanticIndex::new(
+                    paths::embeddings_dir().join(db_name),
+                    embedding_provider,
+                    &mut cx,
+                )
+                .await?;
+                semantic_index.set_reranker(reranker);
+                cx.update(|cx| cx.set_global(semantic_index))
+            }
+            .log_err()
+        }));
+    };
+    settings_changed(cx);
+    cx.observe_global::<SettingsStore>(settings_changed)
+        .detach();
+}
+
+/// Reads the OpenAI API key for embeddings from the `OPENAI_API_KEY` environment variable,
+/// or else from the key saved for the API's URL.
+async fn openai_api_key(api_url: &str, cx: &mut AsyncAppContext) -> Result<Option<String>> {
+    if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
+        return Ok(Some(api_key));
+    }
+    let credentials = cx.update(|cx| cx.read_credentials(api_url))?.await?;
+    Ok(credentials
+        .map(|(_, api_key)| String::from_utf8(api_key))
+        .transpose()?)
+}
+
+async fn reranker(
+    settings: &RerankingSettings,
+    client: &Client,
+    cx: &mut AsyncAppContext,
+) -> Result<Option<Arc<dyn Reranker>>> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let api_key = cx
+        .update(|cx| cx.read_credentials(&settings.api_url))?
+        .await
+        .log_err()
+        .flatten()
+        .and_then(|(_, api_key)| String::from_utf8(api_key).log_err());
+    Ok(Some(Arc::new(HttpReranker::new(
+        client.http_client(),
+        settings.api_url.clone(),
+        settings.model.clone(),
+        api_key,
+    ))))
+}
+
 fn init_language_model_settings(cx: &mut AppContext) {
     update_active_language_model_from_settings(cx);
 
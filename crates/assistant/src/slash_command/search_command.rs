@@ -8,14 +8,16 @@ use assistant_slash_command::{ArgumentCompletion, SlashCommandOutputSection};
 use feature_flags::FeatureFlag;
 use gpui::{AppContext, Task, WeakView};
 use language::{CodeLabel, LineEnding, LspAdapterDelegate};
-use semantic_index::SemanticIndex;
+use semantic_index::{SearchFilter, SemanticIndex, SemanticIndexSettings};
+use settings::Settings;
 use std::{
     fmt::Write,
     path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
+    time::Duration,
 };
 use ui::{prelude::*, IconName};
-use util::ResultExt;
+use util::{paths::PathMatcher, ResultExt};
 use workspace::Workspace;
 
 pub(crate) struct SearchSlashCommandFeatureFlag;
@@ -72,6 +74,10 @@ impl SlashCommand for SearchSlashCommand {
         };
 
         let mut limit = None;
+        let mut filter = SearchFilter {
+            max_results_per_file: SemanticIndexSettings::get_global(cx).max_results_per_file,
+            ..Default::default()
+        };
         let mut query = String::new();
         for part in arguments {
             if let Some(parameter) = part.strip_prefix("--") {
@@ -79,6 +85,35 @@ impl SlashCommand for SearchSlashCommand {
                     limit = Some(count);
                     continue;
                 }
+                if let Some((name, value)) = parameter.split_once('=') {
+                    match name {
+                        "language" => {
+                            filter.languages.push(value.to_string());
+                            continue;
+                        }
+                        "path" => match PathMatcher::new(&[value.to_string()]) {
+                            Ok(path) => {
+                                filter.path = Some(path);
+                                continue;
+                            }
+                            Err(error) => return Task::ready(Err(error.into())),
+                        },
+                        "per-file" => {
+                            if let Ok(count) = value.parse() {
+                                filter.max_results_per_file = Some(count);
+                                continue;
+                            }
+                        }
+                        "days" => {
+                            if let Ok(days) = value.parse::<u64>() {
+                                filter.modified_within =
+                                    Some(Duration::from_secs(days * 24 * 60 * 60));
+                                continue;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
             }
 
             query.push_str(part);
@@ -98,7 +133,7 @@ impl SlashCommand for SearchSlashCommand {
         cx.spawn(|cx| async move {
             let results = project_index
                 .read_with(&cx, |project_index, cx| {
-                    project_index.search(query.clone(), limit.unwrap_or(5), cx)
+                    project_index.search(query.clone(), limit.unwrap_or(5), filter, cx)
                 })?
                 .await?;
 
@@ -1,12 +1,13 @@
-use editor::Editor;
+use editor::{scroll::Autoscroll, Anchor, AnchorRangeExt, Editor};
 use gpui::{
-    Element, EventEmitter, IntoElement, ParentElement, Render, StyledText, Subscription,
-    ViewContext,
+    anchored, deferred, AnchorCorner, AppContext, ClickEvent, DismissEvent, Element, EventEmitter,
+    IntoElement, ParentElement, Pixels, Point, Render, StyledText, Subscription, View, ViewContext,
 };
 use itertools::Itertools;
-use std::cmp;
+use project::ProjectPath;
+use std::{cmp, path::Path};
 use theme::ActiveTheme;
-use ui::{prelude::*, ButtonLike, ButtonStyle, Label, Tooltip};
+use ui::{prelude::*, ButtonLike, ButtonStyle, ContextMenu, IconPosition, Label, Tooltip};
 use workspace::{
     item::{BreadcrumbText, ItemEvent, ItemHandle},
     ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
@@ -16,6 +17,7 @@ pub struct Breadcrumbs {
     pane_focused: bool,
     active_item: Option<Box<dyn ItemHandle>>,
     subscription: Option<Subscription>,
+    segment_menu: Option<(View<ContextMenu>, Point<Pixels>, Subscription)>,
 }
 
 impl Breadcrumbs {
@@ -24,8 +26,173 @@ impl Breadcrumbs {
             pane_focused: false,
             active_item: Default::default(),
             subscription: Default::default(),
+            segment_menu: None,
         }
     }
+
+    /// Opens a menu for lateral navigation from the given breadcrumb segment: the files
+    /// next to the editor's file for the path segment, and the symbols next to the given
+    /// symbol, within the same container, for a symbol segment.
+    fn deploy_segment_menu(
+        &mut self,
+        editor: View<Editor>,
+        segment_ix: usize,
+        position: Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let menu = match segment_ix.checked_sub(1) {
+            None => sibling_files_menu(&editor, cx),
+            Some(symbol_ix) => sibling_symbols_menu(&editor, symbol_ix, cx),
+        };
+        let Some(menu) = menu else {
+            return;
+        };
+
+        cx.focus_view(&menu);
+        let subscription = cx.subscribe(&menu, move |this, _, _: &DismissEvent, cx| {
+            this.segment_menu.take();
+            cx.notify();
+        });
+        self.segment_menu = Some((menu, position, subscription));
+        cx.notify();
+    }
+}
+
+/// The files in the same directory as the editor's file, with whether each of them is the
+/// editor's file.
+fn sibling_files(editor: &View<Editor>, cx: &AppContext) -> Vec<(String, ProjectPath, bool)> {
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return Vec::new();
+    };
+    let Some(project_path) = editor.project_path(cx) else {
+        return Vec::new();
+    };
+    let Some(worktree) = workspace
+        .read(cx)
+        .project()
+        .read(cx)
+        .worktree_for_id(project_path.worktree_id, cx)
+    else {
+        return Vec::new();
+    };
+    let directory = project_path.path.parent().unwrap_or(Path::new(""));
+    worktree
+        .read(cx)
+        .child_entries(directory)
+        .filter(|entry| entry.is_file())
+        .filter_map(|entry| {
+            let file_name = entry.path.file_name()?.to_string_lossy().to_string();
+            let is_current = entry.path == project_path.path;
+            let sibling_path = ProjectPath {
+                worktree_id: project_path.worktree_id,
+                path: entry.path.clone(),
+            };
+            Some((file_name, sibling_path, is_current))
+        })
+        .collect()
+}
+
+/// The symbols at the same depth as the given symbol containing the cursor, and within
+/// the same container, with whether each of them is the given symbol.
+fn sibling_symbols(
+    editor: &View<Editor>,
+    symbol_ix: usize,
+    cx: &AppContext,
+) -> Vec<(String, Anchor, bool)> {
+    let editor = editor.read(cx);
+    let cursor = editor.selections.newest_anchor().head();
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let Some((_, symbols)) = snapshot.symbols_containing(cursor, None) else {
+        return Vec::new();
+    };
+    let Some(symbol) = symbols.get(symbol_ix) else {
+        return Vec::new();
+    };
+    let container = symbol_ix
+        .checked_sub(1)
+        .and_then(|container_ix| symbols.get(container_ix))
+        .map(|container| container.range.to_offset(&snapshot));
+    let symbol_range = symbol.range.to_offset(&snapshot);
+
+    let Some(outline) = snapshot.outline(None) else {
+        return Vec::new();
+    };
+    outline
+        .items
+        .into_iter()
+        .filter(|item| item.depth == symbol.depth)
+        .filter_map(|item| {
+            let range = item.range.to_offset(&snapshot);
+            if let Some(container) = &container {
+                if range.start < container.start || range.end > container.end {
+                    return None;
+                }
+            }
+            Some((item.text, item.range.start, range == symbol_range))
+        })
+        .collect()
+}
+
+fn sibling_files_menu(
+    editor: &View<Editor>,
+    cx: &mut ViewContext<Breadcrumbs>,
+) -> Option<View<ContextMenu>> {
+    let siblings = sibling_files(editor, cx);
+    if siblings.is_empty() {
+        return None;
+    }
+
+    let workspace = editor.read(cx).workspace()?.downgrade();
+    Some(ContextMenu::build(cx, |menu, _| {
+        siblings
+            .into_iter()
+            .fold(menu, |menu, (file_name, project_path, is_current)| {
+                let workspace = workspace.clone();
+                menu.toggleable_entry(file_name, is_current, IconPosition::Start, None, {
+                    move |cx| {
+                        workspace
+                            .update(cx, |workspace, cx| {
+                                workspace
+                                    .open_path(project_path.clone(), None, true, cx)
+                                    .detach_and_log_err(cx);
+                            })
+                            .ok();
+                    }
+                })
+            })
+    }))
+}
+
+fn sibling_symbols_menu(
+    editor: &View<Editor>,
+    symbol_ix: usize,
+    cx: &mut ViewContext<Breadcrumbs>,
+) -> Option<View<ContextMenu>> {
+    let siblings = sibling_symbols(editor, symbol_ix, cx);
+    if siblings.is_empty() {
+        return None;
+    }
+
+    let editor = editor.downgrade();
+    Some(ContextMenu::build(cx, |menu, _| {
+        siblings
+            .into_iter()
+            .fold(menu, |menu, (text, position, is_current)| {
+                let editor = editor.clone();
+                menu.toggleable_entry(text, is_current, IconPosition::Start, None, {
+                    move |cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                    s.select_ranges([position..position])
+                                });
+                                editor.focus(cx);
+                            })
+                            .ok();
+                    }
+                })
+            })
+    }))
 }
 
 impl EventEmitter<ToolbarItemEvent> for Breadcrumbs {}
@@ -46,6 +213,7 @@ impl Render for Breadcrumbs {
             prefix_end_ix,
             segments.len().saturating_sub(MAX_SEGMENTS / 2),
         );
+        let mut segment_indices = (0..segments.len()).map(Some).collect::<Vec<_>>();
         if suffix_start_ix > prefix_end_ix {
             segments.splice(
                 prefix_end_ix..suffix_start_ix,
@@ -55,31 +223,57 @@ impl Render for Breadcrumbs {
                     font: None,
                 }),
             );
+            segment_indices.splice(prefix_end_ix..suffix_start_ix, Some(None));
         }
 
-        let highlighted_segments = segments.into_iter().map(|segment| {
-            let mut text_style = cx.text_style();
-            if let Some(font) = segment.font {
-                text_style.font_family = font.family;
-                text_style.font_features = font.features;
-                text_style.font_style = font.style;
-                text_style.font_weight = font.weight;
-            }
-            text_style.color = Color::Muted.color(cx);
+        let editor = active_item.downcast::<Editor>();
+        let highlighted_segments =
+            segments
+                .into_iter()
+                .zip(segment_indices)
+                .map(|(segment, segment_ix)| {
+                    let mut text_style = cx.text_style();
+                    if let Some(font) = segment.font {
+                        text_style.font_family = font.family;
+                        text_style.font_features = font.features;
+                        text_style.font_style = font.style;
+                        text_style.font_weight = font.weight;
+                    }
+                    text_style.color = Color::Muted.color(cx);
 
-            StyledText::new(segment.text.replace('\n', "␤"))
-                .with_highlights(&text_style, segment.highlights.unwrap_or_default())
-                .into_any()
-        });
+                    let text = StyledText::new(segment.text.replace('\n', "␤"))
+                        .with_highlights(&text_style, segment.highlights.unwrap_or_default());
+                    match editor.clone().zip(segment_ix) {
+                        Some((editor, segment_ix)) => ButtonLike::new(("breadcrumb", segment_ix))
+                            .child(text)
+                            .style(ButtonStyle::Transparent)
+                            .on_click(cx.listener(move |this, event: &ClickEvent, cx| {
+                                // Segments open their own menu instead of the outline.
+                                cx.stop_propagation();
+                                this.deploy_segment_menu(
+                                    editor.clone(),
+                                    segment_ix,
+                                    event.down.position,
+                                    cx,
+                                )
+                            }))
+                            .tooltip(move |cx| {
+                                if segment_ix == 0 {
+                                    Tooltip::text("Show files in this directory", cx)
+                                } else {
+                                    Tooltip::text("Show symbols in this scope", cx)
+                                }
+                            })
+                            .into_any_element(),
+                        None => text.into_any(),
+                    }
+                });
         let breadcrumbs = Itertools::intersperse_with(highlighted_segments, || {
             Label::new("›").color(Color::Placeholder).into_any_element()
         });
 
         let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
-        match active_item
-            .downcast::<Editor>()
-            .map(|editor| editor.downgrade())
-        {
+        match editor.map(|editor| editor.downgrade()) {
             Some(editor) => element.child(
                 ButtonLike::new("toggle outline view")
                     .child(breadcrumbs_stack)
@@ -102,6 +296,15 @@ impl Render for Breadcrumbs {
                 .h(rems_from_px(22.))
                 .child(breadcrumbs_stack),
         }
+        .children(self.segment_menu.as_ref().map(|(menu, position, _)| {
+            deferred(
+                anchored()
+                    .position(*position)
+                    .anchor(AnchorCorner::TopLeft)
+                    .child(menu.clone()),
+            )
+            .with_priority(1)
+        }))
     }
 }
 
@@ -113,6 +316,7 @@ impl ToolbarItemView for Breadcrumbs {
     ) -> ToolbarItemLocation {
         cx.notify();
         self.active_item = None;
+        self.segment_menu = None;
 
         let Some(item) = active_pane_item else {
             return ToolbarItemLocation::Hidden;
@@ -143,3 +347,131 @@ impl ToolbarItemView for Breadcrumbs {
         self.pane_focused = pane_focused;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::sync::Arc;
+    use workspace::{AppState, Workspace};
+
+    #[gpui::test]
+    async fn test_segment_siblings(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": indoc! {"
+                    struct A {
+                        one: i32,
+                        two: i32,
+                    }
+                    struct B {
+                        three: i32,
+                    }
+                "},
+                "b.rs": "",
+                "sub": { "c.rs": "" },
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.rs"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        cx.run_until_parked();
+
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([language::Point::new(2, 4)..language::Point::new(2, 4)])
+            })
+        });
+
+        cx.update(|cx| {
+            let files = sibling_files(&editor, cx)
+                .into_iter()
+                .map(|(name, _, is_current)| (name, is_current))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                files,
+                vec![("a.rs".to_string(), true), ("b.rs".to_string(), false)]
+            );
+
+            let symbols = |symbol_ix, cx: &AppContext| {
+                sibling_symbols(&editor, symbol_ix, cx)
+                    .into_iter()
+                    .map(|(text, _, is_current)| (text, is_current))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                symbols(0, cx),
+                vec![
+                    ("struct A".to_string(), true),
+                    ("struct B".to_string(), false)
+                ]
+            );
+            // Fields of other structs aren't siblings of the field containing the cursor.
+            assert_eq!(
+                symbols(1, cx),
+                vec![("one".to_string(), false), ("two".to_string(), true)]
+            );
+            assert_eq!(symbols(2, cx), Vec::new());
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+
+    fn rust_lang() -> Arc<Language> {
+        Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["rs".to_string()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::language()),
+            )
+            .with_outline_query(
+                r#"(struct_item
+                    "struct" @context
+                    name: (_) @name) @item
+
+                (field_declaration
+                    name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        )
+    }
+}
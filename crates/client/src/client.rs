@@ -493,17 +493,16 @@ impl settings::Settings for TelemetrySettings {
     type FileContent = TelemetrySettingsContent;
 
     fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        let user_values = || [sources.profile, sources.user].into_iter().flatten();
         Ok(Self {
-            diagnostics: sources.user.as_ref().and_then(|v| v.diagnostics).unwrap_or(
+            diagnostics: user_values().find_map(|v| v.diagnostics).unwrap_or(
                 sources
                     .default
                     .diagnostics
                     .ok_or_else(Self::missing_default)?,
             ),
-            metrics: sources
-                .user
-                .as_ref()
-                .and_then(|v| v.metrics)
+            metrics: user_values()
+                .find_map(|v| v.metrics)
                 .unwrap_or(sources.default.metrics.ok_or_else(Self::missing_default)?),
         })
     }
@@ -1,3 +1,4 @@
+mod diagnostics_panel;
 pub mod items;
 mod project_diagnostics_settings;
 mod toolbar_controls;
@@ -7,6 +8,7 @@ mod diagnostics_tests;
 
 use anyhow::Result;
 use collections::{BTreeSet, HashSet};
+pub use diagnostics_panel::DiagnosticsPanel;
 use editor::{
     diagnostic_block_renderer,
     display_map::{BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, RenderBlock},
@@ -25,10 +27,11 @@ use gpui::{
     WeakView, WindowContext,
 };
 use language::{
-    Bias, Buffer, Diagnostic, DiagnosticEntry, DiagnosticSeverity, Point, Selection, SelectionGoal,
+    Bias, Buffer, BufferId, Diagnostic, DiagnosticEntry, DiagnosticSeverity, Point, Selection,
+    SelectionGoal,
 };
 use lsp::LanguageServerId;
-use project::{DiagnosticSummary, Project, ProjectPath};
+use project::{DiagnosticSummary, Item as _, Project, ProjectPath};
 use project_diagnostics_settings::ProjectDiagnosticsSettings;
 use settings::Settings;
 use std::{
@@ -52,6 +55,7 @@ pub fn init(cx: &mut AppContext) {
     ProjectDiagnosticsSettings::register(cx);
     cx.observe_new_views(ProjectDiagnosticsEditor::register)
         .detach();
+    diagnostics_panel::init(cx);
 }
 
 struct ProjectDiagnosticsEditor {
@@ -63,6 +67,7 @@ struct ProjectDiagnosticsEditor {
     excerpts: Model<MultiBuffer>,
     path_states: Vec<PathState>,
     paths_to_update: BTreeSet<(ProjectPath, LanguageServerId)>,
+    excluded_paths: HashSet<ProjectPath>,
     include_warnings: bool,
     context: u32,
     update_paths_tx: UnboundedSender<(ProjectPath, Option<LanguageServerId>)>,
@@ -166,6 +171,7 @@ impl ProjectDiagnosticsEditor {
             let mut editor =
                 Editor::for_multibuffer(excerpts.clone(), Some(project_handle.clone()), false, cx);
             editor.set_vertical_scroll_margin(5, cx);
+            editor.set_show_buffer_header_controls(true, cx);
             editor
         });
         cx.subscribe(&editor, |this, _editor, event: &EditorEvent, cx| {
@@ -177,6 +183,12 @@ impl ProjectDiagnosticsEditor {
                     }
                 }
                 EditorEvent::Blurred => this.enqueue_update_stale_excerpts(None),
+                EditorEvent::BufferExclusionRequested { buffer_id } => {
+                    this.exclude_buffer(*buffer_id, cx)
+                }
+                EditorEvent::BufferRefreshRequested { buffer_id } => {
+                    this.refresh_buffer(*buffer_id, cx)
+                }
                 _ => {}
             }
         })
@@ -195,6 +207,7 @@ impl ProjectDiagnosticsEditor {
             editor,
             path_states: Default::default(),
             paths_to_update: Default::default(),
+            excluded_paths: Default::default(),
             include_warnings: ProjectDiagnosticsSettings::get_global(cx).include_warnings,
             update_paths_tx: update_excerpts_tx,
             _update_excerpts_task: cx.spawn(move |this, mut cx| async move {
@@ -248,6 +261,25 @@ impl ProjectDiagnosticsEditor {
         cx.notify();
     }
 
+    fn buffer_path(&self, buffer_id: BufferId, cx: &AppContext) -> Option<ProjectPath> {
+        let buffer = self.excerpts.read(cx).buffer(buffer_id)?;
+        buffer.read(cx).project_path(cx)
+    }
+
+    /// Removes the buffer's diagnostics from the view, until the view is closed.
+    fn exclude_buffer(&mut self, buffer_id: BufferId, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.buffer_path(buffer_id, cx) {
+            self.excluded_paths.insert(path.clone());
+            self.update_paths_tx.unbounded_send((path, None)).unwrap();
+        }
+    }
+
+    fn refresh_buffer(&mut self, buffer_id: BufferId, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.buffer_path(buffer_id, cx) {
+            self.update_paths_tx.unbounded_send((path, None)).unwrap();
+        }
+    }
+
     fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
         if self.focus_handle.is_focused(cx) && !self.path_states.is_empty() {
             self.editor.focus_handle(cx).focus(cx)
@@ -340,6 +372,7 @@ impl ProjectDiagnosticsEditor {
         } else {
             DiagnosticSeverity::ERROR
         };
+        let is_excluded = self.excluded_paths.contains(&path_to_update);
         let excerpts_snapshot = self.excerpts.update(cx, |excerpts, cx| {
             let mut old_groups = mem::take(&mut path_state.diagnostic_groups)
                 .into_iter()
@@ -349,7 +382,8 @@ impl ProjectDiagnosticsEditor {
                 .diagnostic_groups(server_to_update)
                 .into_iter()
                 .filter(|(_, group)| {
-                    group.entries[group.primary_ix].diagnostic.severity <= max_severity
+                    !is_excluded
+                        && group.entries[group.primary_ix].diagnostic.severity <= max_severity
                 })
                 .peekable();
             loop {
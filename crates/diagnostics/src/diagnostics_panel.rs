@@ -0,0 +1,821 @@
+use std::{ops::Range, time::Duration};
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use editor::{scroll::Autoscroll, Editor, EditorEvent};
+use gpui::{
+    actions, px, uniform_list, Action, AppContext, AsyncWindowContext, ClipboardItem, EventEmitter,
+    FocusHandle, FocusableView, KeyContext, Model, Pixels, Render, Subscription, Task,
+    UniformListScrollHandle, View, ViewContext, WeakView,
+};
+use language::{Bias, Diagnostic, DiagnosticSeverity, PointUtf16, Unclipped};
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use ui::{prelude::*, ListItem};
+use util::ResultExt;
+use workspace::{
+    dock::{read_panel_state, write_panel_state, DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+actions!(diagnostics_panel, [ToggleFocus, CopyDiagnostics]);
+
+const DIAGNOSTICS_PANEL_KEY: &str = "DiagnosticsPanel";
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub(crate) fn init(cx: &mut AppContext) {
+    workspace::register_panel(cx, DiagnosticsPanel::load);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<DiagnosticsPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum GroupBy {
+    #[default]
+    File,
+    /// The tool that reported the diagnostics, such as a compiler or a linter.
+    Source,
+}
+
+/// A panel that lists the diagnostics of the whole project, to filter them by severity or
+/// text and to group them by file or by source. Unlike the project diagnostics editor, it
+/// doesn't open the files that it lists.
+pub struct DiagnosticsPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    filter_editor: View<Editor>,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    position: DockPosition,
+    show_errors: bool,
+    show_warnings: bool,
+    /// Whether to show informational diagnostics and hints.
+    show_info: bool,
+    group_by: GroupBy,
+    diagnostics: Vec<PanelDiagnostic>,
+    groups: Vec<DiagnosticGroup>,
+    collapsed_groups: HashSet<GroupKey>,
+    rows: Vec<Row>,
+    selected_row: Option<usize>,
+    update_task: Task<()>,
+    pending_serialization: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct PanelDiagnostic {
+    path: ProjectPath,
+    range: Range<Unclipped<PointUtf16>>,
+    diagnostic: Diagnostic,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum GroupKey {
+    Path(ProjectPath),
+    /// The source of the diagnostics, or `None` for the diagnostics without one.
+    Source(Option<String>),
+}
+
+struct DiagnosticGroup {
+    key: GroupKey,
+    label: SharedString,
+    /// The indices of the group's diagnostics that pass the filters.
+    diagnostics: Vec<usize>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Row {
+    Group(usize),
+    Diagnostic(usize),
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedDiagnosticsPanel {
+    width: Option<Pixels>,
+    position: Option<DockPosition>,
+    show_errors: Option<bool>,
+    show_warnings: Option<bool>,
+    show_info: Option<bool>,
+    group_by: Option<GroupBy>,
+}
+
+impl DiagnosticsPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<View<Self>> {
+        let serialized =
+            read_panel_state::<SerializedDiagnosticsPanel>(DIAGNOSTICS_PANEL_KEY, &cx).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized) = serialized {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized.width;
+                    panel.position = serialized.position.unwrap_or(panel.position);
+                    panel.show_errors = serialized.show_errors.unwrap_or(true);
+                    panel.show_warnings = serialized.show_warnings.unwrap_or(true);
+                    panel.show_info = serialized.show_info.unwrap_or(true);
+                    panel.group_by = serialized.group_by.unwrap_or_default();
+                    panel.update_groups(cx);
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let workspace = workspace.weak_handle();
+        cx.new_view(|cx| {
+            let filter_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Filter diagnostics…", cx);
+                editor
+            });
+            let subscriptions = vec![
+                cx.subscribe(&project, |panel, _, event, cx| match event {
+                    project::Event::DiagnosticsUpdated { .. }
+                    | project::Event::DiskBasedDiagnosticsFinished { .. }
+                    | project::Event::WorktreeRemoved(_) => panel.schedule_update(cx),
+                    _ => {}
+                }),
+                cx.subscribe(&filter_editor, |panel, _, event, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        panel.update_groups(cx);
+                    }
+                }),
+            ];
+            let mut panel = Self {
+                workspace,
+                project,
+                focus_handle: cx.focus_handle(),
+                filter_editor,
+                scroll_handle: UniformListScrollHandle::new(),
+                width: None,
+                position: DockPosition::Bottom,
+                show_errors: true,
+                show_warnings: true,
+                show_info: true,
+                group_by: GroupBy::File,
+                diagnostics: Vec::new(),
+                groups: Vec::new(),
+                collapsed_groups: HashSet::default(),
+                rows: Vec::new(),
+                selected_row: None,
+                update_task: Task::ready(()),
+                pending_serialization: Task::ready(()),
+                _subscriptions: subscriptions,
+            };
+            panel.update_diagnostics(cx);
+            panel
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let state = SerializedDiagnosticsPanel {
+            width: self.width,
+            position: Some(self.position),
+            show_errors: Some(self.show_errors),
+            show_warnings: Some(self.show_warnings),
+            show_info: Some(self.show_info),
+            group_by: Some(self.group_by),
+        };
+        let write = write_panel_state(DIAGNOSTICS_PANEL_KEY, &state, cx);
+        self.pending_serialization = cx.background_executor().spawn(async move {
+            write.await.log_err();
+        });
+    }
+
+    /// Updates the diagnostics once a burst of updates from the language servers is over.
+    fn schedule_update(&mut self, cx: &mut ViewContext<Self>) {
+        self.update_task = cx.spawn(|panel, mut cx| async move {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            panel
+                .update(&mut cx, |panel, cx| panel.update_diagnostics(cx))
+                .ok();
+        });
+    }
+
+    fn update_diagnostics(&mut self, cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        let mut paths = project
+            .diagnostic_summaries(false, cx)
+            .map(|(path, _, _)| path)
+            .collect::<Vec<_>>();
+        paths.sort_by(|a, b| (a.worktree_id, &a.path).cmp(&(b.worktree_id, &b.path)));
+        paths.dedup();
+
+        self.diagnostics.clear();
+        for path in paths {
+            let start_ix = self.diagnostics.len();
+            for (_, entries) in project.diagnostics_for_path(&path) {
+                self.diagnostics.extend(
+                    entries
+                        .iter()
+                        .filter(|entry| entry.diagnostic.is_primary)
+                        .map(|entry| PanelDiagnostic {
+                            path: path.clone(),
+                            range: entry.range.clone(),
+                            diagnostic: entry.diagnostic.clone(),
+                        }),
+                );
+            }
+            self.diagnostics[start_ix..].sort_by_key(|diagnostic| diagnostic.range.start.0);
+        }
+        self.update_groups(cx);
+    }
+
+    fn is_visible(&self, diagnostic: &PanelDiagnostic, filter: &str) -> bool {
+        let severity_visible = match diagnostic.diagnostic.severity {
+            DiagnosticSeverity::ERROR => self.show_errors,
+            DiagnosticSeverity::WARNING => self.show_warnings,
+            _ => self.show_info,
+        };
+        severity_visible
+            && (filter.is_empty()
+                || diagnostic
+                    .diagnostic
+                    .message
+                    .to_lowercase()
+                    .contains(filter)
+                || diagnostic
+                    .diagnostic
+                    .source
+                    .as_ref()
+                    .map_or(false, |source| source.to_lowercase().contains(filter))
+                || diagnostic
+                    .path
+                    .path
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .contains(filter))
+    }
+
+    fn group_key(&self, diagnostic: &PanelDiagnostic) -> GroupKey {
+        match self.group_by {
+            GroupBy::File => GroupKey::Path(diagnostic.path.clone()),
+            GroupBy::Source => GroupKey::Source(diagnostic.diagnostic.source.clone()),
+        }
+    }
+
+    fn group_label(&self, key: &GroupKey, cx: &AppContext) -> SharedString {
+        match key {
+            GroupKey::Path(path) => {
+                // The same file may be in several worktrees, so the worktree is part of the
+                // label as soon as there's more than one.
+                let project = self.project.read(cx);
+                let root_name = project
+                    .worktree_for_id(path.worktree_id, cx)
+                    .filter(|_| project.visible_worktrees(cx).nth(1).is_some())
+                    .map(|worktree| worktree.read(cx).root_name().to_string());
+                match root_name {
+                    Some(root_name) => {
+                        format!("{root_name}/{}", path.path.to_string_lossy()).into()
+                    }
+                    None => path.path.to_string_lossy().to_string().into(),
+                }
+            }
+            GroupKey::Source(source) => source
+                .clone()
+                .unwrap_or_else(|| "Unknown source".to_string())
+                .into(),
+        }
+    }
+
+    /// Groups the diagnostics that pass the filters, after the diagnostics, the filters or
+    /// the grouping changed.
+    fn update_groups(&mut self, cx: &mut ViewContext<Self>) {
+        let filter = self.filter_editor.read(cx).text(cx).trim().to_lowercase();
+        let mut group_ixs = HashMap::<GroupKey, usize>::default();
+        let mut groups = Vec::<DiagnosticGroup>::new();
+        for (ix, diagnostic) in self.diagnostics.iter().enumerate() {
+            if !self.is_visible(diagnostic, &filter) {
+                continue;
+            }
+            let key = self.group_key(diagnostic);
+            let group_ix = *group_ixs.entry(key).or_insert_with_key(|key| {
+                groups.push(DiagnosticGroup {
+                    key: key.clone(),
+                    label: self.group_label(key, cx),
+                    diagnostics: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            groups[group_ix].diagnostics.push(ix);
+        }
+        if self.group_by == GroupBy::Source {
+            groups.sort_by(|a, b| a.label.cmp(&b.label));
+        }
+        self.groups = groups;
+        self.update_rows(cx);
+    }
+
+    fn update_rows(&mut self, cx: &mut ViewContext<Self>) {
+        self.rows.clear();
+        for (group_ix, group) in self.groups.iter().enumerate() {
+            self.rows.push(Row::Group(group_ix));
+            if !self.collapsed_groups.contains(&group.key) {
+                self.rows
+                    .extend(group.diagnostics.iter().copied().map(Row::Diagnostic));
+            }
+        }
+        self.selected_row = self
+            .selected_row
+            .filter(|ix| *ix < self.rows.len())
+            .or_else(|| (!self.rows.is_empty()).then_some(0));
+        cx.notify();
+    }
+
+    fn toggle_severity(&mut self, severity: DiagnosticSeverity, cx: &mut ViewContext<Self>) {
+        match severity {
+            DiagnosticSeverity::ERROR => self.show_errors = !self.show_errors,
+            DiagnosticSeverity::WARNING => self.show_warnings = !self.show_warnings,
+            _ => self.show_info = !self.show_info,
+        }
+        self.serialize(cx);
+        self.update_groups(cx);
+    }
+
+    fn set_group_by(&mut self, group_by: GroupBy, cx: &mut ViewContext<Self>) {
+        self.group_by = group_by;
+        self.collapsed_groups.clear();
+        self.serialize(cx);
+        self.update_groups(cx);
+    }
+
+    fn toggle_group(&mut self, group_ix: usize, cx: &mut ViewContext<Self>) {
+        let key = self.groups[group_ix].key.clone();
+        if !self.collapsed_groups.remove(&key) {
+            self.collapsed_groups.insert(key);
+        }
+        self.update_rows(cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_row
+            .map_or(0, |ix| (ix + 1).min(self.rows.len() - 1));
+        self.select_row(ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let ix = self.selected_row.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_row(ix, cx);
+    }
+
+    fn select_row(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_row = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_row {
+            self.activate_row(ix, cx);
+        }
+    }
+
+    fn activate_row(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        match self.rows.get(ix) {
+            Some(Row::Group(group_ix)) => self.toggle_group(*group_ix, cx),
+            Some(Row::Diagnostic(diagnostic_ix)) => self.open_diagnostic(*diagnostic_ix, cx),
+            None => {}
+        }
+    }
+
+    fn open_diagnostic(&self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let diagnostic = &self.diagnostics[ix];
+        let range = diagnostic.range.clone();
+        let open = workspace.update(cx, |workspace, cx| {
+            workspace.open_path(diagnostic.path.clone(), None, true, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let item = open.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                    let end = snapshot.clip_point_utf16(range.end, Bias::Right);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([start..end])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Copies the diagnostics that pass the filters as a list, one per line.
+    fn copy_diagnostics(&mut self, _: &CopyDiagnostics, cx: &mut ViewContext<Self>) {
+        let mut text = String::new();
+        for group in &self.groups {
+            for ix in &group.diagnostics {
+                let diagnostic = &self.diagnostics[*ix];
+                let start = diagnostic.range.start.0;
+                text.push_str(&format!(
+                    "{}:{}:{}: {}: {}",
+                    diagnostic.path.path.to_string_lossy(),
+                    start.row + 1,
+                    start.column + 1,
+                    severity_label(diagnostic.diagnostic.severity),
+                    diagnostic
+                        .diagnostic
+                        .message
+                        .lines()
+                        .next()
+                        .unwrap_or_default(),
+                ));
+                if let Some(source) = &diagnostic.diagnostic.source {
+                    text.push_str(&format!(" [{source}]"));
+                }
+                text.push('\n');
+            }
+        }
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("DiagnosticsPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_row(&self, ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let item = ListItem::new(ix)
+            .selected(self.selected_row == Some(ix))
+            .on_click(cx.listener(move |panel, _, cx| {
+                panel.select_row(ix, cx);
+                panel.activate_row(ix, cx);
+            }));
+        match self.rows[ix] {
+            Row::Group(group_ix) => {
+                let group = &self.groups[group_ix];
+                item.toggle(Some(!self.collapsed_groups.contains(&group.key)))
+                    .on_toggle(cx.listener(move |panel, _, cx| {
+                        panel.toggle_group(group_ix, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(group.label.clone()))
+                            .child(
+                                Label::new(group.diagnostics.len().to_string())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+            }
+            Row::Diagnostic(diagnostic_ix) => {
+                let diagnostic = &self.diagnostics[diagnostic_ix];
+                let (icon, color) = match diagnostic.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => (IconName::XCircle, Color::Error),
+                    DiagnosticSeverity::WARNING => (IconName::ExclamationTriangle, Color::Warning),
+                    _ => (IconName::Indicator, Color::Info),
+                };
+                let start = diagnostic.range.start.0;
+                let location = match self.group_by {
+                    GroupBy::File => format!("{}:{}", start.row + 1, start.column + 1),
+                    GroupBy::Source => format!(
+                        "{}:{}",
+                        diagnostic
+                            .path
+                            .path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        start.row + 1
+                    ),
+                };
+                let message = diagnostic
+                    .diagnostic
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                item.indent_level(1)
+                    .indent_step_size(px(12.))
+                    .start_slot(Icon::new(icon).size(IconSize::Small).color(color))
+                    .child(Label::new(message).size(LabelSize::Small))
+                    .end_slot(
+                        Label::new(location)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+            }
+        }
+    }
+
+    fn render_severity_button(
+        &self,
+        severity: DiagnosticSeverity,
+        cx: &mut ViewContext<Self>,
+    ) -> Button {
+        let (id, label, selected) = match severity {
+            DiagnosticSeverity::ERROR => ("show-errors", "Errors", self.show_errors),
+            DiagnosticSeverity::WARNING => ("show-warnings", "Warnings", self.show_warnings),
+            _ => ("show-info", "Info", self.show_info),
+        };
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .selected(selected)
+            .on_click(cx.listener(move |panel, _, cx| panel.toggle_severity(severity, cx)))
+    }
+
+    fn render_group_by_button(&self, group_by: GroupBy, cx: &mut ViewContext<Self>) -> Button {
+        let (id, label) = match group_by {
+            GroupBy::File => ("group-by-file", "By File"),
+            GroupBy::Source => ("group-by-source", "By Source"),
+        };
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .selected(self.group_by == group_by)
+            .on_click(cx.listener(move |panel, _, cx| panel.set_group_by(group_by, cx)))
+    }
+}
+
+fn severity_label(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        DiagnosticSeverity::INFORMATION => "info",
+        _ => "hint",
+    }
+}
+
+impl Render for DiagnosticsPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("diagnostics-panel")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::copy_diagnostics))
+            .child(
+                h_flex()
+                    .p_1()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.render_severity_button(DiagnosticSeverity::ERROR, cx))
+                    .child(self.render_severity_button(DiagnosticSeverity::WARNING, cx))
+                    .child(self.render_severity_button(DiagnosticSeverity::INFORMATION, cx))
+                    .child(div().w_px().h_4().bg(cx.theme().colors().border))
+                    .child(self.render_group_by_button(GroupBy::File, cx))
+                    .child(self.render_group_by_button(GroupBy::Source, cx))
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_2()
+                            .py_1()
+                            .border_1()
+                            .rounded_md()
+                            .border_color(cx.theme().colors().border)
+                            .child(self.filter_editor.clone()),
+                    )
+                    .child(
+                        IconButton::new("copy-diagnostics", IconName::Copy)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| {
+                                ui::Tooltip::for_action("Copy as List", &CopyDiagnostics, cx)
+                            })
+                            .on_click(|_, cx| cx.dispatch_action(CopyDiagnostics.boxed_clone())),
+                    ),
+            )
+            .map(|this| {
+                if self.rows.is_empty() {
+                    let message = if self.diagnostics.is_empty() {
+                        "No problems in workspace"
+                    } else {
+                        "No diagnostics match the filters"
+                    };
+                    this.child(
+                        v_flex().size_full().p_4().justify_center().child(
+                            h_flex()
+                                .justify_center()
+                                .child(Label::new(message).color(Color::Muted)),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "diagnostics-panel-entries",
+                            self.rows.len(),
+                            |panel, range, cx| range.map(|ix| panel.render_row(ix, cx)).collect(),
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+impl FocusableView for DiagnosticsPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for DiagnosticsPanel {}
+
+impl Panel for DiagnosticsPanel {
+    fn persistent_name() -> &'static str {
+        "Diagnostics Panel"
+    }
+
+    fn position(&self, _: &WindowContext) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        self.position = position;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn size(&self, _: &WindowContext) -> Pixels {
+        self.width.unwrap_or(px(320.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &WindowContext) -> Option<IconName> {
+        Some(IconName::ExclamationTriangle)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Diagnostics Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::DiagnosticEntry;
+    use lsp::LanguageServerId;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::PathBuf;
+
+    #[gpui::test]
+    async fn test_diagnostics_panel_groups(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            client::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            editor::init(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/test",
+            json!({ "a.rs": "fn a() {}\n", "b.rs": "fn b() {}\n" }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let workspace = window.root(cx).unwrap();
+
+        let entry =
+            |row: u32, severity: DiagnosticSeverity, message: &str, source: &str| DiagnosticEntry {
+                range: Unclipped(PointUtf16::new(row, 0))..Unclipped(PointUtf16::new(row, 1)),
+                diagnostic: Diagnostic {
+                    message: message.to_string(),
+                    source: Some(source.to_string()),
+                    severity,
+                    is_primary: true,
+                    ..Default::default()
+                },
+            };
+        project.update(cx, |project, cx| {
+            project
+                .update_diagnostic_entries(
+                    LanguageServerId(0),
+                    PathBuf::from("/test/a.rs"),
+                    None,
+                    vec![
+                        entry(0, DiagnosticSeverity::ERROR, "first error", "rustc"),
+                        entry(1, DiagnosticSeverity::WARNING, "unused", "clippy"),
+                    ],
+                    cx,
+                )
+                .unwrap();
+            project
+                .update_diagnostic_entries(
+                    LanguageServerId(0),
+                    PathBuf::from("/test/b.rs"),
+                    None,
+                    vec![entry(0, DiagnosticSeverity::ERROR, "second error", "rustc")],
+                    cx,
+                )
+                .unwrap();
+        });
+
+        let panel = workspace.update(cx, |workspace, cx| DiagnosticsPanel::new(workspace, cx));
+        let groups = |panel: &DiagnosticsPanel| {
+            panel
+                .groups
+                .iter()
+                .map(|group| {
+                    (
+                        group.label.to_string(),
+                        group
+                            .diagnostics
+                            .iter()
+                            .map(|ix| panel.diagnostics[*ix].diagnostic.message.clone())
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        panel.update(cx, |panel, cx| {
+            assert_eq!(
+                groups(panel),
+                [
+                    (
+                        "a.rs".to_string(),
+                        vec!["first error".to_string(), "unused".to_string()]
+                    ),
+                    ("b.rs".to_string(), vec!["second error".to_string()]),
+                ]
+            );
+            assert_eq!(panel.rows.len(), 5);
+
+            panel.toggle_group(0, cx);
+            assert_eq!(panel.rows.len(), 3);
+
+            panel.set_group_by(GroupBy::Source, cx);
+            assert_eq!(
+                groups(panel),
+                [
+                    ("clippy".to_string(), vec!["unused".to_string()]),
+                    (
+                        "rustc".to_string(),
+                        vec!["first error".to_string(), "second error".to_string()]
+                    ),
+                ]
+            );
+            assert_eq!(panel.rows.len(), 5);
+
+            panel.toggle_severity(DiagnosticSeverity::ERROR, cx);
+            assert_eq!(
+                groups(panel),
+                [("clippy".to_string(), vec!["unused".to_string()])]
+            );
+        });
+
+        panel.update(cx, |panel, cx| {
+            panel.toggle_severity(DiagnosticSeverity::ERROR, cx);
+            panel
+                .filter_editor
+                .update(cx, |editor, cx| editor.set_text("second", cx));
+        });
+        panel.update(cx, |panel, _| {
+            assert_eq!(
+                groups(panel),
+                [("rustc".to_string(), vec!["second error".to_string()])]
+            );
+        });
+    }
+}
@@ -689,6 +689,80 @@ async fn test_diagnostics_multiple_servers(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_exclude_buffer(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/test",
+        json!({
+            "a.js": "a();\n",
+            "b.js": "b();\n",
+        }),
+    )
+    .await;
+
+    let server_id = LanguageServerId(0);
+    let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+    let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*window, cx);
+    let workspace = window.root(cx).unwrap();
+
+    project.update(cx, |project, cx| {
+        for (path, message) in [("/test/a.js", "error a"), ("/test/b.js", "error b")] {
+            project
+                .update_diagnostic_entries(
+                    server_id,
+                    PathBuf::from(path),
+                    None,
+                    vec![DiagnosticEntry {
+                        range: Unclipped(PointUtf16::new(0, 0))..Unclipped(PointUtf16::new(0, 1)),
+                        diagnostic: Diagnostic {
+                            message: message.to_string(),
+                            severity: DiagnosticSeverity::ERROR,
+                            is_primary: true,
+                            is_disk_based: true,
+                            group_id: 0,
+                            ..Default::default()
+                        },
+                    }],
+                    cx,
+                )
+                .unwrap();
+        }
+    });
+
+    let view = window.build_view(cx, |cx| {
+        ProjectDiagnosticsEditor::new_with_context(1, project.clone(), workspace.downgrade(), cx)
+    });
+    let editor = view.update(cx, |view, _| view.editor.clone());
+    cx.executor().run_until_parked();
+    let paths = |view: &View<ProjectDiagnosticsEditor>, cx: &mut VisualTestContext| {
+        view.update(cx, |view, _| {
+            view.path_states
+                .iter()
+                .map(|state| state.path.path.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(paths(&view, cx), ["a.js", "b.js"]);
+
+    let buffer_id = view.update(cx, |view, cx| {
+        view.excerpts.read(cx).excerpt_buffer_ids()[0]
+    });
+    editor.update(cx, |editor, cx| {
+        editor.request_buffer_exclusion(buffer_id, cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(paths(&view, cx), ["b.js"]);
+
+    // Excluded buffers stay excluded when their diagnostics are updated.
+    view.update(cx, |view, cx| view.enqueue_update_all_excerpts(cx));
+    cx.executor().run_until_parked();
+    assert_eq!(paths(&view, cx), ["b.js"]);
+}
+
 #[gpui::test(iterations = 20)]
 async fn test_random_diagnostics(cx: &mut TestAppContext, mut rng: StdRng) {
     init_test(cx);
@@ -0,0 +1,112 @@
+use std::{cmp, mem, ops::Range};
+
+use gpui::ViewContext;
+use lsp::DiagnosticSeverity;
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot, ToPoint as _};
+use text::Point;
+
+use crate::{Anchor, AnnounceCursorPosition, Editor, EditorMode};
+
+/// The most characters of a line or a selection that are read out, so that selecting a
+/// large part of a buffer doesn't make the screen reader read all of it.
+const MAX_ANNOUNCEMENT_LEN: usize = 500;
+
+impl Editor {
+    /// Tells screen readers about the newest selection after it moves: the line the cursor
+    /// moved to, along with its diagnostic, the text the cursor moved over within a line,
+    /// or the selected text.
+    pub(crate) fn announce_selection_change(
+        &mut self,
+        old_cursor_position: &Anchor,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.mode != EditorMode::Full || !self.focus_handle.is_focused(cx) {
+            return;
+        }
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        // Screen readers already echo the text that's typed.
+        if mem::replace(&mut self.announced_edit_count, buffer.edit_count()) != buffer.edit_count()
+        {
+            return;
+        }
+
+        let selection = self.selections.newest::<Point>(cx);
+        let old_head = old_cursor_position.to_point(&buffer);
+        let head = selection.head();
+        let message = if !selection.is_empty() {
+            format!("{} selected", announced_text(&buffer, selection.range()))
+        } else if old_head.row != head.row {
+            line_description(&buffer, head.row)
+        } else {
+            let range = cmp::min(old_head, head)..cmp::max(old_head, head);
+            let text = announced_text(&buffer, range.clone());
+            if range.end.column - range.start.column > 1 && !text.is_empty() {
+                text
+            } else {
+                match buffer.chars_at(head).next() {
+                    None | Some('\n') => "end of line".to_string(),
+                    Some(' ') => "space".to_string(),
+                    Some('\t') => "tab".to_string(),
+                    Some(c) => c.to_string(),
+                }
+            }
+        };
+        cx.announce(&message);
+    }
+
+    pub fn announce_cursor_position(
+        &mut self,
+        _: &AnnounceCursorPosition,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest::<Point>(cx).head();
+        cx.announce(&format!(
+            "Line {}, column {}: {}",
+            head.row + 1,
+            head.column + 1,
+            line_description(&buffer, head.row)
+        ));
+    }
+}
+
+/// Returns the trimmed text of the range, truncated to the length that is read out.
+///
+/// Only the start of the range is read from the buffer, so that large selections don't
+/// copy the text they contain.
+fn announced_text(buffer: &MultiBufferSnapshot, range: Range<Point>) -> String {
+    let text = buffer
+        .text_for_range(range)
+        .flat_map(str::chars)
+        .skip_while(|c| c.is_whitespace())
+        .take(MAX_ANNOUNCEMENT_LEN + 1)
+        .collect::<String>();
+    util::truncate_and_trailoff(text.trim_end(), MAX_ANNOUNCEMENT_LEN)
+}
+
+/// Describes a line for a screen reader, with the most severe diagnostic starting on it.
+fn line_description(buffer: &MultiBufferSnapshot, row: u32) -> String {
+    let line_range = Point::new(row, 0)..Point::new(row, buffer.line_len(MultiBufferRow(row)));
+    let line = announced_text(buffer, line_range.clone());
+    let mut description = if line.is_empty() {
+        "blank".to_string()
+    } else {
+        line
+    };
+
+    let diagnostic = buffer
+        .diagnostics_in_range::<_, Point>(line_range, false)
+        .filter(|entry| entry.diagnostic.is_primary && entry.range.start.row == row)
+        .min_by_key(|entry| entry.diagnostic.severity);
+    if let Some(entry) = diagnostic {
+        let severity = match entry.diagnostic.severity {
+            DiagnosticSeverity::ERROR => "error",
+            DiagnosticSeverity::WARNING => "warning",
+            DiagnosticSeverity::INFORMATION => "info",
+            _ => "hint",
+        };
+        let message = entry.diagnostic.message.lines().next().unwrap_or_default();
+        description.push_str(&format!(", {severity}: {message}"));
+    }
+    description
+}
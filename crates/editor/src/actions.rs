@@ -177,8 +177,11 @@ gpui::actions!(
         AcceptPartialCopilotSuggestion,
         AcceptInlineCompletion,
         AcceptPartialInlineCompletion,
+        AcceptRename,
+        AcceptRenameInFile,
         AddSelectionAbove,
         AddSelectionBelow,
+        AnnounceCursorPosition,
         Backspace,
         Cancel,
         CancelLanguageServerWork,
@@ -191,6 +194,7 @@ gpui::actions!(
         ConvertToLowerCamelCase,
         ConvertToLowerCase,
         ConvertToOppositeCase,
+        ConvertToScreamingSnakeCase,
         ConvertToSnakeCase,
         ConvertToTitleCase,
         ConvertToUpperCamelCase,
@@ -202,6 +206,7 @@ gpui::actions!(
         CopyRelativePath,
         Cut,
         CutToEndOfLine,
+        DecreaseFontSize,
         Delete,
         DeleteLine,
         DeleteToBeginningOfLine,
@@ -213,6 +218,8 @@ gpui::actions!(
         DisplayCursorNames,
         DuplicateLineDown,
         DuplicateLineUp,
+        DuplicateSelectionDown,
+        DuplicateSelectionUp,
         ExpandAllHunkDiffs,
         ExpandMacroRecursively,
         FindAllReferences,
@@ -234,6 +241,7 @@ gpui::actions!(
         HalfPageDown,
         HalfPageUp,
         Hover,
+        IncreaseFontSize,
         Indent,
         JoinLines,
         LineDown,
@@ -267,10 +275,14 @@ gpui::actions!(
         PageDown,
         PageUp,
         Paste,
+        PasteWithoutReformatting,
         PreviousInlineCompletion,
         Redo,
         RedoSelection,
+        RejectRename,
+        RejectRenameInFile,
         Rename,
+        ResetFontSize,
         RestartLanguageServer,
         RevealInFileManager,
         ReverseLines,
@@ -282,8 +294,14 @@ gpui::actions!(
         ScrollCursorCenterTopBottom,
         SelectAll,
         SelectAllMatches,
+        SelectColumnDown,
+        SelectColumnLeft,
+        SelectColumnRight,
+        SelectColumnUp,
         SelectDown,
         SelectLargerSyntaxNode,
+        SelectEnclosingFunction,
+        SelectEnclosingStatement,
         SelectEnclosingSymbol,
         SelectLeft,
         SelectLine,
@@ -301,12 +319,20 @@ gpui::actions!(
         SelectPageDown,
         SelectPageUp,
         ShowCharacterPalette,
+        ShowIncomingCalls,
         ShowInlineCompletion,
+        ShowOutgoingCalls,
         ShowSignatureHelp,
+        ShowSubtypes,
+        ShowSupertypes,
         ShuffleLines,
+        SignatureHelpNext,
+        SignatureHelpPrevious,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
+        SortLinesNumerically,
         SplitSelectionIntoLines,
+        SwapSelections,
         SwitchSourceHeader,
         Tab,
         TabPrev,
@@ -317,10 +343,12 @@ gpui::actions!(
         ToggleHunkDiff,
         ToggleInlayHints,
         ToggleLineNumbers,
+        ToggleReadOnly,
         ToggleIndentGuides,
         ToggleSoftWrap,
         ToggleTabBar,
         Transpose,
+        TransposeWords,
         Undo,
         UndoSelection,
         UnfoldLines,
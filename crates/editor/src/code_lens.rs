@@ -0,0 +1,509 @@
+use std::time::Duration;
+
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{Model, Task, ViewContext, WeakView};
+use language::{Buffer, Point, ToPoint as _};
+use multi_buffer::{Anchor, ToPoint as _};
+use project::CodeLens;
+use ui::prelude::*;
+use util::ResultExt;
+
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+    Editor, EditorMode,
+};
+
+const CODE_LENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+const CODE_LENS_RESOLVE_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+pub(crate) struct CodeLensState {
+    blocks: Vec<CodeLensBlock>,
+    refresh_task: Option<Task<()>>,
+    resolve_task: Option<Task<()>>,
+}
+
+/// The lenses shown in a block above a single row of a buffer.
+struct CodeLensBlock {
+    block_id: CustomBlockId,
+    position: Anchor,
+    buffer: Model<Buffer>,
+    lenses: Vec<CodeLens>,
+}
+
+impl Editor {
+    /// Requests the code lenses of all buffers of the editor and replaces the blocks showing
+    /// them once they arrive.
+    pub(crate) fn refresh_code_lenses(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        self.code_lens.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(CODE_LENS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let Some(tasks) = editor
+                .update(&mut cx, |editor, cx| {
+                    let buffers = editor.buffer.read(cx).all_buffers();
+                    project.update(cx, |project, cx| {
+                        buffers
+                            .into_iter()
+                            .map(|buffer| {
+                                let task = project.code_lens(&buffer, cx);
+                                async move { (buffer, task.await.log_err().unwrap_or_default()) }
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .ok()
+            else {
+                return;
+            };
+            let lenses_by_buffer = join_all(tasks).await;
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.set_code_lenses(lenses_by_buffer, cx)
+                })
+                .log_err();
+        }));
+    }
+
+    fn set_code_lenses(
+        &mut self,
+        lenses_by_buffer: Vec<(Model<Buffer>, Vec<CodeLens>)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let old_blocks = std::mem::take(&mut self.code_lens.blocks);
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut new_blocks = Vec::new();
+        for (buffer, mut lenses) in lenses_by_buffer {
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            lenses.sort_by(|a, b| a.range.start.cmp(&b.range.start, &buffer_snapshot));
+            // Keep the commands of lenses that were already resolved, so that their titles
+            // don't disappear until they are resolved again.
+            for lens in &mut lenses {
+                if lens.is_resolved() {
+                    continue;
+                }
+                if let Some(old_lens) = old_blocks
+                    .iter()
+                    .filter(|block| block.buffer == buffer)
+                    .flat_map(|block| &block.lenses)
+                    .find(|old_lens| {
+                        old_lens.is_resolved()
+                            && old_lens.server_id == lens.server_id
+                            && old_lens.lsp_lens.range == lens.lsp_lens.range
+                            && old_lens.lsp_lens.data == lens.lsp_lens.data
+                    })
+                {
+                    lens.lsp_lens.command = old_lens.lsp_lens.command.clone();
+                }
+            }
+
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+                let context = excerpt_range.context;
+                let mut lenses_by_row = Vec::<(u32, Vec<CodeLens>)>::new();
+                for lens in &lenses {
+                    let starts_before = lens.range.start.cmp(&context.start, &buffer_snapshot);
+                    let starts_after = lens.range.start.cmp(&context.end, &buffer_snapshot);
+                    if starts_before.is_lt() || starts_after.is_gt() {
+                        continue;
+                    }
+                    let row = lens.range.start.to_point(&buffer_snapshot).row;
+                    match lenses_by_row.last_mut() {
+                        Some((last_row, row_lenses)) if *last_row == row => {
+                            row_lenses.push(lens.clone())
+                        }
+                        _ => lenses_by_row.push((row, vec![lens.clone()])),
+                    }
+                }
+
+                for (row, lenses) in lenses_by_row {
+                    // Align the lenses with the first non-whitespace character of the row.
+                    let indent = buffer_snapshot.indent_size_for_line(row).len;
+                    let Some(position) = snapshot.anchor_in_excerpt(
+                        excerpt_id,
+                        buffer_snapshot.anchor_before(Point::new(row, indent)),
+                    ) else {
+                        continue;
+                    };
+                    new_blocks.push((position, buffer.clone(), lenses));
+                }
+            }
+        }
+
+        // Keep the blocks that still show the same lenses on the same row, so that edits
+        // only replace the blocks whose lenses changed.
+        let mut old_blocks_by_row = HashMap::<_, Vec<CodeLensBlock>>::default();
+        for block in old_blocks {
+            let row = block.position.to_point(&snapshot).row;
+            old_blocks_by_row.entry(row).or_default().push(block);
+        }
+        let mut blocks = Vec::new();
+        let mut blocks_to_insert = Vec::new();
+        for (position, buffer, lenses) in new_blocks {
+            let row = position.to_point(&snapshot).row;
+            let old_block = old_blocks_by_row.get_mut(&row).and_then(|old_blocks| {
+                let ix = old_blocks.iter().position(|block| {
+                    block.buffer == buffer && same_lenses(&block.lenses, &lenses)
+                })?;
+                Some(old_blocks.remove(ix))
+            });
+            match old_block {
+                Some(old_block) => blocks.push(CodeLensBlock {
+                    lenses,
+                    ..old_block
+                }),
+                None => blocks_to_insert.push((position, buffer, lenses)),
+            }
+        }
+
+        let block_ids_to_remove = old_blocks_by_row
+            .into_values()
+            .flatten()
+            .map(|block| block.block_id)
+            .collect::<HashSet<_>>();
+        if !block_ids_to_remove.is_empty() {
+            self.remove_blocks(block_ids_to_remove, None, cx);
+        }
+        let editor = cx.view().downgrade();
+        let block_ids = self.insert_blocks(
+            blocks_to_insert
+                .iter()
+                .map(|(position, buffer, lenses)| BlockProperties {
+                    position: *position,
+                    height: 1,
+                    style: BlockStyle::Flex,
+                    render: render_code_lens_block(editor.clone(), buffer.clone(), lenses.clone()),
+                    disposition: BlockDisposition::Above,
+                    priority: 0,
+                })
+                .collect::<Vec<_>>(),
+            None,
+            cx,
+        );
+        blocks.extend(block_ids.into_iter().zip(blocks_to_insert).map(
+            |(block_id, (position, buffer, lenses))| CodeLensBlock {
+                block_id,
+                position,
+                buffer,
+                lenses,
+            },
+        ));
+        self.code_lens.blocks = blocks;
+
+        self.resolve_visible_code_lenses(cx);
+    }
+
+    /// Resolves the lenses that language servers returned without a command in the rows
+    /// that are currently visible.
+    pub(crate) fn resolve_visible_code_lenses(&mut self, cx: &mut ViewContext<Self>) {
+        if self.code_lens.blocks.is_empty() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        self.code_lens.resolve_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(CODE_LENS_RESOLVE_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let Some(tasks) = editor
+                .update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer.read(cx).snapshot(cx);
+                    let visible_start = editor.scroll_manager.anchor().anchor.to_point(&snapshot);
+                    let visible_end = visible_start
+                        + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0);
+
+                    let mut tasks = Vec::new();
+                    for block in &editor.code_lens.blocks {
+                        let row = block.position.to_point(&snapshot).row;
+                        if row < visible_start.row || row > visible_end.row {
+                            continue;
+                        }
+                        for (lens_ix, lens) in block.lenses.iter().enumerate() {
+                            if lens.is_resolved() {
+                                continue;
+                            }
+                            let task = project.update(cx, |project, cx| {
+                                project.resolve_code_lens(&block.buffer, lens.clone(), cx)
+                            });
+                            let block_id = block.block_id;
+                            tasks.push(async move { (block_id, lens_ix, task.await) });
+                        }
+                    }
+                    tasks
+                })
+                .ok()
+            else {
+                return;
+            };
+            if tasks.is_empty() {
+                return;
+            }
+            let resolved_lenses = join_all(tasks).await;
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let mut resolved_block_ids = HashSet::default();
+                    for (block_id, lens_ix, resolved_lens) in resolved_lenses {
+                        let Some(resolved_lens) = resolved_lens.log_err() else {
+                            continue;
+                        };
+                        let Some(lens) = editor
+                            .code_lens
+                            .blocks
+                            .iter_mut()
+                            .find(|block| block.block_id == block_id)
+                            .and_then(|block| block.lenses.get_mut(lens_ix))
+                        else {
+                            continue;
+                        };
+                        if resolved_lens.is_resolved() {
+                            *lens = resolved_lens;
+                            resolved_block_ids.insert(block_id);
+                        }
+                    }
+
+                    let editor_handle = cx.view().downgrade();
+                    let renderers = editor
+                        .code_lens
+                        .blocks
+                        .iter()
+                        .filter(|block| resolved_block_ids.contains(&block.block_id))
+                        .map(|block| {
+                            (
+                                block.block_id,
+                                render_code_lens_block(
+                                    editor_handle.clone(),
+                                    block.buffer.clone(),
+                                    block.lenses.clone(),
+                                ),
+                            )
+                        })
+                        .collect::<HashMap<_, _>>();
+                    if !renderers.is_empty() {
+                        editor.replace_blocks(renderers, None, cx);
+                    }
+                })
+                .log_err();
+        }));
+    }
+
+    /// Runs the command of a lens and opens the edits that the language server made while
+    /// running it.
+    fn apply_code_lens(
+        &mut self,
+        buffer: Model<Buffer>,
+        code_lens: CodeLens,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let title = code_lens.title().unwrap_or_default().to_string();
+        let apply_code_lens = project.update(cx, |project, cx| {
+            project.apply_code_lens(&buffer, code_lens, cx)
+        });
+        let workspace = workspace.downgrade();
+        cx.spawn(|editor, cx| async move {
+            let project_transaction = apply_code_lens.await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// Whether two rows of lenses are displayed the same way, regardless of where they are.
+fn same_lenses(old_lenses: &[CodeLens], new_lenses: &[CodeLens]) -> bool {
+    old_lenses.len() == new_lenses.len()
+        && old_lenses
+            .iter()
+            .zip(new_lenses)
+            .all(|(old_lens, new_lens)| {
+                old_lens.server_id == new_lens.server_id
+                    && old_lens.lsp_lens.command == new_lens.lsp_lens.command
+                    && old_lens.lsp_lens.data == new_lens.lsp_lens.data
+            })
+}
+
+fn render_code_lens_block(
+    editor: WeakView<Editor>,
+    buffer: Model<Buffer>,
+    lenses: Vec<CodeLens>,
+) -> RenderBlock {
+    Box::new(move |cx: &mut BlockContext| {
+        h_flex()
+            .id(cx.block_id)
+            .h(cx.line_height)
+            .pl(cx.anchor_x)
+            .gap_2()
+            .children(
+                lenses
+                    .iter()
+                    .filter_map(|lens| Some((lens.title()?.to_string(), lens.clone())))
+                    .enumerate()
+                    .map(|(ix, (title, lens))| {
+                        let editor = editor.clone();
+                        let buffer = buffer.clone();
+                        h_flex()
+                            .gap_2()
+                            .when(ix > 0, |this| {
+                                this.child(
+                                    Label::new("|").size(LabelSize::Small).color(Color::Muted),
+                                )
+                            })
+                            .child(
+                                div()
+                                    .id(("code-lens", ix))
+                                    .cursor_pointer()
+                                    .child(
+                                        Label::new(title)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                    .on_click(move |_, cx| {
+                                        editor
+                                            .update(cx, |editor, cx| {
+                                                editor.apply_code_lens(
+                                                    buffer.clone(),
+                                                    lens.clone(),
+                                                    cx,
+                                                )
+                                            })
+                                            .ok();
+                                    }),
+                            )
+                    }),
+            )
+            .into_any_element()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use gpui::TestAppContext;
+
+    use super::*;
+    use crate::{
+        editor_tests::{init_test, update_test_language_settings},
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+
+    #[gpui::test]
+    async fn test_code_lens_blocks(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        update_test_language_settings(cx, |settings| {
+            settings.defaults.code_lens = Some(true);
+        });
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
+                execute_command_provider: Some(lsp::ExecuteCommandOptions {
+                    commands: vec!["rust-analyzer.showReferences".into()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let lens = |line: u32, title: &str, command: &str| lsp::CodeLens {
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 2)),
+            command: Some(lsp::Command {
+                title: title.into(),
+                command: command.into(),
+                arguments: None,
+            }),
+            data: None,
+        };
+        cx.handle_request::<lsp::request::CodeLensRequest, _, _>(move |_, _, _| async move {
+            Ok(Some(vec![
+                lens(0, "Run", "rust-analyzer.runSingle"),
+                lens(2, "2 references", "rust-analyzer.showReferences"),
+            ]))
+        });
+        cx.set_state("fn main() {}\nˇ\nfn other() {}\n");
+        cx.executor().advance_clock(CODE_LENS_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+
+        let block_ids = |cx: &mut EditorLspTestContext| {
+            cx.update_editor(|editor, _| {
+                editor
+                    .code_lens
+                    .blocks
+                    .iter()
+                    .map(|block| block.block_id)
+                    .collect::<HashSet<_>>()
+            })
+        };
+        let initial_block_ids = block_ids(&mut cx);
+        assert_eq!(initial_block_ids.len(), 2);
+
+        // Editing the buffer moves the lenses, but keeps their blocks.
+        cx.simulate_keystrokes("enter");
+        cx.handle_request::<lsp::request::CodeLensRequest, _, _>(move |_, _, _| async move {
+            Ok(Some(vec![
+                lens(0, "Run", "rust-analyzer.runSingle"),
+                lens(3, "3 references", "rust-analyzer.showReferences"),
+            ]))
+        });
+        cx.executor().advance_clock(CODE_LENS_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+        let new_block_ids = block_ids(&mut cx);
+        assert_eq!(new_block_ids.len(), 2);
+        assert_eq!(
+            initial_block_ids.intersection(&new_block_ids).count(),
+            1,
+            "only the block whose lens changed should be replaced"
+        );
+
+        // Commands that the server doesn't support are not sent to it.
+        let executed_commands = Arc::new(AtomicUsize::new(0));
+        cx.handle_request::<lsp::request::ExecuteCommand, _, _>({
+            let executed_commands = executed_commands.clone();
+            move |_, _, _| {
+                executed_commands.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(None) }
+            }
+        });
+        let (buffer, lenses) = cx.update_editor(|editor, _| {
+            let block = &editor.code_lens.blocks[0];
+            (block.buffer.clone(), block.lenses.clone())
+        });
+        let project = cx.update_editor(|editor, _| editor.project.clone().unwrap());
+        for lens in lenses {
+            let transaction = cx
+                .update(|cx| {
+                    project.update(cx, |project, cx| project.apply_code_lens(&buffer, lens, cx))
+                })
+                .await
+                .unwrap();
+            assert!(transaction.0.is_empty());
+        }
+        assert_eq!(executed_commands.load(Ordering::SeqCst), 0);
+    }
+}
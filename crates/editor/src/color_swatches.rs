@@ -0,0 +1,593 @@
+use std::{ops::Range, time::Duration};
+
+use collections::HashMap;
+use gpui::{hsla, HighlightStyle, Hsla, Pixels, Point as PixelPoint, Rgba, ViewContext};
+use language::{language_settings::language_settings, Bias, BufferSnapshot, Point, ToOffset};
+use multi_buffer::{Anchor, ExcerptId, ToPoint as _};
+use project::DocumentColor;
+use ui::{prelude::*, ContextMenu};
+use util::{post_inc, ResultExt};
+
+use crate::{
+    display_map::Inlay, hover_links::InlayHighlight, mouse_context_menu::MouseContextMenu, Editor,
+    EditorMode, EditorSnapshot, InlayId, PointForPosition,
+};
+
+const COLOR_SWATCHES_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// The longest `rgb()` or `hsl()` literal that is detected without a language server.
+const MAX_COLOR_FUNCTION_LEN: usize = 64;
+
+/// The languages in which color literals are detected without a language server, as
+/// elsewhere `#123` is more likely to be an issue number or a comment than a color.
+const COLOR_LITERAL_LANGUAGES: &[&str] =
+    &["CSS", "SCSS", "Sass", "Less", "HTML", "Svelte", "Vue.js"];
+
+/// How a color literal is written, so that picked colors are written the same way.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ColorSwatch {
+    inlay_id: InlayId,
+    range: Range<Anchor>,
+    color: Hsla,
+    format: ColorFormat,
+}
+
+impl Editor {
+    /// Detects the color literals in the visible part of the editor and shows a swatch
+    /// before each of them, replacing the previous swatches.
+    pub(crate) fn refresh_color_swatches(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let project = self.project.clone();
+
+        self.color_swatches_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(COLOR_SWATCHES_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let Some((visible_excerpts, color_tasks)) = editor
+                .update(&mut cx, |editor, cx| {
+                    let visible_excerpts = editor.visible_color_swatch_excerpts(cx);
+                    let mut color_tasks = HashMap::default();
+                    for (_, buffer, _) in &visible_excerpts {
+                        let buffer_id = buffer.remote_id();
+                        if color_tasks.contains_key(&buffer_id) {
+                            continue;
+                        }
+                        let Some(buffer) = editor.buffer.read(cx).buffer(buffer_id) else {
+                            continue;
+                        };
+                        let task = project.as_ref().map(|project| {
+                            project.update(cx, |project, cx| project.document_colors(&buffer, cx))
+                        });
+                        color_tasks.insert(buffer_id, task);
+                    }
+                    (visible_excerpts, color_tasks)
+                })
+                .ok()
+            else {
+                return;
+            };
+
+            let mut colors_by_buffer = HashMap::default();
+            for (buffer_id, task) in color_tasks {
+                let colors = match task {
+                    Some(task) => task.await.log_err().flatten(),
+                    None => None,
+                };
+                colors_by_buffer.insert(buffer_id, colors);
+            }
+
+            let literals = cx
+                .background_executor()
+                .spawn(async move {
+                    visible_excerpts
+                        .into_iter()
+                        .flat_map(|(excerpt_id, buffer, range)| {
+                            let colors = colors_by_buffer.get(&buffer.remote_id()).cloned();
+                            excerpt_color_literals(&buffer, range, colors.flatten())
+                                .into_iter()
+                                .map(move |(range, color, format)| {
+                                    (excerpt_id, range, color, format)
+                                })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.set_color_swatches(literals, cx)
+                })
+                .log_err();
+        }));
+    }
+
+    /// Returns the visible ranges of the buffers whose language settings enable color
+    /// swatches.
+    fn visible_color_swatch_excerpts(
+        &self,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<(ExcerptId, BufferSnapshot, Range<usize>)> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let visible_start = self.scroll_manager.anchor().anchor.to_point(&snapshot);
+        let visible_row_count = self.visible_line_count().unwrap_or(0.).ceil() as u32;
+        let visible_end = snapshot.clip_point(
+            Point::new(visible_start.row + visible_row_count + 1, 0),
+            Bias::Left,
+        );
+        let visible_range = snapshot.anchor_before(Point::new(visible_start.row, 0))
+            ..snapshot.anchor_after(visible_end);
+
+        snapshot
+            .excerpts_in_ranges([visible_range])
+            .filter(|(_, buffer, range)| {
+                language_settings(buffer.language_at(range.start), buffer.file(), cx).color_swatches
+            })
+            .map(|(excerpt_id, buffer, range)| (excerpt_id, buffer.clone(), range))
+            .collect()
+    }
+
+    fn set_color_swatches(
+        &mut self,
+        literals: Vec<(ExcerptId, Range<text::Anchor>, Hsla, ColorFormat)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let literals = literals
+            .into_iter()
+            .filter_map(|(excerpt_id, range, color, format)| {
+                let start = snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                Some((start..end, color, format))
+            })
+            .collect::<Vec<_>>();
+
+        let unchanged = literals.len() == self.color_swatches.len()
+            && literals
+                .iter()
+                .zip(&self.color_swatches)
+                .all(|((range, color, format), swatch)| {
+                    range.start.cmp(&swatch.range.start, &snapshot).is_eq()
+                        && range.end.cmp(&swatch.range.end, &snapshot).is_eq()
+                        && *color == swatch.color
+                        && *format == swatch.format
+                });
+        if unchanged {
+            return;
+        }
+
+        let swatches = literals
+            .into_iter()
+            .map(|(range, color, format)| ColorSwatch {
+                inlay_id: InlayId::Color(post_inc(&mut self.next_inlay_id)),
+                range,
+                color,
+                format,
+            })
+            .collect::<Vec<_>>();
+        let to_remove = self
+            .color_swatches
+            .drain(..)
+            .map(|swatch| swatch.inlay_id)
+            .collect();
+        let to_insert = swatches
+            .iter()
+            .map(|swatch| Inlay::color(swatch.inlay_id.id(), swatch.range.start))
+            .collect();
+        self.splice_inlays(to_remove, to_insert, cx);
+
+        self.clear_highlights::<ColorSwatch>(cx);
+        for swatch in &swatches {
+            self.highlight_inlays::<ColorSwatch>(
+                vec![InlayHighlight {
+                    inlay: swatch.inlay_id,
+                    inlay_position: swatch.range.start,
+                    range: 0..Inlay::COLOR_SWATCH.trim_end().len(),
+                }],
+                HighlightStyle {
+                    color: Some(swatch.color),
+                    ..HighlightStyle::default()
+                },
+                cx,
+            );
+        }
+        self.color_swatches = swatches;
+    }
+
+    /// Returns the index of the color swatch under the mouse, if any.
+    pub(crate) fn color_swatch_at(
+        &self,
+        point_for_position: &PointForPosition,
+        snapshot: &EditorSnapshot,
+    ) -> Option<usize> {
+        // Swatches are inlays, so clicking them never hits a valid position in the buffer.
+        if point_for_position.as_valid().is_some()
+            || point_for_position.column_overshoot_after_line_end > 0
+        {
+            return None;
+        }
+        let buffer_snapshot = &snapshot.buffer_snapshot;
+        let previous_valid_anchor = buffer_snapshot.anchor_at(
+            point_for_position.previous_valid.to_point(snapshot),
+            Bias::Left,
+        );
+        let next_valid_anchor = buffer_snapshot.anchor_at(
+            point_for_position.next_valid.to_point(snapshot),
+            Bias::Right,
+        );
+        self.color_swatches.iter().position(|swatch| {
+            swatch
+                .range
+                .start
+                .cmp(&previous_valid_anchor, buffer_snapshot)
+                .is_ge()
+                && swatch
+                    .range
+                    .start
+                    .cmp(&next_valid_anchor, buffer_snapshot)
+                    .is_le()
+        })
+    }
+
+    /// Opens a menu of colors next to the given swatch, which rewrites its literal with
+    /// the picked color.
+    pub(crate) fn deploy_color_picker(
+        &mut self,
+        swatch_ix: usize,
+        position: PixelPoint<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(swatch) = self.color_swatches.get(swatch_ix).cloned() else {
+            return;
+        };
+        let editor = cx.view().downgrade();
+        let context_menu = ContextMenu::build(cx, move |mut menu, _| {
+            menu = menu.header("Pick Color");
+            for color in color_palette(swatch.color) {
+                let literal = SharedString::from(format_color(color, swatch.format));
+                let editor = editor.clone();
+                let range = swatch.range.clone();
+                menu = menu.custom_entry(
+                    {
+                        let literal = literal.clone();
+                        move |_| {
+                            h_flex()
+                                .gap_2()
+                                .child(div().size_3().rounded_sm().bg(color))
+                                .child(Label::new(literal.clone()))
+                                .into_any_element()
+                        }
+                    },
+                    move |cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor.transact(cx, |editor, cx| {
+                                    editor.buffer.update(cx, |buffer, cx| {
+                                        buffer.edit(
+                                            [(range.clone(), literal.to_string())],
+                                            None,
+                                            cx,
+                                        )
+                                    });
+                                });
+                            })
+                            .ok();
+                    },
+                );
+            }
+            menu
+        });
+        self.mouse_context_menu = MouseContextMenu::pinned_to_editor(
+            self,
+            swatch.range.start,
+            position,
+            context_menu,
+            cx,
+        );
+        cx.notify();
+    }
+}
+
+/// The colors offered when picking a replacement for `color`: the color itself, other
+/// shades of its hue, and the same shade in other hues.
+fn color_palette(color: Hsla) -> Vec<Hsla> {
+    let mut palette = vec![color];
+    palette.extend(
+        [0.2, 0.35, 0.5, 0.65, 0.8]
+            .into_iter()
+            .map(|lightness| hsla(color.h, color.s, lightness, color.a)),
+    );
+    palette.extend((1..12).map(|step| {
+        let hue = (color.h + step as f32 / 12.).fract();
+        hsla(hue, color.s.max(0.5), color.l, color.a)
+    }));
+    palette
+}
+
+/// Returns the color literals in the given range of the buffer, using the colors
+/// provided by its language server when there are some, and detecting them from the
+/// text in CSS-like languages otherwise.
+fn excerpt_color_literals(
+    buffer: &BufferSnapshot,
+    range: Range<usize>,
+    document_colors: Option<Vec<DocumentColor>>,
+) -> Vec<(Range<text::Anchor>, Hsla, ColorFormat)> {
+    let literals = match document_colors {
+        Some(document_colors) => document_colors
+            .into_iter()
+            .filter_map(|document_color| {
+                let color_range = document_color.range.start.to_offset(buffer)
+                    ..document_color.range.end.to_offset(buffer);
+                if color_range.start < range.start || color_range.end > range.end {
+                    return None;
+                }
+                let text = buffer
+                    .text_for_range(color_range.clone())
+                    .collect::<String>();
+                let format = parse_color(&text).map_or(ColorFormat::Hex, |(_, f)| f);
+                let lsp::Color {
+                    red,
+                    green,
+                    blue,
+                    alpha,
+                } = document_color.color;
+                let color = Rgba {
+                    r: red,
+                    g: green,
+                    b: blue,
+                    a: alpha,
+                };
+                Some((color_range, Hsla::from(color), format))
+            })
+            .collect(),
+        None => {
+            let is_css_like = buffer.language_at(range.start).map_or(false, |language| {
+                COLOR_LITERAL_LANGUAGES.contains(&language.name().as_ref())
+            });
+            if !is_css_like {
+                return Vec::new();
+            }
+            let text = buffer.text_for_range(range.clone()).collect::<String>();
+            find_color_literals(&text)
+                .into_iter()
+                .map(|(literal_range, color, format)| {
+                    let literal_range =
+                        range.start + literal_range.start..range.start + literal_range.end;
+                    (literal_range, color, format)
+                })
+                .collect()
+        }
+    };
+    literals
+        .into_iter()
+        .map(|(range, color, format)| {
+            let range = buffer.anchor_before(range.start)..buffer.anchor_after(range.end);
+            (range, color, format)
+        })
+        .collect()
+}
+
+/// Finds hex, `rgb()` and `hsl()` color literals in the text.
+fn find_color_literals(text: &str) -> Vec<(Range<usize>, Hsla, ColorFormat)> {
+    let mut literals = Vec::new();
+    let mut offset = 0;
+    while let Some(rest) = text.get(offset..).filter(|rest| !rest.is_empty()) {
+        let follows_word = text[..offset].chars().next_back().map_or(false, |c| {
+            c.is_alphanumeric() || matches!(c, '_' | '-' | '#')
+        });
+        if !follows_word {
+            if let Some(len) = color_literal_len(rest) {
+                if let Some((color, format)) = parse_color(&rest[..len]) {
+                    literals.push((offset..offset + len, color, format));
+                    offset += len;
+                    continue;
+                }
+            }
+        }
+        offset += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    literals
+}
+
+/// Returns the length of what looks like a color literal at the start of the text.
+fn color_literal_len(text: &str) -> Option<usize> {
+    if let Some(digits) = text.strip_prefix('#') {
+        let len = digits
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(digits.len());
+        return Some(1 + len);
+    }
+    let name_len = text.bytes().take(5).position(|b| b == b'(')?;
+    if !matches!(
+        text[..name_len].to_ascii_lowercase().as_str(),
+        "rgb" | "rgba" | "hsl" | "hsla"
+    ) {
+        return None;
+    }
+    let close_ix = text
+        .bytes()
+        .take(MAX_COLOR_FUNCTION_LEN)
+        .take_while(|b| *b != b'\n')
+        .position(|b| b == b')')?;
+    Some(close_ix + 1)
+}
+
+fn parse_color(text: &str) -> Option<(Hsla, ColorFormat)> {
+    if let Some(hex) = text.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let hex = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+        let mut value = u32::from_str_radix(&hex, 16).ok()?;
+        if hex.len() == 6 {
+            value = value << 8 | 0xff;
+        }
+        return Some((gpui::rgba(value).into(), ColorFormat::Hex));
+    }
+
+    let open_ix = text.find('(')?;
+    let arguments = text[open_ix + 1..]
+        .strip_suffix(')')?
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|argument| !argument.is_empty())
+        .collect::<Vec<_>>();
+    if arguments.len() != 3 && arguments.len() != 4 {
+        return None;
+    }
+    let alpha = match arguments.get(3) {
+        Some(argument) => parse_component(argument, 1.)?,
+        None => 1.,
+    };
+    match text[..open_ix].to_ascii_lowercase().as_str() {
+        "rgb" | "rgba" => {
+            let color = Rgba {
+                r: parse_component(arguments[0], 255.)?,
+                g: parse_component(arguments[1], 255.)?,
+                b: parse_component(arguments[2], 255.)?,
+                a: alpha,
+            };
+            Some((color.into(), ColorFormat::Rgb))
+        }
+        "hsl" | "hsla" => {
+            let hue = arguments[0].strip_suffix("deg").unwrap_or(arguments[0]);
+            let hue = hue.parse::<f32>().ok()?.rem_euclid(360.) / 360.;
+            let saturation = parse_component(arguments[1], 100.)?;
+            let lightness = parse_component(arguments[2], 100.)?;
+            Some((hsla(hue, saturation, lightness, alpha), ColorFormat::Hsl))
+        }
+        _ => None,
+    }
+}
+
+/// Parses a number out of `max`, or a percentage, into the `0..=1` range.
+fn parse_component(argument: &str, max: f32) -> Option<f32> {
+    let value = match argument.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f32>().ok()? / 100.,
+        None => argument.parse::<f32>().ok()? / max,
+    };
+    Some(value.clamp(0., 1.))
+}
+
+fn format_color(color: Hsla, format: ColorFormat) -> String {
+    let alpha = (color.a * 100.).round() / 100.;
+    match format {
+        ColorFormat::Hex => {
+            let rgba = Rgba::from(color);
+            let [r, g, b, a] = [rgba.r, rgba.g, rgba.b, rgba.a].map(|c| (c * 255.).round() as u8);
+            if a == u8::MAX {
+                format!("#{r:02x}{g:02x}{b:02x}")
+            } else {
+                format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+            }
+        }
+        ColorFormat::Rgb => {
+            let rgba = Rgba::from(color);
+            let [r, g, b] = [rgba.r, rgba.g, rgba.b].map(|c| (c * 255.).round() as u8);
+            if alpha < 1. {
+                format!("rgba({r}, {g}, {b}, {alpha})")
+            } else {
+                format!("rgb({r}, {g}, {b})")
+            }
+        }
+        ColorFormat::Hsl => {
+            let h = (color.h * 360.).round();
+            let s = (color.s * 100.).round();
+            let l = (color.l * 100.).round();
+            if alpha < 1. {
+                format!("hsla({h}, {s}%, {l}%, {alpha})")
+            } else {
+                format!("hsl({h}, {s}%, {l}%)")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use gpui::{Context as _, TestAppContext};
+    use language::{Buffer, Language, LanguageConfig};
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_color_literals_without_language_server(cx: &mut TestAppContext) {
+        let text = "color: #123; // see #456";
+        let css = Arc::new(Language::new(
+            LanguageConfig {
+                name: "CSS".into(),
+                ..Default::default()
+            },
+            None,
+        ));
+        let rust = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            None,
+        ));
+
+        for (language, expected_literals) in [(css, vec!["#123", "#456"]), (rust, vec![])] {
+            let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+            let literals = excerpt_color_literals(&snapshot, 0..text.len(), None)
+                .into_iter()
+                .map(|(range, _, _)| {
+                    snapshot
+                        .text_for_range(
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot),
+                        )
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(literals, expected_literals);
+        }
+    }
+
+    #[test]
+    fn test_find_color_literals() {
+        let text =
+            "a { color: #f00; background: rgba(0, 0, 255, 50%); border: hsl(120deg 100% 25%) }";
+        let literals = find_color_literals(text)
+            .into_iter()
+            .map(|(range, color, format)| (&text[range], format_color(color, format)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            literals,
+            [
+                ("#f00", "#ff0000".to_string()),
+                ("rgba(0, 0, 255, 50%)", "rgba(0, 0, 255, 0.5)".to_string()),
+                ("hsl(120deg 100% 25%)", "hsl(120, 100%, 25%)".to_string()),
+            ]
+        );
+
+        // Invalid literals, and hex digits that are part of other words, are not colors.
+        assert!(find_color_literals("#abcde foo#fff #xyz argb(1, 2, 3) rgb(1, 2)").is_empty());
+    }
+
+    #[test]
+    fn test_format_color() {
+        let (color, format) = parse_color("#12345680").unwrap();
+        assert_eq!(format, ColorFormat::Hex);
+        assert_eq!(format_color(color, format), "#12345680");
+        assert_eq!(
+            format_color(color, ColorFormat::Rgb),
+            "rgba(18, 52, 86, 0.5)"
+        );
+    }
+}
@@ -78,8 +78,18 @@ pub trait ToDisplayPoint {
     fn to_display_point(&self, map: &DisplaySnapshot) -> DisplayPoint;
 }
 
-type TextHighlights = TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
+/// Identifies a set of text highlights that share a style.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum HighlightKey {
+    Type(TypeId),
+    /// One of several sets of highlights added for the same type, each with its own style.
+    TypePlus(TypeId, usize),
+}
+
+type TextHighlights = TreeMap<HighlightKey, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+/// Non-overlapping ranges sorted by their start, each with its own style.
+type SemanticTokenHighlights = Arc<[(Range<Anchor>, HighlightStyle)]>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -103,6 +113,8 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Regions of text styled according to the semantic tokens of a language server.
+    semantic_token_highlights: SemanticTokenHighlights,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
     fold_placeholder: FoldPlaceholder,
@@ -154,6 +166,7 @@ impl DisplayMap {
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_token_highlights: Arc::from([]),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -181,6 +194,7 @@ impl DisplayMap {
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -346,7 +360,20 @@ impl DisplayMap {
         style: HighlightStyle,
     ) {
         self.text_highlights
-            .insert(Some(type_id), Arc::new((style, ranges)));
+            .insert(HighlightKey::Type(type_id), Arc::new((style, ranges)));
+    }
+
+    pub(crate) fn highlight_text_key(
+        &mut self,
+        type_id: TypeId,
+        key: usize,
+        ranges: Vec<Range<Anchor>>,
+        style: HighlightStyle,
+    ) {
+        self.text_highlights.insert(
+            HighlightKey::TypePlus(type_id, key),
+            Arc::new((style, ranges)),
+        );
     }
 
     pub(crate) fn highlight_inlays(
@@ -368,12 +395,34 @@ impl DisplayMap {
         }
     }
 
+    /// Replaces the styles applied from semantic tokens. The ranges must not overlap,
+    /// and must be sorted by their start.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    ) {
+        self.semantic_token_highlights = highlights.into();
+    }
+
     pub fn text_highlights(&self, type_id: TypeId) -> Option<(HighlightStyle, &[Range<Anchor>])> {
-        let highlights = self.text_highlights.get(&Some(type_id))?;
+        let highlights = self.text_highlights.get(&HighlightKey::Type(type_id))?;
         Some((highlights.0, &highlights.1))
     }
     pub fn clear_highlights(&mut self, type_id: TypeId) -> bool {
-        let mut cleared = self.text_highlights.remove(&Some(type_id)).is_some();
+        let mut cleared = self
+            .text_highlights
+            .remove(&HighlightKey::Type(type_id))
+            .is_some();
+        let is_keyed_by_type =
+            |key: &HighlightKey| matches!(key, HighlightKey::TypePlus(id, _) if *id == type_id);
+        if self
+            .text_highlights
+            .iter()
+            .any(|(key, _)| is_keyed_by_type(key))
+        {
+            self.text_highlights.retain(|key, _| !is_keyed_by_type(key));
+            cleared = true;
+        }
         cleared |= self.inlay_highlights.remove(&type_id).is_some();
         cleared
     }
@@ -388,6 +437,15 @@ impl DisplayMap {
             .update(cx, |map, cx| map.set_wrap_width(width, cx))
     }
 
+    pub fn set_wrap_hanging_indent(
+        &self,
+        hanging_indent: u32,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        self.wrap_map
+            .update(cx, |map, cx| map.set_hanging_indent(hanging_indent, cx))
+    }
+
     pub(crate) fn current_inlays(&self) -> impl Iterator<Item = &Inlay> {
         self.inlay_map.current_inlays()
     }
@@ -443,6 +501,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_token_highlights: Option<&'a [(Range<Anchor>, HighlightStyle)]>,
     pub styles: HighlightStyles,
 }
 
@@ -470,6 +529,7 @@ pub struct DisplaySnapshot {
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -654,6 +714,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 styles: highlight_styles,
             },
         )
@@ -1009,7 +1070,9 @@ impl DisplaySnapshot {
         &self,
     ) -> Option<Arc<(HighlightStyle, Vec<Range<Anchor>>)>> {
         let type_id = TypeId::of::<Tag>();
-        self.text_highlights.get(&Some(type_id)).cloned()
+        self.text_highlights
+            .get(&HighlightKey::Type(type_id))
+            .cloned()
     }
 
     #[allow(unused)]
@@ -6,7 +6,6 @@ use multi_buffer::{
     Anchor, MultiBufferChunks, MultiBufferRow, MultiBufferRows, MultiBufferSnapshot, ToOffset,
 };
 use std::{
-    any::TypeId,
     cmp,
     iter::Peekable,
     ops::{Add, AddAssign, Range, Sub, SubAssign},
@@ -16,7 +15,7 @@ use std::{
 use sum_tree::{Bias, Cursor, SumTree, TreeMap};
 use text::{Patch, Rope};
 
-use super::Highlights;
+use super::{HighlightKey, Highlights};
 
 /// Decides where the [`Inlay`]s should be displayed.
 ///
@@ -69,6 +68,17 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    /// A swatch shown before a color literal, colored through an inlay highlight.
+    pub fn color(id: usize, position: Anchor) -> Self {
+        Self {
+            id: InlayId::Color(id),
+            position,
+            text: Self::COLOR_SWATCH.into(),
+        }
+    }
+
+    pub const COLOR_SWATCH: &'static str = "■ ";
 }
 
 impl sum_tree::Item for Transform {
@@ -191,7 +201,7 @@ pub struct InlayBufferRows<'a> {
 struct HighlightEndpoint {
     offset: InlayOffset,
     is_start: bool,
-    tag: Option<TypeId>,
+    tag: HighlightKey,
     style: HighlightStyle,
 }
 
@@ -219,7 +229,10 @@ pub struct InlayChunks<'a> {
     max_output_offset: InlayOffset,
     highlight_styles: HighlightStyles,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    active_highlights: BTreeMap<HighlightKey, HighlightStyle>,
+    /// Offsets where a semantic token's style starts (`Some`) or ends (`None`).
+    semantic_token_boundaries: Peekable<vec::IntoIter<(InlayOffset, Option<HighlightStyle>)>>,
+    active_semantic_token_highlight: Option<HighlightStyle>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -264,6 +277,15 @@ impl<'a> Iterator for InlayChunks<'a> {
                 break;
             }
         }
+        while let Some((offset, style)) = self.semantic_token_boundaries.peek().copied() {
+            if offset <= self.output_offset {
+                self.active_semantic_token_highlight = style;
+                self.semantic_token_boundaries.next();
+            } else {
+                next_highlight_endpoint = cmp::min(next_highlight_endpoint, offset);
+                break;
+            }
+        }
 
         let chunk = match self.transforms.item()? {
             Transform::Isomorphic(_) => {
@@ -288,8 +310,11 @@ impl<'a> Iterator for InlayChunks<'a> {
                     text: prefix,
                     ..chunk.clone()
                 };
-                if !self.active_highlights.is_empty() {
-                    let mut highlight_style = HighlightStyle::default();
+                if self.active_semantic_token_highlight.is_some()
+                    || !self.active_highlights.is_empty()
+                {
+                    let mut highlight_style =
+                        self.active_semantic_token_highlight.unwrap_or_default();
                     for active_highlight in self.active_highlights.values() {
                         highlight_style.highlight(*active_highlight);
                     }
@@ -312,6 +337,7 @@ impl<'a> Iterator for InlayChunks<'a> {
                 let mut highlight_style = match inlay.id {
                     InlayId::Suggestion(_) => self.highlight_styles.suggestion,
                     InlayId::Hint(_) => self.highlight_styles.inlay_hint,
+                    InlayId::Color(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -1041,6 +1067,10 @@ impl InlaySnapshot {
             }
         }
         highlight_endpoints.sort();
+        let semantic_token_boundaries = highlights
+            .semantic_token_highlights
+            .map(|highlights| self.semantic_token_boundaries(&range, highlights))
+            .unwrap_or_default();
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
 
@@ -1055,6 +1085,8 @@ impl InlaySnapshot {
             highlight_styles: highlights.styles,
             highlight_endpoints: highlight_endpoints.into_iter().peekable(),
             active_highlights: Default::default(),
+            semantic_token_boundaries: semantic_token_boundaries.into_iter().peekable(),
+            active_semantic_token_highlight: None,
             highlights,
             snapshot: self,
         }
@@ -1064,7 +1096,7 @@ impl InlaySnapshot {
         &self,
         cursor: &mut Cursor<'_, Transform, (InlayOffset, usize)>,
         range: &Range<InlayOffset>,
-        text_highlights: &TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>,
+        text_highlights: &TreeMap<HighlightKey, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>,
         highlight_endpoints: &mut Vec<HighlightEndpoint>,
     ) {
         while cursor.start().0 < range.end {
@@ -1118,6 +1150,37 @@ impl InlaySnapshot {
         }
     }
 
+    fn semantic_token_boundaries(
+        &self,
+        range: &Range<InlayOffset>,
+        highlights: &[(Range<Anchor>, HighlightStyle)],
+    ) -> Vec<(InlayOffset, Option<HighlightStyle>)> {
+        let buffer_start = self
+            .buffer
+            .anchor_before(self.to_buffer_offset(range.start));
+        let buffer_end = self.buffer.anchor_after(self.to_buffer_offset(range.end));
+        let start_ix = highlights
+            .partition_point(|(probe, _)| probe.end.cmp(&buffer_start, &self.buffer).is_le());
+
+        let mut boundaries = Vec::new();
+        for (highlight_range, style) in &highlights[start_ix..] {
+            if highlight_range.start.cmp(&buffer_end, &self.buffer).is_ge() {
+                break;
+            }
+            // Ends are pushed before the start of the next range, so that adjacent ranges
+            // keep their own style.
+            boundaries.push((
+                self.to_inlay_offset(highlight_range.start.to_offset(&self.buffer)),
+                Some(*style),
+            ));
+            boundaries.push((
+                self.to_inlay_offset(highlight_range.end.to_offset(&self.buffer)),
+                None,
+            ));
+        }
+        boundaries
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
@@ -1177,7 +1240,7 @@ mod tests {
     use project::{InlayHint, InlayHintLabel, ResolveState};
     use rand::prelude::*;
     use settings::SettingsStore;
-    use std::{cmp::Reverse, env, sync::Arc};
+    use std::{any::TypeId, cmp::Reverse, env, sync::Arc};
     use text::Patch;
     use util::post_inc;
 
@@ -1676,7 +1739,7 @@ mod tests {
             text_highlight_ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
             log::info!("highlighting text ranges {text_highlight_ranges:?}");
             text_highlights.insert(
-                Some(TypeId::of::<()>()),
+                HighlightKey::Type(TypeId::of::<()>()),
                 Arc::new((
                     HighlightStyle::default(),
                     text_highlight_ranges
@@ -24,6 +24,7 @@ pub struct WrapMap {
     interpolated_edits: Patch<u32>,
     edits_since_sync: Patch<u32>,
     wrap_width: Option<Pixels>,
+    hanging_indent: u32,
     background_task: Option<Task<()>>,
     font_with_size: (Font, Pixels),
 }
@@ -80,6 +81,7 @@ impl WrapMap {
             let mut this = Self {
                 font_with_size: (font, font_size),
                 wrap_width: None,
+                hanging_indent: 0,
                 pending_edits: Default::default(),
                 interpolated_edits: Default::default(),
                 edits_since_sync: Default::default(),
@@ -149,6 +151,17 @@ impl WrapMap {
         true
     }
 
+    /// Sets how many more columns than the line itself wrapped lines are indented by.
+    pub fn set_hanging_indent(&mut self, hanging_indent: u32, cx: &mut ModelContext<Self>) -> bool {
+        if hanging_indent == self.hanging_indent {
+            return false;
+        }
+
+        self.hanging_indent = hanging_indent;
+        self.rewrap(cx);
+        true
+    }
+
     fn rewrap(&mut self, cx: &mut ModelContext<Self>) {
         self.background_task.take();
         self.interpolated_edits.clear();
@@ -159,6 +172,7 @@ impl WrapMap {
 
             let text_system = cx.text_system().clone();
             let (font, font_size) = self.font_with_size.clone();
+            let hanging_indent = self.hanging_indent;
             let task = cx.background_executor().spawn(async move {
                 let mut line_wrapper = text_system.line_wrapper(font, font_size);
                 let tab_snapshot = new_snapshot.tab_snapshot.clone();
@@ -171,6 +185,7 @@ impl WrapMap {
                             new: range.clone(),
                         }],
                         wrap_width,
+                        hanging_indent,
                         &mut line_wrapper,
                     )
                     .await;
@@ -243,12 +258,19 @@ impl WrapMap {
                 let mut snapshot = self.snapshot.clone();
                 let text_system = cx.text_system().clone();
                 let (font, font_size) = self.font_with_size.clone();
+                let hanging_indent = self.hanging_indent;
                 let update_task = cx.background_executor().spawn(async move {
                     let mut edits = Patch::default();
                     let mut line_wrapper = text_system.line_wrapper(font, font_size);
                     for (tab_snapshot, tab_edits) in pending_edits {
                         let wrap_edits = snapshot
-                            .update(tab_snapshot, &tab_edits, wrap_width, &mut line_wrapper)
+                            .update(
+                                tab_snapshot,
+                                &tab_edits,
+                                wrap_width,
+                                hanging_indent,
+                                &mut line_wrapper,
+                            )
                             .await;
                         edits = edits.compose(&wrap_edits);
                     }
@@ -390,6 +412,7 @@ impl WrapSnapshot {
         new_tab_snapshot: TabSnapshot,
         tab_edits: &[TabEdit],
         wrap_width: Pixels,
+        hanging_indent: u32,
         line_wrapper: &mut LineWrapper,
     ) -> Patch<u32> {
         #[derive(Debug)]
@@ -467,7 +490,11 @@ impl WrapSnapshot {
                     }
 
                     let mut prev_boundary_ix = 0;
-                    for boundary in line_wrapper.wrap_line(&line, wrap_width) {
+                    for boundary in line_wrapper.wrap_line_with_hanging_indent(
+                        &line,
+                        wrap_width,
+                        hanging_indent,
+                    ) {
                         let wrapped = &line[prev_boundary_ix..boundary.ix];
                         push_isomorphic(&mut edit_transforms, TextSummary::from(wrapped));
                         edit_transforms.push(Transform::wrap(boundary.next_indent));
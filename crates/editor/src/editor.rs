@@ -12,33 +12,45 @@
 //! All other submodules and structs are mostly concerned with holding editor data about the way it displays current buffer region(s).
 //!
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
+mod accessibility;
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
 mod clangd_ext;
+mod code_lens;
+mod color_swatches;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod font_zoom;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
 mod hunk_diff;
+mod inactive_regions;
 mod indent_guides;
 mod inlay_hint_cache;
 mod inline_completion_provider;
+mod inline_diagnostics;
 pub mod items;
 mod linked_editing_ranges;
 mod lsp_ext;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod persistent_undo;
+mod rainbow_brackets;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_drag;
 mod selections_collection;
+mod semantic_tokens;
+mod spell_check;
 pub mod tasks;
+mod trailing_whitespace;
 
 #[cfg(test)]
 mod editor_tests;
@@ -74,10 +86,10 @@ use gpui::{
     AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds, ClipboardEntry,
     ClipboardItem, Context, DispatchPhase, ElementId, EntityId, EventEmitter, FocusHandle,
     FocusOutEvent, FocusableView, FontId, FontWeight, HighlightStyle, Hsla, InteractiveText,
-    KeyContext, ListSizingBehavior, Model, MouseButton, PaintQuad, ParentElement, Pixels, Render,
-    SharedString, Size, StrikethroughStyle, Styled, StyledText, Subscription, Task, TextStyle,
-    UnderlineStyle, UniformListScrollHandle, View, ViewContext, ViewInputHandler, VisualContext,
-    WeakFocusHandle, WeakView, WindowContext,
+    KeyContext, ListSizingBehavior, Model, Modifiers, MouseButton, PaintQuad, ParentElement,
+    Pixels, Render, SharedString, Size, StrikethroughStyle, Styled, StyledText, Subscription, Task,
+    TextStyle, UnderlineStyle, UniformListScrollHandle, View, ViewContext, ViewInputHandler,
+    VisualContext, WeakFocusHandle, WeakView, WindowContext,
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
@@ -97,7 +109,7 @@ use language::{
 };
 use language::{point_to_lsp, BufferRow, Runnable, RunnableRange};
 use linked_editing_ranges::refresh_linked_ranges;
-use task::{ResolvedTask, TaskTemplate, TaskVariables};
+use task::{ResolvedTask, TaskId, TaskTemplate, TaskVariables};
 
 use hover_links::{find_file, HoverLink, HoveredLinkState, InlayHighlight};
 pub use lsp::CompletionContext;
@@ -117,11 +129,12 @@ use parking_lot::{Mutex, RwLock};
 use project::project_settings::{GitGutterSetting, ProjectSettings};
 use project::{
     CodeAction, Completion, CompletionIntent, FormatTrigger, Item, Location, Project, ProjectPath,
-    ProjectTransaction, TaskSourceKind, WorktreeId,
+    ProjectTransaction, TaskRunState, TaskSourceKind, WorktreeId,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
+use selection_drag::SelectionDragState;
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsStore};
@@ -246,6 +259,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Color(usize),
 }
 
 impl InlayId {
@@ -253,6 +267,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Color(id) => *id,
         }
     }
 }
@@ -261,6 +276,7 @@ enum DiffRowHighlight {}
 enum DocumentHighlightRead {}
 enum DocumentHighlightWrite {}
 enum InputComposition {}
+enum InputCompositionClause {}
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
@@ -296,10 +312,28 @@ pub fn init(cx: &mut AppContext) {
     workspace::register_serializable_item::<Editor>(cx);
 
     cx.observe_new_views(
-        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
             workspace.register_action(Editor::new_file);
             workspace.register_action(Editor::new_file_vertical);
             workspace.register_action(Editor::new_file_horizontal);
+
+            cx.subscribe(workspace.project(), |workspace, _, event, cx| {
+                if let project::Event::WorkspaceEditApplied {
+                    label,
+                    transaction,
+                    needs_confirmation,
+                } = event
+                {
+                    Editor::open_workspace_edit(
+                        workspace,
+                        label.clone(),
+                        transaction.clone(),
+                        *needs_confirmation,
+                        cx,
+                    );
+                }
+            })
+            .detach();
         },
     )
     .detach();
@@ -490,6 +524,7 @@ pub struct Editor {
     /// typing enters text into each of them, even the ones that aren't focused.
     pub(crate) show_cursor_when_unfocused: bool,
     columnar_selection_tail: Option<Anchor>,
+    keyboard_columnar_selection: Option<KeyboardColumnarSelection>,
     add_selections_state: Option<AddSelectionsState>,
     select_next_state: Option<SelectNextState>,
     select_prev_state: Option<SelectNextState>,
@@ -500,6 +535,9 @@ pub struct Editor {
     ime_transaction: Option<TransactionId>,
     active_diagnostics: Option<ActiveDiagnosticGroup>,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
+    /// Whether the editor shows a file too large for syntax highlighting,
+    /// language servers, soft wrapping and inlay hints.
+    large_file_mode: bool,
     project: Option<Model<Project>>,
     completion_provider: Option<Box<dyn CompletionProvider>>,
     collaboration_hub: Option<Box<dyn CollaborationHub>>,
@@ -510,12 +548,19 @@ pub struct Editor {
     mode: EditorMode,
     show_breadcrumbs: bool,
     show_gutter: bool,
+    /// Hides the gutter while the editor's pane is in zen mode.
+    zen_mode: bool,
     show_line_numbers: Option<bool>,
     show_git_diff_gutter: Option<bool>,
     show_code_actions: Option<bool>,
     show_runnables: Option<bool>,
     show_wrap_guides: Option<bool>,
     show_indent_guides: Option<bool>,
+    show_buffer_header_controls: bool,
+    selection_drag_state: SelectionDragState,
+    drop_cursor: Option<Anchor>,
+    font_size_adjustment: Pixels,
+    announced_edit_count: usize,
     placeholder_text: Option<Arc<str>>,
     highlight_order: usize,
     highlighted_rows: HashMap<TypeId, Vec<RowHighlight>>,
@@ -536,8 +581,15 @@ pub struct Editor {
     code_actions_task: Option<Task<()>>,
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
+    semantic_tokens_task: Option<Task<()>>,
+    spell_check: spell_check::SpellCheckState,
+    code_lens: code_lens::CodeLensState,
+    color_swatches: Vec<color_swatches::ColorSwatch>,
+    color_swatches_task: Option<Task<()>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
     pending_rename: Option<RenameState>,
+    rename_preview: Option<RenamePreview>,
+    persistent_undo_history: Option<persistent_undo::PersistentUndoHistory>,
     searchable: bool,
     cursor_shape: CursorShape,
     current_line_highlight: Option<CurrentLineHighlight>,
@@ -556,6 +608,7 @@ pub struct Editor {
     active_inline_completion: Option<(Inlay, Option<Range<Anchor>>)>,
     show_inline_completions: bool,
     inlay_hint_cache: InlayHintCache,
+    inlay_hints_toggled_by_modifiers: bool,
     expanded_hunks: ExpandedHunks,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
@@ -572,6 +625,7 @@ pub struct Editor {
     show_git_blame_inline_delay_task: Option<Task<()>>,
     git_blame_inline_enabled: bool,
     serialize_dirty_buffers: bool,
+    serialize_selections: Task<()>,
     show_selection_menu: Option<bool>,
     blame: Option<Model<GitBlame>>,
     blame_subscription: Option<Subscription>,
@@ -584,6 +638,8 @@ pub struct Editor {
     last_bounds: Option<Bounds<Pixels>>,
     expect_bounds_change: Option<Bounds<Pixels>>,
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
+    /// The last task spawned from each runnable, to show whether it passed in the gutter.
+    runnable_task_ids: HashMap<(BufferId, BufferRow), TaskId>,
     tasks_update_task: Option<Task<()>>,
     previous_search_ranges: Option<Arc<[Range<Anchor>]>>,
     file_header_size: u32,
@@ -775,6 +831,16 @@ struct RowHighlight {
     should_autoscroll: bool,
 }
 
+/// The corners of a block selection that is being grown with the keyboard.
+#[derive(Clone, Debug)]
+struct KeyboardColumnarSelection {
+    tail: Anchor,
+    head: DisplayPoint,
+    /// The selections produced by the last growth, used to detect whether the
+    /// selections were changed in some other way since then.
+    selections: Arc<[Selection<Anchor>]>,
+}
+
 #[derive(Clone, Debug)]
 struct AddSelectionsState {
     above: bool,
@@ -818,6 +884,23 @@ pub struct RenameState {
     block_id: CustomBlockId,
 }
 
+/// The edits of a multi-file rename or refactoring that are displayed in a preview
+/// multibuffer, and can still be accepted or rejected on a per-file basis.
+struct RenamePreview {
+    edits: HashMap<Model<Buffer>, PreviewedEdits>,
+}
+
+enum PreviewedEdits {
+    /// Edits that are only applied to the buffer once accepted. The text they insert is
+    /// displayed in inlays after the text they replace.
+    Pending {
+        edits: Vec<(Range<text::Anchor>, String)>,
+        inlay_ids: Vec<InlayId>,
+    },
+    /// A transaction that was already applied, and is undone if rejected.
+    Applied(language::Transaction),
+}
+
 struct InvalidationStack<T>(Vec<T>);
 
 struct RegisteredInlineCompletionProvider {
@@ -1324,25 +1407,20 @@ impl CompletionsMenu {
 struct CodeActionContents {
     tasks: Option<Arc<ResolvedTasks>>,
     actions: Option<Arc<[CodeAction]>>,
+    /// The editor's own actions on the symbol under the cursor, listed after the language
+    /// server's code actions.
+    editor_actions: Vec<EditorCodeAction>,
 }
 
 impl CodeActionContents {
     fn len(&self) -> usize {
-        match (&self.tasks, &self.actions) {
-            (Some(tasks), Some(actions)) => actions.len() + tasks.templates.len(),
-            (Some(tasks), None) => tasks.templates.len(),
-            (None, Some(actions)) => actions.len(),
-            (None, None) => 0,
-        }
+        let tasks_len = self.tasks.as_ref().map_or(0, |tasks| tasks.templates.len());
+        let actions_len = self.actions.as_ref().map_or(0, |actions| actions.len());
+        tasks_len + actions_len + self.editor_actions.len()
     }
 
     fn is_empty(&self) -> bool {
-        match (&self.tasks, &self.actions) {
-            (Some(tasks), Some(actions)) => actions.is_empty() && tasks.templates.is_empty(),
-            (Some(tasks), None) => tasks.templates.is_empty(),
-            (None, Some(actions)) => actions.is_empty(),
-            (None, None) => true,
-        }
+        self.len() == 0
     }
 
     fn iter(&self) -> impl Iterator<Item = CodeActionsItem> + '_ {
@@ -1359,31 +1437,65 @@ impl CodeActionContents {
                     .iter()
                     .map(|action| CodeActionsItem::CodeAction(action.clone()))
             }))
+            .chain(
+                self.editor_actions
+                    .iter()
+                    .map(|action| CodeActionsItem::EditorAction(*action)),
+            )
     }
-    fn get(&self, index: usize) -> Option<CodeActionsItem> {
-        match (&self.tasks, &self.actions) {
-            (Some(tasks), Some(actions)) => {
-                if index < tasks.templates.len() {
-                    tasks
-                        .templates
-                        .get(index)
-                        .cloned()
-                        .map(|(kind, task)| CodeActionsItem::Task(kind, task))
-                } else {
-                    actions
-                        .get(index - tasks.templates.len())
-                        .cloned()
-                        .map(CodeActionsItem::CodeAction)
-                }
+    fn get(&self, mut index: usize) -> Option<CodeActionsItem> {
+        if let Some(tasks) = &self.tasks {
+            if let Some((kind, task)) = tasks.templates.get(index) {
+                return Some(CodeActionsItem::Task(kind.clone(), task.clone()));
             }
-            (Some(tasks), None) => tasks
-                .templates
-                .get(index)
-                .cloned()
-                .map(|(kind, task)| CodeActionsItem::Task(kind, task)),
-            (None, Some(actions)) => actions.get(index).cloned().map(CodeActionsItem::CodeAction),
-            (None, None) => None,
+            index -= tasks.templates.len();
         }
+        if let Some(actions) = &self.actions {
+            if let Some(action) = actions.get(index) {
+                return Some(CodeActionsItem::CodeAction(action.clone()));
+            }
+            index -= actions.len();
+        }
+        self.editor_actions
+            .get(index)
+            .copied()
+            .map(CodeActionsItem::EditorAction)
+    }
+}
+
+/// An action of the editor that's offered in the code actions menu, next to the language
+/// server's code actions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditorCodeAction {
+    ShowCallHierarchy,
+    ShowTypeHierarchy,
+}
+
+impl EditorCodeAction {
+    fn label(self) -> &'static str {
+        match self {
+            Self::ShowCallHierarchy => "Show Call Hierarchy",
+            Self::ShowTypeHierarchy => "Show Type Hierarchy",
+        }
+    }
+
+    fn action(self) -> Box<dyn Action> {
+        match self {
+            Self::ShowCallHierarchy => Box::new(ShowIncomingCalls),
+            Self::ShowTypeHierarchy => Box::new(ShowSubtypes),
+        }
+    }
+
+    /// The editor actions that apply to the buffer.
+    fn available(project: &Project, buffer: &Buffer, cx: &AppContext) -> Vec<Self> {
+        let mut actions = Vec::new();
+        if project.supports_call_hierarchy(buffer, cx) {
+            actions.push(Self::ShowCallHierarchy);
+        }
+        if project.supports_type_hierarchy(buffer, cx) {
+            actions.push(Self::ShowTypeHierarchy);
+        }
+        actions
     }
 }
 
@@ -1392,6 +1504,7 @@ impl CodeActionContents {
 enum CodeActionsItem {
     Task(TaskSourceKind, ResolvedTask),
     CodeAction(CodeAction),
+    EditorAction(EditorCodeAction),
 }
 
 impl CodeActionsItem {
@@ -1407,10 +1520,17 @@ impl CodeActionsItem {
         };
         Some(action)
     }
+    fn as_editor_action(&self) -> Option<EditorCodeAction> {
+        let Self::EditorAction(action) = self else {
+            return None;
+        };
+        Some(*action)
+    }
     fn label(&self) -> String {
         match self {
             Self::CodeAction(action) => action.lsp_action.title.clone(),
             Self::Task(_, task) => task.resolved_label.clone(),
+            Self::EditorAction(action) => action.label().to_string(),
         }
     }
 }
@@ -1532,6 +1652,23 @@ impl CodeActionsMenu {
                                 )
                                 .child(SharedString::from(task.resolved_label.clone()))
                             })
+                            .when_some(action.as_editor_action(), |this, action| {
+                                this.on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(move |editor, _, cx| {
+                                        cx.stop_propagation();
+                                        if let Some(task) = editor.confirm_code_action(
+                                            &ConfirmCodeAction {
+                                                item_ix: Some(item_ix),
+                                            },
+                                            cx,
+                                        ) {
+                                            task.detach_and_log_err(cx)
+                                        }
+                                    }),
+                                )
+                                .child(action.label())
+                            })
                     })
                     .collect()
             },
@@ -1549,6 +1686,7 @@ impl CodeActionsMenu {
                 .max_by_key(|(_, action)| match action {
                     CodeActionsItem::Task(_, task) => task.resolved_label.chars().count(),
                     CodeActionsItem::CodeAction(action) => action.lsp_action.title.chars().count(),
+                    CodeActionsItem::EditorAction(action) => action.label().chars().count(),
                 })
                 .map(|(ix, _)| ix),
         )
@@ -1585,6 +1723,10 @@ pub struct ClipboardSelection {
 pub(crate) struct NavigationData {
     cursor_anchor: Anchor,
     cursor_position: Point,
+    /// All selections of the editor, which are restored instead of a single cursor
+    /// when present, e.g. when reopening a closed editor.
+    selections: Vec<Range<Anchor>>,
+    selection_positions: Vec<Range<Point>>,
     scroll_anchor: ScrollAnchor,
     scroll_top_row: u32,
 }
@@ -1768,8 +1910,19 @@ impl Editor {
 
         let blink_manager = cx.new_model(|cx| BlinkManager::new(CURSOR_BLINK_INTERVAL, cx));
 
-        let soft_wrap_mode_override = matches!(mode, EditorMode::SingleLine { .. })
-            .then(|| language_settings::SoftWrap::PreferLine);
+        let large_file_mode = mode == EditorMode::Full
+            && project.is_some()
+            && buffer.read(cx).as_singleton().map_or(false, |buffer| {
+                ProjectSettings::get_global(cx)
+                    .large_file
+                    .is_large_file(buffer.read(cx))
+            });
+        let soft_wrap_mode_override = if large_file_mode {
+            Some(language_settings::SoftWrap::None)
+        } else {
+            matches!(mode, EditorMode::SingleLine { .. })
+                .then(|| language_settings::SoftWrap::PreferLine)
+        };
 
         let mut project_subscriptions = Vec::new();
         if mode == EditorMode::Full {
@@ -1782,6 +1935,10 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::InactiveRegionsUpdated(buffer_id) = event {
+                        if editor.buffer.read(cx).buffer(*buffer_id).is_some() {
+                            editor.refresh_inactive_regions(cx);
+                        }
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1803,6 +1960,11 @@ impl Editor {
                     editor.tasks_update_task = Some(editor.refresh_runnables(cx));
                 }));
             }
+            if let Some(spell_checker) = spellcheck::SpellChecker::global(cx) {
+                project_subscriptions.push(cx.observe(&spell_checker, |editor, _, cx| {
+                    editor.reset_spell_check(cx);
+                }));
+            }
         }
 
         let inlay_hint_settings = inlay_hint_settings(
@@ -1833,6 +1995,7 @@ impl Editor {
             selections,
             scroll_manager: ScrollManager::new(cx),
             columnar_selection_tail: None,
+            keyboard_columnar_selection: None,
             add_selections_state: None,
             select_next_state: None,
             select_prev_state: None,
@@ -1843,6 +2006,7 @@ impl Editor {
             ime_transaction: Default::default(),
             active_diagnostics: None,
             soft_wrap_mode_override,
+            large_file_mode,
             completion_provider: project.clone().map(|project| Box::new(project) as _),
             collaboration_hub: project.clone().map(|project| Box::new(project) as _),
             project,
@@ -1851,12 +2015,18 @@ impl Editor {
             mode,
             show_breadcrumbs: EditorSettings::get_global(cx).toolbar.breadcrumbs,
             show_gutter: mode == EditorMode::Full,
+            zen_mode: false,
             show_line_numbers: None,
             show_git_diff_gutter: None,
             show_code_actions: None,
             show_runnables: None,
             show_wrap_guides: None,
             show_indent_guides,
+            show_buffer_header_controls: false,
+            selection_drag_state: SelectionDragState::default(),
+            drop_cursor: None,
+            font_size_adjustment: Pixels::ZERO,
+            announced_edit_count: buffer.read(cx).snapshot(cx).edit_count(),
             placeholder_text: None,
             highlight_order: 0,
             highlighted_rows: HashMap::default(),
@@ -1878,7 +2048,14 @@ impl Editor {
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
             linked_editing_range_task: Default::default(),
+            semantic_tokens_task: None,
+            spell_check: Default::default(),
+            code_lens: Default::default(),
+            color_swatches: Vec::new(),
+            color_swatches_task: None,
             pending_rename: Default::default(),
+            rename_preview: None,
+            persistent_undo_history: None,
             searchable: true,
             cursor_shape: Default::default(),
             current_line_highlight: None,
@@ -1898,6 +2075,7 @@ impl Editor {
             inline_completion_provider: None,
             active_inline_completion: None,
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
+            inlay_hints_toggled_by_modifiers: false,
             expanded_hunks: ExpandedHunks::default(),
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
@@ -1919,10 +2097,12 @@ impl Editor {
             serialize_dirty_buffers: ProjectSettings::get_global(cx)
                 .session
                 .restore_unsaved_buffers,
+            serialize_selections: Task::ready(()),
             blame: None,
             blame_subscription: None,
             file_header_size,
             tasks: Default::default(),
+            runnable_task_ids: HashMap::default(),
             _subscriptions: vec![
                 cx.observe(&buffer, Self::on_buffer_changed),
                 cx.subscribe(&buffer, Self::on_buffer_event),
@@ -1951,6 +2131,11 @@ impl Editor {
             _scroll_cursor_center_top_bottom_task: Task::ready(()),
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
+        this.refresh_semantic_tokens(cx);
+        this.refresh_color_swatches(cx);
+        this.refresh_code_lenses(cx);
+        this.refresh_inactive_regions(cx);
+        this.refresh_spell_check(cx);
         this._subscriptions.extend(project_subscriptions);
 
         this.end_selection(cx);
@@ -1993,6 +2178,9 @@ impl Editor {
         if self.pending_rename.is_some() {
             key_context.add("renaming");
         }
+        if self.rename_preview.is_some() {
+            key_context.add("rename_preview");
+        }
         if self.context_menu_visible() {
             match self.context_menu.read().as_ref() {
                 Some(ContextMenu::Completions(_)) => {
@@ -2006,6 +2194,12 @@ impl Editor {
                 None => {}
             }
         }
+        if self.signature_help_state.is_shown() {
+            key_context.add("showing_signature_help");
+            if self.signature_help_state.signature_count() > 1 {
+                key_context.add("multiple_signatures");
+            }
+        }
 
         // Disable vim contexts when a sub-editor (e.g. rename/inline assistant) is focused.
         if !self.focus_handle(cx).contains_focused(cx)
@@ -2025,6 +2219,16 @@ impl Editor {
             key_context.set("extension", extension.to_string());
         }
 
+        // In a multibuffer, this is the language of the buffer with the newest cursor.
+        let buffer = self.buffer.read(cx);
+        if let Some(language) = buffer
+            .as_singleton()
+            .or_else(|| buffer.buffer(self.selections.newest_anchor().head().buffer_id?))
+            .and_then(|buffer| buffer.read(cx).language().cloned())
+        {
+            key_context.set("language", language.name().to_lowercase());
+        }
+
         if self.has_active_inline_completion(cx) {
             key_context.add("copilot_suggestion");
             key_context.add("inline_completion");
@@ -2138,7 +2342,7 @@ impl Editor {
     pub fn snapshot(&mut self, cx: &mut WindowContext) -> EditorSnapshot {
         EditorSnapshot {
             mode: self.mode,
-            show_gutter: self.show_gutter,
+            show_gutter: self.show_gutter && !self.zen_mode,
             show_line_numbers: self.show_line_numbers,
             show_git_diff_gutter: self.show_git_diff_gutter,
             show_code_actions: self.show_code_actions,
@@ -2371,6 +2575,7 @@ impl Editor {
 
         self.push_to_nav_history(
             *old_cursor_position,
+            Vec::new(),
             Some(new_cursor_position.to_point(buffer)),
             cx,
         );
@@ -2440,6 +2645,7 @@ impl Editor {
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
             refresh_matching_bracket_highlights(self, cx);
+            self.announce_selection_change(old_cursor_position, cx);
             self.discard_inline_completion(false, cx);
             linked_editing_ranges::refresh_linked_ranges(self, cx);
             if self.git_blame_inline_enabled {
@@ -2448,6 +2654,7 @@ impl Editor {
         }
 
         self.blink_manager.update(cx, BlinkManager::pause_blinking);
+        self.serialize_selections(cx);
         cx.emit(EditorEvent::SelectionsChanged { local });
 
         if self.selections.disjoint_anchors().len() == 1 {
@@ -2877,6 +3084,26 @@ impl Editor {
         let end_column = cmp::max(tail.column(), goal_column);
         let reversed = start_column < tail.column();
 
+        // Selections spanning multiple rows remember their horizontal extent, so that
+        // they behave as a block (e.g. as a visual block selection in vim mode).
+        let goal = if start_row == end_row {
+            SelectionGoal::None
+        } else {
+            let text_layout_details = self.text_layout_details(cx);
+            let start_x = display_map.x_for_display_point(
+                DisplayPoint::new(tail.row(), start_column),
+                &text_layout_details,
+            );
+            let end_x = display_map.x_for_display_point(
+                DisplayPoint::new(tail.row(), end_column),
+                &text_layout_details,
+            );
+            SelectionGoal::HorizontalRange {
+                start: start_x.into(),
+                end: end_x.into(),
+            }
+        };
+
         let selection_ranges = (start_row.0..=end_row.0)
             .map(DisplayRow)
             .filter_map(|row| {
@@ -2887,11 +3114,7 @@ impl Editor {
                     let end = display_map
                         .clip_point(DisplayPoint::new(row, end_column), Bias::Right)
                         .to_point(display_map);
-                    if reversed {
-                        Some(end..start)
-                    } else {
-                        Some(start..end)
-                    }
+                    Some(start..end)
                 } else {
                     None
                 }
@@ -2899,11 +3122,73 @@ impl Editor {
             .collect::<Vec<_>>();
 
         self.change_selections(None, cx, |s| {
-            s.select_ranges(selection_ranges);
+            let selections = selection_ranges
+                .into_iter()
+                .map(|range| Selection {
+                    id: s.new_selection_id(),
+                    start: range.start,
+                    end: range.end,
+                    reversed,
+                    goal,
+                })
+                .collect();
+            s.select(selections);
         });
         cx.notify();
     }
 
+    pub fn select_column_up(&mut self, _: &SelectColumnUp, cx: &mut ViewContext<Self>) {
+        self.grow_columnar_selection(-1, 0, cx);
+    }
+
+    pub fn select_column_down(&mut self, _: &SelectColumnDown, cx: &mut ViewContext<Self>) {
+        self.grow_columnar_selection(1, 0, cx);
+    }
+
+    pub fn select_column_left(&mut self, _: &SelectColumnLeft, cx: &mut ViewContext<Self>) {
+        self.grow_columnar_selection(0, -1, cx);
+    }
+
+    pub fn select_column_right(&mut self, _: &SelectColumnRight, cx: &mut ViewContext<Self>) {
+        self.grow_columnar_selection(0, 1, cx);
+    }
+
+    /// Moves the head of a block selection by the given number of rows and columns,
+    /// starting a new block selection at the newest selection if necessary.
+    fn grow_columnar_selection(
+        &mut self,
+        row_delta: i32,
+        column_delta: i32,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let (tail, head) = match self.keyboard_columnar_selection.take() {
+            Some(state) if Arc::ptr_eq(&state.selections, &self.selections.disjoint_anchors()) => {
+                (state.tail.to_display_point(&display_map), state.head)
+            }
+            _ => {
+                let newest = self.selections.newest_display(cx);
+                (newest.tail(), newest.head())
+            }
+        };
+
+        let max_row = display_map.max_point().row();
+        let head = DisplayPoint::new(
+            DisplayRow(head.row().0.saturating_add_signed(row_delta)).min(max_row),
+            head.column().saturating_add_signed(column_delta),
+        );
+        self.select_columns(tail, head, head.column(), &display_map, cx);
+        self.request_autoscroll(Autoscroll::fit(), cx);
+
+        self.keyboard_columnar_selection = Some(KeyboardColumnarSelection {
+            tail: display_map
+                .buffer_snapshot
+                .anchor_before(tail.to_point(&display_map)),
+            head,
+            selections: self.selections.disjoint_anchors(),
+        });
+    }
+
     pub fn has_pending_nonempty_selection(&self) -> bool {
         let pending_nonempty_selection = match self.selections.pending_anchor() {
             Some(Selection { start, end, .. }) => start != end,
@@ -3316,7 +3601,7 @@ impl Editor {
                 s.select(new_selections)
             });
 
-            if !bracket_inserted && EditorSettings::get_global(cx).use_on_type_format {
+            if !bracket_inserted {
                 if let Some(on_type_format_task) =
                     this.trigger_on_type_formatting(text.to_string(), cx)
                 {
@@ -3881,8 +4166,41 @@ impl Editor {
         self.inlay_hint_cache.enabled
     }
 
+    /// Toggles inlay hints while the modifiers from the `toggle_on_modifiers_press` setting
+    /// are held down, and toggles them back once any of them is released.
+    fn toggle_inlay_hints_on_modifiers(
+        &mut self,
+        modifiers: Modifiers,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let toggle_modifiers = inlay_hint_settings(
+            self.selections.newest_anchor().head(),
+            &self.buffer.read(cx).snapshot(cx),
+            cx,
+        )
+        .toggle_on_modifiers_press;
+        let pressed = toggle_modifiers.map_or(false, |toggle_modifiers| {
+            toggle_modifiers.modified() && modifiers == toggle_modifiers
+        });
+        if pressed == self.inlay_hints_toggled_by_modifiers
+            || (pressed && !self.focus_handle.is_focused(cx))
+        {
+            return;
+        }
+
+        self.inlay_hints_toggled_by_modifiers = pressed;
+        self.refresh_inlay_hints(
+            InlayHintRefreshReason::Toggle(!self.inlay_hint_cache.enabled),
+            cx,
+        );
+    }
+
+    pub fn large_file_mode(&self) -> bool {
+        self.large_file_mode
+    }
+
     fn refresh_inlay_hints(&mut self, reason: InlayHintRefreshReason, cx: &mut ViewContext<Self>) {
-        if self.project.is_none() || self.mode != EditorMode::Full {
+        if self.project.is_none() || self.mode != EditorMode::Full || self.large_file_mode {
             return;
         }
 
@@ -4064,6 +4382,15 @@ impl Editor {
             .buffer
             .read(cx)
             .text_anchor_for_position(position, cx)?;
+        let use_on_type_format = {
+            let buffer = buffer.read(cx);
+            let language = buffer.language_at(buffer_position);
+            language_settings::language_settings(language.as_ref(), buffer.file(), cx)
+                .use_on_type_format
+        };
+        if !use_on_type_format {
+            return None;
+        }
 
         // OnTypeFormatting returns a list of edits, no need to pass them between Zed instances,
         // hence we do LSP request & edit on host side only — add formats to host's history.
@@ -4501,7 +4828,15 @@ impl Editor {
                         .tasks
                         .get(&(buffer_id, buffer_row))
                         .map(|t| Arc::new(t.to_owned()));
-                    if tasks.is_none() && code_actions.is_none() {
+                    // The editor's actions apply to the symbol under the cursor, rather than
+                    // to a whole row of the gutter.
+                    let editor_actions = match &editor.project {
+                        Some(project) if deployed_from_indicator.is_none() => {
+                            EditorCodeAction::available(project.read(cx), buffer.read(cx), cx)
+                        }
+                        _ => Vec::new(),
+                    };
+                    if tasks.is_none() && code_actions.is_none() && editor_actions.is_empty() {
                         return None;
                     }
 
@@ -4563,7 +4898,8 @@ impl Editor {
                             .map_or(false, |tasks| tasks.templates.len() == 1)
                             && code_actions
                                 .as_ref()
-                                .map_or(true, |actions| actions.is_empty());
+                                .map_or(true, |actions| actions.is_empty())
+                            && editor_actions.is_empty();
                         if let Some(task) = editor
                             .update(&mut cx, |editor, cx| {
                                 *editor.context_menu.write() =
@@ -4572,6 +4908,7 @@ impl Editor {
                                         actions: CodeActionContents {
                                             tasks: resolved_tasks,
                                             actions: code_actions,
+                                            editor_actions,
                                         },
                                         selected_item: Default::default(),
                                         scroll_handle: UniformListScrollHandle::default(),
@@ -4627,6 +4964,18 @@ impl Editor {
 
         match action {
             CodeActionsItem::Task(task_source_kind, resolved_task) => {
+                if let Some(tasks) = actions_menu.actions.tasks.as_ref() {
+                    let snapshot = self.buffer.read(cx).snapshot(cx);
+                    let row = tasks.position.to_point(&snapshot).row;
+                    if let Some((buffer_snapshot, range)) =
+                        snapshot.buffer_line_for_row(MultiBufferRow(row))
+                    {
+                        self.runnable_task_ids.insert(
+                            (buffer_snapshot.remote_id(), range.start.row),
+                            resolved_task.id.clone(),
+                        );
+                    }
+                }
                 workspace.update(cx, |workspace, cx| {
                     workspace::tasks::schedule_resolved_task(
                         workspace,
@@ -4660,6 +5009,10 @@ impl Editor {
                     .await
                 }))
             }
+            CodeActionsItem::EditorAction(action) => {
+                cx.dispatch_action(action.action());
+                Some(Task::ready(Ok(())))
+            }
         }
     }
 
@@ -4712,42 +5065,297 @@ impl Editor {
             return Ok(());
         }
 
-        let mut ranges_to_highlight = Vec::new();
+        Self::open_transaction_entries(workspace, replica_id, entries, title, &mut cx)?;
+        Ok(())
+    }
+
+    /// Opens the edits of a multi-file rename or refactoring in a preview multibuffer,
+    /// where they can be reviewed and rejected per file before being saved.
+    async fn open_rename_preview(
+        workspace: WeakView<Workspace>,
+        transaction: ProjectTransaction,
+        title: String,
+        mut cx: AsyncWindowContext,
+    ) -> Result<()> {
+        let replica_id = workspace.update(&mut cx, |workspace, cx| {
+            workspace.project().read(cx).replica_id()
+        })?;
+        let edits = transaction
+            .0
+            .iter()
+            .map(|(buffer, transaction)| {
+                (buffer.clone(), PreviewedEdits::Applied(transaction.clone()))
+            })
+            .collect();
+        let mut entries = transaction.0.into_iter().collect::<Vec<_>>();
+        cx.update(|cx| {
+            entries.sort_unstable_by_key(|(buffer, _)| {
+                buffer.read(cx).file().map(|f| f.path().clone())
+            });
+        })?;
+
+        let editor =
+            Self::open_transaction_entries(workspace, replica_id, entries, title, &mut cx)?;
+        editor.update(&mut cx, |editor, cx| {
+            editor.rename_preview = Some(RenamePreview { edits });
+            cx.notify();
+        })?;
+        Ok(())
+    }
+
+    /// Opens the edits of a multi-file rename in a preview multibuffer without applying
+    /// them. Each file's edits are applied when they are accepted.
+    async fn open_rename_edits_preview(
+        workspace: WeakView<Workspace>,
+        edits: HashMap<Model<Buffer>, Vec<(Range<text::Anchor>, String)>>,
+        title: String,
+        mut cx: AsyncWindowContext,
+    ) -> Result<()> {
+        let replica_id = workspace.update(&mut cx, |workspace, cx| {
+            workspace.project().read(cx).replica_id()
+        })?;
+        let mut entries = edits.into_iter().collect::<Vec<_>>();
+        let ranges = cx.update(|cx| {
+            entries.sort_unstable_by_key(|(buffer, _)| {
+                buffer.read(cx).file().map(|f| f.path().clone())
+            });
+            entries
+                .iter_mut()
+                .map(|(buffer, edits)| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    edits.sort_by_key(|(range, _)| range.start.to_offset(&snapshot));
+                    let ranges = edits
+                        .iter()
+                        .map(|(range, _)| {
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot)
+                        })
+                        .collect();
+                    (buffer.clone(), ranges)
+                })
+                .collect::<Vec<_>>()
+        })?;
+
+        let (editor, excerpt_ranges) =
+            Self::open_edited_ranges(workspace, replica_id, ranges, title, &mut cx)?;
+        editor.update(&mut cx, |editor, cx| {
+            let mut inlays = Vec::new();
+            let mut replaced_ranges = Vec::new();
+            let mut previewed_edits = HashMap::default();
+            for ((buffer, edits), excerpt_ranges) in entries.into_iter().zip(excerpt_ranges) {
+                let mut inlay_ids = Vec::new();
+                for ((_, new_text), range) in edits.iter().zip(excerpt_ranges) {
+                    let inlay = Inlay::suggestion(
+                        post_inc(&mut editor.next_inlay_id),
+                        range.end,
+                        new_text.as_str(),
+                    );
+                    inlay_ids.push(inlay.id);
+                    inlays.push(inlay);
+                    replaced_ranges.push(range);
+                }
+                previewed_edits.insert(buffer, PreviewedEdits::Pending { edits, inlay_ids });
+            }
+            editor.splice_inlays(Vec::new(), inlays, cx);
+            editor.highlight_text::<RenamePreview>(
+                replaced_ranges,
+                HighlightStyle {
+                    strikethrough: Some(StrikethroughStyle {
+                        thickness: px(1.),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                cx,
+            );
+            editor.rename_preview = Some(RenamePreview {
+                edits: previewed_edits,
+            });
+            cx.notify();
+        })?;
+        Ok(())
+    }
+
+    /// Opens the edits that a language server made on its own. Edits to several files, or
+    /// that the server wants reviewed, are opened in a preview where they can be rejected
+    /// per file. Edits to a single file are shown in the active editor when it contains them.
+    fn open_workspace_edit(
+        workspace: &mut Workspace,
+        label: Option<String>,
+        transaction: ProjectTransaction,
+        needs_confirmation: bool,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let title = label.unwrap_or_else(|| "Workspace Edit".to_string());
+        let active_editor = workspace
+            .active_item_as::<Editor>(cx)
+            .map(|editor| editor.downgrade());
+        cx.spawn(|workspace, cx| async move {
+            match active_editor {
+                Some(editor) if !needs_confirmation && transaction.0.len() == 1 => {
+                    Self::open_project_transaction(&editor, workspace, transaction, title, cx).await
+                }
+                _ => Self::open_rename_preview(workspace, transaction, title, cx).await,
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_transaction_entries(
+        workspace: WeakView<Workspace>,
+        replica_id: ReplicaId,
+        entries: Vec<(Model<Buffer>, language::Transaction)>,
+        title: String,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<View<Editor>> {
+        let ranges = cx.update(|cx| {
+            entries
+                .iter()
+                .map(|(buffer, transaction)| {
+                    let ranges = buffer
+                        .read(cx)
+                        .edited_ranges_for_transaction::<usize>(transaction)
+                        .collect();
+                    (buffer.clone(), ranges)
+                })
+                .collect()
+        })?;
+        let (editor, _) = Self::open_edited_ranges(workspace, replica_id, ranges, title, cx)?;
+        editor.update(cx, |editor, cx| {
+            editor.buffer.update(cx, |multibuffer, cx| {
+                multibuffer.push_transaction(entries.iter().map(|(b, t)| (b, t)), cx);
+            });
+        })?;
+        Ok(editor)
+    }
+
+    /// Opens a multibuffer with excerpts around the given ranges of each buffer, and
+    /// returns the ranges of each buffer in the multibuffer.
+    fn open_edited_ranges(
+        workspace: WeakView<Workspace>,
+        replica_id: ReplicaId,
+        ranges: Vec<(Model<Buffer>, Vec<Range<usize>>)>,
+        title: String,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<(View<Editor>, Vec<Vec<Range<Anchor>>>)> {
+        let mut excerpt_ranges = Vec::new();
         let excerpt_buffer = cx.new_model(|cx| {
             let mut multibuffer =
                 MultiBuffer::new(replica_id, Capability::ReadWrite).with_title(title);
-            for (buffer_handle, transaction) in &entries {
-                let buffer = buffer_handle.read(cx);
-                ranges_to_highlight.extend(
-                    multibuffer.push_excerpts_with_context_lines(
-                        buffer_handle.clone(),
-                        buffer
-                            .edited_ranges_for_transaction::<usize>(transaction)
-                            .collect(),
-                        DEFAULT_MULTIBUFFER_CONTEXT,
-                        cx,
-                    ),
-                );
+            for (buffer, ranges) in ranges {
+                excerpt_ranges.push(multibuffer.push_excerpts_with_context_lines(
+                    buffer,
+                    ranges,
+                    DEFAULT_MULTIBUFFER_CONTEXT,
+                    cx,
+                ));
             }
-            multibuffer.push_transaction(entries.iter().map(|(b, t)| (b, t)), cx);
             multibuffer
         })?;
 
-        workspace.update(&mut cx, |workspace, cx| {
+        workspace.update(cx, |workspace, cx| {
             let project = workspace.project().clone();
             let editor =
                 cx.new_view(|cx| Editor::for_multibuffer(excerpt_buffer, Some(project), true, cx));
             workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
             editor.update(cx, |editor, cx| {
                 editor.highlight_background::<Self>(
-                    &ranges_to_highlight,
+                    &excerpt_ranges.concat(),
                     |theme| theme.editor_highlighted_line_background,
                     cx,
                 );
             });
-        })?;
+            (editor, excerpt_ranges)
+        })
+    }
 
-        Ok(())
+    pub fn accept_rename_in_file(&mut self, _: &AcceptRenameInFile, cx: &mut ViewContext<Self>) {
+        if let Some((buffer, edits)) = self.take_rename_preview_entry_at_cursor(cx) {
+            self.resolve_previewed_edits(buffer, edits, true, cx);
+        }
+    }
+
+    pub fn reject_rename_in_file(&mut self, _: &RejectRenameInFile, cx: &mut ViewContext<Self>) {
+        if let Some((buffer, edits)) = self.take_rename_preview_entry_at_cursor(cx) {
+            self.resolve_previewed_edits(buffer, edits, false, cx);
+        }
+    }
+
+    pub fn accept_rename(&mut self, _: &AcceptRename, cx: &mut ViewContext<Self>) {
+        let Some(preview) = self.rename_preview.take() else {
+            cx.propagate();
+            return;
+        };
+        for (buffer, edits) in preview.edits {
+            self.resolve_previewed_edits(buffer, edits, true, cx);
+        }
+    }
+
+    pub fn reject_rename(&mut self, _: &RejectRename, cx: &mut ViewContext<Self>) {
+        let Some(preview) = self.rename_preview.take() else {
+            cx.propagate();
+            return;
+        };
+        for (buffer, edits) in preview.edits {
+            self.resolve_previewed_edits(buffer, edits, false, cx);
+        }
+    }
+
+    fn take_rename_preview_entry_at_cursor(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<(Model<Buffer>, PreviewedEdits)> {
+        let Some(preview) = self.rename_preview.as_mut() else {
+            cx.propagate();
+            return None;
+        };
+        let (_, buffer, _) = self
+            .buffer
+            .read(cx)
+            .excerpt_containing(self.selections.newest_anchor().head(), cx)?;
+        let edits = preview.edits.remove(&buffer)?;
+        if preview.edits.is_empty() {
+            self.rename_preview = None;
+        }
+        Some((buffer, edits))
+    }
+
+    /// Applies or discards the previewed edits of a buffer, and removes the buffer from
+    /// the preview.
+    fn resolve_previewed_edits(
+        &mut self,
+        buffer: Model<Buffer>,
+        edits: PreviewedEdits,
+        accept: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match edits {
+            PreviewedEdits::Pending { edits, inlay_ids } => {
+                self.splice_inlays(inlay_ids, Vec::new(), cx);
+                if accept {
+                    buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+                }
+            }
+            PreviewedEdits::Applied(transaction) => {
+                if !accept {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.undo_transaction(transaction.id, cx);
+                    });
+                }
+            }
+        }
+        self.remove_excerpts_for_buffer(&buffer, cx);
+    }
+
+    fn remove_excerpts_for_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ViewContext<Self>) {
+        self.buffer.update(cx, |multibuffer, cx| {
+            let excerpt_ids = multibuffer
+                .excerpts_for_buffer(buffer, cx)
+                .into_iter()
+                .map(|(excerpt_id, _)| excerpt_id)
+                .collect::<Vec<_>>();
+            multibuffer.remove_excerpts(excerpt_ids, cx);
+        });
+        cx.notify();
     }
 
     fn refresh_code_actions(&mut self, cx: &mut ViewContext<Self>) -> Option<()> {
@@ -5200,18 +5808,49 @@ impl Editor {
         }
     }
 
+    /// Returns the state of the last run of the task spawned from the runnable at the given row.
+    fn runnable_run_state(
+        &self,
+        key: &(BufferId, BufferRow),
+        cx: &AppContext,
+    ) -> Option<TaskRunState> {
+        let task_id = self.runnable_task_ids.get(key)?;
+        self.project
+            .as_ref()?
+            .read(cx)
+            .task_inventory()
+            .read(cx)
+            .task_run_state(task_id)
+    }
+
     fn render_run_indicator(
         &self,
         _style: &EditorStyle,
         is_active: bool,
         row: DisplayRow,
+        run_state: Option<TaskRunState>,
         cx: &mut ViewContext<Self>,
     ) -> IconButton {
-        IconButton::new(("run_indicator", row.0 as usize), ui::IconName::Play)
+        let (icon, color, tooltip) = match run_state {
+            None => (ui::IconName::Play, Color::Muted, None),
+            Some(TaskRunState::Running) => {
+                (ui::IconName::ArrowCircle, Color::Muted, Some("Running"))
+            }
+            Some(TaskRunState::Succeeded) => {
+                (ui::IconName::Check, Color::Success, Some("Last run passed"))
+            }
+            Some(TaskRunState::Failed) => {
+                (ui::IconName::XCircle, Color::Error, Some("Last run failed"))
+            }
+        };
+        IconButton::new(("run_indicator", row.0 as usize), icon)
             .shape(ui::IconButtonShape::Square)
             .icon_size(IconSize::XSmall)
-            .icon_color(Color::Muted)
+            .icon_color(color)
             .selected(is_active)
+            .when_some(tooltip, |button, tooltip| {
+                button.tooltip(move |cx| Tooltip::text(tooltip, cx))
+            })
             .on_click(cx.listener(move |editor, _e, cx| {
                 editor.focus(cx);
                 editor.toggle_code_actions(
@@ -5895,6 +6534,16 @@ impl Editor {
     }
 
     pub fn join_lines(&mut self, _: &JoinLines, cx: &mut ViewContext<Self>) {
+        self.join_lines_impl(true, cx);
+    }
+
+    /// Joins the lines spanned by each selection with the line after them.
+    ///
+    /// When `insert_whitespace` is true, the leading whitespace of each joined line is replaced
+    /// by a single space (or nothing, next to a bracket), the comment marker of a joined comment
+    /// line is removed, and a trailing comma before a closing bracket is dropped. Otherwise the
+    /// lines are concatenated as is.
+    pub fn join_lines_impl(&mut self, insert_whitespace: bool, cx: &mut ViewContext<Self>) {
         if self.read_only(cx) {
             return;
         }
@@ -5929,16 +6578,53 @@ impl Editor {
         self.transact(cx, |this, cx| {
             for row_range in row_ranges.into_iter().rev() {
                 for row in row_range.iter_rows().rev() {
-                    let end_of_line = Point::new(row.0, snapshot.line_len(row));
+                    let mut end_of_line = Point::new(row.0, snapshot.line_len(row));
                     let next_line_row = row.next_row();
+                    if !insert_whitespace {
+                        let start_of_next_line = Point::new(next_line_row.0, 0);
+                        this.buffer.update(cx, |buffer, cx| {
+                            buffer.edit([(end_of_line..start_of_next_line, "")], None, cx)
+                        });
+                        continue;
+                    }
+
                     let indent = snapshot.indent_size_for_line(next_line_row);
-                    let start_of_next_line = Point::new(next_line_row.0, indent.len);
+                    let mut start_of_next_line = Point::new(next_line_row.0, indent.len);
+                    let line = snapshot
+                        .text_for_range(Point::new(row.0, 0)..end_of_line)
+                        .collect::<String>();
+                    let next_line = snapshot
+                        .text_for_range(
+                            start_of_next_line
+                                ..Point::new(next_line_row.0, snapshot.line_len(next_line_row)),
+                        )
+                        .collect::<String>();
+                    let mut next_line = next_line.as_str();
 
-                    let replace = if snapshot.line_len(next_line_row) > indent.len {
-                        " "
-                    } else {
+                    // Joining two line comments keeps only the first comment marker.
+                    if let Some(prefix) = line_comment_prefix(&snapshot, row, &line) {
+                        if line_comment_prefix(&snapshot, next_line_row, next_line)
+                            .is_some_and(|next_prefix| next_prefix == prefix)
+                        {
+                            let content = next_line.trim_start()[prefix.len()..].trim_start();
+                            start_of_next_line.column += (next_line.len() - content.len()) as u32;
+                            next_line = content;
+                        }
+                    }
+
+                    let mut replace = if next_line.is_empty() || line.ends_with(['(', '[']) {
                         ""
+                    } else {
+                        " "
                     };
+                    if next_line.starts_with([')', ']', '}']) {
+                        if line.ends_with(',') {
+                            end_of_line.column -= 1;
+                        }
+                        if !next_line.starts_with('}') {
+                            replace = "";
+                        }
+                    }
 
                     this.buffer.update(cx, |buffer, cx| {
                         buffer.edit([(end_of_line..start_of_next_line, replace)], None, cx)
@@ -5968,6 +6654,17 @@ impl Editor {
         self.manipulate_lines(cx, |lines| lines.sort_by_key(|line| line.to_lowercase()))
     }
 
+    /// Sorts lines by the number they start with, ignoring leading whitespace.
+    /// Lines that do not start with a number are kept first, in their original order.
+    pub fn sort_lines_numerically(&mut self, _: &SortLinesNumerically, cx: &mut ViewContext<Self>) {
+        self.manipulate_lines(cx, |lines| {
+            lines.sort_by(|a, b| match (leading_number(a), leading_number(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            })
+        })
+    }
+
     pub fn unique_lines_case_insensitive(
         &mut self,
         _: &UniqueLinesCaseInsensitive,
@@ -6196,6 +6893,14 @@ impl Editor {
         self.manipulate_text(cx, |text| text.to_case(Case::Kebab))
     }
 
+    pub fn convert_to_screaming_snake_case(
+        &mut self,
+        _: &ConvertToScreamingSnakeCase,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.manipulate_text(cx, |text| text.to_case(Case::ScreamingSnake))
+    }
+
     pub fn convert_to_upper_camel_case(
         &mut self,
         _: &ConvertToUpperCamelCase,
@@ -6326,26 +7031,99 @@ impl Editor {
             let insert_location = if upwards {
                 Point::new(rows.end.0, 0)
             } else {
-                start
-            };
-            edits.push((insert_location..insert_location, text));
+                start
+            };
+            edits.push((insert_location..insert_location, text));
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+
+            this.request_autoscroll(Autoscroll::fit(), cx);
+        });
+    }
+
+    pub fn duplicate_line_up(&mut self, _: &DuplicateLineUp, cx: &mut ViewContext<Self>) {
+        self.duplicate_line(true, cx);
+    }
+
+    pub fn duplicate_line_down(&mut self, _: &DuplicateLineDown, cx: &mut ViewContext<Self>) {
+        self.duplicate_line(false, cx);
+    }
+
+    /// Duplicates the text of each selection in place, selecting the copy that ends up
+    /// above (or before) the original when `upwards` is true, and the one below it
+    /// otherwise. Empty selections duplicate their whole line, and keep their column.
+    pub fn duplicate_selection(&mut self, upwards: bool, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<usize>(cx);
+
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::with_capacity(selections.len());
+        let mut delta = 0;
+        let mut delta_before_row = 0;
+        let mut last_duplicated_row = None;
+        for selection in selections {
+            let mut new_selection = selection.clone();
+            new_selection.goal = SelectionGoal::None;
+            if selection.is_empty() {
+                let row = selection.head().to_point(&buffer).row;
+                // Several cursors on the same line only duplicate it once.
+                if last_duplicated_row != Some(row) {
+                    let line_start = Point::new(row, 0);
+                    let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+                    let text = buffer
+                        .text_for_range(line_start..line_end)
+                        .chain(Some("\n"))
+                        .collect::<String>();
+                    let insert_location = line_start.to_offset(&buffer);
+                    delta_before_row = delta;
+                    delta += text.len();
+                    edits.push((insert_location..insert_location, text));
+                    last_duplicated_row = Some(row);
+                }
+                let head = if upwards {
+                    selection.head() + delta_before_row
+                } else {
+                    selection.head() + delta
+                };
+                new_selection.start = head;
+                new_selection.end = head;
+            } else {
+                let text = buffer.text_for_range(selection.range()).collect::<String>();
+                let insert_location = if upwards {
+                    selection.start
+                } else {
+                    selection.end
+                };
+                new_selection.start = insert_location + delta;
+                new_selection.end = insert_location + delta + text.len();
+                delta += text.len();
+                edits.push((insert_location..insert_location, text));
+            }
+            new_selections.push(new_selection);
         }
 
         self.transact(cx, |this, cx| {
             this.buffer.update(cx, |buffer, cx| {
                 buffer.edit(edits, None, cx);
             });
-
-            this.request_autoscroll(Autoscroll::fit(), cx);
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
         });
     }
 
-    pub fn duplicate_line_up(&mut self, _: &DuplicateLineUp, cx: &mut ViewContext<Self>) {
-        self.duplicate_line(true, cx);
+    pub fn duplicate_selection_up(&mut self, _: &DuplicateSelectionUp, cx: &mut ViewContext<Self>) {
+        self.duplicate_selection(true, cx);
     }
 
-    pub fn duplicate_line_down(&mut self, _: &DuplicateLineDown, cx: &mut ViewContext<Self>) {
-        self.duplicate_line(false, cx);
+    pub fn duplicate_selection_down(
+        &mut self,
+        _: &DuplicateSelectionDown,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.duplicate_selection(false, cx);
     }
 
     pub fn move_line_up(&mut self, _: &MoveLineUp, cx: &mut ViewContext<Self>) {
@@ -6602,6 +7380,101 @@ impl Editor {
         });
     }
 
+    pub fn transpose_words(&mut self, _: &TransposeWords, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+        let mut new_heads = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let head = selection.head();
+            if !selection.is_empty() || self.selections.line_mode {
+                new_heads.push(head);
+                continue;
+            }
+
+            let scope = buffer.language_scope_at(head);
+            let is_word = |c: char| char_kind(&scope, c) == CharKind::Word;
+            let skip_forward = |mut offset: usize, in_word: bool| {
+                for c in buffer.chars_at(offset) {
+                    if is_word(c) != in_word {
+                        break;
+                    }
+                    offset += c.len_utf8();
+                }
+                offset
+            };
+            let skip_backward = |mut offset: usize, in_word: bool| {
+                for c in buffer.reversed_chars_at(offset) {
+                    if is_word(c) != in_word {
+                        break;
+                    }
+                    offset -= c.len_utf8();
+                }
+                offset
+            };
+
+            // Like in Emacs, the word under or before the cursor is swapped with the next one.
+            let mut first_end = skip_forward(head, true);
+            if first_end == head {
+                first_end = skip_backward(head, false);
+            }
+            let first_start = skip_backward(first_end, true);
+            let second_start = skip_forward(first_end, false);
+            let second_end = skip_forward(second_start, true);
+            let overlaps_previous_edit = edits
+                .last()
+                .map_or(false, |(range, _)| range.end > first_start);
+            if first_start == first_end || second_start == second_end || overlaps_previous_edit {
+                new_heads.push(head);
+                continue;
+            }
+
+            let first_word = buffer.text_for_range(first_start..first_end).collect();
+            let second_word = buffer.text_for_range(second_start..second_end).collect();
+            edits.push((first_start..first_end, second_word));
+            edits.push((second_start..second_end, first_word));
+            new_heads.push(second_end);
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_heads.into_iter().map(|head| head..head));
+            });
+        });
+    }
+
+    /// Exchanges the contents of the two selections, when there are exactly two.
+    pub fn swap_selections(&mut self, _: &SwapSelections, cx: &mut ViewContext<Self>) {
+        let selections = self.selections.all::<usize>(cx);
+        let [first, second] = selections.as_slice() else {
+            return;
+        };
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let first_text = buffer.text_for_range(first.range()).collect::<String>();
+        let second_text = buffer.text_for_range(second.range()).collect::<String>();
+        let new_first_start = first.start;
+        let new_second_start = second.start + second_text.len() - first_text.len();
+        let new_ranges = [
+            new_first_start..new_first_start + second_text.len(),
+            new_second_start..new_second_start + first_text.len(),
+        ];
+        let edits = [(first.range(), second_text), (second.range(), first_text)];
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_ranges);
+            });
+        });
+    }
+
     pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
         let mut text = String::new();
         let buffer = self.buffer.read(cx).snapshot(cx);
@@ -6696,6 +7569,28 @@ impl Editor {
         clipboard_selections: Option<Vec<ClipboardSelection>>,
         handle_entire_lines: bool,
         cx: &mut ViewContext<Self>,
+    ) {
+        let reindent = {
+            let buffer = self.buffer.read(cx).snapshot(cx);
+            let head = self.selections.newest_anchor().head();
+            buffer.settings_at(head, cx).auto_indent_on_paste
+        };
+        self.do_paste_with_reindent(
+            text,
+            clipboard_selections,
+            handle_entire_lines,
+            reindent,
+            cx,
+        )
+    }
+
+    fn do_paste_with_reindent(
+        &mut self,
+        text: &String,
+        clipboard_selections: Option<Vec<ClipboardSelection>>,
+        handle_entire_lines: bool,
+        reindent: bool,
+        cx: &mut ViewContext<Self>,
     ) {
         if self.read_only(cx) {
             return;
@@ -6754,7 +7649,7 @@ impl Editor {
 
                     buffer.edit(
                         edits,
-                        Some(AutoindentMode::Block {
+                        reindent.then_some(AutoindentMode::Block {
                             original_indent_columns,
                         }),
                         cx,
@@ -6763,27 +7658,43 @@ impl Editor {
 
                 let selections = this.selections.all::<usize>(cx);
                 this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
-            } else {
+            } else if reindent {
                 this.insert(&clipboard_text, cx);
+            } else {
+                this.insert_with_autoindent_mode(&clipboard_text, None, cx);
             }
         });
     }
 
     pub fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
+        self.paste_from_clipboard(true, cx);
+    }
+
+    pub fn paste_without_reformatting(
+        &mut self,
+        _: &PasteWithoutReformatting,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.paste_from_clipboard(false, cx);
+    }
+
+    fn paste_from_clipboard(&mut self, reformat: bool, cx: &mut ViewContext<Self>) {
         if let Some(item) = cx.read_from_clipboard() {
             let entries = item.entries();
 
-            match entries.first() {
+            let (text, clipboard_selections) = match entries.first() {
                 // For now, we only support applying metadata if there's one string. In the future, we can incorporate all the selections
                 // of all the pasted entries.
-                Some(ClipboardEntry::String(clipboard_string)) if entries.len() == 1 => self
-                    .do_paste(
-                        clipboard_string.text(),
-                        clipboard_string.metadata_json::<Vec<ClipboardSelection>>(),
-                        true,
-                        cx,
-                    ),
-                _ => self.do_paste(&item.text().unwrap_or_default(), None, true, cx),
+                Some(ClipboardEntry::String(clipboard_string)) if entries.len() == 1 => (
+                    clipboard_string.text().clone(),
+                    clipboard_string.metadata_json::<Vec<ClipboardSelection>>(),
+                ),
+                _ => (item.text().unwrap_or_default(), None),
+            };
+            if reformat {
+                self.do_paste(&text, clipboard_selections, true, cx);
+            } else {
+                self.do_paste_with_reindent(&text, clipboard_selections, true, false, cx);
             }
         }
     }
@@ -6804,8 +7715,11 @@ impl Editor {
             self.request_autoscroll(Autoscroll::fit(), cx);
             self.unmark_text(cx);
             self.refresh_inline_completion(true, false, cx);
+            self.persistent_undo_transaction_changed(transaction_id, true);
             cx.emit(EditorEvent::Edited { transaction_id });
             cx.emit(EditorEvent::TransactionUndone { transaction_id });
+        } else {
+            self.undo_to_persisted_version(cx);
         }
     }
 
@@ -6825,6 +7739,7 @@ impl Editor {
             self.request_autoscroll(Autoscroll::fit(), cx);
             self.unmark_text(cx);
             self.refresh_inline_completion(true, false, cx);
+            self.persistent_undo_transaction_changed(transaction_id, false);
             cx.emit(EditorEvent::Edited { transaction_id });
         }
     }
@@ -7600,32 +8515,47 @@ impl Editor {
     fn push_to_nav_history(
         &mut self,
         cursor_anchor: Anchor,
+        selections: Vec<Range<Anchor>>,
         new_position: Option<Point>,
         cx: &mut ViewContext<Self>,
     ) {
-        if let Some(nav_history) = self.nav_history.as_mut() {
-            let buffer = self.buffer.read(cx).read(cx);
-            let cursor_position = cursor_anchor.to_point(&buffer);
-            let scroll_state = self.scroll_manager.anchor();
-            let scroll_top_row = scroll_state.top_row(&buffer);
-            drop(buffer);
-
-            if let Some(new_position) = new_position {
-                let row_delta = (new_position.row as i64 - cursor_position.row as i64).abs();
-                if row_delta < MIN_NAVIGATION_HISTORY_ROW_DELTA {
-                    return;
-                }
+        if self.nav_history.is_none() {
+            return;
+        }
+        let data = self.navigation_data_at(cursor_anchor, selections, cx);
+        if let Some(new_position) = new_position {
+            let row_delta = (new_position.row as i64 - data.cursor_position.row as i64).abs();
+            if row_delta < MIN_NAVIGATION_HISTORY_ROW_DELTA {
+                return;
             }
+        }
 
-            nav_history.push(
-                Some(NavigationData {
-                    cursor_anchor,
-                    cursor_position,
-                    scroll_anchor: scroll_state,
-                    scroll_top_row,
-                }),
-                cx,
-            );
+        if let Some(nav_history) = self.nav_history.as_mut() {
+            nav_history.push(Some(data), cx);
+        }
+    }
+
+    pub(crate) fn navigation_data_at(
+        &self,
+        cursor_anchor: Anchor,
+        selections: Vec<Range<Anchor>>,
+        cx: &AppContext,
+    ) -> NavigationData {
+        let buffer = self.buffer.read(cx).read(cx);
+        let cursor_position = cursor_anchor.to_point(&buffer);
+        let selection_positions = selections
+            .iter()
+            .map(|range| range.start.to_point(&buffer)..range.end.to_point(&buffer))
+            .collect();
+        let scroll_state = self.scroll_manager.anchor();
+        let scroll_top_row = scroll_state.top_row(&buffer);
+        NavigationData {
+            cursor_anchor,
+            cursor_position,
+            selections,
+            selection_positions,
+            scroll_anchor: scroll_state,
+            scroll_top_row,
         }
     }
 
@@ -8506,9 +9436,12 @@ impl Editor {
             .collect::<Vec<_>>();
 
         if selected_larger_symbol {
+            let mut stack = mem::take(&mut self.select_larger_syntax_node_stack);
+            stack.push(old_selections);
             self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select(new_selections);
             });
+            self.select_larger_syntax_node_stack = stack;
         }
     }
 
@@ -8573,6 +9506,63 @@ impl Editor {
         self.select_larger_syntax_node_stack = stack;
     }
 
+    pub fn select_enclosing_function(
+        &mut self,
+        _: &SelectEnclosingFunction,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.select_enclosing_syntax_node(is_function_syntax_node, cx);
+    }
+
+    pub fn select_enclosing_statement(
+        &mut self,
+        _: &SelectEnclosingStatement,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.select_enclosing_syntax_node(is_statement_syntax_node, cx);
+    }
+
+    /// Expands each selection to the smallest syntax node around it whose kind matches
+    /// `predicate`, recording the previous selections so that they can be restored by
+    /// selecting a smaller syntax node.
+    fn select_enclosing_syntax_node(
+        &mut self,
+        predicate: fn(&str) -> bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
+
+        let mut selected_enclosing_node = false;
+        let new_selections = old_selections
+            .iter()
+            .map(|selection| {
+                let Some(new_range) =
+                    buffer.range_for_enclosing_syntax_node(selection.range(), predicate)
+                else {
+                    return selection.clone();
+                };
+                selected_enclosing_node = true;
+                Selection {
+                    id: selection.id,
+                    start: new_range.start,
+                    end: new_range.end,
+                    goal: SelectionGoal::None,
+                    reversed: selection.reversed,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if selected_enclosing_node {
+            let mut stack = mem::take(&mut self.select_larger_syntax_node_stack);
+            stack.push(old_selections);
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+            self.select_larger_syntax_node_stack = stack;
+        }
+    }
+
     fn refresh_runnables(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
         if !EditorSettings::get_global(cx).gutter.runnables {
             self.clear_tasks();
@@ -9827,25 +10817,74 @@ impl Editor {
         let old_name = rename.old_name;
         let new_name = rename.editor.read(cx).text(cx);
 
-        let rename = workspace
-            .read(cx)
-            .project()
-            .clone()
-            .update(cx, |project, cx| {
-                project.perform_rename(buffer.clone(), range.start, new_name.clone(), true, cx)
-            });
+        let project = workspace.read(cx).project().clone();
+        // Compute the edits first in local projects, so that renames spanning several
+        // files can be previewed before they are applied.
+        let rename_edits = project.read(cx).is_local().then(|| {
+            project.update(cx, |project, cx| {
+                project.rename_edits(buffer.clone(), range.start, new_name.clone(), cx)
+            })
+        });
         let workspace = workspace.downgrade();
+        let title = format!("Rename: {} → {}", old_name, new_name);
 
         Some(cx.spawn(|editor, mut cx| async move {
-            let project_transaction = rename.await?;
-            Self::open_project_transaction(
-                &editor,
-                workspace,
-                project_transaction,
-                format!("Rename: {} → {}", old_name, new_name),
-                cx.clone(),
-            )
-            .await?;
+            let rename_edits = match rename_edits {
+                Some(rename_edits) => match rename_edits.await? {
+                    Some(rename_edits) => Some(rename_edits),
+                    None => {
+                        workspace.update(&mut cx, |workspace, cx| {
+                            struct RenameWithoutPreview;
+                            workspace.show_toast(
+                                Toast::new(
+                                    NotificationId::unique::<RenameWithoutPreview>(),
+                                    "This rename also creates, renames or deletes files, so it can't be previewed",
+                                ),
+                                cx,
+                            )
+                        })?;
+                        None
+                    }
+                },
+                None => None,
+            };
+            if let Some(rename_edits) = rename_edits.filter(|edits| edits.len() > 1) {
+                Self::open_rename_edits_preview(workspace, rename_edits, title, cx.clone()).await?;
+            } else {
+                let project_transaction = match rename_edits {
+                    Some(rename_edits) => cx.update(|cx| {
+                        let mut project_transaction = ProjectTransaction::default();
+                        for (buffer, edits) in rename_edits {
+                            let transaction = buffer.update(cx, |buffer, cx| {
+                                buffer.finalize_last_transaction();
+                                buffer.start_transaction();
+                                buffer.edit(edits, None, cx);
+                                buffer.end_transaction(cx)?;
+                                buffer.finalize_last_transaction().cloned()
+                            });
+                            if let Some(transaction) = transaction {
+                                project_transaction.0.insert(buffer, transaction);
+                            }
+                        }
+                        project_transaction
+                    })?,
+                    None => {
+                        project
+                            .update(&mut cx, |project, cx| {
+                                project.perform_rename(buffer, range.start, new_name, true, cx)
+                            })?
+                            .await?
+                    }
+                };
+                Self::open_project_transaction(
+                    &editor,
+                    workspace,
+                    project_transaction,
+                    title,
+                    cx.clone(),
+                )
+                .await?;
+            }
 
             editor.update(&mut cx, |editor, cx| {
                 editor.refresh_document_highlights(cx);
@@ -10513,6 +11552,19 @@ impl Editor {
         }
     }
 
+    /// How many more columns than the line itself soft-wrapped lines are indented by.
+    pub fn soft_wrap_hanging_indent(&self, cx: &AppContext) -> u32 {
+        self.buffer
+            .read(cx)
+            .settings_at(0, cx)
+            .soft_wrap_hanging_indent
+    }
+
+    /// Whether soft-wrapped lines are marked in the gutter.
+    pub fn show_soft_wrap_indicator(&self, cx: &AppContext) -> bool {
+        self.buffer.read(cx).settings_at(0, cx).soft_wrap_indicator
+    }
+
     pub fn set_soft_wrap_mode(
         &mut self,
         mode: language_settings::SoftWrap,
@@ -10545,6 +11597,12 @@ impl Editor {
             .update(cx, |map, cx| map.set_wrap_width(width, cx))
     }
 
+    pub(crate) fn set_wrap_hanging_indent(&self, hanging_indent: u32, cx: &mut AppContext) -> bool {
+        self.display_map.update(cx, |map, cx| {
+            map.set_wrap_hanging_indent(hanging_indent, cx)
+        })
+    }
+
     pub fn toggle_soft_wrap(&mut self, _: &ToggleSoftWrap, cx: &mut ViewContext<Self>) {
         if self.soft_wrap_mode_override.is_some() {
             self.soft_wrap_mode_override.take();
@@ -10560,6 +11618,14 @@ impl Editor {
         cx.notify();
     }
 
+    /// Switches the editor between read-only and writable. Buffers that can't be
+    /// edited, such as those of a read-only collaboration project, stay read-only.
+    pub fn toggle_read_only(&mut self, _: &ToggleReadOnly, cx: &mut ViewContext<Self>) {
+        self.read_only = !self.read_only;
+        cx.emit(EditorEvent::TitleChanged);
+        cx.notify();
+    }
+
     pub fn toggle_tab_bar(&mut self, _: &ToggleTabBar, cx: &mut ViewContext<Self>) {
         let Some(workspace) = self.workspace() else {
             return;
@@ -10598,6 +11664,13 @@ impl Editor {
         cx.notify();
     }
 
+    pub(crate) fn set_zen_mode(&mut self, zen_mode: bool, cx: &mut ViewContext<Self>) {
+        if self.zen_mode != zen_mode {
+            self.zen_mode = zen_mode;
+            cx.notify();
+        }
+    }
+
     pub fn set_show_line_numbers(&mut self, show_line_numbers: bool, cx: &mut ViewContext<Self>) {
         self.show_line_numbers = Some(show_line_numbers);
         cx.notify();
@@ -10639,6 +11712,26 @@ impl Editor {
         cx.notify();
     }
 
+    /// Shows buttons in the header of each buffer in a multibuffer, to exclude that buffer
+    /// or refresh its excerpts. The owner of the multibuffer handles them, by subscribing to
+    /// [`EditorEvent::BufferExclusionRequested`] and [`EditorEvent::BufferRefreshRequested`].
+    pub fn set_show_buffer_header_controls(
+        &mut self,
+        show_buffer_header_controls: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.show_buffer_header_controls = show_buffer_header_controls;
+        cx.notify();
+    }
+
+    pub fn request_buffer_exclusion(&mut self, buffer_id: BufferId, cx: &mut ViewContext<Self>) {
+        cx.emit(EditorEvent::BufferExclusionRequested { buffer_id });
+    }
+
+    pub fn request_buffer_refresh(&mut self, buffer_id: BufferId, cx: &mut ViewContext<Self>) {
+        cx.emit(EditorEvent::BufferRefreshRequested { buffer_id });
+    }
+
     pub fn working_directory(&self, cx: &WindowContext) -> Option<PathBuf> {
         if let Some(buffer) = self.buffer().read(cx).as_singleton() {
             if let Some(file) = buffer.read(cx).file().and_then(|f| f.as_local()) {
@@ -11341,6 +12434,22 @@ impl Editor {
         cx.notify();
     }
 
+    /// Like [`Self::highlight_text`], but allows a single type to add several sets of
+    /// highlights with different styles, distinguished by `key`. All of them are removed
+    /// by [`Self::clear_highlights`].
+    pub(crate) fn highlight_text_key<T: 'static>(
+        &mut self,
+        key: usize,
+        ranges: Vec<Range<Anchor>>,
+        style: HighlightStyle,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.display_map.update(cx, |map, _| {
+            map.highlight_text_key(TypeId::of::<T>(), key, ranges, style)
+        });
+        cx.notify();
+    }
+
     pub(crate) fn highlight_inlays<T: 'static>(
         &mut self,
         highlights: Vec<InlayHighlight>,
@@ -11432,6 +12541,10 @@ impl Editor {
                     }
                 }
 
+                self.refresh_semantic_tokens(cx);
+                self.refresh_color_swatches(cx);
+                self.refresh_code_lenses(cx);
+                self.refresh_spell_check(cx);
                 let Some(project) = &self.project else { return };
                 let telemetry = project.read(cx).client().telemetry().clone();
                 refresh_linked_ranges(self, cx);
@@ -11449,9 +12562,18 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_semantic_tokens(cx);
+                self.refresh_color_swatches(cx);
+                self.refresh_rainbow_brackets(cx);
+                self.refresh_code_lenses(cx);
+                self.refresh_inactive_regions(cx);
+                self.refresh_spell_check(cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_color_swatches(cx);
+                self.refresh_rainbow_brackets(cx);
+                self.refresh_code_lenses(cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsEdited { ids } => {
@@ -11462,11 +12584,21 @@ impl Editor {
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
+                self.refresh_rainbow_brackets(cx);
+                self.refresh_spell_check(cx);
+                refresh_matching_bracket_highlights(self, cx);
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, cx);
+                self.refresh_semantic_tokens(cx);
+                self.refresh_color_swatches(cx);
+                self.refresh_rainbow_brackets(cx);
+                self.refresh_code_lenses(cx);
+                self.refresh_inactive_regions(cx);
+                self.reset_spell_check(cx);
+                refresh_matching_bracket_highlights(self, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -11509,8 +12641,15 @@ impl Editor {
             )),
             cx,
         );
+        self.refresh_semantic_tokens(cx);
+        self.refresh_color_swatches(cx);
+        self.refresh_rainbow_brackets(cx);
+        self.refresh_code_lenses(cx);
+        self.refresh_inactive_regions(cx);
+        self.reset_spell_check(cx);
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
+        self.scroll_manager.typewriter_scrolling = editor_settings.typewriter_scrolling;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
 
         let project_settings = ProjectSettings::get_global(cx);
@@ -12355,6 +13494,21 @@ fn inlay_hint_settings(
         .inlay_hints
 }
 
+/// Whether a syntax node is a function, method or closure, going by the names
+/// Tree-sitter grammars commonly give to those nodes.
+fn is_function_syntax_node(kind: &str) -> bool {
+    const EXCLUDED: [&str; 5] = ["call", "invocation", "type", "modifier", "parameter"];
+    const FUNCTION_KINDS: [&str; 5] = ["function", "method", "closure", "lambda", "func_literal"];
+    !EXCLUDED.iter().any(|word| kind.contains(word))
+        && FUNCTION_KINDS.iter().any(|word| kind.contains(word))
+}
+
+/// Whether a syntax node is a statement or a declaration, going by the names
+/// Tree-sitter grammars commonly give to those nodes.
+fn is_statement_syntax_node(kind: &str) -> bool {
+    kind.ends_with("statement") || kind.ends_with("declaration")
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -12384,6 +13538,44 @@ fn ending_row(next_selection: &Selection<Point>, display_map: &DisplaySnapshot)
     }
 }
 
+/// Returns the line comment marker that the given line starts with, without trailing whitespace.
+fn line_comment_prefix(
+    snapshot: &MultiBufferSnapshot,
+    row: MultiBufferRow,
+    line: &str,
+) -> Option<String> {
+    let line = line.trim_start();
+    let scope = snapshot.language_scope_at(Point::new(row.0, 0))?;
+    scope
+        .line_comment_prefixes()
+        .iter()
+        .map(|prefix| prefix.trim_end())
+        .filter(|prefix| !prefix.is_empty() && line.starts_with(prefix))
+        .max_by_key(|prefix| prefix.len())
+        .map(ToString::to_string)
+}
+
+/// Parses the (possibly signed, possibly fractional) number at the start of a line.
+fn leading_number(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    let mut end = 0;
+    let mut seen_digit = false;
+    let mut seen_point = false;
+    for (ix, c) in line.char_indices() {
+        match c {
+            '-' | '+' if ix == 0 => {}
+            '0'..='9' => seen_digit = true,
+            '.' if !seen_point => seen_point = true,
+            _ => break,
+        }
+        end = ix + c.len_utf8();
+    }
+    if !seen_digit {
+        return None;
+    }
+    line[..end].trim_end_matches('.').parse().ok()
+}
+
 impl EditorSnapshot {
     pub fn remote_selections_in_range<'a>(
         &'a self,
@@ -12598,6 +13790,12 @@ pub enum EditorEvent {
     ExcerptsExpanded {
         ids: Vec<ExcerptId>,
     },
+    BufferExclusionRequested {
+        buffer_id: BufferId,
+    },
+    BufferRefreshRequested {
+        buffer_id: BufferId,
+    },
     BufferEdited,
     Edited {
         transaction_id: clock::Lamport,
@@ -12654,7 +13852,7 @@ impl Render for Editor {
                 font_family: settings.buffer_font.family.clone(),
                 font_features: settings.buffer_font.features.clone(),
                 font_fallbacks: settings.buffer_font.fallbacks.clone(),
-                font_size: settings.buffer_font_size(cx).into(),
+                font_size: self.font_size(cx).into(),
                 font_weight: settings.buffer_font.weight,
                 line_height: relative(settings.buffer_line_height.value()),
                 ..Default::default()
@@ -12724,6 +13922,7 @@ impl ViewInputHandler for Editor {
 
     fn unmark_text(&mut self, cx: &mut ViewContext<Self>) {
         self.clear_highlights::<InputComposition>(cx);
+        self.clear_highlights::<InputCompositionClause>(cx);
         self.ime_transaction.take();
     }
 
@@ -12902,6 +14101,34 @@ impl ViewInputHandler for Editor {
                     selections.select_ranges(new_selected_ranges)
                 });
             }
+
+            // The part of the composition that's selected is the clause being converted,
+            // which input methods underline more heavily than the rest.
+            let clause_ranges = {
+                let snapshot = this.buffer.read(cx).snapshot(cx);
+                this.selections
+                    .all::<usize>(cx)
+                    .into_iter()
+                    .filter(|selection| !selection.is_empty())
+                    .map(|selection| selection.range().to_anchors(&snapshot))
+                    .collect::<Vec<_>>()
+            };
+            if text.is_empty() || clause_ranges.is_empty() {
+                this.clear_highlights::<InputCompositionClause>(cx);
+            } else {
+                this.highlight_text::<InputCompositionClause>(
+                    clause_ranges,
+                    HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            thickness: px(2.),
+                            color: None,
+                            wavy: false,
+                        }),
+                        ..Default::default()
+                    },
+                    cx,
+                );
+            }
         });
 
         self.ime_transaction = self.ime_transaction.or(transaction);
@@ -12940,13 +14167,20 @@ impl ViewInputHandler for Editor {
         let scroll_left = scroll_position.x * em_width;
 
         let start = OffsetUtf16(range_utf16.start).to_display_point(&snapshot);
-        let x = snapshot.x_for_display_point(start, &text_layout_details) - scroll_left
-            + self.gutter_dimensions.width;
+        let end = OffsetUtf16(range_utf16.end).to_display_point(&snapshot);
+        let start_x = snapshot.x_for_display_point(start, &text_layout_details);
+        let width = if end.row() == start.row() && end.column() > start.column() {
+            snapshot.x_for_display_point(end, &text_layout_details) - start_x
+        } else {
+            em_width
+        };
+        // Text starts after the gutter, including the margin between the gutter and the text.
+        let x = start_x - scroll_left + self.gutter_dimensions.full_width();
         let y = line_height * (start.row().as_f32() - scroll_position.y);
 
         Some(Bounds {
             origin: element_bounds.origin + point(x, y),
-            size: size(em_width, line_height),
+            size: size(width, line_height),
         })
     }
 }
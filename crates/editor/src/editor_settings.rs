@@ -11,12 +11,12 @@ pub struct EditorSettings {
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
     pub completion_documentation_secondary_query_debounce: u64,
-    pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
+    pub typewriter_scrolling: bool,
     pub scroll_sensitivity: f32,
     pub relative_line_numbers: bool,
     pub seed_search_query_from_cursor: SeedQuerySetting,
@@ -25,10 +25,13 @@ pub struct EditorSettings {
     pub expand_excerpt_lines: u32,
     #[serde(default)]
     pub double_click_in_multibuffer: DoubleClickInMultibuffer,
+    pub drag_and_drop_selection: bool,
     pub search_wrap: bool,
     pub auto_signature_help: bool,
     pub show_signature_help_after_edits: bool,
     pub jupyter: Jupyter,
+    pub persistent_undo: PersistentUndo,
+    pub inline_diagnostics: InlineDiagnostics,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
@@ -85,6 +88,36 @@ pub struct JupyterContent {
     pub enabled: Option<bool>,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct PersistentUndo {
+    pub enabled: bool,
+    pub max_entries: usize,
+    pub max_file_size: usize,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineDiagnostics {
+    pub enabled: bool,
+    pub min_severity: InlineDiagnosticsSeverity,
+    pub max_length: usize,
+}
+
+/// The least severe diagnostics to show inline.
+///
+/// Default: hint
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InlineDiagnosticsSeverity {
+    /// Show errors only.
+    Error,
+    /// Show errors and warnings.
+    Warning,
+    /// Show errors, warnings and information.
+    Info,
+    /// Show all diagnostics.
+    Hint,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Toolbar {
     pub breadcrumbs: bool,
@@ -185,11 +218,6 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 300 ms
     pub completion_documentation_secondary_query_debounce: Option<u64>,
-    /// Whether to use additional LSP queries to format (and amend) the code after
-    /// every "trigger" symbol input, defined by LSP server capabilities.
-    ///
-    /// Default: true
-    pub use_on_type_format: Option<bool>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
@@ -204,6 +232,11 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 3.
     pub vertical_scroll_margin: Option<f32>,
+    /// Whether to keep the line of the newest cursor vertically centered when
+    /// the cursor moves, instead of scrolling only once it reaches the vertical scroll margin.
+    ///
+    /// Default: false
+    pub typewriter_scrolling: Option<bool>,
     /// Scroll sensitivity multiplier. This multiplier is applied
     /// to both the horizontal and vertical delta values while scrolling.
     ///
@@ -238,6 +271,13 @@ pub struct EditorSettingsContent {
     ///
     /// Default: select
     pub double_click_in_multibuffer: Option<DoubleClickInMultibuffer>,
+
+    /// Whether to allow dragging selected text with the mouse to move it, or
+    /// to copy it when holding ctrl or alt.
+    ///
+    /// Default: true
+    pub drag_and_drop_selection: Option<bool>,
+
     /// Whether the editor search results will loop
     ///
     /// Default: true
@@ -255,6 +295,12 @@ pub struct EditorSettingsContent {
 
     /// Jupyter REPL settings.
     pub jupyter: Option<JupyterContent>,
+
+    /// Settings for keeping the undo history of files across restarts.
+    pub persistent_undo: Option<PersistentUndoContent>,
+
+    /// Settings for showing diagnostic messages at the end of the lines they're on.
+    pub inline_diagnostics: Option<InlineDiagnosticsContent>,
 }
 
 // Toolbar related settings
@@ -304,6 +350,43 @@ pub struct ScrollbarContent {
     pub cursors: Option<bool>,
 }
 
+/// Persistent undo related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct PersistentUndoContent {
+    /// Whether previously saved versions of a file can be restored with undo
+    /// after the file is closed and reopened, including across restarts.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The maximum number of saved versions to keep for each file.
+    ///
+    /// Default: 20
+    pub max_entries: Option<usize>,
+    /// Files larger than this many bytes do not keep a persistent undo history.
+    ///
+    /// Default: 1000000
+    pub max_file_size: Option<usize>,
+}
+
+/// Inline diagnostics related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineDiagnosticsContent {
+    /// Whether to show diagnostic messages at the end of the lines they're on,
+    /// in addition to underlining the diagnostics.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The least severe diagnostics to show inline.
+    ///
+    /// Default: hint
+    pub min_severity: Option<InlineDiagnosticsSeverity>,
+    /// The number of characters after which messages are truncated. The full
+    /// message is shown by clicking on it.
+    ///
+    /// Default: 80
+    pub max_length: Option<usize>,
+}
+
 /// Gutter related settings
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct GutterContent {
@@ -1,5 +1,6 @@
 use super::*;
 use crate::{
+    editor_settings::{InlineDiagnosticsContent, InlineDiagnosticsSeverity},
     scroll::scroll_amount::ScrollAmount,
     test::{
         assert_text_with_selections, build_editor, editor_hunks,
@@ -339,6 +340,54 @@ fn test_ime_composition(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_ime_composition_clause(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer = MultiBuffer::build_simple("ab", cx);
+    cx.add_window(|cx| {
+        let mut editor = build_editor(buffer.clone(), cx);
+        let clause_ranges = |editor: &Editor, cx: &mut ViewContext<Editor>| {
+            let snapshot = editor.buffer.read(cx).snapshot(cx);
+            editor
+                .text_highlights::<InputCompositionClause>(cx)
+                .map(|(_, ranges)| {
+                    ranges
+                        .iter()
+                        .map(|range| {
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot)
+                        })
+                        .collect::<Vec<_>>()
+                })
+        };
+
+        // The selected part of the composition is underlined as the clause being converted.
+        editor.replace_and_mark_text_in_range(None, "xyz", Some(0..2), cx);
+        assert_eq!(editor.text(cx), "xyzab");
+        assert_eq!(
+            editor.marked_text_ranges(cx),
+            Some(vec![OffsetUtf16(0)..OffsetUtf16(3)])
+        );
+        assert_eq!(clause_ranges(&editor, cx), Some(vec![0..2]));
+
+        // Moving the caret within the composition removes the clause.
+        editor.replace_and_mark_text_in_range(None, "xyz", Some(3..3), cx);
+        assert_eq!(editor.text(cx), "xyzab");
+        assert_eq!(clause_ranges(&editor, cx), None);
+
+        editor.replace_and_mark_text_in_range(None, "xyz", Some(1..3), cx);
+        assert_eq!(clause_ranges(&editor, cx), Some(vec![1..3]));
+
+        // Finalizing the composition removes the clause.
+        editor.replace_text_in_range(None, "XYZ", cx);
+        assert_eq!(editor.text(cx), "XYZab");
+        assert_eq!(editor.marked_text_ranges(cx), None);
+        assert_eq!(clause_ranges(&editor, cx), None);
+
+        editor
+    });
+}
+
 #[gpui::test]
 fn test_selection_with_mouse(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -737,6 +786,34 @@ async fn test_navigation_history(cx: &mut TestAppContext) {
             editor.navigate(nav_entry.data.unwrap(), cx);
             assert_eq!(editor.scroll_manager.anchor(), original_scroll_position);
 
+            // Deactivating the editor records all of its selections, which are restored
+            // when navigating back to it, e.g. when reopening it after it was closed.
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([
+                    Point::new(1, 0)..Point::new(1, 2),
+                    Point::new(20, 3)..Point::new(20, 1),
+                ])
+            });
+            while pop_history(&mut editor, cx).is_some() {}
+            editor.deactivated(cx);
+            let nav_entry = pop_history(&mut editor, cx).unwrap();
+            let nav_history = editor.nav_history.take();
+            editor.move_to_end(&MoveToEnd, cx);
+            editor.nav_history = nav_history;
+            editor.navigate(nav_entry.data.unwrap(), cx);
+            assert_eq!(
+                editor
+                    .selections
+                    .all::<Point>(cx)
+                    .iter()
+                    .map(|selection| selection.tail()..selection.head())
+                    .collect::<Vec<_>>(),
+                vec![
+                    Point::new(1, 0)..Point::new(1, 2),
+                    Point::new(20, 3)..Point::new(20, 1),
+                ]
+            );
+
             // Ensure we don't panic when navigation data contains invalid anchors *and* points.
             let mut invalid_anchor = editor.scroll_manager.anchor().anchor;
             invalid_anchor.text_anchor.buffer_id = BufferId::new(999).ok();
@@ -745,6 +822,8 @@ async fn test_navigation_history(cx: &mut TestAppContext) {
                 Box::new(NavigationData {
                     cursor_anchor: invalid_anchor,
                     cursor_position: invalid_point,
+                    selections: Vec::new(),
+                    selection_positions: Vec::new(),
                     scroll_anchor: ScrollAnchor {
                         anchor: invalid_anchor,
                         offset: Default::default(),
@@ -1952,6 +2031,287 @@ async fn test_autoscroll(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_typewriter_scrolling(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.typewriter_scrolling = Some(true);
+            });
+        });
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 6. * line_height));
+
+    cx.set_state(
+        &r#"ˇone
+            two
+            three
+            four
+            five
+            six
+            seven
+            eight
+            nine
+            ten
+        "#,
+    );
+
+    // Moving the cursor keeps its line centered, even before it reaches the scroll margin.
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+            selections.select_ranges([Point::new(4, 0)..Point::new(4, 0)]);
+        })
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 2.0)
+        );
+    });
+
+    cx.update_editor(|editor, cx| {
+        editor.move_down(&Default::default(), cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 3.0)
+        );
+    });
+
+    // Lines near the start of the buffer can't be centered.
+    cx.update_editor(|editor, cx| {
+        editor.move_to_beginning(&Default::default(), cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 0.0)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_trailing_whitespace_highlights(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.highlight_trailing_whitespace = Some(true);
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("one  \ntwo  ˇ\nthree\n    \n");
+
+    // The row with the cursor isn't highlighted.
+    cx.update_editor(|editor, cx| {
+        let snapshot = editor.snapshot(cx);
+        let buffer_rows = snapshot.buffer_rows(DisplayRow(0)).collect::<Vec<_>>();
+        let highlights = editor
+            .trailing_whitespace_highlights(&buffer_rows, &snapshot.display_snapshot, cx)
+            .into_iter()
+            .map(|(range, _)| range)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            highlights,
+            [
+                DisplayPoint::new(DisplayRow(0), 3)..DisplayPoint::new(DisplayRow(0), 5),
+                DisplayPoint::new(DisplayRow(3), 0)..DisplayPoint::new(DisplayRow(3), 4),
+            ]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_rainbow_brackets(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.colorize_brackets = Some(true);
+    });
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_brackets_query(
+            r#"
+                ("(" @open ")" @close)
+                ("{" @open "}" @close)
+                ("\"" @open "\"" @close)
+            "#,
+        )
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(r#"fn f(ˇa: A) { g(h("")) }"#);
+    cx.run_until_parked();
+
+    // Sibling pairs share a color, and quotes aren't colored.
+    cx.update_editor(|editor, cx| {
+        let accents = cx.theme().accents().clone();
+        let snapshot = editor.snapshot(cx);
+        let colored_brackets = snapshot
+            .display_snapshot
+            .chunks(DisplayRow(0)..DisplayRow(1), true, Default::default())
+            .filter_map(|chunk| {
+                let color = chunk.highlight_style?.color?;
+                let depth =
+                    (0..accents.0.len() as u32).find(|ix| accents.color_for_index(*ix) == color)?;
+                Some((chunk.text.to_string(), depth))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colored_brackets,
+            [
+                ("(".to_string(), 0),
+                (")".to_string(), 0),
+                ("{".to_string(), 0),
+                ("(".to_string(), 1),
+                ("(".to_string(), 2),
+                (")".to_string(), 2),
+                (")".to_string(), 1),
+                ("}".to_string(), 0),
+            ]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_read_only_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<project::WorktreeSettings>(cx, |settings| {
+                settings.read_only_files = Some(vec!["**/generated".to_string()]);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/a",
+        json!({
+            "main.rs": "fn main() {}",
+            "generated": {
+                "schema.rs": "struct Schema;",
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let mut open_editor = |path: &'static str| {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, path), None, true, cx)
+            })
+            .unwrap()
+    };
+    let main_editor = open_editor("main.rs")
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let generated_editor = open_editor("generated/schema.rs")
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+
+    main_editor.update(cx, |editor, cx| assert!(!editor.read_only(cx)));
+    generated_editor.update(cx, |editor, cx| {
+        assert!(editor.read_only(cx));
+        editor.handle_input("x", cx);
+        assert_eq!(editor.text(cx), "struct Schema;");
+
+        editor.toggle_read_only(&ToggleReadOnly, cx);
+        assert!(!editor.read_only(cx));
+        editor.handle_input("x", cx);
+        assert_eq!(editor.text(cx), "xstruct Schema;");
+    });
+}
+
+#[gpui::test]
+async fn test_autosave_language_override(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.languages.insert(
+            "Rust".into(),
+            LanguageSettingsContent {
+                autosave: Some(language_settings::AutosaveSetting::AfterDelay {
+                    milliseconds: 500,
+                }),
+                ..Default::default()
+            },
+        );
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/a",
+        json!({
+            "main.rs": "fn main() {}",
+            "notes.txt": "notes",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages().add(rust_lang()));
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let mut editors = Vec::new();
+    for path in ["main.rs", "notes.txt"] {
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, path), None, true, cx)
+            })
+            .unwrap()
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        editor.update(cx, |editor, cx| editor.handle_input("// ", cx));
+        editors.push(editor);
+    }
+    cx.executor().run_until_parked();
+
+    // Only the Rust buffer is saved once the delay expires.
+    cx.executor().advance_clock(Duration::from_millis(500));
+    cx.executor().run_until_parked();
+    editors[0].update(cx, |editor, cx| {
+        assert!(!editor.buffer().read(cx).is_dirty(cx));
+    });
+    editors[1].update(cx, |editor, cx| {
+        assert!(editor.buffer().read(cx).is_dirty(cx));
+    });
+}
+
 #[gpui::test]
 async fn test_move_page_up_page_down(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -3060,6 +3420,61 @@ fn test_join_lines_with_multi_selection(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_join_lines_with_comments_and_brackets(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into()],
+            ..LanguageConfig::default()
+        },
+        None,
+    ));
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(indoc! {"
+            // ˇfirst
+            //   second
+        let v = vec![
+            ˇa,
+        ];
+        let s = S {
+            ˇb,
+        };
+    "});
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+            // firstˇ second
+        let v = vec![
+            aˇ];
+        let s = S {
+            bˇ };
+    "});
+
+    cx.set_state(indoc! {"
+        foo(ˇ
+            a,
+        )
+    "});
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        foo(aˇ)
+    "});
+
+    // Without whitespace handling, lines are concatenated as is.
+    cx.set_state(indoc! {"
+        // ˇfirst
+          // second
+    "});
+    cx.update_editor(|e, cx| e.join_lines_impl(false, cx));
+    cx.assert_editor_state(indoc! {"
+        // firstˇ  // second
+    "});
+}
+
 #[gpui::test]
 async fn test_join_lines_with_git_diff_base(
     executor: BackgroundExecutor,
@@ -3186,7 +3601,26 @@ async fn test_manipulate_lines_with_single_selection(cx: &mut TestAppContext) {
         5ˇ»
     "});
 
-    // Skip testing shuffle_line()
+    // Test sort_lines_numerically()
+    cx.set_state(indoc! {"
+        «10 apples
+        9 pears
+        -2.5
+        no number
+          3.75
+        1.2.3ˇ»
+    "});
+    cx.update_editor(|e, cx| e.sort_lines_numerically(&SortLinesNumerically, cx));
+    cx.assert_editor_state(indoc! {"
+        «no number
+        -2.5
+        1.2.3
+          3.75
+        9 pears
+        10 applesˇ»
+    "});
+
+    // Skip testing shuffle_line()
 
     // From here on out, test more complex cases of manipulate_lines() with a single driver method: sort_lines_case_sensitive()
     // Since all methods calling manipulate_lines() are doing the exact same general thing (reordering lines)
@@ -3538,6 +3972,15 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
         «TSCHÜSSˇ»
     "});
 
+    // Test convert_to_screaming_snake_case() with multiple cursors
+    cx.set_state(indoc! {"
+        «fooBarˇ» «baz-quxˇ» ˇhello_world
+    "});
+    cx.update_editor(|e, cx| e.convert_to_screaming_snake_case(&ConvertToScreamingSnakeCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «FOO_BARˇ» «BAZ_QUXˇ» «HELLO_WORLDˇ»
+    "});
+
     // Test to make sure we don't crash when text shrinks
     cx.set_state(indoc! {"
         aaa_bbbˇ
@@ -3669,6 +4112,52 @@ fn test_duplicate_line(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_duplicate_selection(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Non-empty selections duplicate their text, empty ones their line.
+    cx.set_state(indoc! {"
+        one «twoˇ» three
+        fˇour five"});
+    cx.update_editor(|e, cx| e.duplicate_selection_down(&DuplicateSelectionDown, cx));
+    cx.assert_editor_state(indoc! {"
+        one two«twoˇ» three
+        four five
+        fˇour five"});
+
+    cx.set_state(indoc! {"
+        one «twoˇ» three
+        fˇour five"});
+    cx.update_editor(|e, cx| e.duplicate_selection_up(&DuplicateSelectionUp, cx));
+    cx.assert_editor_state(indoc! {"
+        one «twoˇ»two three
+        fˇour five
+        four five"});
+
+    // Column selections are duplicated row by row, keeping their direction.
+    cx.set_state(indoc! {"
+        a«ˇb»c
+        d«ˇe»f"});
+    cx.update_editor(|e, cx| e.duplicate_selection_down(&DuplicateSelectionDown, cx));
+    cx.assert_editor_state(indoc! {"
+        ab«ˇb»c
+        de«ˇe»f"});
+    cx.update_editor(|e, cx| e.duplicate_selection_up(&DuplicateSelectionUp, cx));
+    cx.assert_editor_state(indoc! {"
+        ab«ˇb»bc
+        de«ˇe»ef"});
+
+    // Several cursors on one line only duplicate it once.
+    cx.set_state("aˇbˇc");
+    cx.update_editor(|e, cx| e.duplicate_selection_down(&DuplicateSelectionDown, cx));
+    cx.assert_editor_state("abc\naˇbˇc");
+    cx.set_state("aˇbˇc");
+    cx.update_editor(|e, cx| e.duplicate_selection_up(&DuplicateSelectionUp, cx));
+    cx.assert_editor_state("aˇbˇc\nabc");
+}
+
 #[gpui::test]
 fn test_move_line_up_down(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3891,6 +4380,109 @@ fn test_transpose(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_transpose_words(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    _ = cx.add_window(|cx| {
+        let mut editor = build_editor(MultiBuffer::build_simple("one two three", cx), cx);
+        editor.change_selections(None, cx, |s| s.select_ranges([1..1]));
+        editor.transpose_words(&TransposeWords, cx);
+        assert_eq!(editor.text(cx), "two one three");
+        assert_eq!(editor.selections.ranges(cx), [7..7]);
+
+        editor.transpose_words(&TransposeWords, cx);
+        assert_eq!(editor.text(cx), "two three one");
+        assert_eq!(editor.selections.ranges(cx), [13..13]);
+
+        // There's no word after the last one.
+        editor.transpose_words(&TransposeWords, cx);
+        assert_eq!(editor.text(cx), "two three one");
+        assert_eq!(editor.selections.ranges(cx), [13..13]);
+
+        editor.undo(&Undo, cx);
+        assert_eq!(editor.text(cx), "two one three");
+
+        editor
+    });
+}
+
+#[gpui::test]
+fn test_swap_selections(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    _ = cx.add_window(|cx| {
+        let mut editor = build_editor(
+            MultiBuffer::build_simple("let a = foo(x);\nlet b = bar;", cx),
+            cx,
+        );
+        editor.change_selections(None, cx, |s| s.select_ranges([8..14, 24..27]));
+        editor.swap_selections(&SwapSelections, cx);
+        assert_eq!(editor.text(cx), "let a = bar;\nlet b = foo(x);");
+        assert_eq!(editor.selections.ranges(cx), [8..11, 21..27]);
+
+        // Nothing happens unless there are exactly two selections.
+        editor.change_selections(None, cx, |s| s.select_ranges([8..11]));
+        editor.swap_selections(&SwapSelections, cx);
+        assert_eq!(editor.text(cx), "let a = bar;\nlet b = foo(x);");
+
+        editor
+    });
+}
+
+#[gpui::test]
+fn test_drag_and_drop_selection(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor =
+        cx.add_window(|cx| build_editor(MultiBuffer::build_simple("one two three", cx), cx));
+    let other_editor = cx.add_window(|cx| build_editor(MultiBuffer::build_simple("four", cx), cx));
+
+    // Moving a selection within the editor.
+    _ = editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| s.select_ranges([3..7]));
+        let selection = editor
+            .draggable_selection_at(DisplayPoint::new(DisplayRow(0), 5), cx)
+            .unwrap();
+        editor.start_selection_drag(selection.range(), cx);
+        assert!(editor.drop_selection(DisplayPoint::new(DisplayRow(0), 13), false, cx));
+        assert_eq!(editor.text(cx), "one three two");
+        assert_eq!(editor.selections.ranges(cx), [9..13]);
+    });
+
+    // Copying it instead, when ctrl or alt is held.
+    _ = editor.update(cx, |editor, cx| {
+        let selection = editor
+            .draggable_selection_at(DisplayPoint::new(DisplayRow(0), 11), cx)
+            .unwrap();
+        editor.start_selection_drag(selection.range(), cx);
+        assert!(editor.drop_selection(DisplayPoint::new(DisplayRow(0), 0), true, cx));
+        assert_eq!(editor.text(cx), " twoone three two");
+        assert_eq!(editor.selections.ranges(cx), [0..4]);
+    });
+
+    // Dropping a selection within itself does nothing.
+    _ = editor.update(cx, |editor, cx| {
+        editor.start_selection_drag(editor.selections.newest_anchor().range(), cx);
+        assert!(!editor.drop_selection(DisplayPoint::new(DisplayRow(0), 2), false, cx));
+        assert_eq!(editor.text(cx), " twoone three two");
+    });
+
+    // Moving a selection to another editor.
+    _ = editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| s.select_ranges([7..13]));
+        editor.start_selection_drag(editor.selections.newest_anchor().range(), cx);
+    });
+    _ = other_editor.update(cx, |editor, cx| {
+        assert!(editor.drop_selection(DisplayPoint::new(DisplayRow(0), 4), false, cx));
+        assert_eq!(editor.text(cx), "four three");
+        assert_eq!(editor.selections.ranges(cx), [4..10]);
+    });
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), " twoone two");
+    });
+}
+
 #[gpui::test]
 async fn test_clipboard(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -4091,6 +4683,168 @@ async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_paste_without_reformatting(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(indoc! {"
+        const a: B = (
+            c(),
+            «d(
+                e,
+                f
+            )ˇ»
+        );
+    "});
+    cx.update_editor(|e, cx| e.cut(&Cut, cx));
+
+    // The pasted text keeps its original indentation.
+    cx.set_state(indoc! {"
+        ˇ
+        const a: B = (
+            c(),
+        );
+    "});
+    cx.update_editor(|e, cx| e.paste_without_reformatting(&PasteWithoutReformatting, cx));
+    cx.assert_editor_state(indoc! {"
+        d(
+                e,
+                f
+            )ˇ
+        const a: B = (
+            c(),
+        );
+    "});
+}
+
+#[gpui::test]
+async fn test_paste_with_auto_indent_on_paste_disabled(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.auto_indent_on_paste = Some(false);
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(indoc! {"
+        const a: B = (
+            c(),
+            «d(
+                e,
+                f
+            )ˇ»
+        );
+    "});
+    cx.update_editor(|e, cx| e.cut(&Cut, cx));
+
+    cx.set_state(indoc! {"
+        ˇ
+        const a: B = (
+            c(),
+        );
+    "});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        d(
+                e,
+                f
+            )ˇ
+        const a: B = (
+            c(),
+        );
+    "});
+}
+
+#[gpui::test]
+fn test_font_size_adjustment(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("abc", cx);
+        build_editor(buffer, cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        let base_size = ThemeSettings::get_global(cx).buffer_font_size(cx);
+
+        view.increase_font_size(&IncreaseFontSize, cx);
+        view.increase_font_size(&IncreaseFontSize, cx);
+        assert_eq!(view.font_size(cx), base_size + px(2.));
+        assert_eq!(
+            ThemeSettings::get_global(cx).buffer_font_size(cx),
+            base_size
+        );
+
+        view.decrease_font_size(&DecreaseFontSize, cx);
+        assert_eq!(view.font_size(cx), base_size + px(1.));
+
+        view.reset_font_size(&ResetFontSize, cx);
+        assert_eq!(view.font_size(cx), base_size);
+
+        for _ in 0..100 {
+            view.decrease_font_size(&DecreaseFontSize, cx);
+        }
+        assert_eq!(view.font_size(cx), px(6.));
+    });
+}
+
+#[gpui::test]
+async fn test_screen_reader_announcements(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇfn main() {
+            let x = 1;
+
+        }
+    "});
+    cx.take_announcements();
+
+    // Moving to another line reads the line.
+    cx.update_editor(|editor, cx| editor.move_down(&MoveDown, cx));
+    assert_eq!(cx.take_announcements(), ["let x = 1;"]);
+
+    // Moving within a line reads the text moved over, or the character at the cursor.
+    cx.update_editor(|editor, cx| editor.move_to_next_word_end(&MoveToNextWordEnd, cx));
+    assert_eq!(cx.take_announcements(), ["let"]);
+    cx.update_editor(|editor, cx| editor.move_right(&MoveRight, cx));
+    assert_eq!(cx.take_announcements(), ["x"]);
+
+    cx.update_editor(|editor, cx| editor.move_down(&MoveDown, cx));
+    assert_eq!(cx.take_announcements(), ["blank"]);
+
+    // Typed text isn't read out again.
+    cx.update_editor(|editor, cx| editor.handle_input("a", cx));
+    assert!(cx.take_announcements().is_empty());
+
+    cx.update_editor(|editor, cx| editor.announce_cursor_position(&AnnounceCursorPosition, cx));
+    assert_eq!(cx.take_announcements(), ["Line 3, column 2: a"]);
+
+    cx.update_editor(|editor, cx| editor.select_line(&SelectLine, cx));
+    assert_eq!(cx.take_announcements(), ["a selected"]);
+
+    // Only the start of large selections is read out.
+    cx.set_state(&format!("ˇ{}", "word ".repeat(10_000)));
+    cx.take_announcements();
+    cx.update_editor(|editor, cx| editor.select_all(&SelectAll, cx));
+    let announcements = cx.take_announcements();
+    assert_eq!(announcements.len(), 1);
+    assert!(announcements[0].starts_with("word word"));
+    assert!(announcements[0].ends_with("… selected"));
+    assert!(announcements[0].chars().count() < 600);
+}
+
 #[gpui::test]
 fn test_select_all(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4228,6 +4982,72 @@ fn test_split_selection_into_lines(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_select_columns_with_keyboard(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc!(
+        r#"abˇcdef
+           ghijkl
+           m
+           opqrst
+           "#
+    ));
+
+    cx.update_editor(|editor, cx| {
+        editor.select_column_right(&SelectColumnRight, cx);
+        editor.select_column_right(&SelectColumnRight, cx);
+        editor.select_column_down(&SelectColumnDown, cx);
+    });
+    cx.assert_editor_state(indoc!(
+        r#"ab«cdˇ»ef
+           gh«ijˇ»kl
+           m
+           opqrst
+           "#
+    ));
+
+    // Rows that are too short to intersect the block are skipped.
+    cx.update_editor(|editor, cx| {
+        editor.select_column_down(&SelectColumnDown, cx);
+        editor.select_column_down(&SelectColumnDown, cx);
+    });
+    cx.assert_editor_state(indoc!(
+        r#"ab«cdˇ»ef
+           gh«ijˇ»kl
+           m
+           op«qrˇ»st
+           "#
+    ));
+
+    cx.update_editor(|editor, cx| {
+        editor.select_column_up(&SelectColumnUp, cx);
+        editor.select_column_up(&SelectColumnUp, cx);
+        editor.select_column_left(&SelectColumnLeft, cx);
+    });
+    cx.assert_editor_state(indoc!(
+        r#"ab«cˇ»def
+           gh«iˇ»jkl
+           m
+           opqrst
+           "#
+    ));
+
+    // Changing the selections in some other way starts a new block at the newest selection.
+    cx.update_editor(|editor, cx| {
+        editor.move_right(&MoveRight, cx);
+        editor.select_column_right(&SelectColumnRight, cx);
+    });
+    cx.assert_editor_state(indoc!(
+        r#"abcdef
+           ghi«jˇ»kl
+           m
+           opqrst
+           "#
+    ));
+}
+
 #[gpui::test]
 async fn test_add_selection_above_below(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4868,16 +5688,108 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
             true,
             cx,
         );
-        view.select_larger_syntax_node(&SelectLargerSyntaxNode, cx);
-    });
-    editor.update(cx, |editor, cx| {
+        view.select_larger_syntax_node(&SelectLargerSyntaxNode, cx);
+    });
+    editor.update(cx, |editor, cx| {
+        assert_text_with_selections(
+            editor,
+            indoc! {r#"
+                use mod1::mod2::«{mod3, mod4}ˇ»;
+
+                fn fn_1«ˇ(param1: bool, param2: &str)» {
+                    «let var1 = "text";ˇ»
+                }
+            "#},
+            cx,
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_select_enclosing_function_and_statement(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::language()),
+    ));
+
+    let text = r#"
+        fn outer() {
+            let x = 1;
+            let f = |a: u32| {
+                a + x
+            };
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    editor
+        .condition::<crate::EditorEvent>(&cx, |view, cx| !view.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(3, 8)..Point::new(3, 8)])
+        });
+        editor.select_enclosing_statement(&SelectEnclosingStatement, cx);
+        assert_text_with_selections(
+            editor,
+            indoc! {r#"
+                fn outer() {
+                    let x = 1;
+                    «let f = |a: u32| {
+                        a + x
+                    };ˇ»
+                }
+            "#},
+            cx,
+        );
+
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx);
+        editor.select_enclosing_function(&SelectEnclosingFunction, cx);
+        assert_text_with_selections(
+            editor,
+            indoc! {r#"
+                fn outer() {
+                    let x = 1;
+                    let f = «|a: u32| {
+                        a + x
+                    }ˇ»;
+                }
+            "#},
+            cx,
+        );
+
+        editor.select_enclosing_function(&SelectEnclosingFunction, cx);
         assert_text_with_selections(
             editor,
             indoc! {r#"
-                use mod1::mod2::«{mod3, mod4}ˇ»;
+                «fn outer() {
+                    let x = 1;
+                    let f = |a: u32| {
+                        a + x
+                    };
+                }ˇ»
+            "#},
+            cx,
+        );
 
-                fn fn_1«ˇ(param1: bool, param2: &str)» {
-                    «let var1 = "text";ˇ»
+        // Shrinking the selection goes back through the previous selections.
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx);
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx);
+        assert_text_with_selections(
+            editor,
+            indoc! {r#"
+                fn outer() {
+                    let x = 1;
+                    let f = |a: u32| {
+                        ˇa + x
+                    };
                 }
             "#},
             cx,
@@ -7496,6 +8408,102 @@ async fn test_signature_help(cx: &mut gpui::TestAppContext) {
         .await;
 }
 
+#[gpui::test]
+async fn test_signature_help_overloads(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            signature_help_provider: Some(lsp::SignatureHelpOptions {
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        fn main() {
+            sample(ˇ);
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.show_signature_help(&ShowSignatureHelp, cx);
+    });
+
+    let mocked_response = lsp::SignatureHelp {
+        signatures: vec![
+            lsp::SignatureInformation {
+                label: "fn sample(param1: u8)".to_string(),
+                documentation: Some(lsp::Documentation::String("Samples a byte.".to_string())),
+                parameters: Some(vec![lsp::ParameterInformation {
+                    label: lsp::ParameterLabel::Simple("param1: u8".to_string()),
+                    documentation: Some(lsp::Documentation::String("The byte.".to_string())),
+                }]),
+                active_parameter: None,
+            },
+            lsp::SignatureInformation {
+                label: "fn sample(param1: u16)".to_string(),
+                documentation: None,
+                parameters: Some(vec![lsp::ParameterInformation {
+                    label: lsp::ParameterLabel::Simple("param1: u16".to_string()),
+                    documentation: None,
+                }]),
+                active_parameter: None,
+            },
+        ],
+        active_signature: Some(0),
+        active_parameter: Some(0),
+    };
+    handle_signature_help_request(&mut cx, mocked_response).await;
+
+    cx.condition(|editor, _| editor.signature_help_state.is_shown())
+        .await;
+
+    cx.editor(|editor, _| {
+        assert_eq!(editor.signature_help_state.signature_count(), 2);
+        let popover = editor.signature_help_state.popover().unwrap();
+        assert_eq!(popover.signature_ix, 0);
+        assert_eq!(popover.signature_count, 2);
+        assert!(popover.parsed_content.text.starts_with("param1: u8"));
+        assert_eq!(
+            popover.documentation.as_ref().unwrap().text,
+            "param1: u8 — The byte.\n\nSamples a byte."
+        );
+    });
+
+    cx.update_editor(|editor, cx| {
+        editor.signature_help_next(&SignatureHelpNext, cx);
+    });
+    cx.editor(|editor, _| {
+        let popover = editor.signature_help_state.popover().unwrap();
+        assert_eq!(popover.signature_ix, 1);
+        assert!(popover.parsed_content.text.starts_with("param1: u16"));
+        assert!(popover.documentation.is_none());
+    });
+
+    // Cycling wraps around.
+    cx.update_editor(|editor, cx| {
+        editor.signature_help_next(&SignatureHelpNext, cx);
+    });
+    cx.editor(|editor, _| {
+        assert_eq!(
+            editor.signature_help_state.popover().unwrap().signature_ix,
+            0
+        );
+    });
+    cx.update_editor(|editor, cx| {
+        editor.signature_help_prev(&SignatureHelpPrevious, cx);
+    });
+    cx.editor(|editor, _| {
+        assert_eq!(
+            editor.signature_help_state.popover().unwrap().signature_ix,
+            1
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_completion(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -9090,6 +10098,102 @@ async fn go_to_prev_overlapping_diagnostic(
     "});
 }
 
+#[gpui::test]
+async fn test_inline_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.inline_diagnostics = Some(InlineDiagnosticsContent {
+                    enabled: Some(true),
+                    min_severity: Some(InlineDiagnosticsSeverity::Warning),
+                    max_length: Some(12),
+                });
+            });
+        });
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let project = cx.update_editor(|editor, _| editor.project.clone().unwrap());
+    cx.set_state(indoc! {"
+        ˇlet a = 1;
+        let b = 2;
+        let c = 3;
+    "});
+
+    let diagnostic = |row, severity, message: &str| lsp::Diagnostic {
+        range: lsp::Range::new(lsp::Position::new(row, 4), lsp::Position::new(row, 5)),
+        severity: Some(severity),
+        message: message.to_string(),
+        ..Default::default()
+    };
+    _ = cx.update(|cx| {
+        _ = project.update(cx, |project, cx| {
+            project
+                .update_diagnostics(
+                    LanguageServerId(0),
+                    lsp::PublishDiagnosticsParams {
+                        uri: lsp::Url::from_file_path("/root/file").unwrap(),
+                        version: None,
+                        diagnostics: vec![
+                            diagnostic(0, lsp::DiagnosticSeverity::WARNING, "unused"),
+                            diagnostic(0, lsp::DiagnosticSeverity::ERROR, "mismatched\ntypes"),
+                            diagnostic(1, lsp::DiagnosticSeverity::HINT, "rename"),
+                            diagnostic(
+                                2,
+                                lsp::DiagnosticSeverity::WARNING,
+                                "variable is never read",
+                            ),
+                        ],
+                    },
+                    &[],
+                    cx,
+                )
+                .unwrap()
+        });
+    });
+    cx.executor().run_until_parked();
+
+    cx.update_editor(|editor, cx| {
+        let buffer = editor.buffer.read(cx).snapshot(cx);
+        let rows = (0..4)
+            .map(|row| Some(MultiBufferRow(row)))
+            .collect::<Vec<_>>();
+        let messages = editor
+            .inline_diagnostics(&rows, &buffer, cx)
+            .into_iter()
+            .map(|diagnostic| diagnostic.map(|diagnostic| diagnostic.message.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                Some("mismatched".to_string()),
+                None,
+                Some("variable is …".to_string()),
+                None,
+            ]
+        );
+    });
+
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.inline_diagnostics = Some(InlineDiagnosticsContent {
+                    enabled: Some(false),
+                    ..Default::default()
+                });
+            });
+        });
+    });
+    cx.update_editor(|editor, cx| {
+        let buffer = editor.buffer.read(cx).snapshot(cx);
+        let rows = (0..4)
+            .map(|row| Some(MultiBufferRow(row)))
+            .collect::<Vec<_>>();
+        assert_eq!(editor.inline_diagnostics(&rows, &buffer, cx), vec![None; 4]);
+    });
+}
+
 #[gpui::test]
 async fn test_diagnostics_with_links(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -9340,6 +10444,33 @@ async fn test_move_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_move_to_enclosing_keyword_bracket(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::language()),
+    )
+    .with_brackets_query(indoc! {r#"
+            ("{" @open "}" @close)
+            (if_expression "if" @open (block "}" @close))
+        "#})
+    .unwrap();
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(Arc::new(language)), cx));
+
+    cx.set_state("fn main() { ˇif x { y(); } }");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.move_to_enclosing_bracket(&MoveToEnclosingBracket, cx));
+    cx.assert_editor_state("fn main() { if x { y(); }ˇ }");
+
+    cx.set_state("fn main() { iˇf x { y(); } }");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.move_to_enclosing_bracket(&MoveToEnclosingBracket, cx));
+    cx.assert_editor_state("fn main() { if x { y(); }ˇ }");
+}
+
 #[gpui::test]
 async fn test_on_type_formatting_not_triggered(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -9457,6 +10588,122 @@ async fn test_on_type_formatting_not_triggered(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_on_type_formatting_language_setting(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            document_on_type_formatting_provider: Some(lsp::DocumentOnTypeFormattingOptions {
+                first_trigger_character: ";".to_string(),
+                more_trigger_character: None,
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let requests = Arc::new(AtomicUsize::new(0));
+    cx.lsp
+        .handle_request::<lsp::request::OnTypeFormatting, _, _>({
+            let requests = requests.clone();
+            move |params, _| {
+                requests.fetch_add(1, atomic::Ordering::Release);
+                async move {
+                    assert_eq!(params.ch, ";");
+                    Ok(Some(vec![lsp::TextEdit {
+                        range: lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(1, 0)),
+                        new_text: "    ".into(),
+                    }]))
+                }
+            }
+        });
+
+    cx.set_state(indoc! {"
+        fn main() {
+        let a = 5ˇ
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.handle_input(";", cx));
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = 5;ˇ
+        }
+    "});
+    assert_eq!(requests.load(atomic::Ordering::Acquire), 1);
+
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.languages.insert(
+                    "Rust".into(),
+                    LanguageSettingsContent {
+                        use_on_type_format: Some(false),
+                        ..Default::default()
+                    },
+                );
+            });
+        });
+    });
+    cx.set_state(indoc! {"
+        fn main() {
+        let a = 5ˇ
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.handle_input(";", cx));
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+        let a = 5;ˇ
+        }
+    "});
+    assert_eq!(
+        requests.load(atomic::Ordering::Acquire),
+        1,
+        "Should not request on type formatting when the language disables it"
+    );
+}
+
+#[gpui::test]
+async fn test_hierarchy_code_actions(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+            type_hierarchy_provider: Some(lsp::OneOf::Left(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    cx.set_state("fn ˇmain() {}");
+    cx.update_editor(|editor, cx| {
+        editor.toggle_code_actions(
+            &ToggleCodeActions {
+                deployed_from_indicator: None,
+            },
+            cx,
+        )
+    });
+    cx.executor().run_until_parked();
+
+    cx.update_editor(|editor, _| {
+        if let Some(ContextMenu::CodeActions(menu)) = editor.context_menu.read().as_ref() {
+            assert_eq!(
+                menu.actions
+                    .iter()
+                    .map(|action| action.label())
+                    .collect::<Vec<_>>(),
+                ["Show Call Hierarchy", "Show Type Hierarchy"]
+            );
+        } else {
+            panic!("expected the code actions menu to be open");
+        }
+    });
+}
+
 #[gpui::test]
 async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -9475,6 +10722,8 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
 
     let server_restarts = Arc::new(AtomicUsize::new(0));
     let closure_restarts = Arc::clone(&server_restarts);
+    let sent_configurations = Arc::new(Mutex::new(Vec::new()));
+    let closure_configurations = Arc::clone(&sent_configurations);
     let language_server_name = "test language server";
     let language_name: Arc<str> = "Rust".into();
 
@@ -9503,6 +10752,10 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
                     task_restarts.fetch_add(1, atomic::Ordering::Release);
                     futures::future::ready(Ok(()))
                 });
+                let configurations = Arc::clone(&closure_configurations);
+                fake_server.handle_notification::<lsp::notification::DidChangeConfiguration, _>(
+                    move |params, _| configurations.lock().push(params.settings),
+                );
             })),
             ..Default::default()
         },
@@ -9536,6 +10789,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             "Some other server name".into(),
             LspSettings {
+                enabled: None,
                 binary: None,
                 settings: None,
                 initialization_options: Some(json!({
@@ -9555,6 +10809,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             language_server_name.into(),
             LspSettings {
+                enabled: None,
                 binary: None,
                 settings: None,
                 initialization_options: Some(json!({
@@ -9574,6 +10829,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             language_server_name.into(),
             LspSettings {
+                enabled: None,
                 binary: None,
                 settings: None,
                 initialization_options: Some(json!({
@@ -9593,6 +10849,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             language_server_name.into(),
             LspSettings {
+                enabled: None,
                 binary: None,
                 settings: None,
                 initialization_options: None,
@@ -9605,6 +10862,31 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         2,
         "Should restart LSP server on another related LSP settings change"
     );
+
+    update_test_project_settings(cx, |project_settings| {
+        project_settings.lsp.insert(
+            language_server_name.into(),
+            LspSettings {
+                enabled: None,
+                binary: None,
+                settings: Some(json!({
+                    "someSetting": true
+                })),
+                initialization_options: None,
+            },
+        );
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        server_restarts.load(atomic::Ordering::Acquire),
+        2,
+        "Should not restart LSP server when only its workspace configuration changes"
+    );
+    assert_eq!(
+        sent_configurations.lock().last(),
+        Some(&json!({ "someSetting": true })),
+        "Should send the changed settings to the running LSP server"
+    );
 }
 
 #[gpui::test]
@@ -1,5 +1,6 @@
 use crate::{
     blame_entry_tooltip::{blame_entry_relative_timestamp, BlameEntryTooltip},
+    diagnostic_style,
     display_map::{
         Block, BlockContext, BlockStyle, DisplaySnapshot, HighlightedChunk, ToDisplayPoint,
     },
@@ -63,6 +64,7 @@ use std::{
     sync::Arc,
 };
 use sum_tree::Bias;
+use text::BufferId;
 use theme::{ActiveTheme, PlayerColor};
 use ui::prelude::*;
 use ui::{h_flex, ButtonLike, ButtonStyle, ContextMenu, Tooltip};
@@ -176,6 +178,10 @@ impl EditorElement {
         register_action(view, cx, Editor::select_up_by_lines);
         register_action(view, cx, Editor::select_page_down);
         register_action(view, cx, Editor::select_page_up);
+        register_action(view, cx, Editor::select_column_up);
+        register_action(view, cx, Editor::select_column_down);
+        register_action(view, cx, Editor::select_column_left);
+        register_action(view, cx, Editor::select_column_right);
         register_action(view, cx, Editor::cancel);
         register_action(view, cx, Editor::newline);
         register_action(view, cx, Editor::newline_above);
@@ -190,6 +196,7 @@ impl EditorElement {
         register_action(view, cx, Editor::join_lines);
         register_action(view, cx, Editor::sort_lines_case_sensitive);
         register_action(view, cx, Editor::sort_lines_case_insensitive);
+        register_action(view, cx, Editor::sort_lines_numerically);
         register_action(view, cx, Editor::reverse_lines);
         register_action(view, cx, Editor::shuffle_lines);
         register_action(view, cx, Editor::convert_to_upper_case);
@@ -197,6 +204,7 @@ impl EditorElement {
         register_action(view, cx, Editor::convert_to_title_case);
         register_action(view, cx, Editor::convert_to_snake_case);
         register_action(view, cx, Editor::convert_to_kebab_case);
+        register_action(view, cx, Editor::convert_to_screaming_snake_case);
         register_action(view, cx, Editor::convert_to_upper_camel_case);
         register_action(view, cx, Editor::convert_to_lower_camel_case);
         register_action(view, cx, Editor::convert_to_opposite_case);
@@ -209,12 +217,21 @@ impl EditorElement {
         register_action(view, cx, Editor::cut_to_end_of_line);
         register_action(view, cx, Editor::duplicate_line_up);
         register_action(view, cx, Editor::duplicate_line_down);
+        register_action(view, cx, Editor::duplicate_selection_up);
+        register_action(view, cx, Editor::duplicate_selection_down);
         register_action(view, cx, Editor::move_line_up);
         register_action(view, cx, Editor::move_line_down);
         register_action(view, cx, Editor::transpose);
+        register_action(view, cx, Editor::transpose_words);
+        register_action(view, cx, Editor::swap_selections);
         register_action(view, cx, Editor::cut);
         register_action(view, cx, Editor::copy);
         register_action(view, cx, Editor::paste);
+        register_action(view, cx, Editor::paste_without_reformatting);
+        register_action(view, cx, Editor::increase_font_size);
+        register_action(view, cx, Editor::decrease_font_size);
+        register_action(view, cx, Editor::reset_font_size);
+        register_action(view, cx, Editor::announce_cursor_position);
         register_action(view, cx, Editor::undo);
         register_action(view, cx, Editor::redo);
         register_action(view, cx, Editor::move_page_up);
@@ -289,6 +306,8 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_comments);
         register_action(view, cx, Editor::select_larger_syntax_node);
         register_action(view, cx, Editor::select_smaller_syntax_node);
+        register_action(view, cx, Editor::select_enclosing_function);
+        register_action(view, cx, Editor::select_enclosing_statement);
         register_action(view, cx, Editor::select_enclosing_symbol);
         register_action(view, cx, Editor::move_to_enclosing_bracket);
         register_action(view, cx, Editor::undo_selection);
@@ -342,6 +361,7 @@ impl EditorElement {
         register_action(view, cx, Editor::open_excerpts);
         register_action(view, cx, Editor::open_excerpts_in_split);
         register_action(view, cx, Editor::toggle_soft_wrap);
+        register_action(view, cx, Editor::toggle_read_only);
         register_action(view, cx, Editor::toggle_tab_bar);
         register_action(view, cx, Editor::toggle_line_numbers);
         register_action(view, cx, Editor::toggle_indent_guides);
@@ -402,6 +422,10 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(view, cx, Editor::accept_rename);
+        register_action(view, cx, Editor::accept_rename_in_file);
+        register_action(view, cx, Editor::reject_rename_in_file);
+        register_action(view, cx, Editor::reject_rename);
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.find_all_references(action, cx) {
                 task.detach_and_log_err(cx);
@@ -410,6 +434,8 @@ impl EditorElement {
             }
         });
         register_action(view, cx, Editor::show_signature_help);
+        register_action(view, cx, Editor::signature_help_next);
+        register_action(view, cx, Editor::signature_help_prev);
         register_action(view, cx, Editor::next_inline_completion);
         register_action(view, cx, Editor::previous_inline_completion);
         register_action(view, cx, Editor::show_inline_completion);
@@ -437,6 +463,7 @@ impl EditorElement {
                     return;
                 }
                 editor.update(cx, |editor, cx| {
+                    editor.toggle_inlay_hints_on_modifiers(event.modifiers, cx);
                     if editor.hover_state.focused(cx) {
                         return;
                     }
@@ -534,7 +561,28 @@ impl EditorElement {
 
         let point_for_position =
             position_map.point_for_position(text_hitbox.bounds, event.position);
+        if click_count == 1 && !modifiers.modified() {
+            if let Some(swatch_ix) =
+                editor.color_swatch_at(&point_for_position, &position_map.snapshot)
+            {
+                editor.deploy_color_picker(swatch_ix, event.position, cx);
+                cx.stop_propagation();
+                return;
+            }
+
+            if EditorSettings::get_global(cx).drag_and_drop_selection {
+                if let Some(selection) = point_for_position
+                    .as_valid()
+                    .and_then(|position| editor.draggable_selection_at(position, cx))
+                {
+                    editor.prepare_selection_drag(selection, event.position, cx);
+                    cx.stop_propagation();
+                    return;
+                }
+            }
+        }
         let position = point_for_position.previous_valid;
+        let multi_cursor_setting = EditorSettings::get_global(cx).multi_cursor_modifier;
         if modifiers.shift && modifiers.alt {
             editor.select(
                 SelectPhase::BeginColumnar {
@@ -544,6 +592,20 @@ impl EditorElement {
                 },
                 cx,
             );
+        } else if modifiers.alt
+            && !modifiers.control
+            && !modifiers.secondary()
+            && multi_cursor_setting == MultiCursorModifier::CmdOrCtrl
+        {
+            // When alt isn't used to add cursors, dragging with it starts a new block selection.
+            editor.select(
+                SelectPhase::BeginColumnar {
+                    position,
+                    reset: true,
+                    goal_column: point_for_position.exact_unclipped.column(),
+                },
+                cx,
+            );
         } else if modifiers.shift && !modifiers.control && !modifiers.alt && !modifiers.secondary()
         {
             editor.select(
@@ -554,7 +616,6 @@ impl EditorElement {
                 cx,
             );
         } else {
-            let multi_cursor_setting = EditorSettings::get_global(cx).multi_cursor_modifier;
             let multi_cursor_modifier = match multi_cursor_setting {
                 MultiCursorModifier::Alt => modifiers.alt,
                 MultiCursorModifier::CmdOrCtrl => modifiers.secondary(),
@@ -625,6 +686,19 @@ impl EditorElement {
         text_hitbox: &Hitbox,
         cx: &mut ViewContext<Editor>,
     ) {
+        let position = position_map
+            .point_for_position(text_hitbox.bounds, event.position)
+            .previous_valid;
+        let mut dropped = false;
+        if editor.has_active_selection_drag(cx) && text_hitbox.is_hovered(cx) {
+            let copy = event.modifiers.alt || event.modifiers.control;
+            dropped = editor.drop_selection(position, copy, cx);
+        }
+        if editor.end_selection_drag(position, cx) || dropped {
+            cx.stop_propagation();
+            return;
+        }
+
         let end_selection = editor.has_pending_selection();
         let pending_nonempty_selections = editor.has_pending_nonempty_selection();
 
@@ -674,43 +748,70 @@ impl EditorElement {
         editor: &mut Editor,
         event: &MouseMoveEvent,
         position_map: &PositionMap,
-        text_bounds: Bounds<Pixels>,
+        text_hitbox: &Hitbox,
         cx: &mut ViewContext<Editor>,
     ) {
+        let text_bounds = text_hitbox.bounds;
+        editor.start_selection_drag_if_moved(event.position, cx);
+        if editor.has_active_selection_drag(cx) {
+            // Only the editor under the mouse shows where the selection would be dropped,
+            // and scrolls when it's near its edges.
+            if text_hitbox.is_hovered(cx) {
+                let point_for_position =
+                    position_map.point_for_position(text_bounds, event.position);
+                editor.set_drop_cursor(Some(point_for_position.previous_valid), cx);
+                editor.apply_scroll_delta(
+                    Self::mouse_autoscroll_delta(event.position, text_bounds, position_map),
+                    cx,
+                );
+            } else {
+                editor.set_drop_cursor(None, cx);
+            }
+            return;
+        }
+
         if !editor.has_pending_selection() {
             return;
         }
 
         let point_for_position = position_map.point_for_position(text_bounds, event.position);
+        let scroll_delta = Self::mouse_autoscroll_delta(event.position, text_bounds, position_map);
+        editor.select(
+            SelectPhase::Update {
+                position: point_for_position.previous_valid,
+                goal_column: point_for_position.exact_unclipped.column(),
+                scroll_delta,
+            },
+            cx,
+        );
+    }
+
+    fn mouse_autoscroll_delta(
+        mouse_position: gpui::Point<Pixels>,
+        text_bounds: Bounds<Pixels>,
+        position_map: &PositionMap,
+    ) -> gpui::Point<f32> {
         let mut scroll_delta = gpui::Point::<f32>::default();
         let vertical_margin = position_map.line_height.min(text_bounds.size.height / 3.0);
         let top = text_bounds.origin.y + vertical_margin;
         let bottom = text_bounds.lower_left().y - vertical_margin;
-        if event.position.y < top {
-            scroll_delta.y = -scale_vertical_mouse_autoscroll_delta(top - event.position.y);
+        if mouse_position.y < top {
+            scroll_delta.y = -scale_vertical_mouse_autoscroll_delta(top - mouse_position.y);
         }
-        if event.position.y > bottom {
-            scroll_delta.y = scale_vertical_mouse_autoscroll_delta(event.position.y - bottom);
+        if mouse_position.y > bottom {
+            scroll_delta.y = scale_vertical_mouse_autoscroll_delta(mouse_position.y - bottom);
         }
 
         let horizontal_margin = position_map.line_height.min(text_bounds.size.width / 3.0);
         let left = text_bounds.origin.x + horizontal_margin;
         let right = text_bounds.upper_right().x - horizontal_margin;
-        if event.position.x < left {
-            scroll_delta.x = -scale_horizontal_mouse_autoscroll_delta(left - event.position.x);
+        if mouse_position.x < left {
+            scroll_delta.x = -scale_horizontal_mouse_autoscroll_delta(left - mouse_position.x);
         }
-        if event.position.x > right {
-            scroll_delta.x = scale_horizontal_mouse_autoscroll_delta(event.position.x - right);
+        if mouse_position.x > right {
+            scroll_delta.x = scale_horizontal_mouse_autoscroll_delta(mouse_position.x - right);
         }
-
-        editor.select(
-            SelectPhase::Update {
-                position: point_for_position.previous_valid,
-                goal_column: point_for_position.exact_unclipped.column(),
-                scroll_delta,
-            },
-            cx,
-        );
+        scroll_delta
     }
 
     fn mouse_moved(
@@ -941,6 +1042,21 @@ impl EditorElement {
                 .collect::<Vec<_>>();
             selections.push((player, layouts));
         }
+
+        if let Some(drop_cursor) = editor.drop_cursor_selection() {
+            selections.push((
+                self.style.local_player,
+                vec![SelectionLayout::new(
+                    drop_cursor,
+                    false,
+                    CursorShape::Bar,
+                    &snapshot.display_snapshot,
+                    false,
+                    false,
+                    None,
+                )],
+            ));
+        }
         (selections, active_rows, newest_selection_head)
     }
 
@@ -1116,6 +1232,36 @@ impl EditorElement {
         cursor_layouts
     }
 
+    fn render_buffer_header_controls(
+        &self,
+        buffer_id: BufferId,
+        cx: &mut WindowContext,
+    ) -> impl IntoElement {
+        h_flex()
+            .gap_1()
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .child(
+                IconButton::new("refresh-buffer", IconName::RotateCw)
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted)
+                    .tooltip(|cx| Tooltip::text("Refresh File", cx))
+                    .on_click(cx.listener_for(&self.editor, move |editor, _, cx| {
+                        cx.stop_propagation();
+                        editor.request_buffer_refresh(buffer_id, cx);
+                    })),
+            )
+            .child(
+                IconButton::new("exclude-buffer", IconName::Close)
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted)
+                    .tooltip(|cx| Tooltip::text("Exclude File", cx))
+                    .on_click(cx.listener_for(&self.editor, move |editor, _, cx| {
+                        cx.stop_propagation();
+                        editor.request_buffer_exclusion(buffer_id, cx);
+                    })),
+            )
+    }
+
     fn layout_scrollbar(
         &self,
         snapshot: &EditorSnapshot,
@@ -1323,6 +1469,71 @@ impl EditorElement {
         display_hunks
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_inline_diagnostics(
+        &self,
+        buffer_rows: &[Option<MultiBufferRow>],
+        start_row: DisplayRow,
+        line_layouts: &[LineWithInvisibles],
+        crease_trailers: &[Option<CreaseTrailerLayout>],
+        snapshot: &EditorSnapshot,
+        em_width: Pixels,
+        content_origin: gpui::Point<Pixels>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        cx: &mut WindowContext,
+    ) -> BTreeMap<DisplayRow, InlineDiagnosticLayout> {
+        const INLINE_DIAGNOSTIC_PADDING_EM_WIDTHS: f32 = 4.;
+
+        let diagnostics =
+            self.editor
+                .read(cx)
+                .inline_diagnostics(buffer_rows, &snapshot.buffer_snapshot, cx);
+        let mut layouts = BTreeMap::new();
+        for (ix, diagnostic) in diagnostics.into_iter().enumerate() {
+            let (Some(diagnostic), Some(line_layout)) = (diagnostic, line_layouts.get(ix)) else {
+                continue;
+            };
+            let display_row = DisplayRow(start_row.0 + ix as u32);
+            let line_end = if let Some(Some(crease_trailer)) = crease_trailers.get(ix) {
+                crease_trailer.bounds.right()
+            } else {
+                content_origin.x - scroll_pixel_position.x + line_layout.width
+            };
+            let start_x = line_end + em_width * INLINE_DIAGNOSTIC_PADDING_EM_WIDTHS;
+            let start_y = content_origin.y
+                + line_height * (display_row.as_f32() - scroll_pixel_position.y / line_height);
+
+            let editor = self.editor.clone();
+            let group_id = diagnostic.group_id;
+            let mut element = h_flex()
+                .id(("inline-diagnostic", ix))
+                .font_family(self.style.text.font().family)
+                .text_color(diagnostic_style(diagnostic.severity, cx.theme().status()))
+                .line_height(self.style.text.line_height)
+                .cursor(CursorStyle::PointingHand)
+                .child(diagnostic.message)
+                .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+                .on_click(move |_, cx| {
+                    // Shows the full message of the diagnostic below its line.
+                    editor.update(cx, |editor, cx| {
+                        editor.activate_diagnostics(group_id, cx);
+                    });
+                })
+                .into_any();
+            let size = element.layout_as_root(AvailableSpace::min_size(), cx);
+            element.prepaint_at(point(start_x, start_y), cx);
+            layouts.insert(
+                display_row,
+                InlineDiagnosticLayout {
+                    element,
+                    end_x: start_x + size.width,
+                },
+            );
+        }
+        layouts
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_inline_blame(
         &self,
@@ -1330,6 +1541,7 @@ impl EditorElement {
         display_snapshot: &DisplaySnapshot,
         line_layout: &LineWithInvisibles,
         crease_trailer: Option<&CreaseTrailerLayout>,
+        inline_diagnostic: Option<&InlineDiagnosticLayout>,
         em_width: Pixels,
         content_origin: gpui::Point<Pixels>,
         scroll_pixel_position: gpui::Point<Pixels>,
@@ -1369,7 +1581,9 @@ impl EditorElement {
         let start_x = {
             const INLINE_BLAME_PADDING_EM_WIDTHS: f32 = 6.;
 
-            let line_end = if let Some(crease_trailer) = crease_trailer {
+            let line_end = if let Some(inline_diagnostic) = inline_diagnostic {
+                inline_diagnostic.end_x
+            } else if let Some(crease_trailer) = crease_trailer {
                 crease_trailer.bounds.right()
             } else {
                 content_origin.x - scroll_pixel_position.x + line_layout.width
@@ -1607,7 +1821,7 @@ impl EditorElement {
             editor
                 .tasks
                 .iter()
-                .filter_map(|(_, tasks)| {
+                .filter_map(|(key, tasks)| {
                     let multibuffer_point = tasks.offset.0.to_point(&snapshot.buffer_snapshot);
                     let multibuffer_row = MultiBufferRow(multibuffer_point.row);
                     if snapshot.is_line_folded(multibuffer_row) {
@@ -1618,6 +1832,7 @@ impl EditorElement {
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
                         display_row,
+                        editor.runnable_run_state(key, cx),
                         cx,
                     );
 
@@ -1776,12 +1991,13 @@ impl EditorElement {
             None
         };
         let relative_rows = self.calculate_relative_line_numbers(snapshot, &rows, relative_to);
+        const SOFT_WRAP_INDICATOR: &str = "↪";
+        let show_soft_wrap_indicator = editor.show_soft_wrap_indicator(cx);
         let mut line_number = String::new();
         buffer_rows
             .into_iter()
             .enumerate()
             .map(|(ix, multibuffer_row)| {
-                let multibuffer_row = multibuffer_row?;
                 let display_row = DisplayRow(rows.start.0 + ix as u32);
                 let color = if active_rows.contains_key(&display_row) {
                     cx.theme().colors().editor_active_line_number
@@ -1789,11 +2005,18 @@ impl EditorElement {
                     cx.theme().colors().editor_line_number
                 };
                 line_number.clear();
-                let default_number = multibuffer_row.0 + 1;
-                let number = relative_rows
-                    .get(&DisplayRow(ix as u32 + rows.start.0))
-                    .unwrap_or(&default_number);
-                write!(&mut line_number, "{number}").unwrap();
+                if let Some(multibuffer_row) = multibuffer_row {
+                    let default_number = multibuffer_row.0 + 1;
+                    let number = relative_rows
+                        .get(&DisplayRow(ix as u32 + rows.start.0))
+                        .unwrap_or(&default_number);
+                    write!(&mut line_number, "{number}").unwrap();
+                } else if show_soft_wrap_indicator && !snapshot.is_block_line(display_row) {
+                    // Rows without a buffer row are either blocks or soft-wrapped lines.
+                    line_number.push_str(SOFT_WRAP_INDICATOR);
+                } else {
+                    return None;
+                }
                 let run = TextRun {
                     len: line_number.len(),
                     font: self.style.text.font(),
@@ -2008,6 +2231,7 @@ impl EditorElement {
                 show_excerpt_controls,
                 ..
             } => {
+                let show_buffer_header_controls = self.editor.read(cx).show_buffer_header_controls;
                 let include_root = self
                     .editor
                     .read(cx)
@@ -2095,22 +2319,30 @@ impl EditorElement {
                                 .justify_between()
                                 .hover(|style| style.bg(cx.theme().colors().element_hover))
                                 .child(
-                                    h_flex().gap_3().child(
-                                        h_flex()
-                                            .gap_2()
-                                            .child(
-                                                filename
-                                                    .map(SharedString::from)
-                                                    .unwrap_or_else(|| "untitled".into()),
-                                            )
-                                            .when_some(parent_path, |then, path| {
-                                                then.child(
-                                                    div()
-                                                        .child(path)
-                                                        .text_color(cx.theme().colors().text_muted),
+                                    h_flex()
+                                        .gap_3()
+                                        .child(
+                                            h_flex()
+                                                .gap_2()
+                                                .child(
+                                                    filename
+                                                        .map(SharedString::from)
+                                                        .unwrap_or_else(|| "untitled".into()),
                                                 )
-                                            }),
-                                    ),
+                                                .when_some(parent_path, |then, path| {
+                                                    then.child(
+                                                        div().child(path).text_color(
+                                                            cx.theme().colors().text_muted,
+                                                        ),
+                                                    )
+                                                }),
+                                        )
+                                        .when(show_buffer_header_controls, |el| {
+                                            el.child(self.render_buffer_header_controls(
+                                                buffer.remote_id(),
+                                                cx,
+                                            ))
+                                        }),
                                 )
                                 .when_some(jump_data.clone(), |el, jump_data| {
                                     el.child(Icon::new(IconName::ArrowUpRight))
@@ -3402,6 +3634,7 @@ impl EditorElement {
                 self.paint_redactions(layout, cx);
                 self.paint_cursors(layout, cx);
                 self.paint_inline_blame(layout, cx);
+                self.paint_inline_diagnostics(layout, cx);
                 cx.with_element_namespace("crease_trailers", |cx| {
                     for trailer in layout.crease_trailers.iter_mut().flatten() {
                         trailer.element.paint(cx);
@@ -3893,6 +4126,14 @@ impl EditorElement {
         }
     }
 
+    fn paint_inline_diagnostics(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        cx.paint_layer(layout.text_hitbox.bounds, |cx| {
+            for inline_diagnostic in layout.inline_diagnostics.values_mut() {
+                inline_diagnostic.element.paint(cx);
+            }
+        })
+    }
+
     fn paint_blocks(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         for mut block in layout.blocks.drain(..) {
             block.element.paint(cx);
@@ -3918,6 +4159,17 @@ impl EditorElement {
 
             move |event: &ScrollWheelEvent, phase, cx| {
                 if phase == DispatchPhase::Bubble && hitbox.is_hovered(cx) {
+                    if event.modifiers.control {
+                        // Zoom the editor by a pixel of font size for each line scrolled.
+                        let line_height = position_map.line_height;
+                        let font_size_delta = event.delta.pixel_delta(line_height).y / line_height;
+                        editor.update(cx, |editor, cx| {
+                            editor.adjust_font_size(px(font_size_delta), cx)
+                        });
+                        cx.stop_propagation();
+                        return;
+                    }
+
                     delta = delta.coalesce(event.delta);
                     editor.update(cx, |editor, cx| {
                         let position_map: &PositionMap = &position_map;
@@ -4036,13 +4288,7 @@ impl EditorElement {
                         if event.pressed_button == Some(MouseButton::Left)
                             || event.pressed_button == Some(MouseButton::Middle)
                         {
-                            Self::mouse_dragged(
-                                editor,
-                                event,
-                                &position_map,
-                                text_hitbox.bounds,
-                                cx,
-                            )
+                            Self::mouse_dragged(editor, event, &position_map, &text_hitbox, cx)
                         }
 
                         Self::mouse_moved(
@@ -5001,7 +5247,10 @@ impl Element for EditorElement {
                                 }
                             };
 
-                            if editor.set_wrap_width(wrap_width, cx) {
+                            let hanging_indent = editor.soft_wrap_hanging_indent(cx);
+                            let hanging_indent_changed =
+                                editor.set_wrap_hanging_indent(hanging_indent, cx);
+                            if editor.set_wrap_width(wrap_width, cx) || hanging_indent_changed {
                                 editor.snapshot(cx)
                             } else {
                                 snapshot
@@ -5094,11 +5343,17 @@ impl Element for EditorElement {
                     let highlighted_rows = self
                         .editor
                         .update(cx, |editor, cx| editor.highlighted_display_rows(cx));
-                    let highlighted_ranges = self.editor.read(cx).background_highlights_in_range(
-                        start_anchor..end_anchor,
+                    let mut highlighted_ranges =
+                        self.editor.read(cx).background_highlights_in_range(
+                            start_anchor..end_anchor,
+                            &snapshot.display_snapshot,
+                            cx.theme().colors(),
+                        );
+                    highlighted_ranges.extend(self.editor.read(cx).trailing_whitespace_highlights(
+                        &buffer_rows,
                         &snapshot.display_snapshot,
-                        cx.theme().colors(),
-                    );
+                        cx,
+                    ));
                     let highlighted_gutter_ranges =
                         self.editor.read(cx).gutter_highlights_in_range(
                             start_anchor..end_anchor,
@@ -5255,6 +5510,19 @@ impl Element for EditorElement {
                         )
                     });
 
+                    let inline_diagnostics = self.layout_inline_diagnostics(
+                        &buffer_rows,
+                        start_row,
+                        &line_layouts,
+                        &crease_trailers,
+                        &snapshot,
+                        em_width,
+                        content_origin,
+                        scroll_pixel_position,
+                        line_height,
+                        cx,
+                    );
+
                     let mut inline_blame = None;
                     if let Some(newest_selection_head) = newest_selection_head {
                         let display_row = newest_selection_head.row();
@@ -5267,6 +5535,7 @@ impl Element for EditorElement {
                                 &snapshot.display_snapshot,
                                 line_layout,
                                 crease_trailer_layout,
+                                inline_diagnostics.get(&display_row),
                                 em_width,
                                 content_origin,
                                 scroll_pixel_position,
@@ -5588,6 +5857,7 @@ impl Element for EditorElement {
                         line_numbers,
                         blamed_display_rows,
                         inline_blame,
+                        inline_diagnostics,
                         blocks,
                         cursors,
                         visible_cursors,
@@ -5727,6 +5997,7 @@ pub struct EditorLayout {
     display_hunks: Vec<(DisplayDiffHunk, Option<Hitbox>)>,
     blamed_display_rows: Option<Vec<AnyElement>>,
     inline_blame: Option<AnyElement>,
+    inline_diagnostics: BTreeMap<DisplayRow, InlineDiagnosticLayout>,
     blocks: Vec<BlockLayout>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     highlighted_gutter_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
@@ -5867,6 +6138,11 @@ struct CreaseTrailerLayout {
     bounds: Bounds<Pixels>,
 }
 
+struct InlineDiagnosticLayout {
+    element: AnyElement,
+    end_x: Pixels,
+}
+
 struct PositionMap {
     size: Size<Pixels>,
     line_height: Pixels,
@@ -6323,6 +6599,54 @@ mod tests {
         assert_eq!(relative_rows[&DisplayRow(2)], 3);
     }
 
+    #[gpui::test]
+    fn test_shape_soft_wrap_indicators(cx: &mut TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.soft_wrap_indicator = Some(true);
+        });
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple("one two three four five six\nseven", cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        let element = EditorElement::new(&editor, style);
+        let snapshot = window
+            .update(cx, |editor, cx| {
+                editor.set_wrap_width(Some(140.0.into()), cx);
+                editor.snapshot(cx)
+            })
+            .unwrap();
+        let buffer_rows = snapshot.buffer_rows(DisplayRow(0)).collect::<Vec<_>>();
+        assert!(
+            buffer_rows.len() > 2,
+            "the first line should be soft-wrapped"
+        );
+
+        let layouts = cx
+            .update_window(*window, |_, cx| {
+                element.layout_line_numbers(
+                    DisplayRow(0)..DisplayRow(buffer_rows.len() as u32),
+                    buffer_rows.iter().copied(),
+                    &Default::default(),
+                    Some(DisplayPoint::new(DisplayRow(0), 0)),
+                    &snapshot,
+                    cx,
+                )
+            })
+            .unwrap();
+        let labels = layouts
+            .iter()
+            .map(|line| line.as_ref().map(|line| line.text.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(labels.first(), Some(&Some("1".to_string())));
+        assert_eq!(labels.last(), Some(&Some("2".to_string())));
+        assert!(labels[1..labels.len() - 1]
+            .iter()
+            .all(|label| label.as_deref() == Some("↪")));
+    }
+
     #[gpui::test]
     async fn test_vim_visual_selections(cx: &mut TestAppContext) {
         init_test(cx, |_| {});
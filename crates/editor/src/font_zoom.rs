@@ -0,0 +1,70 @@
+use gpui::{px, AppContext, Pixels, ViewContext};
+use settings::Settings;
+use theme::ThemeSettings;
+use util::ResultExt;
+use workspace::{ItemId, WorkspaceId};
+
+use crate::{
+    persistence::DB, DecreaseFontSize, Editor, EditorMode, IncreaseFontSize, ResetFontSize,
+};
+
+const MIN_FONT_SIZE: Pixels = px(6.0);
+
+impl Editor {
+    /// The size of this editor's buffer font, which is the global buffer font size
+    /// plus this editor's own zoom.
+    pub fn font_size(&self, cx: &AppContext) -> Pixels {
+        (ThemeSettings::get_global(cx).buffer_font_size(cx) + self.font_size_adjustment)
+            .max(MIN_FONT_SIZE)
+    }
+
+    pub fn increase_font_size(&mut self, _: &IncreaseFontSize, cx: &mut ViewContext<Self>) {
+        self.adjust_font_size(px(1.), cx);
+    }
+
+    pub fn decrease_font_size(&mut self, _: &DecreaseFontSize, cx: &mut ViewContext<Self>) {
+        self.adjust_font_size(px(-1.), cx);
+    }
+
+    pub fn reset_font_size(&mut self, _: &ResetFontSize, cx: &mut ViewContext<Self>) {
+        self.set_font_size_adjustment(Pixels::ZERO, cx);
+    }
+
+    pub(crate) fn adjust_font_size(&mut self, delta: Pixels, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let base_size = ThemeSettings::get_global(cx).buffer_font_size(cx);
+        let font_size = (self.font_size(cx) + delta).max(MIN_FONT_SIZE);
+        self.set_font_size_adjustment(font_size - base_size, cx);
+    }
+
+    fn set_font_size_adjustment(&mut self, adjustment: Pixels, cx: &mut ViewContext<Self>) {
+        if self.font_size_adjustment == adjustment {
+            return;
+        }
+        self.font_size_adjustment = adjustment;
+        cx.notify();
+
+        if let Some(workspace_id) = self.workspace.as_ref().and_then(|workspace| workspace.1) {
+            let item_id = cx.view().entity_id().as_u64() as ItemId;
+            cx.foreground_executor()
+                .spawn(async move {
+                    DB.save_font_size_adjustment(item_id, workspace_id, adjustment.0)
+                        .await
+                        .log_err()
+                })
+                .detach();
+        }
+    }
+
+    pub(crate) fn read_font_size_adjustment_from_db(
+        &mut self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+    ) {
+        if let Ok(Some(adjustment)) = DB.get_font_size_adjustment(item_id, workspace_id) {
+            self.font_size_adjustment = px(adjustment);
+        }
+    }
+}
@@ -33,9 +33,15 @@ pub fn refresh_matching_bracket_highlights(editor: &mut Editor, cx: &mut ViewCon
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use crate::{
+        editor_tests::init_test,
+        test::{
+            editor_lsp_test_context::EditorLspTestContext, editor_test_context::EditorTestContext,
+        },
+    };
     use indoc::indoc;
     use language::{BracketPair, BracketPairConfig, Language, LanguageConfig, LanguageMatcher};
+    use std::sync::Arc;
 
     #[gpui::test]
     async fn test_matching_bracket_highlights(cx: &mut gpui::TestAppContext) {
@@ -140,4 +146,45 @@ mod tests {
             }
         "#});
     }
+
+    #[gpui::test]
+    async fn test_matching_keyword_bracket_highlights(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state(indoc! {r#"
+            fn main() {
+                iˇf x {
+                    y();
+                }
+            }
+        "#});
+        cx.assert_editor_background_highlights::<MatchingBracketHighlight>(indoc! {r#"
+            fn main() {
+                if x {
+                    y();
+                }
+            }
+        "#});
+
+        // Brackets are highlighted once the buffer is parsed, without moving the cursor.
+        let language = Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_brackets_query(indoc! {r#"
+                ("{" @open "}" @close)
+                (if_expression "if" @open (block "}" @close))
+                "#})
+        .unwrap();
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(Arc::new(language)), cx));
+        cx.executor().run_until_parked();
+        cx.assert_editor_background_highlights::<MatchingBracketHighlight>(indoc! {r#"
+            fn main() {
+                «if» x {
+                    y();
+                «}»
+            }
+        "#});
+    }
 }
@@ -1180,6 +1180,7 @@ mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
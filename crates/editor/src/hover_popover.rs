@@ -1334,6 +1334,7 @@ mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
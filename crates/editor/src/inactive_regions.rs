@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+use gpui::{HighlightStyle, ViewContext};
+use language::{language_settings::language_settings, Anchor, BufferSnapshot, ToOffset};
+use settings::Settings;
+use theme::{ActiveTheme, ThemeSettings};
+
+use crate::{Editor, EditorMode};
+
+enum InactiveRegionHighlight {}
+
+impl Editor {
+    /// Dims the code that language servers reported as inactive, for buffers whose language
+    /// settings enable it.
+    pub(crate) fn refresh_inactive_regions(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_highlights::<InactiveRegionHighlight>(cx);
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+
+        let project = project.read(cx);
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut ranges = Vec::new();
+        for buffer_handle in multi_buffer.all_buffers() {
+            let buffer = buffer_handle.read(cx);
+            if !language_settings(buffer.language(), buffer.file(), cx).dim_inactive_regions {
+                continue;
+            }
+            let regions = project.inactive_regions(buffer);
+            if regions.is_empty() {
+                continue;
+            }
+            let buffer_snapshot = buffer.snapshot();
+            let regions = regions
+                .into_iter()
+                .filter(|region| !is_faded_by_diagnostics(region, &buffer_snapshot))
+                .collect::<Vec<_>>();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer_handle, cx)
+            {
+                let context = excerpt_range.context;
+                for region in &regions {
+                    if region.end.cmp(&context.start, buffer).is_le()
+                        || region.start.cmp(&context.end, buffer).is_ge()
+                    {
+                        continue;
+                    }
+                    let start = if region.start.cmp(&context.start, buffer).is_lt() {
+                        context.start
+                    } else {
+                        region.start
+                    };
+                    let end = if region.end.cmp(&context.end, buffer).is_gt() {
+                        context.end
+                    } else {
+                        region.end
+                    };
+                    let Some(start) = snapshot.anchor_in_excerpt(excerpt_id, start) else {
+                        continue;
+                    };
+                    let Some(end) = snapshot.anchor_in_excerpt(excerpt_id, end) else {
+                        continue;
+                    };
+                    ranges.push(start..end);
+                }
+            }
+        }
+        if ranges.is_empty() {
+            return;
+        }
+        ranges.sort_by(|a, b| a.start.cmp(&b.start, &snapshot));
+
+        let style = HighlightStyle {
+            background_color: Some(cx.theme().colors().editor_inactive_code_background),
+            fade_out: Some(ThemeSettings::get_global(cx).unnecessary_code_fade),
+            ..HighlightStyle::default()
+        };
+        self.highlight_text::<InactiveRegionHighlight>(ranges, style, cx);
+    }
+}
+
+/// Whether a diagnostic for unnecessary code, such as the `inactive-code` diagnostics of
+/// rust-analyzer, already fades the whole region.
+fn is_faded_by_diagnostics(region: &Range<Anchor>, buffer: &BufferSnapshot) -> bool {
+    let region = region.start.to_offset(buffer)..region.end.to_offset(buffer);
+    buffer
+        .diagnostics_in_range::<_, usize>(region.clone(), false)
+        .any(|entry| {
+            entry.diagnostic.is_unnecessary
+                && entry.range.start <= region.start
+                && entry.range.end >= region.end
+        })
+}
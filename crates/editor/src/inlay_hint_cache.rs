@@ -1268,7 +1268,7 @@ pub mod tests {
         ExcerptRange,
     };
     use futures::StreamExt;
-    use gpui::{Context, SemanticVersion, TestAppContext, WindowHandle};
+    use gpui::{Context, Modifiers, SemanticVersion, TestAppContext, WindowHandle};
     use itertools::Itertools;
     use language::{
         language_settings::AllLanguageSettingsContent, Capability, FakeLspAdapter, Language,
@@ -1296,6 +1296,7 @@ pub mod tests {
                 show_type_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Type)),
                 show_parameter_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: allowed_hint_kinds.contains(&None),
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -1428,6 +1429,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -1547,6 +1549,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -1777,6 +1780,7 @@ pub mod tests {
                 show_type_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Type)),
                 show_parameter_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: allowed_hint_kinds.contains(&None),
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -1941,6 +1945,7 @@ pub mod tests {
                     show_parameter_hints: new_allowed_hint_kinds
                         .contains(&Some(InlayHintKind::Parameter)),
                     show_other_hints: new_allowed_hint_kinds.contains(&None),
+                    toggle_on_modifiers_press: None,
                 })
             });
             cx.executor().run_until_parked();
@@ -1987,6 +1992,7 @@ pub mod tests {
                 show_parameter_hints: another_allowed_hint_kinds
                     .contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: another_allowed_hint_kinds.contains(&None),
+                toggle_on_modifiers_press: None,
             })
         });
         cx.executor().run_until_parked();
@@ -2047,6 +2053,7 @@ pub mod tests {
                 show_parameter_hints: final_allowed_hint_kinds
                     .contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: final_allowed_hint_kinds.contains(&None),
+                toggle_on_modifiers_press: None,
             })
         });
         cx.executor().run_until_parked();
@@ -2122,6 +2129,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -2256,6 +2264,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -2551,6 +2560,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -2902,6 +2912,7 @@ pub mod tests {
                 show_type_hints: false,
                 show_parameter_hints: false,
                 show_other_hints: false,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -3096,6 +3107,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
         cx.executor().run_until_parked();
@@ -3131,6 +3143,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -3225,6 +3238,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
 
@@ -3305,6 +3319,7 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                toggle_on_modifiers_press: None,
             })
         });
         cx.executor().run_until_parked();
@@ -3356,6 +3371,92 @@ pub mod tests {
         }).unwrap();
     }
 
+    #[gpui::test]
+    async fn test_toggle_inlay_hints_on_modifiers_press(cx: &mut gpui::TestAppContext) {
+        let toggle_modifiers = Modifiers {
+            control: true,
+            alt: true,
+            ..Modifiers::default()
+        };
+        init_test(cx, |settings| {
+            settings.defaults.inlay_hints = Some(InlayHintSettings {
+                enabled: false,
+                edit_debounce_ms: 0,
+                scroll_debounce_ms: 0,
+                show_type_hints: true,
+                show_parameter_hints: true,
+                show_other_hints: true,
+                toggle_on_modifiers_press: Some(toggle_modifiers),
+            })
+        });
+
+        let (_, editor, fake_server) = prepare_test_objects(cx).await;
+        fake_server.handle_request::<lsp::request::InlayHintRequest, _, _>(
+            move |_, _| async move {
+                Ok(Some(vec![lsp::InlayHint {
+                    position: lsp::Position::new(0, 1),
+                    label: lsp::InlayHintLabel::String("1".to_string()),
+                    kind: None,
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: None,
+                    data: None,
+                }]))
+            },
+        );
+
+        editor
+            .update(cx, |editor, cx| {
+                cx.focus(&editor.focus_handle);
+                editor.toggle_inlay_hints_on_modifiers(
+                    Modifiers {
+                        control: true,
+                        ..Modifiers::default()
+                    },
+                    cx,
+                );
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        editor
+            .update(cx, |editor, cx| {
+                assert!(
+                    visible_hint_labels(editor, cx).is_empty(),
+                    "Should not toggle hints when only some of the modifiers are pressed"
+                );
+                editor.toggle_inlay_hints_on_modifiers(toggle_modifiers, cx);
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        editor
+            .update(cx, |editor, cx| {
+                assert_eq!(
+                    vec!["1".to_string()],
+                    visible_hint_labels(editor, cx),
+                    "Should show hints while the modifiers are held down"
+                );
+                editor.toggle_inlay_hints_on_modifiers(
+                    Modifiers {
+                        alt: true,
+                        ..Modifiers::default()
+                    },
+                    cx,
+                );
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        editor
+            .update(cx, |editor, cx| {
+                assert!(
+                    visible_hint_labels(editor, cx).is_empty(),
+                    "Should hide hints again after releasing a modifier"
+                );
+                assert!(!editor.inlay_hints_enabled());
+            })
+            .unwrap();
+    }
+
     pub(crate) fn init_test(cx: &mut TestAppContext, f: impl Fn(&mut AllLanguageSettingsContent)) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
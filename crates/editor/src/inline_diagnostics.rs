@@ -0,0 +1,69 @@
+use gpui::{AppContext, SharedString};
+use lsp::DiagnosticSeverity;
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
+use settings::Settings;
+use text::Point;
+
+use crate::{editor_settings::InlineDiagnosticsSeverity, Editor, EditorMode, EditorSettings};
+
+/// A diagnostic message shown at the end of the line the diagnostic starts on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct InlineDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub group_id: usize,
+    pub message: SharedString,
+}
+
+impl InlineDiagnosticsSeverity {
+    fn includes(&self, severity: DiagnosticSeverity) -> bool {
+        let min_severity = match self {
+            InlineDiagnosticsSeverity::Error => DiagnosticSeverity::ERROR,
+            InlineDiagnosticsSeverity::Warning => DiagnosticSeverity::WARNING,
+            InlineDiagnosticsSeverity::Info => DiagnosticSeverity::INFORMATION,
+            InlineDiagnosticsSeverity::Hint => DiagnosticSeverity::HINT,
+        };
+        severity <= min_severity
+    }
+}
+
+impl Editor {
+    /// Returns the diagnostic to show at the end of each of the given rows, when inline
+    /// diagnostics are enabled. Of the primary diagnostics starting on a row, the most
+    /// severe one is shown, with only the first line of its message.
+    pub(crate) fn inline_diagnostics(
+        &self,
+        buffer_rows: &[Option<MultiBufferRow>],
+        buffer: &MultiBufferSnapshot,
+        cx: &AppContext,
+    ) -> Vec<Option<InlineDiagnostic>> {
+        let settings = EditorSettings::get_global(cx).inline_diagnostics;
+        if !settings.enabled || self.mode != EditorMode::Full {
+            return vec![None; buffer_rows.len()];
+        }
+
+        buffer_rows
+            .iter()
+            .map(|row| {
+                let row = (*row)?;
+                let line_start = Point::new(row.0, 0);
+                let line_end = Point::new(row.0, buffer.line_len(row));
+                let entry = buffer
+                    .diagnostics_in_range::<_, Point>(line_start..line_end, false)
+                    .filter(|entry| {
+                        entry.diagnostic.is_primary
+                            && entry.range.start.row == row.0
+                            && settings.min_severity.includes(entry.diagnostic.severity)
+                    })
+                    .min_by_key(|entry| entry.diagnostic.severity)?;
+
+                let message = entry.diagnostic.message.lines().next().unwrap_or_default();
+                Some(InlineDiagnostic {
+                    severity: entry.diagnostic.severity,
+                    group_id: entry.diagnostic.group_id,
+                    message: util::truncate_and_trailoff(message.trim(), settings.max_length)
+                        .into(),
+                })
+            })
+            .collect()
+    }
+}
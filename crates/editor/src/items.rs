@@ -16,15 +16,17 @@ use gpui::{
     VisualContext, WeakView, WindowContext,
 };
 use language::{
-    proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, CharKind, Point, SelectionGoal,
+    language_settings::{language_settings, AutosaveSetting},
+    proto::serialize_anchor as serialize_text_anchor,
+    Bias, Buffer, CharKind, Point, SelectionGoal,
 };
 use multi_buffer::AnchorRangeExt;
 use project::{
     project_settings::ProjectSettings, search::SearchQuery, FormatTrigger, Item as _, Project,
-    ProjectPath,
+    ProjectPath, WorktreeSettings,
 };
 use rpc::proto::{self, update_view, PeerId};
-use settings::Settings;
+use settings::{Settings, SettingsLocation};
 use workspace::item::{Dedup, ItemSettings, SerializableItem, TabContentParams};
 
 use std::{
@@ -35,6 +37,7 @@ use std::{
     ops::Range,
     path::Path,
     sync::Arc,
+    time::Duration,
 };
 use text::{BufferId, Selection};
 use theme::{Theme, ThemeSettings};
@@ -44,10 +47,12 @@ use workspace::item::{BreadcrumbText, FollowEvent};
 use workspace::{
     item::{FollowableItem, Item, ItemEvent, ProjectItem},
     searchable::{Direction, SearchEvent, SearchableItem, SearchableItemHandle},
-    ItemId, ItemNavHistory, Pane, ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
+    title_template, ItemId, ItemNavHistory, Pane, ToolbarItemLocation, ViewId, Workspace,
+    WorkspaceId,
 };
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
+const SERIALIZE_SELECTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
 
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
@@ -534,6 +539,20 @@ fn deserialize_anchor(buffer: &MultiBufferSnapshot, anchor: proto::EditorAnchor)
 impl Item for Editor {
     type Event = EditorEvent;
 
+    fn navigation_data(&self, cx: &mut ViewContext<Self>) -> Option<Box<dyn std::any::Any + Send>> {
+        let cursor_anchor = self.selections.newest_anchor().head();
+        Some(Box::new(self.navigation_data_at(
+            cursor_anchor,
+            Vec::new(),
+            cx,
+        )))
+    }
+
+    fn navigation_row(&self, data: &dyn std::any::Any) -> Option<u32> {
+        data.downcast_ref::<NavigationData>()
+            .map(|data| data.cursor_position.row)
+    }
+
     fn navigate(&mut self, data: Box<dyn std::any::Any>, cx: &mut ViewContext<Self>) -> bool {
         if let Ok(data) = data.downcast::<NavigationData>() {
             let newest_selection = self.selections.newest::<Point>(cx);
@@ -544,6 +563,20 @@ impl Item for Editor {
                 buffer.clip_point(data.cursor_position, Bias::Left)
             };
 
+            let selections = data
+                .selections
+                .iter()
+                .zip(&data.selection_positions)
+                .map(|(range, position)| {
+                    if buffer.can_resolve(&range.start) && buffer.can_resolve(&range.end) {
+                        range.start.to_point(&buffer)..range.end.to_point(&buffer)
+                    } else {
+                        buffer.clip_point(position.start, Bias::Left)
+                            ..buffer.clip_point(position.end, Bias::Left)
+                    }
+                })
+                .collect::<Vec<_>>();
+
             let mut scroll_anchor = data.scroll_anchor;
             if !buffer.can_resolve(&scroll_anchor.anchor) {
                 scroll_anchor.anchor = buffer.anchor_before(
@@ -553,13 +586,26 @@ impl Item for Editor {
 
             drop(buffer);
 
-            if newest_selection.head() == offset {
+            let unchanged = if selections.is_empty() {
+                newest_selection.head() == offset
+            } else {
+                self.selections
+                    .all::<Point>(cx)
+                    .iter()
+                    .map(|selection| selection.tail()..selection.head())
+                    .eq(selections.iter().cloned())
+            };
+            if unchanged {
                 false
             } else {
                 let nav_history = self.nav_history.take();
                 self.set_scroll_anchor(scroll_anchor, cx);
                 self.change_selections(Some(Autoscroll::fit()), cx, |s| {
-                    s.select_ranges([offset..offset])
+                    if selections.is_empty() {
+                        s.select_ranges([offset..offset])
+                    } else {
+                        s.select_ranges(selections)
+                    }
                 });
                 self.nav_history = nav_history;
                 true
@@ -634,13 +680,31 @@ impl Item for Editor {
             Some(util::truncate_and_trailoff(&description, MAX_TAB_TITLE_LEN))
         });
 
+        let title = ItemSettings::get_global(cx)
+            .title
+            .as_deref()
+            .and_then(|template| {
+                let project = self.project.as_ref()?.read(cx);
+                let buffer = self.buffer().read(cx).as_singleton()?;
+                let project_path = buffer.read(cx).project_path(cx)?;
+                let is_dirty = self.is_dirty(cx);
+                let title = title_template::expand_title_template(template, |name| {
+                    title_template::title_variable(name, project, Some(&project_path), is_dirty, cx)
+                });
+                (!title.is_empty()).then_some(title)
+            })
+            .unwrap_or_else(|| self.title(cx).to_string());
+
         h_flex()
             .gap_2()
-            .child(
-                Label::new(self.title(cx).to_string())
-                    .color(label_color)
-                    .italic(params.preview),
-            )
+            .child(Label::new(title).color(label_color).italic(params.preview))
+            .when(self.read_only(cx), |this| {
+                this.child(
+                    Icon::new(IconName::FileLock)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
             .when_some(description, |this, description| {
                 this.child(
                     Label::new(description)
@@ -687,8 +751,14 @@ impl Item for Editor {
     }
 
     fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
-        let selection = self.selections.newest_anchor();
-        self.push_to_nav_history(selection.head(), None, cx);
+        let cursor_anchor = self.selections.newest_anchor().head();
+        let selections = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .map(|selection| selection.tail()..selection.head())
+            .collect();
+        self.push_to_nav_history(cursor_anchor, selections, None, cx);
     }
 
     fn workspace_deactivated(&mut self, cx: &mut ViewContext<Self>) {
@@ -733,6 +803,9 @@ impl Item for Editor {
                 project
                     .update(&mut cx, |project, cx| project.save_buffers(buffers, cx))?
                     .await?;
+                this.update(&mut cx, |editor, cx| {
+                    editor.record_persistent_undo_version(cx)
+                })?;
             } else {
                 // For multi-buffers, only format and save the buffers with changes.
                 // For clean buffers, we simulate saving by calling `Buffer::did_save`,
@@ -904,6 +977,16 @@ impl Item for Editor {
     fn preserve_preview(&self, cx: &AppContext) -> bool {
         self.buffer.read(cx).preserve_preview(cx)
     }
+
+    fn autosave_setting(&self, cx: &AppContext) -> Option<AutosaveSetting> {
+        let buffer = self.buffer.read(cx).as_singleton()?;
+        let buffer = buffer.read(cx);
+        Some(language_settings(buffer.language(), buffer.file(), cx).autosave)
+    }
+
+    fn set_zen_mode(&mut self, zen_mode: bool, cx: &mut ViewContext<Self>) {
+        Editor::set_zen_mode(self, zen_mode, cx)
+    }
 }
 
 impl SerializableItem for Editor {
@@ -1051,7 +1134,9 @@ impl SerializableItem for Editor {
                 cx.new_view(|cx| {
                     let mut editor = Editor::for_buffer(buffer, Some(project), cx);
 
+                    editor.read_selections_from_db(item_id, workspace_id, cx);
                     editor.read_scroll_position_from_db(item_id, workspace_id, cx);
+                    editor.read_font_size_adjustment_from_db(item_id, workspace_id);
                     editor
                 })
             })
@@ -1126,6 +1211,76 @@ impl SerializableItem for Editor {
     }
 }
 
+/// The `[row, column]` points of a selection's tail and head.
+type SerializedSelection = ([u32; 2], [u32; 2]);
+
+impl Editor {
+    /// Stores the selections of an editor for a single buffer, so that its cursors are
+    /// restored along with the editor.
+    pub(crate) fn serialize_selections(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(workspace_id) = self.workspace.as_ref().and_then(|workspace| workspace.1) else {
+            return;
+        };
+        if self.buffer.read(cx).as_singleton().is_none() {
+            return;
+        }
+
+        let item_id = cx.view().entity_id().as_u64() as ItemId;
+        let selections = self
+            .selections
+            .all::<Point>(cx)
+            .into_iter()
+            .map(|selection| {
+                let (tail, head) = (selection.tail(), selection.head());
+                ([tail.row, tail.column], [head.row, head.column])
+            })
+            .collect::<Vec<SerializedSelection>>();
+        self.serialize_selections = cx.spawn(|_, cx| async move {
+            cx.background_executor()
+                .timer(SERIALIZE_SELECTIONS_DEBOUNCE_TIMEOUT)
+                .await;
+            if let Some(selections) = serde_json::to_string(&selections).log_err() {
+                DB.save_selections(item_id, workspace_id, selections)
+                    .await
+                    .log_err();
+            }
+        });
+    }
+
+    pub(crate) fn read_selections_from_db(
+        &mut self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(selections) = DB
+            .get_selections(item_id, workspace_id)
+            .log_err()
+            .flatten()
+            .flatten()
+            .and_then(|selections| {
+                serde_json::from_str::<Vec<SerializedSelection>>(&selections).log_err()
+            })
+        else {
+            return;
+        };
+        if selections.is_empty() {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let ranges = selections
+            .into_iter()
+            .map(|([tail_row, tail_column], [head_row, head_column])| {
+                let tail = snapshot.clip_point(Point::new(tail_row, tail_column), Bias::Left);
+                let head = snapshot.clip_point(Point::new(head_row, head_column), Bias::Left);
+                tail..head
+            })
+            .collect::<Vec<_>>();
+        self.change_selections(None, cx, |selections| selections.select_ranges(ranges));
+    }
+}
+
 impl ProjectItem for Editor {
     type Item = Buffer;
 
@@ -1134,10 +1289,33 @@ impl ProjectItem for Editor {
         buffer: Model<Buffer>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        Self::for_buffer(buffer, Some(project), cx)
+        let read_only = is_read_only_file(&buffer, cx);
+        let mut editor = Self::for_buffer(buffer, Some(project), cx);
+        if read_only {
+            editor.set_read_only(true);
+        }
+        editor.load_persistent_undo_history(cx);
+        editor
     }
 }
 
+/// Whether the buffer's file matches the `read_only_files` setting, by its path within
+/// the project or by its absolute path, so that dependency sources can be matched too.
+fn is_read_only_file(buffer: &Model<Buffer>, cx: &AppContext) -> bool {
+    let Some(file) = project::File::from_dyn(buffer.read(cx).file()) else {
+        return false;
+    };
+    let settings = WorktreeSettings::get(
+        Some(SettingsLocation {
+            worktree_id: file.worktree_id(cx),
+            path: &file.path,
+        }),
+        cx,
+    );
+    let abs_path = file.worktree.read(cx).abs_path().join(&file.path);
+    settings.is_path_read_only(&file.path) || settings.is_path_read_only(&abs_path)
+}
+
 impl EventEmitter<SearchEvent> for Editor {}
 
 pub(crate) enum BufferSearchHighlights {}
@@ -1173,6 +1351,17 @@ impl SearchableItem for Editor {
 
         let ranges = self.selections.disjoint_anchor_ranges();
         if ranges.iter().any(|range| range.start != range.end) {
+            // Unlike the selections, the ranges grow to contain text inserted at their
+            // edges, so that replacing a match at the start or end of a range keeps the
+            // replacement within it.
+            let snapshot = self.buffer.read(cx).snapshot(cx);
+            let ranges = ranges
+                .into_iter()
+                .map(|range| {
+                    let range = range.to_offset(&snapshot);
+                    snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)
+                })
+                .collect::<Vec<_>>();
             self.set_search_within_ranges(&ranges, cx);
         } else if let Some(previous_search_ranges) = self.previous_search_ranges.take() {
             self.set_search_within_ranges(&previous_search_ranges, cx)
@@ -1231,6 +1420,19 @@ impl SearchableItem for Editor {
         }
         self.change_selections(None, cx, |s| s.select_ranges(ranges));
     }
+
+    fn add_match_to_selections(
+        &mut self,
+        index: usize,
+        matches: &[Range<Anchor>],
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.unfold_ranges([matches[index].clone()], false, true, cx);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let range = self.range_for_match(&matches[index]).to_offset(&snapshot);
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| s.insert_range(range));
+    }
+
     fn replace(
         &mut self,
         identifier: &Self::Match,
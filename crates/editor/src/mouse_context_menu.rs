@@ -1,14 +1,15 @@
 use std::ops::Range;
 
+use crate::spell_check::SpellingSuggestions;
 use crate::GoToDeclaration;
 use crate::{
     selections_collection::SelectionsCollection, Copy, CopyPermalinkToLine, Cut, DisplayPoint,
-    DisplaySnapshot, Editor, EditorMode, FindAllReferences, GoToDefinition, GoToImplementation,
-    GoToTypeDefinition, Paste, Rename, RevealInFileManager, SelectMode, ToDisplayPoint,
-    ToggleCodeActions,
+    DisplaySnapshot, Editor, EditorCodeAction, EditorMode, FindAllReferences, GoToDefinition,
+    GoToImplementation, GoToTypeDefinition, Paste, Rename, RevealInFileManager, SelectMode,
+    ToDisplayPoint, ToggleCodeActions,
 };
 use gpui::prelude::FluentBuilder;
-use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
+use gpui::{DismissEvent, FocusHandle, Pixels, Point, Subscription, View, ViewContext};
 use workspace::OpenInTerminal;
 
 #[derive(Debug)]
@@ -158,40 +159,43 @@ pub fn deploy_context_menu(
             });
         }
 
+        // Hierarchies are only offered where a language server can provide them, which
+        // also leaves them out of remote projects.
+        let editor_actions = editor
+            .project
+            .as_ref()
+            .zip(editor.buffer.read(cx).text_anchor_for_position(anchor, cx))
+            .map(|(project, (buffer, _))| {
+                EditorCodeAction::available(project.read(cx), buffer.read(cx), cx)
+            })
+            .unwrap_or_default();
         let focus = cx.focused();
-        ui::ContextMenu::build(cx, |menu, _cx| {
-            let builder = menu
-                .on_blur_subscription(Subscription::new(|| {}))
-                .action("Rename Symbol", Box::new(Rename))
-                .action("Go to Definition", Box::new(GoToDefinition))
-                .action("Go to Declaration", Box::new(GoToDeclaration))
-                .action("Go to Type Definition", Box::new(GoToTypeDefinition))
-                .action("Go to Implementation", Box::new(GoToImplementation))
-                .action("Find All References", Box::new(FindAllReferences))
-                .action(
-                    "Code Actions",
-                    Box::new(ToggleCodeActions {
-                        deployed_from_indicator: None,
-                    }),
-                )
-                .separator()
-                .action("Cut", Box::new(Cut))
-                .action("Copy", Box::new(Copy))
-                .action("Paste", Box::new(Paste))
-                .separator()
-                .when(cfg!(target_os = "macos"), |builder| {
-                    builder.action("Reveal in Finder", Box::new(RevealInFileManager))
-                })
-                .when(cfg!(not(target_os = "macos")), |builder| {
-                    builder.action("Reveal in File Manager", Box::new(RevealInFileManager))
-                })
-                .action("Open in Terminal", Box::new(OpenInTerminal))
-                .action("Copy Permalink", Box::new(CopyPermalinkToLine));
-            match focus {
-                Some(focus) => builder.context(focus),
-                None => builder,
-            }
-        })
+        if let Some(spelling_suggestions) = editor.spelling_suggestions_at(anchor, cx) {
+            // The menu is shown once the suggestions for the misspelled word are computed.
+            editor.spell_check.suggestions_task = Some(cx.spawn(|editor, mut cx| async move {
+                let spelling_suggestions = spelling_suggestions.await;
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        let context_menu = build_context_menu(
+                            Some(spelling_suggestions),
+                            editor_actions,
+                            focus,
+                            cx,
+                        );
+                        editor.mouse_context_menu = MouseContextMenu::pinned_to_editor(
+                            editor,
+                            source_anchor,
+                            position,
+                            context_menu,
+                            cx,
+                        );
+                        cx.notify();
+                    })
+                    .ok();
+            }));
+            return;
+        }
+        build_context_menu(None, editor_actions, focus, cx)
     };
 
     editor.mouse_context_menu =
@@ -199,6 +203,56 @@ pub fn deploy_context_menu(
     cx.notify();
 }
 
+fn build_context_menu(
+    spelling_suggestions: Option<SpellingSuggestions>,
+    editor_actions: Vec<EditorCodeAction>,
+    focus: Option<FocusHandle>,
+    cx: &mut ViewContext<Editor>,
+) -> View<ui::ContextMenu> {
+    ui::ContextMenu::build(cx, |menu, _cx| {
+        let menu = menu.on_blur_subscription(Subscription::new(|| {}));
+        let menu = match spelling_suggestions {
+            Some(spelling_suggestions) => spelling_suggestions.add_to_menu(menu),
+            None => menu,
+        };
+        let builder = menu
+            .action("Rename Symbol", Box::new(Rename))
+            .action("Go to Definition", Box::new(GoToDefinition))
+            .action("Go to Declaration", Box::new(GoToDeclaration))
+            .action("Go to Type Definition", Box::new(GoToTypeDefinition))
+            .action("Go to Implementation", Box::new(GoToImplementation))
+            .action("Find All References", Box::new(FindAllReferences))
+            .map(|builder| {
+                editor_actions.iter().fold(builder, |builder, action| {
+                    builder.action(action.label(), action.action())
+                })
+            })
+            .action(
+                "Code Actions",
+                Box::new(ToggleCodeActions {
+                    deployed_from_indicator: None,
+                }),
+            )
+            .separator()
+            .action("Cut", Box::new(Cut))
+            .action("Copy", Box::new(Copy))
+            .action("Paste", Box::new(Paste))
+            .separator()
+            .when(cfg!(target_os = "macos"), |builder| {
+                builder.action("Reveal in Finder", Box::new(RevealInFileManager))
+            })
+            .when(cfg!(not(target_os = "macos")), |builder| {
+                builder.action("Reveal in File Manager", Box::new(RevealInFileManager))
+            })
+            .action("Open in Terminal", Box::new(OpenInTerminal))
+            .action("Copy Permalink", Box::new(CopyPermalinkToLine));
+        match focus {
+            Some(focus) => builder.context(focus),
+            None => builder,
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
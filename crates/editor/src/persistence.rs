@@ -82,12 +82,21 @@ define_connection!(
     //   workspace_id: usize,
     //   path: Option<PathBuf>,
     //   scroll_top_row: usize,
+    //   scroll_top_column: usize,
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     //   content: Option<String>,
     //   language: Option<String>,
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
+    //   font_size_adjustment: f32,
+    //   selections: Option<String>, // JSON array of [tail, head] points
+    // )
+    //
+    // undo_histories(
+    //   path: PathBuf,
+    //   content_hash: String,
+    //   versions: String, // JSON array of previously saved file contents
     // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
@@ -136,6 +145,22 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN mtime_seconds INTEGER DEFAULT NULL;
             ALTER TABLE editors ADD COLUMN mtime_nanos INTEGER DEFAULT NULL;
         ),
+        sql! (
+            CREATE TABLE undo_histories(
+                path BLOB NOT NULL PRIMARY KEY,
+                content_hash TEXT NOT NULL,
+                versions TEXT NOT NULL
+            ) STRICT;
+        ),
+        sql! (
+            ALTER TABLE editors ADD COLUMN font_size_adjustment REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            ALTER TABLE editors ADD COLUMN selections TEXT DEFAULT NULL;
+        ),
+        sql! (
+            ALTER TABLE editors ADD COLUMN scroll_top_column INTEGER NOT NULL DEFAULT 0;
+        ),
         ];
 );
 
@@ -164,10 +189,10 @@ impl EditorDb {
         }
     }
 
-    // Returns the scroll top row, and offset
+    // Returns the scroll top row and column, and offset
     query! {
-        pub fn get_scroll_position(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<(u32, f32, f32)>> {
-            SELECT scroll_top_row, scroll_horizontal_offset, scroll_vertical_offset
+        pub fn get_scroll_position(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<(u32, u32, f32, f32)>> {
+            SELECT scroll_top_row, scroll_top_column, scroll_horizontal_offset, scroll_vertical_offset
             FROM editors
             WHERE item_id = ? AND workspace_id = ?
         }
@@ -178,18 +203,87 @@ impl EditorDb {
             item_id: ItemId,
             workspace_id: WorkspaceId,
             top_row: u32,
+            top_column: u32,
             vertical_offset: f32,
             horizontal_offset: f32
         ) -> Result<()> {
             UPDATE OR IGNORE editors
             SET
                 scroll_top_row = ?3,
-                scroll_horizontal_offset = ?4,
-                scroll_vertical_offset = ?5
+                scroll_top_column = ?4,
+                scroll_horizontal_offset = ?5,
+                scroll_vertical_offset = ?6
+            WHERE item_id = ?1 AND workspace_id = ?2
+        }
+    }
+
+    query! {
+        pub fn get_font_size_adjustment(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<f32>> {
+            SELECT font_size_adjustment
+            FROM editors
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_font_size_adjustment(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            font_size_adjustment: f32
+        ) -> Result<()> {
+            UPDATE OR IGNORE editors
+            SET font_size_adjustment = ?3
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
 
+    // Returns the JSON-encoded [tail, head] points of the editor's selections
+    query! {
+        pub fn get_selections(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<Option<String>>> {
+            SELECT selections
+            FROM editors
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_selections(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            selections: String
+        ) -> Result<()> {
+            UPDATE OR IGNORE editors
+            SET selections = ?3
+            WHERE item_id = ?1 AND workspace_id = ?2
+        }
+    }
+
+    // Returns the content hash of the last saved version of the file, and the
+    // JSON-encoded versions that preceded it
+    query! {
+        pub fn get_undo_history(path: PathBuf) -> Result<Option<(String, String)>> {
+            SELECT content_hash, versions
+            FROM undo_histories
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        pub async fn save_undo_history(
+            path: PathBuf,
+            content_hash: String,
+            versions: String
+        ) -> Result<()> {
+            INSERT INTO undo_histories
+                (path, content_hash, versions)
+            VALUES
+                (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                content_hash = ?2,
+                versions = ?3
+        }
+    }
+
     pub async fn delete_unloaded_items(
         &self,
         workspace: WorkspaceId,
@@ -298,4 +392,71 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_selections() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        assert_eq!(DB.get_selections(5678, workspace_id).unwrap(), None);
+
+        DB.save_serialized_editor(5678, workspace_id, SerializedEditor::default())
+            .await
+            .unwrap();
+        assert_eq!(DB.get_selections(5678, workspace_id).unwrap(), Some(None));
+
+        let selections = "[[[1,2],[3,4]]]".to_owned();
+        DB.save_selections(5678, workspace_id, selections.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_selections(5678, workspace_id).unwrap(),
+            Some(Some(selections))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_save_and_get_scroll_position() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        DB.save_serialized_editor(6789, workspace_id, SerializedEditor::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_scroll_position(6789, workspace_id).unwrap(),
+            Some((0, 0, 0., 0.))
+        );
+
+        // The column is kept, as a soft-wrapped row can start in the middle of a line.
+        DB.save_scroll_position(6789, workspace_id, 12, 80, 0., 0.5)
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_scroll_position(6789, workspace_id).unwrap(),
+            Some((12, 80, 0., 0.5))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_save_and_get_undo_history() {
+        let path = PathBuf::from("/undo/testing.txt");
+        assert_eq!(DB.get_undo_history(path.clone()).unwrap(), None);
+
+        DB.save_undo_history(path.clone(), "abc".to_owned(), "[\"one\"]".to_owned())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_undo_history(path.clone()).unwrap(),
+            Some(("abc".to_owned(), "[\"one\"]".to_owned()))
+        );
+
+        DB.save_undo_history(
+            path.clone(),
+            "def".to_owned(),
+            "[\"one\",\"two\"]".to_owned(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            DB.get_undo_history(path).unwrap(),
+            Some(("def".to_owned(), "[\"one\",\"two\"]".to_owned()))
+        );
+    }
 }
@@ -0,0 +1,268 @@
+//! Keeps previously saved versions of a file around after it is closed, so that
+//! `editor::Undo` can keep going back in time once the buffer's own undo history
+//! (which only lives as long as the buffer) is exhausted.
+//!
+//! Histories are keyed by the file's absolute path, and are only restored when the
+//! hash of the file's contents matches the version that was last saved by Zed.
+use std::{mem, path::PathBuf};
+
+use gpui::ViewContext;
+use settings::Settings;
+use sha2::{Digest, Sha256};
+use text::TransactionId;
+use util::ResultExt;
+
+use crate::{persistence::DB, Autoscroll, Editor, EditorSettings};
+
+pub(crate) struct PersistentUndoHistory {
+    abs_path: PathBuf,
+    /// The contents of the file when it was opened, or last saved.
+    last_saved_text: String,
+    /// The contents of the file at previous saves, oldest first.
+    versions: Vec<String>,
+    /// How many versions come before the buffer's undo history. Undoing past that history
+    /// restores the newest of them.
+    older_versions: usize,
+    /// The transactions that reapply the newer version after restoring one by undo, with
+    /// the index of the restored version. Undoing and redoing them moves between the two.
+    reapply_transactions: Vec<(TransactionId, usize)>,
+}
+
+fn content_hash(text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+impl Editor {
+    pub(crate) fn load_persistent_undo_history(&mut self, cx: &mut ViewContext<Self>) {
+        let settings = EditorSettings::get_global(cx).persistent_undo;
+        if !settings.enabled {
+            return;
+        }
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        if buffer.len() > settings.max_file_size {
+            return;
+        }
+        let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        let abs_path = file.abs_path(cx);
+        let text = buffer.text();
+
+        let history = cx.background_executor().spawn(async move {
+            let versions = DB
+                .get_undo_history(abs_path.clone())
+                .log_err()
+                .flatten()
+                .filter(|(saved_hash, _)| *saved_hash == content_hash(&text))
+                .and_then(|(_, versions)| serde_json::from_str(&versions).log_err())
+                .unwrap_or_default();
+            PersistentUndoHistory {
+                abs_path,
+                last_saved_text: text,
+                older_versions: versions.len(),
+                versions,
+                reapply_transactions: Vec::new(),
+            }
+        });
+        cx.spawn(|editor, mut cx| async move {
+            let history = history.await;
+            editor
+                .update(&mut cx, |editor, _| {
+                    editor.persistent_undo_history = Some(history);
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    pub(crate) fn record_persistent_undo_version(&mut self, cx: &mut ViewContext<Self>) {
+        let settings = EditorSettings::get_global(cx).persistent_undo;
+        let Some(history) = self.persistent_undo_history.as_mut() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let text = buffer.read(cx).text();
+        if text == history.last_saved_text {
+            return;
+        }
+
+        let previous_text = mem::replace(&mut history.last_saved_text, text.clone());
+        if text.len() > settings.max_file_size {
+            history.versions.clear();
+            history.reapply_transactions.clear();
+        } else {
+            history.versions.push(previous_text);
+            let excess_versions = history.versions.len().saturating_sub(settings.max_entries);
+            history.versions.drain(..excess_versions);
+            history.reapply_transactions.retain_mut(|(_, version_ix)| {
+                *version_ix = match version_ix.checked_sub(excess_versions) {
+                    Some(version_ix) => version_ix,
+                    None => return false,
+                };
+                true
+            });
+        }
+        history.older_versions = history.versions.len();
+
+        let Some(versions) = serde_json::to_string(&history.versions).log_err() else {
+            return;
+        };
+        let abs_path = history.abs_path.clone();
+        cx.background_executor()
+            .spawn(async move {
+                DB.save_undo_history(abs_path, content_hash(&text), versions)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
+    /// Restores the most recent version of the file that was saved before the
+    /// buffer's undo history begins. Returns whether there was such a version.
+    pub(crate) fn undo_to_persisted_version(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(history) = self.persistent_undo_history.as_mut() else {
+            return false;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return false;
+        };
+        let Some(version_ix) = history.older_versions.checked_sub(1) else {
+            return false;
+        };
+        history.older_versions = version_ix;
+        let previous_text = history.versions[version_ix].clone();
+
+        let diff = buffer.read(cx).diff(previous_text, cx);
+        cx.spawn(|editor, mut cx| async move {
+            let diff = diff.await;
+            let reapply_transaction_id = buffer.update(&mut cx, |buffer, cx| {
+                let newer_version = buffer.text_snapshot();
+                buffer.finalize_last_transaction();
+                let restore_transaction_id = buffer.apply_diff(diff, cx)?;
+                buffer.finalize_last_transaction();
+
+                // Put the newer version on the redo stack, by reapplying it in its own
+                // transaction and undoing that transaction.
+                let reapply_edits = buffer
+                    .edits_since::<usize>(newer_version.version())
+                    .map(|edit| {
+                        let text = newer_version.text_for_range(edit.old).collect::<String>();
+                        (edit.new, text)
+                    })
+                    .collect::<Vec<_>>();
+                buffer.start_transaction();
+                buffer.edit(reapply_edits, None, cx);
+                let reapply_transaction_id = buffer.end_transaction(cx);
+                buffer.finalize_last_transaction();
+                buffer.undo(cx);
+
+                // Keep the restored version out of the undo stack, so that the next
+                // undo continues further back instead of reverting this one.
+                buffer.forget_transaction(restore_transaction_id);
+                reapply_transaction_id
+            })?;
+            editor.update(&mut cx, |editor, cx| {
+                if let Some((history, transaction_id)) = editor
+                    .persistent_undo_history
+                    .as_mut()
+                    .zip(reapply_transaction_id)
+                {
+                    history
+                        .reapply_transactions
+                        .push((transaction_id, version_ix));
+                }
+                editor.request_autoscroll(Autoscroll::fit(), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+        true
+    }
+
+    /// Keeps the restored version in sync when the buffer's undo or redo goes back to it
+    /// or forward from it, so that redoing doesn't lose the versions restored by undo.
+    pub(crate) fn persistent_undo_transaction_changed(
+        &mut self,
+        transaction_id: TransactionId,
+        undone: bool,
+    ) {
+        let Some(history) = self.persistent_undo_history.as_mut() else {
+            return;
+        };
+        if let Some((_, version_ix)) = history
+            .reapply_transactions
+            .iter()
+            .find(|(reapply_transaction_id, _)| *reapply_transaction_id == transaction_id)
+        {
+            history.older_versions = if undone { *version_ix } else { version_ix + 1 };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{Context as _, TestAppContext};
+    use multi_buffer::MultiBuffer;
+
+    use super::*;
+    use crate::{
+        actions::{Redo, Undo},
+        editor_tests::init_test,
+        test::build_editor,
+    };
+
+    #[gpui::test]
+    fn test_redo_after_undo_to_persisted_version(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let buffer = cx.new_model(|cx| language::Buffer::local("one\nthree\n", cx));
+        let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+        let editor = cx.add_window(|cx| build_editor(buffer.clone(), cx));
+        _ = editor.update(cx, |editor, _| {
+            editor.persistent_undo_history = Some(PersistentUndoHistory {
+                abs_path: PathBuf::from("/a.txt"),
+                last_saved_text: "one\nthree\n".into(),
+                versions: vec!["one\n".into(), "one\ntwo\n".into()],
+                older_versions: 2,
+                reapply_transactions: Vec::new(),
+            });
+        });
+
+        _ = editor.update(cx, |editor, cx| editor.undo(&Undo, cx));
+        cx.run_until_parked();
+        _ = editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "one\ntwo\n");
+            editor.redo(&Redo, cx);
+            assert_eq!(editor.text(cx), "one\nthree\n");
+            editor.undo(&Undo, cx);
+            assert_eq!(editor.text(cx), "one\ntwo\n");
+        });
+
+        // Undoing past the restored version keeps going back in time.
+        _ = editor.update(cx, |editor, cx| editor.undo(&Undo, cx));
+        cx.run_until_parked();
+        _ = editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "one\n");
+            editor.redo(&Redo, cx);
+            assert_eq!(editor.text(cx), "one\ntwo\n");
+        });
+
+        // Redoing keeps the restored version, so undoing again restores it.
+        _ = editor.update(cx, |editor, cx| editor.undo(&Undo, cx));
+        cx.run_until_parked();
+        _ = editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "one\n");
+            editor.redo(&Redo, cx);
+            assert_eq!(editor.text(cx), "one\ntwo\n");
+            let history = editor.persistent_undo_history.as_ref().unwrap();
+            assert_eq!(history.versions, ["one\n", "one\ntwo\n"]);
+            assert_eq!(history.older_versions, 1);
+        });
+    }
+}
@@ -0,0 +1,102 @@
+use std::{cmp, ops::Range};
+
+use gpui::{HighlightStyle, ViewContext};
+use language::{language_settings::language_settings, Bias, Point};
+use multi_buffer::{Anchor, ToPoint as _};
+use theme::ActiveTheme;
+
+use crate::{Editor, EditorMode};
+
+enum RainbowBracketHighlight {}
+
+impl Editor {
+    /// Colors the brackets in the visible part of the editor by how deeply they're nested,
+    /// for buffers whose language settings enable it.
+    pub(crate) fn refresh_rainbow_brackets(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_highlights::<RainbowBracketHighlight>(cx);
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let accents = cx.theme().accents().clone();
+        if accents.0.is_empty() {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let visible_start = self.scroll_manager.anchor().anchor.to_point(&snapshot);
+        // Look past the visible rows, as folds can bring more of the buffer into view.
+        let visible_row_count = self.visible_line_count().unwrap_or(0.).ceil() as u32;
+        let visible_end = snapshot.clip_point(
+            Point::new(visible_start.row + visible_row_count * 2 + 1, 0),
+            Bias::Left,
+        );
+        let visible_range = snapshot.anchor_before(Point::new(visible_start.row, 0))
+            ..snapshot.anchor_after(visible_end);
+
+        let mut ranges_by_color = vec![Vec::new(); accents.0.len()];
+        for (excerpt_id, buffer, range) in snapshot.excerpts_in_ranges([visible_range]) {
+            let settings = language_settings(buffer.language_at(range.start), buffer.file(), cx);
+            if !settings.colorize_brackets {
+                continue;
+            }
+
+            // Pairs enclosing the range are included, so depths are the same no matter
+            // which part of the buffer is shown, in a single buffer or in an excerpt.
+            let mut pairs = buffer
+                .bracket_ranges(range.clone())
+                .filter(|(open, close)| {
+                    !buffer
+                        .chars_for_range(open.clone())
+                        .eq(buffer.chars_for_range(close.clone()))
+                })
+                .collect::<Vec<_>>();
+            pairs.sort_by_key(|(open, close)| (open.start, cmp::Reverse(close.end)));
+            pairs.dedup();
+
+            let mut enclosing_pair_ends = Vec::new();
+            for (open, close) in pairs {
+                while enclosing_pair_ends
+                    .last()
+                    .is_some_and(|end| *end <= open.start)
+                {
+                    enclosing_pair_ends.pop();
+                }
+                let depth = enclosing_pair_ends.len();
+                enclosing_pair_ends.push(close.end);
+
+                for bracket in [open, close] {
+                    if bracket.end <= range.start || bracket.start >= range.end {
+                        continue;
+                    }
+                    let Some(start) =
+                        snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_after(bracket.start))
+                    else {
+                        continue;
+                    };
+                    let Some(end) =
+                        snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_before(bracket.end))
+                    else {
+                        continue;
+                    };
+                    ranges_by_color[depth % accents.0.len()].push(start..end);
+                }
+            }
+        }
+
+        for (color_ix, mut ranges) in ranges_by_color.into_iter().enumerate() {
+            if ranges.is_empty() {
+                continue;
+            }
+            ranges.sort_by(|a: &Range<Anchor>, b| a.start.cmp(&b.start, &snapshot));
+            self.highlight_text_key::<RainbowBracketHighlight>(
+                color_ix,
+                ranges,
+                HighlightStyle {
+                    color: Some(accents.color_for_index(color_ix as u32)),
+                    ..Default::default()
+                },
+                cx,
+            );
+        }
+    }
+}
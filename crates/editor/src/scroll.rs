@@ -130,6 +130,7 @@ impl OngoingScroll {
 
 pub struct ScrollManager {
     pub(crate) vertical_scroll_margin: f32,
+    pub(crate) typewriter_scrolling: bool,
     anchor: ScrollAnchor,
     ongoing: OngoingScroll,
     autoscroll_request: Option<(Autoscroll, bool)>,
@@ -145,6 +146,7 @@ impl ScrollManager {
     pub fn new(cx: &mut WindowContext) -> Self {
         ScrollManager {
             vertical_scroll_margin: EditorSettings::get_global(cx).vertical_scroll_margin,
+            typewriter_scrolling: EditorSettings::get_global(cx).typewriter_scrolling,
             anchor: ScrollAnchor::new(),
             ongoing: OngoingScroll::new(),
             autoscroll_request: None,
@@ -191,13 +193,13 @@ impl ScrollManager {
         if self.forbid_vertical_scroll {
             return;
         }
-        let (new_anchor, top_row) = if scroll_position.y <= 0. {
+        let (new_anchor, top_point) = if scroll_position.y <= 0. {
             (
                 ScrollAnchor {
                     anchor: Anchor::min(),
                     offset: scroll_position.max(&gpui::Point::default()),
                 },
-                0,
+                Point::zero(),
             )
         } else {
             let scroll_top = scroll_position.y;
@@ -237,17 +239,17 @@ impl ScrollManager {
                         scroll_top - top_anchor.to_display_point(&map).row().as_f32(),
                     ),
                 },
-                scroll_top_buffer_point.row,
+                scroll_top_buffer_point,
             )
         };
 
-        self.set_anchor(new_anchor, top_row, local, autoscroll, workspace_id, cx);
+        self.set_anchor(new_anchor, top_point, local, autoscroll, workspace_id, cx);
     }
 
     fn set_anchor(
         &mut self,
         anchor: ScrollAnchor,
-        top_row: u32,
+        top_point: Point,
         local: bool,
         autoscroll: bool,
         workspace_id: Option<WorkspaceId>,
@@ -268,7 +270,8 @@ impl ScrollManager {
                     DB.save_scroll_position(
                         item_id,
                         workspace_id,
-                        top_row,
+                        top_point.row,
+                        top_point.column,
                         anchor.offset.x,
                         anchor.offset.y,
                     )
@@ -366,7 +369,11 @@ impl Editor {
             cx.spawn(|editor, mut cx| async move {
                 editor
                     .update(&mut cx, |editor, cx| {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx)
+                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                        editor.resolve_visible_code_lenses(cx);
+                        editor.refresh_rainbow_brackets(cx);
+                        editor.refresh_color_swatches(cx);
+                        editor.refresh_spell_check(cx);
                     })
                     .ok()
             })
@@ -430,6 +437,10 @@ impl Editor {
         );
 
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+        self.resolve_visible_code_lenses(cx);
+        self.refresh_rainbow_brackets(cx);
+        self.refresh_color_swatches(cx);
+        self.refresh_spell_check(cx);
     }
 
     pub fn scroll_position(&self, cx: &mut ViewContext<Self>) -> gpui::Point<f32> {
@@ -440,12 +451,11 @@ impl Editor {
     pub fn set_scroll_anchor(&mut self, scroll_anchor: ScrollAnchor, cx: &mut ViewContext<Self>) {
         hide_hover(self, cx);
         let workspace_id = self.workspace.as_ref().and_then(|workspace| workspace.1);
-        let top_row = scroll_anchor
+        let top_point = scroll_anchor
             .anchor
-            .to_point(&self.buffer().read(cx).snapshot(cx))
-            .row;
+            .to_point(&self.buffer().read(cx).snapshot(cx));
         self.scroll_manager
-            .set_anchor(scroll_anchor, top_row, true, false, workspace_id, cx);
+            .set_anchor(scroll_anchor, top_point, true, false, workspace_id, cx);
     }
 
     pub(crate) fn set_scroll_anchor_remote(
@@ -460,9 +470,9 @@ impl Editor {
             log::warn!("Invalid scroll anchor: {:?}", scroll_anchor);
             return;
         }
-        let top_row = scroll_anchor.anchor.to_point(snapshot).row;
+        let top_point = scroll_anchor.anchor.to_point(snapshot);
         self.scroll_manager
-            .set_anchor(scroll_anchor, top_row, false, false, workspace_id, cx);
+            .set_anchor(scroll_anchor, top_point, false, false, workspace_id, cx);
     }
 
     pub fn scroll_screen(&mut self, amount: &ScrollAmount, cx: &mut ViewContext<Self>) {
@@ -520,12 +530,10 @@ impl Editor {
         cx: &mut ViewContext<Editor>,
     ) {
         let scroll_position = DB.get_scroll_position(item_id, workspace_id);
-        if let Ok(Some((top_row, x, y))) = scroll_position {
-            let top_anchor = self
-                .buffer()
-                .read(cx)
-                .snapshot(cx)
-                .anchor_at(Point::new(top_row, 0), Bias::Left);
+        if let Ok(Some((top_row, top_column, x, y))) = scroll_position {
+            let snapshot = self.buffer().read(cx).snapshot(cx);
+            let top_point = snapshot.clip_point(Point::new(top_row, top_column), Bias::Left);
+            let top_anchor = snapshot.anchor_at(top_point, Bias::Left);
             let scroll_anchor = ScrollAnchor {
                 offset: gpui::Point::new(x, y),
                 anchor: top_anchor,
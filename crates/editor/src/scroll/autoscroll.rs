@@ -164,6 +164,16 @@ impl Editor {
             }
         };
 
+        // In typewriter mode, the cursor stays centered instead of scrolling minimally.
+        let strategy = match strategy {
+            AutoscrollStrategy::Fit | AutoscrollStrategy::Newest
+                if self.scroll_manager.typewriter_scrolling && self.mode == EditorMode::Full =>
+            {
+                AutoscrollStrategy::Center
+            }
+            strategy => strategy,
+        };
+
         match strategy {
             AutoscrollStrategy::Fit | AutoscrollStrategy::Newest => {
                 let margin = margin.min(self.scroll_manager.vertical_scroll_margin);
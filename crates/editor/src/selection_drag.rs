@@ -0,0 +1,242 @@
+use std::ops::Range;
+
+use gpui::{px, Global, Pixels, ViewContext, WeakView};
+use multi_buffer::{Anchor, ToOffset as _};
+use text::SelectionGoal;
+
+use crate::{scroll::Autoscroll, Bias, DisplayPoint, Editor, Selection};
+
+/// How far the mouse has to move, after being pressed on a selection, to start dragging it.
+const DRAG_THRESHOLD: Pixels = px(4.);
+
+#[derive(Clone, Debug, Default)]
+pub(crate) enum SelectionDragState {
+    #[default]
+    None,
+    /// The mouse was pressed on a selection. It starts being dragged once the mouse
+    /// moves far enough, or is collapsed to the click position if it's released first.
+    ReadyToDrag {
+        selection: Selection<Anchor>,
+        click_position: gpui::Point<Pixels>,
+    },
+    Dragging,
+}
+
+/// The selection being dragged. It's shared by all editors, so that it can be dropped
+/// in a different one than it was dragged from.
+#[derive(Default)]
+struct ActiveSelectionDrag(Option<DraggedSelection>);
+
+impl Global for ActiveSelectionDrag {}
+
+struct DraggedSelection {
+    source: WeakView<Editor>,
+    range: Range<Anchor>,
+    text: String,
+}
+
+impl Editor {
+    /// Returns the non-empty selection containing the given position, from which a drag
+    /// can be started.
+    pub(crate) fn draggable_selection_at(
+        &mut self,
+        position: DisplayPoint,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Selection<Anchor>> {
+        if self.read_only(cx) {
+            return None;
+        }
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let offset = position.to_offset(&display_map, Bias::Left);
+        let buffer = &display_map.buffer_snapshot;
+        self.selections
+            .disjoint_anchors()
+            .iter()
+            .find(|selection| {
+                let start = selection.start.to_offset(buffer);
+                let end = selection.end.to_offset(buffer);
+                start <= offset && offset < end
+            })
+            .cloned()
+    }
+
+    pub(crate) fn prepare_selection_drag(
+        &mut self,
+        selection: Selection<Anchor>,
+        click_position: gpui::Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if !self.focus_handle.is_focused(cx) {
+            self.last_focused_descendant = None;
+            cx.focus(&self.focus_handle);
+        }
+        self.selection_drag_state = SelectionDragState::ReadyToDrag {
+            selection,
+            click_position,
+        };
+    }
+
+    /// Starts dragging the selection the mouse was pressed on, if the mouse has moved
+    /// far enough from where it was pressed.
+    pub(crate) fn start_selection_drag_if_moved(
+        &mut self,
+        mouse_position: gpui::Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let SelectionDragState::ReadyToDrag {
+            selection,
+            click_position,
+        } = &self.selection_drag_state
+        else {
+            return;
+        };
+        let delta = mouse_position - *click_position;
+        if delta.x.abs() < DRAG_THRESHOLD && delta.y.abs() < DRAG_THRESHOLD {
+            return;
+        }
+
+        let range = selection.range();
+        self.start_selection_drag(range, cx);
+    }
+
+    pub(crate) fn start_selection_drag(
+        &mut self,
+        range: Range<Anchor>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let text = self
+            .buffer
+            .read(cx)
+            .snapshot(cx)
+            .text_for_range(range.clone())
+            .collect();
+        cx.set_global(ActiveSelectionDrag(Some(DraggedSelection {
+            source: cx.view().downgrade(),
+            range,
+            text,
+        })));
+        self.selection_drag_state = SelectionDragState::Dragging;
+        cx.notify();
+    }
+
+    pub(crate) fn has_active_selection_drag(&self, cx: &ViewContext<Self>) -> bool {
+        cx.try_global::<ActiveSelectionDrag>()
+            .map_or(false, |drag| drag.0.is_some())
+    }
+
+    /// Shows where the dragged selection would be dropped, if anywhere in this editor.
+    pub(crate) fn set_drop_cursor(
+        &mut self,
+        position: Option<DisplayPoint>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let drop_cursor = position.map(|position| {
+            let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+            display_map
+                .buffer_snapshot
+                .anchor_before(position.to_offset(&display_map, Bias::Left))
+        });
+        if self.drop_cursor != drop_cursor {
+            self.drop_cursor = drop_cursor;
+            cx.notify();
+        }
+    }
+
+    /// Drops the dragged selection at the given position, moving its text there or copying
+    /// it when `copy` is true. Returns whether the text was dropped.
+    pub(crate) fn drop_selection(
+        &mut self,
+        position: DisplayPoint,
+        copy: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        self.set_drop_cursor(None, cx);
+        let Some(drag) = cx.default_global::<ActiveSelectionDrag>().0.take() else {
+            return false;
+        };
+        if self.read_only(cx) {
+            return false;
+        }
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let drop_offset = position.to_offset(&display_map, Bias::Left);
+        let buffer = &display_map.buffer_snapshot;
+        let drop_anchor = buffer.anchor_before(drop_offset);
+        let is_source = drag.source == cx.view().downgrade();
+
+        let mut edits = vec![(drop_offset..drop_offset, drag.text.clone())];
+        if is_source {
+            let dragged_range =
+                drag.range.start.to_offset(buffer)..drag.range.end.to_offset(buffer);
+            if dragged_range.start <= drop_offset && drop_offset <= dragged_range.end {
+                return false;
+            }
+            if !copy {
+                edits.push((dragged_range, String::new()));
+                edits.sort_by_key(|(range, _)| range.start);
+            }
+        }
+
+        self.transact(cx, |editor, cx| {
+            editor
+                .buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            let buffer = editor.buffer.read(cx).snapshot(cx);
+            let start = drop_anchor.to_offset(&buffer);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                selections.select_ranges([start..start + drag.text.len()])
+            });
+        });
+
+        if !is_source {
+            drag.source
+                .update(cx, |source, cx| {
+                    source.selection_drag_state = SelectionDragState::None;
+                    if !copy {
+                        source.transact(cx, |source, cx| {
+                            source.buffer.update(cx, |buffer, cx| {
+                                buffer.edit([(drag.range.clone(), "")], None, cx)
+                            });
+                        });
+                    }
+                })
+                .ok();
+        }
+        true
+    }
+
+    /// Ends the drag when the mouse is released, collapsing the selection the mouse was
+    /// pressed on if it was never dragged.
+    pub(crate) fn end_selection_drag(
+        &mut self,
+        position: DisplayPoint,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        self.set_drop_cursor(None, cx);
+        match std::mem::take(&mut self.selection_drag_state) {
+            SelectionDragState::None => false,
+            SelectionDragState::ReadyToDrag { .. } => {
+                self.change_selections(None, cx, |selections| {
+                    selections.select_display_ranges([position..position])
+                });
+                true
+            }
+            SelectionDragState::Dragging => {
+                // Other editors may still receive the mouse release and take the drop.
+                cx.defer(|_, cx| cx.set_global(ActiveSelectionDrag::default()));
+                true
+            }
+        }
+    }
+
+    pub(crate) fn drop_cursor_selection(&self) -> Option<Selection<Anchor>> {
+        let anchor = self.drop_cursor?;
+        Some(Selection {
+            id: 0,
+            start: anchor,
+            end: anchor,
+            reversed: false,
+            goal: SelectionGoal::None,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use std::{ops::Range, time::Duration};
+
+use futures::future::join_all;
+use gpui::{HighlightStyle, StrikethroughStyle, ViewContext};
+use multi_buffer::Anchor;
+use project::SemanticToken;
+use theme::{ActiveTheme, SyntaxTheme};
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+const SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+impl Editor {
+    /// Requests fresh semantic tokens for all buffers of the editor and replaces the
+    /// highlights drawn from them once they arrive.
+    pub(crate) fn refresh_semantic_tokens(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        self.semantic_tokens_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let Some(tasks) = editor
+                .update(&mut cx, |editor, cx| {
+                    let buffers = editor.buffer.read(cx).all_buffers();
+                    project.update(cx, |project, cx| {
+                        buffers
+                            .into_iter()
+                            .map(|buffer| {
+                                let task = project.semantic_tokens(&buffer, cx);
+                                async move { (buffer, task.await.log_err().unwrap_or_default()) }
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .ok()
+            else {
+                return;
+            };
+            let tokens_by_buffer = join_all(tasks).await;
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let syntax_theme = cx.theme().syntax().clone();
+                    let multi_buffer = editor.buffer.read(cx);
+                    let snapshot = multi_buffer.snapshot(cx);
+                    let mut highlights = Vec::new();
+                    for (buffer, tokens) in tokens_by_buffer {
+                        let buffer_snapshot = buffer.read(cx).snapshot();
+                        for (excerpt_id, excerpt_range) in
+                            multi_buffer.excerpts_for_buffer(&buffer, cx)
+                        {
+                            let context = excerpt_range.context;
+                            for token in &tokens {
+                                let ends_before =
+                                    token.range.end.cmp(&context.start, &buffer_snapshot);
+                                let starts_after =
+                                    token.range.start.cmp(&context.end, &buffer_snapshot);
+                                if ends_before.is_le() || starts_after.is_ge() {
+                                    continue;
+                                }
+                                let Some(style) = semantic_token_style(&syntax_theme, token) else {
+                                    continue;
+                                };
+                                let Some(start) =
+                                    snapshot.anchor_in_excerpt(excerpt_id, token.range.start)
+                                else {
+                                    continue;
+                                };
+                                let Some(end) =
+                                    snapshot.anchor_in_excerpt(excerpt_id, token.range.end)
+                                else {
+                                    continue;
+                                };
+                                highlights.push((start..end, style));
+                            }
+                        }
+                    }
+                    highlights.sort_by(|(a, _): &(Range<Anchor>, _), (b, _)| {
+                        a.start.cmp(&b.start, &snapshot)
+                    });
+
+                    editor.display_map.update(cx, |display_map, _| {
+                        display_map.set_semantic_token_highlights(highlights)
+                    });
+                    cx.notify();
+                })
+                .log_err();
+        }));
+    }
+}
+
+/// Picks the theme style for a token, preferring `semantic.<type>.<modifier>` and
+/// `semantic.<type>` keys and falling back to the closest Tree-sitter capture.
+fn semantic_token_style(theme: &SyntaxTheme, token: &SemanticToken) -> Option<HighlightStyle> {
+    let token_type = token.token_type.as_str();
+    let style_for = |name: &str| theme.highlight_id(name).map(|_| theme.get(name));
+
+    let mut style = token
+        .modifiers
+        .iter()
+        .find_map(|modifier| style_for(&format!("semantic.{token_type}.{}", modifier.as_str())))
+        .or_else(|| style_for(&format!("semantic.{token_type}")))
+        .or_else(|| {
+            fallback_captures(token_type)
+                .iter()
+                .find_map(|capture| style_for(capture))
+        });
+
+    if token
+        .modifiers
+        .iter()
+        .any(|modifier| *modifier == lsp::SemanticTokenModifier::DEPRECATED)
+    {
+        let style = style.get_or_insert_with(HighlightStyle::default);
+        style.strikethrough = Some(StrikethroughStyle {
+            thickness: 1.0.into(),
+            ..Default::default()
+        });
+    }
+    style
+}
+
+fn fallback_captures(token_type: &str) -> &'static [&'static str] {
+    match token_type {
+        "namespace" => &["namespace", "type"],
+        "class" | "struct" | "interface" | "type" | "typeParameter" => &["type"],
+        "enum" => &["enum", "type"],
+        "enumMember" => &["variant", "constant"],
+        "function" | "method" => &["function"],
+        "macro" => &["function.macro", "function"],
+        "parameter" | "variable" => &["variable"],
+        "property" => &["property"],
+        "keyword" | "modifier" => &["keyword"],
+        "decorator" => &["attribute"],
+        "comment" => &["comment"],
+        "string" => &["string"],
+        "regexp" => &["string.regex", "string"],
+        "number" => &["number"],
+        "operator" => &["operator"],
+        _ => &[],
+    }
+}
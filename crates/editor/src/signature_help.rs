@@ -1,13 +1,14 @@
 mod popover;
 mod state;
 
-use crate::actions::ShowSignatureHelp;
+use crate::actions::{ShowSignatureHelp, SignatureHelpNext, SignatureHelpPrevious};
 use crate::{Editor, EditorSettings, ToggleAutoSignatureHelp};
 use gpui::{AppContext, ViewContext};
-use language::markdown::parse_markdown;
+use language::{markdown::parse_markdown, Language, LanguageRegistry};
 use multi_buffer::{Anchor, ToOffset};
+use project::lsp_command::SignatureHelp;
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, sync::Arc};
 
 pub use popover::SignatureHelpPopover;
 pub use state::SignatureHelpState;
@@ -139,7 +140,9 @@ impl Editor {
                 let condition = self.signature_help_state.hidden_by_selection()
                     || previous != current
                     || (previous == current && self.signature_help_state.is_shown());
-                if !condition {
+                // The shown signatures are only kept until the new ones arrive while the
+                // cursor stays within the same brackets.
+                if !condition || previous != current {
                     self.signature_help_state
                         .hide(SignatureHelpHiddenBy::AutoClose);
                 }
@@ -179,24 +182,35 @@ impl Editor {
                     })
                     .ok()
                     .flatten();
-                let signature_help_popover = if let Some((
+                let signature_help_popovers = if let Some((
                     signature_help_task,
                     language_registry,
                     language,
                 )) = signature_help
                 {
                     // TODO allow multiple signature helps inside the same popover
-                    if let Some(mut signature_help) = signature_help_task.await.into_iter().next() {
-                        let mut parsed_content = parse_markdown(
-                            signature_help.markdown.as_str(),
-                            &language_registry,
-                            language,
-                        )
-                        .await;
-                        parsed_content
-                            .highlights
-                            .append(&mut signature_help.highlights);
-                        Some(SignatureHelpPopover { parsed_content })
+                    if let Some(signature_help) = signature_help_task.await.into_iter().next() {
+                        let mut popovers = Vec::new();
+                        let mut active_signature = 0;
+                        for signature_ix in 0..signature_help.signature_count {
+                            let Some(help) = signature_help.with_active_signature(signature_ix)
+                            else {
+                                continue;
+                            };
+                            if signature_ix == signature_help.active_signature {
+                                active_signature = popovers.len();
+                            }
+                            popovers.push(
+                                signature_help_popover(help, &language_registry, language.clone())
+                                    .await,
+                            );
+                        }
+                        let signature_count = popovers.len();
+                        for (signature_ix, popover) in popovers.iter_mut().enumerate() {
+                            popover.signature_ix = signature_ix;
+                            popover.signature_count = signature_count;
+                        }
+                        Some((popovers, active_signature))
                     } else {
                         None
                     }
@@ -205,21 +219,62 @@ impl Editor {
                 };
                 editor
                     .update(&mut cx, |editor, cx| {
-                        let previous_popover = editor.signature_help_state.popover();
-                        if previous_popover != signature_help_popover.as_ref() {
-                            if let Some(signature_help_popover) = signature_help_popover {
+                        match signature_help_popovers {
+                            Some((popovers, active_signature)) if !popovers.is_empty() => {
                                 editor
                                     .signature_help_state
-                                    .set_popover(signature_help_popover);
-                            } else {
+                                    .set_popovers(popovers, active_signature);
+                            }
+                            // Keep showing the signatures while the arguments are being typed,
+                            // as servers may not have any help for incomplete code.
+                            _ if editor.signature_help_state.is_shown() => return,
+                            _ => {
                                 editor
                                     .signature_help_state
                                     .hide(SignatureHelpHiddenBy::AutoClose);
                             }
-                            cx.notify();
                         }
+                        cx.notify();
                     })
                     .ok();
             }));
     }
+
+    pub fn signature_help_next(&mut self, _: &SignatureHelpNext, cx: &mut ViewContext<Self>) {
+        self.signature_help_state.select_next();
+        cx.notify();
+    }
+
+    pub fn signature_help_prev(&mut self, _: &SignatureHelpPrevious, cx: &mut ViewContext<Self>) {
+        self.signature_help_state.select_prev();
+        cx.notify();
+    }
+}
+
+async fn signature_help_popover(
+    mut signature_help: SignatureHelp,
+    language_registry: &Arc<LanguageRegistry>,
+    language: Option<Arc<Language>>,
+) -> SignatureHelpPopover {
+    let mut parsed_content = parse_markdown(
+        signature_help.markdown.as_str(),
+        language_registry,
+        language.clone(),
+    )
+    .await;
+    parsed_content
+        .highlights
+        .append(&mut signature_help.highlights);
+    let documentation = match signature_help.documentation {
+        Some(documentation) => {
+            Some(parse_markdown(documentation.as_str(), language_registry, language).await)
+        }
+        None => None,
+    };
+    SignatureHelpPopover {
+        parsed_content,
+        documentation,
+        signature_ix: 0,
+        signature_count: 1,
+    }
 }
@@ -1,22 +1,33 @@
+use crate::actions::{SignatureHelpNext, SignatureHelpPrevious};
 use crate::{Editor, EditorStyle};
 use gpui::{
     div, AnyElement, InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, Size,
     StatefulInteractiveElement, Styled, ViewContext, WeakView,
 };
 use language::ParsedMarkdown;
-use ui::StyledExt;
+use ui::{prelude::*, IconButtonShape};
 use workspace::Workspace;
 
 #[derive(Clone, Debug)]
 pub struct SignatureHelpPopover {
     pub parsed_content: ParsedMarkdown,
+    /// The documentation of the active parameter and of the signature.
+    pub documentation: Option<ParsedMarkdown>,
+    pub signature_ix: usize,
+    pub signature_count: usize,
 }
 
 impl PartialEq for SignatureHelpPopover {
     fn eq(&self, other: &Self) -> bool {
         let str_equality = self.parsed_content.text.as_str() == other.parsed_content.text.as_str();
         let highlight_equality = self.parsed_content.highlights == other.parsed_content.highlights;
-        str_equality && highlight_equality
+        let documentation_equality = self.documentation.as_ref().map(|doc| &doc.text)
+            == other.documentation.as_ref().map(|doc| &doc.text);
+        str_equality
+            && highlight_equality
+            && documentation_equality
+            && self.signature_ix == other.signature_ix
+            && self.signature_count == other.signature_count
     }
 }
 
@@ -28,6 +39,50 @@ impl SignatureHelpPopover {
         workspace: Option<WeakView<Workspace>>,
         cx: &mut ViewContext<Editor>,
     ) -> AnyElement {
+        let overloads = (self.signature_count > 1).then(|| {
+            h_flex()
+                .flex_none()
+                .gap_0p5()
+                .child(
+                    IconButton::new("signature_help_prev", IconName::ChevronUp)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::XSmall)
+                        .on_click(cx.listener(|editor, _, cx| {
+                            editor.signature_help_prev(&SignatureHelpPrevious, cx)
+                        })),
+                )
+                .child(
+                    Label::new(format!(
+                        "{} of {}",
+                        self.signature_ix + 1,
+                        self.signature_count
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .child(
+                    IconButton::new("signature_help_next", IconName::ChevronDown)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::XSmall)
+                        .on_click(cx.listener(|editor, _, cx| {
+                            editor.signature_help_next(&SignatureHelpNext, cx)
+                        })),
+                )
+        });
+        let documentation = self.documentation.as_ref().map(|documentation| {
+            div()
+                .p_2()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(crate::render_parsed_markdown(
+                    "signature_help_popover_documentation",
+                    documentation,
+                    style,
+                    workspace.clone(),
+                    cx,
+                ))
+        });
+
         div()
             .id("signature_help_popover")
             .elevation_2(cx)
@@ -36,13 +91,21 @@ impl SignatureHelpPopover {
             .max_h(max_size.height)
             .on_mouse_move(|_, cx| cx.stop_propagation())
             .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
-            .child(div().p_2().child(crate::render_parsed_markdown(
-                "signature_help_popover_content",
-                &self.parsed_content,
-                style,
-                workspace,
-                cx,
-            )))
+            .child(
+                h_flex()
+                    .items_start()
+                    .p_2()
+                    .gap_2()
+                    .child(div().flex_1().child(crate::render_parsed_markdown(
+                        "signature_help_popover_content",
+                        &self.parsed_content,
+                        style,
+                        workspace,
+                        cx,
+                    )))
+                    .children(overloads),
+            )
+            .children(documentation)
             .into_any_element()
     }
 }
@@ -5,7 +5,10 @@ use gpui::Task;
 #[derive(Default, Debug)]
 pub struct SignatureHelpState {
     task: Option<Task<()>>,
-    popover: Option<SignatureHelpPopover>,
+    /// A popover for each of the signatures, such as the overloads of a function.
+    popovers: Vec<SignatureHelpPopover>,
+    active_signature: usize,
+    signature_picked: bool,
     hidden_by: Option<SignatureHelpHiddenBy>,
     backspace_pressed: bool,
 }
@@ -21,11 +24,11 @@ impl SignatureHelpState {
     }
 
     pub fn popover(&self) -> Option<&SignatureHelpPopover> {
-        self.popover.as_ref()
+        self.popovers.get(self.active_signature)
     }
 
     pub fn popover_mut(&mut self) -> Option<&mut SignatureHelpPopover> {
-        self.popover.as_mut()
+        self.popovers.get_mut(self.active_signature)
     }
 
     pub fn backspace_pressed(&self) -> bool {
@@ -36,14 +39,47 @@ impl SignatureHelpState {
         self.backspace_pressed = backspace_pressed;
     }
 
-    pub fn set_popover(&mut self, popover: SignatureHelpPopover) {
-        self.popover = Some(popover);
+    /// Shows the given signatures. When they're the same as the shown ones and another one
+    /// was picked with [`Self::select_next`] or [`Self::select_prev`], it stays picked.
+    pub fn set_popovers(&mut self, popovers: Vec<SignatureHelpPopover>, active_signature: usize) {
+        let same_signatures = self.popovers.len() == popovers.len()
+            && self
+                .popovers
+                .iter()
+                .zip(&popovers)
+                .all(|(old, new)| old.parsed_content.text == new.parsed_content.text);
+        if !(same_signatures && self.signature_picked) {
+            self.active_signature = active_signature.min(popovers.len().saturating_sub(1));
+            self.signature_picked = false;
+        }
+        self.popovers = popovers;
         self.hidden_by = None;
     }
 
+    pub fn signature_count(&self) -> usize {
+        self.popovers.len()
+    }
+
+    pub fn select_next(&mut self) {
+        if self.popovers.len() > 1 {
+            self.active_signature = (self.active_signature + 1) % self.popovers.len();
+            self.signature_picked = true;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if self.popovers.len() > 1 {
+            self.active_signature =
+                (self.active_signature + self.popovers.len() - 1) % self.popovers.len();
+            self.signature_picked = true;
+        }
+    }
+
     pub fn hide(&mut self, hidden_by: SignatureHelpHiddenBy) {
         if self.hidden_by.is_none() {
-            self.popover = None;
+            self.popovers.clear();
+            self.active_signature = 0;
+            self.signature_picked = false;
             self.hidden_by = Some(hidden_by);
         }
     }
@@ -53,7 +89,7 @@ impl SignatureHelpState {
     }
 
     pub fn is_shown(&self) -> bool {
-        self.popover.is_some()
+        !self.popovers.is_empty()
     }
 }
 
@@ -0,0 +1,497 @@
+use std::{ops::Range, path::Path, sync::Arc, time::Duration};
+
+use collections::HashMap;
+use gpui::{px, HighlightStyle, Model, Task, UnderlineStyle, ViewContext, WeakView};
+use language::{
+    language_settings::{language_settings, SpellCheck},
+    Bias, Buffer, BufferSnapshot, OffsetRangeExt, Point,
+};
+use multi_buffer::{Anchor, ToPoint as _};
+use spellcheck::{words_to_check, SpellChecker, SpellingSnapshot};
+use text::BufferId;
+use theme::ActiveTheme;
+use ui::ContextMenu;
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+const SPELL_CHECK_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(300);
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The Markdown nodes holding code rather than prose, which aren't checked even when the
+/// whole buffer is.
+const CODE_BLOCK_KINDS: &[&str] = &["fenced_code_block", "indented_code_block", "code_span"];
+
+enum MisspelledWord {}
+
+/// The misspelled words found in the editor's buffers, which are only checked where they're
+/// shown and where they were edited since they were last checked.
+#[derive(Default)]
+pub(crate) struct SpellCheckState {
+    buffers: HashMap<BufferId, BufferSpelling>,
+    task: Option<Task<()>>,
+    /// Computes the suggestions for the misspelled word that a context menu is deployed on.
+    pub(crate) suggestions_task: Option<Task<()>>,
+}
+
+struct BufferSpelling {
+    /// The version of the buffer that was checked.
+    version: clock::Global,
+    /// The ranges of the buffer that were checked, sorted and whole lines.
+    checked: Vec<Range<text::Anchor>>,
+    /// The misspelled words in the checked ranges, sorted.
+    misspellings: Vec<Range<text::Anchor>>,
+}
+
+/// The ranges of a buffer that are left to check, with what was found in the other ones.
+struct BufferCheck {
+    buffer_id: BufferId,
+    snapshot: BufferSnapshot,
+    mode: SpellCheck,
+    spelling: SpellingSnapshot,
+    checked: Vec<Range<usize>>,
+    misspellings: Vec<Range<usize>>,
+    ranges: Vec<Range<usize>>,
+}
+
+impl Editor {
+    /// Checks the spelling of the words in the visible parts of the editor's buffers that
+    /// weren't checked yet, underlining the misspelled ones. Which words are checked depends
+    /// on the `spell_check` setting of each buffer's language.
+    pub(crate) fn refresh_spell_check(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(spell_checker) = SpellChecker::global(cx) else {
+            return;
+        };
+
+        self.spell_check.task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(SPELL_CHECK_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let Some(checks) = editor
+                .update(&mut cx, |editor, cx| {
+                    editor.buffer_spell_checks(&spell_checker, cx)
+                })
+                .ok()
+            else {
+                return;
+            };
+
+            let checked_buffers = cx
+                .background_executor()
+                .spawn(async move {
+                    checks
+                        .into_iter()
+                        .map(|check| check.run())
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.spell_check.buffers.extend(checked_buffers);
+                    editor.highlight_misspellings(cx);
+                })
+                .log_err();
+        }));
+    }
+
+    /// Forgets which words were checked, to check them all again, as when the dictionaries
+    /// or the settings change.
+    pub(crate) fn reset_spell_check(&mut self, cx: &mut ViewContext<Self>) {
+        self.spell_check.buffers.clear();
+        self.refresh_spell_check(cx);
+    }
+
+    fn buffer_spell_checks(
+        &mut self,
+        spell_checker: &Model<SpellChecker>,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<BufferCheck> {
+        let mut visible_ranges = HashMap::<BufferId, (Model<Buffer>, Vec<Range<usize>>)>::default();
+        for (buffer, range) in self.visible_buffer_ranges(cx) {
+            let buffer_id = buffer.read(cx).remote_id();
+            visible_ranges
+                .entry(buffer_id)
+                .or_insert_with(|| (buffer, Vec::new()))
+                .1
+                .push(range);
+        }
+
+        let mut checks = Vec::new();
+        for (buffer_id, (buffer, ranges)) in visible_ranges {
+            let buffer = buffer.read(cx);
+            let mode = language_settings(buffer.language(), buffer.file(), cx).spell_check;
+            if mode == SpellCheck::Off {
+                self.spell_check.buffers.remove(&buffer_id);
+                continue;
+            }
+            let snapshot = buffer.snapshot();
+            let worktree_root = project::File::from_dyn(buffer.file())
+                .map(|file| file.worktree.read(cx).abs_path());
+            let spelling = spell_checker.update(cx, |spell_checker, cx| {
+                spell_checker.snapshot(worktree_root.as_ref(), cx)
+            });
+            if spelling.is_empty() {
+                continue;
+            }
+
+            let (mut checked, mut misspellings) = (Vec::new(), Vec::new());
+            if let Some(state) = self.spell_check.buffers.get(&buffer_id) {
+                let edited = merge_ranges(
+                    snapshot
+                        .edits_since::<usize>(&state.version)
+                        .map(|edit| line_range(edit.new, &snapshot))
+                        .collect(),
+                );
+                checked = subtract_ranges(
+                    &state
+                        .checked
+                        .iter()
+                        .map(|range| range.to_offset(&snapshot))
+                        .collect::<Vec<_>>(),
+                    &edited,
+                );
+                misspellings = state
+                    .misspellings
+                    .iter()
+                    .map(|range| range.to_offset(&snapshot))
+                    .filter(|word| {
+                        !edited
+                            .iter()
+                            .any(|range| range.start <= word.end && word.start <= range.end)
+                    })
+                    .collect();
+            }
+            let visible = merge_ranges(
+                ranges
+                    .into_iter()
+                    .map(|range| line_range(range, &snapshot))
+                    .collect(),
+            );
+            checks.push(BufferCheck {
+                buffer_id,
+                ranges: subtract_ranges(&visible, &checked),
+                snapshot,
+                mode,
+                spelling,
+                checked,
+                misspellings,
+            });
+        }
+        checks
+    }
+
+    /// The ranges of the buffers that the editor shows.
+    fn visible_buffer_ranges(
+        &self,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<(Model<Buffer>, Range<usize>)> {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let visible_start = self.scroll_manager.anchor().anchor.to_point(&snapshot);
+        let visible_end = snapshot.clip_point(
+            visible_start + Point::new(self.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+            Bias::Left,
+        );
+        multi_buffer
+            .range_to_buffer_ranges(visible_start..visible_end, cx)
+            .into_iter()
+            .map(|(buffer, range, _)| (buffer, range))
+            .collect()
+    }
+
+    fn highlight_misspellings(&mut self, cx: &mut ViewContext<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        self.spell_check
+            .buffers
+            .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+        let mut ranges = Vec::new();
+        for (buffer_id, spelling) in &self.spell_check.buffers {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+                let context = excerpt_range.context;
+                for word in &spelling.misspellings {
+                    if word.start.cmp(&context.start, &buffer_snapshot).is_lt()
+                        || word.end.cmp(&context.end, &buffer_snapshot).is_gt()
+                    {
+                        continue;
+                    }
+                    let Some(start) = snapshot.anchor_in_excerpt(excerpt_id, word.start) else {
+                        continue;
+                    };
+                    let Some(end) = snapshot.anchor_in_excerpt(excerpt_id, word.end) else {
+                        continue;
+                    };
+                    ranges.push(start..end);
+                }
+            }
+        }
+
+        if ranges.is_empty() {
+            self.clear_highlights::<MisspelledWord>(cx);
+            return;
+        }
+        ranges.sort_by(|a, b| a.start.cmp(&b.start, &snapshot));
+        let style = HighlightStyle {
+            underline: Some(UnderlineStyle {
+                thickness: px(1.),
+                color: Some(cx.theme().status().info),
+                wavy: true,
+            }),
+            ..HighlightStyle::default()
+        };
+        self.highlight_text::<MisspelledWord>(ranges, style, cx);
+    }
+
+    /// Computes the replacements for the misspelled word at the given position, if any, on
+    /// a background thread.
+    pub(crate) fn spelling_suggestions_at(
+        &self,
+        position: Anchor,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<SpellingSuggestions>> {
+        let spell_checker = SpellChecker::global(cx)?;
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let (_, ranges) = self.text_highlights::<MisspelledWord>(cx)?;
+        let range = ranges
+            .iter()
+            .find(|range| {
+                range.start.cmp(&position, &snapshot).is_le()
+                    && range.end.cmp(&position, &snapshot).is_ge()
+            })?
+            .clone();
+
+        let word = snapshot.text_for_range(range.clone()).collect::<String>();
+        let worktree_root = range
+            .start
+            .buffer_id
+            .and_then(|buffer_id| multi_buffer.buffer(buffer_id))
+            .and_then(|buffer| {
+                let file = project::File::from_dyn(buffer.read(cx).file())?;
+                Some(file.worktree.read(cx).abs_path())
+            });
+        let spelling = spell_checker.update(cx, |spell_checker, cx| {
+            spell_checker.snapshot(worktree_root.as_ref(), cx)
+        });
+        let suggestions = cx.background_executor().spawn({
+            let word = word.clone();
+            async move { spelling.suggestions(&word, MAX_SUGGESTIONS) }
+        });
+        let editor = cx.view().downgrade();
+        Some(cx.spawn(|_, _| async move {
+            SpellingSuggestions {
+                editor,
+                spell_checker,
+                range,
+                word,
+                worktree_root,
+                suggestions: suggestions.await,
+            }
+        }))
+    }
+
+    fn replace_misspelling(
+        &mut self,
+        range: Range<Anchor>,
+        replacement: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.transact(cx, |editor, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(range, replacement)], None, cx);
+            });
+        });
+    }
+}
+
+/// A misspelled word under the cursor and what it can be replaced with.
+pub(crate) struct SpellingSuggestions {
+    editor: WeakView<Editor>,
+    spell_checker: Model<SpellChecker>,
+    range: Range<Anchor>,
+    word: String,
+    worktree_root: Option<Arc<Path>>,
+    suggestions: Vec<String>,
+}
+
+impl SpellingSuggestions {
+    /// Adds an entry replacing the word for each suggestion to a context menu, followed by
+    /// an entry adding the word to the project's dictionary.
+    pub(crate) fn add_to_menu(self, mut menu: ContextMenu) -> ContextMenu {
+        if self.suggestions.is_empty() {
+            menu = menu.label("No Spelling Suggestions");
+        }
+        for suggestion in self.suggestions {
+            let editor = self.editor.clone();
+            let range = self.range.clone();
+            menu = menu.entry(suggestion.clone(), None, move |cx| {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.replace_misspelling(range.clone(), &suggestion, cx)
+                    })
+                    .ok();
+            });
+        }
+        if let Some(worktree_root) = self.worktree_root {
+            let spell_checker = self.spell_checker;
+            let word = self.word;
+            menu = menu.entry(format!("Add “{word}” to Dictionary"), None, move |cx| {
+                spell_checker
+                    .update(cx, |spell_checker, cx| {
+                        spell_checker.add_to_project_dictionary(
+                            worktree_root.clone(),
+                            word.clone(),
+                            cx,
+                        )
+                    })
+                    .detach_and_log_err(cx);
+            });
+        }
+        menu.separator()
+    }
+}
+
+impl BufferCheck {
+    /// Checks the ranges that are left, returning what was checked in the buffer so far.
+    fn run(self) -> (BufferId, BufferSpelling) {
+        let snapshot = &self.snapshot;
+        let mut misspellings = self.misspellings;
+        misspellings.extend(misspelled_words(
+            snapshot,
+            self.mode,
+            &self.spelling,
+            &self.ranges,
+        ));
+        misspellings.sort_unstable_by_key(|word| word.start);
+        let checked = merge_ranges([self.checked, self.ranges].concat());
+
+        let spelling = BufferSpelling {
+            version: snapshot.version().clone(),
+            checked: checked
+                .into_iter()
+                .map(|range| snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end))
+                .collect(),
+            misspellings: misspellings
+                .into_iter()
+                .map(|word| snapshot.anchor_after(word.start)..snapshot.anchor_before(word.end))
+                .collect(),
+        };
+        (self.buffer_id, spelling)
+    }
+}
+
+/// Finds the misspelled words in the parts of the given ranges that the mode checks.
+fn misspelled_words(
+    snapshot: &BufferSnapshot,
+    mode: SpellCheck,
+    spelling: &SpellingSnapshot,
+    ranges: &[Range<usize>],
+) -> Vec<Range<usize>> {
+    let mut misspellings = Vec::new();
+    for range in ranges {
+        let text_ranges = match mode {
+            SpellCheck::Off => Vec::new(),
+            SpellCheck::CommentsAndStrings => snapshot
+                .comment_and_string_ranges(range.clone())
+                .into_iter()
+                .map(|text_range| text_range.start.max(range.start)..text_range.end.min(range.end))
+                .filter(|text_range| !text_range.is_empty())
+                .collect(),
+            SpellCheck::All => subtract_ranges(
+                &[range.clone()],
+                &snapshot.node_ranges(range.clone(), CODE_BLOCK_KINDS),
+            ),
+        };
+        for text_range in text_ranges {
+            let text = snapshot
+                .text_for_range(text_range.clone())
+                .collect::<String>();
+            for word in words_to_check(&text) {
+                if !spelling.is_correct(&text[word.clone()]) {
+                    misspellings.push(text_range.start + word.start..text_range.start + word.end);
+                }
+            }
+        }
+    }
+    misspellings
+}
+
+/// Extends the range to the start of its first line and the end of its last line.
+fn line_range(range: Range<usize>, snapshot: &BufferSnapshot) -> Range<usize> {
+    let start = snapshot.offset_to_point(range.start);
+    let end = snapshot.offset_to_point(range.end);
+    snapshot.point_to_offset(Point::new(start.row, 0))
+        ..snapshot.point_to_offset(Point::new(end.row, snapshot.line_len(end.row)))
+}
+
+/// Sorts the ranges, merging those that overlap or touch.
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Removes the parts of the sorted ranges that the other sorted ranges cover.
+fn subtract_ranges(ranges: &[Range<usize>], removed: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut result = Vec::new();
+    let mut removed = removed.iter().peekable();
+    for range in ranges {
+        let mut start = range.start;
+        while let Some(removed_range) = removed.peek() {
+            if removed_range.end <= start {
+                removed.next();
+                continue;
+            }
+            if removed_range.start >= range.end {
+                break;
+            }
+            if removed_range.start > start {
+                result.push(start..removed_range.start);
+            }
+            start = start.max(removed_range.end);
+            if removed_range.end > range.end {
+                break;
+            }
+            removed.next();
+        }
+        if start < range.end {
+            result.push(start..range.end);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtract_ranges() {
+        assert_eq!(
+            subtract_ranges(&[0..10, 20..30], &[2..4, 8..22, 25..26]),
+            [0..2, 4..8, 22..25, 26..30]
+        );
+        assert_eq!(subtract_ranges(&[0..10], &[]), [0..10]);
+        assert_eq!(
+            subtract_ranges(&[5..10], &[0..20]),
+            Vec::<Range<usize>>::new()
+        );
+        assert_eq!(merge_ranges(vec![4..6, 0..2, 1..3, 6..8]), [0..3, 4..8]);
+    }
+}
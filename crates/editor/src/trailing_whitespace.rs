@@ -0,0 +1,62 @@
+use std::ops::Range;
+
+use collections::HashSet;
+use gpui::{Hsla, WindowContext};
+use multi_buffer::{MultiBufferRow, ToPoint as _};
+use text::Point;
+use theme::ActiveTheme;
+
+use crate::{display_map::ToDisplayPoint as _, DisplayPoint, DisplaySnapshot, Editor};
+
+impl Editor {
+    /// Returns the whitespace at the ends of the given rows, for buffers whose language
+    /// settings highlight it. Rows with a cursor on them are skipped, so that whitespace
+    /// isn't flagged while it's being typed.
+    pub(crate) fn trailing_whitespace_highlights(
+        &self,
+        buffer_rows: &[Option<MultiBufferRow>],
+        display_snapshot: &DisplaySnapshot,
+        cx: &WindowContext,
+    ) -> Vec<(Range<DisplayPoint>, Hsla)> {
+        let buffer = &display_snapshot.buffer_snapshot;
+        let cursor_rows = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .map(|selection| selection.head().to_point(buffer).row)
+            .collect::<HashSet<_>>();
+        let color = cx.theme().status().error_background;
+
+        let mut highlights = Vec::new();
+        let mut last_row = None;
+        for row in buffer_rows.iter().flatten() {
+            // Soft-wrapped lines span several display rows.
+            if last_row == Some(*row) || cursor_rows.contains(&row.0) {
+                continue;
+            }
+            last_row = Some(*row);
+
+            let line_end = Point::new(row.0, buffer.line_len(*row));
+            let whitespace_len = buffer
+                .reversed_chars_at(line_end)
+                .take_while(|c| matches!(c, ' ' | '\t'))
+                .map(|c| c.len_utf8() as u32)
+                .sum::<u32>();
+            if whitespace_len == 0
+                || !buffer
+                    .settings_at(line_end, cx)
+                    .highlight_trailing_whitespace
+            {
+                continue;
+            }
+
+            let start = Point::new(row.0, line_end.column - whitespace_len);
+            highlights.push((
+                start.to_display_point(display_snapshot)
+                    ..line_end.to_display_point(display_snapshot),
+                color,
+            ));
+        }
+        highlights
+    }
+}
@@ -201,6 +201,11 @@ impl LspAdapter for ExtensionLspAdapter {
         })
     }
 
+    // Extensions read the `lsp` settings themselves.
+    fn workspace_configuration_includes_settings(&self) -> bool {
+        true
+    }
+
     async fn workspace_configuration(
         self: Arc<Self>,
         delegate: &Arc<dyn LspAdapterDelegate>,
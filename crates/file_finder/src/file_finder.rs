@@ -1,11 +1,14 @@
 #[cfg(test)]
 mod file_finder_tests;
 
+mod file_finder_settings;
 mod new_path_prompt;
 mod open_path_prompt;
 
 use collections::{BTreeSet, HashMap};
 use editor::{scroll::Autoscroll, Bias, Editor};
+use file_finder_settings::FileFinderSettings;
+use file_icons::FileIcons;
 use fuzzy::{CharBag, PathMatch, PathMatchCandidate};
 use gpui::{
     actions, rems, Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
@@ -41,6 +44,7 @@ pub struct FileFinder {
 }
 
 pub fn init(cx: &mut AppContext) {
+    FileFinderSettings::register(cx);
     cx.observe_new_views(FileFinder::register).detach();
     cx.observe_new_views(NewPathPrompt::register).detach();
     cx.observe_new_views(OpenPathPrompt::register).detach();
@@ -544,6 +548,22 @@ impl FileFinderDelegate {
         }
     }
 
+    /// Returns the path of the file icon shown next to the match, unless file icons are
+    /// turned off.
+    fn file_icon(path_match: &Match, cx: &AppContext) -> Option<SharedString> {
+        if !FileFinderSettings::get_global(cx).file_icons {
+            return None;
+        }
+        let path = match path_match {
+            Match::History(found_path, _) => found_path
+                .absolute
+                .as_deref()
+                .unwrap_or(found_path.project.path.as_ref()),
+            Match::Search(path_match) => path_match.0.path.as_ref(),
+        };
+        FileIcons::get_icon(path, cx)
+    }
+
     fn labels_for_match(
         &self,
         path_match: &Match,
@@ -966,12 +986,18 @@ impl PickerDelegate for FileFinderDelegate {
                 .size(IconSize::Small.rems())
                 .into_any_element(),
         };
+        let file_icon = Self::file_icon(path_match, cx).map(|icon| {
+            Icon::from_path(icon)
+                .color(Color::Muted)
+                .size(IconSize::Small)
+        });
         let (file_name, file_name_positions, full_path, full_path_positions) =
             self.labels_for_match(path_match, cx, ix);
 
         Some(
             ListItem::new(ix)
                 .spacing(ListItemSpacing::Sparse)
+                .start_slot::<Icon>(file_icon)
                 .end_slot::<AnyElement>(Some(icon))
                 .inset(true)
                 .selected(selected)
@@ -6,6 +6,7 @@ use gpui::{Entity, TestAppContext, VisualTestContext};
 use menu::{Confirm, SelectNext, SelectPrev};
 use project::FS_WATCH_LATENCY;
 use serde_json::json;
+use settings::SettingsStore;
 use workspace::{AppState, ToggleFileFinder, Workspace};
 
 #[ctor::ctor]
@@ -1841,6 +1842,58 @@ async fn open_queried_buffer(
     history_items
 }
 
+#[gpui::test]
+async fn test_file_icons(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    cx.update(|cx| {
+        file_icons::init((), cx);
+        cx.global_mut::<FileIcons>()
+            .insert_icon_theme(
+                Path::new("/icon_themes/mine.json"),
+                r#"{
+                    "name": "Mine",
+                    "suffixes": { "rs": "rust" },
+                    "types": { "rust": { "icon": "mine/rust.svg" } }
+                }"#,
+            )
+            .unwrap();
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<file_icons::FileIconsSettings>(cx, |settings| {
+                settings.icon_theme = Some("Mine".to_string());
+            });
+        });
+    });
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree("/root", json!({ "main.rs": "", "notes.txt": "" }))
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (picker, _, cx) = build_find_picker(project, cx);
+
+    cx.simulate_input("main");
+    picker.update(cx, |picker, cx| {
+        let path_match = picker.delegate.matches.get(0).unwrap();
+        assert_eq!(
+            FileFinderDelegate::file_icon(path_match, cx).as_deref(),
+            Some("/icon_themes/mine/rust.svg")
+        );
+    });
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<FileFinderSettings>(cx, |settings| {
+                settings.file_icons = Some(false);
+            });
+        });
+    });
+    picker.update(cx, |picker, cx| {
+        let path_match = picker.delegate.matches.get(0).unwrap();
+        assert_eq!(FileFinderDelegate::file_icon(path_match, cx), None);
+    });
+}
+
 fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
     cx.update(|cx| {
         let state = AppState::test(cx);
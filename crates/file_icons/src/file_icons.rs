@@ -1,9 +1,15 @@
-use std::{path::Path, str};
+use std::{
+    path::{Component, Path, PathBuf},
+    str,
+};
 
-use collections::HashMap;
+use anyhow::{anyhow, Context, Result};
+use collections::{HashMap, HashSet};
 
 use gpui::{AppContext, AssetSource, Global, SharedString};
-use serde_derive::Deserialize;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
 use util::{maybe, paths::PathExt};
 
 #[derive(Deserialize, Debug)]
@@ -11,22 +17,54 @@ struct TypeConfig {
     icon: SharedString,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct FileIcons {
+/// A mapping of file stems and suffixes to file types, and of file types to icons.
+#[derive(Deserialize, Debug, Default)]
+pub struct IconTheme {
+    #[serde(default)]
     stems: HashMap<String, String>,
+    #[serde(default)]
     suffixes: HashMap<String, String>,
+    #[serde(default)]
     types: HashMap<String, TypeConfig>,
 }
 
+/// The content of a user-installed icon theme.
+#[derive(Deserialize, Debug)]
+struct IconThemeContent {
+    name: String,
+    #[serde(flatten)]
+    theme: IconTheme,
+}
+
+impl IconTheme {
+    fn type_for(&self, stem_or_suffix: &str) -> Option<&str> {
+        self.stems
+            .get(stem_or_suffix)
+            .or_else(|| self.suffixes.get(stem_or_suffix))
+            .map(String::as_str)
+    }
+}
+
+pub struct FileIcons {
+    /// The icon theme bundled with Zed.
+    default: IconTheme,
+    /// The user-installed icon themes, with the file and name of each, in the order the
+    /// files were loaded. When several files have a theme with the same name, the one
+    /// loaded last is used.
+    icon_themes: Vec<(PathBuf, SharedString, IconTheme)>,
+}
+
 impl Global for FileIcons {}
 
 const COLLAPSED_DIRECTORY_TYPE: &str = "collapsed_folder";
 const EXPANDED_DIRECTORY_TYPE: &str = "expanded_folder";
 const COLLAPSED_CHEVRON_TYPE: &str = "collapsed_chevron";
 const EXPANDED_CHEVRON_TYPE: &str = "expanded_chevron";
+const DEFAULT_TYPE: &str = "default";
 pub const FILE_TYPES_ASSET: &str = "icons/file_icons/file_types.json";
 
 pub fn init(assets: impl AssetSource, cx: &mut AppContext) {
+    FileIconsSettings::register(cx);
     cx.set_global(FileIcons::new(assets))
 }
 
@@ -36,64 +74,294 @@ impl FileIcons {
     }
 
     pub fn new(assets: impl AssetSource) -> Self {
+        Self {
+            default: Self::load_default(assets),
+            icon_themes: Vec::new(),
+        }
+    }
+
+    fn load_default(assets: impl AssetSource) -> IconTheme {
         assets
-            .load("icons/file_icons/file_types.json")
+            .load(FILE_TYPES_ASSET)
             .ok()
             .flatten()
-            .and_then(|file| serde_json::from_str::<FileIcons>(str::from_utf8(&file).unwrap()).ok())
-            .unwrap_or_else(|| FileIcons {
-                stems: HashMap::default(),
-                suffixes: HashMap::default(),
-                types: HashMap::default(),
-            })
+            .and_then(|file| serde_json::from_str::<IconTheme>(str::from_utf8(&file).ok()?).ok())
+            .unwrap_or_default()
+    }
+
+    /// Reloads the icon theme bundled with Zed, keeping the user-installed icon themes.
+    pub fn reload_default(&mut self, assets: impl AssetSource) {
+        self.default = Self::load_default(assets);
+    }
+
+    /// Adds the user icon theme with the given file contents, replacing the icon theme
+    /// previously loaded from the same path. Returns the paths of the theme's icons.
+    ///
+    /// Icon paths in the theme are relative to the directory containing it, and can't
+    /// leave that directory.
+    pub fn insert_icon_theme(&mut self, theme_path: &Path, content: &str) -> Result<Vec<PathBuf>> {
+        let mut content: IconThemeContent = serde_json_lenient::from_str(content)
+            .with_context(|| format!("failed to parse icon theme at path {theme_path:?}"))?;
+        let theme_dir = theme_path.parent().unwrap_or(Path::new(""));
+        let mut icon_paths = Vec::new();
+        for type_config in content.theme.types.values_mut() {
+            let icon = Path::new(type_config.icon.as_ref());
+            if !icon
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(anyhow!(
+                    "icon path {icon:?} in icon theme at path {theme_path:?} isn't relative to its directory"
+                ));
+            }
+            let icon_path = theme_dir.join(icon);
+            type_config.icon = icon_path.to_string_lossy().into_owned().into();
+            icon_paths.push(icon_path);
+        }
+
+        self.remove_icon_theme(theme_path);
+        self.icon_themes
+            .push((theme_path.to_path_buf(), content.name.into(), content.theme));
+        Ok(icon_paths)
+    }
+
+    /// Removes the user icon theme loaded from the given path. If another file has an icon
+    /// theme with the same name, that theme is used instead.
+    ///
+    /// Returns whether an icon theme was removed.
+    pub fn remove_icon_theme(&mut self, theme_path: &Path) -> bool {
+        let Some(ix) = self
+            .icon_themes
+            .iter()
+            .position(|(path, _, _)| path == theme_path)
+        else {
+            return false;
+        };
+        self.icon_themes.remove(ix);
+        true
+    }
+
+    /// Returns the names of the user-installed icon themes.
+    pub fn icon_theme_names(&self) -> Vec<SharedString> {
+        let mut names = self
+            .icon_themes
+            .iter()
+            .map(|(_, name, _)| name.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Returns the paths of the icons of all the user-installed icon themes.
+    pub fn icon_paths(&self) -> HashSet<PathBuf> {
+        self.icon_themes
+            .iter()
+            .flat_map(|(_, _, icon_theme)| icon_theme.types.values())
+            .map(|type_config| PathBuf::from(type_config.icon.as_ref()))
+            .collect()
+    }
+
+    /// Returns the icon theme selected in the settings, if it is installed.
+    fn active_icon_theme(&self, cx: &AppContext) -> Option<&IconTheme> {
+        let name = FileIconsSettings::get_global(cx).icon_theme.as_ref()?;
+        self.icon_themes
+            .iter()
+            .rev()
+            .find(|(_, icon_theme_name, _)| icon_theme_name.as_ref() == name.as_str())
+            .map(|(_, _, icon_theme)| icon_theme)
     }
 
     pub fn get_icon(path: &Path, cx: &AppContext) -> Option<SharedString> {
         let this = cx.try_global::<Self>()?;
+        let icon_theme = this.active_icon_theme(cx);
 
         // FIXME: Associate a type with the languages and have the file's language
         //        override these associations
         maybe!({
             let suffix = path.icon_stem_or_suffix()?;
-
-            if let Some(type_str) = this.stems.get(suffix) {
-                return this.get_type_icon(type_str);
-            }
-
-            this.suffixes
-                .get(suffix)
-                .and_then(|type_str| this.get_type_icon(type_str))
+            let type_str = icon_theme
+                .and_then(|icon_theme| icon_theme.type_for(suffix))
+                .or_else(|| this.default.type_for(suffix))?;
+            this.type_icon(icon_theme, type_str)
         })
-        .or_else(|| this.get_type_icon("default"))
+        .or_else(|| this.type_icon(icon_theme, DEFAULT_TYPE))
+    }
+
+    pub fn get_type_icon(typ: &str, cx: &AppContext) -> Option<SharedString> {
+        let this = cx.try_global::<Self>()?;
+        this.type_icon(this.active_icon_theme(cx), typ)
     }
 
-    pub fn get_type_icon(&self, typ: &str) -> Option<SharedString> {
-        self.types
-            .get(typ)
+    fn type_icon(&self, icon_theme: Option<&IconTheme>, typ: &str) -> Option<SharedString> {
+        icon_theme
+            .and_then(|icon_theme| icon_theme.types.get(typ))
+            .or_else(|| self.default.types.get(typ))
             .map(|type_config| type_config.icon.clone())
     }
 
     pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
-        let this = cx.try_global::<Self>()?;
-
         let key = if expanded {
             EXPANDED_DIRECTORY_TYPE
         } else {
             COLLAPSED_DIRECTORY_TYPE
         };
 
-        this.get_type_icon(key)
+        Self::get_type_icon(key, cx)
     }
 
     pub fn get_chevron_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
-        let this = cx.try_global::<Self>()?;
-
         let key = if expanded {
             EXPANDED_CHEVRON_TYPE
         } else {
             COLLAPSED_CHEVRON_TYPE
         };
 
-        this.get_type_icon(key)
+        Self::get_type_icon(key, cx)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FileIconsSettings {
+    pub icon_theme: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct FileIconsSettingsContent {
+    /// The name of the icon theme to use for file icons, from the icon themes
+    /// installed in `~/.config/zed/icon_themes`. Zed's own icons are used when
+    /// this is `null`, and for the files the icon theme doesn't have an icon for.
+    ///
+    /// Default: null
+    pub icon_theme: Option<String>,
+}
+
+impl Settings for FileIconsSettings {
+    const KEY: Option<&'static str> = None;
+
+    type FileContent = FileIconsSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, UpdateGlobal};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_icon_theme(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            init((), cx);
+
+            cx.global_mut::<FileIcons>().default = serde_json::from_str(
+                r#"{
+                    "stems": { "Makefile": "make" },
+                    "suffixes": { "rs": "rust", "toml": "toml" },
+                    "types": {
+                        "default": { "icon": "icons/file.svg" },
+                        "make": { "icon": "icons/make.svg" },
+                        "rust": { "icon": "icons/rust.svg" },
+                        "toml": { "icon": "icons/toml.svg" }
+                    }
+                }"#,
+            )
+            .unwrap();
+            cx.global_mut::<FileIcons>()
+                .insert_icon_theme(
+                    Path::new("/icon_themes/mine/theme.json"),
+                    r#"{
+                        "name": "Mine",
+                        // Files with the `rs` suffix get the icon of the `rust` type.
+                        "suffixes": { "toml": "config" },
+                        "types": {
+                            "rust": { "icon": "rust.svg" },
+                            "config": { "icon": "config.svg" }
+                        }
+                    }"#,
+                )
+                .unwrap();
+            assert_eq!(
+                FileIcons::get(cx).icon_theme_names(),
+                vec![SharedString::from("Mine")]
+            );
+
+            let icon = |path: &str, cx: &AppContext| {
+                FileIcons::get_icon(Path::new(path), cx).map(|icon| icon.to_string())
+            };
+            assert_eq!(icon("a.rs", cx).as_deref(), Some("icons/rust.svg"));
+
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<FileIconsSettings>(cx, |settings| {
+                    settings.icon_theme = Some("Mine".to_string());
+                });
+            });
+            assert_eq!(
+                icon("a.rs", cx).as_deref(),
+                Some("/icon_themes/mine/rust.svg")
+            );
+            assert_eq!(
+                icon("Cargo.toml", cx).as_deref(),
+                Some("/icon_themes/mine/config.svg")
+            );
+            // The icons missing from the icon theme fall back to Zed's own icons.
+            assert_eq!(icon("Makefile", cx).as_deref(), Some("icons/make.svg"));
+            assert_eq!(icon("a.txt", cx).as_deref(), Some("icons/file.svg"));
+
+            // An icon theme with the same name from another file is used instead of the
+            // first one until it's removed.
+            let icon_paths = cx
+                .global_mut::<FileIcons>()
+                .insert_icon_theme(
+                    Path::new("/icon_themes/mine-too/theme.json"),
+                    r#"{
+                        "name": "Mine",
+                        "types": { "rust": { "icon": "icons/rust.svg" } }
+                    }"#,
+                )
+                .unwrap();
+            assert_eq!(
+                icon_paths,
+                [PathBuf::from("/icon_themes/mine-too/icons/rust.svg")]
+            );
+            assert_eq!(
+                FileIcons::get(cx).icon_theme_names(),
+                vec![SharedString::from("Mine")]
+            );
+            assert_eq!(
+                icon("a.rs", cx).as_deref(),
+                Some("/icon_themes/mine-too/icons/rust.svg")
+            );
+            assert!(cx
+                .global_mut::<FileIcons>()
+                .remove_icon_theme(Path::new("/icon_themes/mine-too/theme.json")));
+            assert_eq!(
+                icon("a.rs", cx).as_deref(),
+                Some("/icon_themes/mine/rust.svg")
+            );
+
+            assert!(cx
+                .global_mut::<FileIcons>()
+                .remove_icon_theme(Path::new("/icon_themes/mine/theme.json")));
+            assert_eq!(icon("a.rs", cx).as_deref(), Some("icons/rust.svg"));
+
+            // Icons can't be outside of the icon theme's directory.
+            assert!(cx
+                .global_mut::<FileIcons>()
+                .insert_icon_theme(
+                    Path::new("/icon_themes/mine/theme.json"),
+                    r#"{
+                        "name": "Mine",
+                        "types": { "rust": { "icon": "../../secrets.svg" } }
+                    }"#,
+                )
+                .is_err());
+        });
     }
 }
@@ -23,7 +23,7 @@ use std::{
     time::{Duration, SystemTime},
 };
 use tempfile::{NamedTempFile, TempDir};
-use text::LineEnding;
+use text::{Encoding, LineEnding};
 use util::ResultExt;
 
 #[cfg(any(test, feature = "test-support"))]
@@ -74,6 +74,15 @@ pub trait Fs: Send + Sync {
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    /// Saves the text in the given encoding, in place like [`Fs::save`]. The text is encoded
+    /// before the file is opened, so that a failure to encode it leaves the file untouched.
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -377,6 +386,29 @@ impl Fs for RealFs {
         Ok(())
     }
 
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        if encoding == Encoding::Utf8 {
+            return self.save(path, text, line_ending).await;
+        }
+        // Encode the whole text before opening the file, so that text which can't be
+        // encoded leaves the file untouched.
+        let content = chunks(text, line_ending).collect::<String>();
+        let bytes = encoding.encode(&content)?;
+        if let Some(path) = path.parent() {
+            self.create_dir(path).await?;
+        }
+        let mut file = smol::fs::File::create(path).await?;
+        file.write_all(&bytes).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         Ok(smol::fs::canonicalize(path).await?)
     }
@@ -1477,6 +1509,27 @@ impl Fs for FakeFs {
         Ok(())
     }
 
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        if encoding == Encoding::Utf8 {
+            return self.save(path, text, line_ending).await;
+        }
+        self.simulate_random_delay().await;
+        let path = normalize_path(path);
+        let content = chunks(text, line_ending).collect::<String>();
+        let bytes = encoding.encode(&content)?.into_owned();
+        if let Some(path) = path.parent() {
+            self.create_dir(path).await?;
+        }
+        self.write_file_internal(path, bytes)?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         let path = normalize_path(path);
         self.simulate_random_delay().await;
@@ -1810,6 +1863,34 @@ mod tests {
             "D",
         );
     }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_save_with_encoding_writes_in_place(executor: BackgroundExecutor) {
+        executor.allow_parking();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        let link_path = dir.path().join("link.txt");
+        std::fs::write(&path, b"caf\xe9").unwrap();
+        std::os::unix::fs::symlink(&path, &link_path).unwrap();
+
+        // Saving through a symlink writes to its target instead of replacing it.
+        let fs = RealFs::default();
+        fs.save_with_encoding(
+            &link_path,
+            &Rope::from("café!"),
+            LineEnding::Unix,
+            Encoding::Latin1,
+        )
+        .await
+        .unwrap();
+        assert!(std::fs::symlink_metadata(&link_path)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read(&path).unwrap(), b"caf\xe9!");
+    }
 }
 
 #[cfg(target_os = "linux")]
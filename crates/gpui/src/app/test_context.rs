@@ -678,6 +678,11 @@ impl VisualTestContext {
         self.cx.test_window(self.window).0.lock().title.clone()
     }
 
+    /// Take the messages read out to screen readers (set by `WindowContext#announce`)
+    pub fn take_announcements(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.cx.test_window(self.window).0.lock().announcements)
+    }
+
     /// Simulate a sequence of keystrokes `cx.simulate_keystrokes("cmd-p escape")`
     /// Automatically runs until parked.
     pub fn simulate_keystrokes(&mut self, keystrokes: &str) {
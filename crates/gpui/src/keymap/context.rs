@@ -207,6 +207,13 @@ impl KeyBindingContextPredicate {
     ///
     /// This syntax supports `!=`, `||` and `&&` as logical operators.
     /// You can also preface an operation or check with a `!` to negate it.
+    ///
+    /// Values that aren't identifiers can be quoted. A quote followed by whitespace, `)`, `&`,
+    /// `|` or the end of the source is vim's register operator instead:
+    ///
+    /// `Editor && language == "shell script"` -> A predicate that will match a context with
+    ///                                           the identifier `Editor` and the key `language`
+    ///                                           with the value `shell script`
     pub fn parse(source: &str) -> Result<Self> {
         let source = skip_whitespace(source);
         let (predicate, rest) = Self::parse_expr(source, 0)?;
@@ -304,6 +311,20 @@ impl KeyBindingContextPredicate {
                     source,
                 ))
             }
+            // A quote on its own is vim's register operator.
+            '"' if source[1..]
+                .starts_with(|c: char| !c.is_whitespace() && !matches!(c, ')' | '&' | '|')) =>
+            {
+                let len = source[1..]
+                    .find('"')
+                    .ok_or_else(|| anyhow!("unterminated quoted value"))?;
+                let (value, rest) = source[1..].split_at(len);
+                source = skip_whitespace(&rest[1..]);
+                Ok((
+                    KeyBindingContextPredicate::Identifier(value.to_string().into()),
+                    source,
+                ))
+            }
             _ if is_vim_operator_char(next) => {
                 let (operator, rest) = source.split_at(1);
                 source = skip_whitespace(rest);
@@ -449,6 +470,28 @@ mod tests {
                 .to_string(),
             "operands must be identifiers"
         );
+        assert_eq!(
+            KeyBindingContextPredicate::parse(r#"language == "shell script""#).unwrap(),
+            Equal("language".into(), "shell script".into())
+        );
+        assert_eq!(
+            KeyBindingContextPredicate::parse(r#"vim_operator == ""#).unwrap(),
+            Equal("vim_operator".into(), "\"".into())
+        );
+        assert_eq!(
+            KeyBindingContextPredicate::parse(r#"vim_operator == " && language == "rust""#)
+                .unwrap(),
+            And(
+                Box::new(Equal("vim_operator".into(), "\"".into())),
+                Box::new(Equal("language".into(), "rust".into()))
+            )
+        );
+        assert_eq!(
+            KeyBindingContextPredicate::parse(r#"language == "rust"#)
+                .unwrap_err()
+                .to_string(),
+            "unterminated quoted value"
+        );
     }
 
     #[test]
@@ -1,5 +1,6 @@
 use anyhow::anyhow;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 
 /// A keystroke and associated metadata generated by the platform
@@ -215,7 +216,8 @@ impl std::fmt::Display for Keystroke {
 }
 
 /// The state of the modifier keys at some point in time
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Deserialize, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize, Hash, JsonSchema)]
+#[serde(default)]
 pub struct Modifiers {
     /// The control key
     pub control: bool,
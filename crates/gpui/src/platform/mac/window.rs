@@ -78,6 +78,17 @@ const NSDragOperationNone: NSDragOperation = 0;
 #[allow(non_upper_case_globals)]
 const NSDragOperationCopy: NSDragOperation = 1;
 
+#[allow(non_upper_case_globals)]
+const NSAccessibilityPriorityHigh: NSInteger = 90;
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    static NSAccessibilityAnnouncementRequestedNotification: id;
+    static NSAccessibilityAnnouncementKey: id;
+    static NSAccessibilityPriorityKey: id;
+    fn NSAccessibilityPostNotificationWithUserInfo(element: id, notification: id, user_info: id);
+}
+
 #[link(name = "CoreGraphics", kind = "framework")]
 extern "C" {
     // Widely used private APIs; Apple uses them for their Terminal.app.
@@ -1114,6 +1125,27 @@ impl PlatformWindow for MacWindow {
             .detach();
     }
 
+    fn announce(&self, message: &str) {
+        unsafe {
+            let window = self.0.lock().native_window;
+            let priority: id =
+                msg_send![class!(NSNumber), numberWithInteger: NSAccessibilityPriorityHigh];
+            let user_info = NSDictionary::dictionaryWithObjects_forKeys_(
+                nil,
+                NSArray::arrayWithObjects(nil, &[ns_string(message), priority]),
+                NSArray::arrayWithObjects(
+                    nil,
+                    &[NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey],
+                ),
+            );
+            NSAccessibilityPostNotificationWithUserInfo(
+                window,
+                NSAccessibilityAnnouncementRequestedNotification,
+                user_info,
+            );
+        }
+    }
+
     fn set_app_id(&mut self, _app_id: &str) {}
 
     fn gpu_specs(&self) -> Option<crate::GPUSpecs> {
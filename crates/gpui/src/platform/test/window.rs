@@ -18,6 +18,7 @@ pub(crate) struct TestWindowState {
     display: Rc<dyn PlatformDisplay>,
     pub(crate) title: Option<String>,
     pub(crate) edited: bool,
+    pub(crate) announcements: Vec<String>,
     platform: Weak<TestPlatform>,
     sprite_atlas: Arc<dyn PlatformAtlas>,
     pub(crate) should_close_handler: Option<Box<dyn FnMut() -> bool>>,
@@ -64,6 +65,7 @@ impl TestWindow {
             sprite_atlas: Arc::new(TestAtlas::new()),
             title: Default::default(),
             edited: false,
+            announcements: Vec::new(),
             should_close_handler: None,
             input_callback: None,
             active_status_change_callback: None,
@@ -204,6 +206,10 @@ impl PlatformWindow for TestWindow {
         unimplemented!()
     }
 
+    fn announce(&self, message: &str) {
+        self.0.lock().announcements.push(message.to_owned());
+    }
+
     fn minimize(&self) {
         unimplemented!()
     }
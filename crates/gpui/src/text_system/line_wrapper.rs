@@ -34,6 +34,17 @@ impl LineWrapper {
         &'a mut self,
         line: &'a str,
         wrap_width: Pixels,
+    ) -> impl Iterator<Item = Boundary> + 'a {
+        self.wrap_line_with_hanging_indent(line, wrap_width, 0)
+    }
+
+    /// Wrap a line of text like [`Self::wrap_line`], indenting the wrapped lines by
+    /// `hanging_indent` more columns than the indentation of the line itself.
+    pub fn wrap_line_with_hanging_indent<'a>(
+        &'a mut self,
+        line: &'a str,
+        wrap_width: Pixels,
+        hanging_indent: u32,
     ) -> impl Iterator<Item = Boundary> + 'a {
         let mut width = px(0.);
         let mut first_non_whitespace_ix = None;
@@ -71,9 +82,8 @@ impl LineWrapper {
                 if width > wrap_width && ix > last_wrap_ix {
                     if let (None, Some(first_non_whitespace_ix)) = (indent, first_non_whitespace_ix)
                     {
-                        indent = Some(
-                            Self::MAX_INDENT.min((first_non_whitespace_ix - last_wrap_ix) as u32),
-                        );
+                        let line_indent = (first_non_whitespace_ix - last_wrap_ix) as u32;
+                        indent = Some(Self::MAX_INDENT.min(line_indent + hanging_indent));
                     }
 
                     if last_candidate_ix > 0 {
@@ -249,6 +259,18 @@ mod tests {
                     Boundary::new(22, 3),
                 ]
             );
+            assert_eq!(
+                wrapper
+                    .wrap_line_with_hanging_indent("     aaaaaaa", px(72.), 1)
+                    .collect::<Vec<_>>(),
+                &[
+                    Boundary::new(7, 6),
+                    Boundary::new(8, 6),
+                    Boundary::new(9, 6),
+                    Boundary::new(10, 6),
+                    Boundary::new(11, 6),
+                ]
+            );
         });
     }
 
@@ -1305,6 +1305,11 @@ impl<'a> WindowContext<'a> {
         self.window.platform_window.set_edited(edited);
     }
 
+    /// Asks the screen reader, if one is running, to read out the given message.
+    pub fn announce(&mut self, message: &str) {
+        self.window.platform_window.announce(message);
+    }
+
     /// Determine the display on which the window is visible.
     pub fn display(&self) -> Option<Rc<dyn PlatformDisplay>> {
         self.platform
@@ -0,0 +1,775 @@
+use std::{ops::Range, path::PathBuf};
+
+use anyhow::Result;
+use editor::{
+    actions::{ShowIncomingCalls, ShowOutgoingCalls, ShowSubtypes, ShowSupertypes},
+    scroll::Autoscroll,
+    Editor,
+};
+use gpui::{
+    actions, px, uniform_list, Action, AppContext, AsyncWindowContext, ElementId, EventEmitter,
+    FocusHandle, FocusableView, KeyContext, Model, Pixels, Render, Task, UniformListScrollHandle,
+    View, ViewContext, WeakView,
+};
+use language::{Bias, PointUtf16, ToPointUtf16, Unclipped};
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{HierarchyCall, Project, TypeHierarchyItem};
+use serde::{Deserialize, Serialize};
+use ui::{prelude::*, ListItem};
+use util::ResultExt;
+use workspace::{
+    dock::{read_panel_state, write_panel_state, DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+actions!(
+    hierarchy_panel,
+    [ToggleFocus, ExpandSelectedEntry, CollapseSelectedEntry]
+);
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_panel(cx, HierarchyPanel::load);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<HierarchyPanel>(cx);
+        });
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, cx| {
+            show_hierarchy(workspace, HierarchyDirection::Incoming, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, cx| {
+            show_hierarchy(workspace, HierarchyDirection::Outgoing, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSupertypes, cx| {
+            show_hierarchy(workspace, HierarchyDirection::Supertypes, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, cx| {
+            show_hierarchy(workspace, HierarchyDirection::Subtypes, cx);
+        });
+    })
+    .detach();
+}
+
+/// Shows the call or type hierarchy of the symbol under the active editor's cursor.
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    direction: HierarchyDirection,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, anchor)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    let position = anchor.to_point_utf16(&buffer.read(cx).snapshot());
+    let prepare = workspace.project().update(cx, |project, cx| {
+        if direction.is_call_hierarchy() {
+            map_entries(
+                project.prepare_call_hierarchy(&buffer, position, cx),
+                |item| {
+                    HierarchyEntry::Call(HierarchyCall {
+                        call_site_path: item.path(),
+                        call_sites: Vec::new(),
+                        item,
+                    })
+                },
+                cx,
+            )
+        } else {
+            map_entries(
+                project.prepare_type_hierarchy(&buffer, position, cx),
+                HierarchyEntry::Type,
+                cx,
+            )
+        }
+    });
+    if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(cx) {
+        panel.update(cx, |panel, cx| panel.show(prepare, direction, cx));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyDirection {
+    /// Shows the callers of each function.
+    Incoming,
+    /// Shows the callees of each function.
+    Outgoing,
+    /// Shows the types that each type extends or implements.
+    Supertypes,
+    /// Shows the types that extend or implement each type.
+    Subtypes,
+}
+
+impl HierarchyDirection {
+    fn is_call_hierarchy(self) -> bool {
+        matches!(self, Self::Incoming | Self::Outgoing)
+    }
+}
+
+/// An entry of the tree, which is a call for call hierarchies and a type for type
+/// hierarchies.
+#[derive(Clone)]
+pub enum HierarchyEntry {
+    Call(HierarchyCall),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyEntry {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(call) => call.item.name(),
+            Self::Type(item) => item.name(),
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Call(call) => call.item.detail(),
+            Self::Type(item) => item.detail(),
+        }
+    }
+
+    fn call_sites(&self) -> &[Range<Unclipped<PointUtf16>>] {
+        match self {
+            Self::Call(call) => &call.call_sites,
+            Self::Type(_) => &[],
+        }
+    }
+
+    /// The location of the call site with the given index, or of the entry's own name when
+    /// it has no such call site.
+    fn location(&self, site_ix: usize) -> Option<(PathBuf, Range<Unclipped<PointUtf16>>)> {
+        match self {
+            Self::Call(call) => match call.call_sites.get(site_ix) {
+                Some(call_site) => call
+                    .call_site_path
+                    .clone()
+                    .map(|path| (path, call_site.clone())),
+                None => call
+                    .item
+                    .path()
+                    .map(|path| (path, call.item.selection_range())),
+            },
+            Self::Type(item) => item.path().map(|path| (path, item.selection_range())),
+        }
+    }
+}
+
+/// A panel that shows the callers or callees of a function, or the supertypes or subtypes of
+/// a type, as a tree whose entries are requested from the language server as they are
+/// expanded.
+pub struct HierarchyPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    position: DockPosition,
+    direction: HierarchyDirection,
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+    /// The visible rows, in order, flattened from the expanded nodes.
+    rows: Vec<Row>,
+    selected_row: Option<usize>,
+    /// Incremented whenever the tree is replaced, so that calls requested for the previous
+    /// tree are discarded.
+    generation: usize,
+    loading_roots: bool,
+    message: Option<SharedString>,
+    _load_roots: Task<()>,
+    pending_serialization: Task<()>,
+}
+
+struct Node {
+    entry: HierarchyEntry,
+    expanded: bool,
+    children: Children,
+}
+
+enum Children {
+    NotLoaded,
+    Loading,
+    Loaded(Vec<usize>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Row {
+    Node {
+        node_ix: usize,
+        depth: usize,
+    },
+    /// One of several call sites of an expanded node.
+    CallSite {
+        node_ix: usize,
+        site_ix: usize,
+        depth: usize,
+    },
+    Loading {
+        depth: usize,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+    position: Option<DockPosition>,
+}
+
+impl HierarchyPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<View<Self>> {
+        let serialized =
+            read_panel_state::<SerializedHierarchyPanel>(HIERARCHY_PANEL_KEY, &cx).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized) = serialized {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized.width;
+                    if let Some(position) = serialized.position {
+                        panel.position = position;
+                    }
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let workspace = workspace.weak_handle();
+        cx.new_view(|cx| Self {
+            workspace,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            width: None,
+            position: DockPosition::Right,
+            direction: HierarchyDirection::Incoming,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            rows: Vec::new(),
+            selected_row: None,
+            generation: 0,
+            loading_roots: false,
+            message: None,
+            _load_roots: Task::ready(()),
+            pending_serialization: Task::ready(()),
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let state = SerializedHierarchyPanel {
+            width: self.width,
+            position: Some(self.position),
+        };
+        let write = write_panel_state(HIERARCHY_PANEL_KEY, &state, cx);
+        self.pending_serialization = cx.background_executor().spawn(async move {
+            write.await.log_err();
+        });
+    }
+
+    /// Replaces the tree with the items that the given request resolves to, and expands them
+    /// in the given direction.
+    pub fn show(
+        &mut self,
+        prepare: Task<Result<Vec<HierarchyEntry>>>,
+        direction: HierarchyDirection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.direction = direction;
+        self.clear();
+        self.loading_roots = true;
+        self._load_roots = cx.spawn(|panel, mut cx| async move {
+            let entries = prepare.await;
+            panel
+                .update(&mut cx, |panel, cx| {
+                    panel.loading_roots = false;
+                    match entries {
+                        Ok(entries) if entries.is_empty() => {
+                            panel.message = Some(if direction.is_call_hierarchy() {
+                                "No function or method at the cursor".into()
+                            } else {
+                                "No type at the cursor".into()
+                            });
+                        }
+                        Ok(entries) => panel.set_roots(entries, cx),
+                        Err(error) => panel.message = Some(format!("{error:#}").into()),
+                    }
+                    panel.update_rows(cx);
+                })
+                .log_err();
+        });
+        self.update_rows(cx);
+    }
+
+    fn clear(&mut self) {
+        self.generation += 1;
+        self.nodes.clear();
+        self.roots.clear();
+        self.selected_row = None;
+        self.message = None;
+    }
+
+    fn set_roots(&mut self, entries: Vec<HierarchyEntry>, cx: &mut ViewContext<Self>) {
+        for entry in entries {
+            let node_ix = self.push_node(entry);
+            self.roots.push(node_ix);
+        }
+        for root in self.roots.clone() {
+            self.expand(root, cx);
+        }
+        if !self.roots.is_empty() {
+            self.selected_row = Some(0);
+        }
+    }
+
+    fn set_direction(&mut self, direction: HierarchyDirection, cx: &mut ViewContext<Self>) {
+        if self.direction == direction {
+            return;
+        }
+        self.direction = direction;
+        let roots = self
+            .roots
+            .iter()
+            .map(|root| self.nodes[*root].entry.clone())
+            .collect::<Vec<_>>();
+        self.clear();
+        self.set_roots(roots, cx);
+        self.update_rows(cx);
+    }
+
+    fn push_node(&mut self, entry: HierarchyEntry) -> usize {
+        self.nodes.push(Node {
+            entry,
+            expanded: false,
+            children: Children::NotLoaded,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn expand(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        let node = &mut self.nodes[node_ix];
+        node.expanded = true;
+        if !matches!(node.children, Children::NotLoaded) {
+            return;
+        }
+        node.children = Children::Loading;
+
+        let entry = node.entry.clone();
+        let direction = self.direction;
+        let children = self.project.update(cx, |project, cx| {
+            let children = match (&entry, direction) {
+                (HierarchyEntry::Call(call), HierarchyDirection::Incoming) => {
+                    project.incoming_calls(&call.item, cx)
+                }
+                (HierarchyEntry::Call(call), HierarchyDirection::Outgoing) => {
+                    project.outgoing_calls(&call.item, cx)
+                }
+                (HierarchyEntry::Type(item), HierarchyDirection::Supertypes) => {
+                    return map_entries(project.supertypes(item, cx), HierarchyEntry::Type, cx);
+                }
+                (HierarchyEntry::Type(item), HierarchyDirection::Subtypes) => {
+                    return map_entries(project.subtypes(item, cx), HierarchyEntry::Type, cx);
+                }
+                _ => return Task::ready(Ok(Vec::new())),
+            };
+            map_entries(children, HierarchyEntry::Call, cx)
+        });
+        let generation = self.generation;
+        cx.spawn(|panel, mut cx| async move {
+            let children = children.await.log_err().unwrap_or_default();
+            panel.update(&mut cx, |panel, cx| {
+                if panel.generation != generation {
+                    return;
+                }
+                let children = children
+                    .into_iter()
+                    .map(|entry| panel.push_node(entry))
+                    .collect();
+                panel.nodes[node_ix].children = Children::Loaded(children);
+                panel.update_rows(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn collapse(&mut self, node_ix: usize) {
+        self.nodes[node_ix].expanded = false;
+    }
+
+    fn toggle_expanded(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        if self.nodes[node_ix].expanded {
+            self.collapse(node_ix);
+        } else {
+            self.expand(node_ix, cx);
+        }
+        self.update_rows(cx);
+    }
+
+    fn update_rows(&mut self, cx: &mut ViewContext<Self>) {
+        let selected_row = self.selected_row.and_then(|ix| self.rows.get(ix).copied());
+        self.rows.clear();
+        let mut stack = self
+            .roots
+            .iter()
+            .rev()
+            .map(|root| (*root, 0))
+            .collect::<Vec<_>>();
+        while let Some((node_ix, depth)) = stack.pop() {
+            self.rows.push(Row::Node { node_ix, depth });
+            let node = &self.nodes[node_ix];
+            if !node.expanded {
+                continue;
+            }
+            if node.entry.call_sites().len() > 1 {
+                self.rows.extend(
+                    (0..node.entry.call_sites().len()).map(|site_ix| Row::CallSite {
+                        node_ix,
+                        site_ix,
+                        depth: depth + 1,
+                    }),
+                );
+            }
+            match &node.children {
+                Children::NotLoaded => {}
+                Children::Loading => self.rows.push(Row::Loading { depth: depth + 1 }),
+                Children::Loaded(children) => {
+                    stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+                }
+            }
+        }
+
+        // Keep the same node selected as the rows around it change.
+        self.selected_row = match selected_row {
+            Some(Row::Node { node_ix, .. }) => self
+                .rows
+                .iter()
+                .position(|row| matches!(row, Row::Node { node_ix: ix, .. } if *ix == node_ix)),
+            _ => self
+                .selected_row
+                .filter(|ix| *ix < self.rows.len())
+                .or_else(|| (!self.rows.is_empty()).then_some(0)),
+        };
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_row
+            .map_or(0, |ix| (ix + 1).min(self.rows.len() - 1));
+        self.select_row(ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let ix = self.selected_row.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_row(ix, cx);
+    }
+
+    fn select_row(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_row = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn selected_node(&self) -> Option<usize> {
+        match self.rows.get(self.selected_row?)? {
+            Row::Node { node_ix, .. } => Some(*node_ix),
+            _ => None,
+        }
+    }
+
+    fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node() {
+            self.expand(node_ix, cx);
+            self.update_rows(cx);
+        }
+    }
+
+    fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node() {
+            self.collapse(node_ix);
+            self.update_rows(cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_row {
+            self.open_row(ix, cx);
+        }
+    }
+
+    /// Opens the call site of a row, or the entry itself for types and the roots of the tree.
+    fn open_row(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let (entry, site_ix) = match self.rows.get(ix) {
+            Some(Row::Node { node_ix, .. }) => (&self.nodes[*node_ix].entry, 0),
+            Some(Row::CallSite {
+                node_ix, site_ix, ..
+            }) => (&self.nodes[*node_ix].entry, *site_ix),
+            Some(Row::Loading { .. }) | None => return,
+        };
+        if let Some((path, range)) = entry.location(site_ix) {
+            self.open_location(path, range, cx);
+        }
+    }
+
+    fn open_location(
+        &self,
+        path: PathBuf,
+        range: Range<Unclipped<PointUtf16>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open = workspace.update(cx, |workspace, cx| workspace.open_abs_path(path, true, cx));
+        cx.spawn(|_, mut cx| async move {
+            let item = open.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                    let end = snapshot.clip_point_utf16(range.end, Bias::Right);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([start..end])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_row(&self, ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let selected = self.selected_row == Some(ix);
+        let (id, depth) = match self.rows[ix] {
+            Row::Node { node_ix, depth } => (ElementId::from(("node", node_ix)), depth),
+            Row::CallSite {
+                node_ix,
+                site_ix,
+                depth,
+            } => (
+                ElementId::from(SharedString::from(format!("call-site-{node_ix}-{site_ix}"))),
+                depth,
+            ),
+            Row::Loading { depth } => (ElementId::from(("loading", ix)), depth),
+        };
+        let item = ListItem::new(id)
+            .indent_level(depth)
+            .indent_step_size(px(12.))
+            .selected(selected)
+            .on_click(cx.listener(move |panel, _, cx| {
+                panel.select_row(ix, cx);
+                panel.open_row(ix, cx);
+            }));
+
+        match self.rows[ix] {
+            Row::Node { node_ix, .. } => {
+                let node = &self.nodes[node_ix];
+                let is_leaf = matches!(&node.children, Children::Loaded(children) if children.is_empty())
+                    && node.entry.call_sites().len() <= 1;
+                item.toggle((!is_leaf).then_some(node.expanded))
+                    .on_toggle(cx.listener(move |panel, _, cx| {
+                        panel.toggle_expanded(node_ix, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(node.entry.name().to_string()))
+                            .when_some(node.entry.detail(), |this, detail| {
+                                this.child(
+                                    Label::new(detail.to_string())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .when(node.entry.call_sites().len() > 1, |this| {
+                        this.end_slot(
+                            Label::new(format!("{} calls", node.entry.call_sites().len()))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+            }
+            Row::CallSite {
+                node_ix, site_ix, ..
+            } => {
+                let HierarchyEntry::Call(call) = &self.nodes[node_ix].entry else {
+                    return item;
+                };
+                let file_name = call
+                    .call_site_path
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let row = call.call_sites[site_ix].start.0.row + 1;
+                item.child(Label::new(format!("{file_name}:{row}")).color(Color::Muted))
+            }
+            Row::Loading { .. } => item.child(Label::new("Loading…").color(Color::Muted)),
+        }
+    }
+
+    fn render_direction_button(
+        &self,
+        direction: HierarchyDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> Button {
+        let (id, label) = match direction {
+            HierarchyDirection::Incoming => ("incoming-calls", "Callers"),
+            HierarchyDirection::Outgoing => ("outgoing-calls", "Callees"),
+            HierarchyDirection::Supertypes => ("supertypes", "Supertypes"),
+            HierarchyDirection::Subtypes => ("subtypes", "Subtypes"),
+        };
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .selected(self.direction == direction)
+            .on_click(cx.listener(move |panel, _, cx| panel.set_direction(direction, cx)))
+    }
+}
+
+fn map_entries<T: Send + 'static>(
+    task: Task<Result<Vec<T>>>,
+    entry: fn(T) -> HierarchyEntry,
+    cx: &AppContext,
+) -> Task<Result<Vec<HierarchyEntry>>> {
+    cx.background_executor().spawn(async move {
+        let items = task.await?;
+        Ok(items.into_iter().map(entry).collect())
+    })
+}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let message = if self.loading_roots {
+            Some(SharedString::from("Loading…"))
+        } else if self.roots.is_empty() {
+            Some(self.message.clone().unwrap_or_else(|| {
+                "Show the call or type hierarchy of a symbol from the editor's context menu".into()
+            }))
+        } else {
+            None
+        };
+
+        v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .child(
+                h_flex()
+                    .p_1()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .map(|this| {
+                        if self.direction.is_call_hierarchy() {
+                            this.child(
+                                self.render_direction_button(HierarchyDirection::Incoming, cx),
+                            )
+                            .child(self.render_direction_button(HierarchyDirection::Outgoing, cx))
+                        } else {
+                            this.child(
+                                self.render_direction_button(HierarchyDirection::Supertypes, cx),
+                            )
+                            .child(self.render_direction_button(HierarchyDirection::Subtypes, cx))
+                        }
+                    }),
+            )
+            .map(|this| match message {
+                Some(message) => this.child(
+                    v_flex().size_full().p_4().justify_center().child(
+                        h_flex()
+                            .justify_center()
+                            .child(Label::new(message).color(Color::Muted)),
+                    ),
+                ),
+                None => this.child(
+                    uniform_list(
+                        cx.view().clone(),
+                        "hierarchy-entries",
+                        self.rows.len(),
+                        |panel, range, cx| range.map(|ix| panel.render_row(ix, cx)).collect(),
+                    )
+                    .size_full()
+                    .track_scroll(self.scroll_handle.clone()),
+                ),
+            })
+    }
+}
+
+impl FocusableView for HierarchyPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Hierarchy Panel"
+    }
+
+    fn position(&self, _: &WindowContext) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        self.position = position;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn size(&self, _: &WindowContext) -> Pixels {
+        self.width.unwrap_or(px(300.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &WindowContext) -> Option<IconName> {
+        Some(IconName::Route)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -52,9 +52,9 @@ use text::operation_queue::OperationQueue;
 use text::*;
 pub use text::{
     Anchor, Bias, Buffer as TextBuffer, BufferId, BufferSnapshot as TextBufferSnapshot, Edit,
-    OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection, SelectionGoal,
-    Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint, ToPointUtf16,
-    Transaction, TransactionId, Unclipped,
+    Encoding, OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection,
+    SelectionGoal, Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint,
+    ToPointUtf16, Transaction, TransactionId, Unclipped,
 };
 use theme::SyntaxTheme;
 #[cfg(any(test, feature = "test-support"))]
@@ -94,6 +94,11 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The encoding that the buffer's file is read and written in.
+    encoding: Encoding,
+    /// Whether the buffer's line endings were converted since it was last
+    /// loaded from or saved to disk.
+    has_unsaved_line_ending_change: bool,
     preview_version: clock::Global,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
@@ -386,8 +391,8 @@ pub trait LocalFile: File {
     /// Returns the absolute path of this file
     fn abs_path(&self, cx: &AppContext) -> PathBuf;
 
-    /// Loads the file's contents from disk.
-    fn load(&self, cx: &AppContext) -> Task<Result<String>>;
+    /// Loads the file's contents from disk, decoding them with the given encoding.
+    fn load(&self, encoding: Encoding, cx: &AppContext) -> Task<Result<String>>;
 
     /// Returns true if the file should not be shared with collaborators.
     fn is_private(&self, _: &AppContext) -> bool {
@@ -504,6 +509,7 @@ impl fmt::Debug for ChunkRenderer {
 pub struct Diff {
     pub(crate) base_version: clock::Global,
     line_ending: LineEnding,
+    has_mixed_line_endings: bool,
     edits: Vec<(Range<usize>, Arc<str>)>,
 }
 
@@ -703,6 +709,8 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
+            has_unsaved_line_ending_change: false,
             preview_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -783,6 +791,17 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The encoding that the buffer's file is read and written in.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the encoding that the buffer's file is written in the next time it is saved.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut ModelContext<Self>) {
+        self.encoding = encoding;
+        cx.notify();
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut ModelContext<Self>) {
         self.non_text_state_update_count += 1;
@@ -821,6 +840,8 @@ impl Buffer {
         self.has_unsaved_edits
             .set((self.saved_version().clone(), false));
         self.has_conflict = false;
+        self.has_unsaved_line_ending_change = false;
+        self.text.set_has_mixed_line_endings(false);
         self.saved_mtime = mtime;
         cx.emit(Event::Saved);
         cx.notify();
@@ -832,23 +853,61 @@ impl Buffer {
         cx.notify();
     }
 
+    /// Converts all of the buffer's line endings, which takes effect when it's next saved.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding, cx: &mut ModelContext<Self>) {
+        if line_ending == self.line_ending() && !self.has_mixed_line_endings() {
+            return;
+        }
+
+        let was_dirty = self.is_dirty();
+        self.text.set_line_ending(line_ending);
+        self.text.set_has_mixed_line_endings(false);
+        self.has_unsaved_line_ending_change = true;
+        if !was_dirty {
+            cx.emit(Event::DirtyChanged);
+        }
+        cx.notify();
+    }
+
+    /// Reloads the contents of the buffer from disk, decoding them with the given encoding
+    /// from now on. The buffer keeps its encoding if the file can't be decoded with it.
+    pub fn reload_with_encoding(
+        &mut self,
+        encoding: Encoding,
+        cx: &mut ModelContext<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
+        self.reload_impl(Some(encoding), cx)
+    }
+
     /// Reloads the contents of the buffer from disk.
     pub fn reload(
         &mut self,
         cx: &mut ModelContext<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
+        self.reload_impl(None, cx)
+    }
+
+    fn reload_impl(
+        &mut self,
+        encoding: Option<Encoding>,
+        cx: &mut ModelContext<Self>,
     ) -> oneshot::Receiver<Option<Transaction>> {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
             let Some((new_mtime, new_text)) = this.update(&mut cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
-                Some((file.mtime(), file.load(cx)))
+                let encoding = encoding.unwrap_or(this.encoding);
+                Some((file.mtime(), file.load(encoding, cx)))
             })?
             else {
                 return Ok(());
             };
 
             let new_text = new_text.await?;
+            if let Some(encoding) = encoding {
+                this.update(&mut cx, |this, cx| this.set_encoding(encoding, cx))?;
+            }
             let diff = this
                 .update(&mut cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -890,6 +949,7 @@ impl Buffer {
         self.has_unsaved_edits
             .set((self.saved_version.clone(), false));
         self.text.set_line_ending(line_ending);
+        self.has_unsaved_line_ending_change = false;
         self.saved_mtime = mtime;
         cx.emit(Event::Reloaded);
         cx.notify();
@@ -1411,6 +1471,7 @@ impl Buffer {
             .spawn_labeled(*BUFFER_DIFF_TASK, async move {
                 let old_text = old_text.to_string();
                 let line_ending = LineEnding::detect(&new_text);
+                let has_mixed_line_endings = LineEnding::is_mixed(&new_text);
                 LineEnding::normalize(&mut new_text);
 
                 let diff = TextDiff::from_chars(old_text.as_str(), new_text.as_str());
@@ -1470,6 +1531,7 @@ impl Buffer {
                 Diff {
                     base_version,
                     line_ending,
+                    has_mixed_line_endings,
                     edits,
                 }
             })
@@ -1480,6 +1542,7 @@ impl Buffer {
     pub fn remove_trailing_whitespace(&self, cx: &AppContext) -> Task<Diff> {
         let old_text = self.as_rope().clone();
         let line_ending = self.line_ending();
+        let has_mixed_line_endings = self.has_mixed_line_endings();
         let base_version = self.version();
         cx.background_executor().spawn(async move {
             let ranges = trailing_whitespace_ranges(&old_text);
@@ -1487,6 +1550,7 @@ impl Buffer {
             Diff {
                 base_version,
                 line_ending,
+                has_mixed_line_endings,
                 edits: ranges
                     .into_iter()
                     .map(|range| (range, empty.clone()))
@@ -1551,6 +1615,8 @@ impl Buffer {
 
         self.start_transaction();
         self.text.set_line_ending(diff.line_ending);
+        self.text
+            .set_has_mixed_line_endings(diff.has_mixed_line_endings);
         self.edit(adjusted_edits, None, cx);
         self.end_transaction(cx)
     }
@@ -1573,6 +1639,7 @@ impl Buffer {
     /// Checks if the buffer has unsaved changes.
     pub fn is_dirty(&self) -> bool {
         self.has_conflict
+            || self.has_unsaved_line_ending_change
             || self.has_unsaved_edits()
             || self
                 .file
@@ -2567,6 +2634,65 @@ impl BufferSnapshot {
             .collect();
         (captures, highlight_maps)
     }
+
+    /// Returns the ranges within the given range that the language's highlights query
+    /// captures as comments or strings, merged where they overlap or touch.
+    pub fn comment_and_string_ranges(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let captures = self.syntax.captures(range, &self.text, |grammar| {
+            grammar.highlights_query.as_ref()
+        });
+        let grammars = captures.grammars().to_vec();
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for capture in captures {
+            let Some(query) = grammars[capture.grammar_index].highlights_query.as_ref() else {
+                continue;
+            };
+            let name = query.capture_names()[capture.index as usize];
+            let is_comment_or_string = ["comment", "string"].iter().any(|prefix| {
+                name.strip_prefix(prefix)
+                    .map_or(false, |rest| rest.is_empty() || rest.starts_with('.'))
+            });
+            if !is_comment_or_string || matches!(name, "string.escape" | "string.regex") {
+                continue;
+            }
+            let node_range = capture.node.byte_range();
+            match ranges.last_mut() {
+                Some(last) if last.end >= node_range.start => {
+                    last.end = last.end.max(node_range.end);
+                }
+                _ => ranges.push(node_range),
+            }
+        }
+        ranges
+    }
+
+    /// Returns the ranges of the syntax nodes of the given kinds that intersect the given
+    /// range, in any of the buffer's syntax layers. Nodes within those nodes aren't
+    /// returned.
+    pub fn node_ranges(&self, range: Range<usize>, kinds: &[&str]) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        for layer in self.syntax.layers_for_range(range.clone(), &self.text) {
+            let mut cursor = layer.node().walk();
+            'nodes: loop {
+                let node = cursor.node();
+                let node_range = node.byte_range();
+                let intersects = node_range.start < range.end && node_range.end > range.start;
+                if intersects && kinds.contains(&node.kind()) {
+                    ranges.push(node_range);
+                } else if intersects && cursor.goto_first_child() {
+                    continue;
+                }
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break 'nodes;
+                    }
+                }
+            }
+        }
+        ranges.sort_unstable_by_key(|range| range.start);
+        ranges
+    }
+
     /// Iterates over chunks of text in the given range of the buffer. Text is chunked
     /// in an arbitrary way due to being stored in a [`Rope`](text::Rope). The text is also
     /// returned in chunks where each chunk has a single syntax highlighting style and
@@ -2785,6 +2911,40 @@ impl BufferSnapshot {
         result
     }
 
+    /// Returns the range of the smallest syntax node that strictly contains the given range,
+    /// and whose kind is accepted by `predicate`.
+    pub fn range_for_enclosing_syntax_node<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        mut predicate: impl FnMut(&str) -> bool,
+    ) -> Option<Range<usize>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut result: Option<Range<usize>> = None;
+        for layer in self.syntax.layers_for_range(range.clone(), &self.text) {
+            let mut node = layer
+                .node()
+                .descendant_for_byte_range(range.start, range.end);
+            while let Some(candidate) = node {
+                let node_range = candidate.byte_range();
+                if node_range.start <= range.start
+                    && node_range.end >= range.end
+                    && node_range.len() > range.len()
+                    && predicate(candidate.kind())
+                {
+                    if result
+                        .as_ref()
+                        .map_or(true, |result| node_range.len() < result.len())
+                    {
+                        result = Some(node_range);
+                    }
+                    break;
+                }
+                node = candidate.parent();
+            }
+        }
+        result
+    }
+
     /// Returns the outline for the buffer.
     ///
     /// This method allows passing an optional [SyntaxTheme] to
@@ -3179,6 +3339,49 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges that should be edited together with the one containing the given
+    /// position (such as the names of an opening and a closing tag), according to the
+    /// language's linked edits query. The range containing the position is included.
+    pub fn linked_edit_ranges<T: ToOffset>(&self, position: T) -> Vec<Range<usize>> {
+        let offset = position.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset..offset, self, |grammar| {
+            grammar
+                .linked_edits_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.linked_edits_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut result = Vec::new();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                let mut open = None;
+                let mut close = None;
+                for capture in mat.captures {
+                    if capture.index == config.open_capture_ix {
+                        open = Some(capture.node.byte_range());
+                    } else if capture.index == config.close_capture_ix {
+                        close = Some(capture.node.byte_range());
+                    }
+                }
+                if let Some((open, close)) = open.zip(close) {
+                    if open.to_inclusive().contains(&offset)
+                        || close.to_inclusive().contains(&offset)
+                    {
+                        result = vec![open, close];
+                    }
+                }
+            }
+            syntax_matches.advance();
+        }
+        result
+    }
+
     pub fn injections_intersecting_range<T: ToOffset>(
         &self,
         range: Range<T>,
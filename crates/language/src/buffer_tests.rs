@@ -1104,6 +1104,96 @@ fn test_range_for_syntax_ancestor(cx: &mut AppContext) {
     }
 }
 
+#[gpui::test]
+fn test_linked_edit_ranges(cx: &mut AppContext) {
+    let language = html_lang()
+        .with_linked_edits_query(
+            "
+            (element
+              (start_tag (tag_name) @open)
+              (end_tag (tag_name) @close))
+            ",
+        )
+        .unwrap();
+
+    cx.new_model(|cx| {
+        let text = "<div><span>a</span></div>";
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(language), cx);
+        let snapshot = buffer.snapshot();
+
+        let div_ranges = vec![1..4, 21..24];
+        let span_ranges = vec![6..10, 14..18];
+        assert_eq!(snapshot.linked_edit_ranges(2), div_ranges);
+        assert_eq!(snapshot.linked_edit_ranges(4), div_ranges);
+        assert_eq!(snapshot.linked_edit_ranges(22), div_ranges);
+        assert_eq!(snapshot.linked_edit_ranges(6), span_ranges);
+        assert_eq!(snapshot.linked_edit_ranges(18), span_ranges);
+        assert_eq!(snapshot.linked_edit_ranges(12), Vec::<Range<usize>>::new());
+
+        buffer
+    });
+}
+
+#[gpui::test]
+fn test_comment_and_string_ranges(cx: &mut AppContext) {
+    let language = rust_lang()
+        .with_highlights_query(
+            r#"
+            (line_comment) @comment
+            (string_literal) @string
+            (escape_sequence) @string.escape
+            (identifier) @variable
+            "#,
+        )
+        .unwrap();
+
+    cx.new_model(|cx| {
+        let text = "// A comment\nlet a = \"some text\";\nlet b = a;\n";
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(language), cx);
+        let snapshot = buffer.snapshot();
+
+        assert_eq!(
+            snapshot
+                .comment_and_string_ranges(0..text.len())
+                .into_iter()
+                .map(|range| &text[range])
+                .collect::<Vec<_>>(),
+            ["// A comment\n", "\"some text\""]
+        );
+
+        buffer
+    });
+}
+
+#[gpui::test]
+fn test_node_ranges(cx: &mut AppContext) {
+    cx.new_model(|cx| {
+        let text = "fn a() { \"one\" }\nfn b() { \"two\" }\n";
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx);
+        let snapshot = buffer.snapshot();
+
+        assert_eq!(
+            snapshot
+                .node_ranges(0..text.len(), &["string_literal"])
+                .into_iter()
+                .map(|range| &text[range])
+                .collect::<Vec<_>>(),
+            ["\"one\"", "\"two\""]
+        );
+        let second_line = text.find("fn b").unwrap()..text.len();
+        assert_eq!(
+            snapshot
+                .node_ranges(second_line, &["block", "string_literal"])
+                .into_iter()
+                .map(|range| &text[range])
+                .collect::<Vec<_>>(),
+            ["{ \"two\" }"]
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_autoindent_with_soft_tabs(cx: &mut AppContext) {
     init_settings(cx, |_| {});
@@ -67,7 +67,7 @@ pub use buffer::*;
 pub use diagnostic_set::DiagnosticEntry;
 pub use language_registry::{
     LanguageNotFound, LanguageQueries, LanguageRegistry, LanguageServerBinaryStatus,
-    PendingLanguageServer, QUERY_FILENAME_PREFIXES,
+    PendingLanguageServer, UserLanguageServer, QUERY_FILENAME_PREFIXES,
 };
 pub use lsp::LanguageServerId;
 pub use outline::*;
@@ -235,6 +235,13 @@ impl CachedLspAdapter {
         self.adapter.process_diagnostics(params)
     }
 
+    pub fn inactive_regions(
+        &self,
+        params: &lsp::PublishDiagnosticsParams,
+    ) -> Option<Vec<lsp::Range>> {
+        self.adapter.inactive_regions(params)
+    }
+
     pub async fn process_completions(&self, completion_items: &mut [lsp::CompletionItem]) {
         self.adapter.process_completions(completion_items).await
     }
@@ -424,6 +431,12 @@ pub trait LspAdapter: 'static + Send + Sync {
 
     fn process_diagnostics(&self, _: &mut lsp::PublishDiagnosticsParams) {}
 
+    /// Returns the inactive regions of the document, for servers that report them as
+    /// diagnostics rather than with a notification of their own.
+    fn inactive_regions(&self, _: &lsp::PublishDiagnosticsParams) -> Option<Vec<lsp::Range>> {
+        None
+    }
+
     /// Post-processes completions provided by the language server.
     async fn process_completions(&self, _: &mut [lsp::CompletionItem]) {}
 
@@ -492,6 +505,12 @@ pub trait LspAdapter: 'static + Send + Sync {
         Ok(serde_json::json!({}))
     }
 
+    /// Whether [`LspAdapter::workspace_configuration`] already takes the `settings` of the
+    /// server's `lsp` entry into account. Otherwise, they're merged into its result.
+    fn workspace_configuration_includes_settings(&self) -> bool {
+        false
+    }
+
     /// Returns a list of code actions supported by a given LspAdapter
     fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
         Some(vec![
@@ -856,6 +875,7 @@ pub struct Grammar {
     pub(crate) highlights_query: Option<Query>,
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
+    pub(crate) linked_edits_config: Option<LinkedEditsConfig>,
     pub(crate) runnable_config: Option<RunnableConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
@@ -906,6 +926,12 @@ struct RedactionConfig {
     pub redaction_capture_ix: u32,
 }
 
+struct LinkedEditsConfig {
+    pub query: Query,
+    pub open_capture_ix: u32,
+    pub close_capture_ix: u32,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum RunnableCapture {
     Named(SharedString),
@@ -959,6 +985,7 @@ impl Language {
                     injection_config: None,
                     override_config: None,
                     redactions_config: None,
+                    linked_edits_config: None,
                     runnable_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
@@ -1015,6 +1042,11 @@ impl Language {
                 .with_redaction_query(query.as_ref())
                 .context("Error loading redaction query")?;
         }
+        if let Some(query) = queries.linked_edits {
+            self = self
+                .with_linked_edits_query(query.as_ref())
+                .context("Error loading linked edits query")?;
+        }
         if let Some(query) = queries.runnables {
             self = self
                 .with_runnable_query(query.as_ref())
@@ -1325,6 +1357,33 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_linked_edits_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut open_capture_ix = None;
+        let mut close_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("open", &mut open_capture_ix),
+                ("close", &mut close_capture_ix),
+            ],
+        );
+
+        if let Some((open_capture_ix, close_capture_ix)) = open_capture_ix.zip(close_capture_ix) {
+            grammar.linked_edits_config = Some(LinkedEditsConfig {
+                query,
+                open_capture_ix,
+                close_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> Option<&mut Grammar> {
         Arc::get_mut(self.grammar.as_mut()?)
     }
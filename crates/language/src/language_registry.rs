@@ -16,12 +16,13 @@ use futures::{
 };
 use globset::GlobSet;
 use gpui::{AppContext, BackgroundExecutor, Task};
-use lsp::LanguageServerId;
+use lsp::{LanguageServerBinary, LanguageServerId};
 use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use std::{
     borrow::Cow,
     ffi::OsStr,
+    net::SocketAddr,
     ops::Not,
     path::{Path, PathBuf},
     sync::Arc,
@@ -45,6 +46,9 @@ struct LanguageRegistryState {
     languages: Vec<Arc<Language>>,
     language_settings: AllLanguageSettingsContent,
     available_languages: Vec<AvailableLanguage>,
+    /// The languages that overrides replaced, by name, or `None` for overrides that didn't
+    /// replace a language.
+    overridden_languages: HashMap<Arc<str>, Option<AvailableLanguage>>,
     grammars: HashMap<Arc<str>, AvailableGrammar>,
     lsp_adapters: HashMap<Arc<str>, Vec<Arc<CachedLspAdapter>>>,
     available_lsp_adapters:
@@ -74,6 +78,17 @@ pub struct PendingLanguageServer {
     pub container_dir: Option<Arc<Path>>,
 }
 
+/// A language server that the user configured to be used instead of the one that the
+/// adapter would install.
+#[derive(Clone, Debug)]
+pub struct UserLanguageServer {
+    /// The command that starts the server, if it isn't already running.
+    pub binary: Option<LanguageServerBinary>,
+    /// The address to connect to the server at, rather than talking to it over the standard
+    /// input and output of its process.
+    pub tcp_address: Option<SocketAddr>,
+}
+
 #[derive(Clone)]
 struct AvailableLanguage {
     id: LanguageId,
@@ -125,11 +140,12 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("injections", |q| &mut q.injections),
     ("overrides", |q| &mut q.overrides),
     ("redactions", |q| &mut q.redactions),
+    ("linked_edits", |q| &mut q.linked_edits),
     ("runnables", |q| &mut q.runnables),
 ];
 
 /// Tree-sitter language queries for a given language.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct LanguageQueries {
     pub highlights: Option<Cow<'static, str>>,
     pub brackets: Option<Cow<'static, str>>,
@@ -139,6 +155,7 @@ pub struct LanguageQueries {
     pub injections: Option<Cow<'static, str>>,
     pub overrides: Option<Cow<'static, str>>,
     pub redactions: Option<Cow<'static, str>>,
+    pub linked_edits: Option<Cow<'static, str>>,
     pub runnables: Option<Cow<'static, str>>,
 }
 
@@ -154,6 +171,7 @@ impl LanguageRegistry {
                 next_language_server_id: 0,
                 languages: Vec::new(),
                 available_languages: Vec::new(),
+                overridden_languages: Default::default(),
                 grammars: Default::default(),
                 language_settings: Default::default(),
                 loading_languages: Default::default(),
@@ -188,6 +206,12 @@ impl LanguageRegistry {
         self.state.write().reload();
     }
 
+    /// Clears out the given loaded languages, so that they're loaded again when needed,
+    /// keeping the other loaded languages.
+    pub fn reload_languages(&self, languages_to_reload: &[Arc<str>]) {
+        self.state.write().reload_languages(languages_to_reload);
+    }
+
     /// Reorders the list of language servers for the given language.
     ///
     /// Uses the provided list of ordered [`CachedLspAdapters`] as the desired order.
@@ -346,6 +370,63 @@ impl LanguageRegistry {
         *state.subscription.0.borrow_mut() = ();
     }
 
+    /// Adds a language like [`Self::register_language`], keeping the language with the same
+    /// name that it replaces, so that [`Self::remove_language_overrides`] can restore it.
+    pub fn register_language_override(
+        &self,
+        name: Arc<str>,
+        grammar_name: Option<Arc<str>>,
+        matcher: LanguageMatcher,
+        load: impl Fn() -> Result<(
+                LanguageConfig,
+                LanguageQueries,
+                Option<Arc<dyn ContextProvider>>,
+            )>
+            + 'static
+            + Send
+            + Sync,
+    ) {
+        {
+            let state = &mut *self.state.write();
+            if !state.overridden_languages.contains_key(&name) {
+                let overridden = state
+                    .available_languages
+                    .iter()
+                    .find(|language| language.name == name)
+                    .cloned();
+                state.overridden_languages.insert(name.clone(), overridden);
+            }
+        }
+        self.register_language(name, grammar_name, matcher, load);
+    }
+
+    /// Removes the given language overrides, registering the languages that they replaced
+    /// again.
+    pub fn remove_language_overrides(&self, names: &[Arc<str>]) {
+        let state = &mut *self.state.write();
+        let mut languages_to_remove = Vec::new();
+        for name in names {
+            match state.overridden_languages.remove(name) {
+                Some(Some(overridden)) => {
+                    state.languages.retain(|language| language.name() != *name);
+                    for language in &mut state.available_languages {
+                        if language.name == *name {
+                            language.grammar = overridden.grammar.clone();
+                            language.matcher = overridden.matcher.clone();
+                            language.load = overridden.load.clone();
+                            language.loaded = false;
+                        }
+                    }
+                }
+                Some(None) | None => languages_to_remove.push(name.clone()),
+            }
+        }
+        state.remove_languages(&languages_to_remove, &[]);
+        state.version += 1;
+        state.reload_count += 1;
+        *state.subscription.0.borrow_mut() = ();
+    }
+
     /// Adds grammars to the registry. Language configurations reference a grammar by name. The
     /// grammar controls how the source code is parsed.
     pub fn register_native_grammars(
@@ -726,6 +807,7 @@ impl LanguageRegistry {
         adapter: Arc<CachedLspAdapter>,
         root_path: Arc<Path>,
         delegate: Arc<dyn LspAdapterDelegate>,
+        user_server: Option<UserLanguageServer>,
         cx: &mut AppContext,
     ) -> Option<PendingLanguageServer> {
         let server_id = self.state.write().next_language_server_id();
@@ -752,15 +834,20 @@ impl LanguageRegistry {
                 // the login shell to be set on our process.
                 login_shell_env_loaded.await;
 
-                let binary_result = adapter
-                    .clone()
-                    .get_language_server_command(
-                        language.clone(),
-                        container_dir,
-                        delegate.clone(),
-                        &mut cx,
-                    )
-                    .await;
+                let tcp_address = user_server.as_ref().and_then(|server| server.tcp_address);
+                let binary_result = match user_server {
+                    Some(user_server) => Ok(user_server.binary),
+                    None => adapter
+                        .clone()
+                        .get_language_server_command(
+                            language.clone(),
+                            container_dir,
+                            delegate.clone(),
+                            &mut cx,
+                        )
+                        .await
+                        .map(Some),
+                };
 
                 delegate.update_status(adapter.name.clone(), LanguageServerBinaryStatus::None);
 
@@ -787,7 +874,11 @@ impl LanguageRegistry {
 
                     let (server, mut fake_server) = lsp::FakeLanguageServer::new(
                         server_id,
-                        binary,
+                        binary.unwrap_or_else(|| LanguageServerBinary {
+                            path: PathBuf::new(),
+                            arguments: Vec::new(),
+                            env: None,
+                        }),
                         adapter.name.0.to_string(),
                         capabilities,
                         cx.clone(),
@@ -826,17 +917,29 @@ impl LanguageRegistry {
                 }
 
                 drop(this);
-                Ok((
-                    lsp::LanguageServer::new(
+                let server = match tcp_address {
+                    Some(address) => {
+                        lsp::LanguageServer::new_tcp(
+                            stderr_capture,
+                            server_id,
+                            binary,
+                            address,
+                            &root_path,
+                            adapter.code_action_kinds(),
+                            cx,
+                        )
+                        .await?
+                    }
+                    None => lsp::LanguageServer::new(
                         stderr_capture,
                         server_id,
-                        binary,
+                        binary.context("no language server binary to start")?,
                         &root_path,
                         adapter.code_action_kinds(),
                         cx,
                     )?,
-                    options,
-                ))
+                };
+                Ok((server, options))
             }
         });
 
@@ -913,6 +1016,22 @@ impl LanguageRegistryState {
         *self.subscription.0.borrow_mut() = ();
     }
 
+    fn reload_languages(&mut self, languages_to_reload: &[Arc<str>]) {
+        if languages_to_reload.is_empty() {
+            return;
+        }
+        self.languages
+            .retain(|language| !languages_to_reload.contains(&language.name()));
+        for language in &mut self.available_languages {
+            if languages_to_reload.contains(&language.name) {
+                language.loaded = false;
+            }
+        }
+        self.version += 1;
+        self.reload_count += 1;
+        *self.subscription.0.borrow_mut() = ();
+    }
+
     /// Reorders the list of language servers for the given language.
     ///
     /// Uses the provided list of ordered [`CachedLspAdapters`] as the desired order.
@@ -5,7 +5,8 @@ use anyhow::Result;
 use collections::{HashMap, HashSet};
 use core::slice;
 use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
-use gpui::AppContext;
+use gpui::{AppContext, Modifiers};
+use indexmap::IndexMap;
 use itertools::{Either, Itertools};
 use schemars::{
     schema::{InstanceType, ObjectValidation, Schema, SchemaObject, SingleOrVec},
@@ -76,6 +77,10 @@ pub struct LanguageSettings {
     /// The column at which to soft-wrap lines, for buffers where soft-wrap
     /// is enabled.
     pub preferred_line_length: u32,
+    /// How many more columns than the line itself soft-wrapped lines are indented by.
+    pub soft_wrap_hanging_indent: u32,
+    /// Whether to mark soft-wrapped lines in the gutter.
+    pub soft_wrap_indicator: bool,
     // Whether to show wrap guides (vertical rulers) in the editor.
     // Setting this to true will show a guide at the 'preferred_line_length' value
     // if softwrap is set to 'preferred_line_length', and will show any
@@ -85,6 +90,8 @@ pub struct LanguageSettings {
     pub wrap_guides: Vec<usize>,
     /// Indent guide related settings.
     pub indent_guides: IndentGuideSettings,
+    /// When to save buffers automatically.
+    pub autosave: AutosaveSetting,
     /// Whether or not to perform a buffer format before saving.
     pub format_on_save: FormatOnSave,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
@@ -111,6 +118,17 @@ pub struct LanguageSettings {
     pub show_inline_completions: bool,
     /// Whether to show tabs and spaces in the editor.
     pub show_whitespaces: ShowWhitespaceSetting,
+    /// Whether to highlight whitespace at the end of lines.
+    pub highlight_trailing_whitespace: bool,
+    /// Whether to color pairs of brackets by how deeply they're nested.
+    pub colorize_brackets: bool,
+    /// Whether to dim the code that language servers report as inactive.
+    pub dim_inactive_regions: bool,
+    /// Whether to ask language servers to format the code after typing one of their
+    /// trigger characters.
+    pub use_on_type_format: bool,
+    /// Which parts of buffers to check the spelling of.
+    pub spell_check: SpellCheck,
     /// Whether to start a new line with a comment when a previous line is a comment as well.
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
@@ -119,12 +137,20 @@ pub struct LanguageSettings {
     pub use_autoclose: bool,
     /// Whether to automatically surround text with brackets.
     pub use_auto_surround: bool,
+    /// Whether to re-indent pasted text to match where it's pasted.
+    pub auto_indent_on_paste: bool,
     // Controls how the editor handles the autoclosed characters.
     pub always_treat_brackets_as_autoclosed: bool,
-    /// Which code actions to run on save
-    pub code_actions_on_format: HashMap<String, bool>,
+    /// Which code actions to run on save, in the order they were declared.
+    pub code_actions_on_format: IndexMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to highlight code using semantic tokens from language servers.
+    pub semantic_tokens: bool,
+    /// Whether to show color swatches next to color literals.
+    pub color_swatches: bool,
+    /// Whether to show code lenses from language servers above declarations.
+    pub code_lens: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskConfig,
 }
@@ -242,6 +268,18 @@ pub struct LanguageSettingsContent {
     /// Default: 80
     #[serde(default)]
     pub preferred_line_length: Option<u32>,
+    /// How many more columns than the line itself soft-wrapped lines are indented by.
+    /// Wrapped lines always keep the indentation of the line they belong to.
+    ///
+    /// Default: 0
+    #[serde(default)]
+    pub soft_wrap_hanging_indent: Option<u32>,
+    /// Whether to mark soft-wrapped lines with an indicator in the gutter, where
+    /// their line number would be.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub soft_wrap_indicator: Option<bool>,
     /// Whether to show wrap guides in the editor. Setting this to true will
     /// show a guide at the 'preferred_line_length' value if softwrap is set to
     /// 'preferred_line_length', and will show any additional guides as specified
@@ -258,6 +296,12 @@ pub struct LanguageSettingsContent {
     /// Indent guide related settings.
     #[serde(default)]
     pub indent_guides: Option<IndentGuideSettings>,
+    /// When to save buffers automatically. Saves made after a delay are
+    /// not formatted, so that text doesn't move while it's being typed.
+    ///
+    /// Default: off
+    #[serde(default)]
+    pub autosave: Option<AutosaveSetting>,
     /// Whether or not to perform a buffer format before saving.
     ///
     /// Default: on
@@ -311,6 +355,36 @@ pub struct LanguageSettingsContent {
     /// Whether to show tabs and spaces in the editor.
     #[serde(default)]
     pub show_whitespaces: Option<ShowWhitespaceSetting>,
+    /// Whether to highlight whitespace at the end of lines. Lines with a cursor
+    /// on them aren't highlighted, so that whitespace isn't flagged while typing.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub highlight_trailing_whitespace: Option<bool>,
+    /// Whether to color pairs of brackets by how deeply they're nested, using the
+    /// accent colors of the theme.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub colorize_brackets: Option<bool>,
+    /// Whether to dim the code that language servers report as inactive, such as code
+    /// excluded by `#ifdef` directives or `#[cfg]` attributes.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub dim_inactive_regions: Option<bool>,
+    /// Whether to use additional LSP queries to format (and amend) the code after
+    /// every "trigger" symbol input, defined by LSP server capabilities.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub use_on_type_format: Option<bool>,
+    /// Which parts of buffers to check the spelling of, against the dictionaries
+    /// configured in the `spellcheck` settings.
+    ///
+    /// Default: off
+    #[serde(default)]
+    pub spell_check: Option<SpellCheck>,
     /// Whether to start a new line with a comment when a previous line is a comment as well.
     ///
     /// Default: true
@@ -329,6 +403,13 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub use_auto_surround: Option<bool>,
+    /// Whether to re-indent multi-line text when it's pasted, keeping the relative
+    /// indentation of its lines, so that it fits the indentation where it's pasted.
+    /// The `editor::PasteWithoutReformatting` action always pastes text as is.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub auto_indent_on_paste: Option<bool>,
     // Controls how the editor handles the autoclosed characters.
     // When set to `false`(default), skipping over and auto-removing of the closing characters
     // happen only for auto-inserted characters.
@@ -340,13 +421,31 @@ pub struct LanguageSettingsContent {
     /// Which code actions to run on save after the formatter.
     /// These are not run if formatting is off.
     ///
+    /// Code actions are requested one kind at a time, in the order they are listed.
+    ///
     /// Default: {} (or {"source.organizeImports": true} for Go).
-    pub code_actions_on_format: Option<HashMap<String, bool>>,
+    pub code_actions_on_format: Option<IndexMap<String, bool>>,
     /// Whether to perform linked edits of associated ranges, if the language server supports it.
     /// For example, when editing opening <html> tag, the contents of the closing </html> tag will be edited as well.
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether to highlight code using semantic tokens from language servers, on top of
+    /// the syntax highlighting provided by Tree-sitter.
+    ///
+    /// Default: false
+    pub semantic_tokens: Option<bool>,
+    /// Whether to show color swatches next to color literals, which open a color picker
+    /// when clicked. Colors are provided by language servers when they support it, and
+    /// detected from hex, `rgb()` and `hsl()` literals in CSS-like languages otherwise.
+    ///
+    /// Default: false
+    pub color_swatches: Option<bool>,
+    /// Whether to show code lenses from language servers above declarations, such as
+    /// reference counts or commands to run tests.
+    ///
+    /// Default: true
+    pub code_lens: Option<bool>,
     /// Task configuration for this language.
     ///
     /// Default: {}
@@ -497,6 +596,34 @@ impl<'de> Deserialize<'de> for FormatOnSave {
     }
 }
 
+/// Controls when buffers are saved automatically.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AutosaveSetting {
+    /// Disable autosave.
+    Off,
+    /// Save after inactivity period of `milliseconds`.
+    AfterDelay { milliseconds: u64 },
+    /// Autosave when focus changes.
+    OnFocusChange,
+    /// Autosave when the active window changes.
+    OnWindowChange,
+}
+
+/// Which parts of a buffer are spell checked.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpellCheck {
+    /// Don't check spelling.
+    Off,
+    /// Check the spelling of the comments and strings that the language's syntax
+    /// highlighting recognizes.
+    CommentsAndStrings,
+    /// Check the spelling of the whole buffer, for prose, except for the code blocks of
+    /// Markdown.
+    All,
+}
+
 /// Controls how whitespace should be displayedin the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -651,8 +778,9 @@ pub enum Formatter {
         /// The arguments to pass to the program.
         arguments: Arc<[String]>,
     },
-    /// Files should be formatted using code actions executed by language servers.
-    CodeActions(HashMap<String, bool>),
+    /// Files should be formatted using code actions executed by language servers,
+    /// in the order they are listed.
+    CodeActions(IndexMap<String, bool>),
 }
 
 /// The settings for indent guides.
@@ -754,6 +882,13 @@ pub struct InlayHintSettings {
     /// Default: 50
     #[serde(default = "scroll_debounce_ms")]
     pub scroll_debounce_ms: u64,
+    /// Toggles inlay hints (hides or shows) while the given modifiers are held down,
+    /// e.g. `{ "control": true, "alt": true }`.
+    /// Releasing any of the modifiers switches hints back to their previous state.
+    ///
+    /// Default: None
+    #[serde(default)]
+    pub toggle_on_modifiers_press: Option<Modifiers>,
 }
 
 fn edit_debounce_ms() -> u64 {
@@ -1030,6 +1165,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
     merge(&mut settings.soft_wrap, src.soft_wrap);
     merge(&mut settings.use_autoclose, src.use_autoclose);
     merge(&mut settings.use_auto_surround, src.use_auto_surround);
+    merge(&mut settings.auto_indent_on_paste, src.auto_indent_on_paste);
     merge(
         &mut settings.always_treat_brackets_as_autoclosed,
         src.always_treat_brackets_as_autoclosed,
@@ -1042,14 +1178,23 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.code_actions_on_format.clone(),
     );
     merge(&mut settings.linked_edits, src.linked_edits);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    merge(&mut settings.color_swatches, src.color_swatches);
+    merge(&mut settings.code_lens, src.code_lens);
     merge(&mut settings.tasks, src.tasks.clone());
 
     merge(
         &mut settings.preferred_line_length,
         src.preferred_line_length,
     );
+    merge(
+        &mut settings.soft_wrap_hanging_indent,
+        src.soft_wrap_hanging_indent,
+    );
+    merge(&mut settings.soft_wrap_indicator, src.soft_wrap_indicator);
     merge(&mut settings.formatter, src.formatter.clone());
     merge(&mut settings.prettier, src.prettier.clone());
+    merge(&mut settings.autosave, src.autosave);
     merge(&mut settings.format_on_save, src.format_on_save.clone());
     merge(
         &mut settings.remove_trailing_whitespace_on_save,
@@ -1069,6 +1214,14 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.show_inline_completions,
     );
     merge(&mut settings.show_whitespaces, src.show_whitespaces);
+    merge(
+        &mut settings.highlight_trailing_whitespace,
+        src.highlight_trailing_whitespace,
+    );
+    merge(&mut settings.colorize_brackets, src.colorize_brackets);
+    merge(&mut settings.dim_inactive_regions, src.dim_inactive_regions);
+    merge(&mut settings.use_on_type_format, src.use_on_type_format);
+    merge(&mut settings.spell_check, src.spell_check);
     merge(
         &mut settings.extend_comment_on_newline,
         src.extend_comment_on_newline,
@@ -1139,6 +1292,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_code_actions_on_format_preserve_order() {
+        let raw = r#"{
+            "code_actions_on_format": {
+                "source.organizeImports": true,
+                "source.fixAll": true,
+                "source.addMissingImports": false
+            }
+        }"#;
+        let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            settings
+                .code_actions_on_format
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![
+                ("source.organizeImports".to_string(), true),
+                ("source.fixAll".to_string(), true),
+                ("source.addMissingImports".to_string(), false),
+            ]
+        );
+    }
+
     #[test]
     pub fn test_resolve_language_servers() {
         fn language_server_names(names: &[&str]) -> Vec<LanguageServerName> {
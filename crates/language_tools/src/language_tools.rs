@@ -1,3 +1,4 @@
+mod lsp_inspector;
 mod lsp_log;
 mod syntax_tree_view;
 
@@ -6,6 +7,7 @@ mod lsp_log_tests;
 
 use gpui::AppContext;
 
+pub use lsp_inspector::LspInspector;
 pub use lsp_log::{LogStore, LspLogToolbarItemView, LspLogView};
 pub use syntax_tree_view::{SyntaxTreeToolbarItemView, SyntaxTreeView};
 
@@ -0,0 +1,686 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use gpui::{
+    actions, px, uniform_list, Action, AnchorCorner, AppContext, AsyncWindowContext, EventEmitter,
+    FocusHandle, FocusableView, KeyContext, Model, Pixels, Render, Subscription, Task,
+    UniformListScrollHandle, View, ViewContext, WeakView,
+};
+use language::LanguageServerId;
+use menu::{SelectNext, SelectPrev};
+use project::Project;
+use serde::{Deserialize, Serialize};
+use ui::{prelude::*, ContextMenu, ListItem, PopoverMenu, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{read_panel_state, write_panel_state, DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::lsp_log::{LogStore, MessageKind, RpcEntry};
+
+actions!(lsp_inspector, [ToggleFocus, ReplayRequest]);
+
+const LSP_INSPECTOR_KEY: &str = "LspInspector";
+
+pub(crate) fn init(log_store: Model<LogStore>, cx: &mut AppContext) {
+    workspace::register_panel(cx, move |workspace, cx| {
+        LspInspector::load(workspace, log_store.clone(), cx)
+    });
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<LspInspector>(cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InspectorMode {
+    /// The JSON-RPC messages exchanged with the server.
+    Traffic,
+    /// What the server wrote to its stderr.
+    Stderr,
+}
+
+/// A panel to diagnose a language server, by inspecting the messages that Zed exchanges with
+/// it and what it writes to its stderr, and by sending its requests again.
+pub struct LspInspector {
+    project: Model<Project>,
+    log_store: Model<LogStore>,
+    focus_handle: FocusHandle,
+    filter_editor: View<Editor>,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    position: DockPosition,
+    server_id: Option<LanguageServerId>,
+    mode: InspectorMode,
+    /// The ids of the traffic entries, or of the stderr lines, that match the filter.
+    visible_entries: Vec<usize>,
+    /// The id of the first entry that hasn't been matched against the filter yet, as new
+    /// entries are only filtered once they come in.
+    next_entry_id: usize,
+    selected_entry: Option<usize>,
+    replay_error: Option<SharedString>,
+    pending_replay: Task<()>,
+    pending_serialization: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedLspInspector {
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    position: Option<DockPosition>,
+}
+
+impl LspInspector {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        log_store: Model<LogStore>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<View<Self>> {
+        let serialized = read_panel_state::<SerializedLspInspector>(LSP_INSPECTOR_KEY, &cx).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let inspector = cx.new_view(|cx| Self::new(project, log_store, cx));
+            if let Some(serialized) = serialized {
+                inspector.update(cx, |inspector, cx| {
+                    inspector.width = serialized.width;
+                    inspector.height = serialized.height;
+                    if let Some(position) = serialized.position {
+                        inspector.position = position;
+                    }
+                    cx.notify();
+                });
+            }
+            inspector
+        })
+    }
+
+    fn new(
+        project: Model<Project>,
+        log_store: Model<LogStore>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter by method…", cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.observe(&log_store, |inspector, _, cx| {
+                inspector.update_visible_entries(cx);
+            }),
+            cx.subscribe(&filter_editor, |inspector, _, event, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    inspector.reset_visible_entries(cx);
+                }
+            }),
+        ];
+        let mut inspector = Self {
+            project,
+            log_store,
+            focus_handle: cx.focus_handle(),
+            filter_editor,
+            scroll_handle: UniformListScrollHandle::new(),
+            width: None,
+            height: None,
+            position: DockPosition::Bottom,
+            server_id: None,
+            mode: InspectorMode::Traffic,
+            visible_entries: Vec::new(),
+            next_entry_id: 0,
+            selected_entry: None,
+            replay_error: None,
+            pending_replay: Task::ready(()),
+            pending_serialization: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        inspector.update_visible_entries(cx);
+        inspector
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let state = SerializedLspInspector {
+            width: self.width,
+            height: self.height,
+            position: Some(self.position),
+        };
+        let write = write_panel_state(LSP_INSPECTOR_KEY, &state, cx);
+        self.pending_serialization = cx.background_executor().spawn(async move {
+            write.await.log_err();
+        });
+    }
+
+    /// The project's language servers whose messages are being captured, with their names.
+    fn servers(&self, cx: &AppContext) -> Vec<(LanguageServerId, SharedString)> {
+        let project = self.project.read(cx);
+        let log_store = self.log_store.read(cx);
+        let mut servers = project
+            .language_servers()
+            .filter_map(|(server_id, name, worktree_id)| {
+                let worktree = project.worktree_for_id(worktree_id, cx)?;
+                let label = format!("{} ({})", name.0, worktree.read(cx).root_name());
+                Some((server_id, SharedString::from(label)))
+            })
+            .chain(
+                project
+                    .supplementary_language_servers()
+                    .map(|(server_id, name)| (*server_id, SharedString::from(name.0.to_string()))),
+            )
+            .filter(|(server_id, _)| log_store.has_language_server(*server_id))
+            .collect::<Vec<_>>();
+        servers.sort_by_key(|(server_id, _)| *server_id);
+        servers.dedup_by_key(|(server_id, _)| *server_id);
+        servers
+    }
+
+    fn select_server(&mut self, server_id: LanguageServerId, cx: &mut ViewContext<Self>) {
+        self.server_id = Some(server_id);
+        self.selected_entry = None;
+        self.replay_error = None;
+        self.reset_visible_entries(cx);
+    }
+
+    fn set_mode(&mut self, mode: InspectorMode, cx: &mut ViewContext<Self>) {
+        self.mode = mode;
+        self.selected_entry = None;
+        let placeholder = match mode {
+            InspectorMode::Traffic => "Filter by method…",
+            InspectorMode::Stderr => "Filter lines…",
+        };
+        self.filter_editor.update(cx, |editor, cx| {
+            editor.set_placeholder_text(placeholder, cx);
+        });
+        self.reset_visible_entries(cx);
+    }
+
+    fn is_recording(&self, cx: &AppContext) -> bool {
+        self.server_id.map_or(false, |server_id| {
+            self.log_store
+                .read(cx)
+                .server_rpc_state(server_id)
+                .is_some()
+        })
+    }
+
+    fn toggle_recording(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(server_id) = self.server_id else {
+            return;
+        };
+        let recording = self.is_recording(cx);
+        self.log_store.update(cx, |log_store, cx| {
+            if recording {
+                log_store.disable_rpc_trace_for_language_server(server_id);
+            } else {
+                log_store.enable_rpc_trace_for_language_server(server_id);
+            }
+            cx.notify();
+        });
+    }
+
+    /// Matches all the entries against the filter again, after the filter or the shown
+    /// entries changed.
+    fn reset_visible_entries(&mut self, cx: &mut ViewContext<Self>) {
+        self.visible_entries.clear();
+        self.next_entry_id = 0;
+        self.update_visible_entries(cx);
+    }
+
+    /// Drops the entries that the log store no longer has, and matches the entries that came
+    /// in since the last update against the filter.
+    fn update_visible_entries(&mut self, cx: &mut ViewContext<Self>) {
+        // Servers start after the panel, and may be stopped while it shows them.
+        if self.server_id.map_or(true, |server_id| {
+            !self.log_store.read(cx).has_language_server(server_id)
+        }) {
+            self.server_id = self.servers(cx).first().map(|(server_id, _)| *server_id);
+            self.visible_entries.clear();
+            self.next_entry_id = 0;
+        }
+        let filter = self.filter_editor.read(cx).text(cx).trim().to_lowercase();
+        let log_store = self.log_store.read(cx);
+        let rpc_state = self
+            .server_id
+            .and_then(|server_id| log_store.server_rpc_state(server_id));
+        let stderr = self
+            .server_id
+            .and_then(|server_id| log_store.server_stderr(server_id));
+        let entry_ids = match self.mode {
+            InspectorMode::Traffic => rpc_state.map(|state| state.entry_ids()),
+            InspectorMode::Stderr => stderr.map(|stderr| stderr.line_ids()),
+        }
+        .unwrap_or_default();
+
+        // Recording the traffic again starts over with new ids.
+        if entry_ids.end < self.next_entry_id {
+            self.visible_entries.clear();
+            self.next_entry_id = 0;
+        }
+        let dropped_entries = self
+            .visible_entries
+            .partition_point(|entry_id| *entry_id < entry_ids.start);
+        self.visible_entries.drain(..dropped_entries);
+        match self.mode {
+            InspectorMode::Traffic => {
+                if let Some(state) = rpc_state {
+                    self.visible_entries.extend(
+                        state
+                            .entries_since(self.next_entry_id)
+                            .filter(|entry| {
+                                filter.is_empty()
+                                    || entry.method.as_deref().map_or(false, |method| {
+                                        method.to_lowercase().contains(&filter)
+                                    })
+                            })
+                            .map(|entry| entry.id),
+                    );
+                }
+            }
+            InspectorMode::Stderr => {
+                if let Some(stderr) = stderr {
+                    self.visible_entries.extend(
+                        stderr
+                            .lines_since(self.next_entry_id)
+                            .filter(|(_, line)| line.to_lowercase().contains(&filter))
+                            .map(|(line_id, _)| line_id),
+                    );
+                }
+            }
+        }
+        self.next_entry_id = entry_ids.end;
+
+        if self.selected_entry.map_or(false, |entry| {
+            self.visible_entries.binary_search(&entry).is_err()
+        }) {
+            self.selected_entry = None;
+        }
+        cx.notify();
+    }
+
+    fn selected_rpc_entry<'a>(&self, cx: &'a AppContext) -> Option<&'a RpcEntry> {
+        if self.mode != InspectorMode::Traffic {
+            return None;
+        }
+        self.log_store
+            .read(cx)
+            .server_rpc_state(self.server_id?)?
+            .entry(self.selected_entry?)
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let ix = match self.selected_ix() {
+            Some(ix) => ix + 1,
+            None => 0,
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let ix = match self.selected_ix() {
+            Some(ix) => ix.saturating_sub(1),
+            None => self.visible_entries.len().saturating_sub(1),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn selected_ix(&self) -> Option<usize> {
+        let selected_entry = self.selected_entry?;
+        self.visible_entries
+            .iter()
+            .position(|entry| *entry == selected_entry)
+    }
+
+    fn select_ix(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if self.mode != InspectorMode::Traffic {
+            return;
+        }
+        if let Some(entry) = self.visible_entries.get(ix) {
+            self.selected_entry = Some(*entry);
+            self.replay_error = None;
+            self.scroll_handle.scroll_to_item(ix);
+            cx.notify();
+        }
+    }
+
+    /// Sends the selected request to the server again. The server's response shows up in the
+    /// traffic like any other.
+    fn replay_request(&mut self, _: &ReplayRequest, cx: &mut ViewContext<Self>) {
+        let Some(server_id) = self.server_id else {
+            return;
+        };
+        let Some((method, params)) = self
+            .selected_rpc_entry(cx)
+            .filter(|entry| entry.is_replayable())
+            .and_then(|entry| {
+                let message = serde_json::from_str::<serde_json::Value>(&entry.message).ok()?;
+                let params = message
+                    .get("params")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                Some((entry.method.clone()?, params))
+            })
+        else {
+            return;
+        };
+        let Some(server) = self.project.read(cx).language_server_for_id(server_id) else {
+            self.replay_error = Some("The language server is no longer running".into());
+            cx.notify();
+            return;
+        };
+
+        let request = server.request_raw(&method, params);
+        self.replay_error = None;
+        self.pending_replay = cx.spawn(|inspector, mut cx| async move {
+            let result = request.await;
+            inspector
+                .update(&mut cx, |inspector, cx| {
+                    inspector.replay_error = result
+                        .err()
+                        .map(|error| format!("Replaying {method} failed: {error:#}").into());
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("LspInspector");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_server_menu(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let servers = self.servers(cx);
+        let current_server = self
+            .server_id
+            .and_then(|server_id| servers.iter().find(|(id, _)| *id == server_id))
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| "No server selected".into());
+        let inspector = cx.view().clone();
+        PopoverMenu::new("lsp-inspector-servers")
+            .anchor(AnchorCorner::TopLeft)
+            .trigger(Button::new("lsp-inspector-server", current_server))
+            .menu(move |cx| {
+                let servers = servers.clone();
+                let inspector = inspector.clone();
+                ContextMenu::build(cx, move |mut menu, cx| {
+                    for (server_id, name) in servers {
+                        menu = menu.entry(
+                            name,
+                            None,
+                            cx.handler_for(&inspector, move |inspector, cx| {
+                                inspector.select_server(server_id, cx);
+                            }),
+                        );
+                    }
+                    menu
+                })
+                .into()
+            })
+    }
+
+    fn render_mode_button(&self, mode: InspectorMode, cx: &mut ViewContext<Self>) -> Button {
+        let (id, label) = match mode {
+            InspectorMode::Traffic => ("lsp-inspector-traffic", "Traffic"),
+            InspectorMode::Stderr => ("lsp-inspector-stderr", "Stderr"),
+        };
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .selected(self.mode == mode)
+            .on_click(cx.listener(move |inspector, _, cx| inspector.set_mode(mode, cx)))
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> Option<ListItem> {
+        let server_id = self.server_id?;
+        let entry_id = *self.visible_entries.get(ix)?;
+        let log_store = self.log_store.read(cx);
+        let item = ListItem::new(("lsp-inspector-entry", entry_id));
+        match self.mode {
+            InspectorMode::Traffic => {
+                let entry = log_store.server_rpc_state(server_id)?.entry(entry_id)?;
+                let (icon, direction) = match entry.kind {
+                    MessageKind::Send => (IconName::ArrowUp, "Sent"),
+                    MessageKind::Receive => (IconName::ArrowDown, "Received"),
+                };
+                let method = entry
+                    .method
+                    .clone()
+                    .unwrap_or_else(|| "(unknown)".to_string());
+                let timing = if entry.is_response {
+                    Some("response".to_string())
+                } else if entry.request_id.is_some() {
+                    Some(
+                        entry
+                            .duration
+                            .map_or_else(|| "pending".to_string(), format_duration),
+                    )
+                } else {
+                    None
+                };
+                Some(
+                    item.selected(self.selected_entry == Some(entry_id))
+                        .start_slot(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                        .tooltip(move |cx| Tooltip::text(direction, cx))
+                        .child(Label::new(method).size(LabelSize::Small))
+                        .when_some(timing, |this, timing| {
+                            this.end_slot(
+                                Label::new(timing)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .on_click(cx.listener(move |inspector, _, cx| {
+                            inspector.select_ix(ix, cx);
+                        })),
+                )
+            }
+            InspectorMode::Stderr => {
+                let line = log_store.server_stderr(server_id)?.line(entry_id)?;
+                Some(item.child(Label::new(line.to_string()).size(LabelSize::Small)))
+            }
+        }
+    }
+
+    fn render_details(&self, cx: &mut ViewContext<Self>) -> Option<impl IntoElement> {
+        let entry = self.selected_rpc_entry(cx)?;
+        let message = serde_json::from_str::<serde_json::Value>(&entry.message)
+            .ok()
+            .and_then(|message| serde_json::to_string_pretty(&message).ok())
+            .unwrap_or_else(|| entry.message.clone());
+        let is_replayable = entry.is_replayable();
+        let age = format_duration(entry.timestamp.elapsed());
+        Some(
+            v_flex()
+                .h(px(200.))
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    h_flex()
+                        .p_1()
+                        .gap_2()
+                        .justify_between()
+                        .child(
+                            Label::new(format!("{age} ago"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .when(is_replayable, |this| {
+                            this.child(
+                                Button::new("lsp-inspector-replay", "Replay")
+                                    .icon(IconName::Rerun)
+                                    .icon_position(IconPosition::Start)
+                                    .icon_size(IconSize::Small)
+                                    .on_click(cx.listener(|inspector, _, cx| {
+                                        inspector.replay_request(&ReplayRequest, cx);
+                                    })),
+                            )
+                        }),
+                )
+                .when_some(self.replay_error.clone(), |this, error| {
+                    this.child(
+                        div()
+                            .px_1()
+                            .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                    )
+                })
+                .child(
+                    div()
+                        .id("lsp-inspector-message")
+                        .flex_1()
+                        .p_1()
+                        .overflow_y_scroll()
+                        .font_buffer(cx)
+                        .child(Label::new(message).size(LabelSize::Small)),
+                ),
+        )
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{:.1} ms", duration.as_secs_f64() * 1000.)
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
+
+impl Render for LspInspector {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let recording = self.is_recording(cx);
+        let message = if self.server_id.is_none() {
+            Some("No language servers are running")
+        } else if self.mode == InspectorMode::Traffic && !recording {
+            Some("Start recording to capture the messages exchanged with the server")
+        } else if self.visible_entries.is_empty() {
+            Some("No messages")
+        } else {
+            None
+        };
+
+        v_flex()
+            .id("lsp-inspector")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::replay_request))
+            .child(
+                h_flex()
+                    .p_1()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.render_server_menu(cx))
+                    .child(self.render_mode_button(InspectorMode::Traffic, cx))
+                    .child(self.render_mode_button(InspectorMode::Stderr, cx))
+                    .when(self.mode == InspectorMode::Traffic, |this| {
+                        this.child(
+                            Button::new("lsp-inspector-record", "Record")
+                                .style(ButtonStyle::Subtle)
+                                .selected(recording)
+                                .disabled(self.server_id.is_none())
+                                .on_click(cx.listener(|inspector, _, cx| {
+                                    inspector.toggle_recording(cx);
+                                })),
+                        )
+                    })
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_2()
+                            .py_1()
+                            .border_1()
+                            .rounded_md()
+                            .border_color(cx.theme().colors().border)
+                            .child(self.filter_editor.clone()),
+                    ),
+            )
+            .map(|this| match message {
+                Some(message) => this.child(
+                    v_flex().size_full().p_4().justify_center().child(
+                        h_flex()
+                            .justify_center()
+                            .child(Label::new(message).color(Color::Muted)),
+                    ),
+                ),
+                None => this
+                    .child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "lsp-inspector-entries",
+                            self.visible_entries.len(),
+                            |inspector, range, cx| {
+                                range
+                                    .filter_map(|ix| inspector.render_entry(ix, cx))
+                                    .collect()
+                            },
+                        )
+                        .flex_1()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                    .children(self.render_details(cx)),
+            })
+    }
+}
+
+impl FocusableView for LspInspector {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for LspInspector {}
+
+impl Panel for LspInspector {
+    fn persistent_name() -> &'static str {
+        "LSP Inspector"
+    }
+
+    fn position(&self, _: &WindowContext) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        self.position = position;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn size(&self, _: &WindowContext) -> Pixels {
+        match self.position {
+            DockPosition::Left | DockPosition::Right => self.width.unwrap_or(px(400.)),
+            DockPosition::Bottom => self.height.unwrap_or(px(320.)),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        match self.position {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &WindowContext) -> Option<IconName> {
+        Some(IconName::Server)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Language Server Inspector")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
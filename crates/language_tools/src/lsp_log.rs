@@ -12,7 +12,12 @@ use lsp::{
     notification::SetTrace, IoKind, LanguageServer, MessageType, SetTraceParams, TraceValue,
 };
 use project::{search::SearchQuery, Project};
-use std::{borrow::Cow, sync::Arc};
+use std::{
+    borrow::Cow,
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use ui::{prelude::*, Button, Checkbox, ContextMenu, Label, PopoverMenu, Selection};
 use workspace::{
     item::{Item, ItemHandle},
@@ -102,6 +107,7 @@ struct LanguageServerState {
     kind: LanguageServerKind,
     log_messages: VecDeque<LogMessage>,
     trace_messages: VecDeque<TraceMessage>,
+    stderr_messages: StderrLog,
     rpc_state: Option<LanguageServerRpcState>,
     trace_level: TraceValue,
     log_level: MessageType,
@@ -122,9 +128,143 @@ impl LanguageServerKind {
     }
 }
 
-struct LanguageServerRpcState {
+pub(crate) struct LanguageServerRpcState {
     rpc_messages: VecDeque<RpcMessage>,
     last_message_kind: Option<MessageKind>,
+    entries: VecDeque<RpcEntry>,
+    next_entry_id: usize,
+}
+
+/// A JSON-RPC message exchanged with a language server, as listed by the request inspector.
+pub(crate) struct RpcEntry {
+    pub id: usize,
+    pub kind: MessageKind,
+    /// The method of a request or notification, or of the request that a response answers.
+    pub method: Option<String>,
+    pub request_id: Option<serde_json::Value>,
+    pub is_response: bool,
+    pub message: String,
+    pub timestamp: Instant,
+    /// How long it took for a request to be responded to, once it has been.
+    pub duration: Option<Duration>,
+}
+
+impl RpcEntry {
+    /// Whether the entry is a request from Zed that can be sent to the server again.
+    /// Requests that manage the server's lifecycle are never sent twice.
+    pub fn is_replayable(&self) -> bool {
+        self.kind == MessageKind::Send
+            && !self.is_response
+            && self.request_id.is_some()
+            && self.method.as_deref().map_or(false, |method| {
+                method != "initialize" && method != "shutdown"
+            })
+    }
+}
+
+/// The lines that a language server wrote to its stderr, with ids that stay the same as
+/// the oldest lines are dropped.
+#[derive(Default)]
+pub(crate) struct StderrLog {
+    lines: VecDeque<String>,
+    first_line_id: usize,
+}
+
+impl StderrLog {
+    pub(crate) fn line(&self, id: usize) -> Option<&str> {
+        self.lines
+            .get(id.checked_sub(self.first_line_id)?)
+            .map(String::as_str)
+    }
+
+    /// The lines from the one with the given id on, with their ids.
+    pub(crate) fn lines_since(&self, id: usize) -> impl Iterator<Item = (usize, &str)> {
+        let start = id.saturating_sub(self.first_line_id).min(self.lines.len());
+        self.lines
+            .range(start..)
+            .enumerate()
+            .map(move |(ix, line)| (self.first_line_id + start + ix, line.as_str()))
+    }
+
+    /// The ids of the lines that are still stored.
+    pub(crate) fn line_ids(&self) -> Range<usize> {
+        self.first_line_id..self.first_line_id + self.lines.len()
+    }
+
+    fn push(&mut self, line: String) {
+        while self.lines.len() >= MAX_STORED_LOG_ENTRIES {
+            self.lines.pop_front();
+            self.first_line_id += 1;
+        }
+        self.lines.push_back(line);
+    }
+}
+
+impl LanguageServerRpcState {
+    pub(crate) fn entries(&self) -> impl Iterator<Item = &RpcEntry> {
+        self.entries.iter()
+    }
+
+    /// The entries from the one with the given id on.
+    pub(crate) fn entries_since(&self, id: usize) -> impl Iterator<Item = &RpcEntry> {
+        let first_id = self.entry_ids().start;
+        let start = id.saturating_sub(first_id).min(self.entries.len());
+        self.entries.range(start..)
+    }
+
+    /// The ids of the entries that are still stored.
+    pub(crate) fn entry_ids(&self) -> Range<usize> {
+        let first_id = self
+            .entries
+            .front()
+            .map_or(self.next_entry_id, |entry| entry.id);
+        first_id..self.next_entry_id
+    }
+
+    pub(crate) fn entry(&self, id: usize) -> Option<&RpcEntry> {
+        let first_id = self.entries.front()?.id;
+        self.entries.get(id.checked_sub(first_id)?)
+    }
+
+    fn add_entry(&mut self, kind: MessageKind, message: &str) {
+        let json = serde_json::from_str::<serde_json::Value>(message).ok();
+        let request_id = json.as_ref().and_then(|json| json.get("id")).cloned();
+        let mut method = json
+            .as_ref()
+            .and_then(|json| json.get("method"))
+            .and_then(|method| method.as_str())
+            .map(ToString::to_string);
+        let is_response = method.is_none() && request_id.is_some();
+        let timestamp = Instant::now();
+        if is_response {
+            // Responses go the other way than the requests they answer.
+            if let Some(request) = self.entries.iter_mut().rev().find(|entry| {
+                entry.kind != kind
+                    && !entry.is_response
+                    && entry.duration.is_none()
+                    && entry.request_id == request_id
+            }) {
+                request.duration = Some(timestamp.saturating_duration_since(request.timestamp));
+                method = request.method.clone();
+            }
+        }
+
+        while self.entries.len() >= MAX_STORED_LOG_ENTRIES {
+            self.entries.pop_front();
+        }
+        let id = self.next_entry_id;
+        self.next_entry_id += 1;
+        self.entries.push_back(RpcEntry {
+            id,
+            kind,
+            method,
+            request_id,
+            is_response,
+            message: message.to_string(),
+            timestamp,
+            duration: None,
+        });
+    }
 }
 
 pub struct LspLogView {
@@ -143,8 +283,8 @@ pub struct LspLogToolbarItemView {
     _log_view_subscription: Option<Subscription>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-enum MessageKind {
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MessageKind {
     Send,
     Receive,
 }
@@ -181,6 +321,7 @@ actions!(debug, [OpenLanguageServerLogs]);
 
 pub fn init(cx: &mut AppContext) {
     let log_store = cx.new_model(|cx| LogStore::new(cx));
+    crate::lsp_inspector::init(log_store.clone(), cx);
 
     cx.observe_new_views(move |workspace: &mut Workspace, cx| {
         let project = workspace.project();
@@ -346,6 +487,7 @@ impl LogStore {
                 rpc_state: None,
                 log_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
                 trace_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
+                stderr_messages: StderrLog::default(),
                 trace_level: TraceValue::Off,
                 log_level: MessageType::LOG,
                 io_logs_subscription: None,
@@ -445,6 +587,21 @@ impl LogStore {
         Some(&self.language_servers.get(&server_id)?.trace_messages)
     }
 
+    pub(crate) fn server_stderr(&self, server_id: LanguageServerId) -> Option<&StderrLog> {
+        Some(&self.language_servers.get(&server_id)?.stderr_messages)
+    }
+
+    pub(crate) fn server_rpc_state(
+        &self,
+        server_id: LanguageServerId,
+    ) -> Option<&LanguageServerRpcState> {
+        self.language_servers.get(&server_id)?.rpc_state.as_ref()
+    }
+
+    pub(crate) fn has_language_server(&self, server_id: LanguageServerId) -> bool {
+        self.language_servers.contains_key(&server_id)
+    }
+
     fn server_ids_for_project<'a>(
         &'a self,
         lookup_project: &'a WeakModel<Project>,
@@ -463,7 +620,7 @@ impl LogStore {
             })
     }
 
-    fn enable_rpc_trace_for_language_server(
+    pub(crate) fn enable_rpc_trace_for_language_server(
         &mut self,
         server_id: LanguageServerId,
     ) -> Option<&mut LanguageServerRpcState> {
@@ -474,6 +631,8 @@ impl LogStore {
             .get_or_insert_with(|| LanguageServerRpcState {
                 rpc_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
                 last_message_kind: None,
+                entries: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
+                next_entry_id: 0,
             });
         Some(rpc_state)
     }
@@ -497,6 +656,10 @@ impl LogStore {
             IoKind::StdOut => true,
             IoKind::StdIn => false,
             IoKind::StdErr => {
+                if let Some(state) = self.get_language_server_state(language_server_id) {
+                    state.stderr_messages.push(message.trim_end().to_string());
+                    cx.notify();
+                }
                 let message = format!("stderr: {}", message.trim());
                 self.add_language_server_log(language_server_id, MessageType::LOG, &message, cx);
                 return Some(());
@@ -536,6 +699,7 @@ impl LogStore {
         rpc_log_lines.push_back(RpcMessage {
             message: message.to_string(),
         });
+        state.add_entry(kind, message);
         cx.emit(Event::NewServerLogEntry {
             id: language_server_id,
             entry: message.to_string(),
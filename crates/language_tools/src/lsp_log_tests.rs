@@ -8,7 +8,7 @@ use gpui::{Context, SemanticVersion, TestAppContext, VisualTestContext};
 use language::{
     tree_sitter_rust, FakeLspAdapter, Language, LanguageConfig, LanguageMatcher, LanguageServerName,
 };
-use lsp_log::LogKind;
+use lsp_log::{LogKind, MessageKind};
 use project::{FakeFs, Project};
 use serde_json::json;
 use settings::SettingsStore;
@@ -101,6 +101,110 @@ async fn test_lsp_logs(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_lsp_traffic_entries(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree("/the-root", json!({ "test.rs": "" })).await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    )));
+    let mut fake_rust_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-rust-language-server",
+            ..Default::default()
+        },
+    );
+
+    let log_store = cx.new_model(|cx| LogStore::new(cx));
+    log_store.update(cx, |store, cx| store.add_project(&project, cx));
+
+    let _rust_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/test.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let mut language_server = fake_rust_servers.next().await.unwrap();
+    language_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await;
+    let server_id = language_server.server.server_id();
+    log_store.update(cx, |store, _| {
+        store.enable_rpc_trace_for_language_server(server_id);
+    });
+
+    language_server.handle_request::<lsp::request::WorkspaceSymbolRequest, _, _>(
+        |_, _| async move { Ok(None) },
+    );
+    language_server
+        .server
+        .request_raw("workspace/symbol", json!({ "query": "main" }))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    log_store.read_with(cx, |store, _| {
+        let entries = store
+            .server_rpc_state(server_id)
+            .unwrap()
+            .entries()
+            .map(|entry| {
+                (
+                    entry.kind,
+                    entry.method.clone(),
+                    entry.is_response,
+                    entry.duration.is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            &[
+                (
+                    MessageKind::Send,
+                    Some("workspace/symbol".to_string()),
+                    false,
+                    true
+                ),
+                (
+                    MessageKind::Receive,
+                    Some("workspace/symbol".to_string()),
+                    true,
+                    false
+                ),
+            ]
+        );
+
+        let state = store.server_rpc_state(server_id).unwrap();
+        let first_id = state.entry_ids().start;
+        assert_eq!(state.entry_ids(), first_id..first_id + 2);
+        assert_eq!(
+            state
+                .entries_since(first_id + 1)
+                .map(|entry| entry.is_response)
+                .collect::<Vec<_>>(),
+            &[true]
+        );
+        assert_eq!(state.entries_since(first_id + 2).count(), 0);
+    });
+}
+
 fn init_test(cx: &mut gpui::TestAppContext) {
     cx.update(|cx| {
         let settings_store = SettingsStore::test(cx);
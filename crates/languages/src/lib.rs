@@ -13,6 +13,8 @@ use util::{asset_str, ResultExt};
 
 use crate::{bash::bash_task_context, go::GoContextProvider, rust::RustContextProvider};
 
+pub use user_languages::watch_user_languages;
+
 mod bash;
 mod c;
 mod css;
@@ -22,6 +24,7 @@ mod python;
 mod rust;
 mod tailwind;
 mod typescript;
+mod user_languages;
 mod vtsls;
 mod yaml;
 
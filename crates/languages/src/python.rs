@@ -175,6 +175,10 @@ impl LspAdapter for PythonLspAdapter {
         })
     }
 
+    fn workspace_configuration_includes_settings(&self) -> bool {
+        true
+    }
+
     async fn workspace_configuration(
         self: Arc<Self>,
         _: &Arc<dyn LspAdapterDelegate>,
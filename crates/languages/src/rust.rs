@@ -51,6 +51,7 @@ impl LspAdapter for RustLspAdapter {
                 path,
                 arguments,
                 path_lookup,
+                ..
             })) => {
                 let (path, env) = match (path, path_lookup) {
                     (Some(path), lookup) => {
@@ -196,6 +197,19 @@ impl LspAdapter for RustLspAdapter {
         }
     }
 
+    fn inactive_regions(&self, params: &lsp::PublishDiagnosticsParams) -> Option<Vec<lsp::Range>> {
+        Some(
+            params
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| {
+                    diagnostic.code == Some(lsp::NumberOrString::String("inactive-code".into()))
+                })
+                .map(|diagnostic| diagnostic.range)
+                .collect(),
+        )
+    }
+
     async fn label_for_completion(
         &self,
         completion: &lsp::CompletionItem,
@@ -726,6 +740,34 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_rust_inactive_regions() {
+        let inactive_range = lsp::Range::new(lsp::Position::new(3, 0), lsp::Position::new(5, 1));
+        let params = lsp::PublishDiagnosticsParams {
+            uri: lsp::Url::from_file_path("/a").unwrap(),
+            version: None,
+            diagnostics: vec![
+                lsp::Diagnostic {
+                    range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 5)),
+                    code: Some(lsp::NumberOrString::String("unused_variables".into())),
+                    message: "unused variable: `a`".to_string(),
+                    ..Default::default()
+                },
+                lsp::Diagnostic {
+                    range: inactive_range,
+                    code: Some(lsp::NumberOrString::String("inactive-code".into())),
+                    message: "code is inactive due to #[cfg] directives: test is disabled"
+                        .to_string(),
+                    ..Default::default()
+                },
+            ],
+        };
+        assert_eq!(
+            RustLspAdapter.inactive_regions(&params),
+            Some(vec![inactive_range])
+        );
+    }
+
     #[gpui::test]
     async fn test_rust_label_for_completion() {
         let adapter = Arc::new(RustLspAdapter);
@@ -0,0 +1,5 @@
+(jsx_element
+  (jsx_opening_element
+    (identifier) @open)
+  (jsx_closing_element
+    (identifier) @close))
@@ -322,6 +322,10 @@ impl LspAdapter for EsLintLspAdapter {
         ])
     }
 
+    fn workspace_configuration_includes_settings(&self) -> bool {
+        true
+    }
+
     async fn workspace_configuration(
         self: Arc<Self>,
         delegate: &Arc<dyn LspAdapterDelegate>,
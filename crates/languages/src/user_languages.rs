@@ -0,0 +1,336 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use futures::StreamExt;
+use gpui::AppContext;
+use language::{LanguageConfig, LanguageQueries, LanguageRegistry, QUERY_FILENAME_PREFIXES};
+use util::ResultExt;
+
+/// Registers the languages in the user's languages directory, registering them again
+/// whenever the directory changes, so that languages can be added without rebuilding Zed.
+///
+/// Each language is a directory holding a `config.toml` and the language's queries, along
+/// with the compiled WASM grammars it uses, which are named after the grammar. A language
+/// with the same name as a built-in one replaces it until it's removed.
+pub fn watch_user_languages(fs: Arc<dyn Fs>, languages: Arc<LanguageRegistry>, cx: &AppContext) {
+    cx.background_executor()
+        .spawn(async move {
+            let dir = paths::user_languages_dir().as_path();
+            if fs.metadata(dir).await.ok().flatten().is_none() {
+                fs.create_dir(dir).await.log_err();
+            }
+
+            let (mut events, watcher) = fs.watch(dir, Duration::from_millis(100)).await;
+            let mut registered = HashMap::default();
+            loop {
+                let language_dirs =
+                    register_user_languages(fs.as_ref(), dir, &languages, &mut registered).await;
+                for language_dir in language_dirs {
+                    watcher.add(&language_dir).log_err();
+                }
+                if events.next().await.is_none() {
+                    break;
+                }
+            }
+        })
+        .detach();
+}
+
+/// A language registered from the user's languages directory.
+#[derive(Clone, Debug, PartialEq)]
+struct RegisteredLanguage {
+    name: Arc<str>,
+    /// The language's files with the time they were modified, which tell whether the
+    /// language changed since it was registered.
+    files: Vec<(PathBuf, SystemTime)>,
+}
+
+/// Registers the languages in the given directory that were added or changed, and removes
+/// the previously registered ones that are gone. Returns the directories of the languages.
+async fn register_user_languages(
+    fs: &dyn Fs,
+    dir: &Path,
+    languages: &LanguageRegistry,
+    registered: &mut HashMap<PathBuf, RegisteredLanguage>,
+) -> Vec<PathBuf> {
+    let mut language_dirs = Vec::new();
+    if let Some(mut entries) = fs.read_dir(dir).await.log_err() {
+        while let Some(path) = entries.next().await {
+            let Some(path) = path.log_err() else {
+                continue;
+            };
+            if fs.is_dir(&path).await {
+                language_dirs.push(path);
+            }
+        }
+    }
+    language_dirs.sort();
+
+    let mut current = HashMap::default();
+    let mut changed_languages = Vec::new();
+    let mut grammars = Vec::new();
+    for language_dir in &language_dirs {
+        let files = match language_files(fs, language_dir).await {
+            Ok(files) => files,
+            Err(error) => {
+                log::error!("failed to read language at {language_dir:?}: {error:#}");
+                continue;
+            }
+        };
+        if let Some(language) = registered
+            .get(language_dir)
+            .filter(|language| language.files == files)
+        {
+            current.insert(language_dir.clone(), language.clone());
+            continue;
+        }
+        match load_user_language(fs, language_dir, &files, &mut grammars).await {
+            Ok((config, queries)) => {
+                current.insert(
+                    language_dir.clone(),
+                    RegisteredLanguage {
+                        name: config.name.clone(),
+                        files,
+                    },
+                );
+                changed_languages.push((config, queries));
+            }
+            Err(error) => log::error!("failed to load language at {language_dir:?}: {error:#}"),
+        }
+    }
+
+    let names = current
+        .values()
+        .map(|language| language.name.clone())
+        .collect::<HashSet<_>>();
+    let removed = registered
+        .values()
+        .map(|language| language.name.clone())
+        .filter(|name| !names.contains(name))
+        .collect::<Vec<_>>();
+    *registered = current;
+    if changed_languages.is_empty() && removed.is_empty() {
+        return language_dirs;
+    }
+
+    // Built-in languages that the removed languages replaced are registered again.
+    languages.remove_language_overrides(&removed);
+    if !grammars.is_empty() {
+        languages.register_wasm_grammars(grammars);
+    }
+    let mut changed_names = Vec::new();
+    for (config, queries) in changed_languages {
+        changed_names.push(config.name.clone());
+        languages.register_language_override(
+            config.name.clone(),
+            config.grammar.clone(),
+            config.matcher.clone(),
+            move || Ok((config.clone(), queries.clone(), None)),
+        );
+    }
+    // The changed languages are loaded again, with their new grammars and queries.
+    languages.reload_languages(&changed_names);
+    language_dirs
+}
+
+/// Returns the files in the language's directory, sorted, with the time they were modified.
+async fn language_files(fs: &dyn Fs, language_dir: &Path) -> Result<Vec<(PathBuf, SystemTime)>> {
+    let mut entries = fs.read_dir(language_dir).await?;
+    let mut files = Vec::new();
+    while let Some(path) = entries.next().await {
+        let path = path?;
+        let metadata = fs
+            .metadata(&path)
+            .await?
+            .ok_or_else(|| anyhow!("{path:?} was removed"))?;
+        if !metadata.is_dir {
+            files.push((path, metadata.mtime));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+async fn load_user_language(
+    fs: &dyn Fs,
+    language_dir: &Path,
+    files: &[(PathBuf, SystemTime)],
+    grammars: &mut Vec<(Arc<str>, PathBuf)>,
+) -> Result<(LanguageConfig, LanguageQueries)> {
+    let config = fs.load(&language_dir.join("config.toml")).await?;
+    let config: LanguageConfig = ::toml::from_str(&config).context("invalid config.toml")?;
+
+    let mut queries = LanguageQueries::default();
+    for (path, _) in files {
+        let (Some(file_stem), Some(extension)) = (
+            path.file_stem().and_then(|stem| stem.to_str()),
+            path.extension().and_then(|extension| extension.to_str()),
+        ) else {
+            continue;
+        };
+        match extension {
+            "wasm" => grammars.push((file_stem.into(), path.clone())),
+            "scm" => {
+                let Some((_, query)) = QUERY_FILENAME_PREFIXES
+                    .iter()
+                    .find(|(prefix, _)| file_stem.starts_with(prefix))
+                else {
+                    continue;
+                };
+                let contents = fs.load(path).await?;
+                match query(&mut queries) {
+                    None => *query(&mut queries) = Some(contents.into()),
+                    Some(existing) => existing.to_mut().push_str(&contents),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok((config, queries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_user_languages(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/languages",
+            json!({
+                "my-language": {
+                    "config.toml": r#"
+                        name = "My Language"
+                        grammar = "my_language"
+                        path_suffixes = ["mine"]
+                        first_line_pattern = '^#!.*\bmine\b'
+                    "#,
+                    "highlights.scm": "(comment) @comment",
+                    "outline.scm": "(function name: (identifier) @name) @item",
+                    "my_language.wasm": "",
+                },
+                "broken": {
+                    "config.toml": "name = ",
+                },
+            }),
+        )
+        .await;
+
+        let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+        let mut registered = HashMap::default();
+        let dir = Path::new("/languages");
+        let language_dirs =
+            register_user_languages(fs.as_ref(), dir, &languages, &mut registered).await;
+        assert_eq!(
+            language_dirs,
+            [
+                PathBuf::from("/languages/broken"),
+                PathBuf::from("/languages/my-language")
+            ]
+        );
+        assert!(languages
+            .language_names()
+            .contains(&"My Language".to_string()));
+        assert!(languages
+            .grammar_names()
+            .contains(&Arc::from("my_language")));
+
+        let language_dir = Path::new("/languages/my-language");
+        let files = language_files(fs.as_ref(), language_dir).await.unwrap();
+        let (config, queries) =
+            load_user_language(fs.as_ref(), language_dir, &files, &mut Vec::new())
+                .await
+                .unwrap();
+        assert!(config
+            .matcher
+            .first_line_pattern
+            .unwrap()
+            .is_match("#!/usr/bin/env mine"));
+        assert_eq!(queries.highlights.as_deref(), Some("(comment) @comment"));
+        assert!(queries.outline.is_some());
+        assert!(queries.indents.is_none());
+
+        // Languages that didn't change aren't loaded again.
+        let reload_count = languages.reload_count();
+        register_user_languages(fs.as_ref(), dir, &languages, &mut registered).await;
+        assert_eq!(languages.reload_count(), reload_count);
+
+        fs.remove_dir(
+            Path::new("/languages/my-language"),
+            fs::RemoveOptions {
+                recursive: true,
+                ignore_if_not_exists: false,
+            },
+        )
+        .await
+        .unwrap();
+        register_user_languages(fs.as_ref(), dir, &languages, &mut registered).await;
+        assert!(!languages
+            .language_names()
+            .contains(&"My Language".to_string()));
+        assert!(registered.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_user_language_overriding_built_in_one(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/languages",
+            json!({
+                "my-json": {
+                    "config.toml": r#"
+                        name = "JSON"
+                        path_suffixes = ["json"]
+                    "#,
+                },
+            }),
+        )
+        .await;
+
+        let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+        languages.register_language(
+            "JSON".into(),
+            None,
+            language::LanguageMatcher::default(),
+            || {
+                Ok((
+                    LanguageConfig {
+                        name: "JSON".into(),
+                        ..Default::default()
+                    },
+                    LanguageQueries::default(),
+                    None,
+                ))
+            },
+        );
+        let mut registered = HashMap::default();
+        let dir = Path::new("/languages");
+        register_user_languages(fs.as_ref(), dir, &languages, &mut registered).await;
+        let language = languages.language_for_name("JSON").await.unwrap();
+        assert_eq!(language.path_suffixes(), ["json"]);
+
+        fs.remove_dir(
+            Path::new("/languages/my-json"),
+            fs::RemoveOptions {
+                recursive: true,
+                ignore_if_not_exists: false,
+            },
+        )
+        .await
+        .unwrap();
+        register_user_languages(fs.as_ref(), dir, &languages, &mut registered).await;
+        // The built-in language is registered again.
+        let language = languages.language_for_name("JSON").await.unwrap();
+        assert!(language.path_suffixes().is_empty());
+    }
+}
@@ -14,6 +14,7 @@ use serde_json::{json, value::RawValue, Value};
 use smol::{
     channel,
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
     process::{self, Child},
 };
 
@@ -24,6 +25,7 @@ use std::{
     ffi::OsString,
     fmt,
     io::Write,
+    net::SocketAddr,
     ops::DerefMut,
     path::PathBuf,
     pin::Pin,
@@ -42,6 +44,49 @@ const CONTENT_LEN_HEADER: &str = "Content-Length: ";
 
 const LSP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
 const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const TCP_CONNECT_ATTEMPTS: usize = 50;
+const TCP_CONNECT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The semantic token types advertised to language servers.
+const SEMANTIC_TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::EVENT,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::MACRO,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::MODIFIER,
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::REGEXP,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::DECORATOR,
+];
+
+/// The semantic token modifiers advertised to language servers.
+const SEMANTIC_TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::DEFINITION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::STATIC,
+    SemanticTokenModifier::DEPRECATED,
+    SemanticTokenModifier::ABSTRACT,
+    SemanticTokenModifier::ASYNC,
+    SemanticTokenModifier::MODIFICATION,
+    SemanticTokenModifier::DOCUMENTATION,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+];
 
 type NotificationHandler = Box<dyn Send + FnMut(Option<RequestId>, Value, AsyncAppContext)>;
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
@@ -245,6 +290,24 @@ impl lsp_types::notification::Notification for ServerStatus {
     const METHOD: &'static str = "experimental/serverStatus";
 }
 
+/// Extension: Reports the regions of a document that the preprocessor excludes
+///
+/// [clangd Specification](https://clangd.llvm.org/extensions#inactive-regions)
+#[derive(Debug)]
+pub enum InactiveRegions {}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InactiveRegionsParams {
+    pub text_document: TextDocumentIdentifier,
+    pub regions: Vec<Range>,
+}
+
+impl lsp_types::notification::Notification for InactiveRegions {
+    type Params = InactiveRegionsParams;
+    const METHOD: &'static str = "textDocument/inactiveRegions";
+}
+
 impl LanguageServer {
     /// Starts a language server process.
     pub fn new(
@@ -255,36 +318,8 @@ impl LanguageServer {
         code_action_kinds: Option<Vec<CodeActionKind>>,
         cx: AsyncAppContext,
     ) -> Result<Self> {
-        let working_dir = if root_path.is_dir() {
-            root_path
-        } else {
-            root_path.parent().unwrap_or_else(|| Path::new("/"))
-        };
-
-        log::info!(
-            "starting language server. binary path: {:?}, working directory: {:?}, args: {:?}",
-            binary.path,
-            working_dir,
-            &binary.arguments
-        );
-
-        let mut command = process::Command::new(&binary.path);
-        command
-            .current_dir(working_dir)
-            .args(&binary.arguments)
-            .envs(binary.env.unwrap_or_default())
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .kill_on_drop(true);
-        #[cfg(windows)]
-        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
-        let mut server = command.spawn().with_context(|| {
-            format!(
-                "failed to spawn command. path: {:?}, working directory: {:?}, args: {:?}",
-                binary.path, working_dir, &binary.arguments
-            )
-        })?;
+        let working_dir = Self::working_dir(root_path);
+        let mut server = Self::spawn(&binary, working_dir, true)?;
 
         let stdin = server.stdin.take().unwrap();
         let stdout = server.stdout.take().unwrap();
@@ -317,6 +352,118 @@ impl LanguageServer {
         Ok(server)
     }
 
+    /// Connects to a language server that listens on a TCP address, starting its process
+    /// first when a `binary` is given.
+    ///
+    /// The connection is retried for a while, to give a newly started server time to
+    /// start listening.
+    pub async fn new_tcp(
+        stderr_capture: Arc<Mutex<Option<String>>>,
+        server_id: LanguageServerId,
+        binary: Option<LanguageServerBinary>,
+        address: SocketAddr,
+        root_path: &Path,
+        code_action_kinds: Option<Vec<CodeActionKind>>,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let working_dir = Self::working_dir(root_path);
+        let mut server = binary
+            .as_ref()
+            .map(|binary| Self::spawn(binary, working_dir, false))
+            .transpose()?;
+        let stderr = server.as_mut().and_then(|server| server.stderr.take());
+
+        log::info!("connecting to language server at {address}");
+        let mut attempt = 0;
+        let stream = loop {
+            match TcpStream::connect(address).await {
+                Ok(stream) => break stream,
+                Err(error) if attempt < TCP_CONNECT_ATTEMPTS => {
+                    log::debug!("failed to connect to language server at {address}: {error}");
+                    attempt += 1;
+                    cx.background_executor().timer(TCP_CONNECT_INTERVAL).await;
+                }
+                Err(error) => {
+                    return Err(error).with_context(|| {
+                        format!("failed to connect to language server at {address}")
+                    })
+                }
+            }
+        };
+
+        let mut server = Self::new_internal(
+            server_id,
+            stream.clone(),
+            stream,
+            stderr,
+            stderr_capture,
+            server,
+            root_path,
+            working_dir,
+            code_action_kinds,
+            cx,
+            move |notification| {
+                log::info!(
+                    "Language server with id {} sent unhandled notification {}:\n{}",
+                    server_id,
+                    notification.method,
+                    serde_json::to_string_pretty(&notification.params).unwrap(),
+                );
+            },
+        );
+
+        server.name = match binary.as_ref().and_then(|binary| binary.path.file_name()) {
+            Some(name) => name.to_string_lossy().into(),
+            None => address.to_string().into(),
+        };
+
+        Ok(server)
+    }
+
+    fn working_dir(root_path: &Path) -> &Path {
+        if root_path.is_dir() {
+            root_path
+        } else {
+            root_path.parent().unwrap_or_else(|| Path::new("/"))
+        }
+    }
+
+    /// Starts the process of a language server, communicating with it over its standard input
+    /// and output when `over_stdio` is set. Its standard error is always captured.
+    fn spawn(binary: &LanguageServerBinary, working_dir: &Path, over_stdio: bool) -> Result<Child> {
+        log::info!(
+            "starting language server. binary path: {:?}, working directory: {:?}, args: {:?}",
+            binary.path,
+            working_dir,
+            &binary.arguments
+        );
+
+        let stdio = || {
+            if over_stdio {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            }
+        };
+        let mut command = process::Command::new(&binary.path);
+        command
+            .current_dir(working_dir)
+            .args(&binary.arguments)
+            .envs(binary.env.clone().unwrap_or_default())
+            .stdin(stdio())
+            .stdout(stdio())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(windows)]
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        command.spawn().with_context(|| {
+            format!(
+                "failed to spawn command. path: {:?}, working directory: {:?}, args: {:?}",
+                binary.path, working_dir, &binary.arguments
+            )
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn new_internal<Stdin, Stdout, Stderr, F>(
         server_id: LanguageServerId,
@@ -550,6 +697,10 @@ impl LanguageServer {
                         dynamic_registration: Some(true),
                     }),
                     workspace_folders: Some(true),
+                    apply_edit: Some(true),
+                    execute_command: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     symbol: Some(WorkspaceSymbolClientCapabilities {
                         resolve_support: None,
                         ..WorkspaceSymbolClientCapabilities::default()
@@ -558,7 +709,7 @@ impl LanguageServer {
                         refresh_support: Some(true),
                     }),
                     diagnostic: Some(DiagnosticWorkspaceClientCapabilities {
-                        refresh_support: None,
+                        refresh_support: Some(true),
                     }),
                     workspace_edit: Some(WorkspaceEditClientCapabilities {
                         resource_operations: Some(vec![
@@ -567,6 +718,12 @@ impl LanguageServer {
                             ResourceOperationKind::Delete,
                         ]),
                         document_changes: Some(true),
+                        failure_handling: Some(FailureHandlingKind::Abort),
+                        change_annotation_support: Some(
+                            ChangeAnnotationWorkspaceEditClientCapabilities {
+                                groups_on_label: None,
+                            },
+                        ),
                         snippet_edit_support: Some(true),
                         ..WorkspaceEditClientCapabilities::default()
                     }),
@@ -577,6 +734,9 @@ impl LanguageServer {
                         link_support: Some(true),
                         dynamic_registration: None,
                     }),
+                    code_lens: Some(CodeLensClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     code_action: Some(CodeActionClientCapabilities {
                         code_action_literal_support: Some(CodeActionLiteralSupport {
                             code_action_kind: CodeActionKindLiteralSupport {
@@ -648,6 +808,10 @@ impl LanguageServer {
                         related_information: Some(true),
                         ..Default::default()
                     }),
+                    diagnostic: Some(DiagnosticClientCapabilities {
+                        dynamic_registration: Some(false),
+                        related_document_support: Some(true),
+                    }),
                     formatting: Some(DynamicRegistrationClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
@@ -674,6 +838,29 @@ impl LanguageServer {
                         did_save: Some(true),
                         ..TextDocumentSyncClientCapabilities::default()
                     }),
+                    color_provider: Some(DocumentColorClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    call_hierarchy: Some(CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    type_hierarchy: Some(TypeHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    semantic_tokens: Some(SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(false),
+                        requests: SemanticTokensClientCapabilitiesRequests {
+                            range: Some(false),
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: SEMANTIC_TOKEN_TYPES.to_vec(),
+                        token_modifiers: SEMANTIC_TOKEN_MODIFIERS.to_vec(),
+                        formats: vec![TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        server_cancel_support: Some(false),
+                        augments_syntax_tokens: Some(true),
+                    }),
                     ..TextDocumentClientCapabilities::default()
                 }),
                 experimental: Some(json!({
@@ -701,7 +888,27 @@ impl LanguageServer {
         };
 
         cx.spawn(|_| async move {
-            let response = self.request::<request::Initialize>(params).await?;
+            // clangd only reports inactive regions to clients that declare support for them, in
+            // a capability that lsp-types doesn't know about.
+            let mut params = serde_json::to_value(params)?;
+            if let Some(text_document) = params
+                .pointer_mut("/capabilities/textDocument")
+                .and_then(Value::as_object_mut)
+            {
+                text_document.insert(
+                    "inactiveRegionsCapabilities".into(),
+                    json!({ "inactiveRegions": true }),
+                );
+            }
+            let response = Self::request_with_method::<Value, InitializeResult>(
+                &self.next_id,
+                &self.response_handlers,
+                &self.outbound_tx,
+                &self.executor,
+                request::Initialize::METHOD,
+                params,
+            )
+            .await?;
             if let Some(info) = response.server_info {
                 self.name = info.name.into();
             }
@@ -966,6 +1173,19 @@ impl LanguageServer {
         )
     }
 
+    /// Sends a RPC request with the given method and raw parameters, such as one that was
+    /// previously sent to the server and is being sent again, and returns the raw result.
+    pub fn request_raw(&self, method: &str, params: Value) -> impl LspRequestFuture<Result<Value>> {
+        Self::request_with_method(
+            &self.next_id,
+            &self.response_handlers,
+            &self.outbound_tx,
+            &self.executor,
+            method,
+            params,
+        )
+    }
+
     fn request_internal<T: request::Request>(
         next_id: &AtomicI32,
         response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
@@ -975,12 +1195,34 @@ impl LanguageServer {
     ) -> impl LspRequestFuture<Result<T::Result>>
     where
         T::Result: 'static + Send,
+    {
+        Self::request_with_method(
+            next_id,
+            response_handlers,
+            outbound_tx,
+            executor,
+            T::METHOD,
+            params,
+        )
+    }
+
+    fn request_with_method<P, R>(
+        next_id: &AtomicI32,
+        response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
+        outbound_tx: &channel::Sender<String>,
+        executor: &BackgroundExecutor,
+        method: &str,
+        params: P,
+    ) -> impl LspRequestFuture<Result<R>>
+    where
+        P: Serialize,
+        R: 'static + Send + DeserializeOwned,
     {
         let id = next_id.fetch_add(1, SeqCst);
         let message = serde_json::to_string(&Request {
             jsonrpc: JSON_RPC_VERSION,
             id: RequestId::Int(id),
-            method: T::METHOD,
+            method,
             params,
         })
         .unwrap();
@@ -1021,6 +1263,7 @@ impl LanguageServer {
         let outbound_tx = outbound_tx.downgrade();
         let mut timeout = executor.timer(LSP_REQUEST_TIMEOUT).fuse();
         let started = Instant::now();
+        let method = method.to_string();
         LspRequest::new(id, async move {
             handle_response?;
             send?;
@@ -1037,7 +1280,6 @@ impl LanguageServer {
                 }
             });
 
-            let method = T::METHOD;
             select! {
                 response = rx.fuse() => {
                     let elapsed = started.elapsed();
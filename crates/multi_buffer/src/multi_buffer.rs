@@ -1187,6 +1187,56 @@ impl MultiBuffer {
         context_line_count: u32,
         cx: &mut ModelContext<Self>,
     ) -> Vec<Range<Anchor>>
+    where
+        O: text::ToPoint + text::ToOffset,
+    {
+        self.insert_excerpts_with_context_lines_after(
+            ExcerptId::max(),
+            buffer,
+            ranges,
+            context_line_count,
+            cx,
+        )
+    }
+
+    /// Replaces all of the excerpts for the given buffer with excerpts around the given
+    /// ranges, keeping them at the same position in the multibuffer. If the buffer has
+    /// no excerpts yet, the new ones are appended.
+    pub fn replace_excerpts_with_context_lines<O>(
+        &mut self,
+        buffer: Model<Buffer>,
+        ranges: Vec<Range<O>>,
+        context_line_count: u32,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<Range<Anchor>>
+    where
+        O: text::ToPoint + text::ToOffset,
+    {
+        let old_excerpt_ids = self
+            .excerpts_for_buffer(&buffer, cx)
+            .into_iter()
+            .map(|(excerpt_id, _)| excerpt_id)
+            .collect::<Vec<_>>();
+        let prev_excerpt_id = old_excerpt_ids.last().copied().unwrap_or(ExcerptId::max());
+        let anchor_ranges = self.insert_excerpts_with_context_lines_after(
+            prev_excerpt_id,
+            buffer,
+            ranges,
+            context_line_count,
+            cx,
+        );
+        self.remove_excerpts(old_excerpt_ids, cx);
+        anchor_ranges
+    }
+
+    fn insert_excerpts_with_context_lines_after<O>(
+        &mut self,
+        prev_excerpt_id: ExcerptId,
+        buffer: Model<Buffer>,
+        ranges: Vec<Range<O>>,
+        context_line_count: u32,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<Range<Anchor>>
     where
         O: text::ToPoint + text::ToOffset,
     {
@@ -1195,7 +1245,7 @@ impl MultiBuffer {
         let (excerpt_ranges, range_counts) =
             build_excerpt_ranges(&buffer_snapshot, &ranges, context_line_count);
 
-        let excerpt_ids = self.push_excerpts(buffer, excerpt_ranges, cx);
+        let excerpt_ids = self.insert_excerpts_after(prev_excerpt_id, buffer, excerpt_ranges, cx);
 
         let mut anchor_ranges = Vec::new();
         let mut ranges = ranges.into_iter();
@@ -3694,6 +3744,21 @@ impl MultiBufferSnapshot {
         Some(excerpt.map_range_from_buffer(ancestor_buffer_range))
     }
 
+    pub fn range_for_enclosing_syntax_node<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        predicate: impl FnMut(&str) -> bool,
+    ) -> Option<Range<usize>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let excerpt = self.excerpt_containing(range.clone())?;
+
+        let node_buffer_range = excerpt
+            .buffer()
+            .range_for_enclosing_syntax_node(excerpt.map_range_to_buffer(range), predicate)?;
+
+        Some(excerpt.map_range_from_buffer(node_buffer_range))
+    }
+
     pub fn outline(&self, theme: Option<&SyntaxTheme>) -> Option<Outline<Anchor>> {
         let (excerpt_id, _, buffer) = self.as_singleton()?;
         let outline = buffer.outline(theme)?;
@@ -5474,6 +5539,59 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_replace_excerpts_with_context_lines(cx: &mut AppContext) {
+        let buffer_1 = cx.new_model(|cx| Buffer::local(sample_text(10, 3, 'a'), cx));
+        let buffer_2 = cx.new_model(|cx| Buffer::local(sample_text(10, 3, 'm'), cx));
+        let multibuffer = cx.new_model(|_| MultiBuffer::new(0, Capability::ReadWrite));
+        multibuffer.update(cx, |multibuffer, cx| {
+            multibuffer.push_excerpts_with_context_lines(
+                buffer_1.clone(),
+                vec![Point::new(1, 0)..Point::new(1, 1)],
+                0,
+                cx,
+            );
+            multibuffer.push_excerpts_with_context_lines(
+                buffer_2.clone(),
+                vec![Point::new(2, 0)..Point::new(2, 1)],
+                0,
+                cx,
+            );
+        });
+        assert_eq!(multibuffer.read(cx).snapshot(cx).text(), "bbb\nooo");
+
+        let anchor_ranges = multibuffer.update(cx, |multibuffer, cx| {
+            multibuffer.replace_excerpts_with_context_lines(
+                buffer_1.clone(),
+                vec![
+                    Point::new(3, 0)..Point::new(3, 1),
+                    Point::new(6, 1)..Point::new(6, 2),
+                ],
+                0,
+                cx,
+            )
+        });
+        let snapshot = multibuffer.read(cx).snapshot(cx);
+        assert_eq!(snapshot.text(), "ddd\nggg\nooo");
+        assert_eq!(
+            anchor_ranges
+                .iter()
+                .map(|range| range.to_point(&snapshot))
+                .collect::<Vec<_>>(),
+            vec![
+                Point::new(0, 0)..Point::new(0, 1),
+                Point::new(1, 1)..Point::new(1, 2),
+            ]
+        );
+        assert_eq!(
+            multibuffer
+                .read(cx)
+                .excerpts_for_buffer(&buffer_1, cx)
+                .len(),
+            2
+        );
+    }
+
     #[gpui::test]
     async fn test_stream_excerpts_with_context_lines(cx: &mut TestAppContext) {
         let buffer = cx.new_model(|cx| Buffer::local(sample_text(20, 3, 'a'), cx));
@@ -140,6 +140,21 @@ pub fn tasks_file() -> &'static PathBuf {
     TASKS_FILE.get_or_init(|| config_dir().join("tasks.json"))
 }
 
+/// Returns the path to the directory holding the user's VS Code settings and keybindings.
+pub fn vscode_user_dir() -> &'static PathBuf {
+    static VSCODE_USER_DIR: OnceLock<PathBuf> = OnceLock::new();
+    VSCODE_USER_DIR.get_or_init(|| {
+        if cfg!(target_os = "macos") {
+            return home_dir().join("Library/Application Support/Code/User");
+        }
+
+        dirs::config_dir()
+            .unwrap_or_else(|| home_dir().join(".config"))
+            .join("Code")
+            .join("User")
+    })
+}
+
 /// Returns the path to the extensions directory.
 ///
 /// This is where installed extensions are stored.
@@ -156,6 +171,14 @@ pub fn themes_dir() -> &'static PathBuf {
     THEMES_DIR.get_or_init(|| config_dir().join("themes"))
 }
 
+/// Returns the path to the icon themes directory.
+///
+/// This is where user-installed icon themes for file icons are stored.
+pub fn icon_themes_dir() -> &'static PathBuf {
+    static ICON_THEMES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    ICON_THEMES_DIR.get_or_init(|| config_dir().join("icon_themes"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the saved contexts from the Assistant are stored.
@@ -239,6 +262,14 @@ pub fn languages_dir() -> &'static PathBuf {
     LANGUAGES_DIR.get_or_init(|| support_dir().join("languages"))
 }
 
+/// Returns the path to the user's languages directory.
+///
+/// This is where languages added by the user, with their grammars and queries, are stored.
+pub fn user_languages_dir() -> &'static PathBuf {
+    static USER_LANGUAGES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    USER_LANGUAGES_DIR.get_or_init(|| config_dir().join("languages"))
+}
+
 /// Returns the path to the Copilot directory.
 pub fn copilot_dir() -> &'static PathBuf {
     static COPILOT_DIR: OnceLock<PathBuf> = OnceLock::new();
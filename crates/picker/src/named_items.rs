@@ -0,0 +1,248 @@
+use crate::{Picker, PickerDelegate};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task, View,
+    ViewContext, WeakView, WindowContext,
+};
+use std::sync::Arc;
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use workspace::ModalView;
+
+/// Items that the user saved under a name, such as saved searches or workspace layouts,
+/// which [`NamedItemsModal`] lists to open one of them or to save a new one.
+pub trait NamedItems: 'static {
+    type Item: Clone + 'static;
+
+    const OPEN_PLACEHOLDER: &'static str;
+    const SAVE_PLACEHOLDER: &'static str;
+    const NO_ITEMS_TEXT: &'static str;
+    const DELETE_TOOLTIP: &'static str;
+
+    fn name(item: &Self::Item) -> &str;
+
+    fn set_name(item: &mut Self::Item, name: String);
+
+    /// Persists the items after one of them was saved or deleted.
+    fn store(&mut self, items: &[Self::Item], cx: &mut WindowContext);
+
+    /// Opens the chosen item. `secondary` is whether the secondary confirm was used.
+    fn open(&mut self, item: &Self::Item, secondary: bool, cx: &mut WindowContext);
+
+    /// Called after the item was deleted and the remaining items were stored.
+    fn deleted(&mut self, _item: &Self::Item, _cx: &mut WindowContext) {}
+
+    /// A line describing the item, shown under its name.
+    fn details(&self, _item: &Self::Item) -> Option<SharedString> {
+        None
+    }
+}
+
+pub enum NamedItemsMode<T> {
+    /// Opens the chosen item.
+    Open,
+    /// Saves the given item under the typed name, or over the chosen item.
+    Save(T),
+}
+
+/// A modal that lists named items, to open one of them or to save an item under a new or
+/// existing name.
+pub struct NamedItemsModal<N: NamedItems> {
+    picker: View<Picker<NamedItemsDelegate<N>>>,
+}
+
+impl<N: NamedItems> NamedItemsModal<N> {
+    pub fn new(
+        named_items: N,
+        mode: NamedItemsMode<N::Item>,
+        items: Vec<N::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = NamedItemsDelegate {
+            modal: cx.view().downgrade(),
+            named_items,
+            mode,
+            matches: (0..items.len()).collect(),
+            items,
+            query: String::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl<N: NamedItems> FocusableView for NamedItemsModal<N> {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl<N: NamedItems> EventEmitter<DismissEvent> for NamedItemsModal<N> {}
+impl<N: NamedItems> ModalView for NamedItemsModal<N> {}
+
+impl<N: NamedItems> Render for NamedItemsModal<N> {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct NamedItemsDelegate<N: NamedItems> {
+    modal: WeakView<NamedItemsModal<N>>,
+    named_items: N,
+    mode: NamedItemsMode<N::Item>,
+    items: Vec<N::Item>,
+    /// The indices of the items whose names match the query.
+    matches: Vec<usize>,
+    query: String,
+    selected_index: usize,
+}
+
+impl<N: NamedItems> NamedItemsDelegate<N> {
+    /// In save mode, the first entry saves the item under the typed name, unless an item
+    /// already has that name.
+    fn has_new_entry(&self) -> bool {
+        matches!(self.mode, NamedItemsMode::Save(_))
+            && !self.query.trim().is_empty()
+            && !self
+                .items
+                .iter()
+                .any(|item| N::name(item) == self.query.trim())
+    }
+
+    fn item_ix(&self, entry_ix: usize) -> Option<usize> {
+        let match_ix = if self.has_new_entry() {
+            entry_ix.checked_sub(1)?
+        } else {
+            entry_ix
+        };
+        self.matches.get(match_ix).copied()
+    }
+
+    fn delete(&mut self, item_ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        if item_ix >= self.items.len() {
+            return;
+        }
+        let item = self.items.remove(item_ix);
+        self.named_items.store(&self.items, cx);
+        self.named_items.deleted(&item, cx);
+        self.update_matches(self.query.clone(), cx).detach();
+    }
+
+    fn dismiss(&self, cx: &mut ViewContext<Picker<Self>>) {
+        self.modal.update(cx, |_, cx| cx.emit(DismissEvent)).ok();
+    }
+}
+
+impl<N: NamedItems> PickerDelegate for NamedItemsDelegate<N> {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + self.has_new_entry() as usize
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.mode {
+            NamedItemsMode::Open => N::OPEN_PLACEHOLDER.into(),
+            NamedItemsMode::Save(_) => N::SAVE_PLACEHOLDER.into(),
+        }
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        N::NO_ITEMS_TEXT.into()
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let lowercase_query = query.trim().to_lowercase();
+        self.matches = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| N::name(item).to_lowercase().contains(&lowercase_query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.query = query;
+        self.selected_index = 0;
+        cx.notify();
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let item_ix = self.item_ix(self.selected_index);
+        match &self.mode {
+            NamedItemsMode::Open => {
+                let Some(item) = item_ix.and_then(|ix| self.items.get(ix).cloned()) else {
+                    return;
+                };
+                self.named_items.open(&item, secondary, cx);
+            }
+            NamedItemsMode::Save(item) => {
+                let mut item = item.clone();
+                match item_ix {
+                    Some(ix) => {
+                        N::set_name(&mut item, N::name(&self.items[ix]).to_string());
+                        self.items[ix] = item;
+                    }
+                    None if self.has_new_entry() => {
+                        N::set_name(&mut item, self.query.trim().to_string());
+                        self.items.push(item);
+                    }
+                    None => return,
+                }
+                self.named_items.store(&self.items, cx);
+            }
+        }
+        self.dismiss(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.dismiss(cx);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let list_item = ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .inset(true)
+            .selected(selected);
+        let Some(item_ix) = self.item_ix(ix) else {
+            return Some(list_item.child(Label::new(format!(
+                "Save as \u{201c}{}\u{201d}",
+                self.query.trim()
+            ))));
+        };
+
+        let item = self.items.get(item_ix)?;
+        Some(
+            list_item
+                .child(
+                    v_flex()
+                        .py_px()
+                        .child(Label::new(N::name(item).to_string()))
+                        .children(self.named_items.details(item).map(|details| {
+                            Label::new(details)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                        })),
+                )
+                .end_hover_slot(
+                    IconButton::new(("delete-named-item", ix), IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .tooltip(|cx| Tooltip::text(N::DELETE_TOOLTIP, cx))
+                        .on_click(cx.listener(move |picker, _, cx| {
+                            picker.delegate.delete(item_ix, cx);
+                        })),
+                ),
+        )
+    }
+}
@@ -14,6 +14,7 @@ use workspace::ModalView;
 
 mod head;
 pub mod highlighted_match_with_paths;
+pub mod named_items;
 
 enum ElementContainer {
     List(ListState),
@@ -304,13 +304,15 @@ impl BufferStore {
                     .background_executor()
                     .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
                     .await;
-                cx.insert_model(reservation, |_| {
-                    Buffer::build(
+                cx.insert_model(reservation, |cx| {
+                    let mut buffer = Buffer::build(
                         text_buffer,
                         loaded.diff_base,
                         Some(loaded.file),
                         Capability::ReadWrite,
-                    )
+                    );
+                    buffer.set_encoding(loaded.encoding, cx);
+                    buffer
                 })
             })
         });
@@ -465,6 +467,7 @@ impl BufferStore {
         let buffer = buffer_handle.read(cx);
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         let buffer_id = buffer.remote_id();
         if buffer.file().is_some_and(|file| !file.is_created()) {
@@ -472,7 +475,7 @@ impl BufferStore {
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         cx.spawn(move |this, mut cx| async move {
@@ -0,0 +1,201 @@
+use crate::{File, Project};
+use anyhow::{anyhow, Context as _, Result};
+use gpui::{AppContext, Model, ModelContext, Task};
+use language::{point_to_lsp, range_from_lsp, Buffer, PointUtf16, Unclipped};
+use lsp::{LanguageServer, LanguageServerId};
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+/// A function or method in a call hierarchy, as returned by the language server that the
+/// hierarchy was requested from.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub server_id: LanguageServerId,
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+impl CallHierarchyItem {
+    pub fn name(&self) -> &str {
+        &self.lsp_item.name
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.lsp_item.detail.as_deref()
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        self.lsp_item.uri.to_file_path().ok()
+    }
+
+    /// The range of the item's name, to jump to when opening the item.
+    pub fn selection_range(&self) -> Range<Unclipped<PointUtf16>> {
+        range_from_lsp(self.lsp_item.selection_range)
+    }
+}
+
+/// A call to or from an item of a call hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    /// The caller, for incoming calls, or the callee, for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The file that contains the call sites, which is the caller's file in both directions.
+    pub call_site_path: Option<PathBuf>,
+    pub call_sites: Vec<Range<Unclipped<PointUtf16>>>,
+}
+
+impl Project {
+    /// Whether a call hierarchy can be shown for the buffer, which takes a local project and
+    /// a language server that supports call hierarchies.
+    pub fn supports_call_hierarchy(&self, buffer: &Buffer, cx: &AppContext) -> bool {
+        self.is_local() && self.call_hierarchy_server(buffer, cx).is_some()
+    }
+
+    fn call_hierarchy_server(
+        &self,
+        buffer: &Buffer,
+        cx: &AppContext,
+    ) -> Option<Arc<LanguageServer>> {
+        self.language_servers_for_buffer(buffer, cx)
+            .find(|(_, server)| {
+                !matches!(
+                    server.capabilities().call_hierarchy_provider,
+                    None | Some(lsp::CallHierarchyServerCapability::Simple(false))
+                )
+            })
+            .map(|(_, server)| server.clone())
+    }
+
+    /// Resolves the function or method at the given position into the items of a call
+    /// hierarchy, with the first of the buffer's language servers that supports call
+    /// hierarchies.
+    pub fn prepare_call_hierarchy(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        position: PointUtf16,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "call hierarchies are only available in local projects"
+            )));
+        }
+        let buffer = buffer_handle.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let Some(server) = self.call_hierarchy_server(buffer, cx) else {
+            return Task::ready(Err(anyhow!(
+                "no language server supports call hierarchies for this file"
+            )));
+        };
+        let uri = match lsp::Url::from_file_path(file.abs_path(cx)) {
+            Ok(uri) => uri,
+            Err(()) => return Task::ready(Err(anyhow!("invalid file path"))),
+        };
+
+        cx.spawn(move |_, _| async move {
+            let items = server
+                .request::<lsp::request::CallHierarchyPrepare>(lsp::CallHierarchyPrepareParams {
+                    text_document_position_params: lsp::TextDocumentPositionParams::new(
+                        lsp::TextDocumentIdentifier::new(uri),
+                        point_to_lsp(position),
+                    ),
+                    work_done_progress_params: Default::default(),
+                })
+                .await
+                .context("preparing a call hierarchy")?;
+            Ok(items
+                .unwrap_or_default()
+                .into_iter()
+                .map(|lsp_item| CallHierarchyItem {
+                    server_id: server.server_id(),
+                    lsp_item,
+                })
+                .collect())
+        })
+    }
+
+    /// Requests the functions and methods that call the given item.
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "call hierarchies are only available in local projects"
+            )));
+        }
+        let Some(server) = self.language_server_for_id(item.server_id) else {
+            return Task::ready(Err(anyhow!("the language server is no longer running")));
+        };
+        let server_id = item.server_id;
+        let lsp_item = item.lsp_item.clone();
+        cx.spawn(move |_, _| async move {
+            let calls = server
+                .request::<lsp::request::CallHierarchyIncomingCalls>(
+                    lsp::CallHierarchyIncomingCallsParams {
+                        item: lsp_item,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                )
+                .await
+                .context("requesting incoming calls")?;
+            Ok(calls
+                .unwrap_or_default()
+                .into_iter()
+                .map(|call| HierarchyCall {
+                    call_site_path: call.from.uri.to_file_path().ok(),
+                    call_sites: call.from_ranges.into_iter().map(range_from_lsp).collect(),
+                    item: CallHierarchyItem {
+                        server_id,
+                        lsp_item: call.from,
+                    },
+                })
+                .collect())
+        })
+    }
+
+    /// Requests the functions and methods that the given item calls.
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "call hierarchies are only available in local projects"
+            )));
+        }
+        let Some(server) = self.language_server_for_id(item.server_id) else {
+            return Task::ready(Err(anyhow!("the language server is no longer running")));
+        };
+        let server_id = item.server_id;
+        let lsp_item = item.lsp_item.clone();
+        let call_site_path = item.path();
+        cx.spawn(move |_, _| async move {
+            let calls = server
+                .request::<lsp::request::CallHierarchyOutgoingCalls>(
+                    lsp::CallHierarchyOutgoingCallsParams {
+                        item: lsp_item,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                )
+                .await
+                .context("requesting outgoing calls")?;
+            Ok(calls
+                .unwrap_or_default()
+                .into_iter()
+                .map(|call| HierarchyCall {
+                    call_site_path: call_site_path.clone(),
+                    call_sites: call.from_ranges.into_iter().map(range_from_lsp).collect(),
+                    item: CallHierarchyItem {
+                        server_id,
+                        lsp_item: call.to,
+                    },
+                })
+                .collect())
+        })
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{File, Project, ProjectTransaction};
+use anyhow::{anyhow, Context as _, Result};
+use futures::future::join_all;
+use gpui::{Model, ModelContext, Task};
+use language::{language_settings::language_settings, range_from_lsp, Anchor, Bias, Buffer};
+use lsp::LanguageServerId;
+use std::ops::Range;
+use util::ResultExt;
+
+/// A command that a language server shows above a range of a buffer, like "Run test" or
+/// "3 references".
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    pub server_id: LanguageServerId,
+    pub range: Range<Anchor>,
+    pub lsp_lens: lsp::CodeLens,
+}
+
+impl CodeLens {
+    /// The title of the lens, which is only known once the lens is resolved.
+    pub fn title(&self) -> Option<&str> {
+        let command = self.lsp_lens.command.as_ref()?;
+        Some(command.title.as_str())
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.lsp_lens.command.is_some()
+    }
+}
+
+impl Project {
+    /// Requests the code lenses of the buffer from all of its language servers that provide them.
+    /// Lenses may be returned without a command, in which case they need to be resolved
+    /// with [`Project::resolve_code_lens`] before being shown.
+    pub fn code_lens(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        if !self.is_local() {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let buffer = buffer_handle.read(cx);
+        if !language_settings(buffer.language(), buffer.file(), cx).code_lens {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let uri = match lsp::Url::from_file_path(file.abs_path(cx)) {
+            Ok(uri) => uri,
+            Err(()) => return Task::ready(Err(anyhow!("invalid file path"))),
+        };
+        let snapshot = buffer.text_snapshot();
+        let requests = self
+            .language_servers_for_buffer(buffer, cx)
+            .filter(|(_, server)| server.capabilities().code_lens_provider.is_some())
+            .map(|(_, server)| {
+                let server_id = server.server_id();
+                let request =
+                    server.request::<lsp::request::CodeLensRequest>(lsp::CodeLensParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    });
+                async move { (server_id, request.await) }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |_, _| async move {
+            let mut code_lenses = Vec::new();
+            for (server_id, response) in join_all(requests).await {
+                let Some(lenses) = response.log_err().flatten() else {
+                    continue;
+                };
+                for lsp_lens in lenses {
+                    let range = range_from_lsp(lsp_lens.range);
+                    let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                    let end = snapshot.clip_point_utf16(range.end, Bias::Right);
+                    code_lenses.push(CodeLens {
+                        server_id,
+                        range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                        lsp_lens,
+                    });
+                }
+            }
+            Ok(code_lenses)
+        })
+    }
+
+    /// Fills in the command of a lens that was returned without one.
+    pub fn resolve_code_lens(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        code_lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<CodeLens>> {
+        if code_lens.is_resolved() {
+            return Task::ready(Ok(code_lens));
+        }
+        let buffer = buffer_handle.read(cx);
+        let Some((_, server)) = self.language_server_for_buffer(buffer, code_lens.server_id, cx)
+        else {
+            return Task::ready(Err(anyhow!("no language server for code lens")));
+        };
+        let can_resolve = server
+            .capabilities()
+            .code_lens_provider
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false);
+        if !can_resolve {
+            return Task::ready(Ok(code_lens));
+        }
+
+        let server = server.clone();
+        cx.spawn(move |_, _| async move {
+            let lsp_lens = server
+                .request::<lsp::request::CodeLensResolve>(code_lens.lsp_lens.clone())
+                .await
+                .context("resolving a code lens")?;
+            Ok(CodeLens {
+                lsp_lens,
+                ..code_lens
+            })
+        })
+    }
+
+    /// Executes the command of a resolved lens, returning the edits that the language server
+    /// applied while running it.
+    pub fn apply_code_lens(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        code_lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        if !self.is_local() {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        }
+        let Some(command) = code_lens.lsp_lens.command else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        let buffer = buffer_handle.read(cx);
+        let Some((_, server)) = self.language_server_for_buffer(buffer, code_lens.server_id, cx)
+        else {
+            return Task::ready(Err(anyhow!("no language server for code lens")));
+        };
+        // Lenses can have commands that are meant to be run by the client, and these are
+        // not sent to the server.
+        let supports_command = server
+            .capabilities()
+            .execute_command_provider
+            .as_ref()
+            .map_or(false, |options| options.commands.contains(&command.command));
+        if !supports_command {
+            log::warn!(
+                "language server {} doesn't support the code lens command {:?}",
+                server.name(),
+                command.command
+            );
+            return Task::ready(Ok(ProjectTransaction::default()));
+        }
+        let server = server.clone();
+
+        cx.spawn(move |this, mut cx| async move {
+            Self::execute_lsp_command(this, server, command, &mut cx)
+                .await
+                .context("executing a code lens command")
+        })
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{File, Project};
+use anyhow::{anyhow, Result};
+use gpui::{Model, ModelContext, Task};
+use language::{language_settings::language_settings, range_from_lsp, Anchor, Bias, Buffer};
+use std::ops::Range;
+
+/// A color literal that a language server found in a buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentColor {
+    pub range: Range<Anchor>,
+    pub color: lsp::Color,
+}
+
+impl Project {
+    /// Requests the colors of the buffer from the first of its language servers that
+    /// provides them. Returns `None` when none of the servers does, so that callers can
+    /// detect colors on their own.
+    pub fn document_colors(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Vec<DocumentColor>>>> {
+        if !self.is_local() {
+            return Task::ready(Ok(None));
+        }
+        let buffer = buffer_handle.read(cx);
+        if !language_settings(buffer.language(), buffer.file(), cx).color_swatches {
+            return Task::ready(Ok(Some(Vec::new())));
+        }
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Task::ready(Ok(None));
+        };
+        let Some(server) = self
+            .language_servers_for_buffer(buffer, cx)
+            .find(|(_, server)| {
+                !matches!(
+                    server.capabilities().color_provider,
+                    None | Some(lsp::ColorProviderCapability::Simple(false))
+                )
+            })
+            .map(|(_, server)| server.clone())
+        else {
+            return Task::ready(Ok(None));
+        };
+        let text_document = match lsp::Url::from_file_path(file.abs_path(cx)) {
+            Ok(uri) => lsp::TextDocumentIdentifier::new(uri),
+            Err(()) => return Task::ready(Err(anyhow!("invalid file path"))),
+        };
+        let snapshot = buffer.text_snapshot();
+
+        cx.spawn(move |_, _| async move {
+            let colors = server
+                .request::<lsp::request::DocumentColor>(lsp::DocumentColorParams {
+                    text_document,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await?;
+            Ok(Some(
+                colors
+                    .into_iter()
+                    .filter_map(|info| {
+                        let range = range_from_lsp(info.range);
+                        let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                        let end = snapshot.clip_point_utf16(range.end, Bias::Right);
+                        (start < end).then(|| DocumentColor {
+                            range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                            color: info.color,
+                        })
+                    })
+                    .collect(),
+            ))
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Event, Project, ProjectPath};
+use gpui::ModelContext;
+use language::{range_from_lsp, Anchor, Bias, Buffer};
+use lsp::LanguageServerId;
+use std::ops::Range;
+use text::BufferId;
+
+impl Project {
+    /// The regions of the buffer that its language servers reported as inactive, such as code
+    /// that the preprocessor or `#[cfg]` attributes exclude. Only local projects receive them.
+    pub fn inactive_regions(&self, buffer: &Buffer) -> Vec<Range<Anchor>> {
+        let buffer_id = buffer.remote_id();
+        let mut regions = self
+            .inactive_regions
+            .iter()
+            .filter(|((region_buffer_id, _), _)| *region_buffer_id == buffer_id)
+            .flat_map(|(_, regions)| regions.iter().cloned())
+            .collect::<Vec<_>>();
+        regions.sort_by(|a, b| a.start.cmp(&b.start, buffer));
+        regions
+    }
+
+    /// Replaces the inactive regions that a language server reported for a document. They're
+    /// only kept for open buffers, as servers report them again when a document is opened.
+    pub(crate) fn update_inactive_regions(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: &lsp::Url,
+        regions: Vec<lsp::Range>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Ok(abs_path) = uri.to_file_path() else {
+            return;
+        };
+        let Some((worktree, relative_path)) = self.find_worktree(&abs_path, cx) else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path.into(),
+        };
+        let Some(buffer) = self.get_open_buffer(&project_path, cx) else {
+            return;
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_id = snapshot.remote_id();
+        let regions = regions
+            .into_iter()
+            .map(|range| {
+                let range = range_from_lsp(range);
+                let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+                snapshot.anchor_after(start)..snapshot.anchor_before(end)
+            })
+            .collect::<Vec<_>>();
+        if regions.is_empty() {
+            if self
+                .inactive_regions
+                .remove(&(buffer_id, server_id))
+                .is_none()
+            {
+                return;
+            }
+        } else {
+            self.inactive_regions
+                .insert((buffer_id, server_id), regions);
+        }
+        cx.emit(Event::InactiveRegionsUpdated(buffer_id));
+    }
+
+    pub(crate) fn forget_inactive_regions(&mut self, buffer_id: BufferId) {
+        self.inactive_regions
+            .retain(|(region_buffer_id, _), _| *region_buffer_id != buffer_id);
+    }
+
+    pub(crate) fn forget_inactive_regions_for_server(&mut self, server_id: LanguageServerId) {
+        self.inactive_regions
+            .retain(|(_, region_server_id), _| *region_server_id != server_id);
+    }
+}
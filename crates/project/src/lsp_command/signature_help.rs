@@ -27,17 +27,30 @@ pub const SIGNATURE_HELP_HIGHLIGHT_OVERLOAD: MarkdownHighlight =
 pub struct SignatureHelp {
     pub markdown: String,
     pub highlights: Vec<(Range<usize>, MarkdownHighlight)>,
+    /// The documentation of the active parameter, followed by that of the signature, as
+    /// Markdown.
+    pub documentation: Option<String>,
+    /// The index of the shown signature, out of the [`Self::signature_count`] overloads.
+    pub active_signature: usize,
+    pub signature_count: usize,
     pub(super) original_data: lsp::SignatureHelp,
+    language: Option<Arc<Language>>,
 }
 
 impl SignatureHelp {
     pub fn new(help: lsp::SignatureHelp, language: Option<Arc<Language>>) -> Option<Self> {
         let function_options_count = help.signatures.len();
 
-        let signature_information = help
+        let active_signature = help
             .active_signature
-            .and_then(|active_signature| help.signatures.get(active_signature as usize))
-            .or_else(|| help.signatures.first())?;
+            .map(|active_signature| active_signature as usize)
+            .filter(|active_signature| *active_signature < function_options_count)
+            .unwrap_or(0);
+        let signature_information = help.signatures.get(active_signature)?;
+        // Servers may report the active parameter of each signature separately.
+        let active_parameter = signature_information
+            .active_parameter
+            .or(help.active_parameter);
 
         let str_for_join = ", ";
         let parameter_length = signature_information
@@ -45,6 +58,7 @@ impl SignatureHelp {
             .as_ref()
             .map_or(0, |parameters| parameters.len());
         let mut highlight_start = 0;
+        let mut active_parameter_documentation = None;
         let (markdown, mut highlights): (Vec<_>, Vec<_>) = signature_information
             .parameters
             .as_ref()?
@@ -62,8 +76,14 @@ impl SignatureHelp {
                 };
                 let label_length = label.len();
 
-                let highlights = help.active_parameter.and_then(|active_parameter| {
+                let highlights = active_parameter.and_then(|active_parameter| {
                     if i == active_parameter as usize {
+                        active_parameter_documentation = parameter_information
+                            .documentation
+                            .clone()
+                            .map(|documentation| {
+                                format!("`{label}` — {}", documentation_markdown(documentation))
+                            });
                         Some((
                             highlight_start..(highlight_start + label_length),
                             SIGNATURE_HELP_HIGHLIGHT_CURRENT,
@@ -86,6 +106,7 @@ impl SignatureHelp {
         } else {
             let markdown = markdown.join(str_for_join);
             let language_name = language
+                .as_ref()
                 .map(|n| n.name().to_lowercase())
                 .unwrap_or_default();
 
@@ -101,13 +122,44 @@ impl SignatureHelp {
                 format!("```{language_name}\n{markdown}")
             };
 
+            let documentation = active_parameter_documentation
+                .into_iter()
+                .chain(
+                    signature_information
+                        .documentation
+                        .clone()
+                        .map(documentation_markdown),
+                )
+                .filter(|documentation| !documentation.trim().is_empty())
+                .collect::<Vec<_>>();
+            let documentation = (!documentation.is_empty()).then(|| documentation.join("\n\n"));
+
             Some(Self {
                 markdown,
                 highlights: highlights.into_iter().flatten().collect(),
+                documentation,
+                active_signature,
+                signature_count: function_options_count,
                 original_data: help,
+                language,
             })
         }
     }
+
+    /// Returns the help for another one of the signatures, such as another overload of the
+    /// function.
+    pub fn with_active_signature(&self, active_signature: usize) -> Option<Self> {
+        let mut help = self.original_data.clone();
+        help.active_signature = Some(active_signature as u32);
+        Self::new(help, self.language.clone())
+    }
+}
+
+fn documentation_markdown(documentation: lsp::Documentation) -> String {
+    match documentation {
+        lsp::Documentation::String(string) => string,
+        lsp::Documentation::MarkupContent(content) => content.value,
+    }
 }
 
 pub fn lsp_to_proto_signature(lsp_help: lsp::SignatureHelp) -> proto::SignatureHelp {
@@ -633,4 +685,72 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_signature_help_documentation_and_overloads() {
+        let signature_help = lsp::SignatureHelp {
+            signatures: vec![
+                lsp::SignatureInformation {
+                    label: "fn test1(foo: u8, bar: &str)".to_string(),
+                    documentation: Some(lsp::Documentation::String("Tests the foo.".to_string())),
+                    parameters: Some(vec![
+                        lsp::ParameterInformation {
+                            label: lsp::ParameterLabel::Simple("foo: u8".to_string()),
+                            documentation: None,
+                        },
+                        lsp::ParameterInformation {
+                            label: lsp::ParameterLabel::Simple("bar: &str".to_string()),
+                            documentation: Some(lsp::Documentation::MarkupContent(
+                                lsp::MarkupContent {
+                                    kind: lsp::MarkupKind::Markdown,
+                                    value: "The *bar*.".to_string(),
+                                },
+                            )),
+                        },
+                    ]),
+                    active_parameter: None,
+                },
+                lsp::SignatureInformation {
+                    label: "fn test2(hoge: String, fuga: bool)".to_string(),
+                    documentation: None,
+                    parameters: Some(vec![
+                        lsp::ParameterInformation {
+                            label: lsp::ParameterLabel::Simple("hoge: String".to_string()),
+                            documentation: None,
+                        },
+                        lsp::ParameterInformation {
+                            label: lsp::ParameterLabel::Simple("fuga: bool".to_string()),
+                            documentation: None,
+                        },
+                    ]),
+                    active_parameter: Some(0),
+                },
+            ],
+            active_signature: Some(0),
+            active_parameter: Some(1),
+        };
+        let signature_help = SignatureHelp::new(signature_help, None).unwrap();
+        assert_eq!(signature_help.active_signature, 0);
+        assert_eq!(signature_help.signature_count, 2);
+        assert_eq!(
+            signature_help.documentation.as_deref(),
+            Some("`bar: &str` — The *bar*.\n\nTests the foo.")
+        );
+
+        // The second signature reports its own active parameter.
+        let overload = signature_help.with_active_signature(1).unwrap();
+        assert_eq!(overload.active_signature, 1);
+        assert_eq!(overload.signature_count, 2);
+        assert_eq!(
+            (overload.markdown, overload.highlights),
+            (
+                "```\nhoge: String, fuga: bool (+1 overload)".to_string(),
+                vec![
+                    (0..12, SIGNATURE_HELP_HIGHLIGHT_CURRENT),
+                    (25..38, SIGNATURE_HELP_HIGHLIGHT_OVERLOAD)
+                ]
+            )
+        );
+        assert_eq!(overload.documentation, None);
+    }
 }
@@ -1,13 +1,21 @@
 pub mod buffer_store;
+mod call_hierarchy;
+mod code_lens;
 pub mod connection_manager;
 pub mod debounced_delay;
+mod document_colors;
+mod inactive_regions;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
+mod pull_diagnostics;
 pub mod search;
+mod semantic_tokens;
+pub mod structural_search;
 mod task_inventory;
 pub mod terminals;
+mod type_hierarchy;
 pub mod worktree_store;
 
 #[cfg(test)]
@@ -24,7 +32,7 @@ use client::{
     TypedEnvelope, UserStore,
 };
 use clock::ReplicaId;
-use collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use debounced_delay::DebouncedDelay;
 use futures::{
     channel::mpsc::{self, UnboundedReceiver},
@@ -43,6 +51,7 @@ use gpui::{
 use http_client::HttpClient;
 use itertools::Itertools;
 use language::{
+    char_kind,
     language_settings::{
         language_settings, AllLanguageSettings, FormatOnSave, Formatter, InlayHintKind,
         LanguageSettings, SelectedFormatter,
@@ -52,11 +61,11 @@ use language::{
         deserialize_anchor, deserialize_version, serialize_anchor, serialize_line_ending,
         serialize_version, split_operations,
     },
-    range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel,
-    ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation,
+    range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CharKind,
+    CodeLabel, ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation,
     Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName, LocalFile,
-    LspAdapterDelegate, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot, ToOffset,
-    ToPointUtf16, Transaction, Unclipped,
+    LspAdapter, LspAdapterDelegate, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot,
+    ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
 use log::error;
 use lsp::{
@@ -74,14 +83,18 @@ use paths::{
 };
 use postage::watch;
 use prettier_support::{DefaultPrettier, PrettierInstance};
-use project_settings::{DirenvSettings, LspSettings, ProjectSettings};
+use project_settings::{
+    take_settings_running_commands, DirenvSettings, LspSettings, ProjectSettings, TrustedWorktrees,
+};
 use rand::prelude::*;
 use remote::SshSession;
 use rpc::{proto::AddWorktree, ErrorCode};
 use search::SearchQuery;
 use search_history::SearchHistory;
 use serde::Serialize;
-use settings::{watch_config_file, Settings, SettingsLocation, SettingsStore};
+use settings::{
+    parse_json_with_comments, watch_config_file, Settings, SettingsLocation, SettingsStore,
+};
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use smol::{
@@ -123,13 +136,19 @@ use worktree::{CreatedEntry, Snapshot, Traversal};
 use worktree_store::{WorktreeStore, WorktreeStoreEvent};
 use yarn::YarnPathStore;
 
+pub use call_hierarchy::{CallHierarchyItem, HierarchyCall};
+pub use code_lens::CodeLens;
+pub use document_colors::DocumentColor;
 pub use fs::*;
 pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
+pub use semantic_tokens::SemanticToken;
 pub use task_inventory::{
-    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskSourceKind,
+    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskProblemMarker, TaskRun,
+    TaskRunState, TaskSourceKind,
 };
+pub use type_hierarchy::TypeHierarchyItem;
 pub use worktree::{
     Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId, RepositoryEntry,
     UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId, WorktreeSettings,
@@ -178,6 +197,8 @@ pub struct Project {
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
     last_formatting_failure: Option<String>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
+    /// The number of commands that each language server is running for this project.
+    pending_lsp_commands: HashMap<LanguageServerId, usize>,
     language_server_watched_paths: HashMap<LanguageServerId, HashMap<WorktreeId, GlobSet>>,
     language_server_watcher_registrations:
         HashMap<LanguageServerId, HashMap<String, Vec<FileSystemWatcher>>>,
@@ -212,6 +233,15 @@ pub struct Project {
         HashMap<Arc<Path>, Shared<Task<Result<Model<Worktree>, Arc<anyhow::Error>>>>>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
     buffers_being_formatted: HashSet<BufferId>,
+    semantic_tokens: HashMap<(BufferId, LanguageServerId), semantic_tokens::CachedSemanticTokens>,
+    inactive_regions: HashMap<(BufferId, LanguageServerId), Vec<Range<Anchor>>>,
+    pulled_diagnostics: pull_diagnostics::PulledDiagnostics,
+    /// The contents of the local settings files of the untrusted worktrees that run commands,
+    /// by directory. They're applied without those settings until the worktree is trusted.
+    untrusted_local_settings: HashMap<WorktreeId, BTreeMap<Arc<Path>, String>>,
+    /// The local tasks files of the untrusted worktrees, by path in the worktree. They're
+    /// loaded once the worktree is trusted.
+    untrusted_tasks_files: HashMap<WorktreeId, BTreeMap<Arc<Path>, LocalTasksFile>>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay<Self>,
     nonce: u128,
@@ -266,6 +296,13 @@ enum LocalProjectUpdate {
     },
 }
 
+/// The format of a tasks file in a worktree.
+#[derive(Clone, Copy, Debug)]
+enum LocalTasksFile {
+    Zed,
+    VsCode,
+}
+
 #[derive(Debug)]
 enum ProjectClientState {
     Local,
@@ -353,8 +390,20 @@ pub enum Event {
     Reshared,
     Rejoined,
     RefreshInlayHints,
+    InactiveRegionsUpdated(BufferId),
     RevealInProjectPanel(ProjectEntryId),
+    /// The local settings of a worktree contain settings that run commands, which aren't
+    /// applied until the worktree is trusted.
+    UntrustedLocalSettings(WorktreeId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    /// A language server applied a workspace edit on its own, rather than while running a
+    /// command it was asked to, so the edit isn't shown anywhere yet.
+    WorkspaceEditApplied {
+        label: Option<String>,
+        transaction: ProjectTransaction,
+        /// Whether the server asked for the edit to be reviewed before being kept.
+        needs_confirmation: bool,
+    },
 }
 
 pub enum LanguageServerState {
@@ -605,7 +654,7 @@ impl Hover {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ProjectTransaction(pub HashMap<Model<Buffer>, language::Transaction>);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -842,9 +891,15 @@ impl Project {
                 language_server_statuses: Default::default(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
+                pending_lsp_commands: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
                 buffers_being_formatted: Default::default(),
+                semantic_tokens: Default::default(),
+                inactive_regions: Default::default(),
+                pulled_diagnostics: Default::default(),
+                untrusted_local_settings: Default::default(),
+                untrusted_tasks_files: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 nonce: StdRng::from_entropy().gen(),
@@ -1033,9 +1088,15 @@ impl Project {
                     .collect(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
+                pending_lsp_commands: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
                 buffers_being_formatted: Default::default(),
+                semantic_tokens: Default::default(),
+                inactive_regions: Default::default(),
+                pulled_diagnostics: Default::default(),
+                untrusted_local_settings: Default::default(),
+                untrusted_tasks_files: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 buffer_snapshots: Default::default(),
@@ -1292,23 +1353,26 @@ impl Project {
                 let file = worktree.as_ref().and_then(|tree| {
                     tree.update(cx, |tree, cx| tree.root_file(cx).map(|f| f as _))
                 });
-                if !language_settings(Some(language), file.as_ref(), cx).enable_language_server {
+                let disabled_in_lsp_settings = new_lsp_settings
+                    .get(&adapter.name.0)
+                    .is_some_and(|settings| !settings.is_enabled());
+                if !language_settings(Some(language), file.as_ref(), cx).enable_language_server
+                    || disabled_in_lsp_settings
+                {
                     language_servers_to_stop.push((*worktree_id, started_lsp_name.clone()));
                 } else if let Some(worktree) = worktree {
                     let server_name = &adapter.name.0;
-                    match (
-                        current_lsp_settings.get(server_name),
-                        new_lsp_settings.get(server_name),
-                    ) {
-                        (None, None) => {}
-                        (Some(_), None) | (None, Some(_)) => {
-                            language_servers_to_restart.push((worktree, Arc::clone(language)));
-                        }
-                        (Some(current_lsp_settings), Some(new_lsp_settings)) => {
-                            if current_lsp_settings != new_lsp_settings {
-                                language_servers_to_restart.push((worktree, Arc::clone(language)));
-                            }
-                        }
+                    let default_lsp_settings = LspSettings::default();
+                    let current_server_settings = current_lsp_settings
+                        .get(server_name)
+                        .unwrap_or(&default_lsp_settings);
+                    let new_server_settings = new_lsp_settings
+                        .get(server_name)
+                        .unwrap_or(&default_lsp_settings);
+                    // Changes to the workspace configuration alone are sent to the running
+                    // server by `maintain_workspace_config`.
+                    if current_server_settings.requires_restart(new_server_settings) {
+                        language_servers_to_restart.push((worktree, Arc::clone(language)));
                     }
                 }
             }
@@ -2292,6 +2356,8 @@ impl Project {
                 }
             }
         }
+
+        self.pull_buffer_diagnostics(buffer_handle, false, cx);
     }
 
     fn unregister_buffer_from_language_servers(
@@ -2319,6 +2385,9 @@ impl Project {
             }
 
             self.buffer_snapshots.remove(&buffer.remote_id());
+            self.forget_semantic_tokens(buffer.remote_id());
+            self.forget_inactive_regions(buffer.remote_id());
+            self.forget_pulled_diagnostics(buffer.remote_id());
             let file_url = lsp::Url::from_file_path(old_path).unwrap();
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server
@@ -2529,6 +2598,7 @@ impl Project {
             }
 
             BufferEvent::Edited { .. } => {
+                self.pull_buffer_diagnostics(&buffer, true, cx);
                 let buffer = buffer.read(cx);
                 let file = File::from_dyn(buffer.file())?;
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -2896,7 +2966,8 @@ impl Project {
                 })?;
 
                 for (adapter, server, delegate) in servers {
-                    let settings = adapter.workspace_configuration(&delegate, &mut cx).await?;
+                    let settings =
+                        adapter_workspace_configuration(adapter, &delegate, &mut cx).await?;
 
                     server
                         .notify::<lsp::notification::DidChangeConfiguration>(
@@ -2921,6 +2992,14 @@ impl Project {
         let Some(file) = buffer.file() else {
             return;
         };
+        // Parsing and language servers are too slow for very large files, so those
+        // are treated as plain text unless a language is explicitly selected.
+        if ProjectSettings::get_global(cx)
+            .large_file
+            .is_large_file(buffer)
+        {
+            return;
+        }
         let content = buffer.as_rope();
         let Some(new_language_result) = self
             .languages
@@ -2994,8 +3073,16 @@ impl Project {
             .map(|lsp_adapter| lsp_adapter.name.clone())
             .collect::<Vec<_>>();
 
-        let desired_language_servers =
-            settings.customized_language_servers(&available_language_servers);
+        let lsp_settings = &ProjectSettings::get_global(cx).lsp;
+        let desired_language_servers = settings
+            .customized_language_servers(&available_language_servers)
+            .into_iter()
+            .filter(|server_name| {
+                lsp_settings
+                    .get(&server_name.0)
+                    .map_or(true, |settings| settings.is_enabled())
+            })
+            .collect::<Vec<_>>();
 
         let mut enabled_lsp_adapters: Vec<Arc<CachedLspAdapter>> = Vec::new();
         for desired_language_server in desired_language_servers {
@@ -3065,6 +3152,20 @@ impl Project {
             return;
         }
 
+        let project_settings = ProjectSettings::get(
+            Some(SettingsLocation {
+                worktree_id: worktree_id.to_proto() as usize,
+                path: Path::new(""),
+            }),
+            cx,
+        );
+        let lsp = project_settings.lsp.get(&adapter.name.0);
+        let override_options = lsp.and_then(|s| s.initialization_options.clone());
+        let user_server = lsp
+            .and_then(|s| s.binary.as_ref())
+            .and_then(|binary| binary.user_language_server(&worktree_path).log_err())
+            .flatten();
+
         let stderr_capture = Arc::new(Mutex::new(Some(String::new())));
         let lsp_adapter_delegate = ProjectLspAdapterDelegate::new(self, worktree_handle, cx);
         let pending_server = match self.languages.create_pending_language_server(
@@ -3073,22 +3174,13 @@ impl Project {
             adapter.clone(),
             Arc::clone(&worktree_path),
             lsp_adapter_delegate.clone(),
+            user_server,
             cx,
         ) {
             Some(pending_server) => pending_server,
             None => return,
         };
 
-        let project_settings = ProjectSettings::get(
-            Some(SettingsLocation {
-                worktree_id: worktree_id.to_proto() as usize,
-                path: Path::new(""),
-            }),
-            cx,
-        );
-        let lsp = project_settings.lsp.get(&adapter.name.0);
-        let override_options = lsp.and_then(|s| s.initialization_options.clone());
-
         let server_id = pending_server.server_id;
         let container_dir = pending_server.container_dir.clone();
         let state = LanguageServerState::Starting({
@@ -3264,11 +3356,8 @@ impl Project {
         server_id: LanguageServerId,
         cx: &mut AsyncAppContext,
     ) -> Result<Arc<LanguageServer>> {
-        let workspace_config = adapter
-            .adapter
-            .clone()
-            .workspace_configuration(&delegate, cx)
-            .await?;
+        let workspace_config =
+            adapter_workspace_configuration(adapter.adapter.clone(), &delegate, cx).await?;
         let (language_server, mut initialization_options) = pending_server.task.await?;
 
         let name = language_server.name();
@@ -3280,8 +3369,12 @@ impl Project {
                     let adapter = adapter.clone();
                     if let Some(this) = this.upgrade() {
                         adapter.process_diagnostics(&mut params);
+                        let inactive_regions = adapter.inactive_regions(&params);
                         this.update(&mut cx, |this, cx| {
-                            this.update_diagnostics(
+                            if let Some(regions) = inactive_regions {
+                                this.update_inactive_regions(server_id, &params.uri, regions, cx);
+                            }
+                            this.publish_diagnostics(
                                 server_id,
                                 params,
                                 &adapter.disk_based_diagnostic_sources,
@@ -3304,7 +3397,7 @@ impl Project {
                     let delegate = delegate.clone();
                     async move {
                         let workspace_config =
-                            adapter.workspace_configuration(&delegate, &mut cx).await?;
+                            adapter_workspace_configuration(adapter, &delegate, &mut cx).await?;
                         Ok(params
                             .items
                             .into_iter()
@@ -3549,6 +3642,21 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = project.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            project.pull_server_diagnostics(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = project.clone();
@@ -3631,6 +3739,24 @@ impl Project {
                 }
             })
             .detach();
+        language_server
+            .on_notification::<lsp::InactiveRegions, _>({
+                let this = project.clone();
+                move |params, mut cx| {
+                    if let Some(this) = this.upgrade() {
+                        this.update(&mut cx, |this, cx| {
+                            this.update_inactive_regions(
+                                server_id,
+                                &params.text_document.uri,
+                                params.regions,
+                                cx,
+                            );
+                        })
+                        .ok();
+                    }
+                }
+            })
+            .detach();
         language_server
             .on_notification::<lsp::notification::ShowMessage, _>({
                 let this = project.clone();
@@ -3865,6 +3991,7 @@ impl Project {
             }
             anyhow::Ok(())
         })?;
+        self.pull_server_diagnostics(server_id, cx);
 
         cx.notify();
         Ok(())
@@ -3941,6 +4068,9 @@ impl Project {
 
             self.language_server_watched_paths.remove(&server_id);
             self.language_server_statuses.remove(&server_id);
+            self.forget_semantic_tokens_for_server(server_id);
+            self.forget_inactive_regions_for_server(server_id);
+            self.forget_pulled_diagnostics_for_server(server_id);
             cx.notify();
 
             let server_state = self.language_servers.remove(&server_id);
@@ -4534,7 +4664,8 @@ impl Project {
         let language_server = this
             .update(&mut cx, |this, _| this.language_server_for_id(server_id))?
             .ok_or_else(|| anyhow!("language server not found"))?;
-        let transaction = Self::deserialize_workspace_edit(
+        let needs_confirmation = workspace_edit_needs_confirmation(&params.edit);
+        let transaction = match Self::deserialize_workspace_edit(
             this.clone(),
             params.edit,
             true,
@@ -4543,11 +4674,31 @@ impl Project {
             &mut cx,
         )
         .await
-        .log_err();
-        this.update(&mut cx, |this, _| {
-            if let Some(transaction) = transaction {
+        {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                log::error!("failed to apply workspace edit: {error:#}");
+                return Ok(lsp::ApplyWorkspaceEditResponse {
+                    applied: false,
+                    failed_change: None,
+                    failure_reason: Some(format!("{error:#}")),
+                });
+            }
+        };
+        this.update(&mut cx, |this, cx| {
+            // The edits made while running a command are returned to whoever ran it.
+            if this.pending_lsp_commands.contains_key(&server_id) {
                 this.last_workspace_edits_by_language_server
-                    .insert(server_id, transaction);
+                    .entry(server_id)
+                    .or_default()
+                    .0
+                    .extend(transaction.0);
+            } else if !transaction.0.is_empty() {
+                cx.emit(Event::WorkspaceEditApplied {
+                    label: params.label,
+                    transaction,
+                    needs_confirmation,
+                });
             }
         })?;
         Ok(lsp::ApplyWorkspaceEditResponse {
@@ -4557,6 +4708,53 @@ impl Project {
         })
     }
 
+    /// Runs a command on a language server, returning the edits that the server applied
+    /// while running it. Commands that the server doesn't list as supported are skipped.
+    async fn execute_lsp_command(
+        this: WeakModel<Self>,
+        language_server: Arc<LanguageServer>,
+        command: lsp::Command,
+        cx: &mut AsyncAppContext,
+    ) -> Result<ProjectTransaction> {
+        let server_id = language_server.server_id();
+        if let Some(options) = language_server.capabilities().execute_command_provider {
+            if !options.commands.contains(&command.command) {
+                log::warn!(
+                    "language server {} doesn't support the command {:?}",
+                    language_server.name(),
+                    command.command
+                );
+                return Ok(ProjectTransaction::default());
+            }
+        }
+
+        this.update(cx, |this, _| {
+            *this.pending_lsp_commands.entry(server_id).or_default() += 1;
+        })?;
+        let result = language_server
+            .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                command: command.command.clone(),
+                arguments: command.arguments.unwrap_or_default(),
+                ..Default::default()
+            })
+            .await;
+        let transaction = this.update(cx, |this, _| {
+            if let hash_map::Entry::Occupied(mut pending) =
+                this.pending_lsp_commands.entry(server_id)
+            {
+                *pending.get_mut() -= 1;
+                if *pending.get() == 0 {
+                    pending.remove();
+                }
+            }
+            this.last_workspace_edits_by_language_server
+                .remove(&server_id)
+                .unwrap_or_default()
+        })?;
+        result.with_context(|| format!("executing the command {:?}", command.command))?;
+        Ok(transaction)
+    }
+
     pub fn language_server_statuses(
         &self,
     ) -> impl DoubleEndedIterator<Item = (LanguageServerId, &LanguageServerStatus)> {
@@ -5371,6 +5569,7 @@ impl Project {
             Formatter::External { command, arguments } => {
                 let buffer_abs_path = buffer_abs_path.as_ref().map(|path| path.as_path());
                 Self::format_via_external_command(
+                    project.clone(),
                     buffer,
                     buffer_abs_path,
                     &command,
@@ -5455,25 +5654,39 @@ impl Project {
     }
 
     async fn format_via_external_command(
+        project: WeakModel<Self>,
         buffer: &Model<Buffer>,
         buffer_abs_path: Option<&Path>,
         command: &str,
         arguments: &[String],
         cx: &mut AsyncAppContext,
     ) -> Result<Option<Diff>> {
-        let working_dir_path = buffer.update(cx, |buffer, cx| {
+        let working_dir = buffer.update(cx, |buffer, cx| {
             let file = File::from_dyn(buffer.file())?;
             let worktree = file.worktree.read(cx);
             let mut worktree_path = worktree.abs_path().to_path_buf();
             if worktree.root_entry()?.is_file() {
                 worktree_path.pop();
             }
-            Some(worktree_path)
+            Some((worktree.id(), worktree_path))
         })?;
 
+        // Formatters are frequently installed into per-project environments
+        // (virtualenvs, direnv, node_modules/.bin), so run them with the same
+        // shell environment we use for the worktree's language servers and tasks.
+        let environment = if let Some((worktree_id, working_dir_path)) = working_dir.as_ref() {
+            Self::get_worktree_shell_env(project, *worktree_id, working_dir_path, cx.clone()).await
+        } else {
+            None
+        };
+
         let mut child = smol::process::Command::new(command);
 
-        if let Some(working_dir_path) = working_dir_path {
+        if let Some(environment) = environment {
+            child.envs(environment);
+        }
+
+        if let Some((_, working_dir_path)) = working_dir {
             child.current_dir(working_dir_path);
         }
 
@@ -5643,6 +5856,23 @@ impl Project {
         position: PointUtf16,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<DocumentHighlight>>> {
+        if self.is_local() {
+            let buffer = buffer.read(cx);
+            let supports_highlights = match self.primary_language_server_for_buffer(buffer, cx) {
+                Some((_, server)) => !matches!(
+                    server.capabilities().document_highlight_provider,
+                    None | Some(OneOf::Left(false))
+                ),
+                None => false,
+            };
+            if !supports_highlights {
+                let snapshot = buffer.snapshot();
+                return cx
+                    .background_executor()
+                    .spawn(async move { Ok(word_highlights(&snapshot, position)) });
+            }
+        }
+
         self.request_lsp(
             buffer.clone(),
             LanguageServerToQuery::Primary,
@@ -6046,6 +6276,18 @@ impl Project {
     ) -> Task<Result<Vec<Range<Anchor>>>> {
         let snapshot = buffer.read(cx).snapshot();
         let scope = snapshot.language_scope_at(position);
+        let linked_edits_enabled = maybe!({
+            let language_name = buffer.read(cx).language_at(position)?.name();
+            Some(
+                AllLanguageSettings::get_global(cx)
+                    .language(Some(&language_name))
+                    .linked_edits,
+            )
+        }) == Some(true);
+        if !linked_edits_enabled {
+            return Task::ready(Ok(vec![]));
+        }
+
         let Some(server_id) = self
             .language_servers_for_buffer(buffer.read(cx), cx)
             .filter(|(_, server)| {
@@ -6063,18 +6305,14 @@ impl Project {
             .map(|(_, server)| LanguageServerToQuery::Other(server.server_id()))
             .next()
             .or_else(|| self.is_remote().then_some(LanguageServerToQuery::Primary))
-            .filter(|_| {
-                maybe!({
-                    let language_name = buffer.read(cx).language_at(position)?.name();
-                    Some(
-                        AllLanguageSettings::get_global(cx)
-                            .language(Some(&language_name))
-                            .linked_edits,
-                    )
-                }) == Some(true)
-            })
         else {
-            return Task::ready(Ok(vec![]));
+            // Without a language server, fall back to the language's tree-sitter query.
+            let ranges = snapshot
+                .linked_edit_ranges(position)
+                .into_iter()
+                .map(|range| snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end))
+                .collect();
+            return Task::ready(Ok(ranges));
         };
 
         self.request_lsp(
@@ -6643,9 +6881,11 @@ impl Project {
                 Self::try_resolve_code_action(&lang_server, &mut action)
                     .await
                     .context("resolving a code action")?;
+                // When an action has both, its edit is applied before its command is run.
+                let mut project_transaction = ProjectTransaction::default();
                 if let Some(edit) = action.lsp_action.edit {
                     if edit.changes.is_some() || edit.document_changes.is_some() {
-                        return Self::deserialize_workspace_edit(
+                        project_transaction = Self::deserialize_workspace_edit(
                             this.upgrade().ok_or_else(|| anyhow!("no app present"))?,
                             edit,
                             push_to_history,
@@ -6653,37 +6893,17 @@ impl Project {
                             lang_server.clone(),
                             &mut cx,
                         )
-                        .await;
+                        .await?;
                     }
                 }
 
                 if let Some(command) = action.lsp_action.command {
-                    this.update(&mut cx, |this, _| {
-                        this.last_workspace_edits_by_language_server
-                            .remove(&lang_server.server_id());
-                    })?;
-
-                    let result = lang_server
-                        .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
-                            command: command.command,
-                            arguments: command.arguments.unwrap_or_default(),
-                            ..Default::default()
-                        })
-                        .await;
-
-                    if let Err(err) = result {
-                        // TODO: LSP ERROR
-                        return Err(err);
-                    }
-
-                    return this.update(&mut cx, |this, _| {
-                        this.last_workspace_edits_by_language_server
-                            .remove(&lang_server.server_id())
-                            .unwrap_or_default()
-                    });
+                    let command_transaction =
+                        Self::execute_lsp_command(this, lang_server, command, &mut cx).await?;
+                    project_transaction.0.extend(command_transaction.0);
                 }
 
-                Ok(ProjectTransaction::default())
+                Ok(project_transaction)
             })
         } else if let Some(project_id) = self.remote_id() {
             let client = self.client.clone();
@@ -6818,28 +7038,8 @@ impl Project {
         cx: &mut AsyncAppContext,
     ) -> Result<ProjectTransaction> {
         let fs = this.update(cx, |this, _| this.fs.clone())?;
-        let mut operations = Vec::new();
-        if let Some(document_changes) = edit.document_changes {
-            match document_changes {
-                lsp::DocumentChanges::Edits(edits) => {
-                    operations.extend(edits.into_iter().map(lsp::DocumentChangeOperation::Edit))
-                }
-                lsp::DocumentChanges::Operations(ops) => operations = ops,
-            }
-        } else if let Some(changes) = edit.changes {
-            operations.extend(changes.into_iter().map(|(uri, edits)| {
-                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
-                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
-                        uri,
-                        version: None,
-                    },
-                    edits: edits.into_iter().map(Edit::Plain).collect(),
-                })
-            }));
-        }
-
         let mut project_transaction = ProjectTransaction::default();
-        for operation in operations {
+        for operation in workspace_edit_operations(edit) {
             match operation {
                 lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
                     let abs_path = op
@@ -7076,6 +7276,97 @@ impl Project {
         self.perform_rename_impl(buffer, position, new_name, push_to_history, cx)
     }
 
+    /// Computes the edits of renaming the symbol at the given position, without applying
+    /// them, so that they can be previewed first.
+    ///
+    /// This is only supported in local projects. Returns `None` when the rename also creates,
+    /// renames or deletes files, which can't be previewed.
+    pub fn rename_edits<T: ToPointUtf16>(
+        &mut self,
+        buffer: Model<Buffer>,
+        position: T,
+        new_name: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<HashMap<Model<Buffer>, Vec<(Range<Anchor>, String)>>>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "rename edits can only be computed in local projects"
+            )));
+        }
+        let buffer_ref = buffer.read(cx);
+        let Some((adapter, server)) = self
+            .primary_language_server_for_buffer(buffer_ref, cx)
+            .map(|(adapter, server)| (adapter.clone(), server.clone()))
+        else {
+            return Task::ready(Ok(Some(HashMap::default())));
+        };
+        let Some(abs_path) = File::from_dyn(buffer_ref.file()).map(|file| file.abs_path(cx)) else {
+            return Task::ready(Ok(Some(HashMap::default())));
+        };
+        let params = PerformRename {
+            position: position.to_point_utf16(buffer_ref),
+            new_name,
+            push_to_history: false,
+        }
+        .to_lsp(&abs_path, buffer_ref, &server, cx);
+        let request = server.request::<lsp::request::Rename>(params);
+
+        cx.spawn(move |this, mut cx| async move {
+            let Some(edit) = request.await? else {
+                return Ok(Some(HashMap::default()));
+            };
+            let operations = workspace_edit_operations(edit);
+            if operations
+                .iter()
+                .any(|operation| !matches!(operation, lsp::DocumentChangeOperation::Edit(_)))
+            {
+                return Ok(None);
+            }
+            let mut edits_by_buffer = HashMap::<_, Vec<_>>::default();
+            for operation in operations {
+                let lsp::DocumentChangeOperation::Edit(op) = operation else {
+                    continue;
+                };
+                let buffer = this
+                    .update(&mut cx, |this, cx| {
+                        this.open_local_buffer_via_lsp(
+                            op.text_document.uri.clone(),
+                            server.server_id(),
+                            adapter.name.clone(),
+                            cx,
+                        )
+                    })?
+                    .await?;
+                let lsp_edits = op
+                    .edits
+                    .into_iter()
+                    .map(|edit| match edit {
+                        Edit::Plain(edit) => edit,
+                        Edit::Annotated(edit) => edit.text_edit,
+                        Edit::Snippet(edit) => TextEdit {
+                            range: edit.range,
+                            new_text: Snippet::parse(&edit.snippet.value)
+                                .map_or(edit.snippet.value, |snippet| snippet.text),
+                        },
+                    })
+                    .collect::<Vec<_>>();
+                let edits = this
+                    .update(&mut cx, |this, cx| {
+                        this.edits_from_lsp(
+                            &buffer,
+                            lsp_edits,
+                            server.server_id(),
+                            op.text_document.version,
+                            cx,
+                        )
+                    })?
+                    .await?;
+                edits_by_buffer.entry(buffer).or_default().extend(edits);
+            }
+            Ok(Some(edits_by_buffer))
+        })
+    }
+
     pub fn on_type_format_impl(
         &mut self,
         buffer: Model<Buffer>,
@@ -7267,6 +7558,17 @@ impl Project {
                             this.wait_for_remote_buffer(buffer_id, cx)
                         })?
                         .await?;
+                    // The host doesn't know about the scope, so it's applied to the results.
+                    let in_scope = target_buffer.read_with(&cx, |buffer, cx| {
+                        buffer
+                            .project_path(cx)
+                            .map_or(query.scope().is_none(), |path| {
+                                query.path_in_scope(path.worktree_id, &path.path)
+                            })
+                    })?;
+                    if !in_scope {
+                        continue;
+                    }
                     let start = location
                         .start
                         .and_then(deserialize_anchor)
@@ -7361,14 +7663,19 @@ impl Project {
             buffer_store
                 .buffers()
                 .filter_map(|buffer| {
-                    let (is_ignored, snapshot) = buffer.update(cx, |buffer, cx| {
-                        let is_ignored = buffer
-                            .project_path(cx)
+                    let (is_ignored, in_scope, snapshot) = buffer.update(cx, |buffer, cx| {
+                        let project_path = buffer.project_path(cx);
+                        let in_scope = project_path
+                            .as_ref()
+                            .map_or(query.scope().is_none(), |path| {
+                                query.path_in_scope(path.worktree_id, &path.path)
+                            });
+                        let is_ignored = project_path
                             .and_then(|path| self.entry_for_path(&path, cx))
                             .map_or(false, |entry| entry.is_ignored);
-                        (is_ignored, buffer.snapshot())
+                        (is_ignored, in_scope, buffer.snapshot())
                     });
-                    if is_ignored && !query.include_ignored() {
+                    if !in_scope || is_ignored && !query.include_ignored() {
                         return None;
                     } else if let Some(file) = snapshot.file() {
                         let matched_path = if include_root {
@@ -7597,7 +7904,16 @@ impl Project {
         if self.is_local() {
             let language_server = match server {
                 LanguageServerToQuery::Primary => {
-                    match self.primary_language_server_for_buffer(buffer, cx) {
+                    // Send the request to the first of the buffer's language servers that can
+                    // handle it, so that servers added for other purposes, such as linters,
+                    // don't shadow the servers that come after them.
+                    match self
+                        .language_servers_for_buffer(buffer, cx)
+                        .find(|(_, server)| {
+                            request.check_capabilities(server.adapter_server_capabilities())
+                        })
+                        .or_else(|| self.primary_language_server_for_buffer(buffer, cx))
+                    {
                         Some((_, server)) => Some(Arc::clone(server)),
                         None => return Task::ready(Ok(Default::default())),
                     }
@@ -8095,6 +8411,8 @@ impl Project {
         }
         self.diagnostics.remove(&id_to_remove);
         self.diagnostic_summaries.remove(&id_to_remove);
+        self.untrusted_local_settings.remove(&id_to_remove);
+        self.untrusted_tasks_files.remove(&id_to_remove);
         self.cached_shell_environments.remove(&id_to_remove);
 
         let mut servers_to_remove = HashMap::default();
@@ -8115,6 +8433,7 @@ impl Project {
                 .remove(&server_id_to_remove);
             self.last_workspace_edits_by_language_server
                 .remove(&server_id_to_remove);
+            self.pending_lsp_commands.remove(&server_id_to_remove);
             self.language_servers.remove(&server_id_to_remove);
             cx.emit(Event::LanguageServerRemoved(server_id_to_remove));
         }
@@ -8269,6 +8588,8 @@ impl Project {
         let project_id = self.remote_id();
         let worktree_id = worktree.entity_id();
         let remote_worktree_id = worktree.read(cx).id();
+        let trusted = is_worktree_trusted(worktree, cx);
+        let had_untrusted_settings = self.has_untrusted_local_settings(remote_worktree_id);
 
         let mut settings_contents = Vec::new();
         for (path, _, change) in changes.iter() {
@@ -8299,66 +8620,54 @@ impl Project {
                     )
                 });
             } else if path.ends_with(local_tasks_file_relative_path()) {
-                self.task_inventory().update(cx, |task_inventory, cx| {
-                    if removed {
-                        task_inventory.remove_local_static_source(&abs_path);
-                    } else {
-                        let fs = self.fs.clone();
-                        let task_abs_path = abs_path.clone();
-                        let tasks_file_rx =
-                            watch_config_file(&cx.background_executor(), fs, task_abs_path);
-                        task_inventory.add_source(
-                            TaskSourceKind::Worktree {
-                                id: remote_worktree_id,
-                                abs_path,
-                                id_base: "local_tasks_for_worktree".into(),
-                            },
-                            |tx, cx| StaticSource::new(TrackedFile::new(tasks_file_rx, tx, cx)),
-                            cx,
-                        );
-                    }
-                })
+                self.update_local_tasks_file(
+                    remote_worktree_id,
+                    path,
+                    abs_path,
+                    LocalTasksFile::Zed,
+                    removed,
+                    trusted,
+                    cx,
+                );
             } else if path.ends_with(local_vscode_tasks_file_relative_path()) {
-                self.task_inventory().update(cx, |task_inventory, cx| {
-                    if removed {
-                        task_inventory.remove_local_static_source(&abs_path);
-                    } else {
-                        let fs = self.fs.clone();
-                        let task_abs_path = abs_path.clone();
-                        let tasks_file_rx =
-                            watch_config_file(&cx.background_executor(), fs, task_abs_path);
-                        task_inventory.add_source(
-                            TaskSourceKind::Worktree {
-                                id: remote_worktree_id,
-                                abs_path,
-                                id_base: "local_vscode_tasks_for_worktree".into(),
-                            },
-                            |tx, cx| {
-                                StaticSource::new(TrackedFile::new_convertible::<
-                                    task::VsCodeTaskFile,
-                                >(
-                                    tasks_file_rx, tx, cx
-                                ))
-                            },
-                            cx,
-                        );
-                    }
-                })
+                self.update_local_tasks_file(
+                    remote_worktree_id,
+                    path,
+                    abs_path,
+                    LocalTasksFile::VsCode,
+                    removed,
+                    trusted,
+                    cx,
+                );
             }
         }
 
+        if !had_untrusted_settings && self.has_untrusted_local_settings(remote_worktree_id) {
+            cx.emit(Event::UntrustedLocalSettings(remote_worktree_id));
+        }
+
         if settings_contents.is_empty() {
             return;
         }
 
         let client = self.client.clone();
-        cx.spawn(move |_, cx| async move {
+        let worktree = worktree.clone();
+        cx.spawn(move |this, mut cx| async move {
             let settings_contents: Vec<(Arc<Path>, _)> =
                 futures::future::join_all(settings_contents).await;
-            cx.update(|cx| {
-                cx.update_global::<SettingsStore, _>(|store, cx| {
-                    for (directory, file_content) in settings_contents {
-                        let file_content = file_content.and_then(|content| content.log_err());
+            this.update(&mut cx, |this, cx| {
+                let trusted = is_worktree_trusted(&worktree, cx);
+                let had_untrusted_settings = this.has_untrusted_local_settings(remote_worktree_id);
+                for (directory, file_content) in settings_contents {
+                    let mut file_content = file_content.and_then(|content| content.log_err());
+                    if !trusted {
+                        file_content = this.withhold_settings_running_commands(
+                            remote_worktree_id,
+                            &directory,
+                            file_content,
+                        );
+                    }
+                    cx.update_global::<SettingsStore, _>(|store, cx| {
                         store
                             .set_local_settings(
                                 worktree_id.as_u64() as usize,
@@ -8367,24 +8676,202 @@ impl Project {
                                 cx,
                             )
                             .log_err();
-                        if let Some(remote_id) = project_id {
-                            client
-                                .send(proto::UpdateWorktreeSettings {
-                                    project_id: remote_id,
-                                    worktree_id: remote_worktree_id.to_proto(),
-                                    path: directory.to_string_lossy().into_owned(),
-                                    content: file_content,
-                                })
-                                .log_err();
-                        }
+                    });
+                    if let Some(remote_id) = project_id {
+                        client
+                            .send(proto::UpdateWorktreeSettings {
+                                project_id: remote_id,
+                                worktree_id: remote_worktree_id.to_proto(),
+                                path: directory.to_string_lossy().into_owned(),
+                                content: file_content,
+                            })
+                            .log_err();
                     }
-                });
+                }
+                if !had_untrusted_settings && this.has_untrusted_local_settings(remote_worktree_id)
+                {
+                    cx.emit(Event::UntrustedLocalSettings(remote_worktree_id));
+                }
             })
             .ok();
         })
         .detach();
     }
 
+    /// Loads the tasks of a local tasks file, or remembers the file until the worktree is
+    /// trusted.
+    #[allow(clippy::too_many_arguments)]
+    fn update_local_tasks_file(
+        &mut self,
+        worktree_id: WorktreeId,
+        path: &Arc<Path>,
+        abs_path: PathBuf,
+        tasks_file: LocalTasksFile,
+        removed: bool,
+        trusted: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if removed {
+            if let Some(untrusted_files) = self.untrusted_tasks_files.get_mut(&worktree_id) {
+                untrusted_files.remove(path);
+                if untrusted_files.is_empty() {
+                    self.untrusted_tasks_files.remove(&worktree_id);
+                }
+            }
+            self.task_inventory().update(cx, |task_inventory, _| {
+                task_inventory.remove_local_static_source(&abs_path);
+            });
+        } else if trusted {
+            self.add_local_tasks_source(worktree_id, abs_path, tasks_file, cx);
+        } else {
+            self.untrusted_tasks_files
+                .entry(worktree_id)
+                .or_default()
+                .insert(path.clone(), tasks_file);
+        }
+    }
+
+    fn add_local_tasks_source(
+        &self,
+        worktree_id: WorktreeId,
+        abs_path: PathBuf,
+        tasks_file: LocalTasksFile,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let tasks_file_rx =
+            watch_config_file(&cx.background_executor(), self.fs.clone(), abs_path.clone());
+        self.task_inventory()
+            .update(cx, |task_inventory, cx| match tasks_file {
+                LocalTasksFile::Zed => task_inventory.add_source(
+                    TaskSourceKind::Worktree {
+                        id: worktree_id,
+                        abs_path,
+                        id_base: "local_tasks_for_worktree".into(),
+                    },
+                    |tx, cx| StaticSource::new(TrackedFile::new(tasks_file_rx, tx, cx)),
+                    cx,
+                ),
+                LocalTasksFile::VsCode => task_inventory.add_source(
+                    TaskSourceKind::Worktree {
+                        id: worktree_id,
+                        abs_path,
+                        id_base: "local_vscode_tasks_for_worktree".into(),
+                    },
+                    |tx, cx| {
+                        StaticSource::new(TrackedFile::new_convertible::<task::VsCodeTaskFile>(
+                            tasks_file_rx,
+                            tx,
+                            cx,
+                        ))
+                    },
+                    cx,
+                ),
+            });
+    }
+
+    fn has_untrusted_local_settings(&self, worktree_id: WorktreeId) -> bool {
+        self.untrusted_local_settings.contains_key(&worktree_id)
+            || self.untrusted_tasks_files.contains_key(&worktree_id)
+    }
+
+    /// Returns the contents of a local settings file without the settings that run commands,
+    /// remembering the full contents until the worktree is trusted.
+    fn withhold_settings_running_commands(
+        &mut self,
+        worktree_id: WorktreeId,
+        directory: &Arc<Path>,
+        file_content: Option<String>,
+    ) -> Option<String> {
+        let untrusted_settings = self
+            .untrusted_local_settings
+            .entry(worktree_id)
+            .or_default();
+        untrusted_settings.remove(directory);
+        let content = file_content?;
+        let withheld_content = parse_json_with_comments::<serde_json::Value>(&content)
+            .ok()
+            .and_then(|mut settings| {
+                let key_paths = take_settings_running_commands(&mut settings);
+                (!key_paths.is_empty()).then(|| settings.to_string())
+            });
+        if let Some(withheld_content) = withheld_content {
+            untrusted_settings.insert(directory.clone(), content);
+            Some(withheld_content)
+        } else {
+            if untrusted_settings.is_empty() {
+                self.untrusted_local_settings.remove(&worktree_id);
+            }
+            Some(content)
+        }
+    }
+
+    /// Returns the key paths of the local settings of a worktree that run commands, and the
+    /// paths of its tasks files, which aren't applied until the worktree is trusted.
+    pub fn untrusted_local_settings(&self, worktree_id: WorktreeId) -> Vec<String> {
+        let mut key_paths = self
+            .untrusted_local_settings
+            .get(&worktree_id)
+            .into_iter()
+            .flat_map(|untrusted_settings| untrusted_settings.values())
+            .filter_map(|content| {
+                let mut settings = parse_json_with_comments::<serde_json::Value>(content).ok()?;
+                Some(take_settings_running_commands(&mut settings))
+            })
+            .flatten()
+            .chain(
+                self.untrusted_tasks_files
+                    .get(&worktree_id)
+                    .into_iter()
+                    .flat_map(|untrusted_files| untrusted_files.keys())
+                    .map(|path| path.to_string_lossy().into_owned()),
+            )
+            .collect::<Vec<_>>();
+        key_paths.sort();
+        key_paths.dedup();
+        key_paths
+    }
+
+    /// Trusts the local settings of a worktree, applying the settings that run commands and
+    /// loading its tasks files.
+    pub fn trust_worktree(&mut self, worktree_id: WorktreeId, cx: &mut ModelContext<Self>) {
+        let Some(worktree) = self.worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        cx.default_global::<TrustedWorktrees>()
+            .insert(worktree.read(cx).abs_path());
+        for (path, tasks_file) in self
+            .untrusted_tasks_files
+            .remove(&worktree_id)
+            .unwrap_or_default()
+        {
+            if let Some(abs_path) = worktree.read(cx).absolutize(&path).log_err() {
+                self.add_local_tasks_source(worktree_id, abs_path, tasks_file, cx);
+            }
+        }
+        let untrusted_settings = self
+            .untrusted_local_settings
+            .remove(&worktree_id)
+            .unwrap_or_default();
+        let root_id = worktree.entity_id().as_u64() as usize;
+        for (directory, content) in untrusted_settings {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_local_settings(root_id, directory.clone(), Some(&content), cx)
+                    .log_err();
+            });
+            if let Some(project_id) = self.remote_id() {
+                self.client
+                    .send(proto::UpdateWorktreeSettings {
+                        project_id,
+                        worktree_id: worktree_id.to_proto(),
+                        path: directory.to_string_lossy().into_owned(),
+                        content: Some(content),
+                    })
+                    .log_err();
+            }
+        }
+    }
+
     pub fn set_active_path(&mut self, entry: Option<ProjectPath>, cx: &mut ModelContext<Self>) {
         let new_active_entry = entry.and_then(|project_path| {
             let worktree = self.worktree_for_id(project_path.worktree_id, cx)?;
@@ -8420,6 +8907,21 @@ impl Project {
         summary
     }
 
+    /// The diagnostics that language servers reported for a path, whether or not the path is
+    /// open in a buffer. Only local projects keep them.
+    pub fn diagnostics_for_path(
+        &self,
+        path: &ProjectPath,
+    ) -> impl Iterator<Item = (LanguageServerId, &[DiagnosticEntry<Unclipped<PointUtf16>>])> + '_
+    {
+        self.diagnostics
+            .get(&path.worktree_id)
+            .and_then(|diagnostics| diagnostics.get(&path.path))
+            .into_iter()
+            .flatten()
+            .map(|(server_id, entries)| (*server_id, entries.as_slice()))
+    }
+
     pub fn diagnostic_summaries<'a>(
         &'a self,
         include_ignored: bool,
@@ -9598,6 +10100,7 @@ impl Project {
                         }),
                     }),
                     tags: template.tags,
+                    problem_matcher: template.problem_matcher,
                 });
                 proto::TemplatePair { kind, template }
             })
@@ -9632,14 +10135,17 @@ impl Project {
         project_transaction: &mut ProjectTransaction,
         cx: &mut AsyncAppContext,
     ) -> Result<(), anyhow::Error> {
-        for (lsp_adapter, language_server) in adapters_and_servers.iter() {
-            let code_actions = code_actions.clone();
-
+        // Request each kind separately so that the edits of one kind (e.g. organizing
+        // imports) are applied before the next kind (e.g. fixing lints) is computed.
+        for (code_action, (lsp_adapter, language_server)) in code_actions
+            .into_iter()
+            .cartesian_product(adapters_and_servers.iter())
+        {
             let actions = project
                 .update(cx, move |this, cx| {
                     let request = GetCodeActions {
                         range: text::Anchor::MIN..text::Anchor::MAX,
-                        kinds: Some(code_actions),
+                        kinds: Some(vec![code_action]),
                     };
                     let server = LanguageServerToQuery::Other(language_server.server_id());
                     this.request_lsp(buffer.clone(), server, request, cx)
@@ -9671,27 +10177,14 @@ impl Project {
                 }
 
                 if let Some(command) = action.lsp_action.command {
-                    project.update(cx, |this, _| {
-                        this.last_workspace_edits_by_language_server
-                            .remove(&language_server.server_id());
-                    })?;
-
-                    language_server
-                        .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
-                            command: command.command,
-                            arguments: command.arguments.unwrap_or_default(),
-                            ..Default::default()
-                        })
-                        .await?;
-
-                    project.update(cx, |this, _| {
-                        project_transaction.0.extend(
-                            this.last_workspace_edits_by_language_server
-                                .remove(&language_server.server_id())
-                                .unwrap_or_default()
-                                .0,
-                        )
-                    })?;
+                    let command_transaction = Self::execute_lsp_command(
+                        project.clone(),
+                        language_server.clone(),
+                        command,
+                        cx,
+                    )
+                    .await?;
+                    project_transaction.0.extend(command_transaction.0);
                 }
             }
         }
@@ -10719,6 +11212,7 @@ impl Project {
                         hide,
                         shell,
                         tags: proto_template.tags,
+                        problem_matcher: proto_template.problem_matcher,
                     };
                     Some((task_source_kind, task_template))
                 })
@@ -10757,6 +11251,14 @@ impl Project {
     }
 }
 
+/// Returns whether the local settings and tasks of a worktree are trusted to run commands.
+fn is_worktree_trusted(worktree: &Model<Worktree>, cx: &AppContext) -> bool {
+    cx.try_global::<TrustedWorktrees>()
+        .map_or(false, |trusted| {
+            trusted.contains(&worktree.read(cx).abs_path())
+        })
+}
+
 fn combine_task_variables(
     mut captured_variables: TaskVariables,
     location: Location,
@@ -10857,6 +11359,31 @@ async fn populate_labels_for_symbols(
     }
 }
 
+/// Returns the workspace configuration to send to a language server: the one that its
+/// adapter provides, with the `settings` of the server's `lsp` entry merged on top of it
+/// unless the adapter already uses them.
+async fn adapter_workspace_configuration(
+    adapter: Arc<dyn LspAdapter>,
+    delegate: &Arc<dyn LspAdapterDelegate>,
+    cx: &mut AsyncAppContext,
+) -> Result<serde_json::Value> {
+    let user_settings = if adapter.workspace_configuration_includes_settings() {
+        None
+    } else {
+        cx.update(|cx| {
+            ProjectSettings::get_global(cx)
+                .lsp
+                .get(&adapter.name().0)
+                .and_then(|settings| settings.settings.clone())
+        })?
+    };
+    let mut workspace_config = adapter.workspace_configuration(delegate, cx).await?;
+    if let Some(user_settings) = user_settings {
+        merge_json_value_into(user_settings, &mut workspace_config);
+    }
+    Ok(workspace_config)
+}
+
 async fn populate_labels_for_completions(
     mut new_completions: Vec<CoreCompletion>,
     language_registry: &Arc<LanguageRegistry>,
@@ -10907,9 +11434,11 @@ async fn populate_labels_for_completions(
     }
 }
 
-fn deserialize_code_actions(code_actions: &HashMap<String, bool>) -> Vec<lsp::CodeActionKind> {
+fn deserialize_code_actions<'a>(
+    code_actions: impl IntoIterator<Item = (&'a String, &'a bool)>,
+) -> Vec<lsp::CodeActionKind> {
     code_actions
-        .iter()
+        .into_iter()
         .flat_map(|(kind, enabled)| {
             if *enabled {
                 Some(kind.clone().into())
@@ -10957,7 +11486,9 @@ async fn search_snapshots(
                 if results_tx.is_closed() {
                     break;
                 }
-                if opened_buffers.contains_key(&entry.path) {
+                if opened_buffers.contains_key(&entry.path)
+                    || !query.path_in_scope(snapshot.id(), &entry.path)
+                {
                     continue;
                 }
 
@@ -11035,7 +11566,11 @@ async fn search_ignored_entry(
                     }
                 }
             } else if !fs_metadata.is_symlink {
-                if !query.file_matches(Some(&ignored_abs_path))
+                let in_scope = ignored_abs_path
+                    .strip_prefix(snapshot.abs_path())
+                    .map_or(false, |path| query.path_in_scope(snapshot.id(), path));
+                if !in_scope
+                    || !query.file_matches(Some(&ignored_abs_path))
                     || settings.is_path_excluded(&ignored_entry.path)
                 {
                     continue;
@@ -11302,6 +11837,35 @@ impl LspAdapterDelegate for ProjectLspAdapterDelegate {
     }
 }
 
+/// Returns the operations of a workspace edit, whether they are described as changes or
+/// as document changes.
+fn workspace_edit_operations(edit: lsp::WorkspaceEdit) -> Vec<lsp::DocumentChangeOperation> {
+    if let Some(document_changes) = edit.document_changes {
+        match document_changes {
+            lsp::DocumentChanges::Edits(edits) => edits
+                .into_iter()
+                .map(lsp::DocumentChangeOperation::Edit)
+                .collect(),
+            lsp::DocumentChanges::Operations(ops) => ops,
+        }
+    } else if let Some(changes) = edit.changes {
+        changes
+            .into_iter()
+            .map(|(uri, edits)| {
+                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                        uri,
+                        version: None,
+                    },
+                    edits: edits.into_iter().map(Edit::Plain).collect(),
+                })
+            })
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
 fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
     proto::Symbol {
         language_server_name: symbol.language_server_name.0.to_string(),
@@ -11435,6 +11999,17 @@ fn include_text(server: &lsp::LanguageServer) -> Option<bool> {
     }
 }
 
+/// Whether any of the changes in the edit are annotated as needing the user's confirmation.
+fn workspace_edit_needs_confirmation(edit: &lsp::WorkspaceEdit) -> bool {
+    edit.change_annotations
+        .as_ref()
+        .map_or(false, |annotations| {
+            annotations
+                .values()
+                .any(|annotation| annotation.needs_confirmation == Some(true))
+        })
+}
+
 async fn load_direnv_environment(dir: &Path) -> Result<Option<HashMap<String, String>>> {
     let Ok(direnv_path) = which::which("direnv") else {
         return Ok(None);
@@ -11555,6 +12130,32 @@ fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     }
 }
 
+/// Finds the whole-word occurrences of the word at the given position, to highlight them
+/// in buffers whose language server doesn't provide document highlights.
+fn word_highlights(snapshot: &BufferSnapshot, position: PointUtf16) -> Vec<DocumentHighlight> {
+    let offset = snapshot.point_utf16_to_offset(position);
+    let (word_range, kind) = snapshot.surrounding_word(offset);
+    if kind != Some(CharKind::Word) || word_range.is_empty() {
+        return Vec::new();
+    }
+
+    let scope = snapshot.language_scope_at(offset);
+    let is_word_char = |c| char_kind(&scope, c) == CharKind::Word;
+    let word = snapshot.text_for_range(word_range).collect::<String>();
+    let text = snapshot.text();
+    text.match_indices(word.as_str())
+        .filter(|(ix, _)| {
+            let end = ix + word.len();
+            !text[..*ix].chars().next_back().map_or(false, is_word_char)
+                && !text[end..].chars().next().map_or(false, is_word_char)
+        })
+        .map(|(ix, _)| DocumentHighlight {
+            range: snapshot.anchor_before(ix)..snapshot.anchor_after(ix + word.len()),
+            kind: DocumentHighlightKind::TEXT,
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct NoRepositoryError {}
 
@@ -1,16 +1,27 @@
-use collections::HashMap;
-use gpui::AppContext;
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use gpui::{AppContext, Global};
+use language::{Buffer, UserLanguageServer};
+use lsp::LanguageServerBinary;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
-use std::{sync::Arc, time::Duration};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectSettings {
     /// Configuration for language servers.
     ///
     /// The following settings can be overridden for specific language servers:
+    /// - enabled
+    /// - binary
     /// - initialization_options
+    /// - settings
     /// To override settings for a language, add an entry for that language server's
     /// name to the lsp value.
     /// Default: null
@@ -28,6 +39,10 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for opening very large files
+    #[serde(default)]
+    pub large_file: LargeFileSettings,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -113,19 +128,84 @@ const fn true_value() -> bool {
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct BinarySettings {
+    /// The language server binary to use instead of the one that Zed installs.
+    /// Relative paths such as `tools/bin/server` are resolved against the root of the
+    /// worktree, while bare names such as `server` are looked up on the `PATH`.
     pub path: Option<String>,
     pub arguments: Option<Vec<String>>,
+    /// Environment variables to start the language server with.
+    pub env: Option<HashMap<String, String>>,
     pub path_lookup: Option<bool>,
+    /// The address of a language server that listens on TCP, such as `127.0.0.1:6005`,
+    /// to connect to instead of talking to the server over its standard input and output.
+    /// When a `path` is also set, Zed starts the server first.
+    pub tcp_address: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct LspSettings {
+    /// Whether to start this language server for the languages that use it.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
     pub binary: Option<BinarySettings>,
     pub initialization_options: Option<serde_json::Value>,
     pub settings: Option<serde_json::Value>,
 }
 
+impl BinarySettings {
+    /// Returns the language server to use instead of the adapter's one, when a `path` or a
+    /// `tcp_address` is set. Relative paths with a separator are resolved against
+    /// `worktree_path`, while bare command names are left to be looked up on the `PATH`.
+    pub fn user_language_server(&self, worktree_path: &Path) -> Result<Option<UserLanguageServer>> {
+        let tcp_address = self
+            .tcp_address
+            .as_deref()
+            .map(|address| {
+                address
+                    .parse::<SocketAddr>()
+                    .with_context(|| format!("invalid language server TCP address {address:?}"))
+            })
+            .transpose()?;
+        let binary = self.path.as_ref().map(|path| LanguageServerBinary {
+            path: if path.contains(std::path::is_separator) {
+                worktree_path.join(path)
+            } else {
+                PathBuf::from(path)
+            },
+            arguments: self
+                .arguments
+                .iter()
+                .flatten()
+                .map(|argument| argument.into())
+                .collect(),
+            env: self.env.clone(),
+        });
+        if binary.is_none() && tcp_address.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(UserLanguageServer {
+            binary,
+            tcp_address,
+        }))
+    }
+}
+
+impl LspSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    /// Whether the language server has to be restarted to switch from these settings to
+    /// `new` ones. Changes to `settings` alone are sent to the running server instead.
+    pub fn requires_restart(&self, new: &Self) -> bool {
+        self.enabled != new.enabled
+            || self.binary != new.binary
+            || self.initialization_options != new.initialization_options
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SessionSettings {
     /// Whether or not to restore unsaved buffers on restart.
@@ -145,6 +225,41 @@ impl Default for SessionSettings {
     }
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LargeFileSettings {
+    /// Whether to open very large files in a degraded mode, without syntax
+    /// highlighting, language servers, soft wrapping or inlay hints.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// Files larger than this many bytes are opened in large file mode.
+    ///
+    /// Default: 20000000
+    pub size_threshold: usize,
+    /// Files with more than this many lines are opened in large file mode.
+    ///
+    /// Default: 300000
+    pub line_threshold: u32,
+}
+
+impl LargeFileSettings {
+    pub fn is_large_file(&self, buffer: &Buffer) -> bool {
+        self.enabled
+            && (buffer.len() > self.size_threshold || buffer.max_point().row >= self.line_threshold)
+    }
+}
+
+impl Default for LargeFileSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            size_threshold: 20_000_000,
+            line_threshold: 300_000,
+        }
+    }
+}
+
 impl Settings for ProjectSettings {
     const KEY: Option<&'static str> = None;
 
@@ -157,3 +272,125 @@ impl Settings for ProjectSettings {
         sources.json_merge()
     }
 }
+
+/// The absolute paths of the worktrees whose local settings are trusted to run commands.
+#[derive(Default)]
+pub struct TrustedWorktrees(HashSet<Arc<Path>>);
+
+impl Global for TrustedWorktrees {}
+
+impl TrustedWorktrees {
+    pub fn new(paths: impl IntoIterator<Item = Arc<Path>>) -> Self {
+        Self(paths.into_iter().collect())
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.0.contains(path)
+    }
+
+    pub fn insert(&mut self, path: Arc<Path>) -> bool {
+        self.0.insert(path)
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Arc<Path>> {
+        self.0.iter()
+    }
+}
+
+/// Removes the settings that make Zed run commands, such as external formatters, language
+/// server binaries and the options passed to language servers, from the contents of a local
+/// settings file. Returns their key paths, which are empty when the settings don't run any
+/// commands.
+pub fn take_settings_running_commands(settings: &mut serde_json::Value) -> Vec<String> {
+    let mut key_paths = Vec::new();
+    let Some(settings) = settings.as_object_mut() else {
+        return key_paths;
+    };
+
+    take_language_settings_running_commands(settings, "", &mut key_paths);
+    if let Some(languages) = settings
+        .get_mut("languages")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        for (language, language_settings) in languages {
+            if let Some(language_settings) = language_settings.as_object_mut() {
+                take_language_settings_running_commands(
+                    language_settings,
+                    &format!("languages.{language}."),
+                    &mut key_paths,
+                );
+            }
+        }
+    }
+    if let Some(lsp) = settings
+        .get_mut("lsp")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        for (server_name, server_settings) in lsp {
+            if let Some(server_settings) = server_settings.as_object_mut() {
+                for key in ["binary", "initialization_options", "settings"] {
+                    take_key(
+                        server_settings,
+                        key,
+                        &format!("lsp.{server_name}."),
+                        &mut key_paths,
+                    );
+                }
+            }
+        }
+    }
+    if let Some(terminal) = settings
+        .get_mut("terminal")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        for key in ["shell", "env"] {
+            take_key(terminal, key, "terminal.", &mut key_paths);
+        }
+    }
+    take_key(settings, "load_direnv", "", &mut key_paths);
+    key_paths
+}
+
+/// Removes the external formatters, whether they run on format or on save, and the Prettier
+/// plugins, which are installed with npm, from the settings of a language or of all languages.
+fn take_language_settings_running_commands(
+    settings: &mut serde_json::Map<String, serde_json::Value>,
+    prefix: &str,
+    key_paths: &mut Vec<String>,
+) {
+    fn is_external(formatter: &serde_json::Value) -> bool {
+        match formatter {
+            serde_json::Value::Object(formatter) => formatter.contains_key("external"),
+            serde_json::Value::Array(formatters) => formatters.iter().any(is_external),
+            _ => false,
+        }
+    }
+
+    for key in ["formatter", "format_on_save"] {
+        if settings.get(key).map_or(false, is_external) {
+            take_key(settings, key, prefix, key_paths);
+        }
+    }
+    if let Some(prettier) = settings
+        .get_mut("prettier")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        take_key(
+            prettier,
+            "plugins",
+            &format!("{prefix}prettier."),
+            key_paths,
+        );
+    }
+}
+
+fn take_key(
+    settings: &mut serde_json::Map<String, serde_json::Value>,
+    key: &str,
+    prefix: &str,
+    key_paths: &mut Vec<String>,
+) {
+    if settings.remove(key).is_some() {
+        key_paths.push(format!("{prefix}{key}"));
+    }
+}
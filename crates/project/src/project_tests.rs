@@ -4,8 +4,10 @@ use futures::{future, StreamExt};
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use language::{
-    language_settings::{AllLanguageSettings, LanguageSettingsContent},
-    tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
+    language_settings::{
+        AllLanguageSettings, FormatOnSave, LanguageSettingsContent, SelectedFormatter,
+    },
+    tree_sitter_rust, tree_sitter_typescript, Diagnostic, Encoding, FakeLspAdapter, LanguageConfig,
     LanguageMatcher, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
 use lsp::NumberOrString;
@@ -90,9 +92,70 @@ async fn test_symlinks(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[cfg(unix)]
+#[gpui::test]
+async fn test_external_formatter_environment(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let dir = temp_tree(json!({ "a.txt": "one\n" }));
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.formatter = Some(
+                    serde_json::from_value(json!({
+                        "external": {
+                            "command": "sh",
+                            "arguments": ["-c", "echo \"$GREETING $(basename \"$PWD\")\""]
+                        }
+                    }))
+                    .unwrap(),
+                );
+            });
+        });
+    });
+
+    let project = Project::test(Arc::new(RealFs::default()), [dir.path()], cx).await;
+    project.update(cx, |project, cx| {
+        let worktree_id = project.worktrees(cx).next().unwrap().read(cx).id();
+        project.cached_shell_environments.insert(
+            worktree_id,
+            HashMap::from_iter([("GREETING".to_string(), "hello".to_string())]),
+        );
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(dir.path().join("a.txt"), cx)
+        })
+        .await
+        .unwrap();
+
+    // The formatter runs in the worktree's root with the worktree's shell environment.
+    project
+        .update(cx, |project, cx| {
+            project.format(
+                HashSet::from_iter([buffer.clone()]),
+                true,
+                FormatTrigger::Manual,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    let root_name = dir.path().file_name().unwrap().to_string_lossy();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), format!("hello {root_name}\n"));
+    });
+}
+
 #[gpui::test]
 async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext) {
     init_test(cx);
+    cx.update(|cx| {
+        cx.set_global(project_settings::TrustedWorktrees::new([Arc::from(
+            Path::new("/the-root"),
+        )]))
+    });
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
@@ -295,6 +358,105 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_untrusted_project_specific_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".zed": {
+                "settings.json": r#"{
+                    "tab_size": 8,
+                    "formatter": { "external": { "command": "fmt" } },
+                    "format_on_save": [{ "external": { "command": "evil" } }],
+                    "load_direnv": "shell_hook",
+                    "terminal": { "env": { "LD_PRELOAD": "evil.so" } },
+                    "languages": {
+                        "JavaScript": {
+                            "format_on_save": { "external": { "command": "evil" } },
+                            "prettier": { "allowed": true, "plugins": ["evil"] }
+                        }
+                    },
+                    "lsp": {
+                        "rust-analyzer": {
+                            "initialization_options": { "check": { "command": "evil" } }
+                        }
+                    }
+                }"#,
+                "tasks.json": r#"[{ "label": "cargo check", "command": "cargo" }]"#,
+            },
+            "a.rs": "fn a() {\n    A\n}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    let worktree_id = worktree.update(cx, |worktree, _| worktree.id());
+
+    let formatters = |cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let tree = worktree.read(cx);
+            let settings = language_settings(
+                None,
+                Some(
+                    &(File::for_entry(
+                        tree.entry_for_path("a.rs").unwrap().clone(),
+                        worktree.clone(),
+                    ) as _),
+                ),
+                cx,
+            );
+            assert_eq!(settings.tab_size.get(), 8);
+            (settings.formatter.clone(), settings.format_on_save.clone())
+        })
+    };
+
+    // The settings and tasks running commands are withheld until the worktree is trusted.
+    assert_eq!(formatters(cx), (SelectedFormatter::Auto, FormatOnSave::On));
+    let tasks = cx
+        .update(|cx| get_all_tasks(&project, Some(worktree_id), &TaskContext::default(), cx))
+        .await;
+    assert!(tasks.is_empty());
+    project.update(cx, |project, _| {
+        assert_eq!(
+            project.untrusted_local_settings(worktree_id),
+            [
+                ".zed/tasks.json",
+                "format_on_save",
+                "formatter",
+                "languages.JavaScript.format_on_save",
+                "languages.JavaScript.prettier.plugins",
+                "load_direnv",
+                "lsp.rust-analyzer.initialization_options",
+                "terminal.env",
+            ]
+        );
+    });
+
+    project.update(cx, |project, cx| project.trust_worktree(worktree_id, cx));
+    cx.executor().run_until_parked();
+    let (formatter, format_on_save) = formatters(cx);
+    assert_ne!(formatter, SelectedFormatter::Auto);
+    assert!(matches!(format_on_save, FormatOnSave::List(_)));
+    let tasks = cx
+        .update(|cx| get_all_tasks(&project, Some(worktree_id), &TaskContext::default(), cx))
+        .await;
+    assert_eq!(
+        tasks
+            .into_iter()
+            .map(|(_, task)| task.resolved_label)
+            .collect::<Vec<_>>(),
+        ["cargo check"]
+    );
+    project.update(cx, |project, _| {
+        assert!(project.untrusted_local_settings(worktree_id).is_empty());
+    });
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1233,6 +1395,147 @@ async fn test_disk_based_diagnostics_progress(cx: &mut gpui::TestAppContext) {
     assert_eq!(futures::poll!(events.next()), Poll::Pending);
 }
 
+#[gpui::test]
+async fn test_pulled_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "let a = A;" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let previous_result_ids = previous_result_ids.clone();
+        move |params, _| {
+            assert_eq!(
+                params.text_document.uri,
+                Url::from_file_path("/dir/a.rs").unwrap()
+            );
+            let previous_result_id = params.previous_result_id;
+            previous_result_ids.lock().push(previous_result_id.clone());
+            async move {
+                let report = if previous_result_id.as_deref() == Some("1") {
+                    lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport {
+                                    result_id: "1".to_string(),
+                                },
+                        },
+                    )
+                } else {
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("1".to_string()),
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 8),
+                                    lsp::Position::new(0, 9),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "undefined variable 'A'".to_string(),
+                                ..Default::default()
+                            }],
+                        },
+                    })
+                };
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        }
+    });
+
+    // The server asks for its diagnostics to be pulled again, and reports them in full.
+    fake_server
+        .server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let expected_diagnostics = [DiagnosticEntry {
+        range: Point::new(0, 8)..Point::new(0, 9),
+        diagnostic: Diagnostic {
+            severity: lsp::DiagnosticSeverity::ERROR,
+            message: "undefined variable 'A'".to_string(),
+            group_id: 0,
+            is_primary: true,
+            ..Default::default()
+        },
+    }];
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics, expected_diagnostics);
+    });
+
+    // Pulling them again sends the id of the last report, so that the server can reply that
+    // they're unchanged.
+    fake_server
+        .server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        previous_result_ids.lock().last(),
+        Some(&Some("1".to_string()))
+    );
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics, expected_diagnostics);
+    });
+
+    // The diagnostics that the server publishes don't replace those it reported on request.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path("/dir/a.rs").unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 5)),
+            severity: Some(lsp::DiagnosticSeverity::WARNING),
+            message: "unused variable 'a'".to_string(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        let messages = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| entry.diagnostic.message)
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["unused variable 'a'", "undefined variable 'A'"]);
+    });
+}
+
 #[gpui::test]
 async fn test_restarting_server_with_diagnostics_running(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1431,6 +1734,74 @@ async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::T
     assert_eq!(notification.version, 0);
 }
 
+#[gpui::test]
+async fn test_inactive_regions(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({ "a.c": "#ifdef A\nint a;\n#else\nint b;\n#endif\n" }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "C".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["c".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    )));
+    let mut fake_servers =
+        language_registry.register_fake_lsp_adapter("C", FakeLspAdapter::default());
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.c", cx))
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    fake_server.notify::<lsp::InactiveRegions>(lsp::InactiveRegionsParams {
+        text_document: lsp::TextDocumentIdentifier::new(
+            lsp::Url::from_file_path("/dir/a.c").unwrap(),
+        ),
+        regions: vec![lsp::Range::new(
+            lsp::Position::new(1, 0),
+            lsp::Position::new(1, 6),
+        )],
+    });
+    cx.executor().run_until_parked();
+    project.update(cx, |project, cx| {
+        let buffer = buffer.read(cx);
+        assert_eq!(
+            project
+                .inactive_regions(buffer)
+                .iter()
+                .map(|range| range.to_point(buffer))
+                .collect::<Vec<_>>(),
+            [Point::new(1, 0)..Point::new(1, 6)]
+        );
+    });
+
+    fake_server.notify::<lsp::InactiveRegions>(lsp::InactiveRegionsParams {
+        text_document: lsp::TextDocumentIdentifier::new(
+            lsp::Url::from_file_path("/dir/a.c").unwrap(),
+        ),
+        regions: Vec::new(),
+    });
+    cx.executor().run_until_parked();
+    project.update(cx, |project, cx| {
+        assert!(project.inactive_regions(buffer.read(cx)).is_empty());
+    });
+}
+
 #[gpui::test]
 async fn test_cancel_language_server_work(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2490,6 +2861,54 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_document_highlights_without_language_server(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.txt": "let foo = 1;\nfoo + foofoo + foo_bar + foo",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+
+    // Without a language server, whole-word occurrences of the word at the cursor are highlighted.
+    let highlights = project
+        .update(cx, |project, cx| {
+            project.document_highlights(&buffer, Point::new(0, 5), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let ranges = highlights
+            .iter()
+            .map(|highlight| {
+                assert_eq!(highlight.kind, lsp::DocumentHighlightKind::TEXT);
+                highlight.range.to_offset(buffer)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [4..7, 13..16, 38..41]);
+    });
+
+    let highlights = project
+        .update(cx, |project, cx| {
+            project.document_highlights(&buffer, Point::new(0, 8), cx)
+        })
+        .await
+        .unwrap();
+    assert!(highlights.is_empty());
+}
+
 #[gpui::test]
 async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2776,26 +3195,125 @@ async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
     });
 }
 
-#[gpui::test(iterations = 10)]
-async fn test_save_file(cx: &mut gpui::TestAppContext) {
+#[gpui::test]
+async fn test_workspace_edits_outside_of_commands(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         "/dir",
         json!({
-            "file1": "the old contents",
+            "a.ts": "a",
         }),
     )
     .await;
 
-    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers =
+        language_registry.register_fake_lsp_adapter("TypeScript", FakeLspAdapter::default());
+
     let buffer = project
-        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
         .await
         .unwrap();
-    buffer.update(cx, |buffer, cx| {
-        assert_eq!(buffer.text(), "the old contents");
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        cx.subscribe(&project, {
+            let events = events.clone();
+            move |_, event, _| {
+                if let Event::WorkspaceEditApplied {
+                    label,
+                    transaction,
+                    needs_confirmation,
+                } = event
+                {
+                    events
+                        .lock()
+                        .push((label.clone(), transaction.0.len(), *needs_confirmation));
+                }
+            }
+        })
+        .detach();
+    });
+
+    // A language server applying an edit on its own is reported, so that the edit can be shown.
+    let response = fake_server
+        .server
+        .request::<lsp::request::ApplyWorkspaceEdit>(lsp::ApplyWorkspaceEditParams {
+            label: Some("Move item".into()),
+            edit: lsp::WorkspaceEdit {
+                changes: Some(
+                    [(
+                        lsp::Url::from_file_path("/dir/a.ts").unwrap(),
+                        vec![lsp::TextEdit {
+                            range: lsp::Range::new(
+                                lsp::Position::new(0, 0),
+                                lsp::Position::new(0, 0),
+                            ),
+                            new_text: "X".into(),
+                        }],
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    assert!(response.applied);
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "Xa"));
+    assert_eq!(*events.lock(), [(Some("Move item".to_string()), 1, false)]);
+
+    // Edits that can't be applied are reported back to the language server.
+    let response = fake_server
+        .server
+        .request::<lsp::request::ApplyWorkspaceEdit>(lsp::ApplyWorkspaceEditParams {
+            label: None,
+            edit: lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(lsp::RenameFile {
+                        old_uri: lsp::Url::from_file_path("/dir/missing.ts").unwrap(),
+                        new_uri: lsp::Url::from_file_path("/dir/b.ts").unwrap(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                ])),
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    assert!(!response.applied);
+    assert!(response.failure_reason.is_some());
+    assert_eq!(events.lock().len(), 1);
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_save_file(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "file1": "the old contents",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "the old contents");
         buffer.edit([(0..0, "a line of text.\n".repeat(10 * 1024))], None, cx);
     });
 
@@ -2808,6 +3326,78 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_save_file_with_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file("/dir/file1", b"caf\xe9 cr\xe8me".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "café crème");
+        assert_eq!(buffer.encoding(), Encoding::Latin1);
+        buffer.edit([(0..0, "très ")], None, cx);
+    });
+
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new("/dir/file1")).await.unwrap(),
+        b"tr\xe8s caf\xe9 cr\xe8me"
+    );
+
+    // Characters that can't be represented in the file's encoding fail the save.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "日本 ")], None, cx));
+    let save = project.update(cx, |project, cx| project.save_buffer(buffer.clone(), cx));
+    assert!(save.await.is_err());
+
+    buffer.update(cx, |buffer, cx| buffer.set_encoding(Encoding::Utf16Le, cx));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    let mut expected_bytes = vec![0xFF, 0xFE];
+    expected_bytes.extend(
+        "日本 très café crème"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes),
+    );
+    assert_eq!(
+        fs.load_bytes(Path::new("/dir/file1")).await.unwrap(),
+        expected_bytes
+    );
+
+    cx.run_until_parked();
+
+    let reload = buffer.update(cx, |buffer, cx| {
+        buffer.reload_with_encoding(Encoding::Latin1, cx)
+    });
+    reload.await.unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.encoding(), Encoding::Latin1);
+        assert!(buffer.text().starts_with("ÿþ"));
+    });
+
+    // Reloading with an encoding that can't decode the file keeps the buffer as it is.
+    let reload = buffer.update(cx, |buffer, cx| {
+        buffer.reload_with_encoding(Encoding::Utf8, cx)
+    });
+    assert!(reload.await.is_err());
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.encoding(), Encoding::Latin1);
+        assert!(buffer.text().starts_with("ÿþ"));
+    });
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2958,6 +3548,53 @@ async fn test_save_in_single_file_worktree(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_large_files_are_opened_as_plain_text(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file.line_threshold = 3;
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "small.rs": "fn a() {}\n",
+            "large.rs": "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let languages = project.update(cx, |project, _| project.languages().clone());
+    languages.add(rust_lang());
+
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/small.rs", cx)
+        })
+        .await
+        .unwrap();
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    small_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.language().unwrap().name().as_ref(), "Rust");
+    });
+    large_buffer.update(cx, |buffer, _| {
+        assert!(buffer.language().is_none());
+    });
+}
+
 #[gpui::test]
 async fn test_save_as(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3540,6 +4177,51 @@ async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_convert_line_endings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "file1": "one\r\ntwo\nthree\r\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+
+    // Files with mixed line endings use the most common one.
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "one\ntwo\nthree\n");
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert!(buffer.has_mixed_line_endings());
+        assert!(!buffer.is_dirty());
+    });
+
+    // Converting the line endings marks the buffer as dirty until it's saved.
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_line_ending(LineEnding::Unix, cx);
+        assert_eq!(buffer.line_ending(), LineEnding::Unix);
+        assert!(!buffer.has_mixed_line_endings());
+        assert!(buffer.is_dirty());
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load("/dir/file1".as_ref()).await.unwrap(),
+        "one\ntwo\nthree\n",
+    );
+    buffer.update(cx, |buffer, _| assert!(!buffer.is_dirty()));
+}
+
 #[gpui::test]
 async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3916,6 +4598,122 @@ async fn test_rename(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_rename_edits_are_not_applied(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE;"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let response = project.update(cx, |project, cx| {
+        project.rename_edits(buffer.clone(), 7, "THREE".to_string(), cx)
+    });
+    fake_server
+        .handle_request::<lsp::request::Rename, _, _>(|_, _| async move {
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(
+                    [
+                        (
+                            lsp::Url::from_file_path("/dir/one.rs").unwrap(),
+                            vec![lsp::TextEdit::new(
+                                lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 9)),
+                                "THREE".to_string(),
+                            )],
+                        ),
+                        (
+                            lsp::Url::from_file_path("/dir/two.rs").unwrap(),
+                            vec![lsp::TextEdit::new(
+                                lsp::Range::new(
+                                    lsp::Position::new(0, 24),
+                                    lsp::Position::new(0, 27),
+                                ),
+                                "THREE".to_string(),
+                            )],
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    let mut edits = response.await.unwrap().unwrap();
+    assert_eq!(edits.len(), 2);
+
+    let (buffer, one_edits) = edits.remove_entry(&buffer).unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "const ONE: usize = 1;");
+        let one_edits = one_edits
+            .iter()
+            .map(|(range, text)| (range.to_offset(buffer), text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(one_edits, [(6..9, "THREE")]);
+        buffer.edit(one_edits, None, cx);
+        assert_eq!(buffer.text(), "const THREE: usize = 1;");
+    });
+    let (buffer, two_edits) = edits.into_iter().next().unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "const TWO: usize = one::ONE;");
+        assert_eq!(two_edits.len(), 1);
+    });
+
+    // Renames that also operate on files can't be previewed.
+    let response = project.update(cx, |project, cx| {
+        project.rename_edits(buffer.clone(), 7, "FOUR".to_string(), cx)
+    });
+    fake_server
+        .handle_request::<lsp::request::Rename, _, _>(|_, _| async move {
+            Ok(Some(lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(lsp::RenameFile {
+                        old_uri: lsp::Url::from_file_path("/dir/two.rs").unwrap(),
+                        new_uri: lsp::Url::from_file_path("/dir/four.rs").unwrap(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                ])),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    assert!(response.await.unwrap().is_none());
+}
+
 #[gpui::test]
 async fn test_search(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3989,6 +4787,90 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_with_scope(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "// file one",
+            "src": {
+                "two.rs": "// file two",
+                "three.rs": "// file three",
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+
+    let scope = vec![
+        ProjectPath {
+            worktree_id,
+            path: Path::new("one.rs").into(),
+        },
+        ProjectPath {
+            worktree_id,
+            path: Path::new("src/three.rs").into(),
+        },
+    ];
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::text(
+                "file",
+                false,
+                true,
+                false,
+                Default::default(),
+                Default::default()
+            )
+            .unwrap()
+            .with_scope(scope),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![3..7]),
+            ("dir/src/three.rs".to_string(), vec![3..7]),
+        ]),
+        "Only the files in scope should be searched"
+    );
+
+    let scope = vec![ProjectPath {
+        worktree_id,
+        path: Path::new("src").into(),
+    }];
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::text(
+                "file",
+                false,
+                true,
+                false,
+                Default::default(),
+                Default::default()
+            )
+            .unwrap()
+            .with_scope(scope),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            ("dir/src/two.rs".to_string(), vec![3..7]),
+            ("dir/src/three.rs".to_string(), vec![3..7]),
+        ]),
+        "Directories in scope should include the files within them"
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4750,6 +5632,327 @@ async fn test_multiple_language_server_hovers(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_language_server_requests_routed_by_capability(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.tsx": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(tsx_lang());
+    let mut fake_linter_servers = language_registry.register_fake_lsp_adapter(
+        "tsx",
+        FakeLspAdapter {
+            name: "ESLintServer",
+            capabilities: lsp::ServerCapabilities::default(),
+            ..FakeLspAdapter::default()
+        },
+    );
+    let mut fake_typescript_servers = language_registry.register_fake_lsp_adapter(
+        "tsx",
+        FakeLspAdapter {
+            name: "TypeScriptServer",
+            capabilities: lsp::ServerCapabilities {
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.tsx", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let linter_server = fake_linter_servers.next().await.unwrap();
+    let typescript_server = fake_typescript_servers.next().await.unwrap();
+    let _never_handled =
+        linter_server.handle_request::<lsp::request::GotoDefinition, _, _>(|_, _| async move {
+            panic!("Should not send definition requests to a server that can't handle them")
+        });
+    let mut definition_requests = typescript_server
+        .handle_request::<lsp::request::GotoDefinition, _, _>(|_, _| async move { Ok(None) });
+
+    let definition_task = project.update(cx, |project, cx| {
+        project.definition(&buffer, Point::new(0, 0), cx)
+    });
+    definition_requests
+        .next()
+        .await
+        .expect("The definition request should go to the server that provides definitions");
+    assert!(definition_task.await.unwrap().is_empty());
+}
+
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.tsx": "function a() { b(); }\nfunction b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(tsx_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "tsx",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.tsx", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let fake_server = fake_servers.next().await.unwrap();
+    assert!(project.read_with(cx, |project, cx| {
+        project.supports_call_hierarchy(buffer.read(cx), cx)
+    }));
+
+    let uri = lsp::Url::from_file_path("/dir/a.tsx").unwrap();
+    let lsp_item = |name: &str, row: u32| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 15)),
+        selection_range: lsp::Range::new(lsp::Position::new(row, 9), lsp::Position::new(row, 10)),
+        data: None,
+    };
+    let b_item = lsp_item("b", 1);
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let b_item = b_item.clone();
+        move |params, _| {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(1, 9)
+            );
+            let b_item = b_item.clone();
+            async move { Ok(Some(vec![b_item])) }
+        }
+    });
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let a_item = lsp_item("a", 0);
+        move |params, _| {
+            assert_eq!(params.item.name, "b");
+            let a_item = a_item.clone();
+            async move {
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: a_item,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 15),
+                        lsp::Position::new(0, 16),
+                    )],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, PointUtf16::new(1, 9), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        items.iter().map(|item| item.name()).collect::<Vec<_>>(),
+        ["b"]
+    );
+    assert_eq!(items[0].server_id, fake_server.server.server_id());
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.name(), "a");
+    assert_eq!(
+        calls[0].call_site_path.as_deref(),
+        Some(Path::new("/dir/a.tsx"))
+    );
+    assert_eq!(
+        calls[0].call_sites,
+        [Unclipped(PointUtf16::new(0, 15))..Unclipped(PointUtf16::new(0, 16))]
+    );
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.tsx": "class A {}\nclass B extends A {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(tsx_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "tsx",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                type_hierarchy_provider: Some(lsp::OneOf::Left(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.tsx", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let fake_server = fake_servers.next().await.unwrap();
+    project.read_with(cx, |project, cx| {
+        assert!(project.supports_type_hierarchy(buffer.read(cx), cx));
+        assert!(!project.supports_call_hierarchy(buffer.read(cx), cx));
+    });
+
+    let uri = lsp::Url::from_file_path("/dir/a.tsx").unwrap();
+    let lsp_item = |name: &str, row: u32| lsp::TypeHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::CLASS,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 10)),
+        selection_range: lsp::Range::new(lsp::Position::new(row, 6), lsp::Position::new(row, 7)),
+        data: None,
+    };
+    fake_server.handle_request::<lsp::request::TypeHierarchyPrepare, _, _>({
+        let b_item = lsp_item("B", 1);
+        move |_, _| {
+            let b_item = b_item.clone();
+            async move { Ok(Some(vec![b_item])) }
+        }
+    });
+    fake_server.handle_request::<lsp::request::TypeHierarchySupertypes, _, _>({
+        let a_item = lsp_item("A", 0);
+        move |params, _| {
+            assert_eq!(params.item.name, "B");
+            let a_item = a_item.clone();
+            async move { Ok(Some(vec![a_item])) }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, PointUtf16::new(1, 6), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        items.iter().map(|item| item.name()).collect::<Vec<_>>(),
+        ["B"]
+    );
+
+    let supertypes = project
+        .update(cx, |project, cx| project.supertypes(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        supertypes
+            .iter()
+            .map(|item| item.name())
+            .collect::<Vec<_>>(),
+        ["A"]
+    );
+    assert_eq!(supertypes[0].server_id, fake_server.server.server_id());
+    assert_eq!(
+        supertypes[0].selection_range(),
+        Unclipped(PointUtf16::new(0, 6))..Unclipped(PointUtf16::new(0, 7))
+    );
+}
+
+#[test]
+fn test_user_language_server_settings() {
+    let worktree_path = Path::new("/monorepo");
+
+    let user_server = project_settings::BinarySettings {
+        path: Some("tools/bin/rust-analyzer".into()),
+        arguments: Some(vec!["--log-file".into(), "ra.log".into()]),
+        ..Default::default()
+    }
+    .user_language_server(worktree_path)
+    .unwrap()
+    .unwrap();
+    let binary = user_server.binary.unwrap();
+    assert_eq!(binary.path, Path::new("/monorepo/tools/bin/rust-analyzer"));
+    assert_eq!(binary.arguments, ["--log-file", "ra.log"]);
+    assert_eq!(user_server.tcp_address, None);
+
+    // Bare command names are looked up on the `PATH`, and absolute paths are kept.
+    for path in ["rust-analyzer", "/usr/bin/rust-analyzer"] {
+        let user_server = project_settings::BinarySettings {
+            path: Some(path.into()),
+            ..Default::default()
+        }
+        .user_language_server(worktree_path)
+        .unwrap()
+        .unwrap();
+        assert_eq!(user_server.binary.unwrap().path, Path::new(path));
+    }
+
+    let user_server = project_settings::BinarySettings {
+        tcp_address: Some("127.0.0.1:6005".into()),
+        ..Default::default()
+    }
+    .user_language_server(worktree_path)
+    .unwrap()
+    .unwrap();
+    assert!(user_server.binary.is_none());
+    assert_eq!(
+        user_server.tcp_address,
+        Some("127.0.0.1:6005".parse().unwrap())
+    );
+
+    let path_lookup_only = project_settings::BinarySettings {
+        path_lookup: Some(true),
+        ..Default::default()
+    };
+    assert!(path_lookup_only
+        .user_language_server(worktree_path)
+        .unwrap()
+        .is_none());
+
+    let invalid_address = project_settings::BinarySettings {
+        tcp_address: Some("localhost".into()),
+        ..Default::default()
+    };
+    assert!(invalid_address.user_language_server(worktree_path).is_err());
+}
+
 #[gpui::test]
 async fn test_hovers_with_empty_parts(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -0,0 +1,427 @@
+use crate::{File, Project};
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use gpui::{Model, ModelContext, Task};
+use language::Buffer;
+use lsp::{DiagnosticServerCapabilities, LanguageServer, LanguageServerId};
+use std::{sync::Arc, time::Duration};
+use text::BufferId;
+use util::ResultExt;
+
+const PULL_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The diagnostics that language servers only report when asked to with
+/// `textDocument/diagnostic` and `workspace/diagnostic`, rather than publishing them.
+///
+/// A server can both publish and report diagnostics on request, so the two are kept
+/// apart, and a document's diagnostics are those of both.
+#[derive(Default)]
+pub(crate) struct PulledDiagnostics {
+    /// The last report for each document, by language server.
+    documents: HashMap<LanguageServerId, HashMap<lsp::Url, PulledDocument>>,
+    /// The diagnostics last published for each document by the language servers that
+    /// also report them on request.
+    published: HashMap<LanguageServerId, HashMap<lsp::Url, PublishedDocument>>,
+    buffer_tasks: HashMap<(BufferId, LanguageServerId), Task<()>>,
+    workspace_tasks: HashMap<LanguageServerId, Task<()>>,
+}
+
+struct PulledDocument {
+    /// Sending the id back lets the server reply that the diagnostics are unchanged.
+    result_id: Option<String>,
+    version: Option<i32>,
+    diagnostics: Vec<lsp::Diagnostic>,
+}
+
+struct PublishedDocument {
+    version: Option<i32>,
+    diagnostics: Vec<lsp::Diagnostic>,
+}
+
+impl Project {
+    /// Requests the diagnostics of a buffer from those of its language servers that report
+    /// them on request. While the buffer is being edited, the requests are debounced.
+    pub(crate) fn pull_buffer_diagnostics(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        debounce: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if !self.is_local() {
+            return;
+        }
+        let buffer = buffer_handle.read(cx);
+        let servers = self
+            .language_servers_for_buffer(buffer, cx)
+            .filter_map(|(_, server)| {
+                let options = diagnostic_options(server)?;
+                Some((server.clone(), options.inter_file_dependencies))
+            })
+            .collect::<Vec<_>>();
+        for (server, inter_file_dependencies) in servers {
+            // Changing one document can change the diagnostics of the others.
+            if inter_file_dependencies {
+                for buffer in self.buffers_for_server(server.server_id(), cx) {
+                    self.pull_document_diagnostics(&buffer, server.clone(), debounce, cx);
+                }
+            } else {
+                self.pull_document_diagnostics(buffer_handle, server, debounce, cx);
+            }
+        }
+    }
+
+    /// Requests all the diagnostics that a language server reports on request: those of the
+    /// open buffers, and those of the whole workspace when the server supports it.
+    pub(crate) fn pull_server_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if !self.is_local() {
+            return;
+        }
+        let Some(server) = self.language_server_for_id(server_id) else {
+            return;
+        };
+        let Some(options) = diagnostic_options(&server) else {
+            return;
+        };
+        for buffer in self.buffers_for_server(server_id, cx) {
+            self.pull_document_diagnostics(&buffer, server.clone(), false, cx);
+        }
+        if options.workspace_diagnostics {
+            self.pull_workspace_diagnostics(server, options.identifier, cx);
+        }
+    }
+
+    /// Updates the diagnostics that a language server published for a document, keeping
+    /// those it reported on request.
+    pub(crate) fn publish_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        params: lsp::PublishDiagnosticsParams,
+        disk_based_sources: &[String],
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let pulls_diagnostics = self
+            .language_server_for_id(server_id)
+            .map_or(false, |server| diagnostic_options(&server).is_some());
+        if !pulls_diagnostics {
+            return self.update_diagnostics(server_id, params, disk_based_sources, cx);
+        }
+
+        self.pulled_diagnostics
+            .published
+            .entry(server_id)
+            .or_default()
+            .insert(
+                params.uri.clone(),
+                PublishedDocument {
+                    version: params.version,
+                    diagnostics: params.diagnostics,
+                },
+            );
+        self.update_merged_diagnostics(server_id, params.uri, disk_based_sources, cx)
+    }
+
+    pub(crate) fn forget_pulled_diagnostics(&mut self, buffer_id: BufferId) {
+        self.pulled_diagnostics
+            .buffer_tasks
+            .retain(|(task_buffer_id, _), _| *task_buffer_id != buffer_id);
+    }
+
+    pub(crate) fn forget_pulled_diagnostics_for_server(&mut self, server_id: LanguageServerId) {
+        let pulled_diagnostics = &mut self.pulled_diagnostics;
+        pulled_diagnostics.documents.remove(&server_id);
+        pulled_diagnostics.published.remove(&server_id);
+        pulled_diagnostics
+            .buffer_tasks
+            .retain(|(_, task_server_id), _| *task_server_id != server_id);
+        pulled_diagnostics.workspace_tasks.remove(&server_id);
+    }
+
+    fn buffers_for_server(
+        &self,
+        server_id: LanguageServerId,
+        cx: &ModelContext<Self>,
+    ) -> Vec<Model<Buffer>> {
+        let buffer_store = self.buffer_store.read(cx);
+        self.buffer_snapshots
+            .iter()
+            .filter(|(_, snapshots)| snapshots.contains_key(&server_id))
+            .filter_map(|(buffer_id, _)| buffer_store.get(*buffer_id))
+            .collect()
+    }
+
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: &Model<Buffer>,
+        server: Arc<LanguageServer>,
+        debounce: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return;
+        };
+        let Some(uri) = lsp::Url::from_file_path(file.abs_path(cx)).log_err() else {
+            return;
+        };
+        let Some(options) = diagnostic_options(&server) else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let server_id = server.server_id();
+
+        let task = cx.spawn(move |this, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(PULL_DIAGNOSTICS_DEBOUNCE)
+                    .await;
+            }
+            // The diagnostics are for the document as the server knows it when it's asked.
+            let Ok((previous_result_id, version)) = this.update(&mut cx, |this, _| {
+                let previous_result_id = this
+                    .pulled_diagnostics
+                    .documents
+                    .get(&server_id)
+                    .and_then(|documents| documents.get(&uri))
+                    .and_then(|document| document.result_id.clone());
+                let version = this
+                    .buffer_snapshots
+                    .get(&buffer_id)
+                    .and_then(|snapshots| snapshots.get(&server_id))
+                    .and_then(|snapshots| snapshots.last())
+                    .map(|snapshot| snapshot.version);
+                (previous_result_id, version)
+            }) else {
+                return;
+            };
+            let response = server
+                .request::<lsp::request::DocumentDiagnosticRequest>(lsp::DocumentDiagnosticParams {
+                    text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                    identifier: options.identifier,
+                    previous_result_id,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await;
+            let Some(response) = response.log_err() else {
+                return;
+            };
+
+            let mut reports = Vec::new();
+            let related_documents = match response {
+                lsp::DocumentDiagnosticReportResult::Report(
+                    lsp::DocumentDiagnosticReport::Full(report),
+                ) => {
+                    reports.push((
+                        uri,
+                        lsp::DocumentDiagnosticReportKind::Full(
+                            report.full_document_diagnostic_report,
+                        ),
+                        version,
+                    ));
+                    report.related_documents
+                }
+                lsp::DocumentDiagnosticReportResult::Report(
+                    lsp::DocumentDiagnosticReport::Unchanged(report),
+                ) => {
+                    reports.push((
+                        uri,
+                        lsp::DocumentDiagnosticReportKind::Unchanged(
+                            report.unchanged_document_diagnostic_report,
+                        ),
+                        version,
+                    ));
+                    report.related_documents
+                }
+                lsp::DocumentDiagnosticReportResult::Partial(partial) => partial.related_documents,
+            };
+            reports.extend(
+                related_documents
+                    .into_iter()
+                    .flatten()
+                    .map(|(uri, report)| (uri, report, None)),
+            );
+
+            this.update(&mut cx, |this, cx| {
+                for (uri, report, version) in reports {
+                    this.apply_pulled_diagnostics(server_id, uri, report, version, cx)
+                        .log_err();
+                }
+            })
+            .ok();
+        });
+        self.pulled_diagnostics
+            .buffer_tasks
+            .insert((buffer_id, server_id), task);
+    }
+
+    fn pull_workspace_diagnostics(
+        &mut self,
+        server: Arc<LanguageServer>,
+        identifier: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let server_id = server.server_id();
+        let previous_result_ids = self
+            .pulled_diagnostics
+            .documents
+            .get(&server_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|(uri, document)| {
+                Some(lsp::PreviousResultId {
+                    uri: uri.clone(),
+                    value: document.result_id.clone()?,
+                })
+            })
+            .collect();
+
+        let task = cx.spawn(move |this, mut cx| async move {
+            let response = server
+                .request::<lsp::request::WorkspaceDiagnosticRequest>(
+                    lsp::WorkspaceDiagnosticParams {
+                        identifier,
+                        previous_result_ids,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                )
+                .await;
+            let Some(response) = response.log_err() else {
+                return;
+            };
+            let items = match response {
+                lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+                lsp::WorkspaceDiagnosticReportResult::Partial(partial) => partial.items,
+            };
+
+            this.update(&mut cx, |this, cx| {
+                for item in items {
+                    let (uri, version, report) = match item {
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                            report.uri,
+                            report.version.map(|version| version as i32),
+                            lsp::DocumentDiagnosticReportKind::Full(
+                                report.full_document_diagnostic_report,
+                            ),
+                        ),
+                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                            report.uri,
+                            report.version.map(|version| version as i32),
+                            lsp::DocumentDiagnosticReportKind::Unchanged(
+                                report.unchanged_document_diagnostic_report,
+                            ),
+                        ),
+                    };
+                    this.apply_pulled_diagnostics(server_id, uri, report, version, cx)
+                        .log_err();
+                }
+            })
+            .ok();
+        });
+        self.pulled_diagnostics
+            .workspace_tasks
+            .insert(server_id, task);
+    }
+
+    fn apply_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        report: lsp::DocumentDiagnosticReportKind,
+        version: Option<i32>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let adapter = self
+            .language_server_adapter_for_id(server_id)
+            .ok_or_else(|| anyhow!("language server not found"))?;
+        let documents = self
+            .pulled_diagnostics
+            .documents
+            .entry(server_id)
+            .or_default();
+        match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri: uri.clone(),
+                    diagnostics: report.items,
+                    version,
+                };
+                adapter.process_diagnostics(&mut params);
+                documents.insert(
+                    uri.clone(),
+                    PulledDocument {
+                        result_id: report.result_id,
+                        version,
+                        diagnostics: params.diagnostics,
+                    },
+                );
+            }
+            // The diagnostics are those of the report with the given id, which are only
+            // kept while its id is the last one.
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                return match documents.get(&uri) {
+                    Some(document) if document.result_id.as_ref() == Some(&report.result_id) => {
+                        Ok(())
+                    }
+                    _ => Err(anyhow!(
+                        "unchanged diagnostics report for {uri} has unknown result id {:?}",
+                        report.result_id
+                    )),
+                };
+            }
+        }
+        self.update_merged_diagnostics(server_id, uri, &adapter.disk_based_diagnostic_sources, cx)
+    }
+
+    /// Updates a document's diagnostics to those that a language server published for it,
+    /// together with those it reported on request.
+    fn update_merged_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        disk_based_sources: &[String],
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let pulled = self
+            .pulled_diagnostics
+            .documents
+            .get(&server_id)
+            .and_then(|documents| documents.get(&uri));
+        let published = self
+            .pulled_diagnostics
+            .published
+            .get(&server_id)
+            .and_then(|documents| documents.get(&uri));
+        let version = published
+            .and_then(|document| document.version)
+            .or_else(|| pulled.and_then(|document| document.version));
+        let diagnostics = published
+            .into_iter()
+            .flat_map(|document| document.diagnostics.iter())
+            .chain(
+                pulled
+                    .into_iter()
+                    .flat_map(|document| document.diagnostics.iter()),
+            )
+            .cloned()
+            .collect();
+        let params = lsp::PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        self.update_diagnostics(server_id, params, disk_based_sources, cx)
+    }
+}
+
+fn diagnostic_options(server: &LanguageServer) -> Option<lsp::DiagnosticOptions> {
+    match server.capabilities().diagnostic_provider? {
+        DiagnosticServerCapabilities::Options(options) => Some(options),
+        DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options)
+        }
+    }
+}
@@ -1,25 +1,28 @@
+use crate::{structural_search::StructuralPattern, ProjectPath};
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::Result;
 use client::proto;
-use language::{char_kind, BufferSnapshot};
+use language::{char_kind, BufferSnapshot, Node};
 use regex::{Captures, Regex, RegexBuilder};
+use regex_syntax::hir::{Class, Hir, HirKind};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
     io::{BufRead, BufReader, Read},
     ops::Range,
     path::Path,
-    sync::{Arc, OnceLock},
+    sync::Arc,
 };
 use util::paths::PathMatcher;
-
-static TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX: OnceLock<Regex> = OnceLock::new();
+use worktree::WorktreeId;
 
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: PathMatcher,
     files_to_exclude: PathMatcher,
+    /// The files and directories that the search is limited to, if any.
+    scope: Option<Arc<[ProjectPath]>>,
 }
 
 impl SearchInputs {
@@ -32,6 +35,9 @@ impl SearchInputs {
     pub fn files_to_exclude(&self) -> &PathMatcher {
         &self.files_to_exclude
     }
+    pub fn scope(&self) -> Option<&[ProjectPath]> {
+        self.scope.as_deref()
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -53,6 +59,15 @@ pub enum SearchQuery {
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    /// Matches code by its syntax, see [`StructuralPattern`].
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        replacement: Option<String>,
+        case_sensitive: bool,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -72,6 +87,7 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            scope: None,
         };
         Ok(Self::Text {
             search: Arc::new(search),
@@ -101,7 +117,9 @@ impl SearchQuery {
             query = word_query
         }
 
-        let multiline = query.contains('\n') || query.contains("\\n");
+        // Patterns that can't match a line break are searched line by line, which is
+        // faster than searching the whole text at once.
+        let multiline = regex_syntax::parse(&query).map_or(true, |hir| can_match_newline(&hir));
         let regex = RegexBuilder::new(&query)
             .case_insensitive(!case_sensitive)
             .multi_line(multiline)
@@ -110,6 +128,7 @@ impl SearchQuery {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            scope: None,
         };
         Ok(Self::Regex {
             regex,
@@ -122,8 +141,40 @@ impl SearchQuery {
         })
     }
 
+    pub fn structural(
+        query: impl ToString,
+        case_sensitive: bool,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query, case_sensitive)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            scope: None,
+        };
+        Ok(Self::Structural {
+            pattern: Arc::new(pattern),
+            replacement: None,
+            case_sensitive,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        if message.structural {
+            Self::structural(
+                message.query,
+                message.case_sensitive,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -152,17 +203,35 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
             }
         }
     }
+
+    /// Limits the search to the given files and to the files within the given directories.
+    pub fn with_scope(mut self, scope: impl Into<Arc<[ProjectPath]>>) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => {
+                inner.scope = Some(scope.into());
+                self
+            }
+        }
+    }
+
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
         proto::SearchProject {
             project_id,
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -206,14 +275,19 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let mut text = String::new();
+                BufReader::new(stream).read_to_string(&mut text)?;
+                Ok(pattern.is_match(&text))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
@@ -223,22 +297,24 @@ impl SearchQuery {
             SearchQuery::Regex {
                 regex, replacement, ..
             } => {
-                if let Some(replacement) = replacement {
-                    let replacement = TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX
-                        .get_or_init(|| Regex::new(r"\\\\|\\n|\\t").unwrap())
-                        .replace_all(replacement, |c: &Captures| {
-                            match c.get(0).unwrap().as_str() {
-                                r"\\" => "\\",
-                                r"\n" => "\n",
-                                r"\t" => "\t",
-                                x => unreachable!("Unexpected escape sequence: {}", x),
-                            }
-                        });
-                    Some(regex.replace(text, replacement))
-                } else {
-                    None
-                }
+                let replacement = replacement.as_ref()?;
+                let Some(captures) = regex.captures(text) else {
+                    return Some(Cow::Borrowed(text));
+                };
+                let mat = captures.get(0).unwrap();
+                let mut replaced = String::with_capacity(text.len());
+                replaced.push_str(&text[..mat.start()]);
+                expand_regex_replacement(&captures, replacement, &mut replaced);
+                replaced.push_str(&text[mat.end()..]);
+                Some(Cow::Owned(replaced))
             }
+            SearchQuery::Structural {
+                pattern,
+                replacement,
+                ..
+            } => pattern
+                .replace(text, replacement.as_deref()?)
+                .map(Cow::from),
         }
     }
 
@@ -326,6 +402,32 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { pattern, .. } => {
+                let text = rope.to_string();
+                let tokens = StructuralPattern::tokenize(&text);
+                let is_complete_syntax = |range: Range<usize>| {
+                    let range = range_offset + range.start..range_offset + range.end;
+                    buffer
+                        .syntax_layer_at(range.start)
+                        .map_or(true, |layer| is_node_sequence(layer.node(), range))
+                };
+                let mut token_ix = 0;
+                while token_ix < tokens.len() {
+                    if (token_ix + 1) % YIELD_INTERVAL == 0 {
+                        yield_now().await;
+                    }
+
+                    if let Some((mat, end_ix)) =
+                        pattern.match_at(&text, &tokens, token_ix, &is_complete_syntax)
+                    {
+                        matches.push(mat.range);
+                        token_ix = end_ix;
+                    } else {
+                        token_ix += 1;
+                    }
+                }
+            }
         }
 
         matches
@@ -343,6 +445,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -350,6 +453,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { case_sensitive, .. } => *case_sensitive,
         }
     }
 
@@ -361,6 +465,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -368,6 +475,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -376,6 +487,19 @@ impl SearchQuery {
         self.as_inner().files_to_exclude()
     }
 
+    pub fn scope(&self) -> Option<&[ProjectPath]> {
+        self.as_inner().scope()
+    }
+
+    /// Whether the file at the given worktree-relative path is within the search's scope.
+    pub fn path_in_scope(&self, worktree_id: WorktreeId, path: &Path) -> bool {
+        self.scope().map_or(true, |scope| {
+            scope.iter().any(|scope_path| {
+                scope_path.worktree_id == worktree_id && path.starts_with(&scope_path.path)
+            })
+        })
+    }
+
     pub fn file_matches(&self, file_path: Option<&Path>) -> bool {
         match file_path {
             Some(file_path) => {
@@ -397,9 +521,124 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
+        }
+    }
+}
+
+/// Whether a match of the pattern can contain a line break, e.g. because of a `\n`, a
+/// `\s` or a negated character class such as `[^)]`.
+fn can_match_newline(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => false,
+        HirKind::Literal(literal) => literal.0.contains(&b'\n'),
+        HirKind::Class(Class::Unicode(class)) => class
+            .ranges()
+            .iter()
+            .any(|range| range.start() <= '\n' && '\n' <= range.end()),
+        HirKind::Class(Class::Bytes(class)) => class
+            .ranges()
+            .iter()
+            .any(|range| range.start() <= b'\n' && b'\n' <= range.end()),
+        HirKind::Repetition(repetition) => can_match_newline(&repetition.sub),
+        HirKind::Capture(capture) => can_match_newline(&capture.sub),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs.iter().any(can_match_newline),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CaseConversion {
+    Upper,
+    Lower,
+}
+
+/// Expands a regex replacement template into `output`.
+///
+/// Besides the `$1` and `${name}` capture references and the `\n`, `\t` and `\\` escapes,
+/// the template may change the case of what follows: `\U` and `\L` convert everything up
+/// to the next `\E` to upper or lower case, while `\u` and `\l` only convert the next
+/// character.
+fn expand_regex_replacement(captures: &Captures, template: &str, output: &mut String) {
+    let mut case_conversion = None;
+    let mut next_char_conversion = None;
+    let mut segment = String::new();
+    let mut flush_segment =
+        |segment: &mut String,
+         case_conversion: Option<CaseConversion>,
+         next_char_conversion: &mut Option<CaseConversion>| {
+            let mut expanded = String::new();
+            captures.expand(segment, &mut expanded);
+            segment.clear();
+            let mut chars = expanded.chars();
+            if let Some(conversion) = next_char_conversion.take() {
+                match chars.next() {
+                    Some(first) => push_converted(output, first, Some(conversion)),
+                    None => *next_char_conversion = Some(conversion),
+                }
+            }
+            for c in chars {
+                push_converted(output, c, case_conversion);
+            }
+        };
+
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            segment.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => segment.push('\\'),
+            Some('n') => segment.push('\n'),
+            Some('t') => segment.push('\t'),
+            Some(modifier @ ('U' | 'L' | 'E' | 'u' | 'l')) => {
+                flush_segment(&mut segment, case_conversion, &mut next_char_conversion);
+                match modifier {
+                    'U' => case_conversion = Some(CaseConversion::Upper),
+                    'L' => case_conversion = Some(CaseConversion::Lower),
+                    'E' => case_conversion = None,
+                    'u' => next_char_conversion = Some(CaseConversion::Upper),
+                    _ => next_char_conversion = Some(CaseConversion::Lower),
+                }
+            }
+            Some(other) => {
+                segment.push('\\');
+                segment.push(other);
+            }
+            None => segment.push('\\'),
         }
     }
+    flush_segment(&mut segment, case_conversion, &mut next_char_conversion);
+}
+
+fn push_converted(output: &mut String, c: char, conversion: Option<CaseConversion>) {
+    match conversion {
+        Some(CaseConversion::Upper) => output.extend(c.to_uppercase()),
+        Some(CaseConversion::Lower) => output.extend(c.to_lowercase()),
+        None => output.push(c),
+    }
+}
+
+/// Whether the range is exactly covered by a node of the tree, or by a run of siblings.
+fn is_node_sequence(root: Node, range: Range<usize>) -> bool {
+    let Some(node) = root.descendant_for_byte_range(range.start, range.end) else {
+        return false;
+    };
+    if node.byte_range() == range {
+        return true;
+    }
+
+    let mut cursor = node.walk();
+    let mut starts_at_child = false;
+    for child in node.children(&mut cursor) {
+        starts_at_child |= child.start_byte() == range.start;
+        if starts_at_child && child.end_byte() == range.end {
+            return true;
+        }
+    }
+    false
 }
 
 fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<PathMatcher> {
@@ -436,6 +675,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn regex_replacement_with_captures_and_case_conversion() {
+        let replace = |query: &str, replacement: &str, text: &str| {
+            SearchQuery::regex(
+                query,
+                false,
+                true,
+                false,
+                PathMatcher::default(),
+                PathMatcher::default(),
+            )
+            .unwrap()
+            .with_replacement(replacement.to_string())
+            .replacement_for(text)
+            .unwrap()
+            .into_owned()
+        };
+
+        assert_eq!(replace(r"(\w+)_(\w+)", "${2}_$1", "foo_bar"), "bar_foo");
+        assert_eq!(
+            replace(r"get_(?P<field>\w+)", "${field}Getter", "get_name"),
+            "nameGetter"
+        );
+        assert_eq!(replace(r"(\w+)_(\w+)", r"$1\u$2", "get_name"), "getName");
+        assert_eq!(
+            replace(r"(\w+) (\w+)", r"\U$1\E \l$2\n\\", "Hello World"),
+            "HELLO world\n\\"
+        );
+        assert_eq!(replace(r"(\w+)", r"\L$1", "MiXeD"), "mixed");
+    }
+
+    #[test]
+    fn multiline_regex_detection() {
+        let regex = |query: &str| {
+            SearchQuery::regex(
+                query,
+                false,
+                false,
+                false,
+                PathMatcher::default(),
+                PathMatcher::default(),
+            )
+            .unwrap()
+        };
+        let is_multiline = |query: &SearchQuery| {
+            matches!(
+                query,
+                SearchQuery::Regex {
+                    multiline: true,
+                    ..
+                }
+            )
+        };
+
+        for query in [r"fn new\([^)]*\)\s*\{", r"a\nb", r"foo\W+bar", "(?s)a.b"] {
+            assert!(is_multiline(&regex(query)), "{query} should be multiline");
+        }
+        for query in [r"fn \w+\(", "^foo.*bar$", r"[^)\n]+", r"a[ \t]*b"] {
+            assert!(
+                !is_multiline(&regex(query)),
+                "{query} should not be multiline"
+            );
+        }
+
+        let query = regex(r"fn new\([^)]*\)\s*\{");
+        assert!(query
+            .detect("impl A {\n    fn new(\n        a: u32,\n    )\n    {".as_bytes())
+            .unwrap());
+        assert!(!query.detect("fn new(a: u32);\n".as_bytes()).unwrap());
+    }
+
     #[test]
     fn path_matcher_creation_for_globs() {
         for invalid_glob in ["dir/[].txt", "dir/[a-z.txt", "dir/{file"] {
@@ -42,6 +42,25 @@ impl SearchHistory {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Returns the queries in the history, from oldest to newest.
+    pub fn queries(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Replaces the history with the given queries, such as those persisted by a previous
+    /// session, keeping only the newest ones if there are too many.
+    pub fn restore(&mut self, queries: Vec<String>) {
+        self.history = queries;
+        if let Some(max_history_len) = self.max_history_len {
+            let excess = self.history.len().saturating_sub(max_history_len);
+            self.history.drain(..excess);
+        }
+    }
+
     pub fn add(&mut self, cursor: &mut SearchHistoryCursor, search_string: String) {
         if let Some(selected_ix) = cursor.selection {
             if self.history.get(selected_ix) == Some(&search_string) {
@@ -258,4 +277,22 @@ mod tests {
         assert_eq!(search_history.current(&cursor1), None);
         assert_eq!(search_history.current(&cursor2), None);
     }
+
+    #[test]
+    fn test_restore() {
+        let mut search_history = SearchHistory::new(Some(2), QueryInsertionBehavior::AlwaysInsert);
+        let mut cursor = SearchHistoryCursor::default();
+
+        search_history.restore(vec![
+            "Rust".to_string(),
+            "Go".to_string(),
+            "Zig".to_string(),
+        ]);
+        assert_eq!(search_history.queries(), ["Go", "Zig"]);
+        assert_eq!(search_history.previous(&mut cursor), Some("Zig"));
+        assert_eq!(search_history.previous(&mut cursor), Some("Go"));
+
+        search_history.add(&mut cursor, "C".to_string());
+        assert_eq!(search_history.queries(), ["Zig", "C"]);
+    }
 }
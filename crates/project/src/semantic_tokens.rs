@@ -0,0 +1,234 @@
+use crate::{File, Project};
+use anyhow::{anyhow, Result};
+use gpui::{Model, ModelContext, Task};
+use language::{language_settings::language_settings, Anchor, Bias, Buffer, PointUtf16, Unclipped};
+use lsp::{LanguageServerId, SemanticTokensServerCapabilities};
+use std::ops::Range;
+use text::BufferId;
+
+/// A range of a buffer that a language server classified with a token type and modifiers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    pub token_type: lsp::SemanticTokenType,
+    pub modifiers: Vec<lsp::SemanticTokenModifier>,
+}
+
+/// The last semantic tokens a language server returned for a buffer, which the
+/// next `textDocument/semanticTokens/full/delta` response is applied to.
+pub(crate) struct CachedSemanticTokens {
+    result_id: Option<String>,
+    data: Vec<lsp::SemanticToken>,
+}
+
+impl Project {
+    /// Requests the semantic tokens of the buffer from the first of its language servers that
+    /// provides them, only transferring the tokens that changed when the server supports it.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<SemanticToken>>> {
+        if !self.is_local() {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let buffer = buffer_handle.read(cx);
+        if !language_settings(buffer.language(), buffer.file(), cx).semantic_tokens {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let Some((server, options)) =
+            self.language_servers_for_buffer(buffer, cx)
+                .find_map(|(_, server)| {
+                    let options = match server.capabilities().semantic_tokens_provider? {
+                        SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+                        SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                            options,
+                        ) => options.semantic_tokens_options,
+                    };
+                    Some((server.clone(), options))
+                })
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let supports_delta = match options.full {
+            Some(lsp::SemanticTokensFullOptions::Delta { delta }) => delta == Some(true),
+            Some(lsp::SemanticTokensFullOptions::Bool(true)) => false,
+            _ => return Task::ready(Ok(Vec::new())),
+        };
+
+        let buffer_id = buffer.remote_id();
+        let server_id = server.server_id();
+        let snapshot = buffer.text_snapshot();
+        let previous_result_id = self
+            .semantic_tokens
+            .get(&(buffer_id, server_id))
+            .and_then(|cached| cached.result_id.clone())
+            .filter(|_| supports_delta);
+        let text_document = match lsp::Url::from_file_path(file.abs_path(cx)) {
+            Ok(uri) => lsp::TextDocumentIdentifier::new(uri),
+            Err(()) => return Task::ready(Err(anyhow!("invalid file path"))),
+        };
+
+        cx.spawn(move |this, mut cx| async move {
+            let (result_id, data) = if let Some(previous_result_id) = previous_result_id {
+                let response = server
+                    .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                        lsp::SemanticTokensDeltaParams {
+                            text_document,
+                            previous_result_id,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await?;
+                match response {
+                    Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                        (tokens.result_id, tokens.data)
+                    }
+                    Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                        let mut data = this.update(&mut cx, |this, _| {
+                            this.semantic_tokens
+                                .get(&(buffer_id, server_id))
+                                .map(|cached| cached.data.clone())
+                                .unwrap_or_default()
+                        })?;
+                        apply_semantic_token_edits(&mut data, delta.edits);
+                        (delta.result_id, data)
+                    }
+                    Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { .. }) | None => {
+                        (None, Vec::new())
+                    }
+                }
+            } else {
+                let response = server
+                    .request::<lsp::request::SemanticTokensFullRequest>(lsp::SemanticTokensParams {
+                        text_document,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    })
+                    .await?;
+                match response {
+                    Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                        (tokens.result_id, tokens.data)
+                    }
+                    Some(lsp::SemanticTokensResult::Partial(partial)) => (None, partial.data),
+                    None => (None, Vec::new()),
+                }
+            };
+
+            let tokens = decode_semantic_tokens(&data, &options.legend, &snapshot);
+            this.update(&mut cx, |this, _| {
+                this.semantic_tokens.insert(
+                    (buffer_id, server_id),
+                    CachedSemanticTokens { result_id, data },
+                );
+            })?;
+            Ok(tokens)
+        })
+    }
+
+    pub(crate) fn forget_semantic_tokens(&mut self, buffer_id: BufferId) {
+        self.semantic_tokens
+            .retain(|(cached_buffer_id, _), _| *cached_buffer_id != buffer_id);
+    }
+
+    pub(crate) fn forget_semantic_tokens_for_server(&mut self, server_id: LanguageServerId) {
+        self.semantic_tokens
+            .retain(|(_, cached_server_id), _| *cached_server_id != server_id);
+    }
+}
+
+/// Applies the edits of a semantic tokens delta. Their offsets count the integers of the
+/// encoded token array, in which every token takes five.
+fn apply_semantic_token_edits(
+    data: &mut Vec<lsp::SemanticToken>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) {
+    // All edits refer to the original array, so apply them back to front.
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+    for edit in edits {
+        let start = (edit.start as usize / 5).min(data.len());
+        let end = (start + edit.delete_count as usize / 5).min(data.len());
+        data.splice(start..end, edit.data.unwrap_or_default());
+    }
+}
+
+fn decode_semantic_tokens(
+    data: &[lsp::SemanticToken],
+    legend: &lsp::SemanticTokensLegend,
+    snapshot: &text::BufferSnapshot,
+) -> Vec<SemanticToken> {
+    let mut row = 0;
+    let mut column = 0;
+    data.iter()
+        .filter_map(|token| {
+            // Positions are relative to the previous token.
+            if token.delta_line > 0 {
+                row += token.delta_line;
+                column = token.delta_start;
+            } else {
+                column += token.delta_start;
+            }
+
+            let token_type = legend.token_types.get(token.token_type as usize)?.clone();
+            let modifiers = legend
+                .token_modifiers
+                .iter()
+                .take(32)
+                .enumerate()
+                .filter(|(ix, _)| token.token_modifiers_bitset & (1 << ix) != 0)
+                .map(|(_, modifier)| modifier.clone())
+                .collect();
+            let start =
+                snapshot.clip_point_utf16(Unclipped(PointUtf16::new(row, column)), Bias::Left);
+            let end = snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(row, column + token.length)),
+                Bias::Right,
+            );
+            (start < end).then(|| SemanticToken {
+                range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                token_type,
+                modifiers,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(delta_line: u32, delta_start: u32, length: u32) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_semantic_token_edits() {
+        let mut data = vec![token(0, 0, 1), token(1, 0, 2), token(1, 0, 3)];
+        apply_semantic_token_edits(
+            &mut data,
+            vec![
+                lsp::SemanticTokensEdit {
+                    start: 0,
+                    delete_count: 5,
+                    data: Some(vec![token(0, 0, 4), token(0, 5, 5)]),
+                },
+                lsp::SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 5,
+                    data: None,
+                },
+            ],
+        );
+        assert_eq!(data, vec![token(0, 0, 4), token(0, 5, 5), token(1, 0, 2)]);
+    }
+}
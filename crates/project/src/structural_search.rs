@@ -0,0 +1,408 @@
+use anyhow::Result;
+use std::{ops::Range, sync::Arc};
+
+/// The most steps that matching a pattern at a single position may take. Patterns with
+/// several metavariables could otherwise take exponential time to not match.
+const MAX_MATCH_STEPS: usize = 4096;
+
+/// A pattern for structural search, such as `if ($COND) { return $X; }`.
+///
+/// The pattern matches token by token, so whitespace and line breaks don't need to agree
+/// with the searched text. Each `$NAME` metavariable matches a bracket-balanced sequence of
+/// one or more tokens, and a metavariable that appears several times must match the same
+/// tokens every time. When the searched buffer has a syntax tree, metavariables and whole
+/// matches are only accepted if they cover complete syntax nodes, so that `$X * 2` matches
+/// `b * 2` in `a + b * 2`, rather than `a + b * 2` as a whole.
+#[derive(Clone, Debug)]
+pub struct StructuralPattern {
+    tokens: Vec<PatternToken>,
+    case_sensitive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PatternToken {
+    Literal(String),
+    Metavariable(Arc<str>),
+}
+
+/// A match of a [`StructuralPattern`], along with the text each metavariable captured.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    pub captures: Vec<(Arc<str>, Range<usize>)>,
+}
+
+impl StructuralPattern {
+    pub fn new(pattern: &str, case_sensitive: bool) -> Result<Self> {
+        let pattern_tokens = tokenize(pattern);
+        let mut tokens = Vec::with_capacity(pattern_tokens.len());
+        let mut pattern_tokens = pattern_tokens.into_iter().peekable();
+        while let Some(token) = pattern_tokens.next() {
+            let text = &pattern[token.clone()];
+            if text == "$" {
+                if let Some(name) = pattern_tokens.next_if(|name| {
+                    name.start == token.end && pattern[name.clone()].starts_with(is_word_char)
+                }) {
+                    tokens.push(PatternToken::Metavariable(pattern[name].into()));
+                    continue;
+                }
+            }
+            tokens.push(PatternToken::Literal(text.to_string()));
+        }
+
+        anyhow::ensure!(
+            tokens
+                .iter()
+                .any(|token| matches!(token, PatternToken::Literal(_))),
+            "structural search pattern must contain text besides metavariables"
+        );
+        Ok(Self {
+            tokens,
+            case_sensitive,
+        })
+    }
+
+    /// Splits the text into the tokens that patterns are matched against.
+    pub fn tokenize(text: &str) -> Vec<Range<usize>> {
+        tokenize(text)
+    }
+
+    /// Matches the pattern against the tokens that start at `start_ix`.
+    ///
+    /// `is_complete_syntax` decides whether the given range of `text` consists of whole
+    /// syntax nodes.
+    pub fn match_at(
+        &self,
+        text: &str,
+        tokens: &[Range<usize>],
+        start_ix: usize,
+        is_complete_syntax: &dyn Fn(Range<usize>) -> bool,
+    ) -> Option<(StructuralMatch, usize)> {
+        let mut matcher = Matcher::new(self, text, tokens, start_ix, false, is_complete_syntax);
+        let end_ix = matcher.match_tokens(0, start_ix)?;
+        Some((
+            StructuralMatch {
+                range: tokens[start_ix].start..tokens[end_ix - 1].end,
+                captures: matcher.captures,
+            },
+            end_ix,
+        ))
+    }
+
+    /// Returns all non-overlapping matches of the pattern in the text, ignoring syntax.
+    pub fn find_all(&self, text: &str) -> Vec<StructuralMatch> {
+        let tokens = tokenize(text);
+        let mut matches = Vec::new();
+        let mut token_ix = 0;
+        while token_ix < tokens.len() {
+            if let Some((mat, end_ix)) = self.match_at(text, &tokens, token_ix, &|_| true) {
+                matches.push(mat);
+                token_ix = end_ix;
+            } else {
+                token_ix += 1;
+            }
+        }
+        matches
+    }
+
+    /// Whether the text could contain a match of the pattern. Used to skip files cheaply
+    /// before their syntax is known.
+    pub fn is_match(&self, text: &str) -> bool {
+        let longest_literal = self
+            .tokens
+            .iter()
+            .filter_map(|token| match token {
+                PatternToken::Literal(literal) => Some(literal),
+                PatternToken::Metavariable(_) => None,
+            })
+            .max_by_key(|literal| literal.len());
+        if let Some(literal) = longest_literal {
+            let contains_literal = if self.case_sensitive {
+                text.contains(literal.as_str())
+            } else {
+                text.to_lowercase().contains(&literal.to_lowercase())
+            };
+            if !contains_literal {
+                return false;
+            }
+        }
+        !self.find_all(text).is_empty()
+    }
+
+    /// Returns the replacement for `text`, which must match the pattern exactly, with each
+    /// `$NAME` in the template substituted by the text that the metavariable captured.
+    pub fn replace(&self, text: &str, template: &str) -> Option<String> {
+        let tokens = tokenize(text);
+        let mut matcher = Matcher::new(self, text, &tokens, 0, true, &|_| true);
+        matcher.match_tokens(0, 0)?;
+        let captures = matcher.captures;
+
+        let mut replacement = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(dollar_ix) = rest.find('$') {
+            replacement.push_str(&rest[..dollar_ix]);
+            let after_dollar = &rest[dollar_ix + 1..];
+            let name_len = after_dollar
+                .find(|c: char| !is_word_char(c))
+                .unwrap_or(after_dollar.len());
+            let name = &after_dollar[..name_len];
+            match captures
+                .iter()
+                .find(|(capture_name, _)| !name.is_empty() && capture_name.as_ref() == name)
+            {
+                Some((_, range)) => replacement.push_str(&text[range.clone()]),
+                None => {
+                    replacement.push('$');
+                    replacement.push_str(name);
+                }
+            }
+            rest = &after_dollar[name_len..];
+        }
+        replacement.push_str(rest);
+        Some(replacement)
+    }
+}
+
+/// The state of matching a pattern against the tokens that start at one position.
+struct Matcher<'a> {
+    pattern: &'a StructuralPattern,
+    text: &'a str,
+    tokens: &'a [Range<usize>],
+    start_ix: usize,
+    match_to_end: bool,
+    is_complete_syntax: &'a dyn Fn(Range<usize>) -> bool,
+    captures: Vec<(Arc<str>, Range<usize>)>,
+    remaining_steps: usize,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(
+        pattern: &'a StructuralPattern,
+        text: &'a str,
+        tokens: &'a [Range<usize>],
+        start_ix: usize,
+        match_to_end: bool,
+        is_complete_syntax: &'a dyn Fn(Range<usize>) -> bool,
+    ) -> Self {
+        Self {
+            pattern,
+            text,
+            tokens,
+            start_ix,
+            match_to_end,
+            is_complete_syntax,
+            captures: Vec::new(),
+            remaining_steps: MAX_MATCH_STEPS,
+        }
+    }
+
+    /// Matches the pattern from `pattern_ix` on against the tokens from `token_ix` on,
+    /// returning the index of the token after the match.
+    fn match_tokens(&mut self, pattern_ix: usize, token_ix: usize) -> Option<usize> {
+        self.remaining_steps = self.remaining_steps.checked_sub(1)?;
+
+        let Some(pattern_token) = self.pattern.tokens.get(pattern_ix) else {
+            let is_match = token_ix > self.start_ix
+                && (!self.match_to_end || token_ix == self.tokens.len())
+                && (self.is_complete_syntax)(
+                    self.tokens[self.start_ix].start..self.tokens[token_ix - 1].end,
+                );
+            return is_match.then_some(token_ix);
+        };
+
+        match pattern_token {
+            PatternToken::Literal(literal) => {
+                if !self.is_literal_at(literal, token_ix) {
+                    return None;
+                }
+                self.match_tokens(pattern_ix + 1, token_ix + 1)
+            }
+            PatternToken::Metavariable(name) => {
+                let previous_capture = self
+                    .captures
+                    .iter()
+                    .find(|(capture_name, _)| capture_name == name)
+                    .map(|(_, range)| range.clone());
+                // Only the ends that are followed by the pattern's next literal can match.
+                let next_literal = match self.pattern.tokens.get(pattern_ix + 1) {
+                    Some(PatternToken::Literal(literal)) => Some(literal),
+                    _ => None,
+                };
+                let mut depth = 0;
+                for end_ix in token_ix..self.tokens.len() {
+                    depth += bracket_depth_change(&self.text[self.tokens[end_ix].clone()]);
+                    if depth < 0 {
+                        break;
+                    } else if depth > 0
+                        || next_literal
+                            .map_or(false, |literal| !self.is_literal_at(literal, end_ix + 1))
+                    {
+                        continue;
+                    }
+
+                    let range = self.tokens[token_ix].start..self.tokens[end_ix].end;
+                    if let Some(previous_capture) = &previous_capture {
+                        if !same_tokens(
+                            &self.text[previous_capture.clone()],
+                            &self.text[range.clone()],
+                        ) {
+                            continue;
+                        }
+                    } else if (self.is_complete_syntax)(range.clone()) {
+                        self.captures.push((name.clone(), range));
+                    } else {
+                        continue;
+                    }
+
+                    if let Some(end_ix) = self.match_tokens(pattern_ix + 1, end_ix + 1) {
+                        return Some(end_ix);
+                    }
+                    if previous_capture.is_none() {
+                        self.captures.pop();
+                    }
+                    if self.remaining_steps == 0 {
+                        break;
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    fn is_literal_at(&self, literal: &str, token_ix: usize) -> bool {
+        let Some(token) = self.tokens.get(token_ix) else {
+            return false;
+        };
+        let token_text = &self.text[token.clone()];
+        if self.pattern.case_sensitive {
+            token_text == literal
+        } else {
+            token_text.eq_ignore_ascii_case(literal)
+        }
+    }
+}
+
+/// Splits text into words, double-quoted strings and single punctuation characters,
+/// skipping whitespace.
+fn tokenize(text: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        if is_word_char(c) {
+            while let Some((ix, c)) = chars.next_if(|(_, c)| is_word_char(*c)) {
+                end = ix + c.len_utf8();
+            }
+        } else if c == '"' {
+            while let Some((ix, c)) = chars.next() {
+                end = ix + c.len_utf8();
+                if c == '"' {
+                    break;
+                } else if c == '\\' {
+                    if let Some((ix, c)) = chars.next() {
+                        end = ix + c.len_utf8();
+                    }
+                }
+            }
+        }
+        tokens.push(start..end);
+    }
+    tokens
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn bracket_depth_change(token: &str) -> i32 {
+    match token {
+        "(" | "[" | "{" => 1,
+        ")" | "]" | "}" => -1,
+        _ => 0,
+    }
+}
+
+fn same_tokens(a: &str, b: &str) -> bool {
+    let a_tokens = tokenize(a);
+    let b_tokens = tokenize(b);
+    a_tokens.len() == b_tokens.len()
+        && a_tokens
+            .into_iter()
+            .zip(b_tokens)
+            .all(|(a_token, b_token)| a[a_token] == b[b_token])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched_texts<'a>(pattern: &str, text: &'a str) -> Vec<&'a str> {
+        StructuralPattern::new(pattern, true)
+            .unwrap()
+            .find_all(text)
+            .into_iter()
+            .map(|mat| &text[mat.range])
+            .collect()
+    }
+
+    #[test]
+    fn test_structural_matching() {
+        let text = "
+            fn a(x: u32) -> u32 {
+                if (x > f(1, 2)) { return x; }
+                if (ready)
+                {
+                    return   compute(x);
+                }
+                if (x) { log(); return x; }
+            }
+        ";
+        assert_eq!(
+            matched_texts("if ($COND) { return $X; }", text),
+            [
+                "if (x > f(1, 2)) { return x; }",
+                "if (ready)\n                {\n                    return   compute(x);\n                }",
+            ]
+        );
+        assert_eq!(
+            matched_texts("if ($X) { $BODY return $X; }", text),
+            ["if (x) { log(); return x; }"]
+        );
+        assert!(matched_texts("while ($COND) { $BODY }", text).is_empty());
+        assert!(StructuralPattern::new("$A $B", true).is_err());
+    }
+
+    #[test]
+    fn test_structural_matching_is_bounded() {
+        // Each metavariable could end at any of the arguments, so matching without a bound
+        // would try every combination of them before failing.
+        let text = format!("f({}) y", vec!["a"; 500].join(", "));
+        let start = std::time::Instant::now();
+        assert!(matched_texts("f($A, $B, $C, $D, $E) x", &text).is_empty());
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+        assert_eq!(
+            matched_texts("f($A, $B, $C) y", "f(a, b, c, d) y; f(a, b, c) y"),
+            ["f(a, b, c, d) y", "f(a, b, c) y"]
+        );
+    }
+
+    #[test]
+    fn test_structural_replacement() {
+        let pattern = StructuralPattern::new("assert!($A == $B)", false).unwrap();
+        assert_eq!(
+            pattern
+                .replace(
+                    "ASSERT!(left(1) == \"a b\")",
+                    "assert_eq!($A, $B, \"$MISSING\")"
+                )
+                .as_deref(),
+            Some("assert_eq!(left(1), \"a b\", \"$MISSING\")")
+        );
+        assert_eq!(pattern.replace("assert!(a == b);", "$A"), None);
+    }
+}
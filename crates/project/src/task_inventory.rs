@@ -8,19 +8,19 @@ use std::{
 };
 
 use anyhow::Result;
-use collections::{btree_map, BTreeMap, VecDeque};
+use collections::{btree_map, BTreeMap, HashMap, VecDeque};
 use futures::{
     channel::mpsc::{unbounded, UnboundedSender},
     StreamExt,
 };
 use gpui::{AppContext, Context, Model, ModelContext, Task};
 use itertools::Itertools;
-use language::{ContextProvider, File, Language, Location};
+use language::{Buffer, ContextProvider, File, Language, Location};
 use task::{
-    static_source::StaticSource, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates,
-    TaskVariables, VariableName,
+    static_source::StaticSource, ResolvedTask, TaskContext, TaskId, TaskProblem, TaskTemplate,
+    TaskTemplates, TaskVariables, VariableName,
 };
-use text::{Point, ToPoint};
+use text::{Anchor, Bias, Point, ToPoint};
 use util::{post_inc, NumericPrefixWithSuffix, ResultExt};
 use worktree::WorktreeId;
 
@@ -30,10 +30,51 @@ use crate::Project;
 pub struct Inventory {
     sources: Vec<SourceInInventory>,
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
+    /// The last run of each task spawned in a terminal, most recently started last.
+    task_runs: Vec<TaskRun>,
     update_sender: UnboundedSender<()>,
     _update_pooler: Task<anyhow::Result<()>>,
 }
 
+/// The last run of a task, spawned in a terminal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskRun {
+    pub id: TaskId,
+    pub label: String,
+    pub state: TaskRunState,
+    /// The problems that the task's problem matcher found in its output, once it finished.
+    pub problems: Vec<TaskProblemMarker>,
+}
+
+/// A problem that a task's problem matcher found, anchored in the buffer of the problem's
+/// file once it is open, so that its position follows the edits made after the task finished.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskProblemMarker {
+    pub problem: TaskProblem,
+    pub position: Option<(Model<Buffer>, Anchor)>,
+}
+
+impl TaskProblemMarker {
+    /// Returns the problem, at the current position of its anchor.
+    pub fn current(&self, cx: &AppContext) -> TaskProblem {
+        let mut problem = self.problem.clone();
+        if let Some((buffer, anchor)) = &self.position {
+            let point = anchor.to_point(&buffer.read(cx).snapshot());
+            problem.row = point.row;
+            problem.column = point.column;
+        }
+        problem
+    }
+}
+
+/// The state of the last run of a task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskRunState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
 struct SourceInInventory {
     source: StaticSource,
     kind: TaskSourceKind,
@@ -107,6 +148,7 @@ impl Inventory {
             Self {
                 sources: Vec::new(),
                 last_scheduled_tasks: VecDeque::new(),
+                task_runs: Vec::new(),
                 update_sender,
                 _update_pooler,
             }
@@ -382,6 +424,89 @@ impl Inventory {
         }
     }
 
+    /// Records a new run of the given task, replacing its previous one.
+    pub fn task_started(&mut self, id: TaskId, label: String) {
+        self.task_runs.retain(|run| run.id != id);
+        self.task_runs.push(TaskRun {
+            id,
+            label,
+            state: TaskRunState::Running,
+            problems: Vec::new(),
+        });
+        if self.task_runs.len() > 100 {
+            self.task_runs.remove(0);
+        }
+    }
+
+    /// Records the outcome of the running task, and the problems found in its output.
+    pub fn task_finished(&mut self, id: &TaskId, success: bool, problems: Vec<TaskProblem>) {
+        if let Some(run) = self.task_runs.iter_mut().find(|run| &run.id == id) {
+            run.state = if success {
+                TaskRunState::Succeeded
+            } else {
+                TaskRunState::Failed
+            };
+            run.problems = problems
+                .into_iter()
+                .map(|problem| TaskProblemMarker {
+                    problem,
+                    position: None,
+                })
+                .collect();
+        }
+    }
+
+    /// Anchors the problems of the given task run in the buffers of their files, for the
+    /// problems whose files were opened.
+    pub fn anchor_task_problems(
+        &mut self,
+        id: &TaskId,
+        buffers: &HashMap<PathBuf, Model<Buffer>>,
+        cx: &AppContext,
+    ) {
+        let Some(run) = self.task_runs.iter_mut().find(|run| &run.id == id) else {
+            return;
+        };
+        for marker in &mut run.problems {
+            let Some(buffer) = buffers.get(&marker.problem.path) else {
+                continue;
+            };
+            let snapshot = buffer.read(cx).snapshot();
+            let point = snapshot.clip_point(
+                Point::new(marker.problem.row, marker.problem.column),
+                Bias::Left,
+            );
+            marker.position = Some((buffer.clone(), snapshot.anchor_before(point)));
+        }
+    }
+
+    /// Returns the last runs of the tasks, most recently started first.
+    pub fn task_runs(&self) -> impl Iterator<Item = &TaskRun> {
+        self.task_runs.iter().rev()
+    }
+
+    /// Returns the state of the last run of the given task, if it ran.
+    pub fn task_run_state(&self, id: &TaskId) -> Option<TaskRunState> {
+        self.task_runs
+            .iter()
+            .find(|run| &run.id == id)
+            .map(|run| run.state)
+    }
+
+    /// Returns the problems found in the output of the finished tasks, at their current
+    /// positions, along with the labels of the tasks that found them, most recently started
+    /// task first.
+    pub fn task_problems<'a>(
+        &'a self,
+        cx: &'a AppContext,
+    ) -> impl Iterator<Item = (&'a str, TaskProblem)> + 'a {
+        self.task_runs().flat_map(move |run| {
+            run.problems
+                .iter()
+                .map(move |marker| (run.label.as_str(), marker.current(cx)))
+        })
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -991,6 +1116,97 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_task_runs(cx: &mut TestAppContext) {
+        let inventory = cx.update(Inventory::new);
+        let test_id = TaskId("test".to_string());
+        let build_id = TaskId("build".to_string());
+        let problem = TaskProblem {
+            path: PathBuf::from("/project/src/main.rs"),
+            row: 2,
+            column: 4,
+            message: "mismatched types".to_string(),
+        };
+
+        inventory.update(cx, |inventory, cx| {
+            inventory.task_started(test_id.clone(), "test".to_string());
+            inventory.task_started(build_id.clone(), "build".to_string());
+            assert_eq!(
+                inventory.task_run_state(&test_id),
+                Some(TaskRunState::Running)
+            );
+
+            inventory.task_finished(&test_id, true, Vec::new());
+            inventory.task_finished(&build_id, false, vec![problem.clone()]);
+            assert_eq!(
+                inventory.task_run_state(&test_id),
+                Some(TaskRunState::Succeeded)
+            );
+            assert_eq!(
+                inventory.task_run_state(&build_id),
+                Some(TaskRunState::Failed)
+            );
+            assert_eq!(
+                inventory.task_problems(cx).collect::<Vec<_>>(),
+                vec![("build", problem.clone())]
+            );
+
+            // Running a task again replaces its previous run, and moves it to the top.
+            inventory.task_started(test_id.clone(), "test".to_string());
+            assert_eq!(
+                inventory
+                    .task_runs()
+                    .map(|run| (run.label.as_str(), run.state))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("test", TaskRunState::Running),
+                    ("build", TaskRunState::Failed)
+                ]
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_anchored_task_problems(cx: &mut TestAppContext) {
+        let inventory = cx.update(Inventory::new);
+        let build_id = TaskId("build".to_string());
+        let path = PathBuf::from("/project/src/main.rs");
+        let problem = TaskProblem {
+            path: path.clone(),
+            row: 1,
+            column: 4,
+            message: "mismatched types".to_string(),
+        };
+        let buffer = cx.new_model(|cx| Buffer::local("fn main() {\n    1u8 + 1u16\n}\n", cx));
+
+        inventory.update(cx, |inventory, cx| {
+            inventory.task_started(build_id.clone(), "build".to_string());
+            inventory.task_finished(&build_id, false, vec![problem.clone()]);
+            inventory.anchor_task_problems(
+                &build_id,
+                &HashMap::from_iter([(path.clone(), buffer.clone())]),
+                cx,
+            );
+        });
+
+        // The problem follows the edits made after the task finished.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "use std::fmt;\n\n")], None, cx)
+        });
+        inventory.update(cx, |inventory, cx| {
+            assert_eq!(
+                inventory.task_problems(cx).collect::<Vec<_>>(),
+                vec![(
+                    "build",
+                    TaskProblem {
+                        row: 3,
+                        ..problem.clone()
+                    }
+                )]
+            );
+        });
+    }
+
     pub(super) async fn resolved_task_names(
         inventory: &Model<Inventory>,
         worktree: Option<WorktreeId>,
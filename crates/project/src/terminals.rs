@@ -1,4 +1,4 @@
-use crate::Project;
+use crate::{Project, ProjectPath};
 use anyhow::Context as _;
 use collections::HashMap;
 use gpui::{AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, WeakModel};
@@ -10,10 +10,10 @@ use std::{
     iter,
     path::{Path, PathBuf},
 };
-use task::{Shell, SpawnInTerminal};
+use task::{Shell, SpawnInTerminal, TaskId};
 use terminal::{
     terminal_settings::{self, TerminalSettings},
-    TaskState, TaskStatus, Terminal, TerminalBuilder,
+    Event as TerminalEvent, TaskState, TaskStatus, Terminal, TerminalBuilder,
 };
 use util::ResultExt;
 
@@ -147,7 +147,12 @@ impl Project {
                         env = HashMap::default();
                         (None, Shell::WithArguments { program, args })
                     }
-                    None => (None, settings.shell.clone()),
+                    None => {
+                        if settings.shell_integration {
+                            terminal::shell_integration::inject(&settings.shell, &mut env);
+                        }
+                        (None, settings.shell.clone())
+                    }
                 }
             }
             TerminalKind::Task(spawn_task) => {
@@ -159,6 +164,9 @@ impl Project {
                     hide: spawn_task.hide,
                     status: TaskStatus::Running,
                     completion_rx,
+                    problem_matcher: spawn_task.problem_matcher.clone(),
+                    cwd: path.clone(),
+                    problems: Vec::new(),
                 });
 
                 env.extend(spawn_task.env);
@@ -235,6 +243,33 @@ impl Project {
             })
             .detach();
 
+            if let Some(task) = terminal_handle.read(cx).task() {
+                let (id, label) = (task.id.clone(), task.label.clone());
+                self.task_inventory().update(cx, |inventory, cx| {
+                    inventory.task_started(id, label);
+                    cx.notify();
+                });
+            }
+            cx.subscribe(&terminal_handle, |project, terminal, event, cx| {
+                if let TerminalEvent::TaskFinished = event {
+                    if let Some(task) = terminal.read(cx).task() {
+                        let success = task.status == TaskStatus::Completed { success: true };
+                        let (id, problems) = (task.id.clone(), task.problems.clone());
+                        let problem_paths = problems
+                            .iter()
+                            .map(|problem| problem.path.clone())
+                            .unique()
+                            .collect::<Vec<_>>();
+                        project.task_inventory().update(cx, |inventory, cx| {
+                            inventory.task_finished(&id, success, problems);
+                            cx.notify();
+                        });
+                        project.anchor_task_problems(id, problem_paths, cx);
+                    }
+                }
+            })
+            .detach();
+
             if let Some(activate_command) = python_venv_activate_command {
                 self.activate_python_virtual_environment(activate_command, &terminal_handle, cx);
             }
@@ -244,6 +279,46 @@ impl Project {
         terminal
     }
 
+    /// Opens the buffers of the files that the problems of the finished task are in, for
+    /// the files in the project's worktrees, to anchor the problems in them.
+    fn anchor_task_problems(
+        &mut self,
+        id: TaskId,
+        problem_paths: Vec<PathBuf>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let open_buffers = problem_paths
+            .into_iter()
+            .filter_map(|abs_path| {
+                let (worktree, relative_path) = self.find_worktree(&abs_path, cx)?;
+                let project_path = ProjectPath {
+                    worktree_id: worktree.read(cx).id(),
+                    path: relative_path.into(),
+                };
+                let open_buffer = self.open_buffer(project_path, cx);
+                Some(async move { (abs_path, open_buffer.await) })
+            })
+            .collect::<Vec<_>>();
+        if open_buffers.is_empty() {
+            return;
+        }
+
+        cx.spawn(|project, mut cx| async move {
+            let buffers = futures::future::join_all(open_buffers)
+                .await
+                .into_iter()
+                .filter_map(|(abs_path, buffer)| Some((abs_path, buffer.log_err()?)))
+                .collect::<HashMap<_, _>>();
+            project.update(&mut cx, |project, cx| {
+                project.task_inventory().update(cx, |inventory, cx| {
+                    inventory.anchor_task_problems(&id, &buffers, cx);
+                    cx.notify();
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn python_venv_directory(
         &self,
         abs_path: &Path,
@@ -0,0 +1,170 @@
+use crate::{File, Project};
+use anyhow::{anyhow, Context as _, Result};
+use gpui::{AppContext, Model, ModelContext, Task};
+use language::{point_to_lsp, range_from_lsp, Buffer, PointUtf16, Unclipped};
+use lsp::{LanguageServer, LanguageServerId};
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+/// A type in a type hierarchy, as returned by the language server that the hierarchy was
+/// requested from.
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    pub server_id: LanguageServerId,
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
+impl TypeHierarchyItem {
+    pub fn name(&self) -> &str {
+        &self.lsp_item.name
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.lsp_item.detail.as_deref()
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        self.lsp_item.uri.to_file_path().ok()
+    }
+
+    /// The range of the type's name, to jump to when opening the item.
+    pub fn selection_range(&self) -> Range<Unclipped<PointUtf16>> {
+        range_from_lsp(self.lsp_item.selection_range)
+    }
+}
+
+impl Project {
+    /// Whether a type hierarchy can be shown for the buffer, which takes a local project and
+    /// a language server that supports type hierarchies.
+    pub fn supports_type_hierarchy(&self, buffer: &Buffer, cx: &AppContext) -> bool {
+        self.is_local() && self.type_hierarchy_server(buffer, cx).is_some()
+    }
+
+    fn type_hierarchy_server(
+        &self,
+        buffer: &Buffer,
+        cx: &AppContext,
+    ) -> Option<Arc<LanguageServer>> {
+        self.language_servers_for_buffer(buffer, cx)
+            .find(|(_, server)| {
+                !matches!(
+                    server.capabilities().type_hierarchy_provider,
+                    None | Some(lsp::OneOf::Left(false))
+                )
+            })
+            .map(|(_, server)| server.clone())
+    }
+
+    /// Resolves the type at the given position into the items of a type hierarchy, with the
+    /// first of the buffer's language servers that supports type hierarchies.
+    pub fn prepare_type_hierarchy(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        position: PointUtf16,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "type hierarchies are only available in local projects"
+            )));
+        }
+        let buffer = buffer_handle.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let Some(server) = self.type_hierarchy_server(buffer, cx) else {
+            return Task::ready(Err(anyhow!(
+                "no language server supports type hierarchies for this file"
+            )));
+        };
+        let uri = match lsp::Url::from_file_path(file.abs_path(cx)) {
+            Ok(uri) => uri,
+            Err(()) => return Task::ready(Err(anyhow!("invalid file path"))),
+        };
+
+        cx.spawn(move |_, _| async move {
+            let items = server
+                .request::<lsp::request::TypeHierarchyPrepare>(lsp::TypeHierarchyPrepareParams {
+                    text_document_position_params: lsp::TextDocumentPositionParams::new(
+                        lsp::TextDocumentIdentifier::new(uri),
+                        point_to_lsp(position),
+                    ),
+                    work_done_progress_params: Default::default(),
+                })
+                .await
+                .context("preparing a type hierarchy")?;
+            Ok(type_hierarchy_items(&server, items))
+        })
+    }
+
+    /// Requests the types that the given type extends or implements.
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "type hierarchies are only available in local projects"
+            )));
+        }
+        let Some(server) = self.language_server_for_id(item.server_id) else {
+            return Task::ready(Err(anyhow!("the language server is no longer running")));
+        };
+        let lsp_item = item.lsp_item.clone();
+        cx.spawn(move |_, _| async move {
+            let items = server
+                .request::<lsp::request::TypeHierarchySupertypes>(
+                    lsp::TypeHierarchySupertypesParams {
+                        item: lsp_item,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                )
+                .await
+                .context("requesting supertypes")?;
+            Ok(type_hierarchy_items(&server, items))
+        })
+    }
+
+    /// Requests the types that extend or implement the given type.
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "type hierarchies are only available in local projects"
+            )));
+        }
+        let Some(server) = self.language_server_for_id(item.server_id) else {
+            return Task::ready(Err(anyhow!("the language server is no longer running")));
+        };
+        let lsp_item = item.lsp_item.clone();
+        cx.spawn(move |_, _| async move {
+            let items = server
+                .request::<lsp::request::TypeHierarchySubtypes>(lsp::TypeHierarchySubtypesParams {
+                    item: lsp_item,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await
+                .context("requesting subtypes")?;
+            Ok(type_hierarchy_items(&server, items))
+        })
+    }
+}
+
+fn type_hierarchy_items(
+    server: &Arc<LanguageServer>,
+    items: Option<Vec<lsp::TypeHierarchyItem>>,
+) -> Vec<TypeHierarchyItem> {
+    items
+        .unwrap_or_default()
+        .into_iter()
+        .map(|lsp_item| TypeHierarchyItem {
+            server_id: server.server_id(),
+            lsp_item,
+        })
+        .collect()
+}
@@ -145,6 +145,7 @@ actions!(
         OpenPermanent,
         ToggleFocus,
         NewSearchInDirectory,
+        NewSearchInSelection,
         UnfoldDirectory,
         FoldDirectory,
         SelectParent,
@@ -469,6 +470,7 @@ impl ProjectPanel {
                         menu.when(is_dir, |menu| {
                             menu.action("Search Inside", Box::new(NewSearchInDirectory))
                         })
+                        .action("Search in Selection", Box::new(NewSearchInSelection))
                     } else {
                         menu.action("New File", Box::new(NewFile))
                             .action("New Folder", Box::new(NewDirectory))
@@ -480,10 +482,11 @@ impl ProjectPanel {
                                 menu.action("Reveal in File Manager", Box::new(RevealInFileManager))
                             })
                             .action("Open in Terminal", Box::new(OpenInTerminal))
+                            .separator()
                             .when(is_dir, |menu| {
-                                menu.separator()
-                                    .action("Find in Folder…", Box::new(NewSearchInDirectory))
+                                menu.action("Find in Folder…", Box::new(NewSearchInDirectory))
                             })
+                            .action("Find in Selection…", Box::new(NewSearchInSelection))
                             .when(is_unfoldable, |menu| {
                                 menu.action("Unfold Directory", Box::new(UnfoldDirectory))
                             })
@@ -1425,6 +1428,26 @@ impl ProjectPanel {
         }
     }
 
+    /// Opens a project search that is limited to the selected files and directories.
+    pub fn new_search_in_selection(
+        &mut self,
+        _: &NewSearchInSelection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let paths = {
+            let project = self.project.read(cx);
+            self.marked_entries()
+                .into_iter()
+                .filter_map(|entry| project.path_for_entry(entry.entry_id, cx))
+                .collect::<Vec<_>>()
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                search::ProjectSearchView::new_search_in_paths(workspace, paths, cx);
+            })
+            .ok();
+    }
+
     pub fn new_search_in_directory(
         &mut self,
         _: &NewSearchInDirectory,
@@ -2456,6 +2479,7 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::copy_path))
                 .on_action(cx.listener(Self::copy_relative_path))
                 .on_action(cx.listener(Self::new_search_in_directory))
+                .on_action(cx.listener(Self::new_search_in_selection))
                 .on_action(cx.listener(Self::unfold_directory))
                 .on_action(cx.listener(Self::fold_directory))
                 .when(!project.is_read_only(), |el| {
@@ -1665,6 +1665,7 @@ pub async fn spawn_ssh_task(
                     hide: HideStrategy::Never,
                     env: Default::default(),
                     shell: Default::default(),
+                    problem_matcher: None,
                 },
                 cx,
             )
@@ -1,9 +1,10 @@
 mod registrar;
 
 use crate::{
-    search_bar::render_nav_button, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
-    ReplaceNext, SearchOptions, SelectAllMatches, SelectNextMatch, SelectPrevMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
+    search_bar::render_nav_button, AddNextMatch, FocusSearch, NextHistoryQuery,
+    PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions, SelectAllMatches,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleRegex, ToggleReplace,
+    ToggleSelection, ToggleWholeWord,
 };
 use any_vec::AnyVec;
 use collections::HashMap;
@@ -174,13 +175,13 @@ impl Render for BufferSearchBar {
                     .map(AnyVec::len)
                     .unwrap_or(0);
                 if let Some(match_ix) = self.active_match_index {
-                    Some(format!("{}/{}", match_ix + 1, matches_count))
+                    Some(format!("{} of {}", match_ix + 1, matches_count))
                 } else {
                     text_color = Color::Error; // No matches found
                     None
                 }
             })
-            .unwrap_or_else(|| "0/0".to_string());
+            .unwrap_or_else(|| "0 of 0".to_string());
         let should_show_replace_input = self.replace_enabled && supported_options.replacement;
         let in_replace = self.replacement_editor.focus_handle(cx).is_focused(cx);
 
@@ -268,7 +269,11 @@ impl Render for BufferSearchBar {
                     .selected(self.selection_search_enabled)
                     .size(ButtonSize::Compact)
                     .tooltip(|cx| {
-                        Tooltip::for_action("Toggle search selection", &ToggleSelection, cx)
+                        Tooltip::for_action(
+                            "Search and replace within selection",
+                            &ToggleSelection,
+                            cx,
+                        )
                     }),
                 )
             })
@@ -283,6 +288,19 @@ impl Render for BufferSearchBar {
                                 Tooltip::for_action("Select all matches", &SelectAllMatches, cx)
                             }),
                     )
+                    .child(
+                        IconButton::new("add-next-match", ui::IconName::Plus)
+                            .on_click(|_, cx| cx.dispatch_action(AddNextMatch.boxed_clone()))
+                            .size(ButtonSize::Compact)
+                            .disabled(self.active_match_index.is_none())
+                            .tooltip(|cx| {
+                                Tooltip::for_action(
+                                    "Add next match to selections",
+                                    &AddNextMatch,
+                                    cx,
+                                )
+                            }),
+                    )
                     .child(render_nav_button(
                         ui::IconName::ChevronLeft,
                         self.active_match_index.is_some(),
@@ -364,6 +382,7 @@ impl Render for BufferSearchBar {
             .on_action(cx.listener(Self::dismiss))
             .on_action(cx.listener(Self::select_next_match))
             .on_action(cx.listener(Self::select_prev_match))
+            .on_action(cx.listener(Self::add_next_match))
             .when(self.supported_options().replacement, |this| {
                 this.on_action(cx.listener(Self::toggle_replace))
                     .when(in_replace, |this| {
@@ -483,6 +502,9 @@ impl BufferSearchBar {
         registrar.register_handler(WithResults(|this, action: &SelectAllMatches, cx| {
             this.select_all_matches(action, cx);
         }));
+        registrar.register_handler(WithResults(|this, action: &AddNextMatch, cx| {
+            this.add_next_match(action, cx);
+        }));
         registrar.register_handler(ForDeployed(|this, _: &editor::actions::Cancel, cx| {
             this.dismiss(&Dismiss, cx);
         }));
@@ -766,6 +788,34 @@ impl BufferSearchBar {
         }
     }
 
+    /// Adds a selection for the match after the active one, keeping the existing selections,
+    /// so that matches can be turned into multiple cursors one at a time.
+    fn add_next_match(&mut self, _: &AddNextMatch, cx: &mut ViewContext<Self>) {
+        if self.dismissed {
+            return;
+        }
+        let Some(index) = self.active_match_index else {
+            return;
+        };
+        if let Some(searchable_item) = self.active_searchable_item.as_ref() {
+            if let Some(matches) = self
+                .searchable_items_with_matches
+                .get(&searchable_item.downgrade())
+                .filter(|matches| !matches.is_empty())
+            {
+                let new_match_index = searchable_item.match_index_for_direction(
+                    matches,
+                    index,
+                    Direction::Next,
+                    1,
+                    cx,
+                );
+                searchable_item.update_matches(matches, cx);
+                searchable_item.add_match_to_selections(new_match_index, matches, cx);
+            }
+        }
+    }
+
     pub fn select_match(&mut self, direction: Direction, count: usize, cx: &mut ViewContext<Self>) {
         if let Some(index) = self.active_match_index {
             if let Some(searchable_item) = self.active_searchable_item.as_ref() {
@@ -876,6 +926,11 @@ impl BufferSearchBar {
         if let Some(active_item) = self.active_searchable_item.as_mut() {
             self.selection_search_enabled = !self.selection_search_enabled;
             active_item.toggle_filtered_search_ranges(self.selection_search_enabled, cx);
+            // Without a selection to search within, the toggle stays off rather than
+            // pretending to limit the search and replacements.
+            if self.selection_search_enabled && !active_item.has_filtered_search_ranges(cx) {
+                self.selection_search_enabled = false;
+            }
             drop(self.update_matches(cx));
             cx.notify();
         }
@@ -1194,6 +1249,41 @@ mod tests {
         (editor, search_bar, cx)
     }
 
+    #[gpui::test]
+    async fn test_add_next_match(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("regex", None, cx))
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.activate_current_match(cx);
+            assert_eq!(search_bar.active_match_index, Some(0));
+            search_bar.add_next_match(&AddNextMatch, cx);
+        });
+        cx.run_until_parked();
+
+        let selections = editor.update(cx, |editor, cx| editor.selections.display_ranges(cx));
+        assert_eq!(
+            selections,
+            [
+                DisplayPoint::new(DisplayRow(0), 35)..DisplayPoint::new(DisplayRow(0), 40),
+                DisplayPoint::new(DisplayRow(0), 44)..DisplayPoint::new(DisplayRow(0), 49),
+            ]
+        );
+        search_bar.update(cx, |search_bar, _| {
+            assert_eq!(search_bar.active_match_index, Some(1));
+        });
+
+        // Wrapping around to a match that's already selected doesn't add a selection.
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.add_next_match(&AddNextMatch, cx);
+        });
+        cx.run_until_parked();
+        let selections = editor.update(cx, |editor, cx| editor.selections.display_ranges(cx));
+        assert_eq!(selections.len(), 2);
+    }
+
     #[gpui::test]
     async fn test_search_simple(cx: &mut TestAppContext) {
         let (editor, search_bar, cx) = init_test(cx);
@@ -2238,6 +2328,83 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_replace_within_selection(cx: &mut TestAppContext) {
+        init_globals(cx);
+        let buffer = cx.new_model(|cx| {
+            Buffer::local(
+                r#"
+                aaa bbb aaa ccc
+                aaa bbb aaa ccc
+                aaa bbb aaa ccc
+                "#
+                .unindent(),
+                cx,
+            )
+        });
+        let cx = cx.add_empty_window();
+        let editor = cx.new_view(|cx| Editor::for_buffer(buffer.clone(), None, cx));
+        let search_bar = cx.new_view(|cx| {
+            let mut search_bar = BufferSearchBar::new(cx);
+            search_bar.set_active_pane_item(Some(&editor), cx);
+            search_bar.show(cx);
+            search_bar
+        });
+
+        // Without a selection, there's nothing to search within.
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.toggle_selection(&ToggleSelection, cx);
+            assert!(!search_bar.selection_search_enabled);
+        });
+
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges(vec![Point::new(1, 0)..Point::new(2, 3)])
+            })
+        });
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.toggle_selection(&ToggleSelection, cx);
+            assert!(search_bar.selection_search_enabled);
+        });
+        cx.run_until_parked();
+
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("aaa", None, cx))
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.replacement_editor.update(cx, |editor, cx| {
+                editor.set_text("zzzz", cx);
+            });
+            search_bar.replace_all(&ReplaceAll, cx)
+        });
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            r#"
+            aaa bbb aaa ccc
+            zzzz bbb zzzz ccc
+            zzzz bbb aaa ccc
+            "#
+            .unindent()
+        );
+
+        // Replacements at the edges of the selection are still within it.
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("zzzz", None, cx))
+            .await
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.search_background_highlights(cx),
+                &[
+                    Point::new(1, 0)..Point::new(1, 4),
+                    Point::new(1, 9)..Point::new(1, 13),
+                    Point::new(2, 0)..Point::new(2, 4),
+                ]
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_invalid_regexp_search_after_valid(cx: &mut TestAppContext) {
         let (editor, search_bar, cx) = init_test(cx);
@@ -1,9 +1,13 @@
 use crate::{
-    FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
-    ToggleReplace, ToggleWholeWord,
+    results_tree::{tree_entries, CollapsedTreeEntry, TreeEntry, TreeMatch},
+    saved_searches::SavedSearch,
+    FocusSearch, NextHistoryQuery, PreviewReplacements, PreviousHistoryQuery, ReplaceAll,
+    ReplaceInFile, ReplaceNext, SearchOptions, SelectNextMatch, SelectPrevMatch,
+    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleStructural,
+    ToggleWholeWord,
 };
 use collections::{HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
 use editor::{
     actions::SelectAll,
     items::active_match_index,
@@ -12,13 +16,17 @@ use editor::{
     MAX_TAB_TITLE_LEN,
 };
 use gpui::{
-    actions, div, Action, AnyElement, AnyView, AppContext, Context as _, EntityId, EventEmitter,
-    FocusHandle, FocusableView, Global, Hsla, InteractiveElement, IntoElement, KeyContext, Model,
-    ModelContext, ParentElement, Point, Render, SharedString, Styled, Subscription, Task,
-    TextStyle, UpdateGlobal, View, ViewContext, VisualContext, WeakModel, WindowContext,
+    actions, div, uniform_list, Action, AnyElement, AnyView, AppContext, ClickEvent, Context as _,
+    EntityId, EventEmitter, FocusHandle, FocusableView, Global, Hsla, InteractiveElement,
+    IntoElement, KeyContext, Model, ModelContext, ParentElement, Point, Render, SharedString,
+    Styled, Subscription, Task, TextStyle, UpdateGlobal, View, ViewContext, VisualContext,
+    WeakModel, WindowContext,
 };
+use language::{BufferId, BufferSnapshot};
 use menu::Confirm;
-use project::{search::SearchQuery, search_history::SearchHistoryCursor, Project, ProjectPath};
+use project::{
+    search::SearchQuery, search_history::SearchHistoryCursor, Item as _, Project, ProjectPath,
+};
 use settings::Settings;
 use smol::stream::StreamExt;
 use std::{
@@ -26,13 +34,15 @@ use std::{
     mem,
     ops::{Not, Range},
     path::Path,
+    sync::Arc,
 };
 use theme::ThemeSettings;
 use ui::{
-    h_flex, prelude::*, v_flex, Icon, IconButton, IconName, KeyBinding, Label, LabelCommon,
-    LabelSize, Selectable, Tooltip,
+    h_flex, prelude::*, v_flex, ContextMenu, DropdownMenu, HighlightedLabel, Icon, IconButton,
+    IconName, IconPosition, KeyBinding, Label, LabelCommon, LabelSize, ListItem, ListItemSpacing,
+    Selectable, Tooltip,
 };
-use util::paths::PathMatcher;
+use util::{paths::PathMatcher, ResultExt};
 use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
     searchable::{Direction, SearchableItem, SearchableItemHandle},
@@ -41,13 +51,46 @@ use workspace::{
 };
 
 const MIN_INPUT_WIDTH_REMS: f32 = 15.;
+const SEARCH_HISTORY_KEY: &str = "project_search_history";
+/// How many characters of a match's line the results tree shows.
+const MAX_TREE_LINE_LEN: usize = 200;
 const MAX_INPUT_WIDTH_REMS: f32 = 30.;
 
 actions!(
     project_search,
-    [SearchInNew, ToggleFocus, NextField, ToggleFilters]
+    [
+        SearchInNew,
+        ToggleFocus,
+        NextField,
+        ToggleFilters,
+        ToggleResultsTree
+    ]
 );
 
+/// The files that a project search is limited to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum SearchScope {
+    #[default]
+    Project,
+    OpenEditors,
+    /// The files with unsaved changes or uncommitted changes.
+    ModifiedFiles,
+    /// The given files and the files within the given directories, such as the entries
+    /// selected in the project panel.
+    Paths(Arc<[ProjectPath]>),
+}
+
+impl SearchScope {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Project => "Project",
+            Self::OpenEditors => "Open Editors",
+            Self::ModifiedFiles => "Modified Files",
+            Self::Paths(_) => "Selected Files",
+        }
+    }
+}
+
 #[derive(Default)]
 struct ActiveSettings(HashMap<WeakModel<Project>, ProjectSearchSettings>);
 
@@ -62,6 +105,9 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleFilters, cx| {
             search_bar.toggle_filters(cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleResultsTree, cx| {
+            search_bar.toggle_results_tree(cx);
+        });
         register_workspace_action(workspace, move |search_bar, _: &ToggleCaseSensitive, cx| {
             search_bar.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
         });
@@ -71,6 +117,9 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStructural, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+        });
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
@@ -91,6 +140,9 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
             ProjectSearchView::search_in_new(workspace, action, cx)
         });
+        register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
+            ProjectSearchView::preview_replacements(workspace, action, cx)
+        });
 
         // Both on present and dismissed search, we need to unconditionally handle those actions to focus from the editor.
         workspace.register_action(move |workspace, action: &DeploySearch, cx| {
@@ -147,6 +199,17 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    /// Whether the results are shown as a tree grouped by directory and file, rather than
+    /// as excerpts.
+    results_tree_enabled: bool,
+    collapsed_tree_entries: HashSet<CollapsedTreeEntry>,
+    /// The entries of the results tree, computed when the tree is shown after the matches
+    /// or the collapsed entries changed.
+    tree_entries: Option<Arc<[TreeEntry]>>,
+    scope: SearchScope,
+    /// The paths of the files open in the workspace's editors, for [`SearchScope::OpenEditors`].
+    open_editor_paths: Vec<ProjectPath>,
+    _workspace_subscription: Option<Subscription>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -165,6 +228,18 @@ impl ProjectSearch {
     fn new(project: Model<Project>, cx: &mut ModelContext<Self>) -> Self {
         let replica_id = project.read(cx).replica_id();
         let capability = project.read(cx).capability();
+        if project.read(cx).search_history().is_empty() {
+            let persisted_history = KEY_VALUE_STORE
+                .read_kvp(SEARCH_HISTORY_KEY)
+                .log_err()
+                .flatten()
+                .and_then(|history| serde_json::from_str::<Vec<String>>(&history).log_err());
+            if let Some(persisted_history) = persisted_history {
+                project.update(cx, |project, _| {
+                    project.search_history_mut().restore(persisted_history)
+                });
+            }
+        }
 
         Self {
             project,
@@ -204,6 +279,13 @@ impl ProjectSearch {
                 .add(&mut self.search_history_cursor, query.as_str().to_string());
             project.search(query.clone(), cx)
         });
+        if let Some(history) =
+            serde_json::to_string(self.project.read(cx).search_history().queries()).log_err()
+        {
+            db::write_and_log(cx, move || {
+                KEY_VALUE_STORE.write_kvp(SEARCH_HISTORY_KEY.to_string(), history)
+            });
+        }
         self.last_search_query_text = Some(query.as_str().to_string());
         self.search_id += 1;
         self.active_query = Some(query);
@@ -262,6 +344,81 @@ impl ProjectSearch {
         }));
         cx.notify();
     }
+
+    fn exclude_buffer(&mut self, buffer_id: BufferId, cx: &mut ModelContext<Self>) {
+        let Some(buffer) = self.excerpts.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        self.excerpts.update(cx, |excerpts, cx| {
+            let excerpt_ids = excerpts
+                .excerpts_for_buffer(&buffer, cx)
+                .into_iter()
+                .map(|(excerpt_id, _)| excerpt_id);
+            excerpts.remove_excerpts(excerpt_ids.collect::<Vec<_>>(), cx);
+        });
+        self.match_ranges
+            .retain(|range| range.start.buffer_id != Some(buffer_id));
+        self.no_results = Some(self.match_ranges.is_empty());
+        cx.notify();
+    }
+
+    /// Removes a match from the results, so that replacing all matches leaves it alone.
+    fn dismiss_match(&mut self, match_ix: usize, cx: &mut ModelContext<Self>) {
+        if match_ix < self.match_ranges.len() {
+            self.match_ranges.remove(match_ix);
+            self.no_results = Some(self.match_ranges.is_empty());
+            cx.notify();
+        }
+    }
+
+    /// Searches the buffer again with the active query, replacing its excerpts in place.
+    fn refresh_buffer(&mut self, buffer_id: BufferId, cx: &mut ModelContext<Self>) {
+        let Some(query) = self.active_query.clone() else {
+            return;
+        };
+        let Some(buffer) = self.excerpts.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let search_id = self.search_id;
+        cx.spawn(|this, mut cx| async move {
+            let ranges = query.search(&snapshot, None).await;
+            this.update(&mut cx, |this, cx| {
+                // The results were replaced or the buffer excluded in the meantime.
+                if this.search_id != search_id || this.excerpts.read(cx).buffer(buffer_id).is_none()
+                {
+                    return;
+                }
+
+                let ranges = ranges
+                    .into_iter()
+                    .map(|range| {
+                        snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end)
+                    })
+                    .collect::<Vec<_>>();
+                let buffer_match_ranges = this.excerpts.update(cx, |excerpts, cx| {
+                    excerpts.replace_excerpts_with_context_lines(
+                        buffer,
+                        ranges,
+                        editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                        cx,
+                    )
+                });
+                let ix = this
+                    .match_ranges
+                    .iter()
+                    .position(|range| range.start.buffer_id == Some(buffer_id))
+                    .unwrap_or(this.match_ranges.len());
+                this.match_ranges
+                    .retain(|range| range.start.buffer_id != Some(buffer_id));
+                this.match_ranges.splice(ix..ix, buffer_match_ranges);
+                this.no_results = Some(this.match_ranges.is_empty());
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -281,7 +438,14 @@ impl Render for ProjectSearchView {
                 .flex_1()
                 .size_full()
                 .track_focus(&self.focus_handle)
-                .child(self.results_editor.clone())
+                .map(|this| {
+                    if self.results_tree_enabled {
+                        let tree_entries = self.tree_entries(cx);
+                        this.child(self.render_results_tree(tree_entries, cx))
+                    } else {
+                        this.child(self.results_editor.clone())
+                    }
+                })
         } else {
             let model = self.model.read(cx);
             let has_no_results = model.no_results.unwrap_or(false);
@@ -462,6 +626,17 @@ impl Item for ProjectSearchView {
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
         self.results_editor
             .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+        self.open_editor_paths = open_editor_paths(workspace, cx);
+        self._workspace_subscription = workspace.weak_handle().upgrade().map(|workspace| {
+            cx.subscribe(&workspace, |this, workspace, event, cx| {
+                if matches!(
+                    event,
+                    workspace::Event::ItemAdded | workspace::Event::ActiveItemChanged
+                ) {
+                    this.open_editor_paths = open_editor_paths(workspace.read(cx), cx);
+                }
+            })
+        });
     }
 
     fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
@@ -522,6 +697,14 @@ impl ProjectSearchView {
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
         self.search_options.toggle(option);
+        // Regular expressions and structural patterns are different query syntaxes.
+        if self.search_options.contains(option) {
+            if option == SearchOptions::REGEX {
+                self.search_options.remove(SearchOptions::STRUCTURAL);
+            } else if option == SearchOptions::STRUCTURAL {
+                self.search_options.remove(SearchOptions::REGEX);
+            }
+        }
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.model.read(cx).project.downgrade(),
@@ -579,6 +762,35 @@ impl ProjectSearchView {
         });
     }
 
+    /// Replaces the matches in the file of the active match, so that replacements can be
+    /// reviewed and applied one file at a time.
+    fn replace_in_file(&mut self, _: &ReplaceInFile, cx: &mut ViewContext<Self>) {
+        let Some(active_index) = self.active_match_index else {
+            return;
+        };
+        let Some(query) = self.model.read(cx).active_query.as_ref() else {
+            return;
+        };
+        let query = query.clone().with_replacement(self.replacement(cx));
+
+        let match_ranges = &self.model.read(cx).match_ranges;
+        let Some(active_buffer_id) = match_ranges
+            .get(active_index)
+            .map(|range| range.start.buffer_id)
+        else {
+            return;
+        };
+        let file_match_ranges = match_ranges
+            .iter()
+            .filter(|range| range.start.buffer_id == active_buffer_id)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.results_editor.update(cx, |editor, cx| {
+            editor.replace_all(&mut file_match_ranges.iter(), &query, cx);
+        });
+    }
+
     fn new(
         model: Model<ProjectSearch>,
         cx: &mut ViewContext<Self>,
@@ -632,14 +844,22 @@ impl ProjectSearchView {
         let results_editor = cx.new_view(|cx| {
             let mut editor = Editor::for_multibuffer(excerpts, Some(project.clone()), true, cx);
             editor.set_searchable(false);
+            editor.set_show_buffer_header_controls(true, cx);
             editor
         });
         subscriptions.push(cx.observe(&results_editor, |_, _, cx| cx.emit(ViewEvent::UpdateTab)));
 
         subscriptions.push(
             cx.subscribe(&results_editor, |this, _, event: &EditorEvent, cx| {
-                if matches!(event, editor::EditorEvent::SelectionsChanged { .. }) {
-                    this.update_match_index(cx);
+                match event {
+                    EditorEvent::SelectionsChanged { .. } => this.update_match_index(cx),
+                    EditorEvent::BufferExclusionRequested { buffer_id } => this
+                        .model
+                        .update(cx, |model, cx| model.exclude_buffer(*buffer_id, cx)),
+                    EditorEvent::BufferRefreshRequested { buffer_id } => this
+                        .model
+                        .update(cx, |model, cx| model.refresh_buffer(*buffer_id, cx)),
+                    _ => {}
                 }
                 // Reraise editor events for workspace item activation purposes
                 cx.emit(ViewEvent::EditorEvent(event.clone()));
@@ -698,6 +918,12 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            results_tree_enabled: false,
+            collapsed_tree_entries: HashSet::default(),
+            tree_entries: None,
+            scope: SearchScope::default(),
+            open_editor_paths: Vec::new(),
+            _workspace_subscription: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -725,6 +951,26 @@ impl ProjectSearchView {
         });
     }
 
+    /// Opens a new project search that is limited to the given files and directories.
+    pub fn new_search_in_paths(
+        workspace: &mut Workspace,
+        paths: Vec<ProjectPath>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        if paths.is_empty() {
+            return;
+        }
+
+        let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+        let search = cx.new_view(|cx| ProjectSearchView::new(model, cx, None));
+        workspace.add_item_to_active_pane(Box::new(search.clone()), None, true, cx);
+        search.update(cx, |search, cx| {
+            search.scope = SearchScope::Paths(paths.into());
+            search.filters_enabled = true;
+            search.focus_query_editor(cx)
+        });
+    }
+
     // Re-activate the most recently activated search in this pane or the most recent if it has been closed.
     // If no search exists in the workspace, create a new one.
     fn deploy_search(
@@ -774,6 +1020,75 @@ impl ProjectSearchView {
         }
     }
 
+    fn preview_replacements(
+        workspace: &mut Workspace,
+        _: &PreviewReplacements,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(search_view) = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<ProjectSearchView>())
+        else {
+            return;
+        };
+        let project = workspace.project().clone();
+        if project.read(cx).is_remote() {
+            return;
+        }
+        let Some(diff) = search_view.read(cx).replacements_diff(cx) else {
+            return;
+        };
+
+        let buffer = project.update(cx, |project, cx| {
+            project.create_local_buffer(&diff, None, cx)
+        });
+        let editor = cx.new_view(|cx| Editor::for_buffer(buffer, Some(project), cx));
+        workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+    }
+
+    /// Renders the edits that replacing every match would make as a unified diff.
+    fn replacements_diff(&self, cx: &AppContext) -> Option<String> {
+        let model = self.model.read(cx);
+        let query = model
+            .active_query
+            .clone()?
+            .with_replacement(self.replacement(cx));
+        let snapshot = model.excerpts.read(cx).snapshot(cx);
+
+        let mut diff = String::new();
+        let mut current_buffer: Option<&BufferSnapshot> = None;
+        let mut replacements = Vec::new();
+        for range in &model.match_ranges {
+            let Some(buffer) = snapshot.buffer_for_excerpt(range.start.excerpt_id) else {
+                continue;
+            };
+            let start = buffer.summary_for_anchor::<usize>(&range.start.text_anchor);
+            let end = buffer.summary_for_anchor::<usize>(&range.end.text_anchor);
+            let old_text = buffer.text_for_range(start..end).collect::<String>();
+            let Some(new_text) = query.replacement_for(&old_text) else {
+                continue;
+            };
+
+            if current_buffer.map(|buffer| buffer.remote_id()) != Some(buffer.remote_id()) {
+                if let Some(current_buffer) = current_buffer {
+                    push_replacement_hunks(&mut diff, current_buffer, &replacements);
+                    replacements.clear();
+                }
+                let path = buffer.file().map_or_else(
+                    || "untitled".to_string(),
+                    |file| file.full_path(cx).to_string_lossy().into_owned(),
+                );
+                diff.push_str(&format!("--- a/{path}\n+++ b/{path}\n"));
+                current_buffer = Some(buffer);
+            }
+            replacements.push((start..end, new_text.into_owned()));
+        }
+        if let Some(current_buffer) = current_buffer {
+            push_replacement_hunks(&mut diff, current_buffer, &replacements);
+        }
+        (!diff.is_empty()).then_some(diff)
+    }
+
     // Add another search tab to the workspace.
     fn new_search(
         workspace: &mut Workspace,
@@ -875,7 +1190,32 @@ impl ProjectSearchView {
                 }
             };
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::CASE_SENSITIVE),
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -934,7 +1274,30 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        match self.scope_paths(cx) {
+            Some(paths) => query.map(|query| query.with_scope(paths)),
+            None => query,
+        }
+    }
+
+    /// The paths that the search is limited to, or `None` if it searches the whole project.
+    fn scope_paths(&self, cx: &AppContext) -> Option<Vec<ProjectPath>> {
+        match &self.scope {
+            SearchScope::Project => None,
+            SearchScope::OpenEditors => Some(self.open_editor_paths.clone()),
+            SearchScope::ModifiedFiles => {
+                Some(modified_paths(self.model.read(cx).project.read(cx), cx))
+            }
+            SearchScope::Paths(paths) => Some(paths.to_vec()),
+        }
+    }
+
+    fn set_scope(&mut self, scope: SearchScope, cx: &mut ViewContext<Self>) {
+        if self.scope != scope {
+            self.scope = scope;
+            self.search(cx);
+            cx.notify();
+        }
     }
 
     fn parse_path_matches(text: &str) -> anyhow::Result<PathMatcher> {
@@ -983,11 +1346,300 @@ impl ProjectSearchView {
         cx.focus(&editor_handle);
     }
 
+    /// Captures the search's configuration, to be saved under a name.
+    pub(crate) fn saved_search(&self, cx: &AppContext) -> SavedSearch {
+        SavedSearch::new(
+            self.query_editor.read(cx).text(cx),
+            self.search_options,
+            self.included_files_editor.read(cx).text(cx),
+            self.excluded_files_editor.read(cx).text(cx),
+        )
+    }
+
+    /// Runs the saved search in the active project search, or in a new one.
+    pub(crate) fn open_saved_search(
+        workspace: &mut Workspace,
+        saved_search: &SavedSearch,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let search = match workspace.active_item_as::<ProjectSearchView>(cx) {
+            Some(search) => search,
+            None => {
+                let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+                let search = cx.new_view(|cx| ProjectSearchView::new(model, cx, None));
+                workspace.add_item_to_active_pane(Box::new(search.clone()), None, true, cx);
+                search
+            }
+        };
+        search.update(cx, |search, cx| {
+            search.set_query(&saved_search.query, cx);
+            search.search_options = saved_search.options();
+            search.included_files_editor.update(cx, |editor, cx| {
+                editor.set_text(saved_search.files_to_include.as_str(), cx)
+            });
+            search.excluded_files_editor.update(cx, |editor, cx| {
+                editor.set_text(saved_search.files_to_exclude.as_str(), cx)
+            });
+            search.filters_enabled = !saved_search.files_to_include.is_empty()
+                || !saved_search.files_to_exclude.is_empty();
+            search.search(cx);
+            cx.notify();
+        });
+    }
+
     fn set_query(&mut self, query: &str, cx: &mut ViewContext<Self>) {
         self.query_editor
             .update(cx, |query_editor, cx| query_editor.set_text(query, cx));
     }
 
+    fn toggle_results_tree(&mut self, cx: &mut ViewContext<Self>) {
+        self.results_tree_enabled = !self.results_tree_enabled;
+        cx.notify();
+    }
+
+    fn toggle_tree_entry(&mut self, entry: CollapsedTreeEntry, cx: &mut ViewContext<Self>) {
+        if !self.collapsed_tree_entries.remove(&entry) {
+            self.collapsed_tree_entries.insert(entry);
+        }
+        self.tree_entries = None;
+        cx.notify();
+    }
+
+    /// Shows the match in the excerpts of the results editor.
+    fn activate_tree_match(&mut self, match_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(range) = self.model.read(cx).match_ranges.get(match_ix).cloned() else {
+            return;
+        };
+        self.results_tree_enabled = false;
+        self.results_editor.update(cx, |editor, cx| {
+            let range = editor.range_for_match(&range);
+            editor.change_selections(Some(Autoscroll::center()), cx, |s| s.select_ranges([range]));
+        });
+        self.focus_results_editor(cx);
+        cx.notify();
+    }
+
+    fn tree_matches(&self, cx: &AppContext) -> Vec<TreeMatch> {
+        let model = self.model.read(cx);
+        let snapshot = model.excerpts.read(cx).snapshot(cx);
+        model
+            .match_ranges
+            .iter()
+            .enumerate()
+            .filter_map(|(match_ix, range)| {
+                let buffer = snapshot.buffer_for_excerpt(range.start.excerpt_id)?;
+                let start = buffer.summary_for_anchor::<language::Point>(&range.start.text_anchor);
+                let end = buffer.summary_for_anchor::<language::Point>(&range.end.text_anchor);
+                let line_len = buffer.line_len(start.row);
+                let line = buffer
+                    .text_for_range(
+                        language::Point::new(start.row, 0)
+                            ..language::Point::new(start.row, line_len),
+                    )
+                    .collect::<String>();
+                let match_columns = start.column as usize..if end.row == start.row {
+                    end.column as usize
+                } else {
+                    line_len as usize
+                };
+
+                let indent_len = line.len() - line.trim_start().len();
+                let mut displayed_line = String::new();
+                let mut highlights = Vec::new();
+                for (char_ix, (byte_ix, c)) in line[indent_len..]
+                    .char_indices()
+                    .take(MAX_TREE_LINE_LEN)
+                    .enumerate()
+                {
+                    if match_columns.contains(&(indent_len + byte_ix)) {
+                        highlights.push(char_ix);
+                    }
+                    displayed_line.push(c);
+                }
+
+                let path: Arc<Path> = buffer.file().map_or_else(
+                    || Path::new("untitled").into(),
+                    |file| file.full_path(cx).into(),
+                );
+                Some(TreeMatch {
+                    match_ix,
+                    buffer_id: buffer.remote_id(),
+                    path,
+                    row: start.row,
+                    line: displayed_line.into(),
+                    highlights,
+                })
+            })
+            .collect()
+    }
+
+    fn tree_entries(&mut self, cx: &AppContext) -> Arc<[TreeEntry]> {
+        if let Some(tree_entries) = &self.tree_entries {
+            return tree_entries.clone();
+        }
+        let entries: Arc<[TreeEntry]> =
+            tree_entries(self.tree_matches(cx), &self.collapsed_tree_entries).into();
+        self.tree_entries = Some(entries.clone());
+        entries
+    }
+
+    fn render_results_tree(
+        &self,
+        tree_entries: Arc<[TreeEntry]>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        uniform_list(
+            cx.view().clone(),
+            "project-search-results-tree",
+            tree_entries.len(),
+            move |this, range, cx| {
+                tree_entries[range.clone()]
+                    .iter()
+                    .zip(range)
+                    .map(|(entry, ix)| this.render_tree_entry(ix, entry.clone(), cx))
+                    .collect()
+            },
+        )
+        .size_full()
+        .py_1()
+        .bg(cx.theme().colors().editor_background)
+    }
+
+    fn render_tree_entry(
+        &self,
+        ix: usize,
+        entry: TreeEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> ListItem {
+        match entry {
+            TreeEntry::Directory {
+                path,
+                buffer_ids,
+                match_count,
+                collapsed,
+            } => {
+                let collapsed_entry = CollapsedTreeEntry::Directory(path.clone());
+                ListItem::new(ix)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle(!collapsed)
+                    .on_toggle(cx.listener({
+                        let collapsed_entry = collapsed_entry.clone();
+                        move |this, _: &ClickEvent, cx| {
+                            this.toggle_tree_entry(collapsed_entry.clone(), cx)
+                        }
+                    }))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.toggle_tree_entry(collapsed_entry.clone(), cx)
+                    }))
+                    .start_slot(
+                        Icon::new(if collapsed {
+                            IconName::Folder
+                        } else {
+                            IconName::FolderOpen
+                        })
+                        .color(Color::Muted),
+                    )
+                    .child(Self::render_tree_label(
+                        path.to_string_lossy().to_string(),
+                        match_count,
+                    ))
+                    .end_hover_slot(Self::render_dismiss_button(
+                        ix,
+                        "Dismiss matches in this directory",
+                        cx.listener(move |this, _, cx| {
+                            for buffer_id in &buffer_ids {
+                                this.model
+                                    .update(cx, |model, cx| model.exclude_buffer(*buffer_id, cx));
+                            }
+                        }),
+                    ))
+            }
+            TreeEntry::File {
+                buffer_id,
+                path,
+                match_count,
+                collapsed,
+            } => {
+                let collapsed_entry = CollapsedTreeEntry::File(buffer_id);
+                let file_name = path.file_name().map_or_else(
+                    || path.to_string_lossy().to_string(),
+                    |file_name| file_name.to_string_lossy().to_string(),
+                );
+                ListItem::new(ix)
+                    .indent_level(1)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle(!collapsed)
+                    .on_toggle(cx.listener({
+                        let collapsed_entry = collapsed_entry.clone();
+                        move |this, _: &ClickEvent, cx| {
+                            this.toggle_tree_entry(collapsed_entry.clone(), cx)
+                        }
+                    }))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.toggle_tree_entry(collapsed_entry.clone(), cx)
+                    }))
+                    .child(Self::render_tree_label(file_name, match_count))
+                    .end_hover_slot(Self::render_dismiss_button(
+                        ix,
+                        "Dismiss matches in this file",
+                        cx.listener(move |this, _, cx| {
+                            this.model
+                                .update(cx, |model, cx| model.exclude_buffer(buffer_id, cx));
+                        }),
+                    ))
+            }
+            TreeEntry::Match(tree_match) => {
+                let match_ix = tree_match.match_ix;
+                ListItem::new(ix)
+                    .indent_level(2)
+                    .spacing(ListItemSpacing::Dense)
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.activate_tree_match(match_ix, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Label::new((tree_match.row + 1).to_string())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                HighlightedLabel::new(tree_match.line, tree_match.highlights)
+                                    .size(LabelSize::Small),
+                            ),
+                    )
+                    .end_hover_slot(Self::render_dismiss_button(
+                        ix,
+                        "Dismiss this match",
+                        cx.listener(move |this, _, cx| {
+                            this.model
+                                .update(cx, |model, cx| model.dismiss_match(match_ix, cx));
+                        }),
+                    ))
+            }
+        }
+    }
+
+    fn render_tree_label(label: String, match_count: usize) -> impl IntoElement {
+        h_flex().gap_2().child(Label::new(label)).child(
+            Label::new(match_count.to_string())
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+    }
+
+    fn render_dismiss_button(
+        ix: usize,
+        tooltip: &'static str,
+        on_click: impl Fn(&ClickEvent, &mut WindowContext) + 'static,
+    ) -> impl IntoElement {
+        IconButton::new(("dismiss-tree-entry", ix), IconName::Close)
+            .icon_size(IconSize::Small)
+            .tooltip(move |cx| Tooltip::text(tooltip, cx))
+            .on_click(on_click)
+    }
+
     fn focus_results_editor(&mut self, cx: &mut ViewContext<Self>) {
         self.query_editor.update(cx, |query_editor, cx| {
             let cursor = query_editor.selections.newest_anchor().head();
@@ -998,6 +1650,7 @@ impl ProjectSearchView {
     }
 
     fn model_changed(&mut self, cx: &mut ViewContext<Self>) {
+        self.tree_entries = None;
         let match_ranges = self.model.read(cx).match_ranges.clone();
         if match_ranges.is_empty() {
             self.active_match_index = None;
@@ -1222,6 +1875,53 @@ impl ProjectSearchBar {
         }
     }
 
+    fn render_scope_menu(
+        search_view: &View<ProjectSearchView>,
+        cx: &mut WindowContext,
+    ) -> DropdownMenu {
+        let current_scope = search_view.read(cx).scope.clone();
+        let label = current_scope.label();
+        let search_view = search_view.downgrade();
+        let menu = ContextMenu::build(cx, |mut menu, _| {
+            let mut scopes = vec![
+                SearchScope::Project,
+                SearchScope::OpenEditors,
+                SearchScope::ModifiedFiles,
+            ];
+            if let SearchScope::Paths(_) = &current_scope {
+                scopes.push(current_scope.clone());
+            }
+            for scope in scopes {
+                let search_view = search_view.clone();
+                menu = menu.toggleable_entry(
+                    scope.label(),
+                    scope == current_scope,
+                    IconPosition::Start,
+                    None,
+                    move |cx| {
+                        search_view
+                            .update(cx, |search_view, cx| {
+                                search_view.set_scope(scope.clone(), cx)
+                            })
+                            .ok();
+                    },
+                );
+            }
+            menu
+        });
+        DropdownMenu::new("project-search-scope", label, menu)
+    }
+
+    fn toggle_results_tree(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| search_view.toggle_results_tree(cx));
+            cx.notify();
+            true
+        } else {
+            false
+        }
+    }
+
     fn toggle_filters(&mut self, cx: &mut ViewContext<Self>) -> bool {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
@@ -1362,6 +2062,10 @@ impl Render for ProjectSearchBar {
         let Some(search) = self.active_project_search.clone() else {
             return div();
         };
+        let scope_menu = search
+            .read(cx)
+            .filters_enabled
+            .then(|| Self::render_scope_menu(&search, cx));
         let search = search.read(cx);
 
         let query_column = h_flex()
@@ -1398,6 +2102,12 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+                        }),
                     )),
             );
 
@@ -1417,6 +2127,21 @@ impl Render for ProjectSearchBar {
                         )
                         .tooltip(|cx| Tooltip::for_action("Toggle filters", &ToggleFilters, cx)),
                 )
+                .child(
+                    IconButton::new("project-search-toggle-results-tree", IconName::ListTree)
+                        .on_click(cx.listener(|this, _, cx| {
+                            this.toggle_results_tree(cx);
+                        }))
+                        .selected(
+                            self.active_project_search
+                                .as_ref()
+                                .map(|search| search.read(cx).results_tree_enabled)
+                                .unwrap_or_default(),
+                        )
+                        .tooltip(|cx| {
+                            Tooltip::for_action("Toggle results tree", &ToggleResultsTree, cx)
+                        }),
+                )
                 .child(
                     IconButton::new("project-search-toggle-replace", IconName::Replace)
                         .on_click(cx.listener(|this, _, cx| {
@@ -1537,6 +2262,34 @@ impl Render for ProjectSearchBar {
                         }))
                         .tooltip(|cx| Tooltip::for_action("Replace all matches", &ReplaceAll, cx)),
                 )
+                .child(
+                    IconButton::new("project-search-replace-in-file", IconName::File)
+                        .on_click(cx.listener(|this, _, cx| {
+                            if let Some(search) = this.active_project_search.as_ref() {
+                                search.update(cx, |this, cx| {
+                                    this.replace_in_file(&ReplaceInFile, cx);
+                                })
+                            }
+                        }))
+                        .tooltip(|cx| {
+                            Tooltip::for_action(
+                                "Replace all matches in the current file",
+                                &ReplaceInFile,
+                                cx,
+                            )
+                        }),
+                )
+                .child(
+                    IconButton::new("project-search-preview-replacements", IconName::Eye)
+                        .on_click(|_, cx| cx.dispatch_action(PreviewReplacements.boxed_clone()))
+                        .tooltip(|cx| {
+                            Tooltip::for_action(
+                                "Preview replacements as a diff",
+                                &PreviewReplacements,
+                                cx,
+                            )
+                        }),
+                )
             });
             h_flex()
                 .pr(rems(5.5))
@@ -1577,6 +2330,7 @@ impl Render for ProjectSearchBar {
                         .rounded_lg()
                         .child(self.render_text_input(&search.excluded_files_editor, cx)),
                 )
+                .children(scope_menu)
                 .child(
                     SearchOptions::INCLUDE_IGNORED.as_button(
                         search
@@ -1632,6 +2386,13 @@ impl Render for ProjectSearchBar {
                     })
                 }
             }))
+            .on_action(cx.listener(|this, action, cx| {
+                if let Some(search) = this.active_project_search.as_ref() {
+                    search.update(cx, |this, cx| {
+                        this.replace_in_file(action, cx);
+                    })
+                }
+            }))
             .when(search.filters_enabled, |this| {
                 this.on_action(cx.listener(|this, _: &ToggleIncludeIgnored, cx| {
                     this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, cx);
@@ -1668,6 +2429,41 @@ impl ToolbarItemView for ProjectSearchBar {
     }
 }
 
+fn open_editor_paths(workspace: &Workspace, cx: &AppContext) -> Vec<ProjectPath> {
+    workspace
+        .items(cx)
+        .filter_map(|item| item.project_path(cx))
+        .collect()
+}
+
+fn modified_paths(project: &Project, cx: &AppContext) -> Vec<ProjectPath> {
+    let mut paths = project
+        .opened_buffers(cx)
+        .into_iter()
+        .filter_map(|buffer| {
+            let buffer = buffer.read(cx);
+            if buffer.is_dirty() {
+                buffer.project_path(cx)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    for worktree in project.visible_worktrees(cx) {
+        let worktree = worktree.read(cx);
+        paths.extend(
+            worktree
+                .entries(false, 0)
+                .filter(|entry| entry.is_file() && entry.git_status.is_some())
+                .map(|entry| ProjectPath {
+                    worktree_id: worktree.id(),
+                    path: entry.path.clone(),
+                }),
+        );
+    }
+    paths
+}
+
 fn register_workspace_action<A: Action>(
     workspace: &mut Workspace,
     callback: fn(&mut ProjectSearchBar, &A, &mut ViewContext<ProjectSearchBar>),
@@ -1722,6 +2518,59 @@ fn register_workspace_action_for_present_search<A: Action>(
     });
 }
 
+/// Appends the hunks of a unified diff that replace the given ranges of the buffer, which
+/// must be sorted and disjoint. Each hunk spans the lines its replacements touch, and
+/// replacements that touch the same lines share a hunk.
+fn push_replacement_hunks(
+    diff: &mut String,
+    buffer: &BufferSnapshot,
+    replacements: &[(Range<usize>, String)],
+) {
+    let mut row_delta = 0_i64;
+    let mut replacements = replacements.iter().peekable();
+    while let Some((first_range, first_text)) = replacements.next() {
+        let start_row = buffer.offset_to_point(first_range.start).row;
+        let mut end_row = buffer.offset_to_point(first_range.end).row;
+        let mut hunk_replacements = vec![(first_range.clone(), first_text)];
+        while let Some((range, text)) =
+            replacements.next_if(|(range, _)| buffer.offset_to_point(range.start).row <= end_row)
+        {
+            end_row = end_row.max(buffer.offset_to_point(range.end).row);
+            hunk_replacements.push((range.clone(), text));
+        }
+
+        let line_start = buffer.point_to_offset(language::Point::new(start_row, 0));
+        let line_end =
+            buffer.point_to_offset(language::Point::new(end_row, buffer.line_len(end_row)));
+        let old_lines = buffer
+            .text_for_range(line_start..line_end)
+            .collect::<String>();
+        let mut new_lines = String::new();
+        let mut offset = line_start;
+        for (range, text) in hunk_replacements {
+            new_lines.extend(buffer.text_for_range(offset..range.start));
+            new_lines.push_str(text);
+            offset = range.end;
+        }
+        new_lines.extend(buffer.text_for_range(offset..line_end));
+
+        let old_line_count = old_lines.lines().count() as i64;
+        let new_line_count = new_lines.lines().count() as i64;
+        let old_start = start_row as i64 + 1;
+        diff.push_str(&format!(
+            "@@ -{old_start},{old_line_count} +{},{new_line_count} @@\n",
+            old_start + row_delta
+        ));
+        row_delta += new_line_count - old_line_count;
+        for line in old_lines.lines() {
+            diff.push_str(&format!("-{line}\n"));
+        }
+        for line in new_lines.lines() {
+            diff.push_str(&format!("+{line}\n"));
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -3261,6 +4110,37 @@ pub mod tests {
             .expect("unable to update search view");
     }
 
+    #[gpui::test]
+    fn test_replacement_hunks(cx: &mut TestAppContext) {
+        let buffer = cx.new_model(|cx| {
+            language::Buffer::local("let a = 1; let b = a;\nlet c = 2;\nlet d = a;\n", cx)
+        });
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let text = snapshot.text();
+        let replacements = text
+            .match_indices('a')
+            .map(|(ix, _)| (ix..ix + 1, "x\ny".to_string()))
+            .collect::<Vec<_>>();
+
+        // The replacements on the first line share a hunk, which shifts the next one.
+        let mut diff = String::new();
+        push_replacement_hunks(&mut diff, &snapshot, &replacements);
+        assert_eq!(
+            diff,
+            concat!(
+                "@@ -1,1 +1,3 @@\n",
+                "-let a = 1; let b = a;\n",
+                "+let x\n",
+                "+y = 1; let b = x\n",
+                "+y;\n",
+                "@@ -3,1 +5,2 @@\n",
+                "-let d = a;\n",
+                "+let d = x\n",
+                "+y;\n",
+            )
+        );
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
@@ -0,0 +1,183 @@
+use collections::HashSet;
+use gpui::SharedString;
+use language::BufferId;
+use std::{path::Path, sync::Arc};
+
+/// A project search match, as shown in the results tree.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TreeMatch {
+    /// The index of the match in the search's match ranges.
+    pub match_ix: usize,
+    pub buffer_id: BufferId,
+    pub path: Arc<Path>,
+    pub row: u32,
+    pub line: SharedString,
+    /// The indices of the characters of `line` that are part of the match.
+    pub highlights: Vec<usize>,
+}
+
+/// A directory or file whose children are hidden in the results tree.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CollapsedTreeEntry {
+    Directory(Arc<Path>),
+    File(BufferId),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TreeEntry {
+    Directory {
+        path: Arc<Path>,
+        buffer_ids: Vec<BufferId>,
+        match_count: usize,
+        collapsed: bool,
+    },
+    File {
+        buffer_id: BufferId,
+        path: Arc<Path>,
+        match_count: usize,
+        collapsed: bool,
+    },
+    Match(TreeMatch),
+}
+
+struct FileGroup {
+    buffer_id: BufferId,
+    path: Arc<Path>,
+    matches: Vec<TreeMatch>,
+}
+
+/// Groups the matches by directory and file, sorted by path, leaving out the children
+/// of collapsed entries.
+pub(crate) fn tree_entries(
+    matches: Vec<TreeMatch>,
+    collapsed: &HashSet<CollapsedTreeEntry>,
+) -> Vec<TreeEntry> {
+    let mut directories: Vec<(Arc<Path>, Vec<FileGroup>)> = Vec::new();
+    for tree_match in matches {
+        let directory: Arc<Path> = tree_match
+            .path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .into();
+        let directory_ix = match directories.iter().position(|(path, _)| *path == directory) {
+            Some(ix) => ix,
+            None => {
+                directories.push((directory, Vec::new()));
+                directories.len() - 1
+            }
+        };
+        let files = &mut directories[directory_ix].1;
+        match files
+            .iter_mut()
+            .find(|file| file.buffer_id == tree_match.buffer_id)
+        {
+            Some(file) => file.matches.push(tree_match),
+            None => files.push(FileGroup {
+                buffer_id: tree_match.buffer_id,
+                path: tree_match.path.clone(),
+                matches: vec![tree_match],
+            }),
+        }
+    }
+    directories.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut entries = Vec::new();
+    for (path, mut files) in directories {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let directory_collapsed = collapsed.contains(&CollapsedTreeEntry::Directory(path.clone()));
+        entries.push(TreeEntry::Directory {
+            path,
+            buffer_ids: files.iter().map(|file| file.buffer_id).collect(),
+            match_count: files.iter().map(|file| file.matches.len()).sum(),
+            collapsed: directory_collapsed,
+        });
+        if directory_collapsed {
+            continue;
+        }
+
+        for file in files {
+            let file_collapsed = collapsed.contains(&CollapsedTreeEntry::File(file.buffer_id));
+            entries.push(TreeEntry::File {
+                buffer_id: file.buffer_id,
+                path: file.path,
+                match_count: file.matches.len(),
+                collapsed: file_collapsed,
+            });
+            if !file_collapsed {
+                entries.extend(file.matches.into_iter().map(TreeEntry::Match));
+            }
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_match(match_ix: usize, buffer_id: u64, path: &str, row: u32) -> TreeMatch {
+        TreeMatch {
+            match_ix,
+            buffer_id: BufferId::new(buffer_id).unwrap(),
+            path: Path::new(path).into(),
+            row,
+            line: "line".into(),
+            highlights: Vec::new(),
+        }
+    }
+
+    fn describe(entries: &[TreeEntry]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| match entry {
+                TreeEntry::Directory {
+                    path, match_count, ..
+                } => format!("{} ({match_count})", path.display()),
+                TreeEntry::File {
+                    path, match_count, ..
+                } => format!("  {} ({match_count})", path.display()),
+                TreeEntry::Match(tree_match) => format!("    {}", tree_match.row),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tree_entries() {
+        let matches = vec![
+            tree_match(0, 1, "root/src/b.rs", 3),
+            tree_match(1, 1, "root/src/b.rs", 7),
+            tree_match(2, 2, "root/README.md", 1),
+            tree_match(3, 3, "root/src/a.rs", 10),
+        ];
+
+        assert_eq!(
+            describe(&tree_entries(matches.clone(), &HashSet::default())),
+            [
+                "root (1)",
+                "  root/README.md (1)",
+                "    1",
+                "root/src (3)",
+                "  root/src/a.rs (1)",
+                "    10",
+                "  root/src/b.rs (2)",
+                "    3",
+                "    7",
+            ]
+        );
+
+        let collapsed = HashSet::from_iter([
+            CollapsedTreeEntry::Directory(Path::new("root").into()),
+            CollapsedTreeEntry::File(BufferId::new(1).unwrap()),
+        ]);
+        assert_eq!(
+            describe(&tree_entries(matches, &collapsed)),
+            [
+                "root (1)",
+                "root/src (3)",
+                "  root/src/a.rs (1)",
+                "    10",
+                "  root/src/b.rs (2)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{project_search::ProjectSearchView, SearchOptions};
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{actions, AppContext, SharedString, ViewContext, WeakView, WindowContext};
+use picker::named_items::{NamedItems, NamedItemsModal, NamedItemsMode};
+use serde::{Deserialize, Serialize};
+use util::ResultExt;
+use workspace::Workspace;
+
+actions!(project_search, [SaveSearch, OpenSavedSearch]);
+
+const SAVED_SEARCHES_KEY: &str = "project_search_saved_searches";
+
+/// A named project search configuration that can be run again later.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub whole_word: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub include_ignored: bool,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub structural: bool,
+    #[serde(default)]
+    pub files_to_include: String,
+    #[serde(default)]
+    pub files_to_exclude: String,
+}
+
+impl SavedSearch {
+    pub fn new(
+        query: String,
+        options: SearchOptions,
+        files_to_include: String,
+        files_to_exclude: String,
+    ) -> Self {
+        Self {
+            name: String::new(),
+            query,
+            whole_word: options.contains(SearchOptions::WHOLE_WORD),
+            case_sensitive: options.contains(SearchOptions::CASE_SENSITIVE),
+            include_ignored: options.contains(SearchOptions::INCLUDE_IGNORED),
+            regex: options.contains(SearchOptions::REGEX),
+            structural: options.contains(SearchOptions::STRUCTURAL),
+            files_to_include,
+            files_to_exclude,
+        }
+    }
+
+    pub fn options(&self) -> SearchOptions {
+        let mut options = SearchOptions::NONE;
+        options.set(SearchOptions::WHOLE_WORD, self.whole_word);
+        options.set(SearchOptions::CASE_SENSITIVE, self.case_sensitive);
+        options.set(SearchOptions::INCLUDE_IGNORED, self.include_ignored);
+        options.set(SearchOptions::REGEX, self.regex);
+        options.set(SearchOptions::STRUCTURAL, self.structural);
+        options
+    }
+}
+
+fn load_saved_searches() -> Vec<SavedSearch> {
+    KEY_VALUE_STORE
+        .read_kvp(SAVED_SEARCHES_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|saved_searches| serde_json::from_str(&saved_searches).log_err())
+        .unwrap_or_default()
+}
+
+fn store_saved_searches(saved_searches: &[SavedSearch], cx: &mut AppContext) {
+    let Some(saved_searches) = serde_json::to_string(saved_searches).log_err() else {
+        return;
+    };
+    db::write_and_log(cx, move || {
+        KEY_VALUE_STORE.write_kvp(SAVED_SEARCHES_KEY.to_string(), saved_searches)
+    });
+}
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &SaveSearch, cx| {
+            let Some(search) = workspace
+                .active_item_as::<ProjectSearchView>(cx)
+                .map(|search_view| search_view.read(cx).saved_search(cx))
+            else {
+                return;
+            };
+            toggle_saved_searches(NamedItemsMode::Save(search), cx);
+        });
+        workspace.register_action(|_, _: &OpenSavedSearch, cx| {
+            toggle_saved_searches(NamedItemsMode::Open, cx);
+        });
+    })
+    .detach();
+}
+
+/// Reads the saved searches in the background, and then shows them in a modal.
+fn toggle_saved_searches(mode: NamedItemsMode<SavedSearch>, cx: &mut ViewContext<Workspace>) {
+    let load_saved_searches = cx
+        .background_executor()
+        .spawn(async { load_saved_searches() });
+    cx.spawn(|workspace, mut cx| async move {
+        let saved_searches = load_saved_searches.await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let saved_searches_handler = SavedSearches {
+                workspace: cx.view().downgrade(),
+            };
+            workspace.toggle_modal(cx, |cx| {
+                NamedItemsModal::new(saved_searches_handler, mode, saved_searches, cx)
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The saved searches, listed in a [`NamedItemsModal`] to run one of them again or to save
+/// the active project search.
+struct SavedSearches {
+    workspace: WeakView<Workspace>,
+}
+
+impl NamedItems for SavedSearches {
+    type Item = SavedSearch;
+
+    const OPEN_PLACEHOLDER: &'static str = "Run a saved search…";
+    const SAVE_PLACEHOLDER: &'static str = "Name this search…";
+    const NO_ITEMS_TEXT: &'static str = "No saved searches";
+    const DELETE_TOOLTIP: &'static str = "Delete saved search";
+
+    fn name(saved_search: &SavedSearch) -> &str {
+        &saved_search.name
+    }
+
+    fn set_name(saved_search: &mut SavedSearch, name: String) {
+        saved_search.name = name;
+    }
+
+    fn store(&mut self, saved_searches: &[SavedSearch], cx: &mut WindowContext) {
+        store_saved_searches(saved_searches, cx);
+    }
+
+    fn open(&mut self, saved_search: &SavedSearch, _secondary: bool, cx: &mut WindowContext) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::open_saved_search(workspace, saved_search, cx)
+            })
+            .log_err();
+    }
+
+    fn details(&self, saved_search: &SavedSearch) -> Option<SharedString> {
+        let mut details = saved_search.query.clone();
+        if !saved_search.files_to_include.is_empty() {
+            details.push_str(&format!("  in {}", saved_search.files_to_include));
+        }
+        if !saved_search.files_to_exclude.is_empty() {
+            details.push_str(&format!("  excluding {}", saved_search.files_to_exclude));
+        }
+        Some(details.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_search_options_round_trip() {
+        let options = SearchOptions::CASE_SENSITIVE | SearchOptions::REGEX;
+        let search = SavedSearch::new(
+            "fn (\\w+)".to_string(),
+            options,
+            "crates/**".to_string(),
+            String::new(),
+        );
+        assert_eq!(search.options(), options);
+
+        // Searches saved before an option existed default it to off.
+        let search: SavedSearch =
+            serde_json::from_str(r#"{"name": "todo", "query": "TODO"}"#).unwrap();
+        assert_eq!(search.options(), SearchOptions::NONE);
+        assert!(search.files_to_include.is_empty());
+    }
+}
@@ -4,8 +4,11 @@ use gpui::App;
 use http_client::HttpClientWithUrl;
 use language::language_settings::AllLanguageSettings;
 use project::Project;
-use semantic_index::{OpenAiEmbeddingModel, OpenAiEmbeddingProvider, SemanticIndex};
-use settings::SettingsStore;
+use semantic_index::{
+    OpenAiEmbeddingModel, OpenAiEmbeddingProvider, SearchFilter, SemanticIndex,
+    SemanticIndexSettings,
+};
+use settings::{Settings, SettingsStore};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
@@ -21,6 +24,7 @@ fn main() {
         cx.set_global(store);
         language::init(cx);
         Project::init_settings(cx);
+        SemanticIndexSettings::register(cx);
         SettingsStore::update(cx, |store, cx| {
             store.update_user_settings::<AllLanguageSettings>(cx, |_| {});
         });
@@ -92,7 +96,7 @@ fn main() {
                 .update(|cx| {
                     let project_index = project_index.read(cx);
                     let query = "converting an anchor to a point";
-                    project_index.search(query.into(), 4, cx)
+                    project_index.search(query.into(), 4, SearchFilter::default(), cx)
                 })
                 .unwrap()
                 .await
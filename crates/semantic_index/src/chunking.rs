@@ -25,6 +25,15 @@ const CHUNK_SIZE_RANGE: ChunkSizeRange = ChunkSizeRange {
 pub struct Chunk {
     pub range: Range<usize>,
     pub digest: [u8; 32],
+    /// The kind of syntax node the chunk consists of, such as a function or a heading,
+    /// when the file's language has an outline.
+    pub kind: Option<String>,
+}
+
+/// The range of an outline item, such as a type, a function or a heading.
+struct SyntacticRange {
+    range: Range<usize>,
+    kind: &'static str,
 }
 
 pub fn chunk_text(text: &str, language: Option<&Arc<Language>>, path: &Path) -> Vec<Chunk> {
@@ -45,7 +54,7 @@ fn syntactic_ranges(
     text: &str,
     language: Option<&Arc<Language>>,
     path: &Path,
-) -> Option<Vec<Range<usize>>> {
+) -> Option<Vec<SyntacticRange>> {
     let language = language?;
     let grammar = language.grammar()?;
     let outline = grammar.outline_config.as_ref()?;
@@ -84,7 +93,9 @@ fn syntactic_ranges(
 
     // Retrieve a list of ranges of outline items (types, functions, etc) in the document.
     // Omit single-line outline items (e.g. struct fields, constant declarations), because
-    // we'll already be attempting to split on lines.
+    // we'll already be attempting to split on lines. Headings only span a line too, but
+    // they begin a section that lasts until the next heading, so they're kept as empty
+    // ranges and extended below.
     let mut ranges = with_query_cursor(|cursor| {
         cursor
             .matches(&outline.query, tree.root_node(), text.as_bytes())
@@ -97,6 +108,14 @@ fn syntactic_ranges(
                             let mut start_row = node.start_position().row;
                             let end_offset = node.end_byte();
                             let end_row = node.end_position().row;
+                            let kind = node.kind();
+
+                            if kind.contains("heading") {
+                                return Some(SyntacticRange {
+                                    range: start_offset..start_offset,
+                                    kind,
+                                });
+                            }
 
                             // Expand the range to include any preceding comments.
                             while start_row > 0 && row_infos[start_row - 1].is_comment {
@@ -105,7 +124,10 @@ fn syntactic_ranges(
                             }
 
                             if end_row > start_row {
-                                return Some(start_offset..end_offset);
+                                return Some(SyntacticRange {
+                                    range: start_offset..end_offset,
+                                    kind,
+                                });
                             }
                         }
                         None
@@ -114,15 +136,42 @@ fn syntactic_ranges(
             .collect::<Vec<_>>()
     });
 
-    ranges.sort_unstable_by_key(|range| (range.start, Reverse(range.end)));
+    ranges.sort_unstable_by_key(|item| item.range.start);
+    let mut section_end = text.len();
+    for item in ranges.iter_mut().rev() {
+        if item.range.is_empty() {
+            item.range.end = section_end;
+            section_end = item.range.start;
+        }
+    }
+
+    ranges.sort_unstable_by_key(|item| (item.range.start, Reverse(item.range.end)));
     Some(ranges)
 }
 
+/// Returns the kind of the outermost outline item that starts within the chunk or, if
+/// none does, of the innermost one that the chunk is a part of.
+fn chunk_kind(chunk_range: &Range<usize>, syntactic_ranges: &[SyntacticRange]) -> Option<String> {
+    syntactic_ranges
+        .iter()
+        .find(|item| chunk_range.contains(&item.range.start))
+        .or_else(|| {
+            syntactic_ranges
+                .iter()
+                .filter(|item| {
+                    item.range.start <= chunk_range.start && item.range.end >= chunk_range.end
+                })
+                .last()
+        })
+        .map(|item| item.kind.to_string())
+}
+
 fn chunk_text_with_syntactic_ranges(
     text: &str,
-    mut syntactic_ranges: &[Range<usize>],
+    all_syntactic_ranges: &[SyntacticRange],
     size_config: ChunkSizeRange,
 ) -> Vec<Chunk> {
+    let mut syntactic_ranges = all_syntactic_ranges;
     let mut chunks = Vec::new();
     let mut range = 0..0;
     let mut range_end_nesting_depth = 0;
@@ -152,6 +201,7 @@ fn chunk_text_with_syntactic_ranges(
             chunks.push(Chunk {
                 range: range.clone(),
                 digest: Sha256::digest(&text[range.clone()]).into(),
+                kind: chunk_kind(&range, all_syntactic_ranges),
             });
             range_end_nesting_depth = 0;
             range.start = range.end;
@@ -160,7 +210,7 @@ fn chunk_text_with_syntactic_ranges(
 
         // Discard any syntactic ranges that end before the current position.
         while let Some(first_item) = syntactic_ranges.first() {
-            if first_item.end < line_ix {
+            if first_item.range.end < line_ix {
                 syntactic_ranges = &syntactic_ranges[1..];
                 continue;
             } else {
@@ -170,11 +220,11 @@ fn chunk_text_with_syntactic_ranges(
 
         // Count how many syntactic ranges contain the current position.
         let mut nesting_depth = 0;
-        for range in syntactic_ranges {
-            if range.start > line_ix {
+        for item in syntactic_ranges {
+            if item.range.start > line_ix {
                 break;
             }
-            if range.start < line_ix && range.end > line_ix {
+            if item.range.start < line_ix && item.range.end > line_ix {
                 nesting_depth += 1;
             }
         }
@@ -191,8 +241,9 @@ fn chunk_text_with_syntactic_ranges(
 
     if !range.is_empty() {
         chunks.push(Chunk {
-            range: range.clone(),
-            digest: Sha256::digest(&text[range]).into(),
+            kind: chunk_kind(&range, all_syntactic_ranges),
+            digest: Sha256::digest(&text[range.clone()]).into(),
+            range,
         });
     }
 
@@ -260,6 +311,18 @@ mod tests {
                 "    fn last_name",
             ],
         );
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| chunk.kind.as_deref())
+                .collect::<Vec<_>>(),
+            [
+                Some("struct_item"),
+                Some("impl_item"),
+                Some("function_item"),
+                Some("function_item"),
+            ]
+        );
 
         let text = "
             struct T {}
@@ -297,6 +360,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_chunk_markdown_by_section() {
+        let language = markdown_language();
+
+        let text = "
+            # Title
+
+            Intro paragraph.
+
+            ## Section A
+
+            Line a1
+            Line a2
+
+            ## Section B
+
+            Line b1
+        "
+        .unindent();
+
+        let chunks = chunk_text_with_size_range(
+            &text,
+            Some(&language),
+            Path::new("README.md"),
+            ChunkSizeRange {
+                min: 10,
+                max: text.find("## Section B").unwrap() - text.find("## Section A").unwrap(),
+            },
+        );
+
+        // Each section fits in a chunk, so chunks are split at the headings.
+        assert_chunks(&text, &chunks, &["# Title", "## Section A", "## Section B"]);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.kind.as_deref() == Some("atx_heading")));
+    }
+
     #[test]
     fn test_chunk_with_long_lines() {
         let language = rust_language();
@@ -387,6 +487,24 @@ mod tests {
         );
     }
 
+    fn markdown_language() -> Arc<Language> {
+        Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Markdown".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["md".to_string()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Some(tree_sitter_md::language()),
+            )
+            .with_outline_query("(atx_heading . (_) @context . (_) @name) @item")
+            .unwrap(),
+        )
+    }
+
     fn rust_language() -> Arc<Language> {
         Arc::new(
             Language::new(
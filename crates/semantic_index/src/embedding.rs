@@ -72,6 +72,9 @@ impl fmt::Display for Embedding {
 pub trait EmbeddingProvider: Sync + Send {
     fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>>;
     fn batch_size(&self) -> usize;
+    /// Identifies the model that computes the embeddings. Embeddings computed by different
+    /// models can't be compared, so an index built with another model is discarded.
+    fn model_id(&self) -> &str;
 }
 
 #[derive(Debug)]
@@ -110,6 +113,10 @@ impl EmbeddingProvider for FakeEmbeddingProvider {
     fn batch_size(&self) -> usize {
         16
     }
+
+    fn model_id(&self) -> &str {
+        "fake"
+    }
 }
 
 #[cfg(test)]
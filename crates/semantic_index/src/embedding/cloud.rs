@@ -90,4 +90,8 @@ impl EmbeddingProvider for CloudEmbeddingProvider {
     fn batch_size(&self) -> usize {
         2048
     }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
 }
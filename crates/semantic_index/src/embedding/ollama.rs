@@ -35,11 +35,7 @@ impl OllamaEmbeddingProvider {
 
 impl EmbeddingProvider for OllamaEmbeddingProvider {
     fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
-        //
-        let model = match self.model {
-            OllamaEmbeddingModel::NomicEmbedText => "nomic-embed-text",
-            OllamaEmbeddingModel::MxbaiEmbedLarge => "mxbai-embed-large",
-        };
+        let model = self.model_id();
 
         futures::future::try_join_all(texts.into_iter().map(|to_embed| {
             let request = OllamaEmbeddingRequest {
@@ -71,4 +67,11 @@ impl EmbeddingProvider for OllamaEmbeddingProvider {
         // TODO: Figure out decent value
         10
     }
+
+    fn model_id(&self) -> &str {
+        match self.model {
+            OllamaEmbeddingModel::NomicEmbedText => "nomic-embed-text",
+            OllamaEmbeddingModel::MxbaiEmbedLarge => "mxbai-embed-large",
+        }
+    }
 }
@@ -52,4 +52,11 @@ impl EmbeddingProvider for OpenAiEmbeddingProvider {
         // From https://platform.openai.com/docs/api-reference/embeddings/create
         2048
     }
+
+    fn model_id(&self) -> &str {
+        match self.model {
+            OpenAiEmbeddingModel::TextEmbedding3Small => "text-embedding-3-small",
+            OpenAiEmbeddingModel::TextEmbedding3Large => "text-embedding-3-large",
+        }
+    }
 }
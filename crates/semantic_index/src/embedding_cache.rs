@@ -0,0 +1,256 @@
+use crate::{Embedding, EmbeddingProvider, TextToEmbed};
+use anyhow::{Context as _, Result};
+use futures::{future::BoxFuture, FutureExt};
+use heed::types::{Bytes, SerdeBincode};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use util::ResultExt;
+
+const EMBEDDING_CACHE_DB_NAME: &str = "embedding-cache";
+
+/// Wraps an embedding provider with a cache of the embeddings it computed, keyed by the
+/// digest of the text. The cache is shared by every project, so identical files, such as
+/// vendored libraries, duplicates within a monorepo, or the files of a project that's
+/// opened again, are never embedded twice.
+///
+/// Once the cache holds more than `max_entries` embeddings, the ones that were used
+/// least recently are evicted. The cache has its own database environment, so that it
+/// never takes up the space of the worktrees' indices.
+pub struct CachingEmbeddingProvider {
+    provider: Arc<dyn EmbeddingProvider>,
+    db_connection: heed::Env,
+    db: heed::Database<Bytes, SerdeBincode<CachedEmbedding>>,
+    max_entries: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedEmbedding {
+    embedding: Embedding,
+    /// When the embedding was last used, in seconds since the Unix epoch.
+    last_used: u64,
+}
+
+impl CachingEmbeddingProvider {
+    /// Opens the cache stored in the given directory, creating it if needed.
+    pub fn new(
+        provider: Arc<dyn EmbeddingProvider>,
+        db_path: &Path,
+        max_entries: usize,
+    ) -> Result<Self> {
+        std::fs::create_dir_all(db_path)?;
+        let db_connection = unsafe {
+            heed::EnvOpenOptions::new()
+                .map_size(1024 * 1024 * 1024)
+                .max_dbs(1)
+                .open(db_path)?
+        };
+        let mut txn = db_connection.write_txn()?;
+        let db = db_connection.create_database(&mut txn, Some(EMBEDDING_CACHE_DB_NAME))?;
+        txn.commit()?;
+        Ok(Self {
+            provider,
+            db_connection,
+            db,
+            max_entries,
+        })
+    }
+
+    /// Embeddings computed by different models can't be used in place of each other, so
+    /// the model is part of the key.
+    fn cache_key(&self, digest: &[u8; 32]) -> Vec<u8> {
+        let model_id = self.provider.model_id();
+        let mut key = Vec::with_capacity(model_id.len() + 1 + digest.len());
+        key.extend_from_slice(model_id.as_bytes());
+        key.push(0);
+        key.extend_from_slice(digest);
+        key
+    }
+
+    async fn read_cached(&self, keys: Vec<Vec<u8>>) -> Result<Vec<Option<Embedding>>> {
+        let db_connection = self.db_connection.clone();
+        let db = self.db;
+        smol::unblock(move || {
+            let txn = db_connection.read_txn()?;
+            Ok(keys
+                .iter()
+                .map(|key| {
+                    db.get(&txn, key)
+                        .ok()
+                        .flatten()
+                        .map(|cached| cached.embedding)
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Stores the given embeddings, or marks them as just used if they're already cached,
+    /// and then evicts the least recently used embeddings if there are too many.
+    async fn write_cached(&self, entries: Vec<(Vec<u8>, Embedding)>) -> Result<()> {
+        let db_connection = self.db_connection.clone();
+        let db = self.db;
+        let max_entries = self.max_entries;
+        smol::unblock(move || {
+            let last_used = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs());
+            let mut txn = db_connection.write_txn()?;
+            for (key, embedding) in entries {
+                db.put(
+                    &mut txn,
+                    &key,
+                    &CachedEmbedding {
+                        embedding,
+                        last_used,
+                    },
+                )?;
+            }
+
+            let entry_count = db.len(&txn)? as usize;
+            if entry_count > max_entries {
+                // Evict a tenth more than needed, so that eviction doesn't run on every write.
+                let evicted_count = entry_count - max_entries + max_entries / 10;
+                let mut entries_by_last_use = db
+                    .iter(&txn)?
+                    .map(|entry| {
+                        let (key, cached) = entry?;
+                        Ok((cached.last_used, key.to_vec()))
+                    })
+                    .collect::<heed::Result<Vec<_>>>()?;
+                entries_by_last_use.sort_unstable();
+                for (_, key) in entries_by_last_use.into_iter().take(evicted_count) {
+                    db.delete(&mut txn, &key)?;
+                }
+            }
+
+            txn.commit()?;
+            anyhow::Ok(())
+        })
+        .await
+    }
+}
+
+impl EmbeddingProvider for CachingEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        async move {
+            let keys = texts
+                .iter()
+                .map(|text| self.cache_key(&text.digest))
+                .collect::<Vec<_>>();
+            let mut embeddings = self
+                .read_cached(keys.clone())
+                .await
+                .context("failed to read cached embeddings")
+                .log_err()
+                .unwrap_or_else(|| vec![None; texts.len()]);
+
+            let uncached_texts = texts
+                .iter()
+                .zip(&embeddings)
+                .filter(|(_, embedding)| embedding.is_none())
+                .map(|(text, _)| TextToEmbed {
+                    text: text.text,
+                    digest: text.digest,
+                })
+                .collect::<Vec<_>>();
+            if !uncached_texts.is_empty() {
+                let computed_embeddings = self.provider.embed(&uncached_texts).await?;
+                anyhow::ensure!(
+                    computed_embeddings.len() == uncached_texts.len(),
+                    "embedding provider returned unexpected embedding count {}, expected {}",
+                    computed_embeddings.len(),
+                    uncached_texts.len()
+                );
+                let mut computed_embeddings = computed_embeddings.into_iter();
+                for embedding in &mut embeddings {
+                    if embedding.is_none() {
+                        *embedding = computed_embeddings.next();
+                    }
+                }
+            }
+
+            let embeddings = embeddings.into_iter().flatten().collect::<Vec<_>>();
+            self.write_cached(keys.into_iter().zip(embeddings.iter().cloned()).collect())
+                .await
+                .context("failed to cache embeddings")
+                .log_err();
+            Ok(embeddings)
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        self.provider.batch_size()
+    }
+
+    fn model_id(&self) -> &str {
+        self.provider.model_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingEmbeddingProvider {
+        embedded_count: AtomicUsize,
+    }
+
+    impl EmbeddingProvider for CountingEmbeddingProvider {
+        fn embed<'a>(
+            &'a self,
+            texts: &'a [TextToEmbed<'a>],
+        ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+            self.embedded_count.fetch_add(texts.len(), Ordering::SeqCst);
+            let embeddings = texts
+                .iter()
+                .map(|text| Embedding::new(vec![text.text.len() as f32, 1.]))
+                .collect();
+            futures::future::ready(Ok(embeddings)).boxed()
+        }
+
+        fn batch_size(&self) -> usize {
+            16
+        }
+
+        fn model_id(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[test]
+    fn test_caching_embedding_provider() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let provider = Arc::new(CountingEmbeddingProvider {
+            embedded_count: AtomicUsize::new(0),
+        });
+        let caching_provider =
+            CachingEmbeddingProvider::new(provider.clone(), temp_dir.path(), 4).unwrap();
+
+        let embed = |texts: &[&str]| {
+            let texts = texts
+                .iter()
+                .map(|text| TextToEmbed::new(text))
+                .collect::<Vec<_>>();
+            smol::block_on(caching_provider.embed(&texts)).unwrap()
+        };
+
+        let embeddings = embed(&["a", "bb"]);
+        assert_eq!(provider.embedded_count.load(Ordering::SeqCst), 2);
+
+        // Only the text that wasn't embedded before is embedded.
+        let cached_embeddings = embed(&["ccc", "a"]);
+        assert_eq!(provider.embedded_count.load(Ordering::SeqCst), 3);
+        assert_eq!(cached_embeddings[1], embeddings[0]);
+
+        // The least recently used embeddings are evicted once there are too many.
+        embed(&["dddd", "eeeee", "ffffff"]);
+        let txn = caching_provider.db_connection.read_txn().unwrap();
+        assert!(caching_provider.db.len(&txn).unwrap() <= 4);
+    }
+}
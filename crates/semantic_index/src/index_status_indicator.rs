@@ -0,0 +1,156 @@
+use gpui::{
+    actions, Action, AnchorCorner, AppContext, Model, Render, Subscription, ViewContext, WeakModel,
+};
+use project::Project;
+use ui::{prelude::*, ContextMenu, PopoverMenu, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView, Workspace};
+
+use crate::{ProjectIndex, SemanticIndex, Status};
+
+actions!(
+    semantic_index,
+    [PauseIndexing, ResumeIndexing, RebuildIndex]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        workspace
+            .register_action(|workspace, _: &PauseIndexing, cx| {
+                if let Some(project_index) = project_index(workspace, cx) {
+                    project_index.update(cx, |index, cx| index.pause(cx));
+                }
+            })
+            .register_action(|workspace, _: &ResumeIndexing, cx| {
+                if let Some(project_index) = project_index(workspace, cx) {
+                    project_index.update(cx, |index, cx| index.resume(cx));
+                }
+            })
+            .register_action(|workspace, _: &RebuildIndex, cx| {
+                if let Some(project_index) = project_index(workspace, cx) {
+                    project_index
+                        .update(cx, |index, cx| index.rebuild(cx))
+                        .detach_and_log_err(cx);
+                }
+            });
+
+        let indicator = cx.new_view(|cx| IndexStatusIndicator::new(workspace.project(), cx));
+        workspace.status_bar().update(cx, |status_bar, cx| {
+            status_bar.add_right_item(indicator, cx);
+        });
+    })
+    .detach();
+}
+
+/// Returns the workspace's project index, without creating it if the project hasn't
+/// been indexed yet.
+fn project_index(workspace: &Workspace, cx: &AppContext) -> Option<Model<ProjectIndex>> {
+    cx.try_global::<SemanticIndex>()?
+        .existing_project_index(workspace.project())
+}
+
+/// Shows the progress of the project's semantic index in the status bar, with controls
+/// to pause, resume or rebuild it. Nothing is shown until the project is indexed.
+pub struct IndexStatusIndicator {
+    project: WeakModel<Project>,
+    project_index: Option<Model<ProjectIndex>>,
+    _observe_project_index: Option<Subscription>,
+    _observe_semantic_index: Subscription,
+}
+
+impl IndexStatusIndicator {
+    pub fn new(project: &Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            project: project.downgrade(),
+            project_index: None,
+            _observe_project_index: None,
+            _observe_semantic_index: cx
+                .observe_global::<SemanticIndex>(|this, cx| this.update_project_index(cx)),
+        };
+        this.update_project_index(cx);
+        this
+    }
+
+    fn update_project_index(&mut self, cx: &mut ViewContext<Self>) {
+        if self.project_index.is_some() {
+            return;
+        }
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let Some(project_index) = cx
+            .try_global::<SemanticIndex>()
+            .and_then(|index| index.existing_project_index(&project))
+        else {
+            return;
+        };
+
+        self._observe_project_index = Some(cx.observe(&project_index, |_, _, cx| cx.notify()));
+        self.project_index = Some(project_index);
+        cx.notify();
+    }
+}
+
+impl Render for IndexStatusIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let Some(project_index) = self.project_index.as_ref() else {
+            return div();
+        };
+        let project_index = project_index.read(cx);
+        let status = project_index.status();
+        let last_error = project_index.last_error(cx);
+
+        let (icon, icon_color, label): (_, _, SharedString) = match status {
+            Status::Loading => (IconName::ArrowCircle, Color::Muted, "Loading Index".into()),
+            Status::Scanning { remaining_count } => (
+                IconName::ArrowCircle,
+                Color::Muted,
+                format!("Indexing: {remaining_count} files queued").into(),
+            ),
+            Status::Paused => (IconName::Stop, Color::Muted, "Indexing Paused".into()),
+            Status::Idle if last_error.is_some() => (
+                IconName::ExclamationTriangle,
+                Color::Warning,
+                "Indexing Failed".into(),
+            ),
+            Status::Idle => (IconName::Check, Color::Muted, "Indexed".into()),
+        };
+
+        let mut tooltip_meta =
+            format!("{} chunks embedded", project_index.embedded_chunk_count(cx));
+        if let Some(last_error) = &last_error {
+            tooltip_meta.push_str(&format!("\nLast error: {last_error}"));
+        }
+        let is_paused = project_index.is_paused();
+
+        div().child(
+            PopoverMenu::new("semantic-index-status")
+                .menu(move |cx| {
+                    Some(ContextMenu::build(cx, |menu, _| {
+                        let menu = if is_paused {
+                            menu.action("Resume Indexing", ResumeIndexing.boxed_clone())
+                        } else {
+                            menu.action("Pause Indexing", PauseIndexing.boxed_clone())
+                        };
+                        menu.action("Rebuild Index", RebuildIndex.boxed_clone())
+                    }))
+                })
+                .anchor(AnchorCorner::BottomRight)
+                .trigger(
+                    Button::new("semantic-index-status-button", label)
+                        .label_size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .icon(icon)
+                        .icon_size(IconSize::Small)
+                        .icon_color(icon_color)
+                        .icon_position(IconPosition::Start)
+                        .tooltip(move |cx| {
+                            Tooltip::with_meta("Semantic Index", None, tooltip_meta.clone(), cx)
+                        }),
+                ),
+        )
+    }
+}
+
+impl StatusItemView for IndexStatusIndicator {
+    fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+}
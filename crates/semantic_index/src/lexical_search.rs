@@ -0,0 +1,238 @@
+use collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// BM25 parameters: how quickly repeated occurrences of a term stop adding to a
+/// document's score, and how much longer documents are penalized.
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+/// The constant added to each rank when fusing rankings, which keeps the few top
+/// results of a single ranking from outweighing results that rank well in all of them.
+const RANK_FUSION_K: f32 = 60.;
+
+/// Ranks documents by how well their words match a query, using BM25. Queries for exact
+/// identifiers, which embeddings handle poorly, still find the documents containing them.
+///
+/// Only the documents that contain a query term are kept, along with the statistics
+/// needed to score them.
+pub(crate) struct LexicalIndex<T> {
+    terms: Vec<String>,
+    document_count: usize,
+    total_length: usize,
+    document_frequencies: Vec<usize>,
+    matches: Vec<LexicalMatch<T>>,
+}
+
+struct LexicalMatch<T> {
+    document: T,
+    length: usize,
+    term_frequencies: Vec<usize>,
+}
+
+/// The words of a document, counted when it's indexed so that searching doesn't need
+/// its text.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DocumentTerms {
+    length: usize,
+    /// Each distinct lowercased word, with how many times it occurs, sorted by word.
+    frequencies: Vec<(String, usize)>,
+}
+
+impl DocumentTerms {
+    pub fn new(text: &str) -> Self {
+        let mut length = 0;
+        let mut frequencies = HashMap::<String, usize>::default();
+        for word in words(text) {
+            length += 1;
+            *frequencies.entry(word.to_lowercase()).or_default() += 1;
+        }
+        let mut frequencies = frequencies.into_iter().collect::<Vec<_>>();
+        frequencies.sort_unstable();
+        Self {
+            length,
+            frequencies,
+        }
+    }
+
+    fn frequency(&self, term: &str) -> usize {
+        self.frequencies
+            .binary_search_by(|(word, _)| word.as_str().cmp(term))
+            .map_or(0, |ix| self.frequencies[ix].1)
+    }
+}
+
+impl<T> LexicalIndex<T> {
+    pub fn new(query: &str) -> Self {
+        let mut terms = Vec::new();
+        for term in words(query) {
+            let term = term.to_lowercase();
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        Self {
+            document_frequencies: vec![0; terms.len()],
+            terms,
+            document_count: 0,
+            total_length: 0,
+            matches: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn add_document(&mut self, document: T, document_terms: &DocumentTerms) {
+        let term_frequencies = self
+            .terms
+            .iter()
+            .map(|term| document_terms.frequency(term))
+            .collect::<Vec<_>>();
+
+        self.document_count += 1;
+        self.total_length += document_terms.length;
+        if term_frequencies.iter().all(|frequency| *frequency == 0) {
+            return;
+        }
+        for (document_frequency, term_frequency) in
+            self.document_frequencies.iter_mut().zip(&term_frequencies)
+        {
+            if *term_frequency > 0 {
+                *document_frequency += 1;
+            }
+        }
+        self.matches.push(LexicalMatch {
+            document,
+            length,
+            term_frequencies,
+        });
+    }
+
+    /// Combines the documents added to another index for the same query with this one's.
+    pub fn merge(&mut self, other: Self) {
+        self.document_count += other.document_count;
+        self.total_length += other.total_length;
+        for (document_frequency, other_frequency) in self
+            .document_frequencies
+            .iter_mut()
+            .zip(other.document_frequencies)
+        {
+            *document_frequency += other_frequency;
+        }
+        self.matches.extend(other.matches);
+    }
+
+    /// Returns the documents that best match the query, best first.
+    pub fn search(self, limit: usize) -> Vec<(T, f32)> {
+        let document_count = self.document_count as f32;
+        let average_length = self.total_length as f32 / document_count.max(1.);
+        let idfs = self
+            .document_frequencies
+            .iter()
+            .map(|frequency| {
+                let frequency = *frequency as f32;
+                ((document_count - frequency + 0.5) / (frequency + 0.5) + 1.).ln()
+            })
+            .collect::<Vec<_>>();
+
+        let mut results = self
+            .matches
+            .into_iter()
+            .map(|lexical_match| {
+                let length_norm = 1. - B + B * lexical_match.length as f32 / average_length.max(1.);
+                let score = lexical_match
+                    .term_frequencies
+                    .iter()
+                    .zip(&idfs)
+                    .map(|(frequency, idf)| {
+                        let frequency = *frequency as f32;
+                        idf * frequency * (K1 + 1.) / (frequency + K1 * length_norm)
+                    })
+                    .sum::<f32>();
+                (lexical_match.document, score)
+            })
+            .collect::<Vec<_>>();
+        results.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        results.truncate(limit);
+        results
+    }
+}
+
+/// Splits text into the words that can make up identifiers.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+}
+
+/// Combines several rankings of the same kind of results into one, with reciprocal rank
+/// fusion. Results that rank highly in any of the rankings end up near the top, even
+/// though the rankings' scores aren't comparable.
+pub(crate) fn fuse_rankings<T, K: Hash + Eq>(
+    rankings: impl IntoIterator<Item = Vec<T>>,
+    key: impl Fn(&T) -> K,
+) -> Vec<T> {
+    let mut fused: HashMap<K, (T, f32)> = HashMap::default();
+    for ranking in rankings {
+        for (rank, result) in ranking.into_iter().enumerate() {
+            let score = 1. / (RANK_FUSION_K + rank as f32 + 1.);
+            fused
+                .entry(key(&result))
+                .and_modify(|(_, fused_score)| *fused_score += score)
+                .or_insert((result, score));
+        }
+    }
+
+    let mut fused = fused.into_values().collect::<Vec<_>>();
+    fused.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    fused.into_iter().map(|(result, _)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexical_index() {
+        let mut index = LexicalIndex::new("fn handle_project_event");
+        index.add_document(0, &DocumentTerms::new("fn update_status(&mut self) {}"));
+        index.add_document(1, &DocumentTerms::new("self.handle_project_event(cx);"));
+        index.add_document(
+            2,
+            &DocumentTerms::new(
+                "fn handle_project_event(&mut self) { /* handle_project_event */ }",
+            ),
+        );
+        index.add_document(3, &DocumentTerms::new("let project = event;"));
+
+        let mut other_index = LexicalIndex::new("fn Handle_Project_Event");
+        other_index.add_document(4, &DocumentTerms::new("struct ProjectIndex;"));
+        index.merge(other_index);
+
+        let results = index.search(10);
+        assert_eq!(
+            results
+                .iter()
+                .map(|(document, _)| *document)
+                .collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_document_terms() {
+        let terms = DocumentTerms::new("let Project = project.clone(); // project");
+        assert_eq!(terms.length, 5);
+        assert_eq!(terms.frequency("project"), 3);
+        assert_eq!(terms.frequency("clone"), 1);
+        assert_eq!(terms.frequency("Project"), 0);
+        assert_eq!(terms.frequency("event"), 0);
+    }
+
+    #[test]
+    fn test_fuse_rankings() {
+        let fused = fuse_rankings([vec!["a", "b", "c"], vec!["d", "c", "a"]], |result| *result);
+        assert_eq!(fused, ["a", "c", "d", "b"]);
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Context as _, Result};
+use futures::{future::BoxFuture, AsyncReadExt, FutureExt};
+use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Trait for rerankers, which score how relevant each of several texts is to a query.
+/// They're typically cross-encoders, which read the query and a text together, and so are
+/// more precise than comparing their embeddings, but too slow to run on a whole codebase.
+pub trait Reranker: Sync + Send {
+    /// Returns the relevance of each of the documents to the query, in the same order.
+    fn rerank<'a>(
+        &'a self,
+        query: &'a str,
+        documents: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<Vec<f32>>>;
+}
+
+/// A reranker served with the `/rerank` API used by Cohere and Jina, which local
+/// inference servers such as Infinity implement too.
+pub struct HttpReranker {
+    client: Arc<dyn HttpClient>,
+    api_url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RerankRequest<'a> {
+    model: &'a str,
+    query: &'a str,
+    documents: &'a [&'a str],
+}
+
+#[derive(Deserialize)]
+struct RerankResponse {
+    results: Vec<RerankResult>,
+}
+
+#[derive(Deserialize)]
+struct RerankResult {
+    index: usize,
+    relevance_score: f32,
+}
+
+impl HttpReranker {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        api_url: String,
+        model: String,
+        api_key: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            api_url,
+            model,
+            api_key,
+        }
+    }
+}
+
+impl Reranker for HttpReranker {
+    fn rerank<'a>(
+        &'a self,
+        query: &'a str,
+        documents: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<Vec<f32>>> {
+        async move {
+            let request_body = serde_json::to_string(&RerankRequest {
+                model: &self.model,
+                query,
+                documents,
+            })?;
+            let mut request = HttpRequest::builder()
+                .method(Method::POST)
+                .uri(&self.api_url)
+                .header("Content-Type", "application/json");
+            if let Some(api_key) = &self.api_key {
+                request = request.header("Authorization", format!("Bearer {api_key}"));
+            }
+            let request = request.body(AsyncBody::from(request_body))?;
+            let mut response = self.client.send(request).await?;
+            let mut body = String::new();
+            response.body_mut().read_to_string(&mut body).await?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "error during reranking, status: {:?}, body: {:?}",
+                    response.status(),
+                    body
+                ));
+            }
+
+            let response: RerankResponse =
+                serde_json::from_str(&body).context("failed to parse rerank response")?;
+            let mut scores = vec![f32::NEG_INFINITY; documents.len()];
+            for result in response.results {
+                let score = scores
+                    .get_mut(result.index)
+                    .context("rerank response has an out of range index")?;
+                *score = result.relevance_score;
+            }
+            Ok(scores)
+        }
+        .boxed()
+    }
+}
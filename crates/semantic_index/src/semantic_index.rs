@@ -1,23 +1,35 @@
 mod chunking;
 mod embedding;
+mod embedding_cache;
+mod index_status_indicator;
+mod lexical_search;
 mod project_index_debug_view;
+mod reranking;
+mod semantic_index_settings;
+mod semantic_search;
 
 use anyhow::{anyhow, Context as _, Result};
 use chunking::{chunk_text, Chunk};
-use collections::{Bound, HashMap, HashSet};
+use collections::{BTreeMap, Bound, HashMap, HashSet};
 pub use embedding::*;
+pub use embedding_cache::CachingEmbeddingProvider;
 use fs::Fs;
 use futures::{future::Shared, stream::StreamExt, FutureExt};
 use futures_batch::ChunksTimeoutStreamExt;
 use gpui::{
     AppContext, AsyncAppContext, BorrowAppContext, Context, Entity, EntityId, EventEmitter, Global,
-    Model, ModelContext, Subscription, Task, WeakModel,
+    Model, ModelContext, SharedString, Subscription, Task, WeakModel,
+};
+use heed::{
+    types::{Bytes, SerdeBincode, Str},
+    BytesDecode,
 };
-use heed::types::{SerdeBincode, Str};
 use language::LanguageRegistry;
+use lexical_search::{fuse_rankings, DocumentTerms, LexicalIndex};
 use parking_lot::Mutex;
 use project::{Entry, Project, ProjectEntryId, UpdatedEntriesSet, Worktree, WorktreeId};
 use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
 use smol::channel;
 use std::{
     cmp::Ordering,
@@ -26,16 +38,45 @@ use std::{
     num::NonZeroUsize,
     ops::Range,
     path::{Path, PathBuf},
-    sync::{Arc, Weak},
-    time::{Duration, SystemTime},
+    sync::{
+        atomic::{self, AtomicUsize},
+        Arc, Weak,
+    },
+    time::{Duration, Instant, SystemTime},
 };
-use util::ResultExt;
+use util::{paths::PathMatcher, ResultExt};
 use worktree::Snapshot;
 
+pub use index_status_indicator::{
+    IndexStatusIndicator, PauseIndexing, RebuildIndex, ResumeIndexing,
+};
 pub use project_index_debug_view::ProjectIndexDebugView;
+pub use reranking::*;
+pub use semantic_index_settings::*;
+pub use semantic_search::{SemanticSearch, ToggleSemanticSearch};
+
+/// How long to wait for more changes to files before re-indexing them, so that a file
+/// that's saved repeatedly is only re-indexed once.
+const REINDEXING_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The version of the way files are chunked and stored in the database. Bump it when
+/// either changes, so that existing indices are migrated when they're loaded.
+const INDEX_FORMAT_VERSION: u32 = 3;
+
+/// The name of the directory, within the index's, that stores the embedding cache.
+const EMBEDDING_CACHE_DIR_NAME: &str = "embedding-cache";
+
+/// The name of the database that stores the [`IndexMetadata`] of each worktree's index.
+const INDEX_METADATA_DB_NAME: &str = "index-metadata";
+
+pub fn init(cx: &mut AppContext) {
+    index_status_indicator::init(cx);
+    semantic_search::init(cx);
+}
 
 pub struct SemanticIndex {
     embedding_provider: Arc<dyn EmbeddingProvider>,
+    reranker: Option<Arc<dyn Reranker>>,
     db_connection: heed::Env,
     project_indices: HashMap<WeakModel<Project>, Model<ProjectIndex>>,
 }
@@ -48,6 +89,7 @@ impl SemanticIndex {
         embedding_provider: Arc<dyn EmbeddingProvider>,
         cx: &mut AsyncAppContext,
     ) -> Result<Self> {
+        let embedding_cache_path = db_path.join(EMBEDDING_CACHE_DIR_NAME);
         let db_connection = cx
             .background_executor()
             .spawn(async move {
@@ -62,13 +104,39 @@ impl SemanticIndex {
             .await
             .context("opening database connection")?;
 
+        let embedding_cache_size =
+            SemanticIndexSettings::try_read_global(cx, |settings| settings.embedding_cache_size)
+                .unwrap_or_default();
+        let embedding_provider = if embedding_cache_size > 0 {
+            cx.background_executor()
+                .spawn(async move {
+                    CachingEmbeddingProvider::new(
+                        embedding_provider,
+                        &embedding_cache_path,
+                        embedding_cache_size,
+                    )
+                })
+                .await
+                .map(|provider| Arc::new(provider) as Arc<dyn EmbeddingProvider>)
+                .context("opening embedding cache")?
+        } else {
+            embedding_provider
+        };
+
         Ok(SemanticIndex {
             db_connection,
             embedding_provider,
+            reranker: None,
             project_indices: HashMap::default(),
         })
     }
 
+    /// Sets the reranker that re-orders the search results of the projects indexed
+    /// from now on.
+    pub fn set_reranker(&mut self, reranker: Option<Arc<dyn Reranker>>) {
+        self.reranker = reranker;
+    }
+
     pub fn project_index(
         &mut self,
         project: Model<Project>,
@@ -94,12 +162,18 @@ impl SemanticIndex {
                         project,
                         self.db_connection.clone(),
                         self.embedding_provider.clone(),
+                        self.reranker.clone(),
                         cx,
                     )
                 })
             })
             .clone()
     }
+
+    /// Returns the index of the given project, if it has already been created.
+    pub fn existing_project_index(&self, project: &Model<Project>) -> Option<Model<ProjectIndex>> {
+        self.project_indices.get(&project.downgrade()).cloned()
+    }
 }
 
 pub struct ProjectIndex {
@@ -109,10 +183,21 @@ pub struct ProjectIndex {
     language_registry: Arc<LanguageRegistry>,
     fs: Arc<dyn Fs>,
     last_status: Status,
+    paused: bool,
+    /// The `include` and `exclude` globs the worktrees were last scanned with.
+    indexed_path_globs: (Vec<String>, Vec<String>),
+    /// The `additional_roots` the additional worktrees were last opened for.
+    additional_roots: Vec<AdditionalRoot>,
+    /// Worktrees outside of the project that are indexed too, with the weights of their
+    /// search results. The project only holds weak handles to them, as they're hidden.
+    additional_worktrees: Vec<(Model<Worktree>, f32)>,
     status_tx: channel::Sender<()>,
     embedding_provider: Arc<dyn EmbeddingProvider>,
+    reranker: Option<Arc<dyn Reranker>>,
     _maintain_status: Task<()>,
     _subscription: Subscription,
+    _observe_settings: Subscription,
+    _open_additional_roots: Task<()>,
 }
 
 #[derive(Clone)]
@@ -130,11 +215,15 @@ impl ProjectIndex {
         project: Model<Project>,
         db_connection: heed::Env,
         embedding_provider: Arc<dyn EmbeddingProvider>,
+        reranker: Option<Arc<dyn Reranker>>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
         let language_registry = project.read(cx).languages().clone();
         let fs = project.read(cx).fs().clone();
         let (status_tx, mut status_rx) = channel::unbounded();
+        let settings = SemanticIndexSettings::get_global(cx);
+        let indexed_path_globs = (settings.include.clone(), settings.exclude.clone());
+        let additional_roots = settings.additional_roots.clone();
         let mut this = ProjectIndex {
             db_connection,
             project: project.downgrade(),
@@ -143,8 +232,14 @@ impl ProjectIndex {
             fs,
             status_tx,
             last_status: Status::Idle,
+            paused: false,
+            indexed_path_globs,
+            additional_roots: Vec::new(),
+            additional_worktrees: Vec::new(),
             embedding_provider,
+            reranker,
             _subscription: cx.subscribe(&project, Self::handle_project_event),
+            _observe_settings: cx.observe_global::<SettingsStore>(Self::handle_settings_changed),
             _maintain_status: cx.spawn(|this, mut cx| async move {
                 while status_rx.next().await.is_some() {
                     if this
@@ -155,8 +250,10 @@ impl ProjectIndex {
                     }
                 }
             }),
+            _open_additional_roots: Task::ready(()),
         };
         this.update_worktree_indices(cx);
+        this.open_additional_roots(additional_roots, cx);
         this
     }
 
@@ -172,6 +269,81 @@ impl ProjectIndex {
         self.fs.clone()
     }
 
+    /// The number of chunks that have been embedded since the index was loaded or rebuilt.
+    pub fn embedded_chunk_count(&self, cx: &AppContext) -> usize {
+        self.worktree_indices(cx)
+            .iter()
+            .map(|index| {
+                index
+                    .read(cx)
+                    .stats
+                    .embedded_chunk_count
+                    .load(atomic::Ordering::Relaxed)
+            })
+            .sum()
+    }
+
+    /// The most recent error that occurred while indexing any of the worktrees.
+    pub fn last_error(&self, cx: &AppContext) -> Option<SharedString> {
+        self.worktree_indices(cx)
+            .iter()
+            .filter_map(|index| index.read(cx).stats.last_error.lock().clone())
+            .max_by_key(|(occurred_at, _)| *occurred_at)
+            .map(|(_, error)| error)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops indexing changes to the project's files until the index is resumed.
+    pub fn pause(&mut self, cx: &mut ModelContext<Self>) {
+        if self.paused {
+            return;
+        }
+        self.paused = true;
+        for index in self.worktree_indices(cx) {
+            index.update(cx, |index, _| index.stop_indexing());
+        }
+        self.update_status(cx);
+    }
+
+    /// Resumes indexing, catching up on the files that changed while it was paused.
+    pub fn resume(&mut self, cx: &mut ModelContext<Self>) {
+        if !self.paused {
+            return;
+        }
+        self.paused = false;
+        for index in self.worktree_indices(cx) {
+            index.update(cx, |index, cx| index.start_indexing(cx));
+        }
+        self.update_status(cx);
+    }
+
+    /// Discards all of the project's embeddings and indexes its files from scratch.
+    pub fn rebuild(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        self.paused = false;
+        let clear_indices = self
+            .worktree_indices(cx)
+            .into_iter()
+            .map(|index| index.update(cx, |index, cx| index.clear(cx)))
+            .collect::<Vec<_>>();
+        self.update_status(cx);
+
+        cx.spawn(|this, mut cx| async move {
+            let result = futures::future::try_join_all(clear_indices).await;
+            this.update(&mut cx, |this, cx| {
+                if !this.paused {
+                    for index in this.worktree_indices(cx) {
+                        index.update(cx, |index, cx| index.start_indexing(cx));
+                    }
+                }
+            })?;
+            result?;
+            Ok(())
+        })
+    }
+
     fn handle_project_event(
         &mut self,
         _: Model<Project>,
@@ -186,6 +358,89 @@ impl ProjectIndex {
         }
     }
 
+    fn handle_settings_changed(&mut self, cx: &mut ModelContext<Self>) {
+        let settings = SemanticIndexSettings::get_global(cx);
+        if settings.additional_roots != self.additional_roots {
+            let additional_roots = settings.additional_roots.clone();
+            self.open_additional_roots(additional_roots, cx);
+        }
+
+        let settings = SemanticIndexSettings::get_global(cx);
+        let indexed_path_globs = (settings.include.clone(), settings.exclude.clone());
+        if indexed_path_globs == self.indexed_path_globs {
+            return;
+        }
+        self.indexed_path_globs = indexed_path_globs;
+        if self.paused {
+            return;
+        }
+
+        // Rescanning the worktrees indexes the files that are now included and deletes
+        // the embeddings of the files that are now excluded.
+        for index in self.worktree_indices(cx) {
+            index.update(cx, |index, cx| {
+                index.stop_indexing();
+                index.start_indexing(cx);
+            });
+        }
+    }
+
+    /// Opens the directories outside of the project that are configured to be indexed
+    /// along with it, as hidden worktrees. Relative paths are resolved against the
+    /// project's first folder, and directories within the project are already indexed.
+    fn open_additional_roots(
+        &mut self,
+        additional_roots: Vec<AdditionalRoot>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let project_root = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path());
+        let open_worktrees = additional_roots
+            .iter()
+            .filter_map(|root| {
+                let abs_path = root.abs_path(project_root.as_deref())?;
+                let open_worktree = project.update(cx, |project, cx| {
+                    project.find_or_create_worktree(abs_path, false, cx)
+                });
+                let weight = root.weight;
+                Some(async move { (open_worktree.await, weight) })
+            })
+            .collect::<Vec<_>>();
+        self.additional_roots = additional_roots;
+
+        self._open_additional_roots = cx.spawn(|this, mut cx| async move {
+            let mut additional_worktrees = Vec::new();
+            for (result, weight) in futures::future::join_all(open_worktrees).await {
+                match result {
+                    Ok((worktree, relative_path)) if relative_path.as_os_str().is_empty() => {
+                        additional_worktrees.push((worktree, weight));
+                    }
+                    Ok(_) => {}
+                    Err(error) => log::error!("failed to open additional root: {error:#}"),
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                this.additional_worktrees = additional_worktrees;
+                this.update_worktree_indices(cx);
+            })
+            .ok();
+        });
+    }
+
+    /// How much to weigh the search results from the given worktree.
+    fn root_weight(&self, worktree_entity_id: EntityId) -> f32 {
+        self.additional_worktrees
+            .iter()
+            .find(|(worktree, _)| worktree.entity_id() == worktree_entity_id)
+            .map_or(1., |(_, weight)| *weight)
+    }
+
     fn update_worktree_indices(&mut self, cx: &mut ModelContext<Self>) {
         let Some(project) = self.project.upgrade() else {
             return;
@@ -194,6 +449,11 @@ impl ProjectIndex {
         let worktrees = project
             .read(cx)
             .visible_worktrees(cx)
+            .chain(
+                self.additional_worktrees
+                    .iter()
+                    .map(|(worktree, _)| worktree.clone()),
+            )
             .filter_map(|worktree| {
                 if worktree.read(cx).is_local() {
                     Some((worktree.entity_id(), worktree))
@@ -220,7 +480,10 @@ impl ProjectIndex {
                 let load_worktree = cx.spawn(|this, mut cx| async move {
                     let result = match worktree_index.await {
                         Ok(worktree_index) => {
-                            this.update(&mut cx, |this, _| {
+                            this.update(&mut cx, |this, cx| {
+                                if this.paused {
+                                    worktree_index.update(cx, |index, _| index.stop_indexing());
+                                }
                                 this.worktree_indices.insert(
                                     worktree_id,
                                     WorktreeIndexHandle::Loaded {
@@ -268,7 +531,9 @@ impl ProjectIndex {
             }
         }
 
-        let status = if any_loading {
+        let status = if self.paused {
+            Status::Paused
+        } else if any_loading {
             Status::Loading
         } else if let Some(remaining_count) = NonZeroUsize::new(indexing_count) {
             Status::Scanning { remaining_count }
@@ -280,19 +545,34 @@ impl ProjectIndex {
             self.last_status = status;
             cx.emit(status);
         }
+        cx.notify();
     }
 
     pub fn search(
         &self,
         query: String,
         limit: usize,
+        filter: SearchFilter,
         cx: &AppContext,
     ) -> Task<Result<Vec<SearchResult>>> {
-        let (chunks_tx, chunks_rx) = channel::bounded(1024);
+        let (files_tx, files_rx) = channel::bounded(1024);
+        let settings = Arc::new(SemanticIndexSettings::get_global(cx).clone());
+        let filter = Arc::new(filter);
+        let now = SystemTime::now();
+        // The reranker re-orders more results than are returned, to find the best ones.
+        let candidate_count = if self.reranker.is_some() {
+            limit.max(settings.reranking.candidate_count)
+        } else {
+            limit
+        };
         let mut worktree_scan_tasks = Vec::new();
-        for worktree_index in self.worktree_indices.values() {
+        for (worktree_entity_id, worktree_index) in &self.worktree_indices {
             let worktree_index = worktree_index.clone();
-            let chunks_tx = chunks_tx.clone();
+            let weight = self.root_weight(*worktree_entity_id);
+            let files_tx = files_tx.clone();
+            let settings = settings.clone();
+            let filter = filter.clone();
+            let language_registry = self.language_registry.clone();
             worktree_scan_tasks.push(cx.spawn(|cx| async move {
                 let index = match worktree_index {
                     WorktreeIndexHandle::Loading { index } => {
@@ -303,7 +583,8 @@ impl ProjectIndex {
 
                 index
                     .read_with(&cx, |index, cx| {
-                        let worktree_id = index.worktree.read(cx).id();
+                        let worktree = index.worktree.read(cx);
+                        let worktree_id = worktree.id();
                         let db_connection = index.db_connection.clone();
                         let db = index.db;
                         cx.background_executor().spawn(async move {
@@ -313,11 +594,25 @@ impl ProjectIndex {
                             let db_entries = db.iter(&txn).context("failed to iterate database")?;
                             for db_entry in db_entries {
                                 let (_key, db_embedded_file) = db_entry?;
-                                for chunk in db_embedded_file.chunks {
-                                    chunks_tx
-                                        .send((worktree_id, db_embedded_file.path.clone(), chunk))
-                                        .await?;
+                                if !settings.is_path_indexed(&db_embedded_file.path)
+                                    || !filter.matches_file(&db_embedded_file, now)
+                                {
+                                    continue;
                                 }
+                                if !filter.languages.is_empty() {
+                                    let language = language_registry
+                                        .language_for_file_path(&db_embedded_file.path)
+                                        .await
+                                        .ok();
+                                    if !language.map_or(false, |language| {
+                                        filter.matches_language(&language.name())
+                                    }) {
+                                        continue;
+                                    }
+                                }
+                                files_tx
+                                    .send((worktree_id, weight, db_embedded_file))
+                                    .await?;
                             }
                             anyhow::Ok(())
                         })
@@ -325,10 +620,12 @@ impl ProjectIndex {
                     .await
             }));
         }
-        drop(chunks_tx);
+        drop(files_tx);
 
         let project = self.project.clone();
+        let fs = self.fs.clone();
         let embedding_provider = self.embedding_provider.clone();
+        let reranker = self.reranker.clone();
         cx.spawn(|cx| async move {
             #[cfg(debug_assertions)]
             let embedding_query_start = std::time::Instant::now();
@@ -344,7 +641,10 @@ impl ProjectIndex {
 
             let mut results_by_worker = Vec::new();
             for _ in 0..cx.background_executor().num_cpus() {
-                results_by_worker.push(Vec::<WorktreeSearchResult>::new());
+                results_by_worker.push((
+                    Vec::<WorktreeSearchResult>::new(),
+                    LexicalIndex::<(WorktreeSearchResult, f32)>::new(&query),
+                ));
             }
 
             #[cfg(debug_assertions)]
@@ -352,25 +652,31 @@ impl ProjectIndex {
 
             cx.background_executor()
                 .scoped(|cx| {
-                    for results in results_by_worker.iter_mut() {
+                    for (results, lexical_index) in results_by_worker.iter_mut() {
                         cx.spawn(async {
-                            while let Ok((worktree_id, path, chunk)) = chunks_rx.recv().await {
-                                let score = chunk.embedding.similarity(&query_embedding);
-                                let ix = match results.binary_search_by(|probe| {
-                                    score.partial_cmp(&probe.score).unwrap_or(Ordering::Equal)
-                                }) {
-                                    Ok(ix) | Err(ix) => ix,
-                                };
-                                results.insert(
-                                    ix,
-                                    WorktreeSearchResult {
+                            while let Ok((worktree_id, weight, file)) = files_rx.recv().await {
+                                for chunk in file.chunks {
+                                    let score =
+                                        chunk.embedding.similarity(&query_embedding) * weight;
+                                    let result = WorktreeSearchResult {
                                         worktree_id,
-                                        path: path.clone(),
+                                        path: file.path.clone(),
                                         range: chunk.chunk.range.clone(),
                                         score,
-                                    },
-                                );
-                                results.truncate(limit);
+                                    };
+                                    if !lexical_index.is_empty() {
+                                        lexical_index
+                                            .add_document((result.clone(), weight), &chunk.terms);
+                                    }
+
+                                    let ix = match results.binary_search_by(|probe| {
+                                        score.partial_cmp(&probe.score).unwrap_or(Ordering::Equal)
+                                    }) {
+                                        Ok(ix) | Err(ix) => ix,
+                                    };
+                                    results.insert(ix, result);
+                                    results.truncate(candidate_count);
+                                }
                             }
                         });
                     }
@@ -381,37 +687,72 @@ impl ProjectIndex {
                 scan_task.log_err();
             }
 
-            project.read_with(&cx, |project, cx| {
-                let mut search_results = Vec::with_capacity(results_by_worker.len() * limit);
-                for worker_results in results_by_worker {
-                    search_results.extend(worker_results.into_iter().filter_map(|result| {
+            let mut vector_results = Vec::with_capacity(results_by_worker.len() * candidate_count);
+            let mut lexical_index = LexicalIndex::new(&query);
+            for (worker_results, worker_lexical_index) in results_by_worker {
+                vector_results.extend(worker_results);
+                lexical_index.merge(worker_lexical_index);
+            }
+            vector_results
+                .sort_unstable_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+            vector_results.truncate(candidate_count);
+            let mut lexical_results = lexical_index.search(candidate_count);
+            for ((_, weight), score) in &mut lexical_results {
+                *score *= *weight;
+            }
+            lexical_results.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            let lexical_results = lexical_results
+                .into_iter()
+                .map(|((result, _), _)| result)
+                .collect();
+            let mut results = fuse_rankings([vector_results, lexical_results], |result| {
+                (result.worktree_id, result.path.clone(), result.range.start)
+            });
+            if let Some(max_results_per_file) = filter.max_results_per_file {
+                let mut result_counts = HashMap::<(WorktreeId, Arc<Path>), usize>::default();
+                results.retain(|result| {
+                    let count = result_counts
+                        .entry((result.worktree_id, result.path.clone()))
+                        .or_default();
+                    *count += 1;
+                    *count <= max_results_per_file
+                });
+            }
+            results.truncate(candidate_count);
+
+            let mut search_results = project.read_with(&cx, |project, cx| {
+                results
+                    .into_iter()
+                    .filter_map(|result| {
                         Some(SearchResult {
                             worktree: project.worktree_for_id(result.worktree_id, cx)?,
                             path: result.path,
                             range: result.range,
                             score: result.score,
                         })
-                    }));
-                }
-                search_results.sort_unstable_by(|a, b| {
-                    b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
-                });
-                search_results.truncate(limit);
-
-                #[cfg(debug_assertions)]
-                {
-                    let search_elapsed = search_start.elapsed();
-                    log::debug!(
-                        "searched {} entries in {:?}",
-                        search_results.len(),
-                        search_elapsed
-                    );
-                    let embedding_query_elapsed = embedding_query_start.elapsed();
-                    log::debug!("embedding query took {:?}", embedding_query_elapsed);
-                }
+                    })
+                    .collect::<Vec<_>>()
+            })?;
 
-                search_results
-            })
+            #[cfg(debug_assertions)]
+            {
+                let search_elapsed = search_start.elapsed();
+                log::debug!(
+                    "searched {} entries in {:?}",
+                    search_results.len(),
+                    search_elapsed
+                );
+                let embedding_query_elapsed = embedding_query_start.elapsed();
+                log::debug!("embedding query took {:?}", embedding_query_elapsed);
+            }
+
+            if let Some(reranker) = reranker {
+                rerank_search_results(reranker.as_ref(), &query, &mut search_results, fs, &cx)
+                    .await
+                    .log_err();
+            }
+            search_results.truncate(limit);
+            Ok(search_results)
         })
     }
 
@@ -458,13 +799,106 @@ impl ProjectIndex {
     }
 }
 
+/// Re-orders search results by how relevant the reranker finds their text to the query.
+/// The results are left as they were if the reranker fails.
+async fn rerank_search_results(
+    reranker: &dyn Reranker,
+    query: &str,
+    results: &mut Vec<SearchResult>,
+    fs: Arc<dyn Fs>,
+    cx: &AsyncAppContext,
+) -> Result<()> {
+    let mut file_texts = HashMap::<PathBuf, Option<String>>::default();
+    let mut documents = Vec::with_capacity(results.len());
+    for result in results.iter() {
+        let abs_path = result
+            .worktree
+            .read_with(cx, |worktree, _| worktree.abs_path().join(&result.path))?;
+        if !file_texts.contains_key(&abs_path) {
+            let text = fs.load(&abs_path).await.log_err();
+            file_texts.insert(abs_path.clone(), text);
+        }
+        let document = file_texts[&abs_path]
+            .as_ref()
+            .and_then(|text| text.get(result.range.clone()))
+            .unwrap_or_default()
+            .to_string();
+        documents.push(document);
+    }
+
+    let documents = documents.iter().map(String::as_str).collect::<Vec<_>>();
+    let scores = reranker.rerank(query, &documents).await?;
+    anyhow::ensure!(
+        scores.len() == results.len(),
+        "reranker returned {} scores for {} results",
+        scores.len(),
+        results.len()
+    );
+    let mut scored_results = results.drain(..).zip(scores).collect::<Vec<_>>();
+    scored_results.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    results.extend(scored_results.into_iter().map(|(result, _)| result));
+    Ok(())
+}
+
+/// Narrows down the results of [`ProjectIndex::search`]. The default filter returns
+/// results from any indexed file.
+#[derive(Clone, Debug, Default)]
+pub struct SearchFilter {
+    /// Only return results from files in these languages, by name, such as "Rust".
+    /// Any language matches when this is empty.
+    pub languages: Vec<String>,
+    /// Only return results from files whose worktree-relative path matches.
+    pub path: Option<PathMatcher>,
+    /// The most results to return from a single file, so that one large file doesn't
+    /// crowd out the others.
+    pub max_results_per_file: Option<usize>,
+    /// Only return results from files modified this recently.
+    pub modified_within: Option<Duration>,
+}
+
+impl SearchFilter {
+    fn matches_file(&self, file: &EmbeddedFile, now: SystemTime) -> bool {
+        if let Some(path) = &self.path {
+            if !path.is_match(&file.path) {
+                return false;
+            }
+        }
+        if let Some(modified_within) = self.modified_within {
+            let Some(mtime) = file.mtime else {
+                return false;
+            };
+            // Files modified in the future, according to a skewed clock, count as recent.
+            if now
+                .duration_since(mtime)
+                .map_or(false, |age| age > modified_within)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn matches_language(&self, language_name: &str) -> bool {
+        self.languages.is_empty()
+            || self
+                .languages
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(language_name))
+    }
+}
+
 pub struct SearchResult {
     pub worktree: Model<Worktree>,
     pub path: Arc<Path>,
     pub range: Range<usize>,
+    /// The similarity of the chunk's embedding to the query's, scaled by the weight of
+    /// the root the chunk's file is in. Results are ordered by combining this with how
+    /// well the chunk's words match the query, and then by the reranker's scores when
+    /// one is set.
     pub score: f32,
 }
 
+#[derive(Clone)]
 pub struct WorktreeSearchResult {
     pub worktree_id: WorktreeId,
     pub path: Arc<Path>,
@@ -477,6 +911,7 @@ pub enum Status {
     Idle,
     Loading,
     Scanning { remaining_count: NonZeroUsize },
+    Paused,
 }
 
 impl EventEmitter<Status> for ProjectIndex {}
@@ -489,7 +924,9 @@ struct WorktreeIndex {
     fs: Arc<dyn Fs>,
     embedding_provider: Arc<dyn EmbeddingProvider>,
     entry_ids_being_indexed: Arc<IndexingEntrySet>,
-    _index_entries: Task<Result<()>>,
+    stats: Arc<IndexingStats>,
+    updated_entries_tx: Option<channel::Sender<UpdatedEntriesSet>>,
+    index_entries: Option<Task<Result<()>>>,
     _subscription: Subscription,
 }
 
@@ -504,6 +941,7 @@ impl WorktreeIndex {
         cx: &mut AppContext,
     ) -> Task<Result<Model<Self>>> {
         let worktree_abs_path = worktree.read(cx).abs_path();
+        let embedding_model = embedding_provider.model_id().to_string();
         cx.spawn(|mut cx| async move {
             let db = cx
                 .background_executor()
@@ -513,6 +951,19 @@ impl WorktreeIndex {
                         let mut txn = db_connection.write_txn()?;
                         let db_name = worktree_abs_path.to_string_lossy();
                         let db = db_connection.create_database(&mut txn, Some(&db_name))?;
+                        let metadata_db = db_connection
+                            .create_database(&mut txn, Some(INDEX_METADATA_DB_NAME))?;
+                        migrate_worktree_db(
+                            &mut txn,
+                            db,
+                            metadata_db,
+                            &db_name,
+                            &IndexMetadata {
+                                embedding_model: embedding_model.to_string(),
+                                format_version: INDEX_FORMAT_VERSION,
+                            },
+                        )
+                        .context("failed to migrate index")?;
                         txn.commit()?;
                         anyhow::Ok(db)
                     }
@@ -544,24 +995,60 @@ impl WorktreeIndex {
         embedding_provider: Arc<dyn EmbeddingProvider>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
-        let (updated_entries_tx, updated_entries_rx) = channel::unbounded();
-        let _subscription = cx.subscribe(&worktree, move |_this, _worktree, event, _cx| {
+        let _subscription = cx.subscribe(&worktree, |this, _worktree, event, _cx| {
             if let worktree::Event::UpdatedEntries(update) = event {
-                _ = updated_entries_tx.try_send(update.clone());
+                if let Some(updated_entries_tx) = &this.updated_entries_tx {
+                    _ = updated_entries_tx.try_send(update.clone());
+                }
             }
         });
 
-        Self {
+        let mut this = Self {
             db_connection,
             db,
             worktree,
             language_registry,
             fs,
             embedding_provider,
+            stats: Arc::new(IndexingStats::new(status.clone())),
             entry_ids_being_indexed: Arc::new(IndexingEntrySet::new(status)),
-            _index_entries: cx.spawn(|this, cx| Self::index_entries(this, updated_entries_rx, cx)),
+            updated_entries_tx: None,
+            index_entries: None,
             _subscription,
+        };
+        this.start_indexing(cx);
+        this
+    }
+
+    fn start_indexing(&mut self, cx: &mut ModelContext<Self>) {
+        if self.index_entries.is_some() {
+            return;
         }
+        let (updated_entries_tx, updated_entries_rx) = channel::unbounded();
+        self.updated_entries_tx = Some(updated_entries_tx);
+        self.index_entries =
+            Some(cx.spawn(|this, cx| Self::index_entries(this, updated_entries_rx, cx)));
+    }
+
+    /// Cancels any indexing in progress. Files that weren't fully indexed keep their old
+    /// modification time in the database, so they're indexed again once indexing restarts.
+    fn stop_indexing(&mut self) {
+        self.updated_entries_tx = None;
+        self.index_entries = None;
+    }
+
+    /// Stops indexing and deletes all of the worktree's embeddings.
+    fn clear(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        self.stop_indexing();
+        self.stats.reset();
+        let db_connection = self.db_connection.clone();
+        let db = self.db;
+        cx.background_executor().spawn(async move {
+            let mut txn = db_connection.write_txn()?;
+            db.clear(&mut txn)?;
+            txn.commit()?;
+            Ok(())
+        })
     }
 
     async fn index_entries(
@@ -569,14 +1056,36 @@ impl WorktreeIndex {
         updated_entries: channel::Receiver<UpdatedEntriesSet>,
         mut cx: AsyncAppContext,
     ) -> Result<()> {
+        let stats = this.update(&mut cx, |this, _| this.stats.clone())?;
         let index = this.update(&mut cx, |this, cx| this.index_entries_changed_on_disk(cx))?;
-        index.await.log_err();
+        index
+            .await
+            .inspect_err(|error| stats.set_error(error))
+            .log_err();
+
+        while let Ok(first_updated_entries) = updated_entries.recv().await {
+            cx.background_executor().timer(REINDEXING_DEBOUNCE).await;
+
+            // Only the latest change to each path matters.
+            let mut changes = BTreeMap::new();
+            for (path, entry_id, change) in iter::once(first_updated_entries)
+                .chain(iter::from_fn(|| updated_entries.try_recv().ok()))
+                .flat_map(|updated_entries| updated_entries.to_vec())
+            {
+                changes.insert(path, (entry_id, change));
+            }
+            let updated_entries: UpdatedEntriesSet = changes
+                .into_iter()
+                .map(|(path, (entry_id, change))| (path, entry_id, change))
+                .collect();
 
-        while let Ok(updated_entries) = updated_entries.recv().await {
             let index = this.update(&mut cx, |this, cx| {
                 this.index_updated_entries(updated_entries, cx)
             })?;
-            index.await.log_err();
+            index
+                .await
+                .inspect_err(|error| stats.set_error(error))
+                .log_err();
         }
 
         Ok(())
@@ -587,7 +1096,12 @@ impl WorktreeIndex {
         let worktree_abs_path = worktree.abs_path().clone();
         let scan = self.scan_entries(worktree, cx);
         let chunk = self.chunk_files(worktree_abs_path, scan.updated_entries, cx);
-        let embed = Self::embed_files(self.embedding_provider.clone(), chunk.files, cx);
+        let embed = Self::embed_files(
+            self.embedding_provider.clone(),
+            chunk.files,
+            self.stats.clone(),
+            cx,
+        );
         let persist = self.persist_embeddings(scan.deleted_entry_ranges, embed.files, cx);
         async move {
             futures::try_join!(scan.task, chunk.task, embed.task, persist)?;
@@ -604,7 +1118,12 @@ impl WorktreeIndex {
         let worktree_abs_path = worktree.abs_path().clone();
         let scan = self.scan_updated_entries(worktree, updated_entries.clone(), cx);
         let chunk = self.chunk_files(worktree_abs_path, scan.updated_entries, cx);
-        let embed = Self::embed_files(self.embedding_provider.clone(), chunk.files, cx);
+        let embed = Self::embed_files(
+            self.embedding_provider.clone(),
+            chunk.files,
+            self.stats.clone(),
+            cx,
+        );
         let persist = self.persist_embeddings(scan.deleted_entry_ranges, embed.files, cx);
         async move {
             futures::try_join!(scan.task, chunk.task, embed.task, persist)?;
@@ -618,6 +1137,7 @@ impl WorktreeIndex {
         let db_connection = self.db_connection.clone();
         let db = self.db;
         let entries_being_indexed = self.entry_ids_being_indexed.clone();
+        let settings = SemanticIndexSettings::get_global(cx).clone();
         let task = cx.background_executor().spawn(async move {
             let txn = db_connection
                 .read_txn()
@@ -629,7 +1149,11 @@ impl WorktreeIndex {
                 .peekable();
 
             let mut deletion_range: Option<(Bound<&str>, Bound<&str>)> = None;
-            for entry in worktree.files(false, 0) {
+            // The embeddings of excluded files are deleted, as they're skipped here.
+            for entry in worktree
+                .files(false, 0)
+                .filter(|entry| settings.is_path_indexed(&entry.path))
+            {
                 let entry_db_key = db_key_for_path(&entry.path);
 
                 let mut saved_mtime = None;
@@ -699,6 +1223,7 @@ impl WorktreeIndex {
         let (updated_entries_tx, updated_entries_rx) = channel::bounded(512);
         let (deleted_entry_ranges_tx, deleted_entry_ranges_rx) = channel::bounded(128);
         let entries_being_indexed = self.entry_ids_being_indexed.clone();
+        let settings = SemanticIndexSettings::get_global(cx).clone();
         let task = cx.background_executor().spawn(async move {
             for (path, entry_id, status) in updated_entries.iter() {
                 match status {
@@ -706,7 +1231,7 @@ impl WorktreeIndex {
                     | project::PathChange::Updated
                     | project::PathChange::AddedOrUpdated => {
                         if let Some(entry) = worktree.entry_for_id(*entry_id) {
-                            if entry.is_file() {
+                            if entry.is_file() && settings.is_path_indexed(&entry.path) {
                                 let handle = entries_being_indexed.insert(entry.id);
                                 updated_entries_tx.send((entry.clone(), handle)).await?;
                             }
@@ -742,6 +1267,8 @@ impl WorktreeIndex {
     ) -> ChunkFiles {
         let language_registry = self.language_registry.clone();
         let fs = self.fs.clone();
+        let db_connection = self.db_connection.clone();
+        let db = self.db;
         let (chunked_files_tx, chunked_files_rx) = channel::bounded(2048);
         let task = cx.spawn(|cx| async move {
             cx.background_executor()
@@ -764,8 +1291,24 @@ impl WorktreeIndex {
                                     .language_for_file_path(&entry.path)
                                     .await
                                     .ok();
+                                // Chunks that haven't changed since the file was last indexed
+                                // don't need to be embedded again.
+                                let previous_embeddings = db_connection
+                                    .read_txn()
+                                    .ok()
+                                    .and_then(|txn| {
+                                        db.get(&txn, &db_key_for_path(&entry.path)).ok().flatten()
+                                    })
+                                    .map(|file| {
+                                        file.chunks
+                                            .into_iter()
+                                            .map(|chunk| (chunk.chunk.digest, chunk.embedding))
+                                            .collect()
+                                    })
+                                    .unwrap_or_default();
                                 let chunked_file = ChunkedFile {
                                     chunks: chunk_text(&text, language.as_ref(), &entry.path),
+                                    previous_embeddings,
                                     handle,
                                     path: entry.path,
                                     mtime: entry.mtime,
@@ -792,6 +1335,7 @@ impl WorktreeIndex {
     fn embed_files(
         embedding_provider: Arc<dyn EmbeddingProvider>,
         chunked_files: channel::Receiver<ChunkedFile>,
+        stats: Arc<IndexingStats>,
         cx: &AppContext,
     ) -> EmbedFiles {
         let embedding_provider = embedding_provider.clone();
@@ -808,26 +1352,36 @@ impl WorktreeIndex {
                 let chunks: Vec<TextToEmbed> = chunked_files
                     .iter()
                     .flat_map(|file| {
-                        file.chunks.iter().map(|chunk| TextToEmbed {
-                            text: &file.text[chunk.range.clone()],
-                            digest: chunk.digest,
-                        })
+                        file.chunks
+                            .iter()
+                            .filter(|chunk| !file.previous_embeddings.contains_key(&chunk.digest))
+                            .map(|chunk| TextToEmbed {
+                                text: &file.text[chunk.range.clone()],
+                                digest: chunk.digest,
+                            })
                     })
                     .collect::<Vec<_>>();
 
                 let mut embeddings: Vec<Option<Embedding>> = Vec::new();
                 for embedding_batch in chunks.chunks(embedding_provider.batch_size()) {
-                    if let Some(batch_embeddings) =
-                        embedding_provider.embed(embedding_batch).await.log_err()
+                    if let Some(batch_embeddings) = embedding_provider
+                        .embed(embedding_batch)
+                        .await
+                        .inspect_err(|error| stats.set_error(error))
+                        .log_err()
                     {
                         if batch_embeddings.len() == embedding_batch.len() {
+                            stats.add_embedded_chunks(batch_embeddings.len());
                             embeddings.extend(batch_embeddings.into_iter().map(Some));
                             continue;
                         }
-                        log::error!(
+                        let error = anyhow!(
                             "embedding provider returned unexpected embedding count {}, expected {}",
-                            batch_embeddings.len(), embedding_batch.len()
+                            batch_embeddings.len(),
+                            embedding_batch.len()
                         );
+                        log::error!("{error}");
+                        stats.set_error(&error);
                     }
 
                     embeddings.extend(iter::repeat(None).take(embedding_batch.len()));
@@ -842,13 +1396,18 @@ impl WorktreeIndex {
                     };
 
                     let mut embedded_all_chunks = true;
-                    for (chunk, embedding) in
-                        chunked_file.chunks.into_iter().zip(embeddings.by_ref())
-                    {
+                    for chunk in chunked_file.chunks {
+                        let embedding = match chunked_file.previous_embeddings.get(&chunk.digest) {
+                            Some(embedding) => Some(embedding.clone()),
+                            None => embeddings.next().flatten(),
+                        };
                         if let Some(embedding) = embedding {
-                            embedded_file
-                                .chunks
-                                .push(EmbeddedChunk { chunk, embedding });
+                            let terms = DocumentTerms::new(&chunked_file.text[chunk.range.clone()]);
+                            embedded_file.chunks.push(EmbeddedChunk {
+                                chunk,
+                                embedding,
+                                terms,
+                            });
                         } else {
                             embedded_all_chunks = false;
                         }
@@ -968,6 +1527,8 @@ struct ChunkedFile {
     pub handle: IndexingEntryHandle,
     pub text: String,
     pub chunks: Vec<Chunk>,
+    /// The embeddings of the file's chunks when it was last indexed, by chunk digest.
+    pub previous_embeddings: HashMap<[u8; 32], Embedding>,
 }
 
 struct EmbedFiles {
@@ -986,6 +1547,8 @@ struct EmbeddedFile {
 struct EmbeddedChunk {
     chunk: Chunk,
     embedding: Embedding,
+    /// The words of the chunk's text, for lexical search.
+    terms: DocumentTerms,
 }
 
 /// The set of entries that are currently being indexed.
@@ -1032,6 +1595,95 @@ impl Drop for IndexingEntryHandle {
     }
 }
 
+/// Counts of the work done by a worktree index, shared with the tasks that index it.
+struct IndexingStats {
+    embedded_chunk_count: AtomicUsize,
+    last_error: Mutex<Option<(Instant, SharedString)>>,
+    tx: channel::Sender<()>,
+}
+
+impl IndexingStats {
+    fn new(tx: channel::Sender<()>) -> Self {
+        Self {
+            embedded_chunk_count: AtomicUsize::new(0),
+            last_error: Default::default(),
+            tx,
+        }
+    }
+
+    fn add_embedded_chunks(&self, count: usize) {
+        self.embedded_chunk_count
+            .fetch_add(count, atomic::Ordering::Relaxed);
+        self.tx.send_blocking(()).ok();
+    }
+
+    fn set_error(&self, error: &anyhow::Error) {
+        *self.last_error.lock() = Some((Instant::now(), format!("{error:#}").into()));
+        self.tx.send_blocking(()).ok();
+    }
+
+    fn reset(&self) {
+        self.embedded_chunk_count
+            .store(0, atomic::Ordering::Relaxed);
+        self.last_error.lock().take();
+        self.tx.send_blocking(()).ok();
+    }
+}
+
+/// Describes how a worktree's index was built, so that it's only used with the same
+/// embedding model and format.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct IndexMetadata {
+    embedding_model: String,
+    format_version: u32,
+}
+
+/// Makes a worktree's stored index usable with the current embedding model and format.
+///
+/// Embeddings computed by another model are all deleted. When only the format changed,
+/// or the index predates its metadata, every file is marked as changed so that it's chunked
+/// again, and chunks whose text is unchanged keep their embeddings. Files that can no
+/// longer be read are deleted, and indexed again from scratch.
+fn migrate_worktree_db(
+    txn: &mut heed::RwTxn,
+    db: heed::Database<Str, SerdeBincode<EmbeddedFile>>,
+    metadata_db: heed::Database<Str, SerdeBincode<IndexMetadata>>,
+    db_name: &str,
+    metadata: &IndexMetadata,
+) -> Result<()> {
+    let saved_metadata = metadata_db.get(txn, db_name).ok().flatten();
+    if saved_metadata.as_ref() == Some(metadata) {
+        return Ok(());
+    }
+
+    let same_model = saved_metadata.map_or(true, |saved_metadata| {
+        saved_metadata.embedding_model == metadata.embedding_model
+    });
+    if same_model {
+        let mut files = Vec::new();
+        let mut unreadable_keys = Vec::new();
+        for db_entry in db.remap_data_type::<Bytes>().iter(txn)? {
+            let (key, bytes) = db_entry?;
+            match SerdeBincode::<EmbeddedFile>::bytes_decode(bytes) {
+                Ok(file) => files.push((key.to_string(), file)),
+                Err(_) => unreadable_keys.push(key.to_string()),
+            }
+        }
+        for key in unreadable_keys {
+            db.delete(txn, &key)?;
+        }
+        for (key, mut file) in files {
+            file.mtime = None;
+            db.put(txn, &key, &file)?;
+        }
+    } else {
+        db.clear(txn)?;
+    }
+
+    metadata_db.put(txn, db_name, metadata)?;
+    Ok(())
+}
+
 fn db_key_for_path(path: &Arc<Path>) -> String {
     path.to_string_lossy().replace('/', "\0")
 }
@@ -1052,6 +1704,7 @@ mod tests {
             cx.set_global(store);
             language::init(cx);
             Project::init_settings(cx);
+            SemanticIndexSettings::register(cx);
             SettingsStore::update(cx, |store, cx| {
                 store.update_user_settings::<AllLanguageSettings>(cx, |_| {});
             });
@@ -1090,6 +1743,10 @@ mod tests {
         fn batch_size(&self) -> usize {
             self.batch_size
         }
+
+        fn model_id(&self) -> &str {
+            "test"
+        }
     }
 
     #[gpui::test]
@@ -1150,7 +1807,7 @@ mod tests {
             .update(|cx| {
                 let project_index = project_index.read(cx);
                 let query = "garbage in, garbage out";
-                project_index.search(query.into(), 4, cx)
+                project_index.search(query.into(), 4, SearchFilter::default(), cx)
             })
             .await
             .unwrap();
@@ -1183,6 +1840,370 @@ mod tests {
         assert!(content.contains("garbage in, garbage out"));
     }
 
+    /// Ranks documents by how few times they mention garbage.
+    struct TestReranker;
+
+    impl Reranker for TestReranker {
+        fn rerank<'a>(
+            &'a self,
+            _query: &'a str,
+            documents: &'a [&'a str],
+        ) -> BoxFuture<'a, Result<Vec<f32>>> {
+            let scores = documents
+                .iter()
+                .map(|document| -(document.matches("garbage").count() as f32))
+                .collect();
+            future::ready(Ok(scores)).boxed()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_search_with_reranker(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        init_test(cx);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let mut semantic_index = SemanticIndex::new(
+            temp_dir.path().into(),
+            Arc::new(TestEmbeddingProvider::new(16, |text| {
+                let score = if text.contains("garbage in, garbage out") {
+                    0.9
+                } else {
+                    -0.9
+                };
+                Ok(Embedding::new(vec![score, 0.1]))
+            })),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        semantic_index.set_reranker(Some(Arc::new(TestReranker)));
+
+        let project_path = Path::new("./fixture");
+        let project = cx
+            .spawn(|mut cx| async move { Project::example([project_path], &mut cx).await })
+            .await;
+
+        let project_index = cx.update(|cx| semantic_index.project_index(project.clone(), cx));
+        while project_index
+            .read_with(cx, |index, cx| index.path_count(cx))
+            .unwrap()
+            == 0
+        {
+            project_index.next_event(cx).await;
+        }
+
+        let results = cx
+            .update(|cx| {
+                project_index.read(cx).search(
+                    "garbage in, garbage out".into(),
+                    4,
+                    SearchFilter::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert!(results.len() > 1, "should have found some results");
+        assert!(
+            results.iter().any(|result| result.score > 0.9),
+            "the most similar chunk should still be returned"
+        );
+        assert!(
+            results[0].score < 0.,
+            "the reranker should have moved other chunks above the most similar one"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_excluded_paths(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update(cx, |store, cx| {
+                store.update_user_settings::<SemanticIndexSettings>(cx, |settings| {
+                    settings.exclude = Some(vec!["**/*.md".into()]);
+                });
+            });
+        });
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut semantic_index = SemanticIndex::new(
+            temp_dir.path().into(),
+            Arc::new(TestEmbeddingProvider::new(16, |_| {
+                Ok(Embedding::new(vec![1., 0.]))
+            })),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        let project_path = Path::new("./fixture");
+        let project = cx
+            .spawn(|mut cx| async move { Project::example([project_path], &mut cx).await })
+            .await;
+        let project_index = cx.update(|cx| semantic_index.project_index(project.clone(), cx));
+        while project_index
+            .read_with(cx, |index, cx| index.path_count(cx))
+            .unwrap()
+            == 0
+        {
+            project_index.next_event(cx).await;
+        }
+        assert_eq!(
+            project_index
+                .read_with(cx, |index, cx| index.path_count(cx))
+                .unwrap(),
+            1
+        );
+
+        let results = cx
+            .update(|cx| {
+                project_index
+                    .read(cx)
+                    .search("garbage in".into(), 4, SearchFilter::default(), cx)
+            })
+            .await
+            .unwrap();
+        assert!(!results.is_empty());
+        assert!(results
+            .iter()
+            .all(|result| result.path.as_ref() == Path::new("main.rs")));
+
+        // Files that are no longer excluded are indexed.
+        cx.update(|cx| {
+            SettingsStore::update(cx, |store, cx| {
+                store.update_user_settings::<SemanticIndexSettings>(cx, |settings| {
+                    settings.exclude = Some(Vec::new());
+                });
+            });
+        });
+        while project_index
+            .read_with(cx, |index, cx| index.path_count(cx))
+            .unwrap()
+            < 2
+        {
+            project_index.next_event(cx).await;
+        }
+    }
+
+    #[gpui::test]
+    async fn test_additional_roots(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        init_test(cx);
+        let fixture_path = std::fs::canonicalize("./fixture").unwrap();
+        cx.update(|cx| {
+            SettingsStore::update(cx, |store, cx| {
+                store.update_user_settings::<SemanticIndexSettings>(cx, |settings| {
+                    settings.additional_roots = Some(vec![AdditionalRoot {
+                        path: fixture_path.to_string_lossy().into_owned(),
+                        weight: 0.5,
+                    }]);
+                });
+            });
+        });
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project_dir = tempfile::tempdir().unwrap();
+        std::fs::write(project_dir.path().join("lib.rs"), "fn lib() {}").unwrap();
+        let mut semantic_index = SemanticIndex::new(
+            temp_dir.path().into(),
+            Arc::new(TestEmbeddingProvider::new(16, |_| {
+                Ok(Embedding::new(vec![1., 0.]))
+            })),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        let project_path = project_dir.path().to_path_buf();
+        let project = cx
+            .spawn(
+                |mut cx| async move { Project::example([project_path.as_path()], &mut cx).await },
+            )
+            .await;
+        let project_index = cx.update(|cx| semantic_index.project_index(project.clone(), cx));
+        while project_index
+            .read_with(cx, |index, cx| index.path_count(cx))
+            .unwrap()
+            < 3
+        {
+            project_index.next_event(cx).await;
+        }
+
+        let results = cx
+            .update(|cx| {
+                project_index
+                    .read(cx)
+                    .search("fn".into(), 4, SearchFilter::default(), cx)
+            })
+            .await
+            .unwrap();
+        let score_for_path = |path: &str| {
+            results
+                .iter()
+                .find(|result| result.path.as_ref() == Path::new(path))
+                .map(|result| result.score)
+        };
+        assert_eq!(score_for_path("lib.rs"), Some(1.));
+        assert_eq!(score_for_path("main.rs"), Some(0.5));
+        assert!(project.read_with(cx, |project, cx| project.visible_worktrees(cx).count() == 1));
+    }
+
+    #[gpui::test]
+    async fn test_pause_and_rebuild(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        init_test(cx);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut semantic_index = SemanticIndex::new(
+            temp_dir.path().into(),
+            Arc::new(TestEmbeddingProvider::new(16, |_| {
+                Ok(Embedding::new(vec![1., 0.]))
+            })),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        let project_path = Path::new("./fixture");
+        let project = cx
+            .spawn(|mut cx| async move { Project::example([project_path], &mut cx).await })
+            .await;
+        let project_index = cx.update(|cx| semantic_index.project_index(project.clone(), cx));
+        while project_index
+            .read_with(cx, |index, cx| index.path_count(cx))
+            .unwrap()
+            == 0
+        {
+            project_index.next_event(cx).await;
+        }
+
+        project_index.update(cx, |index, cx| index.pause(cx));
+        project_index.read_with(cx, |index, _| {
+            assert!(index.is_paused());
+            assert_eq!(index.status(), Status::Paused);
+        });
+
+        // Rebuilding discards the embeddings and resumes indexing.
+        project_index
+            .update(cx, |index, cx| index.rebuild(cx))
+            .await
+            .unwrap();
+        project_index.read_with(cx, |index, _| assert!(!index.is_paused()));
+        while project_index
+            .read_with(cx, |index, cx| index.path_count(cx))
+            .unwrap()
+            == 0
+        {
+            project_index.next_event(cx).await;
+        }
+        project_index.read_with(cx, |index, cx| {
+            assert!(index.embedded_chunk_count(cx) > 0);
+            assert_eq!(index.last_error(cx), None);
+        });
+    }
+
+    #[test]
+    fn test_search_filter() {
+        let now = SystemTime::now();
+        let file = |path: &str, age_in_days: u64| EmbeddedFile {
+            path: Path::new(path).into(),
+            mtime: Some(now - Duration::from_secs(age_in_days * 24 * 60 * 60)),
+            chunks: Vec::new(),
+        };
+
+        let filter = SearchFilter::default();
+        assert!(filter.matches_file(&file("src/main.rs", 365), now));
+        assert!(filter.matches_language("Rust"));
+
+        let filter = SearchFilter {
+            languages: vec!["rust".into()],
+            path: Some(PathMatcher::new(&["src/**".into()]).unwrap()),
+            modified_within: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+            ..Default::default()
+        };
+        assert!(filter.matches_file(&file("src/main.rs", 1), now));
+        assert!(!filter.matches_file(&file("src/main.rs", 30), now));
+        assert!(!filter.matches_file(&file("docs/main.md", 1), now));
+        assert!(filter.matches_language("Rust"));
+        assert!(!filter.matches_language("Markdown"));
+    }
+
+    #[test]
+    fn test_migrate_worktree_db() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_connection = unsafe {
+            heed::EnvOpenOptions::new()
+                .map_size(1024 * 1024)
+                .max_dbs(2)
+                .open(temp_dir.path())
+                .unwrap()
+        };
+        let mut txn = db_connection.write_txn().unwrap();
+        let db: heed::Database<Str, SerdeBincode<EmbeddedFile>> = db_connection
+            .create_database(&mut txn, Some("worktree"))
+            .unwrap();
+        let metadata_db = db_connection
+            .create_database(&mut txn, Some(INDEX_METADATA_DB_NAME))
+            .unwrap();
+
+        let path: Arc<Path> = Path::new("a.rs").into();
+        let file = EmbeddedFile {
+            path: path.clone(),
+            mtime: Some(SystemTime::UNIX_EPOCH),
+            chunks: vec![EmbeddedChunk {
+                chunk: Chunk {
+                    range: 0..1,
+                    digest: [1; 32],
+                    kind: None,
+                },
+                embedding: Embedding::new(vec![1.0, 0.0]),
+                terms: DocumentTerms::new("a"),
+            }],
+        };
+        db.put(&mut txn, &db_key_for_path(&path), &file).unwrap();
+        db.remap_data_type::<Bytes>()
+            .put(&mut txn, "unreadable", &[0xff])
+            .unwrap();
+
+        // An index without metadata keeps its embeddings, but its files are chunked again.
+        let metadata = IndexMetadata {
+            embedding_model: "test".into(),
+            format_version: INDEX_FORMAT_VERSION,
+        };
+        migrate_worktree_db(&mut txn, db, metadata_db, "worktree", &metadata).unwrap();
+        let files = db
+            .iter(&txn)
+            .unwrap()
+            .map(|entry| entry.unwrap().1)
+            .collect::<Vec<_>>();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].mtime, None);
+        assert_eq!(files[0].chunks[0].embedding, file.chunks[0].embedding);
+        assert_eq!(
+            metadata_db.get(&txn, "worktree").unwrap().as_ref(),
+            Some(&metadata)
+        );
+
+        // Embeddings computed by another model are discarded.
+        let metadata = IndexMetadata {
+            embedding_model: "other".into(),
+            format_version: INDEX_FORMAT_VERSION,
+        };
+        migrate_worktree_db(&mut txn, db, metadata_db, "worktree", &metadata).unwrap();
+        assert_eq!(db.len(&txn).unwrap(), 0);
+        assert_eq!(
+            metadata_db.get(&txn, "worktree").unwrap().as_ref(),
+            Some(&metadata)
+        );
+    }
+
     #[gpui::test]
     async fn test_embed_files(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -1200,6 +2221,7 @@ mod tests {
         }));
 
         let (indexing_progress_tx, _) = channel::unbounded();
+        let stats = Arc::new(IndexingStats::new(indexing_progress_tx.clone()));
         let indexing_entries = Arc::new(IndexingEntrySet::new(indexing_progress_tx));
 
         let (chunked_files_tx, chunked_files_rx) = channel::unbounded::<ChunkedFile>();
@@ -1214,8 +2236,10 @@ mod tests {
                     .map(|range| Chunk {
                         range,
                         digest: Default::default(),
+                        kind: None,
                     })
                     .collect(),
+                previous_embeddings: HashMap::default(),
             })
             .unwrap();
         chunked_files_tx
@@ -1229,14 +2253,17 @@ mod tests {
                     .map(|range| Chunk {
                         range,
                         digest: Default::default(),
+                        kind: None,
                     })
                     .collect(),
+                previous_embeddings: HashMap::default(),
             })
             .unwrap();
         chunked_files_tx.close();
 
-        let embed_files_task =
-            cx.update(|cx| WorktreeIndex::embed_files(provider.clone(), chunked_files_rx, cx));
+        let embed_files_task = cx.update(|cx| {
+            WorktreeIndex::embed_files(provider.clone(), chunked_files_rx, stats.clone(), cx)
+        });
         embed_files_task.task.await.unwrap();
 
         let mut embedded_files_rx = embed_files_task.files;
@@ -1259,5 +2286,171 @@ mod tests {
                 (provider.compute_embedding)("yz").unwrap(),
             ],
         );
+        assert!(stats.last_error.lock().is_some());
+    }
+
+    #[gpui::test]
+    async fn test_embed_files_reuses_previous_embeddings(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let embedded_texts = Arc::new(Mutex::new(Vec::new()));
+        let provider = Arc::new(TestEmbeddingProvider::new(3, {
+            let embedded_texts = embedded_texts.clone();
+            move |text| {
+                embedded_texts.lock().push(text.to_string());
+                Ok(Embedding::new(
+                    ('a'..='z')
+                        .map(|char| text.chars().filter(|c| *c == char).count() as f32)
+                        .collect(),
+                ))
+            }
+        }));
+
+        let (indexing_progress_tx, _) = channel::unbounded();
+        let stats = Arc::new(IndexingStats::new(indexing_progress_tx.clone()));
+        let indexing_entries = Arc::new(IndexingEntrySet::new(indexing_progress_tx));
+
+        let previous_embedding = Embedding::new(vec![1., 0.]);
+        let (chunked_files_tx, chunked_files_rx) = channel::unbounded::<ChunkedFile>();
+        chunked_files_tx
+            .send_blocking(ChunkedFile {
+                path: Path::new("test.md").into(),
+                mtime: None,
+                handle: indexing_entries.insert(ProjectEntryId::from_proto(0)),
+                text: "abcdefgh".to_string(),
+                chunks: vec![
+                    Chunk {
+                        range: 0..4,
+                        digest: [1; 32],
+                        kind: None,
+                    },
+                    Chunk {
+                        range: 4..8,
+                        digest: [2; 32],
+                        kind: None,
+                    },
+                ],
+                previous_embeddings: HashMap::from_iter([([1; 32], previous_embedding.clone())]),
+            })
+            .unwrap();
+        chunked_files_tx.close();
+
+        let embed_files_task = cx.update(|cx| {
+            WorktreeIndex::embed_files(provider.clone(), chunked_files_rx, stats.clone(), cx)
+        });
+        embed_files_task.task.await.unwrap();
+
+        let mut embedded_files_rx = embed_files_task.files;
+        let mut embedded_files = Vec::new();
+        while let Some((embedded_file, _)) = embedded_files_rx.next().await {
+            embedded_files.push(embedded_file);
+        }
+
+        // Only the chunk that changed was embedded again.
+        assert_eq!(*embedded_texts.lock(), ["efgh"]);
+        assert_eq!(
+            stats.embedded_chunk_count.load(atomic::Ordering::Relaxed),
+            1
+        );
+        assert_eq!(embedded_files.len(), 1);
+        assert_eq!(
+            embedded_files[0]
+                .chunks
+                .iter()
+                .map(|embedded_chunk| embedded_chunk.embedding.clone())
+                .collect::<Vec<Embedding>>(),
+            vec![
+                previous_embedding,
+                (provider.compute_embedding)("efgh").unwrap(),
+            ],
+        );
+    }
+
+    #[gpui::test]
+    fn test_embedding_provider_selection(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| {
+            client::init_settings(cx);
+            client::Client::new(
+                Arc::new(clock::FakeSystemClock::default()),
+                http_client::FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+
+        let select_provider = |settings: SemanticIndexSettingsContent,
+                               openai_api_key: Option<&str>,
+                               cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                SettingsStore::update(cx, |store, cx| {
+                    store.update_user_settings::<SemanticIndexSettings>(cx, |content| {
+                        *content = settings;
+                    });
+                });
+                let (provider, db_name) = SemanticIndexSettings::get_global(cx)
+                    .create_embedding_provider(
+                        client.clone(),
+                        openai_api_key.map(ToString::to_string),
+                    );
+                (provider.model_id().to_string(), db_name)
+            })
+        };
+
+        assert_eq!(
+            select_provider(Default::default(), None, cx),
+            (
+                "openai/text-embedding-3-small".into(),
+                "semantic-index-db.1.mdb".into()
+            )
+        );
+        assert_eq!(
+            select_provider(
+                SemanticIndexSettingsContent {
+                    embedding_provider: Some(EmbeddingProviderName::OpenAi),
+                    openai: Some(OpenAiEmbeddingSettingsContent {
+                        model: Some(OpenAiEmbeddingModelName::TextEmbedding3Large),
+                        api_url: None,
+                    }),
+                    ..Default::default()
+                },
+                Some("sk-123"),
+                cx
+            ),
+            (
+                "text-embedding-3-large".into(),
+                "semantic-index-db.openai.text-embedding-3-large.1.mdb".into()
+            )
+        );
+        assert_eq!(
+            select_provider(
+                SemanticIndexSettingsContent {
+                    embedding_provider: Some(EmbeddingProviderName::Ollama),
+                    ..Default::default()
+                },
+                None,
+                cx
+            ),
+            (
+                "nomic-embed-text".into(),
+                "semantic-index-db.ollama.nomic-embed-text.1.mdb".into()
+            )
+        );
+
+        // Without an API key, OpenAI falls back to zed.dev, and its embeddings are stored
+        // apart from OpenAI's.
+        assert_eq!(
+            select_provider(
+                SemanticIndexSettingsContent {
+                    embedding_provider: Some(EmbeddingProviderName::OpenAi),
+                    ..Default::default()
+                },
+                None,
+                cx
+            ),
+            (
+                "openai/text-embedding-3-small".into(),
+                "semantic-index-db.1.mdb".into()
+            )
+        );
     }
 }
@@ -0,0 +1,311 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use client::Client;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use util::paths::{home_dir, PathMatcher};
+
+use crate::{
+    CloudEmbeddingProvider, EmbeddingProvider, OllamaEmbeddingModel, OllamaEmbeddingProvider,
+    OpenAiEmbeddingModel, OpenAiEmbeddingProvider,
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SemanticIndexSettings {
+    pub embedding_provider: EmbeddingProviderName,
+    pub openai: OpenAiEmbeddingSettings,
+    pub ollama: OllamaEmbeddingSettings,
+    pub reranking: RerankingSettings,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub max_results_per_file: Option<usize>,
+    pub additional_roots: Vec<AdditionalRoot>,
+    pub embedding_cache_size: usize,
+    #[serde(skip)]
+    included_paths: Option<PathMatcher>,
+    #[serde(skip)]
+    excluded_paths: PathMatcher,
+}
+
+/// The service that computes the embeddings used for semantic search.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum EmbeddingProviderName {
+    #[default]
+    #[serde(rename = "zed.dev")]
+    ZedDotDev,
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "ollama")]
+    Ollama,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OpenAiEmbeddingSettings {
+    pub model: OpenAiEmbeddingModelName,
+    pub api_url: String,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum OpenAiEmbeddingModelName {
+    #[default]
+    #[serde(rename = "text-embedding-3-small")]
+    TextEmbedding3Small,
+    #[serde(rename = "text-embedding-3-large")]
+    TextEmbedding3Large,
+}
+
+impl From<OpenAiEmbeddingModelName> for OpenAiEmbeddingModel {
+    fn from(model: OpenAiEmbeddingModelName) -> Self {
+        match model {
+            OpenAiEmbeddingModelName::TextEmbedding3Small => Self::TextEmbedding3Small,
+            OpenAiEmbeddingModelName::TextEmbedding3Large => Self::TextEmbedding3Large,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OllamaEmbeddingSettings {
+    pub model: OllamaEmbeddingModelName,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum OllamaEmbeddingModelName {
+    #[default]
+    #[serde(rename = "nomic-embed-text")]
+    NomicEmbedText,
+    #[serde(rename = "mxbai-embed-large")]
+    MxbaiEmbedLarge,
+}
+
+impl From<OllamaEmbeddingModelName> for OllamaEmbeddingModel {
+    fn from(model: OllamaEmbeddingModelName) -> Self {
+        match model {
+            OllamaEmbeddingModelName::NomicEmbedText => Self::NomicEmbedText,
+            OllamaEmbeddingModelName::MxbaiEmbedLarge => Self::MxbaiEmbedLarge,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct RerankingSettings {
+    pub enabled: bool,
+    pub api_url: String,
+    pub model: String,
+    pub candidate_count: usize,
+}
+
+/// A directory outside of the project, such as the source of a dependency or a sibling
+/// repository, that's indexed along with it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AdditionalRoot {
+    /// The directory to index. Relative paths are resolved against the project's first
+    /// folder, and paths starting with `~` against the home directory.
+    pub path: String,
+    /// How much to weigh the directory's search results against the project's, whose
+    /// weight is 1.
+    #[serde(default = "default_root_weight")]
+    pub weight: f32,
+}
+
+fn default_root_weight() -> f32 {
+    1.
+}
+
+impl AdditionalRoot {
+    pub fn abs_path(&self, project_root: Option<&Path>) -> Option<PathBuf> {
+        let path = Path::new(&self.path);
+        if let Ok(path) = path.strip_prefix("~") {
+            Some(home_dir().join(path))
+        } else if path.is_absolute() {
+            Some(path.to_path_buf())
+        } else {
+            Some(project_root?.join(path))
+        }
+    }
+}
+
+impl SemanticIndexSettings {
+    /// The name of the database the embeddings are stored in. Each provider and model
+    /// produces different embeddings, so they're kept apart. Changes to the stored format
+    /// are migrated in place, when each worktree's index is loaded.
+    pub fn db_name(&self) -> String {
+        self.db_name_for(self.embedding_provider)
+    }
+
+    fn db_name_for(&self, embedding_provider: EmbeddingProviderName) -> String {
+        let (provider, model) = match embedding_provider {
+            EmbeddingProviderName::ZedDotDev => return "semantic-index-db.1.mdb".into(),
+            EmbeddingProviderName::OpenAi => (
+                "openai",
+                match self.openai.model {
+                    OpenAiEmbeddingModelName::TextEmbedding3Small => "text-embedding-3-small",
+                    OpenAiEmbeddingModelName::TextEmbedding3Large => "text-embedding-3-large",
+                },
+            ),
+            EmbeddingProviderName::Ollama => (
+                "ollama",
+                match self.ollama.model {
+                    OllamaEmbeddingModelName::NomicEmbedText => "nomic-embed-text",
+                    OllamaEmbeddingModelName::MxbaiEmbedLarge => "mxbai-embed-large",
+                },
+            ),
+        };
+        format!("semantic-index-db.{provider}.{model}.1.mdb")
+    }
+
+    /// The embedding provider selected in the settings, and the name of the database its
+    /// embeddings are stored in. Without an API key, the OpenAI provider falls back to the
+    /// zed.dev one, so that the index keeps working.
+    pub fn create_embedding_provider(
+        &self,
+        client: Arc<Client>,
+        openai_api_key: Option<String>,
+    ) -> (Arc<dyn EmbeddingProvider>, String) {
+        let provider: Arc<dyn EmbeddingProvider> = match self.embedding_provider {
+            EmbeddingProviderName::ZedDotDev => Arc::new(CloudEmbeddingProvider::new(client)),
+            EmbeddingProviderName::OpenAi => match openai_api_key {
+                Some(api_key) => Arc::new(OpenAiEmbeddingProvider::new(
+                    client.http_client(),
+                    self.openai.model.into(),
+                    self.openai.api_url.clone(),
+                    api_key,
+                )),
+                None => {
+                    log::warn!("no OpenAI API key found for embeddings, using zed.dev instead");
+                    let provider = Arc::new(CloudEmbeddingProvider::new(client));
+                    return (provider, self.db_name_for(EmbeddingProviderName::ZedDotDev));
+                }
+            },
+            EmbeddingProviderName::Ollama => Arc::new(OllamaEmbeddingProvider::new(
+                client.http_client(),
+                self.ollama.model.into(),
+            )),
+        };
+        (provider, self.db_name())
+    }
+
+    /// Whether the file at the given worktree-relative path is indexed and can be
+    /// returned in search results.
+    pub fn is_path_indexed(&self, path: &Path) -> bool {
+        if path
+            .ancestors()
+            .any(|ancestor| self.excluded_paths.is_match(ancestor))
+        {
+            return false;
+        }
+        self.included_paths
+            .as_ref()
+            .map_or(true, |included_paths| included_paths.is_match(path))
+    }
+}
+
+/// Semantic index configuration.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct SemanticIndexSettingsContent {
+    /// The service that computes the embeddings used for semantic search of
+    /// the codebase. "ollama" uses a model running on this machine, so that
+    /// codebase context works offline.
+    ///
+    /// Default: "zed.dev"
+    pub embedding_provider: Option<EmbeddingProviderName>,
+    /// Settings for the "openai" embedding provider.
+    pub openai: Option<OpenAiEmbeddingSettingsContent>,
+    /// Settings for the "ollama" embedding provider.
+    pub ollama: Option<OllamaEmbeddingSettingsContent>,
+    /// Settings for re-ranking search results with a cross-encoder before they're
+    /// used as context.
+    pub reranking: Option<RerankingSettingsContent>,
+    /// Only index the files matching these globs. When empty, all files are indexed.
+    ///
+    /// Default: []
+    pub include: Option<Vec<String>>,
+    /// Never index the files matching these globs, such as vendored dependencies,
+    /// generated code or lockfiles, nor return them in search results.
+    ///
+    /// Default: []
+    pub exclude: Option<Vec<String>>,
+    /// The most search results the assistant retrieves from a single file. When null,
+    /// there's no limit.
+    ///
+    /// Default: null
+    pub max_results_per_file: Option<usize>,
+    /// Directories outside of the project to index along with it, such as the source
+    /// of a dependency or a sibling repository, so that their code can be retrieved too.
+    ///
+    /// Default: []
+    pub additional_roots: Option<Vec<AdditionalRoot>>,
+    /// The most embeddings to keep in the cache shared by all projects, which saves
+    /// embedding identical text again. The least recently used are evicted first, and
+    /// 0 disables the cache.
+    ///
+    /// Default: 50000
+    pub embedding_cache_size: Option<usize>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct OpenAiEmbeddingSettingsContent {
+    /// The embedding model to use.
+    ///
+    /// Default: "text-embedding-3-small"
+    pub model: Option<OpenAiEmbeddingModelName>,
+    /// The URL of the OpenAI API. The API key is read from the `OPENAI_API_KEY`
+    /// environment variable, or from the key saved for this URL in the assistant.
+    ///
+    /// Default: "https://api.openai.com/v1"
+    pub api_url: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct OllamaEmbeddingSettingsContent {
+    /// The embedding model to use. It has to be pulled with Ollama first.
+    ///
+    /// Default: "nomic-embed-text"
+    pub model: Option<OllamaEmbeddingModelName>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct RerankingSettingsContent {
+    /// Whether to re-rank the best search results with a cross-encoder, which is more
+    /// precise than comparing embeddings but slower.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The URL of a `/rerank` API compatible with Cohere's, either hosted or running
+    /// on this machine. The API key, if one is needed, is the one saved for this URL.
+    ///
+    /// Default: "http://localhost:7997/rerank"
+    pub api_url: Option<String>,
+    /// The cross-encoder model to use.
+    ///
+    /// Default: "BAAI/bge-reranker-base"
+    pub model: Option<String>,
+    /// How many of the best search results to re-rank.
+    ///
+    /// Default: 20
+    pub candidate_count: Option<usize>,
+}
+
+impl Settings for SemanticIndexSettings {
+    const KEY: Option<&'static str> = Some("semantic_index");
+
+    type FileContent = SemanticIndexSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        let mut settings: Self = sources.json_merge()?;
+        if !settings.include.is_empty() {
+            settings.included_paths = Some(
+                PathMatcher::new(&settings.include)
+                    .context("Failed to parse globs from semantic_index.include")?,
+            );
+        }
+        settings.excluded_paths = PathMatcher::new(&settings.exclude)
+            .context("Failed to parse globs from semantic_index.exclude")?;
+        Ok(settings)
+    }
+}
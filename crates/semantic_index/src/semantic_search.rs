@@ -0,0 +1,303 @@
+use editor::{scroll::Autoscroll, Editor};
+use fs::Fs;
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, Task, View, ViewContext, WeakView,
+};
+use language::Point;
+use picker::{Picker, PickerDelegate};
+use project::{ProjectPath, WorktreeId};
+use std::{ops::Range, path::Path, sync::Arc, time::Duration};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{ProjectIndex, SearchFilter, SemanticIndex};
+
+actions!(semantic_index, [ToggleSemanticSearch]);
+
+/// How long to wait for the query to stop changing before searching, as each search
+/// embeds the query.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+const SEARCH_RESULT_LIMIT: usize = 20;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleSemanticSearch, cx| {
+            if !cx.has_global::<SemanticIndex>() {
+                return;
+            }
+            let project = workspace.project().clone();
+            let fs = project.read(cx).fs().clone();
+            let project_index =
+                cx.update_global(|index: &mut SemanticIndex, cx| index.project_index(project, cx));
+            let workspace_handle = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| {
+                SemanticSearch::new(workspace_handle, project_index, fs, cx)
+            });
+        });
+    })
+    .detach();
+}
+
+/// A modal that searches the project's semantic index and opens the chosen excerpt,
+/// independently of the assistant.
+pub struct SemanticSearch {
+    picker: View<Picker<SemanticSearchDelegate>>,
+}
+
+impl SemanticSearch {
+    fn new(
+        workspace: WeakView<Workspace>,
+        project_index: Model<ProjectIndex>,
+        fs: Arc<dyn Fs>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = SemanticSearchDelegate {
+            semantic_search: cx.view().downgrade(),
+            workspace,
+            project_index,
+            fs,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker =
+            cx.new_view(|cx| Picker::uniform_list(delegate, cx).max_height(Some(vh(0.75, cx))));
+        Self { picker }
+    }
+}
+
+impl FocusableView for SemanticSearch {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SemanticSearch {}
+impl ModalView for SemanticSearch {}
+
+impl Render for SemanticSearch {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(40.)).child(self.picker.clone())
+    }
+}
+
+pub struct SemanticSearchDelegate {
+    semantic_search: WeakView<SemanticSearch>,
+    workspace: WeakView<Workspace>,
+    project_index: Model<ProjectIndex>,
+    fs: Arc<dyn Fs>,
+    matches: Vec<SemanticSearchMatch>,
+    selected_index: usize,
+}
+
+struct SemanticSearchMatch {
+    worktree_id: WorktreeId,
+    path: Arc<Path>,
+    row: u32,
+    excerpt: SharedString,
+}
+
+impl PickerDelegate for SemanticSearchDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search the project by meaning…".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No results".into()
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        if query.trim().is_empty() {
+            self.matches.clear();
+            self.selected_index = 0;
+            cx.notify();
+            return Task::ready(());
+        }
+
+        let project_index = self.project_index.clone();
+        let fs = self.fs.clone();
+        cx.spawn(|picker, mut cx| async move {
+            cx.background_executor().timer(SEARCH_DEBOUNCE).await;
+
+            let Some(search) = project_index
+                .read_with(&cx, |index, cx| {
+                    index.search(query, SEARCH_RESULT_LIMIT, SearchFilter::default(), cx)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            let Some(results) = search.await.log_err() else {
+                return;
+            };
+
+            let mut matches = Vec::with_capacity(results.len());
+            for result in results {
+                let Some((worktree_id, abs_path)) = result
+                    .worktree
+                    .read_with(&cx, |worktree, _| {
+                        (worktree.id(), worktree.abs_path().join(&result.path))
+                    })
+                    .log_err()
+                else {
+                    continue;
+                };
+                let Some(text) = fs.load(&abs_path).await.log_err() else {
+                    continue;
+                };
+                let (row, excerpt) = excerpt_for_range(&text, result.range);
+                matches.push(SemanticSearchMatch {
+                    worktree_id,
+                    path: result.path,
+                    row,
+                    excerpt: excerpt.into(),
+                });
+            }
+
+            picker
+                .update(&mut cx, |picker, cx| {
+                    picker.delegate.matches = matches;
+                    picker.delegate.selected_index = 0;
+                    cx.notify();
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(search_match) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let row = search_match.row;
+        let project_path = ProjectPath {
+            worktree_id: search_match.worktree_id,
+            path: search_match.path.clone(),
+        };
+        let Some(open_task) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(project_path, None, true, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+
+        let semantic_search = self.semantic_search.clone();
+        cx.spawn(|_, mut cx| async move {
+            let item = open_task.await.log_err()?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        let point = Point::new(row, 0);
+                        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                            s.select_ranges([point..point])
+                        });
+                    })
+                    .log_err();
+            }
+            semantic_search
+                .update(&mut cx, |_, cx| cx.emit(DismissEvent))
+                .ok()
+        })
+        .detach();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.semantic_search
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let search_match = self.matches.get(ix)?;
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .inset(true)
+                .selected(selected)
+                .child(
+                    v_flex()
+                        .py_px()
+                        .child(Label::new(format!(
+                            "{}:{}",
+                            search_match.path.display(),
+                            search_match.row + 1
+                        )))
+                        .child(
+                            Label::new(search_match.excerpt.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
+
+/// Returns a search result's first non-blank line to show, along with its row, which
+/// is the one opened when the result is confirmed.
+fn excerpt_for_range(text: &str, range: Range<usize>) -> (u32, String) {
+    let start = range.start.min(text.len());
+    let end = range.end.clamp(start, text.len());
+    let mut row = text
+        .get(..start)
+        .map_or(0, |prefix| prefix.matches('\n').count() as u32);
+    let Some(chunk) = text.get(start..end) else {
+        return (row, String::new());
+    };
+    for line in chunk.lines() {
+        let line = line.trim();
+        if !line.is_empty() {
+            return (row, line.to_string());
+        }
+        row += 1;
+    }
+    (row.min(text.matches('\n').count() as u32), String::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excerpt_for_range() {
+        let text = "fn a() {}\n\n\n    fn b() {\n        c();\n    }\n";
+        // The row is the one of the line that's shown, not the one the range starts on.
+        let start = text.find("\n\n").unwrap() + 1;
+        assert_eq!(
+            excerpt_for_range(text, start..text.len()),
+            (3, "fn b() {".to_string())
+        );
+        let start = text.find("c();").unwrap();
+        assert_eq!(
+            excerpt_for_range(text, start..text.len()),
+            (4, "c();".to_string())
+        );
+        assert_eq!(
+            excerpt_for_range(text, 0..text.len()),
+            (0, "fn a() {}".into())
+        );
+        assert_eq!(excerpt_for_range(text, 100..200), (6, String::new()));
+    }
+}
@@ -1,7 +1,10 @@
-use crate::{settings_store::parse_json_with_comments, SettingsAssets};
+use crate::{
+    settings_store::{parse_json_with_comments, replace_value_in_json_text, to_pretty_json},
+    SettingsAssets,
+};
 use anyhow::{anyhow, Context, Result};
 use collections::BTreeMap;
-use gpui::{Action, AppContext, KeyBinding, SharedString};
+use gpui::{Action, AppContext, Global, KeyBinding, KeyBindingContextPredicate, SharedString};
 use schemars::{
     gen::{SchemaGenerator, SchemaSettings},
     schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
@@ -19,13 +22,57 @@ pub struct KeymapFile(Vec<KeymapBlock>);
 pub struct KeymapBlock {
     #[serde(default)]
     context: Option<String>,
+    /// The language of the editors that the bindings apply to, such as `Rust`. This is a
+    /// shorthand for `language == "rust"` in the context, which defaults to `Editor`.
+    #[serde(default)]
+    language: Option<String>,
     bindings: BTreeMap<String, KeymapAction>,
 }
 
+impl KeymapBlock {
+    /// Returns the context that the bindings apply in, including the block's language.
+    fn context(&self) -> Option<String> {
+        let Some(language) = &self.language else {
+            return self.context.clone();
+        };
+        let context = self
+            .context
+            .as_deref()
+            .map(str::trim)
+            .filter(|context| !context.is_empty())
+            .unwrap_or("Editor");
+        Some(format!(
+            "({context}) && language == \"{}\"",
+            language.to_lowercase()
+        ))
+    }
+}
+
 #[derive(Debug, Deserialize, Default, Clone)]
 #[serde(transparent)]
 pub struct KeymapAction(Value);
 
+impl KeymapAction {
+    /// Returns the name of the action, or `None` if the binding disables the keystrokes.
+    pub fn name(&self) -> Option<&str> {
+        match &self.0 {
+            Value::String(name) => Some(name),
+            Value::Array(items) => items.first()?.as_str(),
+            _ => None,
+        }
+    }
+}
+
+/// The keymap files that the key bindings were loaded from, in the order in which they
+/// were loaded: the default keymaps from the assets, then the user's keymap.
+#[derive(Clone, Default)]
+pub struct LoadedKeymaps {
+    pub asset_paths: Vec<&'static str>,
+    pub user_keymap: KeymapFile,
+}
+
+impl Global for LoadedKeymaps {}
+
 impl JsonSchema for KeymapAction {
     fn schema_name() -> String {
         "KeymapAction".into()
@@ -38,9 +85,13 @@ impl JsonSchema for KeymapAction {
 
 impl KeymapFile {
     pub fn load_asset(asset_path: &str, cx: &mut AppContext) -> Result<()> {
+        Self::parse_asset(asset_path)?.add_to_cx(cx)
+    }
+
+    pub fn parse_asset(asset_path: &str) -> Result<Self> {
         let content = asset_str::<SettingsAssets>(asset_path);
 
-        Self::parse(content.as_ref())?.add_to_cx(cx)
+        Self::parse(content.as_ref())
     }
 
     pub fn parse(content: &str) -> Result<Self> {
@@ -50,9 +101,108 @@ impl KeymapFile {
         parse_json_with_comments::<Self>(content)
     }
 
+    /// Returns the bindings of the keymap in order, as their context, keystrokes and action.
+    pub fn bindings(&self) -> impl Iterator<Item = (Option<String>, &str, &KeymapAction)> {
+        self.0.iter().flat_map(|block| {
+            let context = block.context();
+            block
+                .bindings
+                .iter()
+                .map(move |(keystrokes, action)| (context.clone(), keystrokes.as_str(), action))
+        })
+    }
+
+    /// Binds the keystrokes to the action in the given context, returning the new text of the
+    /// keymap file. The binding is added to the last block with that context, or to a new block
+    /// at the end of the file, preserving the comments and formatting of the rest of the file.
+    /// Binding keystrokes to `null` disables them.
+    pub fn new_text_for_binding_update(
+        text: &str,
+        context: Option<&str>,
+        keystrokes: &str,
+        action: &Value,
+    ) -> String {
+        const TAB_SIZE: usize = 2;
+
+        let mut new_block = serde_json::Map::new();
+        if let Some(context) = context {
+            new_block.insert("context".into(), context.into());
+        }
+        new_block.insert(
+            "bindings".into(),
+            serde_json::json!({ keystrokes: action.clone() }),
+        );
+        let new_block = Value::Object(new_block);
+
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&tree_sitter_json::language()).unwrap();
+        let syntax_tree = parser.parse(text, None).unwrap();
+        let root = syntax_tree.root_node();
+        let mut cursor = root.walk();
+        let array = root
+            .named_children(&mut cursor)
+            .find(|node| node.kind() == "array");
+        let Some(array) = array else {
+            let mut new_text = to_pretty_json(&Value::Array(vec![new_block]), TAB_SIZE, 0);
+            new_text.push('\n');
+            return new_text;
+        };
+
+        let mut cursor = array.walk();
+        let blocks = array
+            .named_children(&mut cursor)
+            .filter(|node| node.kind() == "object")
+            .collect::<Vec<_>>();
+        for block in blocks.iter().rev() {
+            let block_range = block.byte_range();
+            let Ok(existing_block) =
+                parse_json_with_comments::<KeymapBlock>(&text[block_range.clone()])
+            else {
+                continue;
+            };
+            if !same_context(existing_block.context().as_deref(), context) {
+                continue;
+            }
+            let (range, replacement) = replace_value_in_json_text(
+                &text[block_range.clone()],
+                &["bindings", keystrokes],
+                TAB_SIZE,
+                action,
+            );
+            let mut new_text = text.to_string();
+            new_text.replace_range(
+                block_range.start + range.start..block_range.start + range.end,
+                &replacement,
+            );
+            return new_text;
+        }
+
+        // Indent the new block like the existing ones.
+        let (insertion_offset, indent) = match blocks.last() {
+            Some(last_block) => {
+                let line_start = text[..last_block.start_byte()]
+                    .rfind('\n')
+                    .map_or(0, |ix| ix + 1);
+                (last_block.end_byte(), last_block.start_byte() - line_start)
+            }
+            None => (array.start_byte() + 1, TAB_SIZE),
+        };
+        let new_block = to_pretty_json(&new_block, TAB_SIZE, indent);
+        let new_text = if blocks.is_empty() {
+            format!("\n{:indent$}{new_block}\n", "")
+        } else {
+            format!(",\n{:indent$}{new_block}", "")
+        };
+        let mut text = text.to_string();
+        text.insert_str(insertion_offset, &new_text);
+        text
+    }
+
     pub fn add_to_cx(self, cx: &mut AppContext) -> Result<()> {
-        for KeymapBlock { context, bindings } in self.0 {
-            let bindings = bindings
+        for block in self.0 {
+            let context = block.context();
+            let bindings = block
+                .bindings
                 .into_iter()
                 .filter_map(|(keystroke, action)| {
                     let action = action.0;
@@ -137,6 +287,24 @@ impl KeymapFile {
     }
 }
 
+/// Returns whether two keymap contexts are the same, regardless of their formatting.
+pub fn same_context(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            match (
+                KeyBindingContextPredicate::parse(a),
+                KeyBindingContextPredicate::parse(b),
+            ) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => a.trim() == b.trim(),
+            }
+        }
+        (a, b) => {
+            a.map(str::trim).filter(|a| !a.is_empty()) == b.map(str::trim).filter(|b| !b.is_empty())
+        }
+    }
+}
+
 fn no_action() -> Box<dyn gpui::Action> {
     gpui::NoAction.boxed_clone()
 }
@@ -144,6 +312,9 @@ fn no_action() -> Box<dyn gpui::Action> {
 #[cfg(test)]
 mod tests {
     use crate::KeymapFile;
+    use gpui::{KeyBindingContextPredicate, KeyContext};
+    use serde_json::json;
+    use unindent::Unindent;
 
     #[test]
     fn can_deserialize_keymap_with_trailing_comma() {
@@ -160,4 +331,171 @@ mod tests {
         };
         KeymapFile::parse(json).unwrap();
     }
+
+    #[test]
+    fn test_binding_update() {
+        let keymap = r#"[
+              // Workspace bindings
+              {
+                "context": "Workspace",
+                "bindings": {
+                  "ctrl-p": "file_finder::Toggle"
+                }
+              },
+              {
+                "context": "Editor && mode == full",
+                "bindings": {
+                  "ctrl-k": "editor::CutToEndOfLine"
+                }
+              }
+            ]
+        "#
+        .unindent();
+
+        let new_keymap = KeymapFile::new_text_for_binding_update(
+            &keymap,
+            Some("Editor&&mode==full"),
+            "ctrl-shift-k",
+            &json!("editor::DeleteLine"),
+        );
+        pretty_assertions::assert_eq!(
+            new_keymap,
+            r#"[
+                  // Workspace bindings
+                  {
+                    "context": "Workspace",
+                    "bindings": {
+                      "ctrl-p": "file_finder::Toggle"
+                    }
+                  },
+                  {
+                    "context": "Editor && mode == full",
+                    "bindings": {
+                      "ctrl-shift-k": "editor::DeleteLine",
+                      "ctrl-k": "editor::CutToEndOfLine"
+                    }
+                  }
+                ]
+            "#
+            .unindent()
+        );
+
+        let new_keymap = KeymapFile::new_text_for_binding_update(
+            &keymap,
+            Some("Workspace"),
+            "ctrl-p",
+            &serde_json::Value::Null,
+        );
+        assert!(new_keymap.contains(r#""ctrl-p": null"#));
+
+        let new_keymap = KeymapFile::new_text_for_binding_update(
+            &keymap,
+            Some("Terminal"),
+            "ctrl-n",
+            &json!(["terminal::SendKeystroke", "ctrl-n"]),
+        );
+        pretty_assertions::assert_eq!(
+            new_keymap,
+            r#"[
+                  // Workspace bindings
+                  {
+                    "context": "Workspace",
+                    "bindings": {
+                      "ctrl-p": "file_finder::Toggle"
+                    }
+                  },
+                  {
+                    "context": "Editor && mode == full",
+                    "bindings": {
+                      "ctrl-k": "editor::CutToEndOfLine"
+                    }
+                  },
+                  {
+                    "context": "Terminal",
+                    "bindings": {
+                      "ctrl-n": [
+                        "terminal::SendKeystroke",
+                        "ctrl-n"
+                      ]
+                    }
+                  }
+                ]
+            "#
+            .unindent()
+        );
+
+        let new_keymap =
+            KeymapFile::new_text_for_binding_update("", None, "ctrl-q", &json!("zed::Quit"));
+        KeymapFile::parse(&new_keymap).unwrap();
+        assert!(new_keymap.contains(r#""ctrl-q": "zed::Quit""#));
+    }
+
+    #[test]
+    fn test_language_blocks() {
+        let keymap = r#"[
+              {
+                "language": "Rust",
+                "bindings": {
+                  "ctrl-r": "editor::Rename"
+                }
+              },
+              {
+                "context": "Editor && vim_mode == normal",
+                "language": "Shell Script",
+                "bindings": {
+                  "g r": "editor::Rename"
+                }
+              }
+            ]
+        "#
+        .unindent();
+
+        let keymap_file = KeymapFile::parse(&keymap).unwrap();
+        let contexts = keymap_file
+            .bindings()
+            .map(|(context, _, _)| context.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contexts,
+            [
+                r#"(Editor) && language == "rust""#,
+                r#"(Editor && vim_mode == normal) && language == "shell script""#,
+            ]
+        );
+
+        let predicate = KeyBindingContextPredicate::parse(&contexts[1]).unwrap();
+        let mut context = KeyContext::parse("Editor vim_mode=normal").unwrap();
+        assert!(!predicate.eval(&[context.clone()]));
+        context.set("language", "shell script");
+        assert!(predicate.eval(&[context]));
+
+        // Bindings in the same context are added to the language block.
+        let new_keymap = KeymapFile::new_text_for_binding_update(
+            &keymap,
+            Some(r#"Editor && language == "rust""#),
+            "ctrl-d",
+            &json!("editor::GoToDefinition"),
+        );
+        pretty_assertions::assert_eq!(
+            new_keymap,
+            r#"[
+                  {
+                    "language": "Rust",
+                    "bindings": {
+                      "ctrl-d": "editor::GoToDefinition",
+                      "ctrl-r": "editor::Rename"
+                    }
+                  },
+                  {
+                    "context": "Editor && vim_mode == normal",
+                    "language": "Shell Script",
+                    "bindings": {
+                      "g r": "editor::Rename"
+                    }
+                  }
+                ]
+            "#
+            .unindent()
+        );
+    }
 }
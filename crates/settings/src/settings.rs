@@ -11,9 +11,11 @@ use util::asset_str;
 
 pub use editable_setting_control::*;
 pub use json_schema::*;
-pub use keymap_file::KeymapFile;
+pub use keymap_file::{same_context, KeymapAction, KeymapFile, LoadedKeymaps};
 pub use settings_file::*;
-pub use settings_store::{Settings, SettingsLocation, SettingsSources, SettingsStore};
+pub use settings_store::{
+    parse_json_with_comments, Settings, SettingsLocation, SettingsSources, SettingsStore,
+};
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
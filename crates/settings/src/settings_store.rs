@@ -20,6 +20,10 @@ use util::{merge_non_null_json_value_into, RangeExt, ResultExt as _};
 
 use crate::SettingsJsonSchemaParams;
 
+/// The key of the user settings holding the named settings profiles, which can be
+/// layered on top of the rest of the user settings.
+const PROFILES_KEY: &str = "profiles";
+
 /// A value that can be defined as a user setting.
 ///
 /// Settings can be loaded from a combination of multiple JSON files.
@@ -110,6 +114,8 @@ pub struct SettingsSources<'a, T> {
     pub user: Option<&'a T>,
     /// The user settings for the current release channel.
     pub release_channel: Option<&'a T>,
+    /// The user settings for the active settings profile.
+    pub profile: Option<&'a T>,
     /// The project settings, ordered from least specific to most specific.
     pub project: &'a [&'a T],
 }
@@ -126,6 +132,7 @@ impl<'a, T: Serialize> SettingsSources<'a, T> {
             .into_iter()
             .chain(self.user)
             .chain(self.release_channel)
+            .chain(self.profile)
             .chain(self.project.iter().copied())
     }
 
@@ -164,6 +171,7 @@ pub struct SettingsStore {
     raw_user_settings: serde_json::Value,
     raw_extension_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(usize, Arc<Path>), serde_json::Value>,
+    active_profile: Option<String>,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -213,6 +221,7 @@ impl SettingsStore {
             raw_user_settings: serde_json::json!({}),
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            active_profile: None,
             tab_size_callback: Default::default(),
             setting_file_updates_tx,
             _setting_file_updates: cx.spawn(|cx| async move {
@@ -261,6 +270,15 @@ impl SettingsStore {
                     .log_err();
             }
 
+            let profile_value =
+                profile_settings(&self.raw_user_settings, self.active_profile.as_deref()).and_then(
+                    |profile_settings| {
+                        setting_value
+                            .deserialize_setting(profile_settings)
+                            .log_err()
+                    },
+                );
+
             let extension_value = setting_value
                 .deserialize_setting(&self.raw_extension_settings)
                 .log_err();
@@ -270,6 +288,7 @@ impl SettingsStore {
                     SettingsSources {
                         default: &default_settings,
                         release_channel: release_channel_value.as_ref(),
+                        profile: profile_value.as_ref(),
                         extensions: extension_value.as_ref(),
                         user: user_value.as_ref(),
                         project: &[],
@@ -315,6 +334,44 @@ impl SettingsStore {
         &self.raw_user_settings
     }
 
+    /// Returns the names of the settings profiles defined in the user settings.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.raw_user_settings
+            .get(PROFILES_KEY)
+            .and_then(serde_json::Value::as_object)
+            .map(|profiles| profiles.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of the settings profile layered on top of the user settings.
+    pub fn active_profile(&self) -> Option<&str> {
+        self.active_profile.as_deref()
+    }
+
+    /// Sets the settings profile layered on top of the user settings, or clears it.
+    pub fn set_active_profile(
+        &mut self,
+        profile: Option<String>,
+        cx: &mut AppContext,
+    ) -> Result<()> {
+        if self.active_profile == profile {
+            return Ok(());
+        }
+        self.active_profile = profile;
+        self.recompute_values(None, cx)
+    }
+
+    /// Get the default settings as a raw JSON value.
+    pub fn raw_default_settings(&self) -> &serde_json::Value {
+        &self.raw_default_settings
+    }
+
+    /// Get the settings of a worktree's root settings file as a raw JSON value.
+    pub fn raw_local_settings(&self, root_id: usize) -> Option<&serde_json::Value> {
+        self.raw_local_settings
+            .get(&(root_id, Path::new("").into()))
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Self {
         let mut this = Self::new(cx);
@@ -357,14 +414,37 @@ impl SettingsStore {
         &self,
         fs: Arc<dyn Fs>,
         update: impl 'static + Send + FnOnce(&mut T::FileContent, &AppContext),
+    ) {
+        self.update_settings_file_text(fs, move |old_text, store, cx| {
+            store.new_text_for_update::<T>(old_text, |content| update(content, cx))
+        });
+    }
+
+    /// Sets the value at the given key path in the user's settings file, regardless of
+    /// which setting it belongs to. Setting it to `null` removes it from the file.
+    pub fn update_settings_file_value(
+        &self,
+        fs: Arc<dyn Fs>,
+        key_path: Vec<String>,
+        value: serde_json::Value,
+    ) {
+        self.update_settings_file_text(fs, move |old_text, store, _| {
+            let key_path = key_path.iter().map(String::as_str).collect::<Vec<_>>();
+            store.new_text_for_value_update(old_text, &key_path, &value)
+        });
+    }
+
+    fn update_settings_file_text(
+        &self,
+        fs: Arc<dyn Fs>,
+        update: impl 'static + Send + FnOnce(String, &SettingsStore, &AppContext) -> String,
     ) {
         self.setting_file_updates_tx
             .unbounded_send(Box::new(move |cx: AsyncAppContext| {
                 async move {
                     let old_text = Self::load_settings(&fs).await?;
-                    let new_text = cx.read_global(|store: &SettingsStore, cx| {
-                        store.new_text_for_update::<T>(old_text, |content| update(content, cx))
-                    })?;
+                    let new_text =
+                        cx.read_global(|store: &SettingsStore, cx| update(old_text, store, cx))?;
                     let initial_path = paths::settings_file().as_path();
                     if fs.is_file(initial_path).await {
                         let resolved_path =
@@ -407,6 +487,28 @@ impl SettingsStore {
         new_text
     }
 
+    /// Sets the value at the given key path in a JSON file, returning the new text
+    /// for that JSON file. A `null` value removes the key from the file. The comments
+    /// and formatting of the rest of the file are preserved.
+    pub fn new_text_for_value_update(
+        &self,
+        old_text: String,
+        key_path: &[&str],
+        value: &serde_json::Value,
+    ) -> String {
+        let (range, replacement) = if value.is_null() {
+            match remove_key_from_json_text(&old_text, key_path) {
+                Some(range) => (range, String::new()),
+                None => return old_text,
+            }
+        } else {
+            replace_value_in_json_text(&old_text, key_path, self.json_tab_size(), value)
+        };
+        let mut new_text = old_text;
+        new_text.replace_range(range, &replacement);
+        new_text
+    }
+
     /// Updates the value of a setting in a JSON file, returning a list
     /// of edits to apply to the JSON file.
     pub fn edits_for_update<T: Settings>(
@@ -659,14 +761,26 @@ impl SettingsStore {
             }
         }
 
+        let settings_schema = combined_schema.schema.clone();
         for release_stage in ["dev", "nightly", "stable", "preview"] {
-            let schema = combined_schema.schema.clone();
             combined_schema
                 .schema
                 .object()
                 .properties
-                .insert(release_stage.to_string(), schema.into());
+                .insert(release_stage.to_string(), settings_schema.clone().into());
         }
+        combined_schema.schema.object().properties.insert(
+            PROFILES_KEY.to_string(),
+            SchemaObject {
+                instance_type: Some(schemars::schema::InstanceType::Object.into()),
+                object: Some(Box::new(schemars::schema::ObjectValidation {
+                    additional_properties: Some(Box::new(settings_schema.into())),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }
+            .into(),
+        );
 
         serde_json::to_value(&combined_schema).unwrap()
     }
@@ -703,6 +817,15 @@ impl SettingsStore {
                 }
             }
 
+            let profile_settings =
+                profile_settings(&self.raw_user_settings, self.active_profile.as_deref()).and_then(
+                    |profile_settings| {
+                        setting_value
+                            .deserialize_setting(profile_settings)
+                            .log_err()
+                    },
+                );
+
             // If the global settings file changed, reload the global value for the field.
             if changed_local_path.is_none() {
                 if let Some(value) = setting_value
@@ -712,6 +835,7 @@ impl SettingsStore {
                             extensions: extension_settings.as_ref(),
                             user: user_settings.as_ref(),
                             release_channel: release_channel_settings.as_ref(),
+                            profile: profile_settings.as_ref(),
                             project: &[],
                         },
                         cx,
@@ -759,6 +883,7 @@ impl SettingsStore {
                                 extensions: extension_settings.as_ref(),
                                 user: user_settings.as_ref(),
                                 release_channel: release_channel_settings.as_ref(),
+                                profile: profile_settings.as_ref(),
                                 project: &project_settings_stack.iter().collect::<Vec<_>>(),
                             },
                             cx,
@@ -818,6 +943,9 @@ impl<T: Settings> AnySettingValue for SettingValue<T> {
                 release_channel: values
                     .release_channel
                     .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
+                profile: values
+                    .profile
+                    .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
                 project: values
                     .project
                     .iter()
@@ -939,7 +1067,7 @@ fn update_value_in_json_text<'a>(
     }
 }
 
-fn replace_value_in_json_text(
+pub(crate) fn replace_value_in_json_text(
     text: &str,
     key_path: &[&str],
     tab_size: usize,
@@ -1056,7 +1184,63 @@ fn replace_value_in_json_text(
     }
 }
 
-fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len: usize) -> String {
+/// Returns the range to remove from a JSON file to remove the key at the given key path,
+/// along with its value and its separating comma, or `None` if the file doesn't have it.
+fn remove_key_from_json_text(text: &str, key_path: &[&str]) -> Option<Range<usize>> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_json::language()).unwrap();
+    let syntax_tree = parser.parse(text, None)?;
+
+    let mut object = syntax_tree.root_node().named_child(0)?;
+    let mut pair = None;
+    for key in key_path {
+        if object.kind() != "object" {
+            return None;
+        }
+        let mut cursor = object.walk();
+        let found_pair = object.named_children(&mut cursor).find(|child| {
+            child.kind() == "pair"
+                && child
+                    .child_by_field_name("key")
+                    .and_then(|key_node| text.get(key_node.byte_range()))
+                    .map_or(false, |key_text| key_text == format!("\"{key}\""))
+        })?;
+        object = found_pair.child_by_field_name("value")?;
+        pair = Some(found_pair);
+    }
+    let pair = pair?;
+
+    let line_start = text[..pair.start_byte()].rfind('\n').map_or(0, |ix| ix + 1);
+    let starts_line = text[line_start..pair.start_byte()].trim().is_empty();
+    match pair.next_sibling().filter(|sibling| sibling.kind() == ",") {
+        // Remove the pair up to its comma, along with its line when it's the only pair on it.
+        Some(comma) => {
+            let rest_of_line = &text[comma.end_byte()..];
+            let line_end = rest_of_line
+                .find('\n')
+                .map_or(text.len(), |ix| comma.end_byte() + ix + 1);
+            if starts_line && text[comma.end_byte()..line_end].trim().is_empty() {
+                Some(line_start..line_end)
+            } else {
+                let trailing_spaces =
+                    rest_of_line.len() - rest_of_line.trim_start_matches(' ').len();
+                Some(pair.start_byte()..comma.end_byte() + trailing_spaces)
+            }
+        }
+        // Remove the last pair along with the comma before it.
+        None => match pair.prev_sibling().filter(|sibling| sibling.kind() == ",") {
+            Some(comma) => Some(comma.start_byte()..pair.end_byte()),
+            None if starts_line => Some(line_start..pair.end_byte()),
+            None => Some(pair.start_byte()..pair.end_byte()),
+        },
+    }
+}
+
+pub(crate) fn to_pretty_json(
+    value: &impl Serialize,
+    indent_size: usize,
+    indent_prefix_len: usize,
+) -> String {
     const SPACES: [u8; 32] = [b' '; 32];
 
     debug_assert!(indent_size <= SPACES.len());
@@ -1083,6 +1267,14 @@ fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len:
     adjusted_text
 }
 
+/// Returns the settings of a settings profile, which are stored in the user settings.
+fn profile_settings<'a>(
+    raw_user_settings: &'a serde_json::Value,
+    profile: Option<&str>,
+) -> Option<&'a serde_json::Value> {
+    raw_user_settings.get(PROFILES_KEY)?.get(profile?)
+}
+
 pub fn parse_json_with_comments<T: DeserializeOwned>(content: &str) -> Result<T> {
     Ok(serde_json_lenient::from_str(content)?)
 }
@@ -1222,6 +1414,83 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_settings_profiles(cx: &mut AppContext) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<UserSettings>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(
+                r#"{
+                    "user": { "age": 31 },
+                    "profiles": {
+                        "work": { "user": { "staff": true } },
+                        "streaming": { "user": { "name": "Anonymous" } }
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(store.profile_names(), ["work", "streaming"]);
+        assert_eq!(store.active_profile(), None);
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: false,
+            }
+        );
+
+        store
+            .set_active_profile(Some("work".to_string()), cx)
+            .unwrap();
+        assert_eq!(store.active_profile(), Some("work"));
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: true,
+            }
+        );
+
+        // The profile is reloaded along with the user settings.
+        store.register_setting::<TurboSetting>(cx);
+        store
+            .set_user_settings(
+                r#"{
+                    "turbo": false,
+                    "profiles": { "work": { "turbo": true } }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(true));
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 30,
+                staff: false,
+            }
+        );
+
+        store.set_active_profile(None, cx).unwrap();
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(false));
+    }
+
     #[gpui::test]
     fn test_setting_store_assign_json_before_register(cx: &mut AppContext) {
         let mut store = SettingsStore::new(cx);
@@ -1385,6 +1654,87 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_setting_store_update_value(cx: &mut AppContext) {
+        let store = SettingsStore::new(cx);
+
+        let old_json = r#"{
+            // The user's name.
+            "user": { "name": "Max" },
+            "one": 1
+        }"#
+        .unindent();
+        let new_json = store.new_text_for_value_update(
+            old_json.clone(),
+            &["user", "name"],
+            &serde_json::json!("Nathan"),
+        );
+        pretty_assertions::assert_eq!(
+            new_json,
+            r#"{
+                // The user's name.
+                "user": { "name": "Nathan" },
+                "one": 1
+            }"#
+            .unindent()
+        );
+
+        // Setting a value to `null` removes it, along with its comma.
+        let new_json =
+            store.new_text_for_value_update(old_json.clone(), &["one"], &serde_json::Value::Null);
+        pretty_assertions::assert_eq!(
+            new_json,
+            r#"{
+                // The user's name.
+                "user": { "name": "Max" }
+            }"#
+            .unindent()
+        );
+        let new_json =
+            store.new_text_for_value_update(old_json.clone(), &["user"], &serde_json::Value::Null);
+        pretty_assertions::assert_eq!(
+            new_json,
+            r#"{
+                // The user's name.
+                "one": 1
+            }"#
+            .unindent()
+        );
+        let new_json = store.new_text_for_value_update(
+            old_json.clone(),
+            &["user", "name"],
+            &serde_json::Value::Null,
+        );
+        pretty_assertions::assert_eq!(
+            new_json,
+            r#"{
+                // The user's name.
+                "user": {  },
+                "one": 1
+            }"#
+            .unindent()
+        );
+        let new_json =
+            store.new_text_for_value_update(old_json.clone(), &["two"], &serde_json::Value::Null);
+        pretty_assertions::assert_eq!(new_json, old_json);
+
+        let new_json = store.new_text_for_value_update(
+            String::new(),
+            &["user", "staff"],
+            &serde_json::json!(true),
+        );
+        pretty_assertions::assert_eq!(
+            new_json,
+            r#"{
+                "user": {
+                    "staff": true
+                }
+            }
+            "#
+            .unindent()
+        );
+    }
+
     fn check_settings_update<T: Settings>(
         store: &mut SettingsStore,
         old_json: String,
@@ -0,0 +1,365 @@
+use db::kvp::KEY_VALUE_STORE;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusableView, Render, UpdateGlobal, View,
+    ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use settings::SettingsStore;
+use std::sync::Arc;
+use ui::{prelude::*, v_flex, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ui::HighlightedLabel, ModalView, Workspace};
+
+actions!(settings_profile_selector, [Toggle]);
+
+const ACTIVE_PROFILE_KEY: &str = "active_settings_profile";
+
+/// The name of the entry that deactivates the settings profile.
+const NO_PROFILE: &str = "Default";
+
+pub fn init(cx: &mut AppContext) {
+    // Restore the profile that was active when Zed was last closed.
+    cx.spawn(|mut cx| async move {
+        let profile = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(ACTIVE_PROFILE_KEY) })
+            .await
+            .log_err()
+            .flatten()?;
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                // Keep the profile chosen while the previous one was being read.
+                if store.active_profile().is_none() {
+                    store.set_active_profile(Some(profile), cx).log_err();
+                }
+            })
+        })
+        .log_err()
+    })
+    .detach();
+
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(toggle);
+        },
+    )
+    .detach();
+}
+
+pub fn toggle(workspace: &mut Workspace, _: &Toggle, cx: &mut ViewContext<Workspace>) {
+    workspace.toggle_modal(cx, |cx| {
+        let delegate = SettingsProfileSelectorDelegate::new(cx.view().downgrade(), cx);
+        SettingsProfileSelector::new(delegate, cx)
+    });
+}
+
+impl ModalView for SettingsProfileSelector {}
+
+pub struct SettingsProfileSelector {
+    picker: View<Picker<SettingsProfileSelectorDelegate>>,
+}
+
+impl EventEmitter<DismissEvent> for SettingsProfileSelector {}
+
+impl FocusableView for SettingsProfileSelector {
+    fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for SettingsProfileSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl SettingsProfileSelector {
+    pub fn new(delegate: SettingsProfileSelectorDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+pub struct SettingsProfileSelectorDelegate {
+    /// The names of the profiles, after the entry deactivating the profile.
+    profiles: Vec<String>,
+    matches: Vec<StringMatch>,
+    original_profile: Option<String>,
+    selection_completed: bool,
+    selected_index: usize,
+    view: WeakView<SettingsProfileSelector>,
+}
+
+impl SettingsProfileSelectorDelegate {
+    fn new(
+        weak_view: WeakView<SettingsProfileSelector>,
+        cx: &mut ViewContext<SettingsProfileSelector>,
+    ) -> Self {
+        let store = cx.global::<SettingsStore>();
+        let original_profile = store.active_profile().map(ToString::to_string);
+        let profiles = [NO_PROFILE.to_string()]
+            .into_iter()
+            .chain(store.profile_names())
+            .collect::<Vec<_>>();
+        let matches = profiles
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, profile)| StringMatch {
+                candidate_id,
+                score: 0.0,
+                positions: Default::default(),
+                string: profile.clone(),
+            })
+            .collect();
+        let selected_index = original_profile
+            .as_ref()
+            .and_then(|original_profile| {
+                profiles
+                    .iter()
+                    .skip(1)
+                    .position(|profile| profile == original_profile)
+            })
+            .map_or(0, |ix| ix + 1);
+        Self {
+            profiles,
+            matches,
+            original_profile,
+            selected_index,
+            selection_completed: false,
+            view: weak_view,
+        }
+    }
+
+    fn selected_profile(&self) -> Option<String> {
+        let mat = self.matches.get(self.selected_index)?;
+        (mat.candidate_id > 0).then(|| mat.string.clone())
+    }
+
+    fn show_selected_profile(&self, cx: &mut AppContext) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            Self::set_profile((mat.candidate_id > 0).then(|| mat.string.clone()), cx);
+        }
+    }
+
+    fn set_profile(profile: Option<String>, cx: &mut AppContext) {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.set_active_profile(profile, cx).log_err();
+        });
+    }
+}
+
+impl PickerDelegate for SettingsProfileSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select Settings Profile...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        self.selection_completed = true;
+
+        let profile = self.selected_profile();
+        Self::set_profile(profile.clone(), cx);
+        cx.background_executor()
+            .spawn(async move {
+                match profile {
+                    Some(profile) => {
+                        KEY_VALUE_STORE
+                            .write_kvp(ACTIVE_PROFILE_KEY.to_string(), profile)
+                            .await
+                    }
+                    None => {
+                        KEY_VALUE_STORE
+                            .delete_kvp(ACTIVE_PROFILE_KEY.to_string())
+                            .await
+                    }
+                }
+            })
+            .detach_and_log_err(cx);
+
+        self.view
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        if !self.selection_completed {
+            Self::set_profile(self.original_profile.clone(), cx);
+            self.selection_completed = true;
+        }
+
+        self.view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+        self.show_selected_profile(cx);
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(id, profile)| StringMatchCandidate {
+                id,
+                char_bag: profile.as_str().into(),
+                string: profile.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = this
+                    .delegate
+                    .selected_index
+                    .min(this.delegate.matches.len().saturating_sub(1));
+                this.delegate.show_selected_profile(cx);
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let profile_match = &self.matches[ix];
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    profile_match.string.clone(),
+                    profile_match.positions.clone(),
+                )),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_settings_profile_selector(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_user_settings(r#"{ "profiles": { "work": {}, "streaming": {} } }"#, cx)
+                    .unwrap();
+            })
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Selecting a profile previews it, and dismissing the selector restores the
+        // original one.
+        open_selector(&workspace, cx);
+        cx.dispatch_action(menu::SelectNext);
+        assert_eq!(active_profile(cx).as_deref(), Some("work"));
+        cx.dispatch_action(menu::Cancel);
+        assert_eq!(active_profile(cx), None);
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace
+                .active_modal::<SettingsProfileSelector>(cx)
+                .is_none());
+        });
+
+        open_selector(&workspace, cx);
+        cx.dispatch_action(menu::SelectLast);
+        cx.dispatch_action(menu::Confirm);
+        assert_eq!(active_profile(cx).as_deref(), Some("streaming"));
+
+        // The selector opens with the active profile selected.
+        let selector = open_selector(&workspace, cx);
+        selector.update(cx, |selector, cx| {
+            assert_eq!(selector.picker.read(cx).delegate.selected_index, 2);
+        });
+
+        // Choosing the default entry deactivates the profile.
+        cx.dispatch_action(menu::SelectFirst);
+        cx.dispatch_action(menu::Confirm);
+        assert_eq!(active_profile(cx), None);
+    }
+
+    fn open_selector(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<SettingsProfileSelector> {
+        workspace.update(cx, |workspace, cx| toggle(workspace, &Toggle, cx));
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<SettingsProfileSelector>(cx)
+                .unwrap()
+        })
+    }
+
+    fn active_profile(cx: &mut VisualTestContext) -> Option<String> {
+        cx.update(|cx| {
+            cx.global::<SettingsStore>()
+                .active_profile()
+                .map(ToString::to_string)
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            editor::init(cx);
+            crate::init(cx);
+            state
+        })
+    }
+}
@@ -0,0 +1,939 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use collections::HashMap;
+use editor::{Editor, EditorElement, EditorStyle};
+use fs::Fs;
+use gpui::{
+    uniform_list, AppContext, EventEmitter, FocusHandle, FocusableView, KeyDownEvent, Keystroke,
+    Subscription, TextStyle, UniformListScrollHandle, View,
+};
+use serde_json::Value;
+use settings::{same_context, KeymapFile, LoadedKeymaps, Settings};
+use theme::ThemeSettings;
+use ui::{prelude::*, Tooltip};
+use workspace::item::{Item, ItemEvent};
+use workspace::Workspace;
+
+/// The most keystrokes a recorded binding can have.
+const MAX_RECORDED_KEYSTROKES: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BindingSource {
+    Default,
+    User,
+}
+
+/// A binding from one of the keymap files.
+#[derive(Clone, Debug, PartialEq)]
+struct BindingEntry {
+    keystrokes: String,
+    context: Option<String>,
+    /// The bound action, or `None` if the binding disables the keystrokes.
+    action_name: Option<String>,
+    source: BindingSource,
+    /// Whether a later binding of the same keystrokes in the same context takes precedence.
+    overridden: bool,
+    /// The indices of the bindings of other actions to the same keystrokes in the same
+    /// context, which either replace this binding or are replaced by it.
+    conflicts: Vec<usize>,
+}
+
+/// The bindings of the keymap files, in the order in which they were loaded.
+#[derive(Default)]
+struct Bindings {
+    entries: Vec<BindingEntry>,
+    /// The indices of the bindings of each keystroke sequence, written as in
+    /// [`normalized_keystrokes`].
+    by_keystrokes: HashMap<String, Vec<usize>>,
+    /// The indices of the bindings of each action.
+    by_action: HashMap<String, Vec<usize>>,
+}
+
+impl Bindings {
+    fn new(keymaps: &[(BindingSource, KeymapFile)]) -> Self {
+        let mut entries = keymaps
+            .iter()
+            .flat_map(|(source, keymap)| {
+                keymap
+                    .bindings()
+                    .map(move |(context, keystrokes, action)| BindingEntry {
+                        keystrokes: keystrokes.to_string(),
+                        context,
+                        action_name: action.name().map(ToString::to_string),
+                        source: *source,
+                        overridden: false,
+                        conflicts: Vec::new(),
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let mut by_keystrokes = HashMap::<String, Vec<usize>>::default();
+        let mut by_action = HashMap::<String, Vec<usize>>::default();
+        for (ix, entry) in entries.iter().enumerate() {
+            by_keystrokes
+                .entry(normalized_keystrokes(&entry.keystrokes))
+                .or_default()
+                .push(ix);
+            if let Some(action_name) = &entry.action_name {
+                by_action.entry(action_name.clone()).or_default().push(ix);
+            }
+        }
+
+        // Only the bindings of the same keystrokes can override or conflict with each other.
+        for group in by_keystrokes.values() {
+            for (position, &ix) in group.iter().enumerate() {
+                let entry = &entries[ix];
+                let overridden = group[position + 1..].iter().any(|&later_ix| {
+                    same_context(
+                        entry.context.as_deref(),
+                        entries[later_ix].context.as_deref(),
+                    )
+                });
+                let conflicts = group
+                    .iter()
+                    .copied()
+                    .filter(|&other_ix| {
+                        let other = &entries[other_ix];
+                        other.action_name.is_some()
+                            && other.action_name != entry.action_name
+                            && same_context(entry.context.as_deref(), other.context.as_deref())
+                    })
+                    .collect();
+                entries[ix].overridden = overridden;
+                entries[ix].conflicts = conflicts;
+            }
+        }
+
+        Self {
+            entries,
+            by_keystrokes,
+            by_action,
+        }
+    }
+
+    fn for_action<'a>(
+        &'a self,
+        action_name: &str,
+    ) -> impl Iterator<Item = (usize, &'a BindingEntry)> {
+        self.by_action
+            .get(action_name)
+            .into_iter()
+            .flatten()
+            .map(|&ix| (ix, &self.entries[ix]))
+    }
+
+    /// Returns the bindings of other actions to the given keystrokes in the given context,
+    /// which either replace a binding of them to the given action or are replaced by it.
+    fn conflicts(
+        &self,
+        keystrokes: &str,
+        context: Option<&str>,
+        action_name: &str,
+    ) -> Vec<&BindingEntry> {
+        self.by_keystrokes
+            .get(&normalized_keystrokes(keystrokes))
+            .into_iter()
+            .flatten()
+            .map(|&ix| &self.entries[ix])
+            .filter(|entry| {
+                entry.action_name.is_some()
+                    && entry.action_name.as_deref() != Some(action_name)
+                    && same_context(entry.context.as_deref(), context)
+            })
+            .collect()
+    }
+}
+
+/// Returns the keystrokes written the same way regardless of how they were written in
+/// the keymap file, such as with their modifiers in another order.
+fn normalized_keystrokes(keystrokes: &str) -> String {
+    keystrokes
+        .split_whitespace()
+        .map(|keystroke| match Keystroke::parse(keystroke) {
+            Ok(keystroke) => keystroke_text(&keystroke),
+            Err(_) => keystroke.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the keystroke as it's written in keymap files.
+fn keystroke_text(keystroke: &Keystroke) -> String {
+    let mut text = String::new();
+    let modifiers = &keystroke.modifiers;
+    if modifiers.control {
+        text.push_str("ctrl-");
+    }
+    if modifiers.alt {
+        text.push_str("alt-");
+    }
+    if modifiers.shift {
+        text.push_str("shift-");
+    }
+    if modifiers.platform {
+        if cfg!(target_os = "macos") {
+            text.push_str("cmd-");
+        } else {
+            text.push_str("super-");
+        }
+    }
+    if modifiers.function {
+        text.push_str("fn-");
+    }
+    text.push_str(&keystroke.key);
+    text
+}
+
+/// A binding being recorded for an action.
+struct Recording {
+    action_name: String,
+    /// The binding that the recorded one replaces, if any.
+    replacing: Option<BindingEntry>,
+    keystrokes: Vec<Keystroke>,
+    /// Whether the last keystrokes are pending as the prefix of a multi-keystroke binding.
+    /// They're recorded as they're pressed, so they're not recorded again when they're
+    /// replayed.
+    pending_input: bool,
+    context_editor: View<Editor>,
+    focus_handle: FocusHandle,
+}
+
+impl Recording {
+    fn keystrokes_text(&self) -> String {
+        self.keystrokes
+            .iter()
+            .map(keystroke_text)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn context(&self, cx: &AppContext) -> Option<String> {
+        let context = self.context_editor.read(cx).text(cx);
+        let context = context.trim();
+        (!context.is_empty()).then(|| context.to_string())
+    }
+}
+
+/// A page that lists the actions with their key bindings, and records new bindings to
+/// the user's keymap file.
+pub struct KeymapEditor {
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    query_editor: View<Editor>,
+    bindings: Bindings,
+    action_names: Vec<String>,
+    matching_action_names: Vec<String>,
+    recording: Option<Recording>,
+    scroll_handle: UniformListScrollHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl KeymapEditor {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let query_editor = cx.new_view(|cx| {
+                let mut input = Editor::single_line(cx);
+                input.set_placeholder_text("Search actions or keystrokes...", cx);
+                input
+            });
+            let this = cx.view().downgrade();
+
+            let subscriptions = vec![
+                cx.subscribe(&query_editor, |this, _, event: &editor::EditorEvent, cx| {
+                    if let editor::EditorEvent::Edited { .. } = event {
+                        this.update_matches(cx);
+                    }
+                }),
+                cx.observe_global::<LoadedKeymaps>(|this, cx| this.reload(cx)),
+                // The keystrokes that are bound to actions reach the recorder as those
+                // actions, which it handles without running them.
+                cx.observe_keystrokes(move |event, cx| {
+                    if event.action.is_some() {
+                        this.update(cx, |this, cx| this.record_keystroke(&event.keystroke, cx))
+                            .ok();
+                    }
+                }),
+                cx.observe_pending_input(|this, cx| this.pending_input_changed(cx)),
+            ];
+
+            let mut this = Self {
+                fs,
+                focus_handle: cx.focus_handle(),
+                query_editor,
+                bindings: Bindings::default(),
+                action_names: Vec::new(),
+                matching_action_names: Vec::new(),
+                recording: None,
+                scroll_handle: UniformListScrollHandle::new(),
+                _subscriptions: subscriptions,
+            };
+            this.reload(cx);
+            this
+        })
+    }
+
+    fn reload(&mut self, cx: &mut ViewContext<Self>) {
+        let loaded_keymaps = cx
+            .try_global::<LoadedKeymaps>()
+            .cloned()
+            .unwrap_or_default();
+        let mut keymaps = loaded_keymaps
+            .asset_paths
+            .iter()
+            .filter_map(|asset_path| {
+                let keymap = KeymapFile::parse_asset(asset_path).ok()?;
+                Some((BindingSource::Default, keymap))
+            })
+            .collect::<Vec<_>>();
+        keymaps.push((BindingSource::User, loaded_keymaps.user_keymap));
+        self.bindings = Bindings::new(&keymaps);
+
+        let mut action_names = cx
+            .all_action_names()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<BTreeSet<_>>();
+        action_names.extend(self.bindings.by_action.keys().cloned());
+        self.action_names = action_names.into_iter().collect();
+        self.update_matches(cx);
+    }
+
+    fn update_matches(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.query_editor.read(cx).text(cx).trim().to_lowercase();
+        self.matching_action_names = self
+            .action_names
+            .iter()
+            .filter(|action_name| {
+                query.is_empty()
+                    || action_name.to_lowercase().contains(&query)
+                    || self
+                        .bindings
+                        .for_action(action_name)
+                        .any(|(_, entry)| entry.keystrokes.to_lowercase().contains(&query))
+            })
+            .cloned()
+            .collect();
+        cx.notify();
+    }
+
+    fn start_recording(
+        &mut self,
+        action_name: String,
+        replacing: Option<BindingEntry>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let context = replacing
+            .as_ref()
+            .or_else(|| {
+                self.bindings
+                    .for_action(&action_name)
+                    .next()
+                    .map(|(_, entry)| entry)
+            })
+            .and_then(|entry| entry.context.clone())
+            .unwrap_or_default();
+        let context_editor = cx.new_view(|cx| {
+            let mut input = Editor::single_line(cx);
+            input.set_placeholder_text("Context, such as Editor (optional)", cx);
+            input.set_text(context, cx);
+            input
+        });
+        let focus_handle = cx.focus_handle();
+        focus_handle.focus(cx);
+        self.recording = Some(Recording {
+            action_name,
+            replacing,
+            keystrokes: Vec::new(),
+            pending_input: false,
+            context_editor,
+            focus_handle,
+        });
+        // The actions that the recorder handles are those available where it's focused,
+        // once it has been rendered.
+        cx.on_next_frame(|_, cx| cx.notify());
+        cx.notify();
+    }
+
+    /// Records a keystroke pressed while the recorder is focused. Escape and enter cancel
+    /// and save the recording.
+    fn record_keystroke(&mut self, keystroke: &Keystroke, cx: &mut ViewContext<Self>) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        if recording.pending_input || !recording.focus_handle.is_focused(cx) {
+            return;
+        }
+        if !keystroke.modifiers.modified() {
+            match keystroke.key.as_str() {
+                "escape" => return self.cancel_recording(cx),
+                "enter" => return self.save_recording(cx),
+                _ => {}
+            }
+        }
+        if recording.keystrokes.len() == MAX_RECORDED_KEYSTROKES {
+            recording.keystrokes.clear();
+        }
+        recording.keystrokes.push(Keystroke {
+            ime_key: None,
+            ..keystroke.clone()
+        });
+        cx.notify();
+    }
+
+    /// Records the keystrokes that are pending as the prefix of a multi-keystroke binding,
+    /// since only the last keystroke of the binding reaches the recorder.
+    fn pending_input_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        recording.pending_input = false;
+        let keystroke = cx
+            .pending_input_keystrokes()
+            .and_then(|keystrokes| keystrokes.last().cloned());
+        if let Some(keystroke) = keystroke {
+            self.record_keystroke(&keystroke, cx);
+            if let Some(recording) = self.recording.as_mut() {
+                recording.pending_input = true;
+            }
+        }
+    }
+
+    fn save_recording(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+        if recording.keystrokes.is_empty() {
+            return;
+        }
+        let keystrokes = recording.keystrokes_text();
+        let context = recording.context(cx);
+        let mut updates = Vec::new();
+        if let Some(replacing) = recording.replacing {
+            if normalized_keystrokes(&replacing.keystrokes) != normalized_keystrokes(&keystrokes)
+                || !same_context(replacing.context.as_deref(), context.as_deref())
+            {
+                updates.push((replacing.context, replacing.keystrokes, Value::Null));
+            }
+        }
+        updates.push((context, keystrokes, Value::String(recording.action_name)));
+        self.update_keymap_file(updates, cx);
+        self.focus_handle.focus(cx);
+        cx.notify();
+    }
+
+    fn cancel_recording(&mut self, cx: &mut ViewContext<Self>) {
+        self.recording = None;
+        self.focus_handle.focus(cx);
+        cx.notify();
+    }
+
+    /// Disables a binding, by binding its keystrokes to `null` in the user's keymap.
+    fn remove_binding(&mut self, entry: BindingEntry, cx: &mut ViewContext<Self>) {
+        self.update_keymap_file(vec![(entry.context, entry.keystrokes, Value::Null)], cx);
+    }
+
+    fn update_keymap_file(
+        &self,
+        updates: Vec<(Option<String>, String, Value)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let fs = self.fs.clone();
+        cx.background_executor()
+            .spawn(async move {
+                let mut keymap_path = paths::keymap_file().clone();
+                let mut text = if fs.is_file(&keymap_path).await {
+                    keymap_path = fs.canonicalize(&keymap_path).await?;
+                    fs.load(&keymap_path).await?
+                } else {
+                    settings::initial_keymap_content().to_string()
+                };
+                for (context, keystrokes, action) in updates {
+                    text = KeymapFile::new_text_for_binding_update(
+                        &text,
+                        context.as_deref(),
+                        &keystrokes,
+                        &action,
+                    );
+                }
+                fs.atomic_write(keymap_path, text).await
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn render_text_input(&self, editor: &View<Editor>, cx: &ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            line_height: relative(1.3),
+            ..Default::default()
+        };
+
+        h_flex()
+            .flex_1()
+            .px_2()
+            .py_1()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .rounded_lg()
+            .child(EditorElement::new(
+                editor,
+                EditorStyle {
+                    background: cx.theme().colors().editor_background,
+                    local_player: cx.theme().players().local(),
+                    text: text_style,
+                    ..Default::default()
+                },
+            ))
+    }
+
+    fn render_recording(
+        &self,
+        recording: &Recording,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let keystrokes = recording.keystrokes_text();
+        let context = recording.context(cx);
+        let conflicts: Vec<&BindingEntry> = if keystrokes.is_empty() {
+            Vec::new()
+        } else {
+            self.bindings
+                .conflicts(&keystrokes, context.as_deref(), &recording.action_name)
+                .into_iter()
+                .filter(|entry| !entry.overridden)
+                .collect()
+        };
+        let is_focused = recording.focus_handle.is_focused(cx);
+        let recorder = div()
+            .id("keystroke-recorder")
+            .track_focus(&recording.focus_handle)
+            .flex_1()
+            .px_2()
+            .py_1()
+            .border_1()
+            .rounded_lg()
+            .border_color(if is_focused {
+                cx.theme().colors().border_focused
+            } else {
+                cx.theme().colors().border
+            })
+            .on_click(cx.listener(|this, _, cx| {
+                if let Some(recording) = &this.recording {
+                    recording.focus_handle.focus(cx);
+                }
+            }))
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, cx| {
+                this.record_keystroke(&event.keystroke, cx);
+                cx.stop_propagation();
+            }))
+            .child(if keystrokes.is_empty() {
+                Label::new("Press the keystrokes to bind...").color(Color::Muted)
+            } else {
+                Label::new(keystrokes.clone())
+            });
+        // Handling the actions that could run where the recorder is focused keeps them from
+        // running, and reports their keystrokes to the keystroke observers.
+        let available_actions = if is_focused {
+            cx.available_actions()
+        } else {
+            Vec::new()
+        };
+        let recorder = available_actions.iter().fold(recorder, |recorder, action| {
+            recorder.on_boxed_action(action.as_ref(), |_, _| {})
+        });
+
+        v_flex()
+            .p_2()
+            .gap_2()
+            .elevation_2(cx)
+            .on_action(cx.listener(|this, _: &menu::Confirm, cx| this.save_recording(cx)))
+            .on_action(cx.listener(|this, _: &menu::Cancel, cx| this.cancel_recording(cx)))
+            .child(Label::new(format!("Binding for {}", recording.action_name)))
+            .child(h_flex().gap_2().child(recorder).child(
+                Button::new("clear-keystrokes", "Clear").on_click(cx.listener(|this, _, cx| {
+                    if let Some(recording) = &mut this.recording {
+                        recording.keystrokes.clear();
+                        recording.focus_handle.focus(cx);
+                    }
+                    cx.notify();
+                })),
+            ))
+            .child(self.render_text_input(&recording.context_editor, cx))
+            .children(conflicts.into_iter().map(|entry| {
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::ExclamationTriangle)
+                            .size(IconSize::Small)
+                            .color(Color::Warning),
+                    )
+                    .child(
+                        Label::new(format!(
+                            "Replaces {} in {}",
+                            entry.action_name.as_deref().unwrap_or_default(),
+                            context_label(entry.context.as_deref()),
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Warning),
+                    )
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .child(
+                        Button::new("cancel-recording", "Cancel")
+                            .on_click(cx.listener(|this, _, cx| this.cancel_recording(cx))),
+                    )
+                    .child(
+                        Button::new("save-recording", "Save")
+                            .style(ButtonStyle::Filled)
+                            .disabled(keystrokes.is_empty())
+                            .on_click(cx.listener(|this, _, cx| this.save_recording(cx))),
+                    ),
+            )
+    }
+
+    fn render_binding(
+        &self,
+        id: usize,
+        entry: &BindingEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let action_name = entry.action_name.clone().unwrap_or_default();
+        let conflicts = entry
+            .conflicts
+            .iter()
+            .map(|&conflict_ix| {
+                format!(
+                    "{} {}",
+                    if entry.overridden {
+                        "Overridden by"
+                    } else {
+                        "Replaces"
+                    },
+                    self.bindings.entries[conflict_ix]
+                        .action_name
+                        .as_deref()
+                        .unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>();
+        let conflicts = SharedString::from(conflicts.join("\n"));
+
+        let edited_entry = entry.clone();
+        let removed_entry = entry.clone();
+        h_flex()
+            .id(("binding", id))
+            .gap_1()
+            .px_1()
+            .rounded_md()
+            .bg(cx.theme().colors().element_background)
+            .when(!conflicts.is_empty(), |this| {
+                this.child(
+                    div()
+                        .id(("binding-conflicts", id))
+                        .child(
+                            Icon::new(IconName::ExclamationTriangle)
+                                .size(IconSize::XSmall)
+                                .color(Color::Warning),
+                        )
+                        .tooltip(move |cx| Tooltip::text(conflicts.clone(), cx)),
+                )
+            })
+            .child(
+                Button::new(("edit-binding", id), entry.keystrokes.clone())
+                    .color(if entry.overridden {
+                        Color::Muted
+                    } else {
+                        Color::Default
+                    })
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.start_recording(action_name.clone(), Some(edited_entry.clone()), cx)
+                    })),
+            )
+            .child(
+                Label::new(context_label(entry.context.as_deref()))
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+            )
+            .when(entry.source == BindingSource::User, |this| {
+                this.child(
+                    Label::new("user")
+                        .size(LabelSize::XSmall)
+                        .color(Color::Accent),
+                )
+            })
+            .child(
+                IconButton::new(("remove-binding", id), IconName::Close)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(|cx| Tooltip::text("Remove Binding", cx))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.remove_binding(removed_entry.clone(), cx)
+                    })),
+            )
+    }
+
+    fn render_action(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let action_name = self.matching_action_names[ix].clone();
+        let bindings = self
+            .bindings
+            .for_action(&action_name)
+            .map(|(id, entry)| self.render_binding(id, entry, cx).into_any_element())
+            .collect::<Vec<_>>();
+
+        h_flex()
+            .id(("action", ix))
+            .w_full()
+            .h_8()
+            .gap_2()
+            .px_2()
+            .child(div().w_1_3().child(Label::new(action_name.clone())))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .gap_2()
+                    .overflow_hidden()
+                    .children(bindings),
+            )
+            .child(
+                IconButton::new(("add-binding", ix), IconName::Plus)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::text("Add Binding", cx))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.start_recording(action_name.clone(), None, cx)
+                    })),
+            )
+    }
+}
+
+fn context_label(context: Option<&str>) -> SharedString {
+    match context {
+        Some(context) => context.to_string().into(),
+        None => "any context".into(),
+    }
+}
+
+impl EventEmitter<ItemEvent> for KeymapEditor {}
+
+impl FocusableView for KeymapEditor {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for KeymapEditor {
+    type Event = ItemEvent;
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Settings))
+    }
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some("Keymap".into())
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for KeymapEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let item_count = self.matching_action_names.len();
+        v_flex()
+            .key_context("KeymapEditor")
+            .track_focus(&self.focus_handle)
+            .p_4()
+            .size_full()
+            .gap_4()
+            .child(Label::new("Keymap").size(LabelSize::Large))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Icon::new(IconName::MagnifyingGlass))
+                    .child(self.render_text_input(&self.query_editor, cx)),
+            )
+            .children(
+                self.recording
+                    .as_ref()
+                    .map(|recording| self.render_recording(recording, cx)),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "actions",
+                    item_count,
+                    |this, range, cx| {
+                        range
+                            .map(|ix| this.render_action(ix, cx).into_any_element())
+                            .collect()
+                    },
+                )
+                .flex_1()
+                .track_scroll(self.scroll_handle.clone()),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{actions, KeyBinding, TestAppContext};
+    use project::Project;
+    use workspace::AppState;
+
+    use super::*;
+
+    actions!(keymap_editor_test, [Chord]);
+
+    #[test]
+    fn test_binding_conflicts() {
+        let default_keymap = KeymapFile::parse(
+            r#"[
+                {
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-k": "editor::CutToEndOfLine",
+                        "ctrl-d": "editor::SelectNext"
+                    }
+                },
+                {
+                    "bindings": { "ctrl-k": "workspace::Open" }
+                }
+            ]"#,
+        )
+        .unwrap();
+        let user_keymap = KeymapFile::parse(
+            r#"[
+                {
+                    "context": "Editor ",
+                    "bindings": {
+                        "ctrl-k": "editor::DeleteLine",
+                        "ctrl-d": null
+                    }
+                }
+            ]"#,
+        )
+        .unwrap();
+        let bindings = Bindings::new(&[
+            (BindingSource::Default, default_keymap),
+            (BindingSource::User, user_keymap),
+        ]);
+
+        let summary = bindings
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.keystrokes.as_str(),
+                    entry.action_name.as_deref(),
+                    entry.source,
+                    entry.overridden,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "ctrl-d",
+                    Some("editor::SelectNext"),
+                    BindingSource::Default,
+                    true
+                ),
+                (
+                    "ctrl-k",
+                    Some("editor::CutToEndOfLine"),
+                    BindingSource::Default,
+                    true
+                ),
+                (
+                    "ctrl-k",
+                    Some("workspace::Open"),
+                    BindingSource::Default,
+                    false
+                ),
+                ("ctrl-d", None, BindingSource::User, false),
+                (
+                    "ctrl-k",
+                    Some("editor::DeleteLine"),
+                    BindingSource::User,
+                    false
+                ),
+            ]
+        );
+
+        // Bindings in other contexts don't conflict.
+        let conflicting_actions = |keystrokes, context, action_name| {
+            bindings
+                .conflicts(keystrokes, context, action_name)
+                .into_iter()
+                .map(|entry| entry.action_name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            conflicting_actions("ctrl-k", Some("Editor"), "editor::DeleteLine"),
+            [Some("editor::CutToEndOfLine".to_string())]
+        );
+        assert_eq!(
+            conflicting_actions("ctrl-k", None, "editor::DeleteLine"),
+            [Some("workspace::Open".to_string())]
+        );
+        assert!(
+            conflicting_actions("ctrl-shift-k", Some("Editor"), "editor::DeleteLine").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_keystroke_text() {
+        for keystrokes in ["ctrl-alt-shift-k", "f5", "ctrl--"] {
+            let keystroke = Keystroke::parse(keystrokes).unwrap();
+            assert_eq!(keystroke_text(&keystroke), keystrokes);
+        }
+        assert_eq!(normalized_keystrokes("shift-ctrl-k  f5"), "ctrl-shift-k f5");
+    }
+
+    #[gpui::test]
+    async fn test_record_chord(cx: &mut TestAppContext) {
+        let app_state = cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            cx.on_action(|_: &Chord, _| panic!("The recorder should handle the chord's action"));
+            cx.bind_keys([KeyBinding::new("ctrl-k ctrl-s", Chord, None)]);
+            app_state
+        });
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let keymap_editor = workspace.update(cx, |workspace, cx| {
+            let keymap_editor = KeymapEditor::new(workspace, cx);
+            workspace.add_item_to_active_pane(Box::new(keymap_editor.clone()), None, true, cx);
+            keymap_editor
+        });
+        keymap_editor.update(cx, |keymap_editor, cx| {
+            keymap_editor.start_recording("keymap_editor_test::Chord".to_string(), None, cx)
+        });
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes("ctrl-k ctrl-s");
+        keymap_editor.update(cx, |keymap_editor, _| {
+            assert_eq!(
+                keymap_editor.recording.as_ref().unwrap().keystrokes_text(),
+                "ctrl-k ctrl-s"
+            );
+        });
+    }
+}
@@ -0,0 +1,311 @@
+use serde_json::Value;
+
+/// The group of the settings at the root of the settings file that aren't objects.
+pub const GENERAL_GROUP: &str = "General";
+
+/// The settings that hold overrides of other settings rather than settings of their own.
+const OVERRIDE_KEYS: &[&str] = &[
+    "dev",
+    "nightly",
+    "preview",
+    "stable",
+    "languages",
+    "profiles",
+];
+
+/// How deep to look for settings in nested objects. Deeper settings can only be edited
+/// in the settings file.
+const MAX_DEPTH: usize = 3;
+
+/// A setting shown in the settings editor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettingItem {
+    pub key_path: Vec<String>,
+    pub group: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub kind: SettingKind,
+}
+
+impl SettingItem {
+    /// Returns the key path of the setting as it's written in the settings file.
+    pub fn key(&self) -> String {
+        self.key_path.join(".")
+    }
+
+    /// Returns whether the setting's title, key or description contains the query,
+    /// ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.title.to_lowercase().contains(&query)
+            || self.key().to_lowercase().contains(&query)
+            || self.description.as_ref().map_or(false, |description| {
+                description.to_lowercase().contains(&query)
+            })
+    }
+}
+
+/// The control used to edit a setting.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SettingKind {
+    Toggle,
+    Dropdown(Vec<Value>),
+    Number {
+        integer: bool,
+    },
+    /// Settings that can only be edited in the settings file, such as lists and maps.
+    Other,
+}
+
+/// Returns the settings described by the settings JSON schema, sorted by group and key,
+/// with the general settings first.
+pub fn setting_items(schema: &Value) -> Vec<SettingItem> {
+    let mut items = Vec::new();
+    let Some(properties) = resolve(schema, schema)
+        .get("properties")
+        .and_then(Value::as_object)
+    else {
+        return items;
+    };
+    for (key, property) in properties {
+        if OVERRIDE_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let group = if object_properties(property, schema).is_some() {
+            humanize(key)
+        } else {
+            GENERAL_GROUP.to_string()
+        };
+        push_items(&mut items, vec![key.clone()], &group, property, schema);
+    }
+
+    items.sort_by(|a, b| {
+        (a.group != GENERAL_GROUP)
+            .cmp(&(b.group != GENERAL_GROUP))
+            .then_with(|| a.group.cmp(&b.group))
+            .then_with(|| a.key_path.cmp(&b.key_path))
+    });
+    items
+}
+
+fn push_items(
+    items: &mut Vec<SettingItem>,
+    key_path: Vec<String>,
+    group: &str,
+    property: &Value,
+    root: &Value,
+) {
+    if let Some(properties) = object_properties(property, root) {
+        if key_path.len() < MAX_DEPTH {
+            for (key, subproperty) in properties {
+                let mut key_path = key_path.clone();
+                key_path.push(key.clone());
+                push_items(items, key_path, group, subproperty, root);
+            }
+            return;
+        }
+    }
+
+    let title = key_path.last().map(|key| humanize(key)).unwrap_or_default();
+    let resolved = resolve(property, root);
+    // The description of the property is more specific than that of its type.
+    let description = property
+        .get("description")
+        .or_else(|| resolved.get("description"))
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+    items.push(SettingItem {
+        title,
+        description,
+        kind: setting_kind(resolved, root),
+        group: group.to_string(),
+        key_path,
+    });
+}
+
+fn setting_kind(property: &Value, root: &Value) -> SettingKind {
+    if let Some(values) = enum_values(property, root) {
+        return SettingKind::Dropdown(values);
+    }
+    match property.get("type").and_then(Value::as_str) {
+        Some("boolean") => SettingKind::Toggle,
+        Some("integer") => SettingKind::Number { integer: true },
+        Some("number") => SettingKind::Number { integer: false },
+        _ => SettingKind::Other,
+    }
+}
+
+/// Returns the values that a setting can take, when it can only take a few of them.
+fn enum_values(property: &Value, root: &Value) -> Option<Vec<Value>> {
+    if let Some(values) = property.get("enum").and_then(Value::as_array) {
+        return Some(values.clone());
+    }
+    if let Some(value) = property.get("const") {
+        return Some(vec![value.clone()]);
+    }
+    let variants = property
+        .get("oneOf")
+        .or_else(|| property.get("anyOf"))
+        .and_then(Value::as_array)?;
+    let mut values = Vec::new();
+    for variant in variants {
+        values.extend(enum_values(resolve(variant, root), root)?);
+    }
+    Some(values)
+}
+
+fn object_properties<'a>(
+    property: &'a Value,
+    root: &'a Value,
+) -> Option<&'a serde_json::Map<String, Value>> {
+    let properties = resolve(property, root).get("properties")?.as_object()?;
+    (!properties.is_empty()).then_some(properties)
+}
+
+/// Follows the references to the definitions of the schema, including those wrapped
+/// in an `allOf` so that they can have a description.
+fn resolve<'a>(property: &'a Value, root: &'a Value) -> &'a Value {
+    if let Some(reference) = property.get("$ref").and_then(Value::as_str) {
+        if let Some(definition) = reference
+            .strip_prefix("#/definitions/")
+            .and_then(|name| root.get("definitions")?.get(name))
+        {
+            return resolve(definition, root);
+        }
+    }
+    if let Some([subschema]) = property
+        .get("allOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        return resolve(subschema, root);
+    }
+    property
+}
+
+fn humanize(key: &str) -> String {
+    key.split('_')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(ix, word)| {
+            if ix > 0 {
+                return word.to_string();
+            }
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_setting_items() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "tab_size": {
+                    "description": "How many columns a tab should occupy.",
+                    "type": "integer",
+                    "format": "uint32",
+                },
+                "ui_font_size": { "type": "number" },
+                "cursor_blink": { "type": "boolean" },
+                "soft_wrap": {
+                    "description": "How to soft-wrap long lines of text.",
+                    "allOf": [{ "$ref": "#/definitions/SoftWrap" }],
+                },
+                "file_scan_exclusions": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                },
+                "git": {
+                    "type": "object",
+                    "properties": {
+                        "inline_blame": { "$ref": "#/definitions/InlineBlame" },
+                    },
+                },
+                "languages": {
+                    "type": "object",
+                    "properties": {
+                        "Rust": { "type": "object", "properties": { "tab_size": { "type": "integer" } } },
+                    },
+                },
+                "nightly": { "type": "object", "properties": { "tab_size": { "type": "integer" } } },
+            },
+            "definitions": {
+                "SoftWrap": {
+                    "oneOf": [
+                        { "description": "Never wrap.", "type": "string", "enum": ["none"] },
+                        { "type": "string", "enum": ["editor_width", "bounded"] },
+                    ],
+                },
+                "InlineBlame": {
+                    "type": "object",
+                    "properties": {
+                        "enabled": { "description": "Whether to show blame.", "type": "boolean" },
+                        "delay_ms": { "type": "integer" },
+                        "nested": {
+                            "type": "object",
+                            "properties": { "deeper": { "type": "boolean" } },
+                        },
+                    },
+                },
+            },
+        });
+
+        let items = setting_items(&schema);
+        let keys = items
+            .iter()
+            .map(|item| (item.group.as_str(), item.key()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                ("General", "cursor_blink".to_string()),
+                ("General", "file_scan_exclusions".to_string()),
+                ("General", "soft_wrap".to_string()),
+                ("General", "tab_size".to_string()),
+                ("General", "ui_font_size".to_string()),
+                ("Git", "git.inline_blame.delay_ms".to_string()),
+                ("Git", "git.inline_blame.enabled".to_string()),
+                ("Git", "git.inline_blame.nested".to_string()),
+            ]
+        );
+
+        let item = |key: &str| items.iter().find(|item| item.key() == key).unwrap();
+        assert_eq!(item("cursor_blink").kind, SettingKind::Toggle);
+        assert_eq!(item("file_scan_exclusions").kind, SettingKind::Other);
+        assert_eq!(
+            item("ui_font_size").kind,
+            SettingKind::Number { integer: false }
+        );
+        assert_eq!(item("tab_size").kind, SettingKind::Number { integer: true });
+        assert_eq!(item("tab_size").title, "Tab size");
+        assert_eq!(
+            item("soft_wrap").kind,
+            SettingKind::Dropdown(vec![json!("none"), json!("editor_width"), json!("bounded")])
+        );
+        assert_eq!(
+            item("soft_wrap").description.as_deref(),
+            Some("How to soft-wrap long lines of text.")
+        );
+        assert_eq!(
+            item("git.inline_blame.enabled").description.as_deref(),
+            Some("Whether to show blame.")
+        );
+        // Objects deeper than the maximum depth are edited in the settings file.
+        assert_eq!(item("git.inline_blame.nested").kind, SettingKind::Other);
+
+        assert!(item("soft_wrap").matches("SOFT-WRAP"));
+        assert!(item("git.inline_blame.enabled").matches("inline_blame"));
+        assert!(!item("tab_size").matches("blame"));
+    }
+}
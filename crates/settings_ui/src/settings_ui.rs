@@ -1,27 +1,40 @@
 mod appearance_settings_controls;
+mod keymap_editor;
+mod setting_items;
+mod vscode_import;
 
-use std::any::TypeId;
+use std::iter;
+use std::path::PathBuf;
+use std::sync::Arc;
 
-use command_palette_hooks::CommandPaletteFilter;
-use editor::EditorSettingsControls;
-use feature_flags::{FeatureFlag, FeatureFlagViewExt};
-use gpui::{actions, AppContext, EventEmitter, FocusHandle, FocusableView, View};
-use ui::prelude::*;
+use editor::{Editor, EditorElement, EditorEvent, EditorSettingsControls, EditorStyle};
+use fs::Fs;
+use gpui::{
+    actions, AnchorCorner, AnyElement, AppContext, ClickEvent, EventEmitter, FocusHandle,
+    FocusableView, Subscription, TextStyle, View, WeakView,
+};
+use project::WorktreeId;
+use serde_json::Value;
+use settings::{Settings, SettingsJsonSchemaParams, SettingsStore};
+use theme::ThemeSettings;
+use ui::{
+    prelude::*, Checkbox, CheckboxWithLabel, ContextMenu, DropdownMenu, NumericStepper,
+    PopoverMenu, SettingsContainer, SettingsGroup, Tooltip,
+};
 use workspace::item::{Item, ItemEvent};
 use workspace::Workspace;
 
 use crate::appearance_settings_controls::AppearanceSettingsControls;
+use crate::keymap_editor::KeymapEditor;
+use crate::setting_items::{setting_items, SettingItem, SettingKind};
 
-pub struct SettingsUiFeatureFlag;
-
-impl FeatureFlag for SettingsUiFeatureFlag {
-    const NAME: &'static str = "settings-ui";
-}
-
-actions!(zed, [OpenSettingsEditor]);
+actions!(
+    zed,
+    [OpenSettingsEditor, OpenKeymapEditor, ImportVsCodeSettings]
+);
 
 pub fn init(cx: &mut AppContext) {
-    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+    cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         workspace.register_action(|workspace, _: &OpenSettingsEditor, cx| {
             let existing = workspace
                 .active_pane()
@@ -36,39 +49,433 @@ pub fn init(cx: &mut AppContext) {
                 workspace.add_item_to_active_pane(Box::new(settings_page), None, true, cx)
             }
         });
+        workspace.register_action(|workspace, _: &OpenKeymapEditor, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .find_map(|item| item.downcast::<KeymapEditor>());
 
-        let settings_ui_actions = [TypeId::of::<OpenSettingsEditor>()];
-
-        CommandPaletteFilter::update_global(cx, |filter, _cx| {
-            filter.hide_action_types(&settings_ui_actions);
-        });
-
-        cx.observe_flag::<SettingsUiFeatureFlag, _>(move |is_enabled, _view, cx| {
-            if is_enabled {
-                CommandPaletteFilter::update_global(cx, |filter, _cx| {
-                    filter.show_action_types(settings_ui_actions.iter());
-                });
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, cx);
             } else {
-                CommandPaletteFilter::update_global(cx, |filter, _cx| {
-                    filter.hide_action_types(&settings_ui_actions);
-                });
+                let keymap_editor = KeymapEditor::new(workspace, cx);
+                workspace.add_item_to_active_pane(Box::new(keymap_editor), None, true, cx)
             }
-        })
-        .detach();
+        });
+        workspace.register_action(vscode_import::import_vscode_settings);
     })
     .detach();
 }
 
 pub struct SettingsPage {
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
     focus_handle: FocusHandle,
+    query_editor: View<Editor>,
+    items: Vec<SettingItem>,
+    /// The value of each item in the settings file being edited, and the value in effect
+    /// for it.
+    values: Vec<(Option<Value>, Option<Value>)>,
+    target: SettingsTarget,
+    /// The menu listing the settings files that can be edited, with the files it lists.
+    target_menu: Option<(Vec<SettingsTarget>, View<ContextMenu>)>,
+    modified_only: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The settings file that the settings page edits.
+#[derive(Clone, PartialEq)]
+enum SettingsTarget {
+    User,
+    /// The settings file at the root of a worktree, overriding the user's settings.
+    Project {
+        worktree_id: WorktreeId,
+        name: SharedString,
+        settings_path: PathBuf,
+    },
 }
 
 impl SettingsPage {
-    pub fn new(_workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
-        cx.new_view(|cx| Self {
-            focus_handle: cx.focus_handle(),
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let weak_workspace = cx.view().downgrade();
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let query_editor = cx.new_view(|cx| {
+                let mut input = Editor::single_line(cx);
+                input.set_placeholder_text("Search settings...", cx);
+                input
+            });
+
+            let font_names = cx.text_system().all_font_names();
+            let schema = cx.global::<SettingsStore>().json_schema(
+                &SettingsJsonSchemaParams {
+                    staff_mode: cx.is_staff(),
+                    language_names: &[],
+                    font_names: &font_names,
+                },
+                cx,
+            );
+
+            let subscriptions = vec![
+                cx.subscribe(&query_editor, |_, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::Edited { .. } = event {
+                        cx.notify();
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|page, cx| {
+                    page.update_values(cx);
+                    cx.notify();
+                }),
+            ];
+
+            let mut page = Self {
+                workspace: weak_workspace,
+                fs,
+                focus_handle: cx.focus_handle(),
+                query_editor,
+                items: setting_items(&schema),
+                values: Vec::new(),
+                target: SettingsTarget::User,
+                target_menu: None,
+                modified_only: false,
+                _subscriptions: subscriptions,
+            };
+            page.update_values(cx);
+            page
         })
     }
+
+    fn set_target(&mut self, target: SettingsTarget, cx: &mut ViewContext<Self>) {
+        self.target = target;
+        self.update_values(cx);
+        cx.notify();
+    }
+
+    fn update_values(&mut self, cx: &AppContext) {
+        self.values = self
+            .items
+            .iter()
+            .map(|item| self.setting_values(&item.key_path, cx))
+            .collect();
+    }
+
+    /// The settings files of the local worktrees of the project.
+    fn project_targets(&self, cx: &AppContext) -> Vec<SettingsTarget> {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Vec::new();
+        };
+        let project = workspace.read(cx).project().read(cx);
+        if !project.is_local() {
+            return Vec::new();
+        }
+        project
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                if !worktree.is_local() {
+                    return None;
+                }
+                Some(SettingsTarget::Project {
+                    worktree_id: worktree.id(),
+                    name: worktree.root_name().to_string().into(),
+                    settings_path: worktree
+                        .abs_path()
+                        .join(paths::local_settings_file_relative_path()),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the value of the setting in the settings file being edited, and the value
+    /// that's in effect for it, which may come from the user's or the default settings.
+    fn setting_values(
+        &self,
+        key_path: &[String],
+        cx: &AppContext,
+    ) -> (Option<Value>, Option<Value>) {
+        let store = cx.global::<SettingsStore>();
+        let user_value = value_at(store.raw_user_settings(), key_path);
+        let default_value = value_at(store.raw_default_settings(), key_path);
+        match &self.target {
+            SettingsTarget::User => (user_value.cloned(), user_value.or(default_value).cloned()),
+            SettingsTarget::Project { worktree_id, .. } => {
+                let local_value = store
+                    .raw_local_settings(worktree_id.to_usize())
+                    .and_then(|settings| value_at(settings, key_path));
+                (
+                    local_value.cloned(),
+                    local_value.or(user_value).or(default_value).cloned(),
+                )
+            }
+        }
+    }
+
+    /// Writes the value of a setting to the settings file being edited. A `null` value
+    /// removes the setting from the file, resetting it.
+    fn set_value(&mut self, key_path: Vec<String>, value: Value, cx: &mut ViewContext<Self>) {
+        match &self.target {
+            SettingsTarget::User => {
+                cx.global::<SettingsStore>().update_settings_file_value(
+                    self.fs.clone(),
+                    key_path,
+                    value,
+                );
+            }
+            SettingsTarget::Project { settings_path, .. } => {
+                let fs = self.fs.clone();
+                let settings_path = settings_path.clone();
+                cx.spawn(|_, mut cx| async move {
+                    let old_text = if fs.is_file(&settings_path).await {
+                        fs.load(&settings_path).await?
+                    } else {
+                        if let Some(settings_dir) = settings_path.parent() {
+                            fs.create_dir(settings_dir).await?;
+                        }
+                        String::new()
+                    };
+                    let new_text = cx.read_global(|store: &SettingsStore, _| {
+                        let key_path = key_path.iter().map(String::as_str).collect::<Vec<_>>();
+                        store.new_text_for_value_update(old_text, &key_path, &value)
+                    })?;
+                    fs.atomic_write(settings_path, new_text).await
+                })
+                .detach_and_log_err(cx);
+            }
+        }
+    }
+
+    fn render_search(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            line_height: relative(1.3),
+            ..Default::default()
+        };
+
+        h_flex()
+            .flex_1()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .min_w(rems_from_px(384.))
+            .rounded_lg()
+            .child(Icon::new(IconName::MagnifyingGlass))
+            .child(EditorElement::new(
+                &self.query_editor,
+                EditorStyle {
+                    background: cx.theme().colors().editor_background,
+                    local_player: cx.theme().players().local(),
+                    text: text_style,
+                    ..Default::default()
+                },
+            ))
+    }
+
+    fn render_target_menu(
+        &mut self,
+        targets: Vec<SettingsTarget>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let menu = match &self.target_menu {
+            Some((menu_targets, menu)) if *menu_targets == targets => menu.clone(),
+            _ => {
+                let page = cx.view().downgrade();
+                let menu = ContextMenu::build(cx, |mut menu, _| {
+                    for target in targets.clone() {
+                        let page = page.clone();
+                        menu = menu.entry(target.label(), None, move |cx| {
+                            page.update(cx, |page, cx| page.set_target(target.clone(), cx))
+                                .ok();
+                        });
+                    }
+                    menu
+                });
+                self.target_menu = Some((targets, menu.clone()));
+                menu
+            }
+        };
+        DropdownMenu::new("settings-target", self.target.label(), menu)
+    }
+
+    fn render_setting(
+        &self,
+        item: &SettingItem,
+        target_value: Option<&Value>,
+        value: Option<&Value>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let key = item.key();
+        let page = cx.view().downgrade();
+        let control = match &item.kind {
+            SettingKind::Toggle => {
+                let checked = value.and_then(Value::as_bool).unwrap_or(false);
+                let key_path = item.key_path.clone();
+                let page = page.clone();
+                Checkbox::new(SharedString::from(key.clone()), checked.into())
+                    .on_click(move |selection, cx| {
+                        let checked = *selection == Selection::Selected;
+                        page.update(cx, |page, cx| {
+                            page.set_value(key_path.clone(), Value::Bool(checked), cx)
+                        })
+                        .ok();
+                    })
+                    .into_any_element()
+            }
+            SettingKind::Dropdown(values) => {
+                let values = values.clone();
+                let key_path = item.key_path.clone();
+                let page = page.clone();
+                PopoverMenu::new(SharedString::from(format!("{key}-menu")))
+                    .menu(move |cx| {
+                        let values = values.clone();
+                        let key_path = key_path.clone();
+                        let page = page.clone();
+                        Some(ContextMenu::build(cx, move |mut menu, _| {
+                            for value in values {
+                                let key_path = key_path.clone();
+                                let page = page.clone();
+                                menu = menu.entry(display_value(&value), None, move |cx| {
+                                    page.update(cx, |page, cx| {
+                                        page.set_value(key_path.clone(), value.clone(), cx)
+                                    })
+                                    .ok();
+                                });
+                            }
+                            menu
+                        }))
+                    })
+                    .trigger(
+                        Button::new(
+                            SharedString::from(format!("{key}-value")),
+                            value.map_or("Default".into(), display_value),
+                        )
+                        .style(ButtonStyle::Filled)
+                        .icon(IconName::ChevronDown)
+                        .icon_position(IconPosition::End)
+                        .icon_size(IconSize::XSmall),
+                    )
+                    .attach(AnchorCorner::BottomLeft)
+                    .into_any_element()
+            }
+            SettingKind::Number { integer } => {
+                let integer = *integer;
+                let number = value.and_then(Value::as_f64).unwrap_or(0.);
+                // Fractional settings such as opacities and line heights are mostly small
+                // numbers, which a step of one would skip over.
+                let step_size = if integer || number.abs() >= 10. {
+                    1.
+                } else {
+                    0.1
+                };
+                let step = |direction: f64| {
+                    let key_path = item.key_path.clone();
+                    let page = page.clone();
+                    move |_: &ClickEvent, cx: &mut WindowContext| {
+                        let number = number + direction * step_size;
+                        let value = if integer {
+                            Value::from(number.max(0.) as u64)
+                        } else {
+                            Value::from((number * 10.).round() / 10.)
+                        };
+                        page.update(cx, |page, cx| page.set_value(key_path.clone(), value, cx))
+                            .ok();
+                    }
+                };
+                NumericStepper::new(
+                    SharedString::from(key.clone()),
+                    value.map_or("Default".into(), display_value),
+                    step(-1.),
+                    step(1.),
+                )
+                .into_any_element()
+            }
+            SettingKind::Other => Label::new(value.map_or("Default".into(), display_value))
+                .color(Color::Muted)
+                .size(LabelSize::Small)
+                .into_any_element(),
+        };
+
+        let key_path = item.key_path.clone();
+        h_flex()
+            .w_full()
+            .gap_4()
+            .justify_between()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(item.title.clone()))
+                            .child(
+                                Label::new(key.clone())
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .when_some(item.description.clone(), |this, description| {
+                        this.child(
+                            Label::new(description)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(control)
+                    .when(target_value.is_some(), |this| {
+                        this.child(
+                            IconButton::new(
+                                SharedString::from(format!("{key}-reset")),
+                                IconName::RotateCcw,
+                            )
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Reset", cx))
+                            .on_click(move |_, cx| {
+                                page.update(cx, |page, cx| {
+                                    page.set_value(key_path.clone(), Value::Null, cx)
+                                })
+                                .ok();
+                            }),
+                        )
+                    }),
+            )
+    }
+}
+
+/// Returns the value at the given key path of a settings file, ignoring `null` values,
+/// which leave a setting at its default.
+fn value_at<'a>(settings: &'a Value, key_path: &[String]) -> Option<&'a Value> {
+    key_path
+        .iter()
+        .try_fold(settings, |value, key| value.get(key))
+        .filter(|value| !value.is_null())
+}
+
+fn display_value(value: &Value) -> SharedString {
+    match value {
+        Value::String(string) => string.clone().into(),
+        value => value.to_string().into(),
+    }
+}
+
+impl SettingsTarget {
+    fn label(&self) -> SharedString {
+        match self {
+            SettingsTarget::User => "User".into(),
+            SettingsTarget::Project { name, .. } => format!("Project: {name}").into(),
+        }
+    }
 }
 
 impl EventEmitter<ItemEvent> for SettingsPage {}
@@ -101,23 +508,86 @@ impl Item for SettingsPage {
 
 impl Render for SettingsPage {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let targets = iter::once(SettingsTarget::User)
+            .chain(self.project_targets(cx))
+            .collect::<Vec<_>>();
+        if !targets.contains(&self.target) {
+            self.target = SettingsTarget::User;
+            self.update_values(cx);
+        }
+
+        let query = self.query_editor.read(cx).text(cx);
+        let query = query.trim();
+        let mut groups = Vec::<(String, Vec<AnyElement>)>::new();
+        for (item, (target_value, value)) in self.items.iter().zip(&self.values) {
+            if !query.is_empty() && !item.matches(query) {
+                continue;
+            }
+            if self.modified_only && target_value.is_none() {
+                continue;
+            }
+            let row = self
+                .render_setting(item, target_value.as_ref(), value.as_ref(), cx)
+                .into_any_element();
+            match groups.last_mut() {
+                Some((group, rows)) if *group == item.group => rows.push(row),
+                _ => groups.push((item.group.clone(), vec![row])),
+            }
+        }
+
+        let target_menu = self.render_target_menu(targets, cx);
+        let show_controls =
+            query.is_empty() && !self.modified_only && self.target == SettingsTarget::User;
+        let page = cx.view().downgrade();
         v_flex()
+            .id("settings-page")
+            .key_context("SettingsPage")
+            .track_focus(&self.focus_handle)
             .p_4()
             .size_full()
             .gap_4()
+            .overflow_y_scroll()
             .child(Label::new("Settings").size(LabelSize::Large))
             .child(
-                v_flex().gap_1().child(Label::new("Appearance")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(AppearanceSettingsControls::new()),
-                ),
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(self.render_search(cx))
+                    .child(target_menu)
+                    .child(CheckboxWithLabel::new(
+                        "modified-only",
+                        Label::new("Modified only"),
+                        self.modified_only.into(),
+                        move |selection, cx| {
+                            page.update(cx, |page, cx| {
+                                page.modified_only = *selection == Selection::Selected;
+                                cx.notify();
+                            })
+                            .ok();
+                        },
+                    )),
             )
+            .when(show_controls, |this| {
+                this.child(
+                    v_flex().gap_1().child(Label::new("Appearance")).child(
+                        v_flex()
+                            .elevation_2(cx)
+                            .child(AppearanceSettingsControls::new()),
+                    ),
+                )
+                .child(
+                    v_flex().gap_1().child(Label::new("Editor")).child(
+                        v_flex()
+                            .elevation_2(cx)
+                            .child(EditorSettingsControls::new()),
+                    ),
+                )
+            })
             .child(
-                v_flex().gap_1().child(Label::new("Editor")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(EditorSettingsControls::new()),
+                SettingsContainer::new().children(
+                    groups
+                        .into_iter()
+                        .map(|(group, rows)| SettingsGroup::new(group).children(rows)),
                 ),
             )
     }
@@ -0,0 +1,988 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use editor::{Editor, MultiBuffer};
+use fs::Fs;
+use gpui::{AsyncWindowContext, PromptLevel, WeakView};
+use serde_json::{json, Map, Value};
+use settings::{parse_json_with_comments, KeymapFile, SettingsStore};
+use similar::TextDiff;
+use theme::ThemeRegistry;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::Workspace;
+
+use crate::ImportVsCodeSettings;
+
+/// The Zed settings that VS Code's `"[language]"` blocks can be imported into.
+const LANGUAGE_SETTINGS: &[&str] = &[
+    "tab_size",
+    "hard_tabs",
+    "soft_wrap",
+    "preferred_line_length",
+    "wrap_guides",
+    "format_on_save",
+    "show_whitespaces",
+    "remove_trailing_whitespace_on_save",
+    "ensure_final_newline_on_save",
+    "inlay_hints",
+    "use_autoclose",
+    "linked_edits",
+];
+
+/// The Zed names of the languages, by VS Code language identifier.
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("c", "C"),
+    ("cpp", "C++"),
+    ("csharp", "C#"),
+    ("css", "CSS"),
+    ("dockerfile", "Dockerfile"),
+    ("elixir", "Elixir"),
+    ("go", "Go"),
+    ("html", "HTML"),
+    ("java", "Java"),
+    ("javascript", "JavaScript"),
+    ("javascriptreact", "JavaScript"),
+    ("json", "JSON"),
+    ("jsonc", "JSONC"),
+    ("lua", "Lua"),
+    ("markdown", "Markdown"),
+    ("php", "PHP"),
+    ("python", "Python"),
+    ("ruby", "Ruby"),
+    ("rust", "Rust"),
+    ("shellscript", "Shell Script"),
+    ("sql", "SQL"),
+    ("toml", "TOML"),
+    ("typescript", "TypeScript"),
+    ("typescriptreact", "TSX"),
+    ("yaml", "YAML"),
+    ("zig", "Zig"),
+];
+
+/// The Zed actions bound to the VS Code commands, along with the context they're bound in.
+const COMMANDS: &[(&str, &str, Option<&str>)] = &[
+    ("workbench.action.quickOpen", "file_finder::Toggle", None),
+    (
+        "workbench.action.showCommands",
+        "command_palette::Toggle",
+        None,
+    ),
+    ("workbench.action.files.save", "workspace::Save", None),
+    ("workbench.action.files.saveAll", "workspace::SaveAll", None),
+    (
+        "workbench.action.toggleSidebarVisibility",
+        "workspace::ToggleLeftDock",
+        None,
+    ),
+    (
+        "workbench.action.togglePanel",
+        "workspace::ToggleBottomDock",
+        None,
+    ),
+    (
+        "workbench.action.terminal.toggleTerminal",
+        "terminal_panel::ToggleFocus",
+        None,
+    ),
+    (
+        "workbench.view.explorer",
+        "project_panel::ToggleFocus",
+        None,
+    ),
+    (
+        "workbench.action.showAllSymbols",
+        "project_symbols::Toggle",
+        None,
+    ),
+    ("workbench.action.newWindow", "workspace::NewWindow", None),
+    ("workbench.action.openSettings", "zed::OpenSettings", None),
+    (
+        "workbench.action.zoomIn",
+        "zed::IncreaseBufferFontSize",
+        None,
+    ),
+    (
+        "workbench.action.zoomOut",
+        "zed::DecreaseBufferFontSize",
+        None,
+    ),
+    (
+        "workbench.action.closeActiveEditor",
+        "pane::CloseActiveItem",
+        Some("Pane"),
+    ),
+    (
+        "workbench.action.findInFiles",
+        "pane::DeploySearch",
+        Some("Pane"),
+    ),
+    (
+        "workbench.action.splitEditor",
+        "pane::SplitRight",
+        Some("Pane"),
+    ),
+    (
+        "workbench.action.nextEditor",
+        "pane::ActivateNextItem",
+        Some("Pane"),
+    ),
+    (
+        "workbench.action.previousEditor",
+        "pane::ActivatePrevItem",
+        Some("Pane"),
+    ),
+    (
+        "workbench.action.navigateBack",
+        "pane::GoBack",
+        Some("Pane"),
+    ),
+    (
+        "workbench.action.navigateForward",
+        "pane::GoForward",
+        Some("Pane"),
+    ),
+    (
+        "workbench.action.gotoSymbol",
+        "outline::Toggle",
+        Some("Editor"),
+    ),
+    (
+        "workbench.action.gotoLine",
+        "go_to_line::Toggle",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.formatDocument",
+        "editor::Format",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.commentLine",
+        "editor::ToggleComments",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.copyLinesDownAction",
+        "editor::DuplicateLineDown",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.copyLinesUpAction",
+        "editor::DuplicateLineUp",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.moveLinesUpAction",
+        "editor::MoveLineUp",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.moveLinesDownAction",
+        "editor::MoveLineDown",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.deleteLines",
+        "editor::DeleteLine",
+        Some("Editor"),
+    ),
+    ("editor.action.rename", "editor::Rename", Some("Editor")),
+    (
+        "editor.action.revealDefinition",
+        "editor::GoToDefinition",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.goToReferences",
+        "editor::FindAllReferences",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.quickFix",
+        "editor::ToggleCodeActions",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.triggerSuggest",
+        "editor::ShowCompletions",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.triggerParameterHints",
+        "editor::ShowSignatureHelp",
+        Some("Editor"),
+    ),
+    ("editor.action.showHover", "editor::Hover", Some("Editor")),
+    (
+        "editor.action.selectHighlights",
+        "editor::SelectAllMatches",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.insertCursorAbove",
+        "editor::AddSelectionAbove",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.insertCursorBelow",
+        "editor::AddSelectionBelow",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.jumpToBracket",
+        "editor::MoveToEnclosingBracket",
+        Some("Editor"),
+    ),
+    ("editor.fold", "editor::Fold", Some("Editor")),
+    ("editor.unfold", "editor::UnfoldLines", Some("Editor")),
+    ("undo", "editor::Undo", Some("Editor")),
+    ("redo", "editor::Redo", Some("Editor")),
+    ("cursorUndo", "editor::UndoSelection", Some("Editor")),
+    (
+        "editor.action.indentLines",
+        "editor::Indent",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.outdentLines",
+        "editor::Outdent",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.marker.next",
+        "editor::GoToDiagnostic",
+        Some("Editor"),
+    ),
+    (
+        "editor.action.marker.prev",
+        "editor::GoToPrevDiagnostic",
+        Some("Editor"),
+    ),
+    ("actions.find", "buffer_search::Deploy", Some("Editor")),
+];
+
+/// The settings and key bindings translated from VS Code, and what couldn't be translated.
+#[derive(Debug, Default, PartialEq)]
+pub struct VsCodeImport {
+    /// The key paths of the settings in Zed's settings file, with their values.
+    pub settings: Vec<(Vec<String>, Value)>,
+    pub bindings: Vec<ImportedBinding>,
+    /// The VS Code settings and key bindings that were imported with a change in meaning,
+    /// with the change.
+    pub changed: Vec<(String, &'static str)>,
+    /// The VS Code settings and key bindings that weren't imported, with the reason why.
+    pub untranslated: Vec<(String, &'static str)>,
+}
+
+/// A key binding for Zed's keymap file.
+#[derive(Debug, PartialEq)]
+pub struct ImportedBinding {
+    pub context: Option<String>,
+    pub keystrokes: String,
+    pub action: Value,
+}
+
+impl VsCodeImport {
+    /// Translates the contents of VS Code's `settings.json`. Themes are only imported when
+    /// a theme with the same name is installed.
+    pub fn import_settings(&mut self, settings: &Value, theme_names: &[SharedString]) {
+        let Some(settings) = settings.as_object() else {
+            self.untranslated
+                .push(("settings.json".into(), "not a JSON object"));
+            return;
+        };
+        for (key, value) in settings {
+            if let Some(languages) = key.strip_prefix('[').and_then(|key| key.strip_suffix(']')) {
+                self.import_language_settings(key, languages, value, settings, theme_names);
+                continue;
+            }
+            match translate_setting(key, value, settings, theme_names) {
+                Ok(translated) => self.settings.extend(
+                    translated
+                        .into_iter()
+                        .map(|(key_path, value)| (key_path_vec(&[], key_path), value)),
+                ),
+                Err(reason) => self.untranslated.push((key.clone(), reason)),
+            }
+        }
+    }
+
+    fn import_language_settings(
+        &mut self,
+        block_key: &str,
+        languages: &str,
+        value: &Value,
+        settings: &Map<String, Value>,
+        theme_names: &[SharedString],
+    ) {
+        let Some(language_settings) = value.as_object() else {
+            self.untranslated
+                .push((block_key.to_string(), "not a JSON object"));
+            return;
+        };
+        // A block can apply to several languages, as in `"[javascript][typescript]"`.
+        for language in languages.split("][") {
+            let Some(language_name) = language_name(language) else {
+                self.untranslated
+                    .push((format!("[{language}]"), "unknown language"));
+                continue;
+            };
+            for (key, value) in language_settings {
+                let item = format!("[{language}] {key}");
+                match translate_setting(key, value, settings, theme_names) {
+                    Ok(translated) => {
+                        for (key_path, value) in translated {
+                            if LANGUAGE_SETTINGS.contains(&key_path[0]) {
+                                self.settings.push((
+                                    key_path_vec(&["languages", &language_name], key_path),
+                                    value,
+                                ));
+                            } else {
+                                self.untranslated
+                                    .push((item.clone(), "not a language setting in Zed"));
+                            }
+                        }
+                    }
+                    Err(reason) => self.untranslated.push((item, reason)),
+                }
+            }
+        }
+    }
+
+    /// Translates the contents of VS Code's `keybindings.json`.
+    pub fn import_keybindings(&mut self, keybindings: &Value) {
+        let Some(keybindings) = keybindings.as_array() else {
+            self.untranslated
+                .push(("keybindings.json".into(), "not a JSON array"));
+            return;
+        };
+        for keybinding in keybindings {
+            let key = keybinding.get("key").and_then(Value::as_str);
+            let command = keybinding.get("command").and_then(Value::as_str);
+            let (Some(key), Some(command)) = (key, command) else {
+                self.untranslated
+                    .push((keybinding.to_string(), "missing key or command"));
+                continue;
+            };
+            let item = format!("{key}: {command}");
+            let Some(keystrokes) = translate_keystrokes(key) else {
+                self.untranslated.push((item, "unsupported key"));
+                continue;
+            };
+            // VS Code removes a default binding with a command prefixed with a dash. Zed's
+            // default bindings are different, so there's nothing to remove.
+            if command.starts_with('-') {
+                self.untranslated
+                    .push((item, "removes one of VS Code's default bindings"));
+                continue;
+            }
+            let Some((action, context)) = translate_command(command, keybinding.get("args")) else {
+                self.untranslated.push((item, "no equivalent action"));
+                continue;
+            };
+            if keybinding.get("when").is_some() {
+                self.changed
+                    .push((item, "imported without its `when` clause"));
+            }
+            self.bindings.push(ImportedBinding {
+                context: context.map(ToString::to_string),
+                keystrokes,
+                action,
+            });
+        }
+    }
+
+    /// Returns a Markdown report of the import, with the diffs of the files it changes.
+    pub fn report(&self, user_dir: &Path, diffs: &[(&str, String)]) -> String {
+        let mut report = format!(
+            "# VS Code Import\n\nFound in `{}`:\n\n- {} settings for `settings.json`\n- {} key bindings for `keymap.json`\n",
+            user_dir.display(),
+            self.settings.len(),
+            self.bindings.len(),
+        );
+        if !self.changed.is_empty() {
+            report.push_str("\n## Imported with changes\n\n");
+            for (item, change) in &self.changed {
+                report.push_str(&format!("- `{item}`: {change}\n"));
+            }
+        }
+        if !self.untranslated.is_empty() {
+            report.push_str("\n## Not imported\n\n");
+            for (item, reason) in &self.untranslated {
+                report.push_str(&format!("- `{item}`: {reason}\n"));
+            }
+        }
+        for (file_name, diff) in diffs {
+            report.push_str(&format!(
+                "\n## Changes to `{file_name}`\n\n```diff\n{diff}```\n"
+            ));
+        }
+        report
+    }
+}
+
+fn key_path_vec(prefix: &[&str], key_path: &[&str]) -> Vec<String> {
+    prefix
+        .iter()
+        .chain(key_path)
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn language_name(language: &str) -> Option<String> {
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(id, _)| *id == language)
+        .map(|(_, name)| name.to_string())
+}
+
+/// Returns the Zed settings for a VS Code setting, or why it has no equivalent. The other
+/// VS Code settings are needed by the settings that depend on each other.
+fn translate_setting(
+    key: &str,
+    value: &Value,
+    settings: &Map<String, Value>,
+    theme_names: &[SharedString],
+) -> Result<Vec<(&'static [&'static str], Value)>, &'static str> {
+    const UNSUPPORTED_VALUE: &str = "unsupported value";
+    let setting = |key_path: &'static [&'static str], value: Value| Ok(vec![(key_path, value)]);
+    let bool_value = || value.as_bool().ok_or(UNSUPPORTED_VALUE);
+    let number_value = || {
+        value
+            .as_f64()
+            .filter(|value| *value > 0.)
+            .map(|_| value.clone())
+            .ok_or(UNSUPPORTED_VALUE)
+    };
+    // Zed's settings for sizes in columns and characters are integers.
+    let integer_value = || {
+        value
+            .as_f64()
+            .filter(|value| *value > 0. && value.fract() == 0.)
+            .map(|value| json!(value as u64))
+            .ok_or(UNSUPPORTED_VALUE)
+    };
+    let str_value = || value.as_str().ok_or(UNSUPPORTED_VALUE);
+
+    match key {
+        "editor.fontSize" => setting(&["buffer_font_size"], number_value()?),
+        "editor.fontFamily" => {
+            // VS Code takes a CSS font list, of which Zed uses the first font.
+            let family = str_value()?
+                .split(',')
+                .next()
+                .map(|family| family.trim().trim_matches(|c| c == '\'' || c == '"'))
+                .filter(|family| !family.is_empty())
+                .ok_or(UNSUPPORTED_VALUE)?;
+            setting(&["buffer_font_family"], json!(family))
+        }
+        "editor.fontWeight" => {
+            let weight = match value {
+                Value::Number(weight) => weight.as_f64(),
+                Value::String(weight) if weight == "normal" => Some(400.),
+                Value::String(weight) if weight == "bold" => Some(700.),
+                Value::String(weight) => weight.parse().ok(),
+                _ => None,
+            };
+            setting(
+                &["buffer_font_weight"],
+                json!(weight.ok_or(UNSUPPORTED_VALUE)?),
+            )
+        }
+        "editor.lineHeight" => {
+            // VS Code reads line heights below 8 as multiples of the font size, and the
+            // others as pixels, which Zed doesn't support.
+            match value.as_f64() {
+                Some(0.) => setting(&["buffer_line_height"], json!("comfortable")),
+                Some(height) if height >= 1. && height < 8. => {
+                    setting(&["buffer_line_height"], json!({ "custom": height }))
+                }
+                _ => Err(UNSUPPORTED_VALUE),
+            }
+        }
+        "editor.fontLigatures" => match value {
+            Value::Bool(false) => setting(&["buffer_font_features"], json!({ "calt": false })),
+            Value::Bool(true) => Ok(Vec::new()),
+            _ => Err(UNSUPPORTED_VALUE),
+        },
+        "editor.tabSize" => setting(&["tab_size"], integer_value()?),
+        "editor.insertSpaces" => setting(&["hard_tabs"], json!(!bool_value()?)),
+        "editor.wordWrap" => {
+            let soft_wrap = match str_value()? {
+                "off" => "none",
+                "on" => "editor_width",
+                "wordWrapColumn" => "preferred_line_length",
+                "bounded" => "bounded",
+                _ => return Err(UNSUPPORTED_VALUE),
+            };
+            setting(&["soft_wrap"], json!(soft_wrap))
+        }
+        "editor.wordWrapColumn" => setting(&["preferred_line_length"], integer_value()?),
+        "editor.rulers" => {
+            let columns = value
+                .as_array()
+                .ok_or(UNSUPPORTED_VALUE)?
+                .iter()
+                .map(|ruler| {
+                    ruler
+                        .as_u64()
+                        .or_else(|| ruler.get("column")?.as_u64())
+                        .ok_or(UNSUPPORTED_VALUE)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            setting(&["wrap_guides"], json!(columns))
+        }
+        "editor.formatOnSave" => setting(
+            &["format_on_save"],
+            json!(if bool_value()? { "on" } else { "off" }),
+        ),
+        "editor.cursorBlinking" => match str_value()? {
+            "solid" => setting(&["cursor_blink"], json!(false)),
+            "blink" => setting(&["cursor_blink"], json!(true)),
+            _ => Err(UNSUPPORTED_VALUE),
+        },
+        "editor.renderWhitespace" => {
+            let show_whitespaces = match str_value()? {
+                "none" => "none",
+                "all" => "all",
+                "selection" => "selection",
+                "boundary" => "boundary",
+                _ => return Err(UNSUPPORTED_VALUE),
+            };
+            setting(&["show_whitespaces"], json!(show_whitespaces))
+        }
+        "editor.lineNumbers" => match str_value()? {
+            "off" => setting(&["gutter", "line_numbers"], json!(false)),
+            "on" => setting(&["relative_line_numbers"], json!(false)),
+            "relative" => setting(&["relative_line_numbers"], json!(true)),
+            _ => Err(UNSUPPORTED_VALUE),
+        },
+        "editor.inlayHints.enabled" => match value {
+            Value::Bool(enabled) => setting(&["inlay_hints", "enabled"], json!(enabled)),
+            Value::String(enabled) if enabled == "on" || enabled == "off" => {
+                setting(&["inlay_hints", "enabled"], json!(enabled == "on"))
+            }
+            _ => Err(UNSUPPORTED_VALUE),
+        },
+        "editor.scrollBeyondLastLine" => {
+            let scroll = if bool_value()? { "one_page" } else { "off" };
+            setting(&["scroll_beyond_last_line"], json!(scroll))
+        }
+        "editor.cursorSurroundingLines" => setting(&["vertical_scroll_margin"], number_value()?),
+        "editor.hover.enabled" => setting(&["hover_popover_enabled"], json!(bool_value()?)),
+        "editor.autoClosingBrackets" => setting(&["use_autoclose"], json!(str_value()? != "never")),
+        "editor.linkedEditing" => setting(&["linked_edits"], json!(bool_value()?)),
+        "files.autoSave" => {
+            let autosave = match str_value()? {
+                "off" => json!("off"),
+                "afterDelay" => {
+                    let milliseconds = settings
+                        .get("files.autoSaveDelay")
+                        .and_then(Value::as_u64)
+                        .unwrap_or(1000);
+                    json!({ "after_delay": { "milliseconds": milliseconds } })
+                }
+                "onFocusChange" => json!("on_focus_change"),
+                "onWindowChange" => json!("on_window_change"),
+                _ => return Err(UNSUPPORTED_VALUE),
+            };
+            setting(&["autosave"], autosave)
+        }
+        // Imported along with `files.autoSave`.
+        "files.autoSaveDelay" if settings.contains_key("files.autoSave") => Ok(Vec::new()),
+        "files.trimTrailingWhitespace" => setting(
+            &["remove_trailing_whitespace_on_save"],
+            json!(bool_value()?),
+        ),
+        "files.insertFinalNewline" => {
+            setting(&["ensure_final_newline_on_save"], json!(bool_value()?))
+        }
+        "workbench.colorTheme" => {
+            let theme = str_value()?;
+            if !theme_names.iter().any(|name| name.as_ref() == theme) {
+                return Err("theme not installed");
+            }
+            setting(&["theme"], json!(theme))
+        }
+        "terminal.integrated.fontSize" => setting(&["terminal", "font_size"], number_value()?),
+        "terminal.integrated.fontFamily" => {
+            setting(&["terminal", "font_family"], json!(str_value()?))
+        }
+        "telemetry.telemetryLevel" => {
+            let (diagnostics, metrics) = match str_value()? {
+                "all" => (true, true),
+                "error" | "crash" => (true, false),
+                "off" => (false, false),
+                _ => return Err(UNSUPPORTED_VALUE),
+            };
+            const DIAGNOSTICS: &[&str] = &["telemetry", "diagnostics"];
+            const METRICS: &[&str] = &["telemetry", "metrics"];
+            Ok(vec![
+                (DIAGNOSTICS, json!(diagnostics)),
+                (METRICS, json!(metrics)),
+            ])
+        }
+        _ => Err("no equivalent setting"),
+    }
+}
+
+/// Translates VS Code's key syntax, as in `ctrl+shift+p ctrl+k`, to Zed's.
+fn translate_keystrokes(key: &str) -> Option<String> {
+    let keystrokes =
+        key.split_whitespace()
+            .map(|keystroke| {
+                keystroke
+                    .to_lowercase()
+                    .split('+')
+                    .map(|key| match key {
+                        "ctrl" | "shift" | "alt" | "cmd" => Some(key.to_string()),
+                        "meta" | "win" => Some("super".to_string()),
+                        "escape" | "enter" | "tab" | "space" | "backspace" | "delete"
+                        | "insert" | "home" | "end" | "pageup" | "pagedown" | "up" | "down"
+                        | "left" | "right" => Some(key.to_string()),
+                        _ if key.len() > 1
+                            && key.starts_with('f')
+                            && key[1..].parse::<u8>().is_ok() =>
+                        {
+                            Some(key.to_string())
+                        }
+                        _ if key.chars().count() == 1 && !key.starts_with(char::is_whitespace) => {
+                            Some(key.to_string())
+                        }
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map(|keys| keys.join("-"))
+            })
+            .collect::<Option<Vec<_>>>()?;
+    (!keystrokes.is_empty()).then(|| keystrokes.join(" "))
+}
+
+/// Returns the Zed action for a VS Code command, and the context it's bound in.
+fn translate_command(command: &str, args: Option<&Value>) -> Option<(Value, Option<&'static str>)> {
+    if command == "editor.action.addSelectionToNextFindMatch" {
+        return Some((
+            json!(["editor::SelectNext", { "replace_newest": false }]),
+            Some("Editor"),
+        ));
+    }
+    // Commands taking arguments don't have an equivalent action.
+    if args.is_some() {
+        return None;
+    }
+    COMMANDS
+        .iter()
+        .find(|(vscode_command, _, _)| *vscode_command == command)
+        .map(|(_, action, context)| (json!(action), *context))
+}
+
+/// Imports the user's VS Code settings and key bindings into Zed's settings and keymap files,
+/// after showing a report of what would be imported and the changes to the files, and
+/// asking for confirmation.
+pub fn import_vscode_settings(
+    workspace: &mut Workspace,
+    _: &ImportVsCodeSettings,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    let theme_names = ThemeRegistry::global(cx).list_names(false);
+    cx.spawn(|workspace, mut cx| async move {
+        let user_dir = paths::vscode_user_dir();
+        let mut import = VsCodeImport::default();
+        let mut found = false;
+        if let Ok(text) = fs.load(&user_dir.join("settings.json")).await {
+            found = true;
+            match parse_json_with_comments::<Value>(&text) {
+                Ok(settings) => import.import_settings(&settings, &theme_names),
+                Err(_) => import
+                    .untranslated
+                    .push(("settings.json".into(), "invalid JSON")),
+            }
+        }
+        if let Ok(text) = fs.load(&user_dir.join("keybindings.json")).await {
+            found = true;
+            match parse_json_with_comments::<Value>(&text) {
+                Ok(keybindings) => import.import_keybindings(&keybindings),
+                Err(_) => import
+                    .untranslated
+                    .push(("keybindings.json".into(), "invalid JSON")),
+            }
+        }
+        if !found {
+            anyhow::bail!("no VS Code settings found in {user_dir:?}");
+        }
+
+        // The new text of each file that the import changes, along with its path.
+        let mut writes = Vec::new();
+        let mut diffs = Vec::new();
+        if !import.settings.is_empty() {
+            let (settings_path, old_text) = load_config_file(
+                fs.as_ref(),
+                paths::settings_file(),
+                settings::initial_user_settings_content().as_ref(),
+            )
+            .await?;
+            let new_text = cx.update(|cx| {
+                let store = cx.global::<SettingsStore>();
+                import
+                    .settings
+                    .iter()
+                    .fold(old_text.clone(), |text, (key_path, value)| {
+                        let key_path = key_path.iter().map(String::as_str).collect::<Vec<_>>();
+                        store.new_text_for_value_update(text, &key_path, value)
+                    })
+            })?;
+            if new_text != old_text {
+                diffs.push(("settings.json", unified_diff(&old_text, &new_text)));
+                writes.push((settings_path, new_text));
+            }
+        }
+        if !import.bindings.is_empty() {
+            let (keymap_path, old_text) = load_config_file(
+                fs.as_ref(),
+                paths::keymap_file(),
+                settings::initial_keymap_content().as_ref(),
+            )
+            .await?;
+            let new_text = import
+                .bindings
+                .iter()
+                .fold(old_text.clone(), |text, binding| {
+                    KeymapFile::new_text_for_binding_update(
+                        &text,
+                        binding.context.as_deref(),
+                        &binding.keystrokes,
+                        &binding.action,
+                    )
+                });
+            if new_text != old_text {
+                diffs.push(("keymap.json", unified_diff(&old_text, &new_text)));
+                writes.push((keymap_path, new_text));
+            }
+        }
+
+        open_report(workspace, import.report(user_dir, &diffs), &mut cx).await?;
+        if writes.is_empty() {
+            return Ok(());
+        }
+        let answer = cx.prompt(
+            PromptLevel::Info,
+            "Apply the VS Code import?",
+            Some("The changes to your settings and keymap are shown in the report."),
+            &["Apply", "Cancel"],
+        );
+        if answer.await? != 0 {
+            return Ok(());
+        }
+        for (path, text) in writes {
+            fs.atomic_write(path, text).await?;
+        }
+        Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+fn unified_diff(old_text: &str, new_text: &str) -> String {
+    TextDiff::from_lines(old_text, new_text)
+        .unified_diff()
+        .context_radius(2)
+        .to_string()
+}
+
+/// Loads a configuration file through its symlinks, or returns its initial content.
+async fn load_config_file(
+    fs: &dyn Fs,
+    path: &Path,
+    initial_content: &str,
+) -> Result<(PathBuf, String)> {
+    if fs.is_file(path).await {
+        let path = fs.canonicalize(path).await?;
+        let text = fs.load(&path).await?;
+        Ok((path, text))
+    } else {
+        Ok((path.to_path_buf(), initial_content.to_string()))
+    }
+}
+
+async fn open_report(
+    workspace: WeakView<Workspace>,
+    report: String,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let languages = workspace.update(cx, |workspace, _| workspace.app_state().languages.clone())?;
+    let language = languages.language_for_name("Markdown").await.log_err();
+    workspace
+        .update(cx, |workspace, cx| {
+            workspace.with_local_workspace(cx, |workspace, cx| {
+                let project = workspace.project();
+                let buffer = project.update(cx, move |project, cx| {
+                    project.create_local_buffer(&report, language, cx)
+                });
+                let buffer = cx.new_model(|cx| {
+                    MultiBuffer::singleton(buffer, cx).with_title("VS Code Import".into())
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(cx.new_view(|cx| {
+                        Editor::for_multibuffer(buffer, Some(project.clone()), true, cx)
+                    })),
+                    None,
+                    true,
+                    cx,
+                );
+            })
+        })?
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn settings(import: &VsCodeImport) -> Vec<(String, Value)> {
+        import
+            .settings
+            .iter()
+            .map(|(key_path, value)| (key_path.join("."), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_import_settings() {
+        let mut import = VsCodeImport::default();
+        import.import_settings(
+            &json!({
+                "editor.fontSize": 13,
+                "editor.fontFamily": "'Fira Code', Menlo, monospace",
+                "editor.insertSpaces": false,
+                "editor.wordWrap": "wordWrapColumn",
+                "editor.rulers": [80, { "column": 100, "color": "#ff0000" }],
+                "editor.lineNumbers": "off",
+                "editor.minimap.enabled": false,
+                "files.autoSave": "afterDelay",
+                "files.autoSaveDelay": 500,
+                "workbench.colorTheme": "One Dark",
+                "[rust][python]": {
+                    "editor.tabSize": 4,
+                    "editor.fontSize": 12,
+                },
+                "[brainfuck]": { "editor.tabSize": 8 },
+                "[go]": { "editor.tabSize": 4.5 },
+                "editor.renderWhitespace": "trailing",
+            }),
+            &["One Dark".into()],
+        );
+        assert_eq!(
+            settings(&import),
+            [
+                ("buffer_font_size".to_string(), json!(13)),
+                ("buffer_font_family".to_string(), json!("Fira Code")),
+                ("hard_tabs".to_string(), json!(true)),
+                ("soft_wrap".to_string(), json!("preferred_line_length")),
+                ("wrap_guides".to_string(), json!([80, 100])),
+                ("gutter.line_numbers".to_string(), json!(false)),
+                (
+                    "autosave".to_string(),
+                    json!({ "after_delay": { "milliseconds": 500 } })
+                ),
+                ("theme".to_string(), json!("One Dark")),
+                ("languages.Rust.tab_size".to_string(), json!(4)),
+                ("languages.Python.tab_size".to_string(), json!(4)),
+            ]
+        );
+        assert_eq!(
+            import.untranslated,
+            [
+                (
+                    "editor.minimap.enabled".to_string(),
+                    "no equivalent setting"
+                ),
+                (
+                    "[rust] editor.fontSize".to_string(),
+                    "not a language setting in Zed"
+                ),
+                (
+                    "[python] editor.fontSize".to_string(),
+                    "not a language setting in Zed"
+                ),
+                ("[brainfuck]".to_string(), "unknown language"),
+                ("[go] editor.tabSize".to_string(), "unsupported value"),
+                ("editor.renderWhitespace".to_string(), "unsupported value"),
+            ]
+        );
+
+        let mut import = VsCodeImport::default();
+        import.import_settings(
+            &json!({ "workbench.colorTheme": "Monokai Dimmed" }),
+            &["One Dark".into()],
+        );
+        assert!(import.settings.is_empty());
+        assert_eq!(
+            import.untranslated,
+            [("workbench.colorTheme".to_string(), "theme not installed")]
+        );
+    }
+
+    #[test]
+    fn test_import_keybindings() {
+        let mut import = VsCodeImport::default();
+        import.import_keybindings(&json!([
+            { "key": "ctrl+shift+p", "command": "workbench.action.quickOpen" },
+            { "key": "ctrl+k ctrl+c", "command": "editor.action.commentLine", "when": "editorTextFocus" },
+            { "key": "meta+d", "command": "editor.action.addSelectionToNextFindMatch" },
+            { "key": "ctrl+w", "command": "-workbench.action.closeActiveEditor" },
+            { "key": "ctrl+alt+t", "command": "workbench.action.selectTheme" },
+            { "key": "ctrl+[Slash]", "command": "editor.action.commentLine" },
+            { "command": "workbench.action.quickOpen" },
+        ]));
+        assert_eq!(
+            import.bindings,
+            [
+                ImportedBinding {
+                    context: None,
+                    keystrokes: "ctrl-shift-p".into(),
+                    action: json!("file_finder::Toggle"),
+                },
+                ImportedBinding {
+                    context: Some("Editor".into()),
+                    keystrokes: "ctrl-k ctrl-c".into(),
+                    action: json!("editor::ToggleComments"),
+                },
+                ImportedBinding {
+                    context: Some("Editor".into()),
+                    keystrokes: "super-d".into(),
+                    action: json!(["editor::SelectNext", { "replace_newest": false }]),
+                },
+            ]
+        );
+        assert_eq!(
+            import.changed,
+            [(
+                "ctrl+k ctrl+c: editor.action.commentLine".to_string(),
+                "imported without its `when` clause"
+            )]
+        );
+        assert_eq!(
+            import.untranslated,
+            [
+                (
+                    "ctrl+w: -workbench.action.closeActiveEditor".to_string(),
+                    "removes one of VS Code's default bindings"
+                ),
+                (
+                    "ctrl+alt+t: workbench.action.selectTheme".to_string(),
+                    "no equivalent action"
+                ),
+                (
+                    "ctrl+[Slash]: editor.action.commentLine".to_string(),
+                    "unsupported key"
+                ),
+                (
+                    r#"{"command":"workbench.action.quickOpen"}"#.to_string(),
+                    "missing key or command"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,601 @@
+use anyhow::{anyhow, Context as _, Result};
+use collections::HashMap;
+
+/// A word list in the format of Hunspell dictionaries: an affix file describing how
+/// prefixes and suffixes can be attached to words, and a dictionary file listing the
+/// words along with the affixes that they accept.
+///
+/// Only the parts of the format that generate words are supported. Words aren't expanded
+/// with their affixes when the dictionary is loaded: checking a word strips the affixes it
+/// may have and looks up the root that remains instead. Compounding and affixes of affixed
+/// words aren't supported.
+pub struct Dictionary {
+    /// The root words, with the flags of the affixes that they accept.
+    roots: HashMap<String, Vec<String>>,
+    affixes: HashMap<String, Affix>,
+    /// The prefix rules by the text they add, as the flag of their affix and their index.
+    prefix_rules: HashMap<String, Vec<(String, usize)>>,
+    /// The suffix rules by the text they add, as the flag of their affix and their index.
+    suffix_rules: HashMap<String, Vec<(String, usize)>>,
+    /// Flags marking words that are only valid with an affix, or not valid at all.
+    excluded_root_flags: Vec<String>,
+    try_chars: Vec<char>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FlagFormat {
+    #[default]
+    Char,
+    Long,
+    Numeric,
+}
+
+#[derive(Debug)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<ConditionPart>,
+}
+
+#[derive(Debug)]
+struct Affix {
+    is_prefix: bool,
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+#[derive(Debug)]
+enum ConditionPart {
+    Any,
+    Char(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+#[derive(Default)]
+struct AffixFile {
+    flag_format: FlagFormat,
+    flag_aliases: Vec<String>,
+    affixes: HashMap<String, Affix>,
+    try_chars: Vec<char>,
+    /// Flags marking words that are only valid with an affix, or not valid at all.
+    excluded_root_flags: Vec<String>,
+}
+
+impl Dictionary {
+    /// Parses a dictionary from the contents of its `.aff` and `.dic` files.
+    pub fn new(aff: &str, dic: &str) -> Result<Self> {
+        let affix_file = AffixFile::parse(aff)?;
+        let mut roots = HashMap::<String, Vec<String>>::default();
+
+        for line in dic.lines().skip(1) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // Morphological fields follow the word, separated by whitespace.
+            let entry = line.split(['\t', ' ']).next().unwrap_or_default();
+            let (root, flags) = match entry.split_once('/') {
+                Some((root, flags)) => (root, affix_file.parse_flags(flags)),
+                None => (entry, Vec::new()),
+            };
+            if root.is_empty() {
+                continue;
+            }
+            // A word may be listed several times, with different flags.
+            roots.entry(root.to_string()).or_default().extend(flags);
+        }
+
+        let mut prefix_rules = HashMap::<String, Vec<(String, usize)>>::default();
+        let mut suffix_rules = HashMap::<String, Vec<(String, usize)>>::default();
+        for (flag, affix) in &affix_file.affixes {
+            let rules = if affix.is_prefix {
+                &mut prefix_rules
+            } else {
+                &mut suffix_rules
+            };
+            for (ix, rule) in affix.rules.iter().enumerate() {
+                rules
+                    .entry(rule.add.clone())
+                    .or_default()
+                    .push((flag.clone(), ix));
+            }
+        }
+
+        let try_chars = if affix_file.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            affix_file.try_chars
+        };
+        Ok(Self {
+            roots,
+            affixes: affix_file.affixes,
+            prefix_rules,
+            suffix_rules,
+            excluded_root_flags: affix_file.excluded_root_flags,
+            try_chars,
+        })
+    }
+
+    /// Creates a dictionary from a plain list of words.
+    pub fn from_words<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            roots: words
+                .into_iter()
+                .map(|word| (word.to_string(), Vec::new()))
+                .collect(),
+            affixes: HashMap::default(),
+            prefix_rules: HashMap::default(),
+            suffix_rules: HashMap::default(),
+            excluded_root_flags: Vec::new(),
+            try_chars: ('a'..='z').collect(),
+        }
+    }
+
+    /// The number of root words in the dictionary, without their affixed forms.
+    pub fn len(&self) -> usize {
+        self.roots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Whether the dictionary contains the word. Capitalized and uppercase words are also
+    /// accepted when the dictionary contains their lowercase form.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.contains_exact(word) {
+            return true;
+        }
+        match Casing::of(word) {
+            Casing::Lower | Casing::Mixed => false,
+            Casing::Capitalized => self.contains_exact(&word.to_lowercase()),
+            Casing::Upper => {
+                self.contains_exact(&word.to_lowercase())
+                    || self.contains_exact(&Casing::Capitalized.apply(&word.to_lowercase()))
+            }
+        }
+    }
+
+    /// Whether the dictionary contains the word as it's spelled: as a root, or as a root
+    /// with a prefix, a suffix or both.
+    fn contains_exact(&self, word: &str) -> bool {
+        if self.roots.get(word).map_or(false, |flags| {
+            !flags
+                .iter()
+                .any(|flag| self.excluded_root_flags.contains(flag))
+        }) {
+            return true;
+        }
+        if self.has_suffixed_root(word, None) {
+            return true;
+        }
+
+        for (ix, _) in word.char_indices().skip(1) {
+            let (added, rest) = word.split_at(ix);
+            for (flag, rule_ix) in self.prefix_rules.get(added).into_iter().flatten() {
+                let affix = &self.affixes[flag];
+                let rule = &affix.rules[*rule_ix];
+                let root = format!("{}{rest}", rule.strip);
+                if !condition_matches(rule.condition.iter(), root.chars()) {
+                    continue;
+                }
+                if self.root_has_flags(&root, flag, None)
+                    || (affix.cross_product && self.has_suffixed_root(&root, Some(flag.as_str())))
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether the word is a root with one of the suffixes it accepts. With a prefix flag,
+    /// the root must also accept that prefix, and the suffix must combine with it.
+    fn has_suffixed_root(&self, word: &str, prefix_flag: Option<&str>) -> bool {
+        let split_ixs = word
+            .char_indices()
+            .map(|(ix, _)| ix)
+            .skip(1)
+            .chain([word.len()]);
+        for ix in split_ixs {
+            let (stem, added) = word.split_at(ix);
+            for (flag, rule_ix) in self.suffix_rules.get(added).into_iter().flatten() {
+                let affix = &self.affixes[flag];
+                if prefix_flag.is_some() && !affix.cross_product {
+                    continue;
+                }
+                let rule = &affix.rules[*rule_ix];
+                let root = format!("{stem}{}", rule.strip);
+                if condition_matches(rule.condition.iter().rev(), root.chars().rev())
+                    && self.root_has_flags(&root, flag, prefix_flag)
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn root_has_flags(&self, root: &str, flag: &str, other_flag: Option<&str>) -> bool {
+        self.roots.get(root).map_or(false, |flags| {
+            flags.iter().any(|root_flag| root_flag == flag)
+                && other_flag.map_or(true, |other_flag| {
+                    flags.iter().any(|root_flag| root_flag == other_flag)
+                })
+        })
+    }
+
+    /// Returns up to `limit` words from the dictionary that the given word is likely a
+    /// misspelling of, with the closest ones first.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let casing = Casing::of(word);
+        let lowercase = word.to_lowercase();
+        let mut suggestions = Vec::new();
+        // Keep the case of the misspelled word, unless the dictionary spells a word with
+        // capitals, like a name.
+        let push = |candidate: String, suggestions: &mut Vec<String>| {
+            let candidate = if Casing::of(&candidate) == Casing::Lower {
+                casing.apply(&candidate)
+            } else {
+                candidate
+            };
+            if !suggestions.contains(&candidate) {
+                suggestions.push(candidate);
+            }
+        };
+
+        let edits = self.edits(&lowercase);
+        for candidate in &edits {
+            if suggestions.len() >= limit {
+                return suggestions;
+            }
+            if let Some(candidate) = self.lookup(candidate) {
+                push(candidate, &mut suggestions);
+            }
+        }
+
+        // Words that were run together.
+        for (ix, _) in lowercase.char_indices().skip(1) {
+            if suggestions.len() >= limit {
+                return suggestions;
+            }
+            let (first, second) = lowercase.split_at(ix);
+            if self.contains(first) && self.contains(second) {
+                push(format!("{first} {second}"), &mut suggestions);
+            }
+        }
+
+        if suggestions.is_empty() {
+            for edit in &edits {
+                for candidate in self.edits(edit) {
+                    if suggestions.len() >= limit {
+                        return suggestions;
+                    }
+                    if let Some(candidate) = self.lookup(&candidate) {
+                        push(candidate, &mut suggestions);
+                    }
+                }
+            }
+        }
+        suggestions
+    }
+
+    /// Returns how the dictionary spells a lowercase candidate, which is capitalized for
+    /// names.
+    fn lookup(&self, candidate: &str) -> Option<String> {
+        if self.contains(candidate) {
+            return Some(candidate.to_string());
+        }
+        let capitalized = Casing::Capitalized.apply(candidate);
+        self.contains_exact(&capitalized).then_some(capitalized)
+    }
+
+    /// The words that are a single replacement, transposition, deletion or insertion away
+    /// from the given word, in that order.
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let mut edits = Vec::new();
+        for (ix, char) in chars.iter().enumerate() {
+            for replacement in &self.try_chars {
+                if replacement != char {
+                    let mut edit = chars.clone();
+                    edit[ix] = *replacement;
+                    edits.push(edit.into_iter().collect());
+                }
+            }
+        }
+        for ix in 1..chars.len() {
+            let mut edit = chars.clone();
+            edit.swap(ix - 1, ix);
+            edits.push(edit.into_iter().collect());
+        }
+        for ix in 0..chars.len() {
+            let mut edit = chars.clone();
+            edit.remove(ix);
+            edits.push(edit.into_iter().collect());
+        }
+        for ix in 0..=chars.len() {
+            for insertion in &self.try_chars {
+                let mut edit = chars.clone();
+                edit.insert(ix, *insertion);
+                edits.push(edit.into_iter().collect());
+            }
+        }
+        edits
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+impl Casing {
+    fn of(word: &str) -> Self {
+        let mut chars = word.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = chars.next() else {
+            return Self::Lower;
+        };
+        let rest = chars.collect::<Vec<_>>();
+        if first.is_lowercase() {
+            if rest.iter().all(|c| c.is_lowercase()) {
+                Self::Lower
+            } else {
+                Self::Mixed
+            }
+        } else if rest.iter().all(|c| c.is_lowercase()) {
+            Self::Capitalized
+        } else if rest.iter().all(|c| c.is_uppercase()) {
+            Self::Upper
+        } else {
+            Self::Mixed
+        }
+    }
+
+    fn apply(self, word: &str) -> String {
+        match self {
+            Self::Lower | Self::Mixed => word.to_string(),
+            Self::Upper => word.to_uppercase(),
+            Self::Capitalized => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+        }
+    }
+}
+
+impl AffixFile {
+    fn parse(aff: &str) -> Result<Self> {
+        let mut file = Self::default();
+        let mut has_alias_count = false;
+        let mut lines = aff.lines().enumerate();
+        while let Some((row, line)) = lines.next() {
+            let mut fields = line.split_whitespace();
+            let Some(keyword) = fields.next() else {
+                continue;
+            };
+            match keyword {
+                "FLAG" => {
+                    file.flag_format = match fields.next() {
+                        Some("long") => FlagFormat::Long,
+                        Some("num") => FlagFormat::Numeric,
+                        _ => FlagFormat::Char,
+                    }
+                }
+                "TRY" => file.try_chars = fields.next().unwrap_or_default().chars().collect(),
+                "AF" => {
+                    // The first `AF` line holds the number of aliases, the others the flags.
+                    let flags = fields.next().unwrap_or_default();
+                    if has_alias_count {
+                        file.flag_aliases.push(flags.to_string());
+                    }
+                    has_alias_count = true;
+                }
+                "NEEDAFFIX" | "FORBIDDENWORD" | "ONLYINCOMPOUND" => {
+                    if let Some(flag) = fields.next() {
+                        file.excluded_root_flags.push(flag.to_string());
+                    }
+                }
+                "PFX" | "SFX" => {
+                    let flag = fields
+                        .next()
+                        .with_context(|| format!("missing affix flag on line {}", row + 1))?
+                        .to_string();
+                    let cross_product = fields.next() == Some("Y");
+                    let count = fields
+                        .next()
+                        .and_then(|count| count.parse::<usize>().ok())
+                        .with_context(|| format!("invalid affix count on line {}", row + 1))?;
+                    let mut rules = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        let (row, line) = lines
+                            .next()
+                            .ok_or_else(|| anyhow!("missing rules for affix {flag}"))?;
+                        let mut fields = line.split_whitespace().skip(2);
+                        let (Some(strip), Some(add)) = (fields.next(), fields.next()) else {
+                            return Err(anyhow!("invalid affix rule on line {}", row + 1));
+                        };
+                        let condition = fields.next().unwrap_or(".");
+                        // Flags of the affixed word, for affixing it further, are ignored.
+                        let add = add.split('/').next().unwrap_or_default();
+                        rules.push(AffixRule {
+                            strip: if strip == "0" { "" } else { strip }.to_string(),
+                            add: if add == "0" { "" } else { add }.to_string(),
+                            condition: parse_condition(condition),
+                        });
+                    }
+                    file.affixes.insert(
+                        flag,
+                        Affix {
+                            is_prefix: keyword == "PFX",
+                            cross_product,
+                            rules,
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+        Ok(file)
+    }
+
+    fn parse_flags(&self, flags: &str) -> Vec<String> {
+        if !self.flag_aliases.is_empty() {
+            if let Some(alias) = flags
+                .parse::<usize>()
+                .ok()
+                .and_then(|ix| self.flag_aliases.get(ix.checked_sub(1)?))
+            {
+                return self.split_flags(alias);
+            }
+        }
+        self.split_flags(flags)
+    }
+
+    fn split_flags(&self, flags: &str) -> Vec<String> {
+        match self.flag_format {
+            FlagFormat::Char => flags.chars().map(String::from).collect(),
+            FlagFormat::Long => flags
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|chunk| chunk.iter().collect())
+                .collect(),
+            FlagFormat::Numeric => flags.split(',').map(str::to_string).collect(),
+        }
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<ConditionPart> {
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '.' => parts.push(ConditionPart::Any),
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                for (ix, char) in chars.by_ref().enumerate() {
+                    match char {
+                        '^' if ix == 0 => negated = true,
+                        ']' => break,
+                        char => set.push(char),
+                    }
+                }
+                parts.push(if negated {
+                    ConditionPart::NoneOf(set)
+                } else {
+                    ConditionPart::OneOf(set)
+                });
+            }
+            char => parts.push(ConditionPart::Char(char)),
+        }
+    }
+    parts
+}
+
+/// Matches a prefix condition against the start of a word, or a suffix condition against
+/// the end of a word when both the condition and the word's characters are reversed.
+fn condition_matches<'a>(
+    condition: impl Iterator<Item = &'a ConditionPart>,
+    mut chars: impl Iterator<Item = char>,
+) -> bool {
+    for part in condition {
+        let Some(char) = chars.next() else {
+            return false;
+        };
+        let matches = match part {
+            ConditionPart::Any => true,
+            ConditionPart::Char(expected) => char == *expected,
+            ConditionPart::OneOf(set) => set.contains(&char),
+            ConditionPart::NoneOf(set) => !set.contains(&char),
+        };
+        if !matches {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+
+NEEDAFFIX X
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX S Y 2
+SFX S 0 s [^y]
+SFX S y ies [^aeiou]y
+";
+
+    const DIC: &str = "5
+cat/S
+fly/S
+lock/SU
+do/U
+kind/UX
+";
+
+    #[test]
+    fn test_affixes() {
+        let dictionary = Dictionary::new(AFF, DIC).unwrap();
+        for word in [
+            "cat", "cats", "fly", "flies", "lock", "locks", "unlock", "unlocks", "do", "undo",
+            "unkind", "Unlocks", "FLIES",
+        ] {
+            assert!(
+                dictionary.contains(word),
+                "{word} should be in the dictionary"
+            );
+        }
+        for word in ["flys", "undos", "uncat", "kind", "lockies"] {
+            assert!(
+                !dictionary.contains(word),
+                "{word} shouldn't be in the dictionary"
+            );
+        }
+    }
+
+    #[test]
+    fn test_casing() {
+        let dictionary = Dictionary::from_words(["the", "Paris"]);
+        assert!(dictionary.contains("The"));
+        assert!(dictionary.contains("THE"));
+        assert!(dictionary.contains("Paris"));
+        assert!(dictionary.contains("PARIS"));
+        assert!(!dictionary.contains("tHe"));
+        assert!(!dictionary.contains("paris"));
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let dictionary = Dictionary::from_words(["the", "cat", "tech", "ten", "Paris"]);
+
+        let suggestions = dictionary.suggestions("teh", 5);
+        assert!(suggestions.contains(&"the".to_string()));
+        assert!(suggestions.contains(&"ten".to_string()));
+        assert!(suggestions.contains(&"tech".to_string()));
+
+        assert!(dictionary
+            .suggestions("Teh", 5)
+            .contains(&"The".to_string()));
+        assert_eq!(dictionary.suggestions("thecat", 5), ["the cat"]);
+        assert_eq!(dictionary.suggestions("pariss", 5), ["Paris"]);
+        assert_eq!(dictionary.suggestions("cta", 1).len(), 1);
+        assert!(dictionary.suggestions("xylophone", 5).is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+mod dictionary;
+mod spellcheck_settings;
+mod words;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use gpui::{AppContext, Context as _, Global, Model, ModelContext, Task};
+use settings::{Settings, SettingsStore};
+use util::ResultExt;
+
+pub use dictionary::Dictionary;
+pub use spellcheck_settings::SpellcheckSettings;
+pub use words::words_to_check;
+
+/// The file, relative to the root of a worktree, holding the words added to the project's
+/// dictionary, one per line.
+pub const PROJECT_DICTIONARY_PATH: &str = ".zed/dictionary.txt";
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    SpellcheckSettings::register(cx);
+    let spell_checker = cx.new_model(|cx| SpellChecker::new(fs, cx));
+    cx.set_global(GlobalSpellChecker(spell_checker));
+}
+
+struct GlobalSpellChecker(Model<SpellChecker>);
+
+impl Global for GlobalSpellChecker {}
+
+/// Loads the dictionaries listed in the settings and the dictionaries of projects, which
+/// editors check the words of their buffers against.
+pub struct SpellChecker {
+    fs: Arc<dyn Fs>,
+    dictionary_names: Vec<String>,
+    dictionaries: Vec<Arc<Dictionary>>,
+    project_words: HashMap<Arc<Path>, Arc<HashSet<String>>>,
+    load_dictionaries_task: Task<()>,
+}
+
+/// What a buffer's words are checked against, which can be sent to a background thread.
+#[derive(Clone)]
+pub struct SpellingSnapshot {
+    dictionaries: Vec<Arc<Dictionary>>,
+    project_words: Arc<HashSet<String>>,
+}
+
+impl SpellChecker {
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalSpellChecker>()
+            .map(|spell_checker| spell_checker.0.clone())
+    }
+
+    fn new(fs: Arc<dyn Fs>, cx: &mut ModelContext<Self>) -> Self {
+        cx.observe_global::<SettingsStore>(|this, cx| {
+            let dictionary_names = &SpellcheckSettings::get_global(cx).dictionaries;
+            if *dictionary_names != this.dictionary_names {
+                this.load_dictionaries(cx);
+            }
+        })
+        .detach();
+
+        let mut this = Self {
+            fs,
+            dictionary_names: Vec::new(),
+            dictionaries: Vec::new(),
+            project_words: HashMap::default(),
+            load_dictionaries_task: Task::ready(()),
+        };
+        this.load_dictionaries(cx);
+        this
+    }
+
+    /// Whether any dictionary was loaded. Without one, there's nothing to check words against.
+    pub fn has_dictionaries(&self) -> bool {
+        !self.dictionaries.is_empty()
+    }
+
+    /// Returns what the words of a buffer in the worktree with the given root are checked
+    /// against, loading the project's dictionary when it's first needed.
+    pub fn snapshot(
+        &mut self,
+        worktree_root: Option<&Arc<Path>>,
+        cx: &mut ModelContext<Self>,
+    ) -> SpellingSnapshot {
+        let project_words = match worktree_root {
+            Some(root) => self.project_words(root, cx),
+            None => Arc::default(),
+        };
+        SpellingSnapshot {
+            dictionaries: self.dictionaries.clone(),
+            project_words,
+        }
+    }
+
+    /// Adds a word to the dictionary of the project whose worktree has the given root.
+    pub fn add_to_project_dictionary(
+        &mut self,
+        worktree_root: Arc<Path>,
+        word: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let mut words = (*self.project_words(&worktree_root, cx)).clone();
+        if !words.insert(word.clone()) {
+            return Task::ready(Ok(()));
+        }
+        self.project_words
+            .insert(worktree_root.clone(), Arc::new(words));
+        cx.notify();
+
+        let fs = self.fs.clone();
+        cx.background_executor().spawn(async move {
+            let path = worktree_root.join(PROJECT_DICTIONARY_PATH);
+            let mut text = if fs.is_file(&path).await {
+                fs.load(&path).await?
+            } else {
+                let dir = path
+                    .parent()
+                    .ok_or_else(|| anyhow!("invalid dictionary path"))?;
+                fs.create_dir(dir).await?;
+                String::new()
+            };
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&word);
+            text.push('\n');
+            fs.atomic_write(path, text)
+                .await
+                .context("writing the project dictionary")
+        })
+    }
+
+    fn project_words(
+        &mut self,
+        worktree_root: &Arc<Path>,
+        cx: &mut ModelContext<Self>,
+    ) -> Arc<HashSet<String>> {
+        if let Some(words) = self.project_words.get(worktree_root) {
+            return words.clone();
+        }
+
+        // Until the file is loaded, the project's dictionary is empty.
+        self.project_words
+            .insert(worktree_root.clone(), Arc::default());
+        let fs = self.fs.clone();
+        let worktree_root = worktree_root.clone();
+        cx.spawn(|this, mut cx| async move {
+            let path = worktree_root.join(PROJECT_DICTIONARY_PATH);
+            if !fs.is_file(&path).await {
+                return;
+            }
+            let Some(text) = fs.load(&path).await.log_err() else {
+                return;
+            };
+            let loaded_words = text
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_string)
+                .collect::<HashSet<_>>();
+            this.update(&mut cx, |this, cx| {
+                let words = this.project_words.entry(worktree_root).or_default();
+                let mut all_words = loaded_words;
+                all_words.extend(words.iter().cloned());
+                *words = Arc::new(all_words);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+        Arc::default()
+    }
+
+    fn load_dictionaries(&mut self, cx: &mut ModelContext<Self>) {
+        self.dictionary_names = SpellcheckSettings::get_global(cx).dictionaries.clone();
+        let names = self.dictionary_names.clone();
+        let fs = self.fs.clone();
+        self.load_dictionaries_task = cx.spawn(|this, mut cx| async move {
+            let mut dictionaries = Vec::new();
+            for name in names {
+                let Some((aff, dic)) = find_dictionary(fs.as_ref(), &name).await else {
+                    log::warn!("no dictionary found for {name:?}");
+                    continue;
+                };
+                let dictionary = cx
+                    .background_executor()
+                    .spawn(async move { Dictionary::new(&aff, &dic) })
+                    .await
+                    .with_context(|| format!("parsing the {name:?} dictionary"));
+                if let Some(dictionary) = dictionary.log_err() {
+                    dictionaries.push(Arc::new(dictionary));
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                this.dictionaries = dictionaries;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+}
+
+impl SpellingSnapshot {
+    pub fn is_empty(&self) -> bool {
+        self.dictionaries.is_empty()
+    }
+
+    /// Whether any of the dictionaries contains the word.
+    pub fn is_correct(&self, word: &str) -> bool {
+        self.project_words.contains(word)
+            || self
+                .dictionaries
+                .iter()
+                .any(|dictionary| dictionary.contains(word))
+    }
+
+    /// Returns up to `limit` replacements for a misspelled word. This tries many edits of
+    /// the word, so it's best done on a background thread.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        for dictionary in &self.dictionaries {
+            for suggestion in dictionary.suggestions(word, limit) {
+                if suggestions.len() < limit && !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions
+    }
+}
+
+/// The directories that dictionaries are looked up in: Zed's own directory, then the
+/// directories where the system's spell checkers install them.
+fn dictionary_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![paths::config_dir().join("dictionaries")];
+    if cfg!(target_os = "macos") {
+        dirs.push(util::paths::home_dir().join("Library/Spelling"));
+    } else if cfg!(not(target_os = "windows")) {
+        dirs.extend(
+            [
+                "/usr/share/hunspell",
+                "/usr/share/myspell",
+                "/usr/share/myspell/dicts",
+            ]
+            .map(PathBuf::from),
+        );
+    }
+    dirs
+}
+
+async fn find_dictionary(fs: &dyn Fs, name: &str) -> Option<(String, String)> {
+    for dir in dictionary_dirs() {
+        let aff_path = dir.join(format!("{name}.aff"));
+        let dic_path = dir.join(format!("{name}.dic"));
+        if fs.is_file(&aff_path).await && fs.is_file(&dic_path).await {
+            let aff = fs.load(&aff_path).await.log_err()?;
+            let dic = fs.load(&dic_path).await.log_err()?;
+            return Some((aff, dic));
+        }
+    }
+    None
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug)]
+pub struct SpellcheckSettings {
+    pub dictionaries: Vec<String>,
+}
+
+/// Spell checking configuration. Which parts of buffers are checked is configured per
+/// language, with the `spell_check` language setting.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct SpellcheckSettingsContent {
+    /// The Hunspell dictionaries to check words against, by the name of their `.aff` and
+    /// `.dic` files. They're looked up in the `dictionaries` directory of Zed's config
+    /// directory, then in the directories where the system installs them.
+    ///
+    /// Default: ["en_US"]
+    dictionaries: Option<Vec<String>>,
+}
+
+impl Settings for SpellcheckSettings {
+    const KEY: Option<&'static str> = Some("spellcheck");
+    type FileContent = SpellcheckSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
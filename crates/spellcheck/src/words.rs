@@ -0,0 +1,98 @@
+use std::ops::Range;
+
+/// Returns the byte ranges of the words in the text that are worth spell checking.
+///
+/// Text that looks like code is skipped: identifiers in `snake_case` or `camelCase`,
+/// acronyms, words with digits, and whitespace-separated chunks that contain paths,
+/// URLs, email addresses, backticks or file names.
+pub fn words_to_check(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut chunk_start = None;
+    for (ix, char) in text.char_indices().chain([(text.len(), ' ')]) {
+        if char.is_whitespace() {
+            if let Some(start) = chunk_start.take() {
+                push_chunk_words(text, start..ix, &mut words);
+            }
+        } else if chunk_start.is_none() {
+            chunk_start = Some(ix);
+        }
+    }
+    words
+}
+
+fn push_chunk_words(text: &str, range: Range<usize>, words: &mut Vec<Range<usize>>) {
+    let chunk = &text[range.clone()];
+    if looks_like_code(chunk) {
+        return;
+    }
+
+    let mut word_start = None;
+    for (ix, char) in chunk.char_indices().chain([(chunk.len(), ' ')]) {
+        if is_word_char(char) {
+            word_start.get_or_insert(ix);
+        } else if let Some(start) = word_start.take() {
+            let untrimmed = &chunk[start..ix];
+            let word = untrimmed.trim_start_matches(is_apostrophe);
+            let start = range.start + start + untrimmed.len() - word.len();
+            let word = word.trim_end_matches(is_apostrophe);
+            if should_check(word) {
+                words.push(start..start + word.len());
+            }
+        }
+    }
+}
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_' || is_apostrophe(char)
+}
+
+fn is_apostrophe(char: char) -> bool {
+    char == '\'' || char == '’'
+}
+
+fn looks_like_code(chunk: &str) -> bool {
+    if ["://", "::", "/", "\\", "@", "`", "=", "<", ">", "{", "}"]
+        .iter()
+        .any(|pattern| chunk.contains(pattern))
+    {
+        return true;
+    }
+    // A dot between letters, as in file names or method calls.
+    let chars = chunk.chars().collect::<Vec<_>>();
+    chars.windows(3).any(|window| {
+        window[1] == '.' && window[0].is_alphanumeric() && window[2].is_alphanumeric()
+    })
+}
+
+fn should_check(word: &str) -> bool {
+    if word.chars().count() < 2 {
+        return false;
+    }
+    if word.chars().any(|char| char.is_numeric() || char == '_') {
+        return false;
+    }
+    // Acronyms and camel case identifiers have uppercase letters after the first one.
+    !word.chars().skip(1).any(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words_to_check() {
+        let text = "Check this_word, URLs like https://zed.dev and camelCase. Don't skip it's 42nd main.rs";
+        let words = words_to_check(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect::<Vec<_>>();
+        assert_eq!(words, ["Check", "like", "and", "Don't", "skip", "it's"]);
+
+        let text = "'quoted' words… “curly”";
+        let words = words_to_check(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect::<Vec<_>>();
+        assert_eq!(words, ["quoted", "words", "curly"]);
+    }
+}
@@ -144,6 +144,58 @@ async fn test_open_item_on_modifiers_release(cx: &mut gpui::TestAppContext) {
     assert_tab_switcher_is_closed(workspace, cx);
 }
 
+#[gpui::test]
+async fn test_hold_to_switch_in_recently_used_order(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+                "4.txt": "Fourth file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    open_buffer("1.txt", &workspace, cx).await;
+    open_buffer("2.txt", &workspace, cx).await;
+    open_buffer("3.txt", &workspace, cx).await;
+    open_buffer("4.txt", &workspace, cx).await;
+    // Reactivating an item makes the recently used order differ from the tab order.
+    open_buffer("2.txt", &workspace, cx).await;
+
+    let active_title = |cx: &mut VisualTestContext| {
+        cx.read(|cx| {
+            let active_editor = workspace.read(cx).active_item_as::<Editor>(cx).unwrap();
+            active_editor.read(cx).title(cx).to_string()
+        })
+    };
+
+    // Holding the modifier and tapping the key cycles through the items, and releasing
+    // the modifier opens the selected one.
+    cx.simulate_modifiers_change(Modifiers::control());
+    open_tab_switcher(false, &workspace, cx);
+    cx.dispatch_action(Toggle { select_last: false });
+    cx.simulate_modifiers_change(Modifiers::none());
+    assert_eq!(active_title(cx), "3.txt");
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+
+    // A single tap switches back to the previously used item.
+    cx.simulate_modifiers_change(Modifiers::control());
+    open_tab_switcher(false, &workspace, cx);
+    cx.simulate_modifiers_change(Modifiers::none());
+    assert_eq!(active_title(cx), "2.txt");
+    assert_tab_switcher_is_closed(workspace, cx);
+}
+
 #[gpui::test]
 async fn test_open_on_empty_pane(cx: &mut gpui::TestAppContext) {
     let app_state = init_test(cx);
@@ -0,0 +1,101 @@
+use anyhow::Context as _;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// A problem, such as a compiler error, that a task reported in its output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProblem {
+    /// The file the problem is in, made absolute with the task's working directory when
+    /// the output has a relative path.
+    pub path: PathBuf,
+    /// The zero-based row of the problem.
+    pub row: u32,
+    /// The zero-based column of the problem, or zero if the output has none.
+    pub column: u32,
+    /// The problem's message, or the whole output line if the matcher doesn't capture one.
+    pub message: String,
+}
+
+/// Finds the problems in a task's output with its problem matcher: a regex that is matched
+/// against each output line, with `file` and `line` named groups, and optional `column` and
+/// `message` ones. Lines and columns are one-based, as compilers report them.
+pub fn match_problems(
+    problem_matcher: &str,
+    output: &str,
+    cwd: Option<&Path>,
+) -> anyhow::Result<Vec<TaskProblem>> {
+    let regex = Regex::new(problem_matcher)
+        .with_context(|| format!("invalid problem matcher {problem_matcher:?}"))?;
+    anyhow::ensure!(
+        regex.capture_names().flatten().any(|name| name == "file")
+            && regex.capture_names().flatten().any(|name| name == "line"),
+        "problem matcher {problem_matcher:?} must have `file` and `line` named groups"
+    );
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let captures = regex.captures(line)?;
+            let path = Path::new(captures.name("file")?.as_str().trim());
+            let path = match cwd {
+                Some(cwd) if path.is_relative() => cwd.join(path),
+                _ => path.to_path_buf(),
+            };
+            let position = |name| -> Option<u32> {
+                let position = captures.name(name)?.as_str().parse::<u32>().ok()?;
+                Some(position.saturating_sub(1))
+            };
+            Some(TaskProblem {
+                path,
+                row: position("line")?,
+                column: position("column").unwrap_or(0),
+                message: captures
+                    .name("message")
+                    .map_or(line, |message| message.as_str())
+                    .trim()
+                    .to_string(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_problems() {
+        let output = "\
+   Compiling app v0.1.0
+src/main.rs:3:9: error: cannot find value `x` in this scope
+/tmp/lib.rs:10: warning: unused import
+note: this line has no location
+";
+        let problems = match_problems(
+            r"^(?P<file>[^:\s]+):(?P<line>\d+)(:(?P<column>\d+))?: (?P<message>.+)$",
+            output,
+            Some(Path::new("/project")),
+        )
+        .unwrap();
+        assert_eq!(
+            problems,
+            [
+                TaskProblem {
+                    path: PathBuf::from("/project/src/main.rs"),
+                    row: 2,
+                    column: 8,
+                    message: "error: cannot find value `x` in this scope".to_string(),
+                },
+                TaskProblem {
+                    path: PathBuf::from("/tmp/lib.rs"),
+                    row: 9,
+                    column: 0,
+                    message: "warning: unused import".to_string(),
+                },
+            ]
+        );
+
+        assert!(match_problems(r"(?P<file>\S+)", output, None).is_err());
+        assert!(match_problems(r"(?P<file>[", output, None).is_err());
+    }
+}
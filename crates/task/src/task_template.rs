@@ -57,6 +57,12 @@ pub struct TaskTemplate {
     /// Which shell to use when spawning the task.
     #[serde(default)]
     pub shell: Shell,
+    /// A regex to find problems, such as compiler errors, in the task's output with, once it
+    /// finishes. Each output line is matched against it, and it needs `file` and `line` named
+    /// groups, with optional `column` and `message` ones, e.g.
+    /// `^(?P<file>[^:\s]+):(?P<line>\d+):(?P<column>\d+): (?P<message>.+)$`.
+    #[serde(default)]
+    pub problem_matcher: Option<String>,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -228,6 +234,7 @@ impl TaskTemplate {
                 reveal: self.reveal,
                 hide: self.hide,
                 shell: self.shell.clone(),
+                problem_matcher: self.problem_matcher.clone(),
             }),
         })
     }
@@ -2,14 +2,19 @@ use ::settings::Settings;
 use editor::{tasks::task_context, Editor};
 use gpui::{AppContext, Task as AsyncTask, ViewContext, WindowContext};
 use modal::TasksModal;
-use project::{Location, WorktreeId};
+use project::{Location, TaskSourceKind, WorktreeId};
+use task::TaskTemplate;
 use workspace::tasks::schedule_task;
 use workspace::{tasks::schedule_resolved_task, Workspace};
 
 mod modal;
+mod problems;
+mod results;
 mod settings;
 
 pub use modal::{Rerun, Spawn};
+pub use problems::ShowProblems;
+pub use results::ShowResults;
 
 pub fn init(cx: &mut AppContext) {
     settings::TaskSettings::register(cx);
@@ -17,6 +22,8 @@ pub fn init(cx: &mut AppContext) {
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace
                 .register_action(spawn_task_or_modal)
+                .register_action(|workspace, _: &ShowProblems, cx| problems::toggle(workspace, cx))
+                .register_action(|workspace, _: &ShowResults, cx| results::toggle(workspace, cx))
                 .register_action(move |workspace, action: &modal::Rerun, cx| {
                     if let Some((task_source_kind, mut last_scheduled_task)) =
                         workspace.project().update(cx, |project, cx| {
@@ -79,9 +86,12 @@ pub fn init(cx: &mut AppContext) {
 }
 
 fn spawn_task_or_modal(workspace: &mut Workspace, action: &Spawn, cx: &mut ViewContext<Workspace>) {
-    match &action.task_name {
-        Some(name) => spawn_task_with_name(name.clone(), cx).detach_and_log_err(cx),
-        None => toggle_modal(workspace, cx).detach(),
+    match (&action.command, &action.task_name) {
+        (Some(command), _) => spawn_command(command.clone(), action.args.clone(), cx),
+        (None, Some(name)) => {
+            spawn_task_with_name(name.clone(), action.args.clone(), cx).detach_and_log_err(cx)
+        }
+        (None, None) => toggle_modal(workspace, cx).detach(),
     }
 }
 
@@ -105,8 +115,41 @@ fn toggle_modal(workspace: &mut Workspace, cx: &mut ViewContext<'_, Workspace>)
     })
 }
 
+/// Spawns the command as a oneshot task, after substituting the task variables of the
+/// current context in it and in its arguments.
+fn spawn_command(command: String, args: Vec<String>, cx: &mut ViewContext<Workspace>) {
+    let label = args.iter().fold(command.clone(), |mut label, arg| {
+        label.push(' ');
+        label.push_str(arg);
+        label
+    });
+    let template = TaskTemplate {
+        label,
+        command,
+        args,
+        ..TaskTemplate::default()
+    };
+    cx.spawn(|workspace, mut cx| async move {
+        let context_task =
+            workspace.update(&mut cx, |workspace, cx| task_context(workspace, cx))?;
+        let task_context = context_task.await;
+        workspace.update(&mut cx, |workspace, cx| {
+            schedule_task(
+                workspace,
+                TaskSourceKind::UserInput,
+                &template,
+                &task_context,
+                false,
+                cx,
+            )
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 fn spawn_task_with_name(
     name: String,
+    args: Vec<String>,
     cx: &mut ViewContext<Workspace>,
 ) -> AsyncTask<anyhow::Result<()>> {
     cx.spawn(|workspace, mut cx| async move {
@@ -124,8 +167,9 @@ fn spawn_task_with_name(
 
         let did_spawn = workspace
             .update(&mut cx, |workspace, cx| {
-                let (task_source_kind, target_task) =
+                let (task_source_kind, mut target_task) =
                     tasks.into_iter().find(|(_, task)| task.label == name)?;
+                target_task.args.extend(args);
                 schedule_task(
                     workspace,
                     task_source_kind,
@@ -180,12 +224,12 @@ fn active_item_selection_properties(
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, sync::Arc};
+    use std::{collections::HashMap, path::Path, sync::Arc};
 
     use editor::Editor;
     use gpui::{Entity, TestAppContext};
     use language::{Language, LanguageConfig};
-    use project::{BasicContextProvider, FakeFs, Project};
+    use project::{project_settings::TrustedWorktrees, BasicContextProvider, FakeFs, Project};
     use serde_json::json;
     use task::{TaskContext, TaskVariables, VariableName};
     use ui::VisualContext;
@@ -373,6 +417,8 @@ mod tests {
             editor::init(cx);
             workspace::init_settings(cx);
             Project::init_settings(cx);
+            // The tests' local tasks are only loaded from trusted worktrees.
+            cx.set_global(TrustedWorktrees::new([Arc::from(Path::new("/dir"))]));
             state
         })
     }
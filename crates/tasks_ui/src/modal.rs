@@ -29,11 +29,22 @@ pub struct Spawn {
     /// If it is not set, a modal with a list of available tasks is opened instead.
     /// Defaults to None.
     pub task_name: Option<String>,
+    /// A command to spawn as a oneshot task, instead of a task from the task templates.
+    /// Like in task templates, task variables such as `$ZED_FILE`, `$ZED_SYMBOL`
+    /// and `$ZED_SELECTED_TEXT` are substituted in it.
+    /// Defaults to None.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments to append to the arguments of the spawned task or command,
+    /// with task variables substituted in them.
+    /// Defaults to [].
+    #[serde(default)]
+    pub args: Vec<String>,
 }
 
 impl Spawn {
     pub fn modal() -> Self {
-        Self { task_name: None }
+        Self::default()
     }
 }
 
@@ -387,9 +398,10 @@ impl PickerDelegate for TasksModalDelegate {
             TaskSourceKind::UserInput => Some(Icon::new(IconName::Terminal)),
             TaskSourceKind::AbsPath { .. } => Some(Icon::new(IconName::Settings)),
             TaskSourceKind::Worktree { .. } => Some(Icon::new(IconName::FileTree)),
-            TaskSourceKind::Language { name } => file_icons::FileIcons::get(cx)
-                .get_type_icon(&name.to_lowercase())
-                .map(|icon_path| Icon::from_path(icon_path)),
+            TaskSourceKind::Language { name } => {
+                file_icons::FileIcons::get_type_icon(&name.to_lowercase(), cx)
+                    .map(|icon_path| Icon::from_path(icon_path))
+            }
         }
         .map(|icon| icon.color(Color::Muted).size(IconSize::Small));
         let history_run_icon = if Some(ix) <= self.divider_index {
@@ -742,6 +754,7 @@ mod tests {
 
         cx.dispatch_action(Spawn {
             task_name: Some("example task".to_string()),
+            ..Spawn::default()
         });
         let tasks_picker = workspace.update(cx, |workspace, cx| {
             workspace
@@ -757,6 +770,87 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_spawn_command_and_extra_args(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                ".zed": {
+                    "tasks.json": r#"[
+                        {
+                            "label": "example task",
+                            "command": "echo",
+                            "args": ["4"]
+                        }
+                    ]"#,
+                },
+                "a.ts": "a"
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let _ = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(PathBuf::from("/dir/a.ts"), true, cx)
+            })
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+        let last_scheduled_task = |cx: &mut VisualTestContext| {
+            project.update(cx, |project, cx| {
+                let (_, task) = project
+                    .task_inventory()
+                    .read(cx)
+                    .last_scheduled_task(None)
+                    .expect("no task was scheduled");
+                let resolved = task.resolved.expect("task was not resolved");
+                (resolved.label, resolved.command, resolved.args)
+            })
+        };
+
+        cx.dispatch_action(Spawn {
+            task_name: Some("example task".to_string()),
+            args: vec!["--verbose".to_string()],
+            ..Spawn::default()
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            last_scheduled_task(cx),
+            (
+                "example task".to_string(),
+                "echo".to_string(),
+                vec!["4".to_string(), "--verbose".to_string()]
+            ),
+            "Arguments should be appended to the named task's arguments"
+        );
+
+        cx.dispatch_action(Spawn {
+            command: Some("cargo".to_string()),
+            args: vec!["check".to_string(), "$ZED_WORKTREE_ROOT".to_string()],
+            ..Spawn::default()
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            last_scheduled_task(cx),
+            (
+                "cargo check $ZED_WORKTREE_ROOT".to_string(),
+                "cargo".to_string(),
+                vec!["check".to_string(), "/dir".to_string()]
+            ),
+            "The command should be spawned with task variables substituted in its arguments"
+        );
+        assert!(
+            workspace
+                .update(cx, |workspace, cx| workspace.active_modal::<TasksModal>(cx))
+                .is_none(),
+            "No modal should be opened when spawning a command"
+        );
+    }
+
     #[gpui::test]
     async fn test_basic_context_for_simple_files(cx: &mut TestAppContext) {
         init_test(cx);
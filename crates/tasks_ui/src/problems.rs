@@ -0,0 +1,198 @@
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    Task, View, ViewContext, WeakView,
+};
+use language::Point;
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use task::TaskProblem;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(task, [ShowProblems]);
+
+pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let problems = workspace
+        .project()
+        .read(cx)
+        .task_inventory()
+        .read(cx)
+        .task_problems(cx)
+        .map(|(label, problem)| (label.to_string(), problem))
+        .collect::<Vec<_>>();
+    let workspace_handle = cx.view().downgrade();
+    workspace.toggle_modal(cx, |cx| TaskProblems::new(workspace_handle, problems, cx));
+}
+
+/// A modal that lists the problems that the problem matchers of the finished tasks found in
+/// their output, to jump to them.
+pub struct TaskProblems {
+    picker: View<Picker<TaskProblemsDelegate>>,
+}
+
+impl TaskProblems {
+    fn new(
+        workspace: WeakView<Workspace>,
+        problems: Vec<(String, TaskProblem)>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = TaskProblemsDelegate {
+            task_problems: cx.view().downgrade(),
+            workspace,
+            matches: (0..problems.len()).collect(),
+            problems,
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl FocusableView for TaskProblems {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskProblems {}
+impl ModalView for TaskProblems {}
+
+impl Render for TaskProblems {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(40.)).child(self.picker.clone())
+    }
+}
+
+pub struct TaskProblemsDelegate {
+    task_problems: WeakView<TaskProblems>,
+    workspace: WeakView<Workspace>,
+    /// The problems, along with the labels of the tasks that found them.
+    problems: Vec<(String, TaskProblem)>,
+    /// The indices of the problems that match the query.
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl TaskProblemsDelegate {
+    fn dismiss(&self, cx: &mut ViewContext<Picker<Self>>) {
+        self.task_problems
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+}
+
+impl PickerDelegate for TaskProblemsDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Go to a task problem…".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No problems found by tasks".into()
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let lowercase_query = query.trim().to_lowercase();
+        self.matches = self
+            .problems
+            .iter()
+            .enumerate()
+            .filter(|(_, (label, problem))| {
+                problem.message.to_lowercase().contains(&lowercase_query)
+                    || problem
+                        .path
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains(&lowercase_query)
+                    || label.to_lowercase().contains(&lowercase_query)
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        cx.notify();
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some((_, problem)) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.problems.get(*ix))
+        else {
+            return;
+        };
+
+        let position = Point::new(problem.row, problem.column);
+        let open_task = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(problem.path.clone(), true, cx)
+            })
+            .log_err();
+        if let Some(open_task) = open_task {
+            cx.spawn(|_, mut cx| async move {
+                let item = open_task.await?;
+                if let Some(editor) = item.downcast::<Editor>() {
+                    editor.update(&mut cx, |editor, cx| {
+                        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                            s.select_ranges([position..position])
+                        });
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        }
+        self.dismiss(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.dismiss(cx);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let (label, problem) = self.problems.get(*self.matches.get(ix)?)?;
+        let location = format!(
+            "{}:{}:{} — {label}",
+            problem.path.display(),
+            problem.row + 1,
+            problem.column + 1
+        );
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .inset(true)
+                .selected(selected)
+                .child(
+                    v_flex()
+                        .py_px()
+                        .child(Label::new(problem.message.clone()))
+                        .child(
+                            Label::new(location)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,172 @@
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    Task, View, ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::{TaskRun, TaskRunState};
+use std::sync::Arc;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::Rerun;
+
+actions!(task, [ShowResults]);
+
+pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let runs = workspace
+        .project()
+        .read(cx)
+        .task_inventory()
+        .read(cx)
+        .task_runs()
+        .cloned()
+        .collect::<Vec<_>>();
+    workspace.toggle_modal(cx, |cx| TaskResults::new(runs, cx));
+}
+
+/// A modal that lists the last runs of the tasks, whether they passed, and the number of
+/// problems found in their output, to run one of them again.
+pub struct TaskResults {
+    picker: View<Picker<TaskResultsDelegate>>,
+}
+
+impl TaskResults {
+    fn new(runs: Vec<TaskRun>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = TaskResultsDelegate {
+            task_results: cx.view().downgrade(),
+            matches: (0..runs.len()).collect(),
+            runs,
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl FocusableView for TaskResults {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskResults {}
+impl ModalView for TaskResults {}
+
+impl Render for TaskResults {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct TaskResultsDelegate {
+    task_results: WeakView<TaskResults>,
+    runs: Vec<TaskRun>,
+    /// The indices of the runs whose task labels match the query.
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl TaskResultsDelegate {
+    fn dismiss(&self, cx: &mut ViewContext<Picker<Self>>) {
+        self.task_results
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+}
+
+impl PickerDelegate for TaskResultsDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Run a task again…".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No tasks ran yet".into()
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let lowercase_query = query.trim().to_lowercase();
+        self.matches = self
+            .runs
+            .iter()
+            .enumerate()
+            .filter(|(_, run)| run.label.to_lowercase().contains(&lowercase_query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        cx.notify();
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(run) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.runs.get(*ix))
+        else {
+            return;
+        };
+        let rerun = Rerun {
+            task_id: Some(run.id.clone()),
+            ..Rerun::default()
+        };
+        self.dismiss(cx);
+        cx.dispatch_action(Box::new(rerun));
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.dismiss(cx);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let run = self.runs.get(*self.matches.get(ix)?)?;
+        let (icon, color) = match run.state {
+            TaskRunState::Running => (IconName::ArrowCircle, Color::Muted),
+            TaskRunState::Succeeded => (IconName::Check, Color::Success),
+            TaskRunState::Failed => (IconName::XCircle, Color::Error),
+        };
+        let problems = match run.problems.len() {
+            0 => None,
+            1 => Some("1 problem".to_string()),
+            count => Some(format!("{count} problems")),
+        };
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .inset(true)
+                .selected(selected)
+                .start_slot(Icon::new(icon).size(IconSize::Small).color(color))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(run.label.clone()))
+                        .when_some(problems, |this, problems| {
+                            this.child(
+                                Label::new(problems)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                ),
+        )
+    }
+}
@@ -0,0 +1,299 @@
+//! The loop that parses the shell's output into the terminal, and writes the terminal's input to
+//! the shell, on a thread of its own.
+//!
+//! It works like Alacritty's event loop, except that the marks of the shell integration are
+//! found while the output is parsed, along with the line that the cursor is on at that point.
+
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    io::{self, ErrorKind, Read, Write},
+    num::NonZeroUsize,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+use alacritty_terminal::{
+    event::{Event, EventListener, Notify, OnResize},
+    event_loop::Msg,
+    sync::FairMutex,
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+    Term,
+};
+use anyhow::{anyhow, Result};
+use futures::channel::mpsc::UnboundedSender;
+use polling::{Event as PollEvent, Events, PollMode, Poller};
+
+use crate::shell_integration::{HistoryLine, MarkingParser, ShellMark};
+
+/// The most bytes that are read from the pty before waiting for the terminal to be unlocked.
+const READ_BUFFER_SIZE: usize = 0x10_0000;
+/// The most bytes that are parsed before the terminal is unlocked.
+const MAX_LOCKED_READ: usize = u16::MAX as usize;
+
+pub(crate) struct EventLoop<T, U: EventListener> {
+    poller: Arc<Poller>,
+    pty: T,
+    rx: Receiver<Msg>,
+    tx: Sender<Msg>,
+    terminal: Arc<FairMutex<Term<U>>>,
+    event_proxy: U,
+    marks_tx: UnboundedSender<(ShellMark, HistoryLine)>,
+    drain_on_exit: bool,
+}
+
+#[derive(Default)]
+struct State {
+    parser: MarkingParser,
+    write_list: VecDeque<Cow<'static, [u8]>>,
+    writing: Option<Writing>,
+}
+
+impl State {
+    fn needs_write(&self) -> bool {
+        self.writing.is_some() || !self.write_list.is_empty()
+    }
+}
+
+/// The input that is being written to the pty.
+struct Writing {
+    source: Cow<'static, [u8]>,
+    written: usize,
+}
+
+impl<T, U> EventLoop<T, U>
+where
+    T: EventedPty + OnResize + Send + 'static,
+    U: EventListener + Send + 'static,
+{
+    pub(crate) fn new(
+        terminal: Arc<FairMutex<Term<U>>>,
+        event_proxy: U,
+        pty: T,
+        marks_tx: UnboundedSender<(ShellMark, HistoryLine)>,
+        drain_on_exit: bool,
+    ) -> io::Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        Ok(Self {
+            poller: Arc::new(Poller::new()?),
+            pty,
+            rx,
+            tx,
+            terminal,
+            event_proxy,
+            marks_tx,
+            drain_on_exit,
+        })
+    }
+
+    pub(crate) fn channel(&self) -> EventLoopSender {
+        EventLoopSender {
+            sender: self.tx.clone(),
+            poller: self.poller.clone(),
+        }
+    }
+
+    pub(crate) fn spawn(mut self) -> io::Result<JoinHandle<()>> {
+        thread::Builder::new()
+            .name("PTY reader".to_string())
+            .spawn(move || {
+                if let Err(error) = self.run() {
+                    log::error!("error in the terminal's event loop: {error}");
+                }
+                self.pty.deregister(&self.poller).ok();
+            })
+    }
+
+    fn run(&mut self) -> io::Result<()> {
+        let mut state = State::default();
+        let mut buf = vec![0; READ_BUFFER_SIZE];
+        let mode = PollMode::Level;
+        let mut interest = PollEvent::readable(0);
+        unsafe { self.pty.register(&self.poller, interest, mode)? };
+
+        let mut events = Events::with_capacity(NonZeroUsize::new(1024).unwrap());
+        loop {
+            // Wake up when the synchronized update that the output started times out.
+            let timeout = state
+                .parser
+                .sync_timeout()
+                .map(|timeout| timeout.saturating_duration_since(Instant::now()));
+            events.clear();
+            if let Err(error) = self.poller.wait(&mut events, timeout) {
+                match error.kind() {
+                    ErrorKind::Interrupted => continue,
+                    _ => return Err(error),
+                }
+            }
+
+            let messages = self.rx.try_iter().collect::<Vec<_>>();
+            if events.is_empty() && messages.is_empty() {
+                state.parser.stop_sync(&mut *self.terminal.lock());
+                self.event_proxy.send_event(Event::Wakeup);
+                continue;
+            }
+            for message in messages {
+                match message {
+                    Msg::Input(input) => state.write_list.push_back(input),
+                    Msg::Resize(window_size) => self.pty.on_resize(window_size),
+                    Msg::Shutdown => return Ok(()),
+                }
+            }
+
+            for event in events.iter() {
+                // Don't read from or write to a pty that is gone.
+                if event.is_interrupt() {
+                    continue;
+                }
+                if event.readable {
+                    if let Err(error) = self.pty_read(&mut state, &mut buf) {
+                        // On Linux, reading the pty fails with `EIO` once the shell hangs up,
+                        // before its exit is reported.
+                        #[cfg(target_os = "linux")]
+                        if error.raw_os_error() == Some(libc::EIO) {
+                            continue;
+                        }
+                        return Err(error);
+                    }
+                }
+                if event.writable {
+                    self.pty_write(&mut state)?;
+                }
+            }
+
+            if let Some(ChildEvent::Exited(code)) = self.pty.next_child_event() {
+                if let Some(code) = code {
+                    self.event_proxy.send_event(Event::ChildExit(code));
+                }
+                if self.drain_on_exit {
+                    self.pty_read(&mut state, &mut buf).ok();
+                }
+                self.terminal.lock().exit();
+                self.event_proxy.send_event(Event::Wakeup);
+                return Ok(());
+            }
+
+            let needs_write = state.needs_write();
+            if needs_write != interest.writable {
+                interest.writable = needs_write;
+                self.pty.reregister(&self.poller, interest, mode)?;
+            }
+        }
+    }
+
+    fn pty_read(&mut self, state: &mut State, buf: &mut [u8]) -> io::Result<()> {
+        let mut unprocessed = 0;
+        let mut processed = 0;
+
+        // Reserve the next lock of the terminal for parsing the output.
+        let _terminal_lease = self.terminal.lease();
+        let mut terminal = None;
+        loop {
+            match self.pty.reader().read(&mut buf[unprocessed..]) {
+                Ok(0) if unprocessed == 0 => break,
+                Ok(read) => unprocessed += read,
+                Err(error) => match error.kind() {
+                    ErrorKind::Interrupted | ErrorKind::WouldBlock => {
+                        if unprocessed == 0 {
+                            break;
+                        }
+                    }
+                    _ => return Err(error),
+                },
+            }
+
+            // Keep reading while the terminal is locked elsewhere, until the buffer is full.
+            let terminal = match &mut terminal {
+                Some(terminal) => terminal,
+                None => terminal.insert(match self.terminal.try_lock_unfair() {
+                    None if unprocessed >= buf.len() => self.terminal.lock_unfair(),
+                    None => continue,
+                    Some(terminal) => terminal,
+                }),
+            };
+
+            for mark in state.parser.advance(&mut **terminal, &buf[..unprocessed]) {
+                self.marks_tx.unbounded_send(mark).ok();
+            }
+            processed += unprocessed;
+            unprocessed = 0;
+
+            if processed >= MAX_LOCKED_READ {
+                break;
+            }
+        }
+
+        // The terminal is redrawn unless all of the output waits for a synchronized update.
+        if processed > 0 && state.parser.sync_bytes_count() < processed {
+            self.event_proxy.send_event(Event::Wakeup);
+        }
+        Ok(())
+    }
+
+    fn pty_write(&mut self, state: &mut State) -> io::Result<()> {
+        loop {
+            let Some(mut writing) = state.writing.take().or_else(|| {
+                state
+                    .write_list
+                    .pop_front()
+                    .map(|source| Writing { source, written: 0 })
+            }) else {
+                return Ok(());
+            };
+
+            match self.pty.writer().write(&writing.source[writing.written..]) {
+                Ok(0) => {
+                    state.writing = Some(writing);
+                    return Ok(());
+                }
+                Ok(written) => {
+                    writing.written += written;
+                    if writing.written < writing.source.len() {
+                        state.writing = Some(writing);
+                    }
+                }
+                Err(error) => {
+                    state.writing = Some(writing);
+                    return match error.kind() {
+                        ErrorKind::Interrupted | ErrorKind::WouldBlock => Ok(()),
+                        _ => Err(error),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Sends messages to the event loop.
+#[derive(Clone)]
+pub(crate) struct EventLoopSender {
+    sender: Sender<Msg>,
+    poller: Arc<Poller>,
+}
+
+impl EventLoopSender {
+    pub(crate) fn send(&self, message: Msg) -> Result<()> {
+        self.sender
+            .send(message)
+            .map_err(|_| anyhow!("the terminal's event loop has stopped"))?;
+        self.poller.notify()?;
+        Ok(())
+    }
+}
+
+/// Sends the terminal's input to the event loop.
+pub(crate) struct Notifier(pub(crate) EventLoopSender);
+
+impl Notify for Notifier {
+    fn notify<B: Into<Cow<'static, [u8]>>>(&self, bytes: B) {
+        let bytes = bytes.into();
+        // The pty hangs when nothing is written to it.
+        if !bytes.is_empty() {
+            self.0.send(Msg::Input(bytes)).ok();
+        }
+    }
+}
@@ -0,0 +1,574 @@
+//! Shell integration: the hooks that make bash, zsh and fish mark their prompts, and the exit
+//! status of each command, with the OSC 133 escape sequences, and the tracking of the commands
+//! that the shell marks this way.
+//!
+//! The terminal emulator ignores OSC 133, so the shell's output is scanned for these sequences
+//! as it is parsed. Any shell integration that emits them is supported, not only Zed's own
+//! hooks.
+
+use std::{fs, path::Path, time::Instant};
+
+use alacritty_terminal::{
+    event::EventListener,
+    grid::{Dimensions, Scroll as AlacScroll},
+    index::{Column, Line, Point as AlacPoint},
+    vte::ansi::Processor,
+    Term,
+};
+use anyhow::{anyhow, Result};
+use collections::{HashMap, VecDeque};
+use task::Shell;
+
+/// The number of shell commands that are remembered for navigation and decorations.
+const MAX_SHELL_COMMANDS: usize = 1000;
+/// The longest OSC sequence that is checked for a mark. Longer ones, such as hyperlinks or
+/// clipboard contents, are skipped.
+const MAX_MARK_LEN: usize = 32;
+
+/// Reports the exit status of the last command, and the start of the prompt, before each
+/// prompt. The exit status is restored for the prompt commands that follow this one.
+const BASH_PROMPT_COMMAND: &str = r#"__zed_status=$?; printf '\033]133;D;%s\007\033]133;A\007' "$__zed_status"; (exit "$__zed_status")"#;
+const ZSH_SCRIPT: &str = include_str!("shell_integration/zshenv.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+/// Sets the environment variables that make bash, zsh or fish load Zed's shell integration,
+/// without changing how the shell is started.
+///
+/// Any other shell, or one that is started with arguments of its own, is left alone.
+pub fn inject(shell: &Shell, env: &mut HashMap<String, String>) {
+    if cfg!(windows) {
+        return;
+    }
+
+    let program = match shell {
+        Shell::System => std::env::var("SHELL").ok(),
+        Shell::Program(program) => Some(program.clone()),
+        Shell::WithArguments { .. } => None,
+    };
+    let Some(program) = program else {
+        return;
+    };
+    let shell_name = Path::new(&program)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if let Err(error) = inject_for(&shell_name, env) {
+        log::error!("failed to set up the shell integration for {shell_name}: {error:#}");
+    }
+}
+
+fn inject_for(shell_name: &str, env: &mut HashMap<String, String>) -> Result<()> {
+    let inherited = |env: &HashMap<String, String>, name: &str| {
+        env.get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+            .filter(|value| !value.is_empty())
+    };
+    let dir = paths::temp_dir().join("shell_integration");
+    match shell_name {
+        "bash" => {
+            // Bash runs the `PROMPT_COMMAND` it inherits before each prompt, unless the user's
+            // startup files replace it rather than add to it.
+            let prompt_command = match inherited(env, "PROMPT_COMMAND") {
+                Some(prompt_command) => format!("{BASH_PROMPT_COMMAND}; {prompt_command}"),
+                None => BASH_PROMPT_COMMAND.to_string(),
+            };
+            env.insert("PROMPT_COMMAND".to_string(), prompt_command);
+        }
+        "zsh" => {
+            let zdotdir = dir.join("zsh");
+            write_script(&zdotdir.join(".zshenv"), ZSH_SCRIPT)?;
+            if let Some(user_zdotdir) = inherited(env, "ZDOTDIR") {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            env.insert("ZDOTDIR".to_string(), path_to_string(&zdotdir)?);
+        }
+        "fish" => {
+            let data_dir = dir.join("fish");
+            write_script(
+                &data_dir.join("fish").join("vendor_conf.d").join("zed.fish"),
+                FISH_SCRIPT,
+            )?;
+            let data_dirs = inherited(env, "XDG_DATA_DIRS")
+                .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+            env.insert(
+                "XDG_DATA_DIRS".to_string(),
+                format!("{}:{data_dirs}", path_to_string(&data_dir)?),
+            );
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn write_script(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::read_to_string(path).ok().as_deref() != Some(contents) {
+        fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+fn path_to_string(path: &Path) -> Result<String> {
+    path.to_str()
+        .map(|path| path.to_string())
+        .ok_or_else(|| anyhow!("invalid path {path:?}"))
+}
+
+/// A mark that the shell put in its output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShellMark {
+    /// The shell is about to print its prompt: `OSC 133 ; A`.
+    PromptStart,
+    /// The last command finished, with the given exit status: `OSC 133 ; D [; status]`.
+    CommandFinished(Option<i32>),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Finds the OSC 133 marks in the shell's output, across reads.
+#[derive(Default)]
+pub(crate) struct MarkScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+}
+
+impl MarkScanner {
+    pub(crate) fn advance(&mut self, byte: u8) -> Option<ShellMark> {
+        match (self.state, byte) {
+            // An OSC sequence ends with BEL or with ST, `ESC \`.
+            (ScanState::Osc, 0x07) | (ScanState::OscEscape, b'\\') => {
+                self.state = ScanState::Ground;
+                return self.mark();
+            }
+            (_, 0x1b) => {
+                self.state = if self.state == ScanState::Osc {
+                    ScanState::OscEscape
+                } else {
+                    ScanState::Escape
+                };
+            }
+            (ScanState::Escape | ScanState::OscEscape, b']') => {
+                self.state = ScanState::Osc;
+                self.payload.clear();
+            }
+            (ScanState::Osc, _) if self.payload.len() < MAX_MARK_LEN => self.payload.push(byte),
+            _ => self.state = ScanState::Ground,
+        }
+        None
+    }
+
+    fn mark(&self) -> Option<ShellMark> {
+        let payload = std::str::from_utf8(&self.payload).ok()?;
+        let mut params = payload.strip_prefix("133;")?.split(';');
+        match params.next()? {
+            "A" => Some(ShellMark::PromptStart),
+            "D" => Some(ShellMark::CommandFinished(
+                params.next().and_then(|status| status.parse().ok()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the shell's output into the terminal, and finds the marks in it along with the line
+/// that the cursor is on once each mark is parsed.
+#[derive(Default)]
+pub(crate) struct MarkingParser {
+    parser: Processor,
+    scanner: MarkScanner,
+}
+
+impl MarkingParser {
+    pub(crate) fn advance<T: EventListener>(
+        &mut self,
+        term: &mut Term<T>,
+        bytes: &[u8],
+    ) -> Vec<(ShellMark, HistoryLine)> {
+        let mut marks = Vec::new();
+        for byte in bytes {
+            self.parser.advance(term, *byte);
+            if let Some(mark) = self.scanner.advance(*byte) {
+                marks.push((mark, HistoryLine::at_cursor(term)));
+            }
+        }
+        marks
+    }
+
+    /// When the synchronized update that the output started times out.
+    pub(crate) fn sync_timeout(&self) -> Option<Instant> {
+        self.parser.sync_timeout().sync_timeout()
+    }
+
+    /// Ends the synchronized update that the output started.
+    pub(crate) fn stop_sync<T: EventListener>(&mut self, term: &mut Term<T>) {
+        self.parser.stop_sync(term);
+    }
+
+    /// The number of bytes that wait for the synchronized update to end.
+    pub(crate) fn sync_bytes_count(&self) -> usize {
+        self.parser.sync_bytes_count()
+    }
+}
+
+/// The prompt of a shell command, to decorate in the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandMark {
+    /// The line of the prompt in the viewport.
+    pub display_line: usize,
+    /// The exit status of the command, or `None` while it's still running.
+    pub exit_status: Option<i32>,
+}
+
+/// A line of the terminal's grid, along with the size of the scrollback history when the line
+/// was recorded, to find the line again once more lines were pushed into the history.
+///
+/// Once the history is full, the lines that leave it are not counted, so the lines recorded
+/// before then drift down by the number of lines that left the history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct HistoryLine {
+    line: Line,
+    history_size: usize,
+}
+
+impl HistoryLine {
+    pub(crate) fn at_cursor<T>(term: &Term<T>) -> Self {
+        Self {
+            line: term.grid().cursor.point.line,
+            history_size: term.grid().history_size(),
+        }
+    }
+
+    /// The line in the grid now.
+    fn line<T>(&self, term: &Term<T>) -> Line {
+        let pushed_lines = term.grid().history_size() as i32 - self.history_size as i32;
+        Line(self.line.0 - pushed_lines)
+    }
+}
+
+/// A shell command that the shell marked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ShellCommand {
+    prompt: HistoryLine,
+    /// The last line of the command's output, once the command finished.
+    end: Option<HistoryLine>,
+    /// The exit status of the command, once it finished.
+    exit_status: Option<i32>,
+}
+
+/// The shell commands that the shell marked, oldest first.
+#[derive(Default)]
+pub(crate) struct ShellCommands {
+    commands: VecDeque<ShellCommand>,
+    /// The exit status that the shell reported for the running command, which finishes at the
+    /// next prompt.
+    finished: Option<Option<i32>>,
+}
+
+impl ShellCommands {
+    /// Registers the given mark, which was parsed on the given line.
+    pub(crate) fn register<T: EventListener>(
+        &mut self,
+        mark: ShellMark,
+        line: HistoryLine,
+        term: &Term<T>,
+    ) {
+        match mark {
+            ShellMark::CommandFinished(exit_status) => self.finished = Some(exit_status),
+            ShellMark::PromptStart => {
+                let prompt = line;
+                if let Some(command) = self.commands.back_mut() {
+                    if command.end.is_none() {
+                        command.end = Some(HistoryLine {
+                            line: Line(prompt.line.0 - 1),
+                            ..prompt
+                        });
+                        command.exit_status = self.finished.flatten();
+                    }
+                }
+                self.finished = None;
+
+                // Clearing the screen can bring the prompt above the commands that were
+                // registered before it, and the oldest commands leave the history.
+                while self.commands.back().map_or(false, |command| {
+                    command.prompt.line(term) >= prompt.line(term)
+                }) {
+                    self.commands.pop_back();
+                }
+                while self.commands.front().map_or(false, |command| {
+                    command.prompt.line(term) < term.topmost_line()
+                }) {
+                    self.commands.pop_front();
+                }
+                if self.commands.len() == MAX_SHELL_COMMANDS {
+                    self.commands.pop_front();
+                }
+                self.commands.push_back(ShellCommand {
+                    prompt,
+                    end: None,
+                    exit_status: None,
+                });
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.commands.clear();
+        self.finished = None;
+    }
+
+    /// The prompts of the shell commands that are in the viewport.
+    pub(crate) fn marks<T: EventListener>(&self, term: &Term<T>) -> Vec<CommandMark> {
+        let display_offset = term.grid().display_offset() as i32;
+        let screen_lines = term.screen_lines() as i32;
+        self.commands
+            .iter()
+            .filter_map(|command| {
+                let display_line = command.prompt.line(term).0 + display_offset;
+                (0..screen_lines)
+                    .contains(&display_line)
+                    .then(|| CommandMark {
+                        display_line: display_line as usize,
+                        exit_status: command.end.and(command.exit_status),
+                    })
+            })
+            .collect()
+    }
+
+    /// The scroll that brings the prompt of the shell command above the viewport to its top.
+    pub(crate) fn scroll_to_previous<T: EventListener>(
+        &self,
+        term: &Term<T>,
+    ) -> Option<AlacScroll> {
+        let viewport_top = -(term.grid().display_offset() as i32);
+        self.commands
+            .iter()
+            .rev()
+            .map(|command| command.prompt.line(term).0)
+            .find(|prompt_line| *prompt_line < viewport_top)
+            .map(|prompt_line| AlacScroll::Delta(viewport_top - prompt_line))
+    }
+
+    /// The scroll that brings the prompt of the shell command below the top of the viewport to
+    /// its top, or that goes to the bottom when there's no such command.
+    pub(crate) fn scroll_to_next<T: EventListener>(&self, term: &Term<T>) -> AlacScroll {
+        let viewport_top = -(term.grid().display_offset() as i32);
+        self.commands
+            .iter()
+            .map(|command| command.prompt.line(term).0)
+            .find(|prompt_line| *prompt_line > viewport_top)
+            .map_or(AlacScroll::Bottom, |prompt_line| {
+                AlacScroll::Delta(viewport_top - prompt_line)
+            })
+    }
+
+    /// The output of the last shell command that finished.
+    pub(crate) fn last_output<T: EventListener>(&self, term: &Term<T>) -> Option<String> {
+        let command = self
+            .commands
+            .iter()
+            .rev()
+            .find(|command| command.end.is_some())?;
+        let start = Line(command.prompt.line(term).0 + 1).max(term.topmost_line());
+        let end = command.end?.line(term).min(term.bottommost_line());
+        if end < start {
+            return Some(String::new());
+        }
+        let output = term.bounds_to_string(
+            AlacPoint::new(start, Column(0)),
+            AlacPoint::new(end, term.last_column()),
+        );
+        Some(output.trim_end().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        term::{test::TermSize, Config},
+        vte::ansi::Handler,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_mark_scanner() {
+        let mut scanner = MarkScanner::default();
+        let output = "\x1b]133;D;1\x07\x1b]2;title\x07\x1b]133;A\x1b\\$ \x1b[1mbold\x1b]133;D\x07";
+        let marks = output
+            .bytes()
+            .filter_map(|byte| scanner.advance(byte))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            marks,
+            vec![
+                ShellMark::CommandFinished(Some(1)),
+                ShellMark::PromptStart,
+                ShellMark::CommandFinished(None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_commands() {
+        let mut term = Term::new(
+            Config {
+                scrolling_history: 100,
+                ..Config::default()
+            },
+            &TermSize::new(20, 4),
+            (),
+        );
+        let mut commands = ShellCommands::default();
+        let mut run = |term: &mut Term<()>, command: &str, output: &[&str], status| {
+            commands.register(ShellMark::PromptStart, HistoryLine::at_cursor(term), term);
+            write_line(term, &format!("$ {command}"));
+            for line in output {
+                write_line(term, line);
+            }
+            commands.register(
+                ShellMark::CommandFinished(Some(status)),
+                HistoryLine::at_cursor(term),
+                term,
+            );
+        };
+
+        run(&mut term, "ls", &["a.txt", "b.txt"], 0);
+        run(&mut term, "false", &[], 1);
+        commands.register(ShellMark::PromptStart, HistoryLine::at_cursor(&term), &term);
+        assert_eq!(
+            commands.last_output(&term).as_deref(),
+            Some(""),
+            "The last command had no output"
+        );
+
+        // The first prompt left the viewport for the history.
+        assert_eq!(
+            commands.marks(&term),
+            vec![
+                CommandMark {
+                    display_line: 2,
+                    exit_status: Some(1),
+                },
+                CommandMark {
+                    display_line: 3,
+                    exit_status: None,
+                },
+            ]
+        );
+        assert_eq!(
+            commands.scroll_to_previous(&term),
+            Some(AlacScroll::Delta(1))
+        );
+        term.scroll_display(AlacScroll::Delta(1));
+        assert_eq!(commands.marks(&term)[0].display_line, 0);
+        assert_eq!(commands.marks(&term)[0].exit_status, Some(0));
+        assert_eq!(commands.scroll_to_previous(&term), None);
+        assert_eq!(commands.scroll_to_next(&term), AlacScroll::Delta(-3));
+
+        commands.clear();
+        assert_eq!(commands.marks(&term), Vec::new());
+        assert_eq!(commands.last_output(&term), None);
+    }
+
+    #[test]
+    fn test_last_output() {
+        let mut term = Term::new(Config::default(), &TermSize::new(20, 10), ());
+        let mut commands = ShellCommands::default();
+        commands.register(ShellMark::PromptStart, HistoryLine::at_cursor(&term), &term);
+        write_line(&mut term, "$ ls");
+        write_line(&mut term, "a.txt");
+        write_line(&mut term, "b.txt");
+        commands.register(
+            ShellMark::CommandFinished(Some(0)),
+            HistoryLine::at_cursor(&term),
+            &term,
+        );
+        commands.register(ShellMark::PromptStart, HistoryLine::at_cursor(&term), &term);
+        assert_eq!(commands.last_output(&term).as_deref(), Some("a.txt\nb.txt"));
+    }
+
+    #[test]
+    fn test_marks_in_one_read() {
+        let mut term = Term::new(Config::default(), &TermSize::new(20, 10), ());
+        let mut parser = MarkingParser::default();
+        let output = "\x1b]133;A\x07$ ls\r\na.txt\r\nb.txt\r\n\x1b]133;D;0\x07\x1b]133;A\x07$ false\r\n\x1b]133;D;1\x07\x1b]133;A\x07$ ";
+        let marks = parser.advance(&mut term, output.as_bytes());
+        assert_eq!(
+            marks
+                .iter()
+                .map(|(mark, line)| (*mark, line.line(&term).0))
+                .collect::<Vec<_>>(),
+            vec![
+                (ShellMark::PromptStart, 0),
+                (ShellMark::CommandFinished(Some(0)), 3),
+                (ShellMark::PromptStart, 3),
+                (ShellMark::CommandFinished(Some(1)), 4),
+                (ShellMark::PromptStart, 4),
+            ],
+            "Each mark should be on the line that the cursor was on when it was parsed"
+        );
+
+        let mut commands = ShellCommands::default();
+        for (mark, line) in marks {
+            commands.register(mark, line, &term);
+        }
+        assert_eq!(
+            commands
+                .marks(&term)
+                .iter()
+                .map(|mark| (mark.display_line, mark.exit_status))
+                .collect::<Vec<_>>(),
+            vec![(0, Some(0)), (3, Some(1)), (4, None)]
+        );
+        assert_eq!(commands.last_output(&term).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_inject_bash() {
+        let mut env = HashMap::default();
+        env.insert("PROMPT_COMMAND".to_string(), "history -a".to_string());
+        inject(&Shell::Program("/bin/bash".to_string()), &mut env);
+        if cfg!(windows) {
+            return;
+        }
+        assert_eq!(
+            env.get("PROMPT_COMMAND").map(String::as_str),
+            Some(format!("{BASH_PROMPT_COMMAND}; history -a").as_str()),
+            "The user's prompt command should run after the shell integration's"
+        );
+
+        let mut env = HashMap::default();
+        inject(&Shell::Program("/bin/sh".to_string()), &mut env);
+        assert!(env.is_empty(), "Other shells should be left alone");
+        inject(
+            &Shell::WithArguments {
+                program: "/bin/bash".to_string(),
+                args: vec!["--norc".to_string()],
+            },
+            &mut env,
+        );
+        assert!(
+            env.is_empty(),
+            "Shells started with arguments should be left alone"
+        );
+    }
+
+    fn write_line(term: &mut Term<()>, line: &str) {
+        for c in line.chars() {
+            term.input(c);
+        }
+        term.carriage_return();
+        term.linefeed();
+    }
+}
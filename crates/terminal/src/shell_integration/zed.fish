@@ -0,0 +1,12 @@
+# Zed shell integration for fish, loaded from vendor_conf.d by adding its directory to XDG_DATA_DIRS.
+
+if status is-interactive
+    function __zed_postexec --on-event fish_postexec
+        set -g __zed_status $status
+    end
+
+    # Marks the end of the last command, with its exit status, and the start of the prompt.
+    function __zed_prompt --on-event fish_prompt
+        printf '\033]133;D;%s\007\033]133;A\007' (set -q __zed_status; and echo $__zed_status; or echo 0)
+    end
+end
@@ -2,12 +2,16 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod event_loop;
 mod pty_info;
+pub mod shell_integration;
 pub mod terminal_settings;
 
+pub use shell_integration::CommandMark;
+
 use alacritty_terminal::{
     event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
-    event_loop::{EventLoop, Msg, Notifier},
+    event_loop::Msg,
     grid::{Dimensions, Scroll as AlacScroll},
     index::{Boundary, Column, Direction as AlacDirection, Line, Point as AlacPoint},
     selection::{Selection, SelectionRange, SelectionType},
@@ -34,15 +38,17 @@ use mappings::mouse::{
 };
 
 use collections::{HashMap, VecDeque};
+use event_loop::{EventLoop, Notifier};
 use futures::StreamExt;
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{HistoryLine, ShellCommands, ShellMark};
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, TaskId};
+use task::{HideStrategy, Shell, TaskId, TaskProblem};
 use terminal_settings::{AlternateScroll, TerminalBlink, TerminalSettings};
 use theme::{ActiveTheme, Theme};
-use util::truncate_and_trailoff;
+use util::{truncate_and_trailoff, ResultExt};
 
 use std::{
     cmp::{self, min},
@@ -76,6 +82,11 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        ScrollToPreviousCommand,
+        ScrollToNextCommand,
+        CopyLastCommandOutput,
+        OpenHoveredLink,
+        CopyMatchedLine,
     ]
 );
 
@@ -104,6 +115,7 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    TaskFinished,
 }
 
 #[derive(Clone, Debug)]
@@ -357,18 +369,8 @@ impl TerminalBuilder {
         // Setup Alacritty's env
         setup_env();
 
-        let scrolling_history = if task.is_some() {
-            // Tasks like `cargo build --all` may produce a lot of output, ergo allow maximum scrolling.
-            // After the task finishes, we do not allow appending to that terminal, so small tasks output should not
-            // cause excessive memory usage over time.
-            MAX_SCROLL_HISTORY_LINES
-        } else {
-            max_scroll_history_lines
-                .unwrap_or(DEFAULT_SCROLL_HISTORY_LINES)
-                .min(MAX_SCROLL_HISTORY_LINES)
-        };
         let config = Config {
-            scrolling_history,
+            scrolling_history: scrolling_history(task.is_some(), max_scroll_history_lines),
             ..Config::default()
         };
 
@@ -411,19 +413,20 @@ impl TerminalBuilder {
         };
 
         let pty_info = PtyProcessInfo::new(&pty);
+        let (shell_marks_tx, shell_marks_rx) = unbounded();
 
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
             ZedListener(events_tx.clone()),
             pty,
+            shell_marks_tx,
             pty_options.hold,
-            false,
         )?;
 
         //Kick things off
         let pty_tx = event_loop.channel();
-        let _io_thread = event_loop.spawn(); // DANGER
+        let _io_thread = event_loop.spawn()?; // DANGER
 
         let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
         // Optional suffix matches MSBuild diagnostic suffixes for path parsing in PathLikeWithPosition
@@ -433,6 +436,7 @@ impl TerminalBuilder {
 
         let terminal = Terminal {
             task,
+            max_scroll_history_lines,
             pty_tx: Notifier(pty_tx),
             completion_tx,
             term,
@@ -443,6 +447,8 @@ impl TerminalBuilder {
             selection_head: None,
             pty_info,
             breadcrumb_text: String::new(),
+            shell_commands: ShellCommands::default(),
+            shell_marks_rx,
             scroll_px: px(0.),
             last_mouse_position: None,
             next_link_id: 0,
@@ -549,6 +555,9 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    /// The prompts of the shell commands that are in the viewport, reported by the shell
+    /// integration.
+    pub command_marks: Vec<CommandMark>,
 }
 
 #[derive(Clone)]
@@ -573,6 +582,7 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            command_marks: Vec::new(),
         }
     }
 }
@@ -596,6 +606,9 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
+    /// The shell commands that the shell marked in its output.
+    shell_commands: ShellCommands,
+    shell_marks_rx: UnboundedReceiver<(ShellMark, HistoryLine)>,
     pub pty_info: PtyProcessInfo,
     scroll_px: Pixels,
     next_link_id: usize,
@@ -605,6 +618,8 @@ pub struct Terminal {
     url_regex: RegexSearch,
     word_regex: RegexSearch,
     task: Option<TaskState>,
+    /// The scrollback limit from the settings, applied to the terminal.
+    max_scroll_history_lines: Option<usize>,
 }
 
 pub struct TaskState {
@@ -615,6 +630,12 @@ pub struct TaskState {
     pub status: TaskStatus,
     pub completion_rx: Receiver<()>,
     pub hide: HideStrategy,
+    /// The regex to find problems in the task's output with, once it finishes.
+    pub problem_matcher: Option<String>,
+    /// The directory that relative paths in the task's output are relative to.
+    pub cwd: Option<PathBuf>,
+    /// The problems that the problem matcher found in the output of the finished task.
+    pub problems: Vec<TaskProblem>,
 }
 
 /// A status of the current terminal tab's task.
@@ -649,10 +670,12 @@ impl Terminal {
             AlacTermEvent::Title(title) => {
                 self.breadcrumb_text = title.to_string();
                 cx.emit(Event::BreadcrumbsChanged);
+                cx.emit(Event::TitleChanged);
             }
             AlacTermEvent::ResetTitle => {
                 self.breadcrumb_text = String::new();
                 cx.emit(Event::BreadcrumbsChanged);
+                cx.emit(Event::TitleChanged);
             }
             AlacTermEvent::ClipboardStore(_, data) => {
                 cx.write_to_clipboard(ClipboardItem::new_string(data.to_string()))
@@ -681,6 +704,8 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                // The terminal wakes up after parsing the output that the marks were read in.
+                self.register_shell_marks();
                 cx.emit(Event::Wakeup);
 
                 if self.pty_info.has_changed() {
@@ -707,6 +732,35 @@ impl Terminal {
         }
     }
 
+    /// Registers the marks that the shell put in the output that was parsed, on the lines that
+    /// they were parsed on.
+    fn register_shell_marks(&mut self) {
+        let term = self.term.lock();
+        while let Ok(Some((mark, line))) = self.shell_marks_rx.try_next() {
+            self.shell_commands.register(mark, line, &term);
+        }
+    }
+
+    /// Scrolls the prompt of the shell command above the viewport to its top.
+    pub fn scroll_to_previous_command(&mut self) {
+        let scroll = self.shell_commands.scroll_to_previous(&self.term.lock());
+        if let Some(scroll) = scroll {
+            self.events.push_back(InternalEvent::Scroll(scroll));
+        }
+    }
+
+    /// Scrolls the prompt of the shell command below the top of the viewport to its top, or
+    /// to the bottom when there's no such command.
+    pub fn scroll_to_next_command(&mut self) {
+        let scroll = self.shell_commands.scroll_to_next(&self.term.lock());
+        self.events.push_back(InternalEvent::Scroll(scroll));
+    }
+
+    /// The output of the last shell command that finished.
+    pub fn last_command_output(&self) -> Option<String> {
+        self.shell_commands.last_output(&self.term.lock())
+    }
+
     pub fn selection_started(&self) -> bool {
         self.selection_phase == SelectionPhase::Selecting
     }
@@ -734,6 +788,8 @@ impl Terminal {
                 term.resize(new_size);
             }
             InternalEvent::Clear => {
+                self.shell_commands.clear();
+
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -894,12 +950,21 @@ impl Terminal {
                                 MaybeNavigationTarget::Url(maybe_url_or_path)
                             } else {
                                 MaybeNavigationTarget::PathLike(PathLikeTarget {
-                                    maybe_path: maybe_url_or_path,
+                                    maybe_path: with_line_number_suffix(
+                                        term,
+                                        &url_match,
+                                        maybe_url_or_path,
+                                    ),
                                     terminal_dir: self.get_cwd(),
                                 })
                             };
                             cx.emit(Event::Open(target));
                         } else {
+                            let maybe_url_or_path = if is_url {
+                                maybe_url_or_path
+                            } else {
+                                with_line_number_suffix(term, &url_match, maybe_url_or_path)
+                            };
                             self.update_selected_word(
                                 prev_hovered_word,
                                 url_match,
@@ -991,6 +1056,21 @@ impl Terminal {
         }
     }
 
+    /// The lines of the active search match, which is the selected one.
+    pub fn active_match_lines(&self) -> Option<String> {
+        let selection_head = self.selection_head?;
+        let search_match = self
+            .matches
+            .iter()
+            .find(|search_match| search_match.contains(&selection_head))?;
+        let term = self.term.lock();
+        let lines = term.bounds_to_string(
+            AlacPoint::new(search_match.start().line, Column(0)),
+            AlacPoint::new(search_match.end().line, term.last_column()),
+        );
+        Some(lines.trim_end().to_string())
+    }
+
     pub fn select_matches(&mut self, matches: &[RangeInclusive<AlacPoint>]) {
         let matches_to_select = self
             .matches
@@ -1067,6 +1147,20 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    /// Applies a new scrollback limit to the terminal, dropping the oldest lines of the
+    /// scrollback when it's lower than before. Does nothing when the limit didn't change.
+    pub fn set_max_scroll_history_lines(&mut self, max_scroll_history_lines: Option<usize>) {
+        if self.max_scroll_history_lines == max_scroll_history_lines {
+            return;
+        }
+        self.max_scroll_history_lines = max_scroll_history_lines;
+        let config = Config {
+            scrolling_history: scrolling_history(self.task.is_some(), max_scroll_history_lines),
+            ..Config::default()
+        };
+        self.term.lock().set_options(config);
+    }
+
     ///Resize the terminal and the PTY.
     pub fn set_size(&mut self, new_size: TerminalSize) {
         if self.last_content.size != new_size {
@@ -1138,6 +1232,7 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content.command_marks = self.shell_commands.marks(&terminal);
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1165,6 +1260,33 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            command_marks: Vec::new(),
+        }
+    }
+
+    /// The text of the last `max_lines` lines of the terminal, up to the cursor, to restore
+    /// with [`Terminal::restore_scrollback`].
+    pub fn scrollback_text(&self, max_lines: usize) -> String {
+        let term = self.term.lock();
+        let end_line = term.grid().cursor.point.line;
+        let start_line = Line(end_line.0 - max_lines as i32 + 1).max(term.topmost_line());
+        let text = term.bounds_to_string(
+            AlacPoint::new(start_line, Column(0)),
+            AlacPoint::new(end_line, term.last_column()),
+        );
+        text.trim_end().to_string()
+    }
+
+    /// Writes the scrollback of a previous session of this terminal, before what the shell
+    /// prints.
+    pub fn restore_scrollback(&mut self, scrollback: &str) {
+        let mut term = self.term.lock();
+        for line in scrollback.lines() {
+            for c in line.chars() {
+                term.input(c);
+            }
+            term.newline();
+            term.grid_mut().cursor.point.column = Column(0);
         }
     }
 
@@ -1242,11 +1364,19 @@ impl Terminal {
                     self.pty_tx.notify(bytes);
                 }
             }
-        } else if self.secondary_pressed {
+        } else {
             self.word_from_position(Some(position));
         }
     }
 
+    /// Opens the URL or the file path under the mouse, like a secondary click on it would.
+    pub fn open_hovered_link(&mut self) {
+        if let Some(position) = self.last_mouse_position {
+            self.events
+                .push_back(InternalEvent::FindHyperlink(position, true));
+        }
+    }
+
     fn word_from_position(&mut self, position: Option<Point<Pixels>>) {
         if self.selection_phase == SelectionPhase::Selecting {
             self.last_content.last_hovered_word = None;
@@ -1496,6 +1626,16 @@ impl Terminal {
                     task_state.full_label.clone()
                 }
             }
+            // Prefer the title that the shell or the running program set with the OSC 0 and 2
+            // sequences, as it describes the session better than the process alone.
+            None if !self.breadcrumb_text.trim().is_empty() => {
+                let title = self.breadcrumb_text.trim();
+                if truncate {
+                    truncate_and_trailoff(title, MAX_CHARS)
+                } else {
+                    title.to_string()
+                }
+            }
             None => self
                 .pty_info
                 .current
@@ -1532,7 +1672,11 @@ impl Terminal {
     }
 
     pub fn can_navigate_to_selected_word(&self) -> bool {
-        self.secondary_pressed && self.hovered_word
+        self.hovered_word
+    }
+
+    pub fn secondary_pressed(&self) -> bool {
+        self.secondary_pressed
     }
 
     pub fn task(&self) -> Option<&TaskState> {
@@ -1578,6 +1722,18 @@ impl Terminal {
             }
         };
 
+        if let Some(problem_matcher) = task.problem_matcher.as_deref() {
+            let term = self.term.lock();
+            let output = term.bounds_to_string(
+                AlacPoint::new(term.topmost_line(), Column(0)),
+                AlacPoint::new(term.bottommost_line(), term.last_column()),
+            );
+            task.problems = task::match_problems(problem_matcher, &output, task.cwd.as_deref())
+                .log_err()
+                .unwrap_or_default();
+        }
+        cx.emit(Event::TaskFinished);
+
         let (finished_successfully, task_line, command_line) = task_summary(task, error_code);
         // SAFETY: the invocation happens on non `TaskStatus::Running` tasks, once,
         // after either `AlacTermEvent::Exit` or `AlacTermEvent::ChildExit` events that are spawned
@@ -1662,6 +1818,19 @@ impl Drop for Terminal {
 
 impl EventEmitter<Event> for Terminal {}
 
+fn scrolling_history(is_task: bool, max_scroll_history_lines: Option<usize>) -> usize {
+    if is_task {
+        // Tasks like `cargo build --all` may produce a lot of output, ergo allow maximum scrolling.
+        // After the task finishes, we do not allow appending to that terminal, so small tasks output should not
+        // cause excessive memory usage over time.
+        MAX_SCROLL_HISTORY_LINES
+    } else {
+        max_scroll_history_lines
+            .unwrap_or(DEFAULT_SCROLL_HISTORY_LINES)
+            .min(MAX_SCROLL_HISTORY_LINES)
+    }
+}
+
 /// Based on alacritty/src/display/hint.rs > regex_match_at
 /// Retrieve the match, if the specified point is inside the content matching the regex.
 fn regex_match_at<T>(term: &Term<T>, point: AlacPoint, regex: &mut RegexSearch) -> Option<Match> {
@@ -1686,6 +1855,36 @@ pub fn visible_regex_match_iter<'a, T>(
         .take_while(move |rm| rm.start().line <= viewport_end)
 }
 
+/// Appends the line number that follows a path in formats such as Python's
+/// `File "src/main.py", line 12` or Perl's `at script.pl line 12.` to the path, as a
+/// `:row` suffix.
+fn with_line_number_suffix<T>(term: &Term<T>, word_match: &Match, word: String) -> String {
+    let after_word = word_match.end().add(term, Boundary::Grid, 1);
+    if after_word.line != word_match.end().line || after_word == *word_match.end() {
+        return word;
+    }
+    let rest = term.bounds_to_string(
+        after_word,
+        AlacPoint::new(after_word.line, term.last_column()),
+    );
+    match line_number_suffix(&rest) {
+        Some(row) => format!("{word}:{row}"),
+        None => word,
+    }
+}
+
+/// Parses the line number at the start of the text that follows a path, such as
+/// `", line 12, in main` or ` line 12.`.
+fn line_number_suffix(text: &str) -> Option<u32> {
+    let text = text.strip_prefix('"').unwrap_or(text);
+    let text = text.strip_prefix(',').unwrap_or(text);
+    let digits = text.strip_prefix(" line ")?;
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
 fn make_selection(range: &RangeInclusive<AlacPoint>) -> Selection {
     let mut selection = Selection::new(SelectionType::Simple, *range.start(), AlacDirection::Left);
     selection.update(*range.end(), AlacDirection::Right);
@@ -1803,9 +2002,18 @@ mod tests {
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, line_number_suffix, rgb_for_index, IndexedCell, TerminalContent,
+        TerminalSize,
     };
 
+    #[test]
+    fn test_line_number_suffix() {
+        assert_eq!(line_number_suffix("\", line 12, in <module>"), Some(12));
+        assert_eq!(line_number_suffix(" line 7."), Some(7));
+        assert_eq!(line_number_suffix(":12:5"), None);
+        assert_eq!(line_number_suffix(" lines 7"), None);
+    }
+
     #[test]
     fn test_rgb_for_index() {
         // Test every possible value in the color cube.
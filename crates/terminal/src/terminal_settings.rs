@@ -33,15 +33,19 @@ pub struct TerminalSettings {
     pub line_height: TerminalLineHeight,
     pub env: HashMap<String, String>,
     pub blinking: TerminalBlink,
+    pub cursor_shape: Option<TerminalCursorShape>,
+    pub bell: TerminalBell,
     pub alternate_scroll: AlternateScroll,
     pub option_as_meta: bool,
     pub copy_on_select: bool,
+    pub shell_integration: bool,
     pub button: bool,
     pub dock: TerminalDockPosition,
     pub default_width: Pixels,
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
+    pub restore_scrollback_lines: usize,
     pub toolbar: Toolbar,
 }
 
@@ -132,6 +136,15 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: terminal_controlled
     pub blinking: Option<TerminalBlink>,
+    /// Sets the shape of the cursor in the terminal, rather than letting the
+    /// programs that run in it choose it.
+    ///
+    /// Default: null
+    pub cursor_shape: Option<TerminalCursorShape>,
+    /// How the terminal signals the bell.
+    ///
+    /// Default: indicator
+    pub bell: Option<TerminalBell>,
     /// Sets whether Alternate Scroll mode (code: ?1007) is active by default.
     /// Alternate Scroll mode converts mouse scroll events into up / down key
     /// presses when in the alternate screen (e.g. when running applications
@@ -148,6 +161,11 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: false
     pub copy_on_select: Option<bool>,
+    /// Whether to load Zed's shell integration into bash, zsh and fish, to mark
+    /// where each command starts in the terminal and whether it succeeded.
+    ///
+    /// Default: false
+    pub shell_integration: Option<bool>,
     /// Whether to show the terminal button in the status bar.
     ///
     /// Default: true
@@ -170,11 +188,15 @@ pub struct TerminalSettingsContent {
     /// The maximum number of lines to keep in the scrollback history.
     /// Maximum allowed value is 100_000, all values above that will be treated as 100_000.
     /// 0 disables the scrolling.
-    /// Existing terminals will not pick up this change until they are recreated.
     /// See <a href="https://github.com/alacritty/alacritty/blob/cb3a79dbf6472740daca8440d5166c1d4af5029e/extra/man/alacritty.5.scd?plain=1#L207-L213">Alacritty documentation</a> for more information.
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// The number of lines of each terminal's output to save, and to show again when the
+    /// terminal is restored after restarting Zed. 0 disables it.
+    ///
+    /// Default: 0
+    pub restore_scrollback_lines: Option<usize>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
 }
@@ -250,6 +272,31 @@ pub enum TerminalBlink {
     On,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalCursorShape {
+    /// A block that covers the character under the cursor.
+    Block,
+    /// A vertical bar before the character under the cursor.
+    Bar,
+    /// A line under the character under the cursor.
+    Underline,
+    /// The outline of a block around the character under the cursor.
+    Hollow,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalBell {
+    /// Ignore the bell.
+    Off,
+    /// Show an indicator in the terminal's tab until the terminal gets input.
+    #[default]
+    Indicator,
+    /// Flash the terminal, and show an indicator in its tab.
+    Visual,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AlternateScroll {
@@ -35,6 +35,14 @@ define_connection! {
             DROP TABLE terminals;
 
             ALTER TABLE terminals2 RENAME TO terminals;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN title TEXT;
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN custom_title TEXT;
+            ALTER TABLE terminals ADD COLUMN accent_color INTEGER;
         )];
 }
 
@@ -52,19 +60,43 @@ impl TerminalDb {
     }
 
     query! {
-        pub async fn save_working_directory(
+        pub async fn save_terminal(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            working_directory: PathBuf,
+            title: Option<String>,
+            custom_title: Option<String>,
+            accent_color: Option<u32>
+        ) -> Result<()> {
+            INSERT INTO terminals
+                (item_id, workspace_id, working_directory, title, custom_title, accent_color)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT DO UPDATE SET
+                working_directory = ?3,
+                title = ?4,
+                custom_title = ?5,
+                accent_color = ?6
+        }
+    }
+
+    // Saved separately from the rest of the terminal, as the scrollback is costly to
+    // collect and only saved once the terminal's output settles.
+    query! {
+        pub async fn save_scrollback(
             item_id: ItemId,
             workspace_id: WorkspaceId,
-            working_directory: PathBuf
+            scrollback: Option<String>
         ) -> Result<()> {
-            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory)
-            VALUES (?, ?, ?)
+            UPDATE terminals
+            SET scrollback = ?3
+            WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
 
     query! {
-        pub fn get_working_directory(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
-            SELECT working_directory
+        pub fn get_terminal(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<(PathBuf, Option<String>, Option<String>, Option<String>, Option<u32>)>> {
+            SELECT working_directory, title, scrollback, custom_title, accent_color
             FROM terminals
             WHERE item_id = ? AND workspace_id = ?
         }
@@ -96,3 +128,56 @@ impl TerminalDb {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui;
+
+    #[gpui::test]
+    async fn test_save_scrollback_separately() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        TERMINAL_DB
+            .save_terminal(1234, workspace_id, PathBuf::from("/dir"), None, None, None)
+            .await
+            .unwrap();
+        TERMINAL_DB
+            .save_scrollback(1234, workspace_id, Some("$ ls\na.txt".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            TERMINAL_DB.get_terminal(1234, workspace_id).unwrap(),
+            Some((
+                PathBuf::from("/dir"),
+                None,
+                Some("$ ls\na.txt".to_string()),
+                None,
+                None
+            ))
+        );
+
+        // Saving the rest of the terminal keeps the scrollback saved before.
+        TERMINAL_DB
+            .save_terminal(
+                1234,
+                workspace_id,
+                PathBuf::from("/dir/a"),
+                Some("ls".to_string()),
+                Some("Build".to_string()),
+                Some(2),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            TERMINAL_DB.get_terminal(1234, workspace_id).unwrap(),
+            Some((
+                PathBuf::from("/dir/a"),
+                Some("ls".to_string()),
+                Some("$ ls\na.txt".to_string()),
+                Some("Build".to_string()),
+                Some(2)
+            ))
+        );
+    }
+}
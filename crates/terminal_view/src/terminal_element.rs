@@ -20,7 +20,7 @@ use terminal::{
             CursorShape as AlacCursorShape, NamedColor,
         },
     },
-    terminal_settings::TerminalSettings,
+    terminal_settings::{TerminalCursorShape, TerminalSettings},
     HoveredWord, IndexedCell, Terminal, TerminalContent, TerminalSize,
 };
 use theme::{ActiveTheme, Theme, ThemeSettings};
@@ -45,6 +45,9 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// The lines of the shell command prompts in the viewport, along with the colors of
+    /// their exit status marks.
+    command_marks: Vec<(usize, Hsla)>,
     last_hovered_word: Option<HoveredWord>,
     block_below_cursor_element: Option<AnyElement>,
 }
@@ -634,6 +637,7 @@ impl Element for TerminalElement {
                     .clone();
 
                 let font_weight = terminal_settings.font_weight.unwrap_or_default();
+                let cursor_shape_setting = terminal_settings.cursor_shape;
 
                 let line_height = terminal_settings.line_height.value();
                 let font_size = terminal_settings.font_size;
@@ -706,29 +710,37 @@ impl Element for TerminalElement {
 
                 let background_color = theme.colors().terminal_background;
 
-                let last_hovered_word = self.terminal.update(cx, |terminal, cx| {
-                    terminal.set_size(dimensions);
-                    terminal.sync(cx);
-                    if self.can_navigate_to_selected_word
-                        && terminal.can_navigate_to_selected_word()
-                    {
-                        terminal.last_content.last_hovered_word.clone()
-                    } else {
-                        None
-                    }
-                });
+                let (last_hovered_word, secondary_pressed) =
+                    self.terminal.update(cx, |terminal, cx| {
+                        terminal.set_size(dimensions);
+                        terminal.sync(cx);
+                        let last_hovered_word = if self.can_navigate_to_selected_word
+                            && terminal.can_navigate_to_selected_word()
+                        {
+                            terminal.last_content.last_hovered_word.clone()
+                        } else {
+                            None
+                        };
+                        (last_hovered_word, terminal.secondary_pressed())
+                    });
 
+                // Links are underlined on hover, and their targets are shown once they can be
+                // opened with a secondary click.
                 let scroll_top = self.terminal_view.read(cx).scroll_top;
-                let hyperlink_tooltip = last_hovered_word.clone().map(|hovered_word| {
-                    let offset = bounds.origin + point(gutter, px(0.)) - point(px(0.), scroll_top);
-                    let mut element = div()
-                        .size_full()
-                        .id("terminal-element")
-                        .tooltip(move |cx| Tooltip::text(hovered_word.word.clone(), cx))
-                        .into_any_element();
-                    element.prepaint_as_root(offset, bounds.size.into(), cx);
-                    element
-                });
+                let hyperlink_tooltip = last_hovered_word
+                    .clone()
+                    .filter(|_| secondary_pressed)
+                    .map(|hovered_word| {
+                        let offset =
+                            bounds.origin + point(gutter, px(0.)) - point(px(0.), scroll_top);
+                        let mut element = div()
+                            .size_full()
+                            .id("terminal-element")
+                            .tooltip(move |cx| Tooltip::text(hovered_word.word.clone(), cx))
+                            .into_any_element();
+                        element.prepaint_as_root(offset, bounds.size.into(), cx);
+                        element
+                    });
 
                 let TerminalContent {
                     cells,
@@ -737,10 +749,22 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_marks,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
+                let command_marks = command_marks
+                    .iter()
+                    .map(|mark| {
+                        let color = match mark.exit_status {
+                            None => theme.colors().text_muted,
+                            Some(0) => theme.status().success,
+                            Some(_) => theme.status().error,
+                        };
+                        (mark.display_line, color)
+                    })
+                    .collect();
 
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
@@ -790,9 +814,16 @@ impl Element for TerminalElement {
                     };
 
                     let focused = self.focused;
+                    let cursor_shape = match cursor_shape_setting {
+                        Some(TerminalCursorShape::Block) => AlacCursorShape::Block,
+                        Some(TerminalCursorShape::Bar) => AlacCursorShape::Beam,
+                        Some(TerminalCursorShape::Underline) => AlacCursorShape::Underline,
+                        Some(TerminalCursorShape::Hollow) => AlacCursorShape::HollowBlock,
+                        None => cursor.shape,
+                    };
                     TerminalElement::shape_cursor(cursor_point, dimensions, &cursor_text).map(
                         move |(cursor_position, block_width)| {
-                            let (shape, text) = match cursor.shape {
+                            let (shape, text) = match cursor_shape {
                                 AlacCursorShape::Block if !focused => (CursorShape::Hollow, None),
                                 AlacCursorShape::Block => (CursorShape::Block, Some(cursor_text)),
                                 AlacCursorShape::Underline => (CursorShape::Underscore, None),
@@ -857,6 +888,7 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_marks,
                     last_hovered_word,
                     block_below_cursor_element,
                 }
@@ -875,6 +907,9 @@ impl Element for TerminalElement {
             let scroll_top = self.terminal_view.read(cx).scroll_top;
 
             cx.paint_quad(fill(bounds, layout.background_color));
+            if self.terminal_view.read(cx).is_bell_flashing() {
+                cx.paint_quad(fill(bounds, cx.theme().colors().text.opacity(0.1)));
+            }
             let origin =
                 bounds.origin + Point::new(layout.gutter, px(0.)) - Point::new(px(0.), scroll_top);
 
@@ -888,7 +923,7 @@ impl Element for TerminalElement {
             };
 
             self.register_mouse_listeners(origin, layout.mode, &layout.hitbox, cx);
-            if self.can_navigate_to_selected_word && layout.last_hovered_word.is_some() {
+            if layout.hyperlink_tooltip.is_some() {
                 cx.set_cursor_style(gpui::CursorStyle::PointingHand, &layout.hitbox);
             } else {
                 cx.set_cursor_style(gpui::CursorStyle::IBeam, &layout.hitbox);
@@ -921,6 +956,16 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, cx);
                     }
 
+                    let line_height = layout.dimensions.line_height;
+                    for (display_line, color) in &layout.command_marks {
+                        let mark_origin = point(
+                            bounds.origin.x + layout.gutter * 0.25,
+                            origin.y + line_height * (*display_line as f32),
+                        );
+                        let mark_size = size(layout.gutter * 0.5, line_height);
+                        cx.paint_quad(fill(Bounds::new(mark_origin, mark_size), *color));
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -3,25 +3,29 @@ use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
 use crate::{default_working_directory, TerminalView};
 use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
 use futures::future::join_all;
 use gpui::{
-    actions, Action, AnchorCorner, AnyView, AppContext, AsyncWindowContext, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
-    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    actions, impl_actions, Action, AnchorCorner, AnyView, AppContext, AsyncWindowContext, Entity,
+    EventEmitter, ExternalPaths, FocusHandle, FocusableView, InteractiveElement, IntoElement,
+    Model, ParentElement, Pixels, Render, Styled, Subscription, Task, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
-use project::{terminals::TerminalKind, Fs, ProjectEntryId};
+use language::Point;
+use project::{terminals::TerminalKind, Fs, Project, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use task::{RevealStrategy, Shell, SpawnInTerminal, TaskId};
 use terminal::{
+    alacritty_terminal::term::TermMode,
     terminal_settings::{TerminalDockPosition, TerminalSettings},
     Terminal,
 };
 use ui::{
-    h_flex, ButtonCommon, Clickable, ContextMenu, IconButton, IconSize, PopoverMenu, Selectable,
-    Tooltip,
+    div, h_flex, ActiveTheme, ButtonCommon, Clickable, ContextMenu, FluentBuilder as _, IconButton,
+    IconSize, PopoverMenu, Selectable, Tooltip,
 };
 use util::{ResultExt, TryFutureExt};
 use workspace::{
@@ -29,7 +33,7 @@ use workspace::{
     item::SerializableItem,
     pane,
     ui::IconName,
-    DraggedTab, ItemId, NewTerminal, Pane, ToggleZoom, Workspace,
+    DraggedTab, ItemId, NewTerminal, Pane, SplitDirection, ToggleZoom, Workspace,
 };
 
 use anyhow::Result;
@@ -38,11 +42,24 @@ const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
 actions!(terminal_panel, [ToggleFocus]);
 
+/// Sends the selected text of the active editor, or its line under the cursor when nothing
+/// is selected, to the active terminal of the terminal panel.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct SendToTerminal {
+    /// Whether to send the paragraph under the cursor, rather than its line, when nothing
+    /// is selected.
+    #[serde(default)]
+    pub paragraph: bool,
+}
+
+impl_actions!(terminal_panel, [SendToTerminal]);
+
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(TerminalPanel::new_terminal);
             workspace.register_action(TerminalPanel::open_terminal);
+            workspace.register_action(TerminalPanel::send_to_terminal);
             workspace.register_action(|workspace, _: &ToggleFocus, cx| {
                 if workspace
                     .panel::<TerminalPanel>(cx)
@@ -58,7 +75,10 @@ pub fn init(cx: &mut AppContext) {
 }
 
 pub struct TerminalPanel {
+    /// The pane that was focused last, which new terminals are added to.
     pane: View<Pane>,
+    /// The side-by-side panes the panel is split into, from left to right.
+    panes: Vec<View<Pane>>,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
@@ -73,10 +93,36 @@ pub struct TerminalPanel {
 
 impl TerminalPanel {
     fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let pane = Self::new_pane(workspace.weak_handle(), workspace.project().clone(), cx);
+        let project = workspace.project().read(cx);
+        let enabled = project.is_local() || project.supports_remote_terminal(cx);
+        let this = Self {
+            pane: pane.clone(),
+            panes: vec![pane],
+            fs: workspace.app_state().fs.clone(),
+            workspace: workspace.weak_handle(),
+            pending_serialization: Task::ready(None),
+            width: None,
+            height: None,
+            pending_terminals_to_add: 0,
+            deferred_tasks: HashMap::default(),
+            _subscriptions: Vec::new(),
+            enabled,
+            additional_tab_bar_buttons: Vec::new(),
+        };
+        this.apply_tab_bar_buttons(cx);
+        this
+    }
+
+    fn new_pane(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
         let pane = cx.new_view(|cx| {
             let mut pane = Pane::new(
-                workspace.weak_handle(),
-                workspace.project().clone(),
+                workspace.clone(),
+                project,
                 Default::default(),
                 None,
                 NewTerminal.boxed_clone(),
@@ -87,7 +133,6 @@ impl TerminalPanel {
             pane.display_nav_history_buttons(None);
             pane.set_should_display_tab_bar(|_| true);
 
-            let workspace = workspace.weak_handle();
             pane.set_custom_drop_handle(cx, move |pane, dropped_item, cx| {
                 if let Some(tab) = dropped_item.downcast_ref::<DraggedTab>() {
                     let item = if &tab.pane == cx.view() {
@@ -137,27 +182,78 @@ impl TerminalPanel {
                 .update(cx, |toolbar, cx| toolbar.add_item(buffer_search_bar, cx));
             pane
         });
-        let subscriptions = vec![
-            cx.observe(&pane, |_, _, cx| cx.notify()),
-            cx.subscribe(&pane, Self::handle_pane_event),
-        ];
-        let project = workspace.project().read(cx);
-        let enabled = project.is_local() || project.supports_remote_terminal(cx);
-        let this = Self {
-            pane,
-            fs: workspace.app_state().fs.clone(),
-            workspace: workspace.weak_handle(),
-            pending_serialization: Task::ready(None),
-            width: None,
-            height: None,
-            pending_terminals_to_add: 0,
-            deferred_tasks: HashMap::default(),
-            _subscriptions: subscriptions,
-            enabled,
-            additional_tab_bar_buttons: Vec::new(),
+        cx.observe(&pane, |_, _, cx| cx.notify()).detach();
+        cx.subscribe(&pane, Self::handle_pane_event).detach();
+        pane
+    }
+
+    /// Splits the given pane, opening a new terminal in a pane to its left or right.
+    fn split_pane(
+        &mut self,
+        pane: &View<Pane>,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(pane_ix) = self.panes.iter().position(|p| p == pane) else {
+            return;
         };
-        this.apply_tab_bar_buttons(cx);
-        this
+        let new_pane_ix = match direction {
+            SplitDirection::Left => pane_ix,
+            SplitDirection::Right => pane_ix + 1,
+            SplitDirection::Up | SplitDirection::Down => return,
+        };
+        let Ok((project, kind)) = self.workspace.update(cx, |workspace, cx| {
+            (
+                workspace.project().clone(),
+                TerminalKind::Shell(default_working_directory(workspace, cx)),
+            )
+        }) else {
+            return;
+        };
+
+        let new_pane = Self::new_pane(self.workspace.clone(), project, cx);
+        self.panes.insert(new_pane_ix, new_pane.clone());
+        self.pane = new_pane;
+        self.apply_tab_bar_buttons(cx);
+        self.add_terminal(kind, RevealStrategy::Always, cx)
+            .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    /// Removes a pane once its last terminal is closed, closing the panel if it was the
+    /// only pane.
+    fn remove_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        if self.panes.len() == 1 {
+            cx.emit(PanelEvent::Close);
+            return;
+        }
+        let Some(pane_ix) = self.panes.iter().position(|p| p == pane) else {
+            return;
+        };
+        self.panes.remove(pane_ix);
+        if &self.pane == pane {
+            self.pane = self.panes[pane_ix.saturating_sub(1)].clone();
+            cx.focus_view(&self.pane);
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn activate_pane_in_direction(
+        &mut self,
+        action: &workspace::ActivatePaneInDirection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let pane_ix = self.panes.iter().position(|pane| pane == &self.pane);
+        let target_ix = pane_ix.and_then(|pane_ix| match action.0 {
+            SplitDirection::Left => pane_ix.checked_sub(1),
+            SplitDirection::Right => Some(pane_ix + 1),
+            SplitDirection::Up | SplitDirection::Down => None,
+        });
+        match target_ix.and_then(|target_ix| self.panes.get(target_ix)) {
+            Some(target_pane) => cx.focus_view(target_pane),
+            None => cx.propagate(),
+        }
     }
 
     pub fn register_tab_bar_button(
@@ -170,8 +266,14 @@ impl TerminalPanel {
     }
 
     fn apply_tab_bar_buttons(&self, cx: &mut ViewContext<Self>) {
+        for pane in &self.panes {
+            self.apply_tab_bar_buttons_to_pane(pane, cx);
+        }
+    }
+
+    fn apply_tab_bar_buttons_to_pane(&self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
         let additional_buttons = self.additional_tab_bar_buttons.clone();
-        self.pane.update(cx, |pane, cx| {
+        pane.update(cx, |pane, cx| {
             pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
                 if !pane.has_focus(cx) && !pane.context_menu_focused(cx) {
                     return (None, None);
@@ -204,6 +306,9 @@ impl TerminalPanel {
                                             "Spawn task",
                                             tasks_ui::Spawn::modal().boxed_clone(),
                                         )
+                                        .separator()
+                                        .action("Split Right", workspace::SplitRight.boxed_clone())
+                                        .action("Split Left", workspace::SplitLeft.boxed_clone())
                                 });
 
                                 Some(menu)
@@ -248,36 +353,57 @@ impl TerminalPanel {
             .log_err()
             .flatten();
 
-        let (panel, pane, items) = workspace.update(&mut cx, |workspace, cx| {
+        let (panel, panes) = workspace.update(&mut cx, |workspace, cx| {
             let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
-            let items = if let Some((serialized_panel, database_id)) =
+            let panes = if let Some((serialized_panel, database_id)) =
                 serialized_panel.as_ref().zip(workspace.database_id())
             {
                 panel.update(cx, |panel, cx| {
                     cx.notify();
                     panel.height = serialized_panel.height.map(|h| h.round());
                     panel.width = serialized_panel.width.map(|w| w.round());
-                    panel.pane.update(cx, |_, cx| {
-                        serialized_panel
-                            .items
-                            .iter()
-                            .map(|item_id| {
-                                TerminalView::deserialize(
-                                    workspace.project().clone(),
-                                    workspace.weak_handle(),
-                                    database_id,
-                                    *item_id,
-                                    cx,
-                                )
-                            })
-                            .collect::<Vec<_>>()
-                    })
+                    let serialized_panes = serialized_panel.panes();
+                    for _ in 1..serialized_panes.len() {
+                        let pane = Self::new_pane(
+                            workspace.weak_handle(),
+                            workspace.project().clone(),
+                            cx,
+                        );
+                        panel.panes.push(pane);
+                    }
+                    if let Some(pane) = panel.panes.get(serialized_panel.active_pane_ix) {
+                        panel.pane = pane.clone();
+                    }
+                    panel.apply_tab_bar_buttons(cx);
+                    panel
+                        .panes
+                        .clone()
+                        .into_iter()
+                        .zip(serialized_panes)
+                        .map(|(pane, serialized_pane)| {
+                            let items = pane.update(cx, |_, cx| {
+                                serialized_pane
+                                    .items
+                                    .iter()
+                                    .map(|item_id| {
+                                        TerminalView::deserialize(
+                                            workspace.project().clone(),
+                                            workspace.weak_handle(),
+                                            database_id,
+                                            *item_id,
+                                            cx,
+                                        )
+                                    })
+                                    .collect::<Vec<_>>()
+                            });
+                            (pane.downgrade(), serialized_pane.active_item_id, items)
+                        })
+                        .collect::<Vec<_>>()
                 })
             } else {
                 Vec::new()
             };
-            let pane = panel.read(cx).pane.clone();
-            (panel, pane, items)
+            (panel, panes)
         })?;
 
         if let Some(workspace) = workspace.upgrade() {
@@ -295,27 +421,39 @@ impl TerminalPanel {
                 .ok();
         }
 
-        let pane = pane.downgrade();
-        let items = futures::future::join_all(items).await;
         let mut alive_item_ids = Vec::new();
-        pane.update(&mut cx, |pane, cx| {
-            let active_item_id = serialized_panel
-                .as_ref()
-                .and_then(|panel| panel.active_item_id);
-            let mut active_ix = None;
-            for item in items {
-                if let Some(item) = item.log_err() {
-                    let item_id = item.entity_id().as_u64();
-                    pane.add_item(Box::new(item), false, false, None, cx);
-                    alive_item_ids.push(item_id as ItemId);
-                    if Some(item_id) == active_item_id {
-                        active_ix = Some(pane.items_len() - 1);
+        for (pane, active_item_id, items) in panes {
+            let items = futures::future::join_all(items).await;
+            pane.update(&mut cx, |pane, cx| {
+                let mut active_ix = None;
+                for item in items {
+                    if let Some(item) = item.log_err() {
+                        let item_id = item.entity_id().as_u64();
+                        pane.add_item(Box::new(item), false, false, None, cx);
+                        alive_item_ids.push(item_id as ItemId);
+                        if Some(item_id) == active_item_id {
+                            active_ix = Some(pane.items_len() - 1);
+                        }
                     }
                 }
-            }
 
-            if let Some(active_ix) = active_ix {
-                pane.activate_item(active_ix, false, false, cx)
+                if let Some(active_ix) = active_ix {
+                    pane.activate_item(active_ix, false, false, cx)
+                }
+            })?;
+        }
+        // Drop the panes whose terminals could not be restored, keeping at least one.
+        panel.update(&mut cx, |panel, cx| {
+            let (empty_panes, panes): (Vec<_>, Vec<_>) = panel
+                .panes
+                .drain(..)
+                .partition(|pane| pane.read(cx).items_len() == 0);
+            panel.panes = panes;
+            if panel.panes.is_empty() {
+                panel.panes.extend(empty_panes.into_iter().next());
+            }
+            if !panel.panes.contains(&panel.pane) {
+                panel.pane = panel.panes[0].clone();
             }
         })?;
 
@@ -336,20 +474,26 @@ impl TerminalPanel {
 
     fn handle_pane_event(
         &mut self,
-        _pane: View<Pane>,
+        pane: View<Pane>,
         event: &pane::Event,
         cx: &mut ViewContext<Self>,
     ) {
         match event {
             pane::Event::ActivateItem { .. } => self.serialize(cx),
             pane::Event::RemovedItem { .. } => self.serialize(cx),
-            pane::Event::Remove => cx.emit(PanelEvent::Close),
+            pane::Event::Remove => self.remove_pane(&pane, cx),
             pane::Event::ZoomIn => cx.emit(PanelEvent::ZoomIn),
             pane::Event::ZoomOut => cx.emit(PanelEvent::ZoomOut),
+            pane::Event::Split(direction) => self.split_pane(&pane, *direction, cx),
+            pane::Event::Focus => {
+                if self.pane != pane {
+                    self.pane = pane;
+                    self.serialize(cx);
+                }
+            }
 
             pane::Event::AddItem { item } => {
                 if let Some(workspace) = self.workspace.upgrade() {
-                    let pane = self.pane.clone();
                     workspace.update(cx, |workspace, cx| item.added_to_pane(workspace, pane, cx))
                 }
             }
@@ -460,7 +604,7 @@ impl TerminalPanel {
                 .detach_and_log_err(cx);
             return;
         }
-        let (existing_item_index, existing_terminal) = terminals_for_task
+        let (existing_pane, existing_item_index, existing_terminal) = terminals_for_task
             .last()
             .expect("covered no terminals case above")
             .clone();
@@ -469,7 +613,13 @@ impl TerminalPanel {
                 !use_new_terminal,
                 "Should have handled 'allow_concurrent_runs && use_new_terminal' case above"
             );
-            self.replace_terminal(spawn_task, existing_item_index, existing_terminal, cx);
+            self.replace_terminal(
+                spawn_task,
+                existing_pane,
+                existing_item_index,
+                existing_terminal,
+                cx,
+            );
         } else {
             self.deferred_tasks.insert(
                 spawn_in_terminal.id.clone(),
@@ -484,6 +634,7 @@ impl TerminalPanel {
                             } else {
                                 terminal_panel.replace_terminal(
                                     spawn_task,
+                                    existing_pane,
                                     existing_item_index,
                                     existing_terminal,
                                     cx,
@@ -524,20 +675,118 @@ impl TerminalPanel {
             .detach_and_log_err(cx);
     }
 
+    /// Sends code from the active editor to the shell or the REPL that runs in the active
+    /// terminal, opening a terminal when there's none.
+    fn send_to_terminal(
+        workspace: &mut Workspace,
+        action: &SendToTerminal,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(terminal_panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some((code, language)) = editor.update(cx, |editor, cx| {
+            let selection = editor.selections.newest_adjusted(cx);
+            let buffer = editor.buffer().read(cx).as_singleton()?.read(cx).snapshot();
+            let range = if selection.is_empty() {
+                let row = selection.head().row;
+                let (mut start_row, mut end_row) = (row, row);
+                if action.paragraph {
+                    while start_row > 0 && !buffer.is_line_blank(start_row - 1) {
+                        start_row -= 1;
+                    }
+                    while end_row < buffer.max_point().row && !buffer.is_line_blank(end_row + 1) {
+                        end_row += 1;
+                    }
+                }
+                Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row))
+            } else {
+                selection.range()
+            };
+            let language = buffer
+                .language_at(range.start)
+                .map(|language| language.name().to_string());
+            Some((buffer.text_for_range(range).collect::<String>(), language))
+        }) else {
+            return;
+        };
+        if code.trim().is_empty() {
+            return;
+        }
+
+        let terminal = terminal_panel.read(cx).shell_terminal(cx);
+        let kind = TerminalKind::Shell(default_working_directory(workspace, cx));
+        workspace.open_panel::<Self>(cx);
+        cx.spawn(|_, mut cx| async move {
+            let terminal = match terminal {
+                Some(terminal) => terminal,
+                None => {
+                    terminal_panel
+                        .update(&mut cx, |terminal_panel, cx| {
+                            terminal_panel.add_terminal(kind, RevealStrategy::Never, cx)
+                        })?
+                        .await?
+                }
+            };
+            terminal.update(&mut cx, |terminal, _| {
+                let bracketed_paste = terminal
+                    .last_content()
+                    .mode
+                    .contains(TermMode::BRACKETED_PASTE);
+                let code = prepare_code_for_terminal(&code, language.as_deref(), bracketed_paste);
+                terminal.paste(&code);
+                terminal.input("\r".to_string());
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// The terminal to send code to: the active terminal of the focused pane, or else the
+    /// first terminal that doesn't run a task.
+    fn shell_terminal(&self, cx: &AppContext) -> Option<Model<Terminal>> {
+        let runs_task = |terminal_view: &View<TerminalView>| {
+            terminal_view.read(cx).terminal().read(cx).task().is_some()
+        };
+        let active_terminal = self
+            .pane
+            .read(cx)
+            .active_item()
+            .and_then(|item| item.downcast::<TerminalView>())
+            .filter(|terminal_view| !runs_task(terminal_view));
+        active_terminal
+            .or_else(|| {
+                self.panes
+                    .iter()
+                    .flat_map(|pane| pane.read(cx).items())
+                    .filter_map(|item| item.downcast::<TerminalView>())
+                    .find(|terminal_view| !runs_task(terminal_view))
+            })
+            .map(|terminal_view| terminal_view.read(cx).terminal().clone())
+    }
+
     fn terminals_for_task(
         &self,
         label: &str,
         cx: &mut AppContext,
-    ) -> Vec<(usize, View<TerminalView>)> {
-        self.pane
-            .read(cx)
-            .items()
-            .enumerate()
-            .filter_map(|(index, item)| Some((index, item.act_as::<TerminalView>(cx)?)))
-            .filter_map(|(index, terminal_view)| {
+    ) -> Vec<(View<Pane>, usize, View<TerminalView>)> {
+        self.panes
+            .iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .enumerate()
+                    .filter_map(|(index, item)| {
+                        Some((pane.clone(), index, item.act_as::<TerminalView>(cx)?))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter_map(|(pane, index, terminal_view)| {
                 let task_state = terminal_view.read(cx).terminal().read(cx).task()?;
                 if &task_state.full_label == label {
-                    Some((index, terminal_view))
+                    Some((pane, index, terminal_view))
                 } else {
                     None
                 }
@@ -545,8 +794,14 @@ impl TerminalPanel {
             .collect()
     }
 
-    fn activate_terminal_view(&self, item_index: usize, cx: &mut WindowContext) {
-        self.pane.update(cx, |pane, cx| {
+    fn activate_terminal_view(
+        &mut self,
+        pane: &View<Pane>,
+        item_index: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.pane = pane.clone();
+        pane.update(cx, |pane, cx| {
             pane.activate_item(item_index, true, true, cx)
         })
     }
@@ -600,28 +855,27 @@ impl TerminalPanel {
     }
 
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
-        let mut items_to_serialize = HashSet::default();
-        let items = self
-            .pane
-            .read(cx)
-            .items()
-            .filter_map(|item| {
-                let terminal_view = item.act_as::<TerminalView>(cx)?;
-                if terminal_view.read(cx).terminal().read(cx).task().is_some() {
-                    None
-                } else {
-                    let id = item.item_id().as_u64();
-                    items_to_serialize.insert(id);
-                    Some(id)
-                }
-            })
-            .collect::<Vec<_>>();
-        let active_item_id = self
-            .pane
-            .read(cx)
-            .active_item()
-            .map(|item| item.item_id().as_u64())
-            .filter(|active_id| items_to_serialize.contains(active_id));
+        let mut panes = Vec::new();
+        let mut active_pane_ix = 0;
+        for pane in &self.panes {
+            let serialized_pane = serialize_pane(pane, cx);
+            // Panes with task terminals only are not restored.
+            if serialized_pane.items.is_empty() {
+                continue;
+            }
+            if pane == &self.pane {
+                active_pane_ix = panes.len();
+            }
+            panes.push(serialized_pane);
+        }
+        // Older versions only restore `items`, into a single pane.
+        let items = panes
+            .iter()
+            .flat_map(|pane| pane.items.iter().copied())
+            .collect();
+        let active_item_id = panes
+            .get(active_pane_ix)
+            .and_then(|pane| pane.active_item_id);
         let height = self.height;
         let width = self.width;
         self.pending_serialization = cx.background_executor().spawn(
@@ -632,6 +886,8 @@ impl TerminalPanel {
                         serde_json::to_string(&SerializedTerminalPanel {
                             items,
                             active_item_id,
+                            panes,
+                            active_pane_ix,
                             height,
                             width,
                         })?,
@@ -644,8 +900,9 @@ impl TerminalPanel {
     }
 
     fn replace_terminal(
-        &self,
+        &mut self,
         spawn_task: SpawnInTerminal,
+        pane: View<Pane>,
         terminal_item_index: usize,
         terminal_to_replace: View<TerminalView>,
         cx: &mut ViewContext<'_, Self>,
@@ -668,7 +925,7 @@ impl TerminalPanel {
 
         match reveal {
             RevealStrategy::Always => {
-                self.activate_terminal_view(terminal_item_index, cx);
+                self.activate_terminal_view(&pane, terminal_item_index, cx);
                 let task_workspace = self.workspace.clone();
                 cx.spawn(|_, mut cx| async move {
                     task_workspace
@@ -684,15 +941,22 @@ impl TerminalPanel {
     }
 
     fn has_no_terminals(&self, cx: &WindowContext) -> bool {
-        self.pane.read(cx).items_len() == 0 && self.pending_terminals_to_add == 0
+        self.terminal_count(cx) == 0 && self.pending_terminals_to_add == 0
+    }
+
+    fn terminal_count(&self, cx: &WindowContext) -> usize {
+        self.panes
+            .iter()
+            .map(|pane| pane.read(cx).items_len())
+            .sum()
     }
 }
 
 async fn wait_for_terminals_tasks(
-    terminals_for_task: Vec<(usize, View<TerminalView>)>,
+    terminals_for_task: Vec<(View<Pane>, usize, View<TerminalView>)>,
     cx: &mut AsyncWindowContext,
 ) {
-    let pending_tasks = terminals_for_task.iter().filter_map(|(_, terminal)| {
+    let pending_tasks = terminals_for_task.iter().filter_map(|(_, _, terminal)| {
         terminal
             .update(cx, |terminal_view, cx| {
                 terminal_view
@@ -736,7 +1000,24 @@ impl Render for TerminalPanel {
             cx,
         );
         BufferSearchBar::register(&mut registrar);
-        registrar.into_div().size_full().child(self.pane.clone())
+        registrar
+            .into_div()
+            .size_full()
+            .on_action(cx.listener(Self::activate_pane_in_direction))
+            .child(
+                h_flex()
+                    .size_full()
+                    .children(self.panes.iter().enumerate().map(|(ix, pane)| {
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .when(ix > 0, |this| {
+                                this.border_l_1().border_color(cx.theme().colors().border)
+                            })
+                            .child(pane.clone())
+                    })),
+            )
     }
 }
 
@@ -798,7 +1079,9 @@ impl Panel for TerminalPanel {
     }
 
     fn set_zoomed(&mut self, zoomed: bool, cx: &mut ViewContext<Self>) {
-        self.pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        }
     }
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
@@ -818,7 +1101,7 @@ impl Panel for TerminalPanel {
     }
 
     fn icon_label(&self, cx: &WindowContext) -> Option<String> {
-        let count = self.pane.read(cx).items_len();
+        let count = self.terminal_count(cx);
         if count == 0 {
             None
         } else {
@@ -851,14 +1134,65 @@ impl Panel for TerminalPanel {
     }
 }
 
+fn serialize_pane(pane: &View<Pane>, cx: &AppContext) -> SerializedTerminalPane {
+    let mut items_to_serialize = HashSet::default();
+    let items = pane
+        .read(cx)
+        .items()
+        .filter_map(|item| {
+            let terminal_view = item.act_as::<TerminalView>(cx)?;
+            if terminal_view.read(cx).terminal().read(cx).task().is_some() {
+                None
+            } else {
+                let id = item.item_id().as_u64();
+                items_to_serialize.insert(id);
+                Some(id)
+            }
+        })
+        .collect::<Vec<_>>();
+    let active_item_id = pane
+        .read(cx)
+        .active_item()
+        .map(|item| item.item_id().as_u64())
+        .filter(|active_id| items_to_serialize.contains(active_id));
+    SerializedTerminalPane {
+        items,
+        active_item_id,
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 struct SerializedTerminalPanel {
     items: Vec<u64>,
     active_item_id: Option<u64>,
+    /// The side-by-side panes of the panel, from left to right.
+    #[serde(default)]
+    panes: Vec<SerializedTerminalPane>,
+    #[serde(default)]
+    active_pane_ix: usize,
     width: Option<Pixels>,
     height: Option<Pixels>,
 }
 
+impl SerializedTerminalPanel {
+    fn panes(&self) -> Vec<SerializedTerminalPane> {
+        if self.panes.is_empty() {
+            vec![SerializedTerminalPane {
+                items: self.items.clone(),
+                active_item_id: self.active_item_id,
+            }]
+        } else {
+            self.panes.clone()
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SerializedTerminalPane {
+    items: Vec<u64>,
+    active_item_id: Option<u64>,
+}
+
 fn retrieve_system_shell() -> Option<String> {
     #[cfg(not(target_os = "windows"))]
     {
@@ -875,6 +1209,40 @@ fn retrieve_system_shell() -> Option<String> {
     return Some("powershell".to_owned());
 }
 
+/// Prepares code to be typed into a REPL, followed by a return.
+///
+/// Python's REPL ends a block at the first blank line, and only when a blank line follows it,
+/// so without bracketed paste, blank lines are removed from the code and added after each
+/// block instead.
+fn prepare_code_for_terminal(code: &str, language: Option<&str>, bracketed_paste: bool) -> String {
+    let code = code.trim_end();
+    if language != Some("Python") {
+        return code.to_string();
+    }
+
+    let is_indented = |line: &str| line.starts_with(|c: char| c.is_whitespace());
+    let mut prepared = String::new();
+    let mut in_block = false;
+    for line in code.lines() {
+        if line.trim().is_empty() {
+            if bracketed_paste {
+                prepared.push('\n');
+            }
+            continue;
+        }
+        if in_block && !is_indented(line) && !bracketed_paste {
+            prepared.push('\n');
+        }
+        in_block = is_indented(line);
+        prepared.push_str(line);
+        prepared.push('\n');
+    }
+    if !in_block {
+        prepared.pop();
+    }
+    prepared
+}
+
 #[cfg(target_os = "windows")]
 fn to_windows_shell_variable(shell_type: WindowsShellType, input: String) -> String {
     match shell_type {
@@ -948,3 +1316,84 @@ enum WindowsShellType {
     Cmd,
     Other,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_split_terminal_panes(cx: &mut TestAppContext) {
+        let (_project, workspace) = init_test(cx).await;
+        let panel = workspace.update(cx, |workspace, cx| {
+            cx.new_view(|cx| TerminalPanel::new(workspace, cx))
+        });
+
+        panel.update(cx, |panel, cx| {
+            let first_pane = panel.pane.clone();
+            panel.split_pane(&first_pane, SplitDirection::Right, cx);
+            let right_pane = panel.pane.clone();
+            assert_eq!(panel.panes, [first_pane.clone(), right_pane.clone()]);
+
+            panel.split_pane(&first_pane, SplitDirection::Left, cx);
+            let left_pane = panel.pane.clone();
+            assert_eq!(
+                panel.panes,
+                [left_pane.clone(), first_pane.clone(), right_pane.clone()]
+            );
+
+            // Panes are only split side by side.
+            panel.split_pane(&first_pane, SplitDirection::Down, cx);
+            assert_eq!(panel.panes.len(), 3);
+
+            // Removing the active pane activates the one to its left, or the first one.
+            panel.pane = right_pane.clone();
+            panel.remove_pane(&right_pane, cx);
+            assert_eq!(panel.panes, [left_pane.clone(), first_pane.clone()]);
+            assert_eq!(panel.pane, first_pane);
+            panel.remove_pane(&left_pane, cx);
+            assert_eq!(panel.panes, [first_pane.clone()]);
+            assert_eq!(panel.pane, first_pane);
+
+            // The last pane is kept, and the panel closed instead.
+            panel.remove_pane(&first_pane, cx);
+            assert_eq!(panel.panes, [first_pane]);
+        });
+    }
+
+    #[test]
+    fn test_serialized_panel_without_panes() {
+        // Panels serialized before they could be split have a single pane.
+        let panel: SerializedTerminalPanel = serde_json::from_str(
+            r#"{"items": [1, 2], "active_item_id": 2, "width": null, "height": null}"#,
+        )
+        .unwrap();
+        let panes = panel.panes();
+        assert_eq!(panes.len(), 1);
+        assert_eq!(panes[0].items, [1, 2]);
+        assert_eq!(panes[0].active_item_id, Some(2));
+        assert_eq!(panel.active_pane_ix, 0);
+    }
+
+    #[test]
+    fn test_prepare_code_for_terminal() {
+        let code = "def f():\n    x = 1\n\n    return x\nprint(f())\n";
+        assert_eq!(
+            prepare_code_for_terminal(code, Some("Python"), false),
+            "def f():\n    x = 1\n    return x\n\nprint(f())"
+        );
+        assert_eq!(
+            prepare_code_for_terminal(code, Some("Python"), true),
+            "def f():\n    x = 1\n\n    return x\nprint(f())"
+        );
+        assert_eq!(
+            prepare_code_for_terminal("for x in y:\n    print(x)", Some("Python"), false),
+            "for x in y:\n    print(x)\n"
+        );
+        assert_eq!(
+            prepare_code_for_terminal("IO.puts(1)\n\n", Some("Elixir"), false),
+            "IO.puts(1)"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    Task, View, ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::ModalView;
+
+use crate::TerminalView;
+
+actions!(terminal, [RenameTerminal, ChangeTabColor]);
+
+/// A modal to give a terminal a title of its own, rather than the one that the shell sets,
+/// or to color its tab to tell it apart from the other terminals.
+pub struct TerminalTabModal {
+    picker: View<Picker<TerminalTabDelegate>>,
+}
+
+enum TerminalTabMode {
+    /// Renames the terminal to the typed title, or lets it use the automatic title again
+    /// when nothing is typed.
+    Rename,
+    /// Colors the terminal's tab with the chosen accent color.
+    Color,
+}
+
+impl TerminalTabModal {
+    /// Opens the modal to rename the terminal, with its current title filled in.
+    pub(crate) fn rename(
+        terminal_view: WeakView<TerminalView>,
+        title: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let this = Self::new(terminal_view, TerminalTabMode::Rename, cx);
+        this.picker
+            .update(cx, |picker, cx| picker.set_query(title, cx));
+        this
+    }
+
+    pub(crate) fn color(terminal_view: WeakView<TerminalView>, cx: &mut ViewContext<Self>) -> Self {
+        Self::new(terminal_view, TerminalTabMode::Color, cx)
+    }
+
+    fn new(
+        terminal_view: WeakView<TerminalView>,
+        mode: TerminalTabMode,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let accent_count = cx.theme().accents().0.len() as u32;
+        // The first entry removes the color.
+        let colors = (0..=accent_count)
+            .map(|ix| ix.checked_sub(1))
+            .collect::<Vec<_>>();
+        let delegate = TerminalTabDelegate {
+            modal: cx.view().downgrade(),
+            terminal_view,
+            mode,
+            matches: (0..colors.len()).collect(),
+            colors,
+            query: String::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl FocusableView for TerminalTabModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for TerminalTabModal {}
+impl ModalView for TerminalTabModal {}
+
+impl Render for TerminalTabModal {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct TerminalTabDelegate {
+    modal: WeakView<TerminalTabModal>,
+    terminal_view: WeakView<TerminalView>,
+    mode: TerminalTabMode,
+    /// The accent color indices to choose from, with `None` for no color.
+    colors: Vec<Option<u32>>,
+    /// The indices of the colors whose names match the query.
+    matches: Vec<usize>,
+    query: String,
+    selected_index: usize,
+}
+
+impl TerminalTabDelegate {
+    fn color_name(color: Option<u32>) -> String {
+        match color {
+            Some(ix) => format!("Accent {}", ix + 1),
+            None => "No color".to_string(),
+        }
+    }
+
+    fn dismiss(&self, cx: &mut ViewContext<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+}
+
+impl PickerDelegate for TerminalTabDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        match self.mode {
+            TerminalTabMode::Rename => 1,
+            TerminalTabMode::Color => self.matches.len(),
+        }
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.mode {
+            TerminalTabMode::Rename => "Rename terminal…".into(),
+            TerminalTabMode::Color => "Color the terminal's tab…".into(),
+        }
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let lowercase_query = query.trim().to_lowercase();
+        self.matches = self
+            .colors
+            .iter()
+            .enumerate()
+            .filter(|(_, color)| {
+                Self::color_name(**color)
+                    .to_lowercase()
+                    .contains(&lowercase_query)
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.query = query;
+        self.selected_index = 0;
+        cx.notify();
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        match self.mode {
+            TerminalTabMode::Rename => {
+                let title = Some(self.query.trim().to_string()).filter(|title| !title.is_empty());
+                self.terminal_view
+                    .update(cx, |terminal_view, cx| {
+                        terminal_view.set_custom_title(title, cx)
+                    })
+                    .log_err();
+            }
+            TerminalTabMode::Color => {
+                let Some(color) = self
+                    .matches
+                    .get(self.selected_index)
+                    .and_then(|ix| self.colors.get(*ix))
+                    .copied()
+                else {
+                    return;
+                };
+                self.terminal_view
+                    .update(cx, |terminal_view, cx| {
+                        terminal_view.set_tab_color(color, cx)
+                    })
+                    .log_err();
+            }
+        }
+        self.dismiss(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.dismiss(cx);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .inset(true)
+            .selected(selected);
+        match self.mode {
+            TerminalTabMode::Rename => {
+                let title = self.query.trim();
+                let label = if title.is_empty() {
+                    "Use the automatic title".to_string()
+                } else {
+                    format!("Rename to \u{201c}{title}\u{201d}")
+                };
+                Some(item.child(Label::new(label)))
+            }
+            TerminalTabMode::Color => {
+                let color = *self.colors.get(*self.matches.get(ix)?)?;
+                let swatch = div()
+                    .size_3()
+                    .rounded_full()
+                    .when_some(color, |this, color| {
+                        this.bg(cx.theme().accents().color_for_index(color))
+                    });
+                Some(
+                    item.start_slot(swatch)
+                        .child(Label::new(Self::color_name(color))),
+                )
+            }
+        }
+    }
+}
@@ -1,15 +1,16 @@
 mod persistence;
 pub mod terminal_element;
 pub mod terminal_panel;
+mod terminal_tab;
 
 use collections::HashSet;
 use editor::{actions::SelectAll, scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent, EventEmitter,
-    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
-    MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task, View,
-    VisualContext, WeakView,
+    anchored, deferred, div, impl_actions, AnyElement, AppContext, ClipboardItem, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model,
+    MouseButton, MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task,
+    View, VisualContext, WeakView,
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
@@ -19,14 +20,17 @@ use terminal::{
         index::Point,
         term::{search::RegexSearch, TermMode},
     },
-    terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
-    Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
-    ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
-    TerminalSize,
+    terminal_settings::{TerminalBell, TerminalBlink, TerminalSettings, WorkingDirectory},
+    Clear, Copy, CopyLastCommandOutput, CopyMatchedLine, Event, MaybeNavigationTarget,
+    OpenHoveredLink, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp,
+    ScrollToBottom, ScrollToNextCommand, ScrollToPreviousCommand, ScrollToTop,
+    ShowCharacterPalette, TaskStatus, Terminal, TerminalSize,
 };
 use terminal_element::{is_blank, TerminalElement};
+use terminal_tab::TerminalTabModal;
+pub use terminal_tab::{ChangeTabColor, RenameTerminal};
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label, Tooltip};
-use util::{paths::PathWithPosition, ResultExt};
+use util::{paths::PathWithPosition, truncate_and_trailoff, ResultExt};
 use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams},
     notifications::NotifyResultExt,
@@ -55,6 +59,9 @@ const REGEX_SPECIAL_CHARS: &[char] = &[
 ];
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const BELL_FLASH_DURATION: Duration = Duration::from_millis(150);
+/// How long the terminal's output has to settle before its scrollback is saved.
+const SCROLLBACK_SERIALIZATION_DEBOUNCE: Duration = Duration::from_secs(2);
 
 ///Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -90,6 +97,16 @@ pub struct BlockContext<'a, 'b> {
     pub dimensions: TerminalSize,
 }
 
+/// What's saved of a terminal besides its scrollback.
+#[derive(Clone, PartialEq)]
+struct SerializedTerminalState {
+    workspace_id: WorkspaceId,
+    cwd: PathBuf,
+    title: Option<String>,
+    custom_title: Option<String>,
+    accent_color: Option<u32>,
+}
+
 ///A terminal view, maintains the PTY's file handles and communicates with the terminal
 pub struct TerminalView {
     terminal: Model<Terminal>,
@@ -97,14 +114,26 @@ pub struct TerminalView {
     focus_handle: FocusHandle,
     //Currently using iTerm bell, show bell emoji in tab until input is received
     has_bell: bool,
+    /// Set while the terminal flashes for a visual bell.
+    bell_flash_task: Option<Task<()>>,
     context_menu: Option<(View<ContextMenu>, gpui::Point<Pixels>, Subscription)>,
     blink_state: bool,
     blinking_on: bool,
     blinking_paused: bool,
     blink_epoch: usize,
     can_navigate_to_selected_word: bool,
+    /// Checks whether the hovered path-like word exists, to underline it once it does.
+    hover_target_task: Option<Task<()>>,
+    /// Saves the scrollback once the terminal's output settles.
+    scrollback_serialization: Option<Task<()>>,
+    /// The state of the terminal that was last saved, to only save it again once it changes.
+    serialized_state: Option<SerializedTerminalState>,
     workspace_id: Option<WorkspaceId>,
     show_title: bool,
+    /// The title that the user gave the terminal, shown instead of the automatic one.
+    custom_title: Option<String>,
+    /// The index of the theme accent color that the terminal's tab is marked with.
+    accent_color: Option<u32>,
     block_below_cursor: Option<Rc<BlockProperties>>,
     scroll_top: Pixels,
     _subscriptions: Vec<Subscription>,
@@ -172,6 +201,7 @@ impl TerminalView {
             terminal,
             workspace: workspace_handle,
             has_bell: false,
+            bell_flash_task: None,
             focus_handle,
             context_menu: None,
             blink_state: true,
@@ -179,8 +209,13 @@ impl TerminalView {
             blinking_paused: false,
             blink_epoch: 0,
             can_navigate_to_selected_word: false,
+            hover_target_task: None,
+            scrollback_serialization: None,
+            serialized_state: None,
             workspace_id,
             show_title: TerminalSettings::get_global(cx).toolbar.title,
+            custom_title: None,
+            accent_color: None,
             block_below_cursor: None,
             scroll_top: Pixels::ZERO,
             _subscriptions: vec![
@@ -200,6 +235,24 @@ impl TerminalView {
         self.has_bell
     }
 
+    /// Whether the terminal is flashing for a visual bell.
+    pub fn is_bell_flashing(&self) -> bool {
+        self.bell_flash_task.is_some()
+    }
+
+    fn flash_bell(&mut self, cx: &mut ViewContext<Self>) {
+        self.bell_flash_task = Some(cx.spawn(|terminal_view, mut cx| async move {
+            Timer::after(BELL_FLASH_DURATION).await;
+            terminal_view
+                .update(&mut cx, |terminal_view, cx| {
+                    terminal_view.bell_flash_task = None;
+                    cx.notify();
+                })
+                .ok();
+        }));
+        cx.notify();
+    }
+
     pub fn clear_bell(&mut self, cx: &mut ViewContext<TerminalView>) {
         self.has_bell = false;
         cx.emit(Event::Wakeup);
@@ -219,6 +272,9 @@ impl TerminalView {
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
                 .separator()
+                .action("Rename…", Box::new(RenameTerminal))
+                .action("Change Color…", Box::new(ChangeTabColor))
+                .separator()
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
         });
 
@@ -240,6 +296,10 @@ impl TerminalView {
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         let settings = TerminalSettings::get_global(cx);
         self.show_title = settings.toolbar.title;
+        let max_scroll_history_lines = settings.max_scroll_history_lines;
+        self.terminal.update(cx, |terminal, _| {
+            terminal.set_max_scroll_history_lines(max_scroll_history_lines)
+        });
         cx.notify();
     }
 
@@ -401,6 +461,76 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_command(
+        &mut self,
+        _: &ScrollToPreviousCommand,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_command());
+        cx.notify();
+    }
+
+    fn scroll_to_next_command(&mut self, _: &ScrollToNextCommand, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_command());
+        cx.notify();
+    }
+
+    fn open_hovered_link(&mut self, _: &OpenHoveredLink, cx: &mut ViewContext<Self>) {
+        self.terminal.update(cx, |term, _| term.open_hovered_link());
+        cx.notify();
+    }
+
+    fn copy_matched_line(&mut self, _: &CopyMatchedLine, cx: &mut ViewContext<Self>) {
+        if let Some(lines) = self.terminal.read(cx).active_match_lines() {
+            cx.write_to_clipboard(ClipboardItem::new_string(lines));
+        }
+    }
+
+    /// Sets the title shown in the terminal's tab, or lets the terminal use its automatic
+    /// title again when `None`.
+    pub fn set_custom_title(&mut self, title: Option<String>, cx: &mut ViewContext<Self>) {
+        self.custom_title = title;
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    /// Marks the terminal's tab with the theme accent color at the given index.
+    pub fn set_tab_color(&mut self, accent_color: Option<u32>, cx: &mut ViewContext<Self>) {
+        self.accent_color = accent_color;
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    fn rename(&mut self, _: &RenameTerminal, cx: &mut ViewContext<Self>) {
+        let terminal_view = cx.view().downgrade();
+        let title = match &self.custom_title {
+            Some(custom_title) => custom_title.clone(),
+            None => self.terminal.read(cx).title(false),
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, |cx| TerminalTabModal::rename(terminal_view, title, cx))
+            })
+            .ok();
+    }
+
+    fn change_tab_color(&mut self, _: &ChangeTabColor, cx: &mut ViewContext<Self>) {
+        let terminal_view = cx.view().downgrade();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, |cx| TerminalTabModal::color(terminal_view, cx))
+            })
+            .ok();
+    }
+
+    fn copy_last_command_output(&mut self, _: &CopyLastCommandOutput, cx: &mut ViewContext<Self>) {
+        if let Some(output) = self.terminal.read(cx).last_command_output() {
+            cx.write_to_clipboard(ClipboardItem::new_string(output));
+        }
+    }
+
     pub fn should_show_cursor(&self, focused: bool, cx: &mut gpui::ViewContext<Self>) -> bool {
         //Don't blink the cursor when not focused, blinking is disabled, or paused
         if !focused
@@ -607,10 +737,18 @@ fn subscribe_for_terminal_events(
                 cx.emit(SearchEvent::MatchesInvalidated);
             }
 
-            Event::Bell => {
-                this.has_bell = true;
-                cx.emit(Event::Wakeup);
-            }
+            Event::Bell => match TerminalSettings::get_global(cx).bell {
+                TerminalBell::Off => {}
+                TerminalBell::Indicator => {
+                    this.has_bell = true;
+                    cx.emit(Event::Wakeup);
+                }
+                TerminalBell::Visual => {
+                    this.has_bell = true;
+                    this.flash_bell(cx);
+                    cx.emit(Event::Wakeup);
+                }
+            },
 
             Event::BlinkChanged => this.blinking_on = !this.blinking_on,
 
@@ -619,6 +757,7 @@ fn subscribe_for_terminal_events(
             }
 
             Event::NewNavigationTarget(maybe_navigation_target) => {
+                this.hover_target_task = None;
                 this.can_navigate_to_selected_word = match maybe_navigation_target {
                     Some(MaybeNavigationTarget::Url(_)) => true,
                     Some(MaybeNavigationTarget::PathLike(path_like_target)) => {
@@ -632,10 +771,19 @@ fn subscribe_for_terminal_events(
                                 &path_like_target.maybe_path,
                                 cx,
                             );
-                            smol::block_on(valid_files_to_open_task).len() > 0
-                        } else {
-                            false
+                            this.hover_target_task =
+                                Some(cx.spawn(|terminal_view, mut cx| async move {
+                                    let valid_files_to_open = valid_files_to_open_task.await;
+                                    terminal_view
+                                        .update(&mut cx, |terminal_view, cx| {
+                                            terminal_view.can_navigate_to_selected_word =
+                                                !valid_files_to_open.is_empty();
+                                            cx.notify();
+                                        })
+                                        .ok();
+                                }));
                         }
+                        false
                     }
                     None => false,
                 }
@@ -737,6 +885,7 @@ fn subscribe_for_terminal_events(
             Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
             Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
             Event::SelectionsChanged => cx.emit(SearchEvent::ActiveMatchChanged),
+            Event::TaskFinished => {}
         });
     vec![terminal_subscription, terminal_events_subscription]
 }
@@ -834,13 +983,34 @@ fn regex_to_literal(regex: &str) -> String {
         .collect()
 }
 
-pub fn regex_search_for_query(query: &project::search::SearchQuery) -> Option<RegexSearch> {
-    let query = query.as_str();
-    if query == "." {
-        return None;
+pub fn regex_search_for_query(
+    query: &project::search::SearchQuery,
+) -> anyhow::Result<Option<RegexSearch>> {
+    let pattern = match query {
+        SearchQuery::Text { .. } => regex_to_literal(query.as_str()),
+        SearchQuery::Regex { .. } => query.as_str().to_string(),
+        // Terminal output has no syntax to match against.
+        SearchQuery::Structural { .. } => return Ok(None),
+    };
+    if pattern == "." {
+        return Ok(None);
     }
-    let searcher = RegexSearch::new(&query);
-    searcher.ok()
+    // Alacritty searches with DFAs, which only support ASCII word boundaries.
+    let pattern = if query.whole_word() {
+        format!(r"(?-u:\b)(?:{pattern})(?-u:\b)")
+    } else {
+        pattern
+    };
+    // Without a flag, the search would only be case sensitive for queries with uppercase
+    // letters.
+    let case_flag = if query.case_sensitive() {
+        "(?-i)"
+    } else {
+        "(?i)"
+    };
+    let regex_search = RegexSearch::new(&format!("{case_flag}{pattern}"))
+        .with_context(|| format!("invalid terminal search query {:?}", query.as_str()))?;
+    Ok(Some(regex_search))
 }
 
 impl TerminalView {
@@ -896,6 +1066,13 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_command))
+            .on_action(cx.listener(TerminalView::scroll_to_next_command))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
+            .on_action(cx.listener(TerminalView::open_hovered_link))
+            .on_action(cx.listener(TerminalView::copy_matched_line))
+            .on_action(cx.listener(TerminalView::rename))
+            .on_action(cx.listener(TerminalView::change_tab_color))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_key_down(cx.listener(Self::key_down))
@@ -937,12 +1114,18 @@ impl Item for TerminalView {
     type Event = ItemEvent;
 
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
-        Some(self.terminal().read(cx).title(false).into())
+        match &self.custom_title {
+            Some(custom_title) => Some(custom_title.clone().into()),
+            None => Some(self.terminal().read(cx).title(false).into()),
+        }
     }
 
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
         let terminal = self.terminal().read(cx);
-        let title = terminal.title(true);
+        let title = match &self.custom_title {
+            Some(custom_title) => truncate_and_trailoff(custom_title, 25),
+            None => terminal.title(true),
+        };
         let rerun_button = |task_id: task::TaskId| {
             IconButton::new("rerun-icon", IconName::Rerun)
                 .icon_size(IconSize::Small)
@@ -975,7 +1158,13 @@ impl Item for TerminalView {
                     }
                 }
             },
-            None => (IconName::Terminal, Color::Muted, None),
+            None => {
+                let icon_color = match self.accent_color {
+                    Some(ix) => Color::Custom(cx.theme().accents().color_for_index(ix)),
+                    None => Color::Muted,
+                };
+                (IconName::Terminal, icon_color, None)
+            }
         };
 
         h_flex()
@@ -1091,7 +1280,7 @@ impl SerializableItem for TerminalView {
         &mut self,
         _workspace: &mut Workspace,
         item_id: workspace::ItemId,
-        _closing: bool,
+        closing: bool,
         cx: &mut ViewContext<Self>,
     ) -> Option<Task<gpui::Result<()>>> {
         let terminal = self.terminal().read(cx);
@@ -1099,15 +1288,75 @@ impl SerializableItem for TerminalView {
             return None;
         }
 
-        if let Some((cwd, workspace_id)) = terminal.get_cwd().zip(self.workspace_id) {
-            Some(cx.background_executor().spawn(async move {
-                TERMINAL_DB
-                    .save_working_directory(item_id, workspace_id, cwd)
-                    .await
-            }))
+        let (cwd, workspace_id) = terminal.get_cwd().zip(self.workspace_id)?;
+        let state = SerializedTerminalState {
+            workspace_id,
+            cwd,
+            title: Some(terminal.breadcrumb_text.clone()).filter(|title| !title.is_empty()),
+            custom_title: self.custom_title.clone(),
+            accent_color: self.accent_color,
+        };
+        // The terminal is serialized on every wakeup, but its title and directory rarely
+        // change, so they're only saved when they do.
+        let state = (closing || self.serialized_state.as_ref() != Some(&state)).then(|| {
+            self.serialized_state = Some(state.clone());
+            state
+        });
+        let scrollback_lines = TerminalSettings::get_global(cx).restore_scrollback_lines;
+
+        // Collecting the scrollback is costly, so it's only saved once the output settles,
+        // or right away when the terminal is closed.
+        let scrollback = if closing {
+            self.scrollback_serialization = None;
+            (scrollback_lines > 0).then(|| terminal.scrollback_text(scrollback_lines))
         } else {
+            self.scrollback_serialization = (scrollback_lines > 0).then(|| {
+                cx.spawn(|terminal_view, mut cx| async move {
+                    Timer::after(SCROLLBACK_SERIALIZATION_DEBOUNCE).await;
+                    let Some(scrollback) = terminal_view
+                        .update(&mut cx, |terminal_view, cx| {
+                            terminal_view.scrollback_serialization = None;
+                            terminal_view
+                                .terminal
+                                .read(cx)
+                                .scrollback_text(scrollback_lines)
+                        })
+                        .ok()
+                    else {
+                        return;
+                    };
+                    TERMINAL_DB
+                        .save_scrollback(item_id, workspace_id, Some(scrollback))
+                        .await
+                        .log_err();
+                })
+            });
             None
+        };
+
+        if state.is_none() && scrollback.is_none() {
+            return None;
         }
+        Some(cx.background_executor().spawn(async move {
+            if let Some(state) = state {
+                TERMINAL_DB
+                    .save_terminal(
+                        item_id,
+                        state.workspace_id,
+                        state.cwd,
+                        state.title,
+                        state.custom_title,
+                        state.accent_color,
+                    )
+                    .await?;
+            }
+            if scrollback.is_some() {
+                TERMINAL_DB
+                    .save_scrollback(item_id, workspace_id, scrollback)
+                    .await?;
+            }
+            Ok(())
+        }))
     }
 
     fn should_serialize(&self, event: &Self::Event) -> bool {
@@ -1123,12 +1372,16 @@ impl SerializableItem for TerminalView {
     ) -> Task<anyhow::Result<View<Self>>> {
         let window = cx.window_handle();
         cx.spawn(|pane, mut cx| async move {
+            let (from_db, title, scrollback, custom_title, accent_color) = TERMINAL_DB
+                .get_terminal(item_id, workspace_id)
+                .log_err()
+                .flatten()
+                .map(|(cwd, title, scrollback, custom_title, accent_color)| {
+                    (Some(cwd), title, scrollback, custom_title, accent_color)
+                })
+                .unwrap_or_default();
             let cwd = cx
                 .update(|cx| {
-                    let from_db = TERMINAL_DB
-                        .get_working_directory(item_id, workspace_id)
-                        .log_err()
-                        .flatten();
                     if from_db
                         .as_ref()
                         .is_some_and(|from_db| !from_db.as_os_str().is_empty())
@@ -1146,8 +1399,22 @@ impl SerializableItem for TerminalView {
             let terminal = project.update(&mut cx, |project, cx| {
                 project.create_terminal(TerminalKind::Shell(cwd), window, cx)
             })??;
+            terminal.update(&mut cx, |terminal, _| {
+                if let Some(scrollback) = scrollback {
+                    terminal.restore_scrollback(&scrollback);
+                }
+                if let Some(title) = title {
+                    terminal.breadcrumb_text = title;
+                }
+            })?;
             pane.update(&mut cx, |_, cx| {
-                cx.new_view(|cx| TerminalView::new(terminal, workspace, Some(workspace_id), cx))
+                cx.new_view(|cx| {
+                    let mut terminal_view =
+                        TerminalView::new(terminal, workspace, Some(workspace_id), cx);
+                    terminal_view.custom_title = custom_title;
+                    terminal_view.accent_color = accent_color;
+                    terminal_view
+                })
             })
         })
     }
@@ -1158,8 +1425,8 @@ impl SearchableItem for TerminalView {
 
     fn supported_options() -> SearchOptions {
         SearchOptions {
-            case: false,
-            word: false,
+            case: true,
+            word: true,
             regex: true,
             replacement: false,
             selection: false,
@@ -1207,22 +1474,7 @@ impl SearchableItem for TerminalView {
         query: Arc<SearchQuery>,
         cx: &mut ViewContext<Self>,
     ) -> Task<Vec<Self::Match>> {
-        let searcher = match &*query {
-            SearchQuery::Text { .. } => regex_search_for_query(
-                &(SearchQuery::text(
-                    regex_to_literal(&query.as_str()),
-                    query.whole_word(),
-                    query.case_sensitive(),
-                    query.include_ignored(),
-                    query.files_to_include().clone(),
-                    query.files_to_exclude().clone(),
-                )
-                .unwrap()),
-            ),
-            SearchQuery::Regex { .. } => regex_search_for_query(&query),
-        };
-
-        if let Some(s) = searcher {
+        if let Some(s) = regex_search_for_query(&query).log_err().flatten() {
             self.terminal()
                 .update(cx, |term, cx| term.find_matches(s, cx))
         } else {
@@ -1303,6 +1555,12 @@ mod tests {
     use gpui::TestAppContext;
     use project::{Entry, Project, ProjectPath, Worktree};
     use std::path::Path;
+    use terminal::alacritty_terminal::{
+        index::{Column, Direction},
+        term::{search::RegexIter, test::TermSize, Config},
+        vte::ansi::Handler,
+        Term,
+    };
     use workspace::AppState;
 
     // Working directory calculation tests
@@ -1495,4 +1753,70 @@ mod tests {
     fn empty_string_stays_empty() {
         assert_eq!(regex_to_literal(""), "".to_string());
     }
+
+    #[test]
+    fn test_regex_search_for_query() {
+        fn search(query: SearchQuery, content: &str) -> Vec<String> {
+            let mut term = Term::new(Config::default(), &TermSize::new(40, 4), ());
+            for c in content.chars() {
+                term.input(c);
+            }
+            let mut regex_search = regex_search_for_query(&query).unwrap().unwrap();
+            let start = Point::new(term.topmost_line(), Column(0));
+            let end = Point::new(term.bottommost_line(), term.last_column());
+            RegexIter::new(start, end, Direction::Right, &term, &mut regex_search)
+                .map(|search_match| {
+                    term.bounds_to_string(*search_match.start(), *search_match.end())
+                })
+                .collect()
+        }
+        let text = |query: &str, whole_word: bool, case_sensitive: bool| {
+            SearchQuery::text(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            search(text("foo", false, false), "Foo food (föo) foo"),
+            vec!["Foo", "foo", "foo"]
+        );
+        assert_eq!(
+            search(text("foo", false, true), "Foo food (föo) foo"),
+            vec!["foo", "foo"]
+        );
+        // Whole words are delimited by ASCII word boundaries, also next to other characters.
+        assert_eq!(
+            search(text("foo", true, false), "Foo food (föo) foo"),
+            vec!["Foo", "foo"]
+        );
+        assert_eq!(
+            search(text("föo", true, false), "Foo food (föo) foo"),
+            vec!["föo"]
+        );
+        assert_eq!(
+            search(text("a.b", false, false), "a.b axb"),
+            vec!["a.b"],
+            "Text queries should match literally"
+        );
+
+        let regex = SearchQuery::regex(
+            "fo+d?",
+            true,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            search(regex, "Foo food (föo) foo"),
+            vec!["Foo", "food", "foo"]
+        );
+    }
 }
@@ -0,0 +1,177 @@
+use anyhow::{anyhow, Result};
+use std::borrow::Cow;
+
+/// The number of bytes at the start of a file that are inspected to detect UTF-16
+/// without a byte order mark.
+const DETECTION_SAMPLE_LEN: usize = 8 * 1024;
+
+/// A character encoding that files can be read and written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1, decoded as its Windows-1252 superset like web browsers do.
+    Latin1,
+    ShiftJis,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 5] = [
+        Encoding::Utf8,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Latin1,
+        Encoding::ShiftJis,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16 LE",
+            Encoding::Utf16Be => "UTF-16 BE",
+            Encoding::Latin1 => "Latin-1",
+            Encoding::ShiftJis => "Shift-JIS",
+        }
+    }
+
+    /// Guesses the encoding of the contents of a file, returning `None` when they don't
+    /// look like text in any of the supported encodings.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xFE]) {
+            return Some(Encoding::Utf16Le);
+        }
+        if bytes.starts_with(&[0xFE, 0xFF]) {
+            return Some(Encoding::Utf16Be);
+        }
+
+        let sample = &bytes[..bytes.len().min(DETECTION_SAMPLE_LEN)];
+        if let Some(encoding) = detect_utf16_without_bom(sample) {
+            return Some(encoding);
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Some(Encoding::Utf8);
+        }
+        if sample.contains(&0) {
+            return None;
+        }
+        if looks_like_shift_jis(bytes) {
+            Some(Encoding::ShiftJis)
+        } else if looks_like_latin1(bytes) {
+            Some(Encoding::Latin1)
+        } else {
+            None
+        }
+    }
+
+    /// Decodes the bytes into text, failing when they contain sequences that are invalid
+    /// in this encoding.
+    pub fn decode(&self, bytes: &[u8]) -> Result<String> {
+        let text = match self {
+            Encoding::Utf8 => String::from_utf8(bytes.to_vec()).ok(),
+            _ => {
+                let encoding = self.to_encoding_rs();
+                let bytes = match encoding_rs::Encoding::for_bom(bytes) {
+                    Some((bom_encoding, bom_len)) if bom_encoding == encoding => &bytes[bom_len..],
+                    _ => bytes,
+                };
+                encoding
+                    .decode_without_bom_handling_and_without_replacement(bytes)
+                    .map(|text| text.into_owned())
+            }
+        };
+        text.ok_or_else(|| anyhow!("the file contains bytes that aren't valid {}", self.name()))
+    }
+
+    /// Encodes the text, failing when it contains characters that can't be represented
+    /// in this encoding. UTF-16 is always written with a byte order mark.
+    pub fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        match self {
+            Encoding::Utf8 => Ok(Cow::Borrowed(text.as_bytes())),
+            Encoding::Utf16Le => {
+                let mut bytes = vec![0xFF, 0xFE];
+                bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+                Ok(Cow::Owned(bytes))
+            }
+            Encoding::Utf16Be => {
+                let mut bytes = vec![0xFE, 0xFF];
+                bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+                Ok(Cow::Owned(bytes))
+            }
+            Encoding::Latin1 | Encoding::ShiftJis => {
+                let (bytes, _, has_unmappable_characters) = self.to_encoding_rs().encode(text);
+                if has_unmappable_characters {
+                    Err(anyhow!(
+                        "the text contains characters that can't be encoded in {}",
+                        self.name()
+                    ))
+                } else {
+                    Ok(bytes)
+                }
+            }
+        }
+    }
+
+    fn to_encoding_rs(self) -> &'static encoding_rs::Encoding {
+        match self {
+            Encoding::Utf8 => encoding_rs::UTF_8,
+            Encoding::Utf16Le => encoding_rs::UTF_16LE,
+            Encoding::Utf16Be => encoding_rs::UTF_16BE,
+            Encoding::Latin1 => encoding_rs::WINDOWS_1252,
+            Encoding::ShiftJis => encoding_rs::SHIFT_JIS,
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Text that is mostly ASCII has a zero in the high byte of nearly every UTF-16 code unit.
+fn detect_utf16_without_bom(sample: &[u8]) -> Option<Encoding> {
+    let code_units = sample.len() / 2;
+    if code_units < 2 {
+        return None;
+    }
+
+    let mut zeros_in_low_bytes = 0;
+    let mut zeros_in_high_bytes = 0;
+    for code_unit in sample.chunks_exact(2) {
+        if code_unit[0] == 0 {
+            zeros_in_low_bytes += 1;
+        }
+        if code_unit[1] == 0 {
+            zeros_in_high_bytes += 1;
+        }
+    }
+
+    if zeros_in_high_bytes * 10 >= code_units * 9 && zeros_in_low_bytes * 10 < code_units {
+        Some(Encoding::Utf16Le)
+    } else if zeros_in_low_bytes * 10 >= code_units * 9 && zeros_in_high_bytes * 10 < code_units {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Windows-1252 maps nearly every byte to a character, so binary data only stands out by
+/// decoding into control characters.
+fn looks_like_latin1(bytes: &[u8]) -> bool {
+    let (text, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+    !text
+        .chars()
+        .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x0C'))
+}
+
+/// Latin-1 text often decodes as valid Shift-JIS too, but practically never into kana or
+/// Japanese punctuation, which appear in nearly all Japanese text.
+fn looks_like_shift_jis(bytes: &[u8]) -> bool {
+    let (text, has_errors) = encoding_rs::SHIFT_JIS.decode_without_bom_handling(bytes);
+    !has_errors
+        && text
+            .chars()
+            .any(|c| matches!(c, '\u{3001}'..='\u{303F}' | '\u{3041}'..='\u{30FF}'))
+}
@@ -176,10 +176,18 @@ fn test_line_endings() {
         LineEnding::detect(&"abcd\r\n".repeat(1000)),
         LineEnding::Windows
     );
+    assert_eq!(LineEnding::detect("a\nb\r\nc\r\n"), LineEnding::Windows);
+    assert_eq!(LineEnding::detect("a\r\nb\nc\n"), LineEnding::Unix);
+    assert_eq!(LineEnding::detect("a\r\nb\nc"), LineEnding::Windows);
+    assert!(!LineEnding::is_mixed("a\r\nb\r\nc"));
+    assert!(!LineEnding::is_mixed("a\nb\nc"));
+    assert!(LineEnding::is_mixed("a\r\nb\nc"));
+    assert!(LineEnding::is_mixed("a\nb\rc"));
 
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "one\r\ntwo\rthree".into());
     assert_eq!(buffer.text(), "one\ntwo\nthree");
     assert_eq!(buffer.line_ending(), LineEnding::Windows);
+    assert!(buffer.has_mixed_line_endings());
     buffer.check_invariants();
 
     buffer.edit([(buffer.len()..buffer.len(), "\r\nfour")]);
@@ -189,6 +197,36 @@ fn test_line_endings() {
     buffer.check_invariants();
 }
 
+#[test]
+fn test_encodings() {
+    let shift_jis = [0x82, 0xB1, 0x82, 0xF1, 0x82, 0xC9, 0x82, 0xBF, 0x82, 0xCD];
+    assert_eq!(Encoding::detect(&shift_jis), Some(Encoding::ShiftJis));
+    assert_eq!(Encoding::ShiftJis.decode(&shift_jis).unwrap(), "こんにちは");
+    assert!(Encoding::ShiftJis.decode(&shift_jis[..3]).is_err());
+    assert_eq!(
+        Encoding::ShiftJis.encode("こんにちは").unwrap().as_ref(),
+        &shift_jis
+    );
+
+    let latin1 = b"Gr\xFC\xDFe";
+    assert_eq!(Encoding::detect(latin1), Some(Encoding::Latin1));
+    assert_eq!(Encoding::Latin1.decode(latin1).unwrap(), "Grüße");
+    assert!(Encoding::Utf8.decode(latin1).is_err());
+    assert_eq!(Encoding::Latin1.encode("Grüße").unwrap().as_ref(), latin1);
+    assert!(Encoding::Latin1.encode("こんにちは").is_err());
+
+    let utf16 = Encoding::Utf16Le.encode("abc\n").unwrap().into_owned();
+    assert_eq!(utf16, b"\xFF\xFEa\0b\0c\0\n\0");
+    assert_eq!(Encoding::detect(&utf16), Some(Encoding::Utf16Le));
+    assert_eq!(Encoding::Utf16Le.decode(&utf16).unwrap(), "abc\n");
+    assert_eq!(Encoding::detect(b"\0a\0b\0c"), Some(Encoding::Utf16Be));
+
+    assert_eq!(Encoding::detect("🍐✅\n".as_bytes()), Some(Encoding::Utf8));
+    assert_eq!(Encoding::detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), None);
+    // Bytes that aren't UTF-8 are only decoded as Latin-1 when they look like text.
+    assert_eq!(Encoding::detect(b"\x89PNG\r\n\x1a\n\x01\x02"), None);
+}
+
 #[test]
 fn test_line_len() {
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
@@ -1,4 +1,5 @@
 mod anchor;
+mod encoding;
 pub mod locator;
 #[cfg(any(test, feature = "test-support"))]
 pub mod network;
@@ -14,6 +15,7 @@ pub use anchor::*;
 use anyhow::{anyhow, Context as _, Result};
 pub use clock::ReplicaId;
 use collections::{HashMap, HashSet};
+pub use encoding::Encoding;
 use locator::Locator;
 use operation_queue::OperationQueue;
 pub use patch::Patch;
@@ -108,6 +110,7 @@ pub struct BufferSnapshot {
     visible_text: Rope,
     deleted_text: Rope,
     line_ending: LineEnding,
+    has_mixed_line_endings: bool,
     undo_map: UndoMap,
     fragments: SumTree<Fragment>,
     insertions: SumTree<InsertionFragment>,
@@ -122,7 +125,7 @@ pub struct HistoryEntry {
     suppress_grouping: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Transaction {
     pub id: TransactionId,
     pub edit_ids: Vec<clock::Lamport>,
@@ -643,8 +646,12 @@ impl FromIterator<char> for LineIndent {
 impl Buffer {
     pub fn new(replica_id: u16, remote_id: BufferId, mut base_text: String) -> Buffer {
         let line_ending = LineEnding::detect(&base_text);
+        let has_mixed_line_endings = LineEnding::is_mixed(&base_text);
         LineEnding::normalize(&mut base_text);
-        Self::new_normalized(replica_id, remote_id, line_ending, Rope::from(base_text))
+        let mut buffer =
+            Self::new_normalized(replica_id, remote_id, line_ending, Rope::from(base_text));
+        buffer.snapshot.has_mixed_line_endings = has_mixed_line_endings;
+        buffer
     }
 
     pub fn new_normalized(
@@ -689,6 +696,7 @@ impl Buffer {
                 visible_text,
                 deleted_text: Rope::new(),
                 line_ending,
+                has_mixed_line_endings: false,
                 fragments,
                 insertions,
                 version,
@@ -923,6 +931,12 @@ impl Buffer {
         self.snapshot.line_ending = line_ending;
     }
 
+    /// Records whether the text this buffer was loaded from used more than one kind of
+    /// line ending. All of its lines are written with [`Self::line_ending`] when saved.
+    pub fn set_has_mixed_line_endings(&mut self, has_mixed_line_endings: bool) {
+        self.snapshot.has_mixed_line_endings = has_mixed_line_endings;
+    }
+
     pub fn apply_ops<I: IntoIterator<Item = Operation>>(&mut self, ops: I) -> Result<()> {
         let mut deferred_ops = Vec::new();
         for op in ops {
@@ -1903,6 +1917,10 @@ impl BufferSnapshot {
         self.line_ending
     }
 
+    pub fn has_mixed_line_endings(&self) -> bool {
+        self.has_mixed_line_endings
+    }
+
     pub fn deleted_text(&self) -> String {
         self.deleted_text.to_string()
     }
@@ -2993,6 +3011,41 @@ pub enum LineEnding {
     Windows,
 }
 
+struct LineEndingCounts {
+    unix: usize,
+    windows: usize,
+    /// Carriage returns that aren't followed by a newline.
+    carriage_returns: usize,
+    first: Option<LineEnding>,
+}
+
+impl LineEndingCounts {
+    fn new(text: &str) -> Self {
+        let mut counts = Self {
+            unix: 0,
+            windows: 0,
+            carriage_returns: 0,
+            first: None,
+        };
+        let bytes = text.as_bytes();
+        for (ix, byte) in bytes.iter().enumerate() {
+            match byte {
+                b'\n' if ix > 0 && bytes[ix - 1] == b'\r' => {
+                    counts.windows += 1;
+                    counts.first.get_or_insert(LineEnding::Windows);
+                }
+                b'\n' => {
+                    counts.unix += 1;
+                    counts.first.get_or_insert(LineEnding::Unix);
+                }
+                b'\r' if bytes.get(ix + 1) != Some(&b'\n') => counts.carriage_returns += 1,
+                _ => {}
+            }
+        }
+        counts
+    }
+}
+
 impl Default for LineEnding {
     fn default() -> Self {
         #[cfg(unix)]
@@ -3011,23 +3064,36 @@ impl LineEnding {
         }
     }
 
-    pub fn detect(text: &str) -> Self {
-        let mut max_ix = cmp::min(text.len(), 1000);
-        while !text.is_char_boundary(max_ix) {
-            max_ix -= 1;
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineEnding::Unix => "LF",
+            LineEnding::Windows => "CRLF",
         }
+    }
 
-        if let Some(ix) = text[..max_ix].find(['\n']) {
-            if ix > 0 && text.as_bytes()[ix - 1] == b'\r' {
-                Self::Windows
-            } else {
-                Self::Unix
-            }
+    /// Detects the line ending used by most lines of the text, preferring the one
+    /// that appears first when both are used equally often.
+    pub fn detect(text: &str) -> Self {
+        let counts = LineEndingCounts::new(text);
+        if counts.windows > counts.unix {
+            Self::Windows
+        } else if counts.unix > counts.windows {
+            Self::Unix
         } else {
-            Self::default()
+            counts.first.unwrap_or_default()
         }
     }
 
+    /// Returns whether the text separates its lines in more than one way.
+    pub fn is_mixed(text: &str) -> bool {
+        let counts = LineEndingCounts::new(text);
+        [counts.unix, counts.windows, counts.carriage_returns]
+            .into_iter()
+            .filter(|count| *count > 0)
+            .count()
+            > 1
+    }
+
     pub fn normalize(text: &mut String) {
         if let Cow::Owned(replaced) = LINE_SEPARATORS_REGEX.replace_all(text, "\n") {
             *text = replaced;
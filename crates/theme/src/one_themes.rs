@@ -102,6 +102,7 @@ pub(crate) fn one_dark() -> Theme {
                     0.2,
                 ),
                 editor_document_highlight_write_background: gpui::red(),
+                editor_inactive_code_background: gpui::transparent_black(),
 
                 terminal_background: bg,
                 // todo("Use one colors for terminal")
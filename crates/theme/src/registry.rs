@@ -1,5 +1,8 @@
 use std::sync::Arc;
-use std::{fmt::Debug, path::Path};
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
@@ -36,6 +39,21 @@ impl Global for GlobalThemeRegistry {}
 
 struct ThemeRegistryState {
     themes: HashMap<SharedString, Arc<Theme>>,
+    /// The themes loaded from each user theme file, in the order the files were loaded.
+    user_themes: Vec<(PathBuf, Vec<Arc<Theme>>)>,
+    /// The themes that aren't from a user theme file, such as the bundled themes, which were
+    /// replaced by a user theme with the same name.
+    replaced_themes: HashMap<SharedString, Arc<Theme>>,
+}
+
+impl ThemeRegistryState {
+    fn user_theme_owns(&self, theme: &Arc<Theme>) -> bool {
+        self.user_themes.iter().any(|(_, themes)| {
+            themes
+                .iter()
+                .any(|user_theme| Arc::ptr_eq(user_theme, theme))
+        })
+    }
 }
 
 pub struct ThemeRegistry {
@@ -65,6 +83,8 @@ impl ThemeRegistry {
         let registry = Self {
             state: RwLock::new(ThemeRegistryState {
                 themes: HashMap::default(),
+                user_themes: Vec::new(),
+                replaced_themes: HashMap::default(),
             }),
             assets,
         };
@@ -283,13 +303,80 @@ impl ThemeRegistry {
     }
 
     /// Loads the user theme from the specified path and adds it to the registry.
+    ///
+    /// The themes previously loaded from the same path are replaced, so that the
+    /// themes renamed or removed from the file are removed from the registry.
     pub async fn load_user_theme(&self, theme_path: &Path, fs: Arc<dyn Fs>) -> Result<()> {
         let theme = Self::read_user_theme(theme_path, fs).await?;
-
+        let theme_names = theme
+            .themes
+            .iter()
+            .map(|theme| SharedString::from(theme.name.clone()))
+            .collect::<Vec<_>>();
+
+        self.remove_user_theme(theme_path);
+        {
+            let mut state = self.state.write();
+            for name in &theme_names {
+                if let Some(theme) = state.themes.get(name).cloned() {
+                    if !state.user_theme_owns(&theme) {
+                        state.replaced_themes.insert(name.clone(), theme);
+                    }
+                }
+            }
+        }
         self.insert_user_theme_families([theme]);
+        let mut state = self.state.write();
+        let themes = theme_names
+            .iter()
+            .filter_map(|name| state.themes.get(name).cloned())
+            .collect();
+        state.user_themes.push((theme_path.to_path_buf(), themes));
 
         Ok(())
     }
+
+    /// Removes the themes loaded from the user theme at the specified path. A theme with the
+    /// same name from another user theme file, or the theme it replaced, takes its place.
+    ///
+    /// Returns whether any themes were removed.
+    pub fn remove_user_theme(&self, theme_path: &Path) -> bool {
+        let mut state = self.state.write();
+        let Some(ix) = state
+            .user_themes
+            .iter()
+            .position(|(path, _)| path == theme_path)
+        else {
+            return false;
+        };
+        let (_, themes) = state.user_themes.remove(ix);
+        for theme in themes {
+            // A theme with the same name loaded later has already taken its place.
+            if !state
+                .themes
+                .get(&theme.name)
+                .map_or(false, |current| Arc::ptr_eq(current, &theme))
+            {
+                continue;
+            }
+            let previous_theme = state
+                .user_themes
+                .iter()
+                .rev()
+                .find_map(|(_, themes)| themes.iter().find(|other| other.name == theme.name))
+                .or_else(|| state.replaced_themes.get(&theme.name))
+                .cloned();
+            match previous_theme {
+                Some(previous_theme) => {
+                    state.themes.insert(theme.name.clone(), previous_theme);
+                }
+                None => {
+                    state.themes.remove(&theme.name);
+                }
+            }
+        }
+        true
+    }
 }
 
 impl Default for ThemeRegistry {
@@ -297,3 +384,85 @@ impl Default for ThemeRegistry {
         Self::new(Box::new(()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_reload_user_theme(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let theme_family = |theme_names: &[&str]| {
+            json!({
+                "name": "My Themes",
+                "author": "Me",
+                "themes": theme_names
+                    .iter()
+                    .map(|name| json!({ "name": name, "appearance": "dark", "style": {} }))
+                    .collect::<Vec<_>>(),
+            })
+            .to_string()
+        };
+        let theme_path = Path::new("/themes/my-themes.json");
+        fs.insert_tree(
+            "/themes",
+            json!({ "my-themes.json": theme_family(&["Foo", "Bar"]) }),
+        )
+        .await;
+
+        let registry = ThemeRegistry::new(Box::new(()));
+        registry
+            .load_user_themes(Path::new("/themes"), fs.clone())
+            .await
+            .unwrap();
+        assert!(registry.get("Foo").is_ok());
+        assert!(registry.get("Bar").is_ok());
+
+        // Reloading the file drops the themes that are no longer in it.
+        fs.insert_file(theme_path, theme_family(&["Foo", "Baz"]).into_bytes())
+            .await;
+        registry
+            .load_user_theme(theme_path, fs.clone())
+            .await
+            .unwrap();
+        assert!(registry.get("Foo").is_ok());
+        assert!(registry.get("Bar").is_err());
+        assert!(registry.get("Baz").is_ok());
+
+        // Themes with the same name in another file take the place of the removed ones, and
+        // the bundled themes take the place of the user themes replacing them.
+        let other_theme_path = Path::new("/themes/other-themes.json");
+        fs.insert_file(
+            other_theme_path,
+            theme_family(&["Baz", "One Dark"]).into_bytes(),
+        )
+        .await;
+        let bundled_one_dark = registry.get("One Dark").unwrap();
+        registry
+            .load_user_theme(other_theme_path, fs.clone())
+            .await
+            .unwrap();
+        let other_baz = registry.get("Baz").unwrap();
+        registry
+            .load_user_theme(theme_path, fs.clone())
+            .await
+            .unwrap();
+        assert!(!Arc::ptr_eq(&registry.get("Baz").unwrap(), &other_baz));
+
+        assert!(registry.remove_user_theme(theme_path));
+        assert!(registry.get("Foo").is_err());
+        assert!(Arc::ptr_eq(&registry.get("Baz").unwrap(), &other_baz));
+        assert!(!registry.remove_user_theme(theme_path));
+
+        assert!(registry.remove_user_theme(other_theme_path));
+        assert!(registry.get("Baz").is_err());
+        assert!(Arc::ptr_eq(
+            &registry.get("One Dark").unwrap(),
+            &bundled_one_dark
+        ));
+    }
+}
@@ -417,6 +417,11 @@ pub struct ThemeColorsContent {
     #[serde(rename = "editor.document_highlight.write_background")]
     pub editor_document_highlight_write_background: Option<String>,
 
+    /// Background of the code that language servers report as inactive, like code excluded
+    /// by the preprocessor. The code's text is faded as well.
+    #[serde(rename = "editor.inactive_code.background")]
+    pub editor_inactive_code_background: Option<String>,
+
     /// Terminal background color.
     #[serde(rename = "terminal.background")]
     pub terminal_background: Option<String>,
@@ -792,6 +797,10 @@ impl ThemeColorsContent {
                 .editor_document_highlight_write_background
                 .as_ref()
                 .and_then(|color| try_parse_color(color).ok()),
+            editor_inactive_code_background: self
+                .editor_inactive_code_background
+                .as_ref()
+                .and_then(|color| try_parse_color(color).ok()),
             terminal_background: self
                 .terminal_background
                 .as_ref()
@@ -126,6 +126,7 @@ impl ThemeSettings {
 
             if let Some(_theme) = theme_settings.switch_theme(theme_name, cx) {
                 ThemeSettings::override_global(theme_settings, cx);
+                cx.refresh();
             }
         }
     }
@@ -288,11 +289,17 @@ pub struct ThemeSettingsContent {
     #[serde(default)]
     pub unnecessary_code_fade: Option<f32>,
 
-    /// EXPERIMENTAL: Overrides for the current theme.
+    /// Overrides for the current theme's colors and syntax highlighting.
     ///
     /// These values will override the ones on the current theme specified in `theme`.
-    #[serde(rename = "experimental.theme_overrides", default)]
+    #[serde(default)]
     pub theme_overrides: Option<ThemeStyleContent>,
+
+    /// Overrides for the current theme, under the name used by earlier versions of Zed.
+    ///
+    /// `theme_overrides` is used instead when both are set.
+    #[serde(rename = "experimental.theme_overrides", default)]
+    pub experimental_theme_overrides: Option<ThemeStyleContent>,
 }
 
 fn default_font_features() -> Option<FontFeatures> {
@@ -558,7 +565,12 @@ impl settings::Settings for ThemeSettings {
             unnecessary_code_fade: defaults.unnecessary_code_fade.unwrap_or(0.0),
         };
 
-        for value in sources.user.into_iter().chain(sources.release_channel) {
+        for value in sources
+            .user
+            .into_iter()
+            .chain(sources.release_channel)
+            .chain(sources.profile)
+        {
             if let Some(value) = value.ui_density {
                 this.ui_density = value;
             }
@@ -599,8 +611,13 @@ impl settings::Settings for ThemeSettings {
                 }
             }
 
-            this.theme_overrides.clone_from(&value.theme_overrides);
-            this.apply_theme_overrides();
+            if let Some(value) = value
+                .theme_overrides
+                .as_ref()
+                .or(value.experimental_theme_overrides.as_ref())
+            {
+                this.theme_overrides = Some(value.clone());
+            }
 
             merge(&mut this.ui_font_size, value.ui_font_size.map(Into::into));
             merge(
@@ -613,6 +630,7 @@ impl settings::Settings for ThemeSettings {
             merge(&mut this.unnecessary_code_fade, value.unnecessary_code_fade);
             this.unnecessary_code_fade = this.unnecessary_code_fade.clamp(0.0, 0.9);
         }
+        this.apply_theme_overrides();
 
         Ok(this)
     }
@@ -660,3 +678,30 @@ fn merge<T: Copy>(target: &mut T, value: Option<T>) {
         *target = value;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_theme_overrides_with_previous_name() {
+        let content: ThemeSettingsContent = serde_json::from_value(json!({
+            "theme_overrides": { "editor.background": "#111111" },
+            "experimental.theme_overrides": { "editor.background": "#222222" },
+        }))
+        .unwrap();
+        assert_eq!(
+            content.theme_overrides.unwrap().colors.editor_background,
+            Some("#111111".to_string())
+        );
+        assert_eq!(
+            content
+                .experimental_theme_overrides
+                .unwrap()
+                .colors
+                .editor_background,
+            Some("#222222".to_string())
+        );
+    }
+}
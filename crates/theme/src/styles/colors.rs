@@ -171,6 +171,9 @@ pub struct ThemeColors {
     /// special attention. Usually a document highlight is visualized by changing
     /// the background color of its range.
     pub editor_document_highlight_write_background: Hsla,
+    /// Background of the code that language servers report as inactive, like code excluded
+    /// by the preprocessor. The code's text is faded as well.
+    pub editor_inactive_code_background: Hsla,
 
     // ===
     // Terminal
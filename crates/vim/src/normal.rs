@@ -57,6 +57,7 @@ actions!(
         ConvertToUpperCase,
         ConvertToLowerCase,
         JoinLines,
+        JoinLinesNoWhitespace,
         Indent,
         Outdent,
         ToggleComments,
@@ -114,25 +115,11 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
         );
     });
     Vim::action(editor, cx, |vim, _: &JoinLines, cx| {
-        vim.record_current_action(cx);
-        let mut times = vim.take_count(cx).unwrap_or(1);
-        if vim.mode.is_visual() {
-            times = 1;
-        } else if times > 1 {
-            // 2J joins two lines together (same as J or 1J)
-            times -= 1;
-        }
+        vim.join_lines_impl(true, cx);
+    });
 
-        vim.update_editor(cx, |_, editor, cx| {
-            editor.transact(cx, |editor, cx| {
-                for _ in 0..times {
-                    editor.join_lines(&Default::default(), cx)
-                }
-            })
-        });
-        if vim.mode.is_visual() {
-            vim.switch_mode(Mode::Normal, false, cx)
-        }
+    Vim::action(editor, cx, |vim, _: &JoinLinesNoWhitespace, cx| {
+        vim.join_lines_impl(false, cx);
     });
 
     Vim::action(editor, cx, |vim, _: &Indent, cx| {
@@ -440,6 +427,28 @@ impl Vim {
         )
     }
 
+    fn join_lines_impl(&mut self, insert_whitespace: bool, cx: &mut ViewContext<Self>) {
+        self.record_current_action(cx);
+        let mut times = self.take_count(cx).unwrap_or(1);
+        if self.mode.is_visual() {
+            times = 1;
+        } else if times > 1 {
+            // 2J joins two lines together (same as J or 1J)
+            times -= 1;
+        }
+
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                for _ in 0..times {
+                    editor.join_lines_impl(insert_whitespace, cx)
+                }
+            })
+        });
+        if self.mode.is_visual() {
+            self.switch_mode(Mode::Normal, false, cx)
+        }
+    }
+
     fn toggle_comments(&mut self, _: &ToggleComments, cx: &mut ViewContext<Self>) {
         self.record_current_action(cx);
         self.update_editor(cx, |_, editor, cx| {
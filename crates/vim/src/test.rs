@@ -350,6 +350,31 @@ async fn test_join_lines(cx: &mut gpui::TestAppContext) {
       "});
 }
 
+#[gpui::test]
+async fn test_join_lines_without_whitespace(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state(
+        indoc! {"
+        ˇone
+          two
+        three
+        four
+        "},
+        Mode::Normal,
+    );
+    cx.simulate_keystrokes("g shift-j");
+    cx.assert_editor_state(indoc! {"
+        oneˇ  two
+        three
+        four
+        "});
+    cx.simulate_keystrokes("3 g shift-j");
+    cx.assert_editor_state(indoc! {"
+        one  twothreeˇfour
+        "});
+}
+
 #[cfg(target_os = "macos")]
 #[gpui::test]
 async fn test_wrapped_lines(cx: &mut gpui::TestAppContext) {
@@ -93,7 +93,10 @@ impl Settings for BaseKeymap {
         sources: SettingsSources<Self::FileContent>,
         _: &mut gpui::AppContext,
     ) -> anyhow::Result<Self> {
-        if let Some(Some(user_value)) = sources.user.copied() {
+        if let Some(user_value) = [sources.profile, sources.user]
+            .into_iter()
+            .find_map(|value| value.copied().flatten())
+        {
             return Ok(user_value);
         }
         sources.default.ok_or_else(Self::missing_default)
@@ -1,19 +1,22 @@
 use crate::persistence::model::DockData;
 use crate::{status_bar::StatusItemView, Workspace};
 use crate::{DraggedDock, Event, Pane};
+use anyhow::Result;
 use client::proto;
+use db::kvp::KEY_VALUE_STORE;
 use gpui::{
-    deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, Entity, EntityId,
-    EventEmitter, FocusHandle, FocusableView, IntoElement, KeyContext, MouseButton, MouseDownEvent,
-    MouseUpEvent, ParentElement, Render, SharedString, StyleRefinement, Styled, Subscription, View,
-    ViewContext, VisualContext, WeakView, WindowContext,
+    deferred, div, px, Action, AnchorCorner, AnyView, AppContext, AsyncWindowContext, Axis, Entity,
+    EntityId, EventEmitter, FocusHandle, FocusableView, IntoElement, KeyContext, MouseButton,
+    MouseDownEvent, MouseUpEvent, ParentElement, Render, SharedString, StyleRefinement, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use settings::SettingsStore;
 use std::sync::Arc;
 use ui::{h_flex, ContextMenu, IconButton, Tooltip};
 use ui::{prelude::*, right_click_menu};
+use util::ResultExt;
 
 const RESIZE_HANDLE_SIZE: Pixels = Pixels(6.);
 
@@ -55,6 +58,33 @@ pub trait Panel: FocusableView + EventEmitter<PanelEvent> {
     }
 }
 
+/// Reads the state that a panel persisted under the given key with [write_panel_state].
+pub async fn read_panel_state<S: DeserializeOwned>(
+    key: &'static str,
+    cx: &AsyncWindowContext,
+) -> Option<S> {
+    cx.background_executor()
+        .spawn(async move { KEY_VALUE_STORE.read_kvp(key) })
+        .await
+        .log_err()
+        .flatten()
+        .and_then(|state| serde_json::from_str(&state).log_err())
+}
+
+/// Persists a panel's state under the given key, to be read back with [read_panel_state]
+/// when the panel is loaded again.
+pub fn write_panel_state<S: Serialize>(
+    key: &'static str,
+    state: &S,
+    cx: &AppContext,
+) -> Task<Result<()>> {
+    let state = serde_json::to_string(state);
+    cx.background_executor().spawn(async move {
+        KEY_VALUE_STORE.write_kvp(key.into(), state?).await?;
+        anyhow::Ok(())
+    })
+}
+
 pub trait PanelHandle: Send + Sync {
     fn panel_id(&self) -> EntityId;
     fn persistent_name(&self) -> &'static str;
@@ -583,9 +613,10 @@ impl Dock {
         }
     }
 
-    fn dispatch_context() -> KeyContext {
+    fn dispatch_context(&self) -> KeyContext {
         let mut dispatch_context = KeyContext::new_with_defaults();
         dispatch_context.add("Dock");
+        dispatch_context.set("dock", self.position.to_label());
 
         dispatch_context
     }
@@ -593,7 +624,7 @@ impl Dock {
 
 impl Render for Dock {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let dispatch_context = Self::dispatch_context();
+        let dispatch_context = self.dispatch_context();
         if let Some(entry) = self.visible_entry() {
             let size = entry.panel.size(cx);
 
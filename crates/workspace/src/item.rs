@@ -41,6 +41,8 @@ pub struct ItemSettings {
     pub git_status: bool,
     pub close_position: ClosePosition,
     pub file_icons: bool,
+    pub pinned_tabs_icon_only: bool,
+    pub title: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -81,6 +83,15 @@ pub struct ItemSettingsContent {
     ///
     /// Default: true
     file_icons: Option<bool>,
+    /// Whether pinned tabs only show their icon, for items that have one.
+    ///
+    /// Default: false
+    pinned_tabs_icon_only: Option<bool>,
+    /// A template for the titles of editor tabs, or null to show the file's name.
+    /// Supports the same variables as the window title.
+    ///
+    /// Default: null
+    title: Option<String>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -189,6 +200,15 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn navigate(&mut self, _: Box<dyn Any>, _: &mut ViewContext<Self>) -> bool {
         false
     }
+    /// Returns the navigation data for the item's current location, in the form that
+    /// [`Item::navigate`] accepts.
+    fn navigation_data(&self, _: &mut ViewContext<Self>) -> Option<Box<dyn Any + Send>> {
+        None
+    }
+    /// Returns the row of the location that the navigation data points to, if any.
+    fn navigation_row(&self, _data: &dyn Any) -> Option<u32> {
+        None
+    }
     fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
         None
     }
@@ -292,6 +312,16 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn preserve_preview(&self, _cx: &AppContext) -> bool {
         false
     }
+
+    /// Returns the autosave setting for this item, when it's configured for the item's
+    /// language. Otherwise, the `autosave` setting of the workspace is used.
+    fn autosave_setting(&self, _cx: &AppContext) -> Option<AutosaveSetting> {
+        None
+    }
+
+    /// Called when the item's pane enters or leaves zen mode, in which items should hide
+    /// decorations that aren't part of their content.
+    fn set_zen_mode(&mut self, _zen_mode: bool, _cx: &mut ViewContext<Self>) {}
 }
 
 pub trait SerializableItem: Item {
@@ -398,6 +428,8 @@ pub trait ItemHandle: 'static + Send {
     fn discarded(&self, project: Model<Project>, cx: &mut WindowContext);
     fn workspace_deactivated(&self, cx: &mut WindowContext);
     fn navigate(&self, data: Box<dyn Any>, cx: &mut WindowContext) -> bool;
+    fn navigation_data(&self, cx: &mut WindowContext) -> Option<Box<dyn Any + Send>>;
+    fn navigation_row(&self, data: &dyn Any, cx: &AppContext) -> Option<u32>;
     fn item_id(&self) -> EntityId;
     fn to_any(&self) -> AnyView;
     fn is_dirty(&self, cx: &AppContext) -> bool;
@@ -435,6 +467,8 @@ pub trait ItemHandle: 'static + Send {
     fn downgrade_item(&self) -> Box<dyn WeakItemHandle>;
     fn workspace_settings<'a>(&self, cx: &'a AppContext) -> &'a WorkspaceSettings;
     fn preserve_preview(&self, cx: &AppContext) -> bool;
+    fn autosave_setting(&self, cx: &AppContext) -> AutosaveSetting;
+    fn set_zen_mode(&self, zen_mode: bool, cx: &mut WindowContext);
 }
 
 pub trait WeakItemHandle: Send + Sync {
@@ -696,7 +730,7 @@ impl<T: Item> ItemHandle for View<T> {
                         }
 
                         ItemEvent::Edit => {
-                            let autosave = item.workspace_settings(cx).autosave;
+                            let autosave = item.autosave_setting(cx);
 
                             if let AutosaveSetting::AfterDelay { milliseconds } = autosave {
                                 let delay = Duration::from_millis(milliseconds);
@@ -706,6 +740,7 @@ impl<T: Item> ItemHandle for View<T> {
                                 });
                             }
                             pane.update(cx, |pane, cx| pane.handle_item_edit(item.item_id(), cx));
+                            workspace.record_edit_location(&item, cx);
                         }
 
                         _ => {}
@@ -715,7 +750,7 @@ impl<T: Item> ItemHandle for View<T> {
 
             cx.on_blur(&self.focus_handle(cx), move |workspace, cx| {
                 if let Some(item) = weak_item.upgrade() {
-                    if item.workspace_settings(cx).autosave == AutosaveSetting::OnFocusChange {
+                    if item.autosave_setting(cx) == AutosaveSetting::OnFocusChange {
                         Pane::autosave_item(&item, workspace.project.clone(), cx)
                             .detach_and_log_err(cx);
                     }
@@ -753,6 +788,14 @@ impl<T: Item> ItemHandle for View<T> {
         self.update(cx, |this, cx| this.navigate(data, cx))
     }
 
+    fn navigation_data(&self, cx: &mut WindowContext) -> Option<Box<dyn Any + Send>> {
+        self.update(cx, |this, cx| this.navigation_data(cx))
+    }
+
+    fn navigation_row(&self, data: &dyn Any, cx: &AppContext) -> Option<u32> {
+        self.read(cx).navigation_row(data)
+    }
+
     fn item_id(&self) -> EntityId {
         self.entity_id()
     }
@@ -845,6 +888,16 @@ impl<T: Item> ItemHandle for View<T> {
     fn preserve_preview(&self, cx: &AppContext) -> bool {
         self.read(cx).preserve_preview(cx)
     }
+
+    fn autosave_setting(&self, cx: &AppContext) -> AutosaveSetting {
+        self.read(cx)
+            .autosave_setting(cx)
+            .unwrap_or_else(|| self.workspace_settings(cx).autosave)
+    }
+
+    fn set_zen_mode(&self, zen_mode: bool, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| this.set_zen_mode(zen_mode, cx))
+    }
 }
 
 impl From<Box<dyn ItemHandle>> for AnyView {
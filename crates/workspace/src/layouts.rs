@@ -0,0 +1,511 @@
+use crate::{
+    dock::Dock, item::ItemHandle, persistence::model::DockData, Member, Pane, PaneAxis, PaneGroup,
+    Workspace,
+};
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{px, AppContext, Axis, Task, View, ViewContext, WindowContext};
+use project::{Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use util::ResultExt;
+
+const LAYOUTS_KEY: &str = "workspace_layouts";
+const PROJECT_LAYOUT_KEY_PREFIX: &str = "workspace_layout_for_project";
+
+/// The arrangement of a workspace's panes and docks. Items are recorded by their path
+/// relative to the project, so that a layout can be restored after the workspace's items
+/// have changed, or in another checkout of the same project.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceLayout {
+    pub center: PaneGroupLayout,
+    #[serde(default)]
+    pub left_dock: DockLayout,
+    #[serde(default)]
+    pub right_dock: DockLayout,
+    #[serde(default)]
+    pub bottom_dock: DockLayout,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PaneGroupLayout {
+    Split {
+        vertical: bool,
+        flexes: Vec<f32>,
+        children: Vec<PaneGroupLayout>,
+    },
+    Pane {
+        /// The paths of the pane's items in tab order, each starting with the name of its
+        /// worktree's root.
+        paths: Vec<PathBuf>,
+        active_ix: Option<usize>,
+        active: bool,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DockLayout {
+    pub visible: bool,
+    pub active_panel: Option<String>,
+    /// The size of the active panel, in pixels.
+    pub size: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NamedLayout {
+    pub name: String,
+    pub layout: WorkspaceLayout,
+}
+
+pub fn load_layouts() -> Vec<NamedLayout> {
+    KEY_VALUE_STORE
+        .read_kvp(LAYOUTS_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|layouts| serde_json::from_str(&layouts).log_err())
+        .unwrap_or_default()
+}
+
+pub fn store_layouts(layouts: &[NamedLayout], cx: &mut AppContext) {
+    let Some(layouts) = serde_json::to_string(layouts).log_err() else {
+        return;
+    };
+    db::write_and_log(cx, move || {
+        KEY_VALUE_STORE.write_kvp(LAYOUTS_KEY.to_string(), layouts)
+    });
+}
+
+fn project_layout_key(workspace: &Workspace, cx: &AppContext) -> Option<String> {
+    let mut paths = workspace.local_paths(cx)?;
+    if paths.is_empty() {
+        return None;
+    }
+    paths.sort();
+    let paths = paths
+        .iter()
+        .map(|path| path.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(format!("{PROJECT_LAYOUT_KEY_PREFIX}:{paths}"))
+}
+
+/// Reads the name of the layout that's restored when the workspace's project is opened
+/// without a previous session.
+pub fn project_layout_name(workspace: &Workspace, cx: &AppContext) -> Task<Option<String>> {
+    let Some(key) = project_layout_key(workspace, cx) else {
+        return Task::ready(None);
+    };
+    cx.background_executor()
+        .spawn(async move { KEY_VALUE_STORE.read_kvp(&key).log_err().flatten() })
+}
+
+pub fn set_project_layout_name(workspace: &Workspace, name: Option<String>, cx: &mut AppContext) {
+    let Some(key) = project_layout_key(workspace, cx) else {
+        return;
+    };
+    db::write_and_log(cx, move || async move {
+        match name {
+            Some(name) => KEY_VALUE_STORE.write_kvp(key, name).await,
+            None => KEY_VALUE_STORE.delete_kvp(key).await,
+        }
+    });
+}
+
+/// Applies the layout chosen for the workspace's project, if any, once it's been read.
+pub(crate) fn apply_project_layout(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let load_project_layout = project_layout_name(workspace, cx);
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(name) = load_project_layout.await else {
+            return Ok(());
+        };
+        let layout = cx
+            .background_executor()
+            .spawn(async move {
+                load_layouts()
+                    .into_iter()
+                    .find(|layout| layout.name == name)
+            })
+            .await;
+        if let Some(layout) = layout {
+            workspace
+                .update(&mut cx, |workspace, cx| {
+                    apply_layout(workspace, &layout.layout, cx)
+                })?
+                .await;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+pub fn capture_layout(workspace: &Workspace, cx: &WindowContext) -> WorkspaceLayout {
+    WorkspaceLayout {
+        center: capture_member(workspace, &workspace.center.root, cx),
+        left_dock: capture_dock(workspace.left_dock(), cx),
+        right_dock: capture_dock(workspace.right_dock(), cx),
+        bottom_dock: capture_dock(workspace.bottom_dock(), cx),
+    }
+}
+
+fn capture_member(workspace: &Workspace, member: &Member, cx: &WindowContext) -> PaneGroupLayout {
+    match member {
+        Member::Axis(axis) => PaneGroupLayout::Split {
+            vertical: axis.axis == Axis::Vertical,
+            flexes: axis.flexes.lock().clone(),
+            children: axis
+                .members
+                .iter()
+                .map(|member| capture_member(workspace, member, cx))
+                .collect(),
+        },
+        Member::Pane(pane_handle) => {
+            let project = workspace.project.read(cx);
+            let pane = pane_handle.read(cx);
+            let mut paths = Vec::new();
+            let mut active_ix = None;
+            for (ix, item) in pane.items().enumerate() {
+                let Some(path) = layout_path(item.as_ref(), project, cx) else {
+                    continue;
+                };
+                if ix == pane.active_item_index() {
+                    active_ix = Some(paths.len());
+                }
+                paths.push(path);
+            }
+            PaneGroupLayout::Pane {
+                paths,
+                active_ix,
+                active: *pane_handle == workspace.active_pane,
+            }
+        }
+    }
+}
+
+fn capture_dock(dock: &View<Dock>, cx: &WindowContext) -> DockLayout {
+    let dock = dock.read(cx);
+    DockLayout {
+        visible: dock.is_open(),
+        active_panel: dock
+            .active_panel()
+            .map(|panel| panel.persistent_name().to_string()),
+        size: dock.active_panel_size(cx).map(|size| size.0),
+    }
+}
+
+fn layout_path(item: &dyn ItemHandle, project: &Project, cx: &AppContext) -> Option<PathBuf> {
+    let project_path = item.project_path(cx)?;
+    let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+    Some(Path::new(worktree.read(cx).root_name()).join(&project_path.path))
+}
+
+struct PaneToRestore {
+    pane: View<Pane>,
+    active_path: Option<PathBuf>,
+    active: bool,
+}
+
+/// Rearranges the workspace's panes and docks to match the layout.
+///
+/// Items that are already open are moved into the pane that the layout puts them in, and
+/// the layout's other items are opened. Items that the layout doesn't mention are kept, in
+/// the layout's active pane. The returned task resolves once all items have been opened.
+pub fn apply_layout(
+    workspace: &mut Workspace,
+    layout: &WorkspaceLayout,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<()> {
+    let mut open_items = Vec::new();
+    for pane in workspace.panes.clone() {
+        let items = pane.read(cx).items().cloned().collect::<Vec<_>>();
+        pane.update(cx, |pane, cx| {
+            while pane.items_len() > 0 {
+                pane.remove_item(0, false, false, cx);
+            }
+        });
+        for item in items {
+            let path = layout_path(item.as_ref(), workspace.project.read(cx), cx);
+            open_items.push((path, item));
+        }
+    }
+
+    let mut panes_to_restore = Vec::new();
+    let mut paths_to_open = Vec::new();
+    let root = build_member(
+        workspace,
+        &layout.center,
+        &mut open_items,
+        &mut panes_to_restore,
+        &mut paths_to_open,
+        cx,
+    );
+    let active_pane = panes_to_restore
+        .iter()
+        .find(|pane_to_restore| pane_to_restore.active)
+        .or(panes_to_restore.first())
+        .map(|pane_to_restore| pane_to_restore.pane.clone())
+        .unwrap_or_else(|| workspace.add_pane(cx));
+    active_pane.update(cx, |pane, cx| {
+        for (_, item) in open_items {
+            pane.add_item(item, false, false, Some(pane.items_len()), cx);
+        }
+    });
+
+    workspace.remove_panes(workspace.center.root.clone(), cx);
+    workspace.center = PaneGroup::with_root(root);
+    workspace.last_active_center_pane = Some(active_pane.downgrade());
+    workspace.active_pane = active_pane.clone();
+    active_pane.update(cx, |pane, cx| pane.focus(cx));
+
+    for (dock, dock_layout) in [
+        (workspace.left_dock.clone(), &layout.left_dock),
+        (workspace.right_dock.clone(), &layout.right_dock),
+        (workspace.bottom_dock.clone(), &layout.bottom_dock),
+    ] {
+        dock.update(cx, |dock, cx| {
+            // Panels that are added later restore this state too.
+            dock.serialized_dock = Some(DockData {
+                visible: dock_layout.visible,
+                active_panel: dock_layout.active_panel.clone(),
+                zoom: false,
+            });
+            dock.restore_state(cx);
+            if let Some(size) = dock_layout.size.filter(|_| dock_layout.visible) {
+                dock.resize_active_panel(Some(px(size)), cx);
+            }
+        });
+    }
+
+    activate_restored_items(workspace, &panes_to_restore, cx);
+    workspace.serialize_workspace(cx);
+    cx.notify();
+
+    if paths_to_open.is_empty() {
+        return Task::ready(());
+    }
+    cx.spawn(|workspace, mut cx| async move {
+        for (pane, ix, project_path) in paths_to_open {
+            let Some(open_task) = workspace
+                .update(&mut cx, |workspace, cx| {
+                    workspace.open_path(project_path, Some(pane.downgrade()), false, cx)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            let Some(item) = open_task.await.log_err() else {
+                continue;
+            };
+            pane.update(&mut cx, |pane, cx| {
+                pane.add_item(item, false, false, Some(ix), cx)
+            })
+            .log_err();
+        }
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                activate_restored_items(workspace, &panes_to_restore, cx);
+                workspace.active_pane.update(cx, |pane, cx| pane.focus(cx));
+            })
+            .log_err();
+    })
+}
+
+/// Builds the panes of the layout, moving the open items that it mentions into them.
+fn build_member(
+    workspace: &mut Workspace,
+    layout: &PaneGroupLayout,
+    open_items: &mut Vec<(Option<PathBuf>, Box<dyn ItemHandle>)>,
+    panes_to_restore: &mut Vec<PaneToRestore>,
+    paths_to_open: &mut Vec<(View<Pane>, usize, ProjectPath)>,
+    cx: &mut ViewContext<Workspace>,
+) -> Member {
+    match layout {
+        PaneGroupLayout::Split {
+            vertical,
+            flexes,
+            children,
+        } if !children.is_empty() => {
+            let mut members = children
+                .iter()
+                .map(|child| {
+                    build_member(
+                        workspace,
+                        child,
+                        open_items,
+                        panes_to_restore,
+                        paths_to_open,
+                        cx,
+                    )
+                })
+                .collect::<Vec<_>>();
+            if members.len() == 1 {
+                return members.pop().unwrap();
+            }
+            let axis = if *vertical {
+                Axis::Vertical
+            } else {
+                Axis::Horizontal
+            };
+            let flexes = (flexes.len() == members.len()).then(|| flexes.clone());
+            Member::Axis(PaneAxis::load(axis, members, flexes))
+        }
+        PaneGroupLayout::Split { .. } => {
+            let pane = workspace.add_pane(cx);
+            panes_to_restore.push(PaneToRestore {
+                pane: pane.clone(),
+                active_path: None,
+                active: false,
+            });
+            Member::Pane(pane)
+        }
+        PaneGroupLayout::Pane {
+            paths,
+            active_ix,
+            active,
+        } => {
+            let pane = workspace.add_pane(cx);
+            for (ix, path) in paths.iter().enumerate() {
+                let open_item_ix = open_items
+                    .iter()
+                    .position(|(item_path, _)| item_path.as_ref() == Some(path));
+                if let Some(open_item_ix) = open_item_ix {
+                    let (_, item) = open_items.remove(open_item_ix);
+                    pane.update(cx, |pane, cx| {
+                        pane.add_item(item, false, false, Some(pane.items_len()), cx)
+                    });
+                } else if let Some(project_path) =
+                    workspace.project.read(cx).find_project_path(path, cx)
+                {
+                    paths_to_open.push((pane.clone(), ix, project_path));
+                }
+            }
+            panes_to_restore.push(PaneToRestore {
+                pane: pane.clone(),
+                active_path: active_ix.and_then(|ix| paths.get(ix).cloned()),
+                active: *active,
+            });
+            Member::Pane(pane)
+        }
+    }
+}
+
+fn activate_restored_items(
+    workspace: &Workspace,
+    panes_to_restore: &[PaneToRestore],
+    cx: &mut WindowContext,
+) {
+    for pane_to_restore in panes_to_restore {
+        let Some(active_path) = &pane_to_restore.active_path else {
+            continue;
+        };
+        let project = workspace.project.read(cx);
+        let Some(ix) =
+            pane_to_restore.pane.read(cx).items().position(|item| {
+                layout_path(item.as_ref(), project, cx).as_ref() == Some(active_path)
+            })
+        else {
+            continue;
+        };
+        pane_to_restore
+            .pane
+            .update(cx, |pane, cx| pane.activate_item(ix, false, false, cx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, tests::init_test, SplitDirection};
+    use fs::FakeFs;
+
+    #[test]
+    fn test_layout_serialization() {
+        let layout: WorkspaceLayout = serde_json::from_str(
+            r#"{
+                "center": {
+                    "kind": "split",
+                    "vertical": false,
+                    "flexes": [1.5, 0.5],
+                    "children": [
+                        {"kind": "pane", "paths": ["root/a.rs"], "active_ix": 0, "active": true},
+                        {"kind": "pane", "paths": [], "active_ix": null, "active": false}
+                    ]
+                },
+                "left_dock": {"visible": true, "active_panel": "ProjectPanel", "size": 240.0}
+            }"#,
+        )
+        .unwrap();
+        // Layouts saved without a dock leave it closed.
+        assert_eq!(layout.right_dock, DockLayout::default());
+        assert_eq!(
+            serde_json::from_str::<WorkspaceLayout>(&serde_json::to_string(&layout).unwrap())
+                .unwrap(),
+            layout
+        );
+    }
+
+    #[gpui::test]
+    async fn test_capture_and_apply_layout(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        workspace.update(cx, |workspace, cx| {
+            let item1 = cx.new_view(|cx| TestItem::new(cx));
+            let item2 = cx.new_view(|cx| TestItem::new(cx));
+            workspace.add_item_to_active_pane(Box::new(item1), None, true, cx);
+            let pane = workspace.active_pane().clone();
+            let new_pane = workspace.split_pane(pane, SplitDirection::Down, cx);
+            new_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(item2), true, true, None, cx)
+            });
+        });
+
+        let layout = workspace.update(cx, |workspace, cx| capture_layout(workspace, cx));
+        let PaneGroupLayout::Split {
+            vertical, children, ..
+        } = &layout.center
+        else {
+            panic!("expected a split, got {:?}", layout.center);
+        };
+        assert!(vertical);
+        assert_eq!(children.len(), 2);
+        assert!(matches!(
+            children[1],
+            PaneGroupLayout::Pane { active: true, .. }
+        ));
+
+        // Items that the layout doesn't place are kept in its active pane.
+        let single_pane = WorkspaceLayout {
+            center: PaneGroupLayout::Pane {
+                paths: Vec::new(),
+                active_ix: None,
+                active: true,
+            },
+            left_dock: DockLayout::default(),
+            right_dock: DockLayout::default(),
+            bottom_dock: DockLayout::default(),
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                apply_layout(workspace, &single_pane, cx)
+            })
+            .await;
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 1);
+            assert_eq!(workspace.active_pane().read(cx).items_len(), 2);
+            assert_eq!(
+                capture_layout(workspace, cx).center,
+                single_pane.center.clone()
+            );
+        });
+
+        workspace
+            .update(cx, |workspace, cx| apply_layout(workspace, &layout, cx))
+            .await;
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 2);
+            assert_eq!(capture_layout(workspace, cx).center, layout.center);
+        });
+    }
+}
@@ -6,17 +6,18 @@ use crate::{
     notifications::NotifyResultExt,
     toolbar::Toolbar,
     workspace_settings::{AutosaveSetting, TabBarSettings, WorkspaceSettings},
-    CloseWindow, CopyPath, CopyRelativePath, NewFile, NewTerminal, OpenInTerminal, OpenTerminal,
-    OpenVisible, SplitDirection, ToggleFileFinder, ToggleProjectSymbols, ToggleZoom, Workspace,
+    CloseWindow, CopyPath, CopyRelativePath, MoveItemToNewWindow, NewFile, NewTerminal,
+    OpenInTerminal, OpenTerminal, OpenVisible, SplitDirection, ToggleFileFinder,
+    ToggleProjectSymbols, ToggleZoom, Workspace,
 };
 use anyhow::Result;
 use collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
     actions, anchored, deferred, impl_actions, prelude::*, Action, AnchorCorner, AnyElement,
-    AppContext, AsyncWindowContext, ClickEvent, ClipboardItem, Div, DragMoveEvent, EntityId,
-    EventEmitter, ExternalPaths, FocusHandle, FocusOutEvent, FocusableView, KeyContext, Model,
-    MouseButton, MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render,
+    AppContext, AsyncWindowContext, ClickEvent, ClipboardItem, DismissEvent, Div, DragMoveEvent,
+    EntityId, EventEmitter, ExternalPaths, FocusHandle, FocusOutEvent, FocusableView, KeyContext,
+    Model, MouseButton, MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render,
     ScrollHandle, Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView,
     WindowContext,
 };
@@ -29,12 +30,13 @@ use std::{
     any::Any,
     cmp, fmt, mem,
     ops::ControlFlow,
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 use theme::ThemeSettings;
 
@@ -154,6 +156,7 @@ actions!(
         SplitDown,
         SplitHorizontal,
         SplitVertical,
+        TogglePinTab,
         TogglePreviewTab,
     ]
 );
@@ -167,6 +170,8 @@ impl DeploySearch {
 }
 
 const MAX_NAVIGATION_HISTORY_LEN: usize = 1024;
+const MAX_NAVIGATION_HISTORY_MENU_LEN: usize = 20;
+const NAVIGATION_HISTORY_MENU_HOLD_DURATION: Duration = Duration::from_millis(400);
 
 pub enum Event {
     AddItem {
@@ -232,10 +237,6 @@ impl fmt::Debug for Event {
 /// responsible for managing item tabs, focus and zoom states and drag and drop features.
 /// Can be split, see `PaneGroup` for more details.
 pub struct Pane {
-    alternate_file_items: (
-        Option<Box<dyn WeakItemHandle>>,
-        Option<Box<dyn WeakItemHandle>>,
-    ),
     focus_handle: FocusHandle,
     items: Vec<Box<dyn ItemHandle>>,
     activation_history: Vec<ActivationHistoryEntry>,
@@ -244,6 +245,8 @@ pub struct Pane {
     was_focused: bool,
     active_item_index: usize,
     preview_item_id: Option<EntityId>,
+    /// The number of pinned items, which are always the first items of the pane.
+    pinned_tab_count: usize,
     last_focus_handle_by_item: HashMap<EntityId, WeakFocusHandle>,
     nav_history: NavHistory,
     toolbar: View<Toolbar>,
@@ -255,6 +258,8 @@ pub struct Pane {
         Option<Arc<dyn Fn(&mut Pane, &dyn Any, &mut ViewContext<Pane>) -> ControlFlow<(), ()>>>,
     can_split: bool,
     should_display_tab_bar: Rc<dyn Fn(&ViewContext<Pane>) -> bool>,
+    /// Whether the tab bar is hidden and the items are asked to hide their decorations.
+    zen_mode: bool,
     render_tab_bar_buttons:
         Rc<dyn Fn(&mut Pane, &mut ViewContext<Pane>) -> (Option<AnyElement>, Option<AnyElement>)>,
     _subscriptions: Vec<Subscription>,
@@ -266,6 +271,11 @@ pub struct Pane {
     save_modals_spawned: HashSet<EntityId>,
     pub new_item_context_menu_handle: PopoverMenuHandle<ContextMenu>,
     split_item_context_menu_handle: PopoverMenuHandle<ContextMenu>,
+    /// The menu listing the locations that going back or forward would visit.
+    nav_history_menu: Option<(View<ContextMenu>, Point<Pixels>, Subscription)>,
+    /// Deploys the navigation history menu when a navigation button is held down.
+    nav_history_menu_timer: Option<Task<()>>,
+    nav_history_menu_deployed_on_hold: bool,
 }
 
 pub struct ActivationHistoryEntry {
@@ -346,7 +356,6 @@ impl Pane {
 
         let handle = cx.view().downgrade();
         Self {
-            alternate_file_items: (None, None),
             focus_handle,
             items: Vec::new(),
             activation_history: Vec::new(),
@@ -355,6 +364,7 @@ impl Pane {
             zoomed: false,
             active_item_index: 0,
             preview_item_id: None,
+            pinned_tab_count: 0,
             last_focus_handle_by_item: Default::default(),
             nav_history: NavHistory(Arc::new(Mutex::new(NavHistoryState {
                 mode: NavigationMode::Normal,
@@ -374,6 +384,7 @@ impl Pane {
             custom_drop_handle: None,
             can_split: true,
             should_display_tab_bar: Rc::new(|cx| TabBarSettings::get_global(cx).show),
+            zen_mode: false,
             render_tab_bar_buttons: Rc::new(move |pane, cx| {
                 if !pane.has_focus(cx) && !pane.context_menu_focused(cx) {
                     return (None, None);
@@ -463,40 +474,25 @@ impl Pane {
             double_click_dispatch_action,
             save_modals_spawned: HashSet::default(),
             split_item_context_menu_handle: Default::default(),
+            nav_history_menu: None,
+            nav_history_menu_timer: None,
+            nav_history_menu_deployed_on_hold: false,
             new_item_context_menu_handle: Default::default(),
         }
     }
 
+    /// Switches to the item that was active before the current one, so that repeating the
+    /// action toggles between the two most recently used items.
     fn alternate_file(&mut self, cx: &mut ViewContext<Pane>) {
-        let (_, alternative) = &self.alternate_file_items;
-        if let Some(alternative) = alternative {
-            let existing = self
-                .items()
-                .find_position(|item| item.item_id() == alternative.id());
-            if let Some((ix, _)) = existing {
-                self.activate_item(ix, true, true, cx);
-            } else {
-                if let Some(upgraded) = alternative.upgrade() {
-                    self.add_item(upgraded, true, true, None, cx);
-                }
-            }
-        }
-    }
-
-    pub fn track_alternate_file_items(&mut self) {
-        if let Some(item) = self.active_item().map(|item| item.downgrade_item()) {
-            let (current, _) = &self.alternate_file_items;
-            match current {
-                Some(current) => {
-                    if current.id() != item.id() {
-                        self.alternate_file_items =
-                            (Some(item), self.alternate_file_items.0.take());
-                    }
-                }
-                None => {
-                    self.alternate_file_items = (Some(item), None);
-                }
-            }
+        let active_item_id = self.active_item().map(|item| item.item_id());
+        let alternate_ix = self
+            .activation_history
+            .iter()
+            .rev()
+            .filter(|entry| Some(entry.entity_id) != active_item_id)
+            .find_map(|entry| self.index_for_item_id(entry.entity_id));
+        if let Some(ix) = alternate_ix {
+            self.activate_item(ix, true, true, cx);
         }
     }
 
@@ -585,6 +581,21 @@ impl Pane {
         self.should_display_tab_bar = Rc::new(should_display_tab_bar);
     }
 
+    pub fn is_zen_mode(&self) -> bool {
+        self.zen_mode
+    }
+
+    pub fn set_zen_mode(&mut self, zen_mode: bool, cx: &mut ViewContext<Self>) {
+        if self.zen_mode == zen_mode {
+            return;
+        }
+        self.zen_mode = zen_mode;
+        for item in self.items.clone() {
+            item.set_zen_mode(zen_mode, cx);
+        }
+        cx.notify();
+    }
+
     pub fn set_can_split(&mut self, can_split: bool, cx: &mut ViewContext<Self>) {
         self.can_split = can_split;
         cx.notify();
@@ -668,6 +679,173 @@ impl Pane {
         }
     }
 
+    /// Goes back or forward through the history until the given number of entries have
+    /// been visited.
+    fn navigate_history_by(
+        &mut self,
+        mode: NavigationMode,
+        steps: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let target_count = self.nav_history.entry_count(mode).saturating_sub(steps);
+        cx.spawn(|pane, mut cx| async move {
+            while pane.update(&mut cx, |pane, _| pane.nav_history.entry_count(mode))? > target_count
+            {
+                workspace
+                    .update(&mut cx, |workspace, cx| match mode {
+                        NavigationMode::GoingForward => workspace.go_forward(pane.clone(), cx),
+                        _ => workspace.go_back(pane.clone(), cx),
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Describes the locations that going back or forward would visit, most recent first.
+    fn nav_history_menu_labels(&self, mode: NavigationMode, cx: &AppContext) -> Vec<SharedString> {
+        let state = self.nav_history.0.lock();
+        let stack = match mode {
+            NavigationMode::GoingForward => &state.forward_stack,
+            _ => &state.backward_stack,
+        };
+        stack
+            .iter()
+            .rev()
+            .take(MAX_NAVIGATION_HISTORY_MENU_LEN)
+            .map(|entry| {
+                let item = entry.item.upgrade();
+                let name = state
+                    .paths_by_item
+                    .get(&entry.item.id())
+                    .map(|(project_path, _)| project_path.path.clone())
+                    .or_else(|| Some(item.as_ref()?.project_path(cx)?.path))
+                    .and_then(|path| Some(path.file_name()?.to_string_lossy().to_string()))
+                    .or_else(|| Some(item.as_ref()?.tab_description(0, cx)?.to_string()))
+                    .unwrap_or_else(|| "untitled".to_string());
+                let row = item
+                    .as_ref()
+                    .zip(entry.data.as_ref())
+                    .and_then(|(item, data)| item.navigation_row(data.as_ref(), cx));
+                match row {
+                    Some(row) => format!("{name}:{}", row + 1).into(),
+                    None => name.into(),
+                }
+            })
+            .collect()
+    }
+
+    fn deploy_nav_history_menu(
+        &mut self,
+        mode: NavigationMode,
+        position: Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let labels = self.nav_history_menu_labels(mode, cx);
+        if labels.is_empty() {
+            return;
+        }
+
+        let pane = cx.view().clone();
+        let menu = ContextMenu::build(cx, |menu, cx| {
+            labels
+                .into_iter()
+                .enumerate()
+                .fold(menu, |menu, (ix, label)| {
+                    menu.entry(
+                        label,
+                        None,
+                        cx.handler_for(&pane, move |pane, cx| {
+                            pane.navigate_history_by(mode, ix + 1, cx)
+                        }),
+                    )
+                })
+        });
+        cx.focus_view(&menu);
+        let subscription = cx.subscribe(&menu, |pane, menu, _: &DismissEvent, cx| {
+            if menu.focus_handle(cx).contains_focused(cx) {
+                pane.focus_active_item(cx);
+            }
+            pane.nav_history_menu.take();
+            cx.notify();
+        });
+        self.nav_history_menu = Some((menu, position, subscription));
+        cx.notify();
+    }
+
+    fn render_nav_history_button(
+        &self,
+        mode: NavigationMode,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let (id, icon, enabled, tooltip, action): (_, _, _, _, Box<dyn Action>) = match mode {
+            NavigationMode::GoingForward => (
+                "navigate_forward",
+                IconName::ArrowRight,
+                self.can_navigate_forward(),
+                "Go Forward",
+                Box::new(GoForward),
+            ),
+            _ => (
+                "navigate_backward",
+                IconName::ArrowLeft,
+                self.can_navigate_backward(),
+                "Go Back",
+                Box::new(GoBack),
+            ),
+        };
+
+        div()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |pane, event: &MouseDownEvent, cx| {
+                    let position = event.position;
+                    pane.nav_history_menu_deployed_on_hold = false;
+                    pane.nav_history_menu_timer = Some(cx.spawn(|pane, mut cx| async move {
+                        cx.background_executor()
+                            .timer(NAVIGATION_HISTORY_MENU_HOLD_DURATION)
+                            .await;
+                        pane.update(&mut cx, |pane, cx| {
+                            pane.nav_history_menu_deployed_on_hold = true;
+                            pane.deploy_nav_history_menu(mode, position, cx);
+                        })
+                        .ok();
+                    }));
+                }),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|pane, _, _| pane.nav_history_menu_timer = None),
+            )
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |pane, event: &MouseDownEvent, cx| {
+                    pane.deploy_nav_history_menu(mode, event.position, cx);
+                }),
+            )
+            .child(
+                IconButton::new(id, icon)
+                    .shape(IconButtonShape::Square)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |pane, _, cx| {
+                        if mem::take(&mut pane.nav_history_menu_deployed_on_hold) {
+                            return;
+                        }
+                        pane.nav_history_menu_timer = None;
+                        match mode {
+                            NavigationMode::GoingForward => pane.navigate_forward(cx),
+                            _ => pane.navigate_backward(cx),
+                        }
+                    }))
+                    .disabled(!enabled)
+                    .tooltip(move |cx| Tooltip::for_action(tooltip, action.as_ref(), cx)),
+            )
+    }
+
     fn history_updated(&mut self, cx: &mut ViewContext<Self>) {
         self.toolbar.update(cx, |_, cx| cx.notify());
     }
@@ -697,13 +875,92 @@ impl Pane {
     }
 
     /// Marks the item with the given ID as the preview item.
-    /// This will be ignored if the global setting `preview_tabs` is disabled.
+    /// This will be ignored if the global setting `preview_tabs` is disabled,
+    /// or if the item is pinned.
     pub fn set_preview_item_id(&mut self, item_id: Option<EntityId>, cx: &AppContext) {
-        if PreviewTabsSettings::get_global(cx).enabled {
+        let is_pinned = item_id.map_or(false, |item_id| {
+            self.items[..self.pinned_tab_count]
+                .iter()
+                .any(|item| item.item_id() == item_id)
+        });
+        if PreviewTabsSettings::get_global(cx).enabled && !is_pinned {
             self.preview_item_id = item_id;
         }
     }
 
+    pub fn pinned_count(&self) -> usize {
+        self.pinned_tab_count
+    }
+
+    pub(crate) fn set_pinned_count(&mut self, count: usize, cx: &mut ViewContext<Self>) {
+        self.pinned_tab_count = count.min(self.items.len());
+        if self
+            .preview_item_idx()
+            .map_or(false, |ix| ix < self.pinned_tab_count)
+        {
+            self.preview_item_id = None;
+        }
+        cx.notify();
+    }
+
+    pub fn is_tab_pinned(&self, ix: usize) -> bool {
+        ix < self.pinned_tab_count
+    }
+
+    fn pinned_item_ids(&self) -> HashSet<EntityId> {
+        self.items[..self.pinned_tab_count]
+            .iter()
+            .map(|item| item.item_id())
+            .collect()
+    }
+
+    pub fn toggle_pin_tab(&mut self, _: &TogglePinTab, cx: &mut ViewContext<Self>) {
+        if self.items.is_empty() {
+            return;
+        }
+        let ix = self.active_item_index;
+        if self.is_tab_pinned(ix) {
+            self.unpin_tab_at(ix, cx);
+        } else {
+            self.pin_tab_at(ix, cx);
+        }
+    }
+
+    /// Pins the item at the given index, moving it after the other pinned items.
+    pub fn pin_tab_at(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix >= self.items.len() || self.is_tab_pinned(ix) {
+            return;
+        }
+        if self.preview_item_idx() == Some(ix) {
+            self.preview_item_id = None;
+        }
+        self.reorder_item(ix, self.pinned_tab_count);
+        self.pinned_tab_count += 1;
+        cx.notify();
+    }
+
+    /// Unpins the item at the given index, moving it before the other unpinned items.
+    pub fn unpin_tab_at(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if !self.is_tab_pinned(ix) {
+            return;
+        }
+        self.pinned_tab_count -= 1;
+        self.reorder_item(ix, self.pinned_tab_count);
+        cx.notify();
+    }
+
+    fn reorder_item(&mut self, from_ix: usize, to_ix: usize) {
+        let item = self.items.remove(from_ix);
+        self.items.insert(to_ix, item);
+        if self.active_item_index == from_ix {
+            self.active_item_index = to_ix;
+        } else if from_ix < self.active_item_index && self.active_item_index <= to_ix {
+            self.active_item_index -= 1;
+        } else if to_ix <= self.active_item_index && self.active_item_index < from_ix {
+            self.active_item_index += 1;
+        }
+    }
+
     pub fn handle_item_edit(&mut self, item_id: EntityId, cx: &AppContext) {
         if let Some(preview_item) = self.preview_item() {
             if preview_item.item_id() == item_id && !preview_item.preserve_preview(cx) {
@@ -838,6 +1095,16 @@ impl Pane {
             }
         });
 
+        item.set_zen_mode(self.zen_mode, cx);
+
+        // Pinned items stay where they are, and other items are never placed among them.
+        match existing_item_index {
+            Some(existing_item_index) if self.is_tab_pinned(existing_item_index) => {
+                insertion_index = existing_item_index;
+            }
+            _ => insertion_index = insertion_index.max(self.pinned_tab_count),
+        }
+
         if let Some(existing_item_index) = existing_item_index {
             // If the item already exists, move it to the desired destination and activate it
 
@@ -938,9 +1205,11 @@ impl Pane {
     }
 
     pub fn index_for_item(&self, item: &dyn ItemHandle) -> Option<usize> {
-        self.items
-            .iter()
-            .position(|i| i.item_id() == item.item_id())
+        self.index_for_item_id(item.item_id())
+    }
+
+    pub fn index_for_item_id(&self, item_id: EntityId) -> Option<usize> {
+        self.items.iter().position(|i| i.item_id() == item_id)
     }
 
     pub fn item_for_index(&self, ix: usize) -> Option<&dyn ItemHandle> {
@@ -1066,10 +1335,11 @@ impl Pane {
         }
 
         let active_item_id = self.items[self.active_item_index].item_id();
+        let pinned_item_ids = self.pinned_item_ids();
         Some(self.close_items(
             cx,
             action.save_intent.unwrap_or(SaveIntent::Close),
-            move |item_id| item_id != active_item_id,
+            move |item_id| item_id != active_item_id && !pinned_item_ids.contains(&item_id),
         ))
     }
 
@@ -1080,6 +1350,7 @@ impl Pane {
     ) -> Option<Task<Result<()>>> {
         let item_ids: Vec<_> = self
             .items()
+            .skip(self.pinned_tab_count)
             .filter(|item| !item.is_dirty(cx))
             .map(|item| item.item_id())
             .collect();
@@ -1108,6 +1379,7 @@ impl Pane {
         let item_ids: Vec<_> = self
             .items()
             .take_while(|item| item.item_id() != item_id)
+            .skip(self.pinned_tab_count)
             .map(|item| item.item_id())
             .collect();
         self.close_items(cx, SaveIntent::Close, move |item_id| {
@@ -1132,11 +1404,13 @@ impl Pane {
         item_id: EntityId,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
+        let pinned_item_ids = self.pinned_item_ids();
         let item_ids: Vec<_> = self
             .items()
             .rev()
             .take_while(|item| item.item_id() != item_id)
             .map(|item| item.item_id())
+            .filter(|item_id| !pinned_item_ids.contains(item_id))
             .collect();
         self.close_items(cx, SaveIntent::Close, move |item_id| {
             item_ids.contains(&item_id)
@@ -1152,11 +1426,12 @@ impl Pane {
             return None;
         }
 
-        Some(
-            self.close_items(cx, action.save_intent.unwrap_or(SaveIntent::Close), |_| {
-                true
-            }),
-        )
+        let pinned_item_ids = self.pinned_item_ids();
+        Some(self.close_items(
+            cx,
+            action.save_intent.unwrap_or(SaveIntent::Close),
+            move |item_id| !pinned_item_ids.contains(&item_id),
+        ))
     }
 
     pub(super) fn file_names_for_prompt(
@@ -1346,6 +1621,9 @@ impl Pane {
         cx.emit(Event::RemoveItem { idx: item_index });
 
         let item = self.items.remove(item_index);
+        if item_index < self.pinned_tab_count {
+            self.pinned_tab_count -= 1;
+        }
 
         cx.emit(Event::RemovedItem {
             item_id: item.item_id(),
@@ -1463,7 +1741,7 @@ impl Pane {
             if save_intent == SaveIntent::Close {
                 let will_autosave = cx.update(|cx| {
                     matches!(
-                        item.workspace_settings(cx).autosave,
+                        item.autosave_setting(cx),
                         AutosaveSetting::OnFocusChange | AutosaveSetting::OnWindowChange
                     ) && Self::can_autosave_item(item, cx)
                 })?;
@@ -1544,12 +1822,11 @@ impl Pane {
         project: Model<Project>,
         cx: &mut WindowContext,
     ) -> Task<Result<()>> {
-        let format =
-            if let AutosaveSetting::AfterDelay { .. } = item.workspace_settings(cx).autosave {
-                false
-            } else {
-                true
-            };
+        let format = if let AutosaveSetting::AfterDelay { .. } = item.autosave_setting(cx) {
+            false
+        } else {
+            true
+        };
         if Self::can_autosave_item(item, cx) {
             item.save(format, project, cx)
         } else {
@@ -1572,6 +1849,20 @@ impl Pane {
         cx.emit(Event::Split(direction));
     }
 
+    /// Splits the pane in the given direction, opening a copy of the given item in the new
+    /// pane.
+    pub fn split_item_by_id(
+        &mut self,
+        item_id: EntityId,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(ix) = self.index_for_item_id(item_id) {
+            self.activate_item(ix, true, false, cx);
+            self.split(direction, cx);
+        }
+    }
+
     pub fn toolbar(&self) -> &View<Toolbar> {
         &self.toolbar
     }
@@ -1639,6 +1930,31 @@ impl Pane {
         }
     }
 
+    fn entry_relative_path(&self, entry: ProjectEntryId, cx: &WindowContext) -> Option<Arc<Path>> {
+        let worktree = self.project.read(cx).worktree_for_entry(entry, cx)?;
+        let path = worktree.read(cx).entry_for_id(entry)?.path.clone();
+        Some(path)
+    }
+
+    fn active_item_entry(&self, cx: &AppContext) -> Option<ProjectEntryId> {
+        let item = self.active_item()?;
+        if !item.is_singleton(cx) {
+            return None;
+        }
+        item.project_entry_ids(cx).first().copied()
+    }
+
+    fn copy_path(&mut self, _: &CopyPath, cx: &mut ViewContext<Self>) {
+        if let Some(abs_path) = self
+            .active_item_entry(cx)
+            .and_then(|entry| self.entry_abs_path(entry, cx))
+        {
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                abs_path.to_string_lossy().to_string(),
+            ));
+        }
+    }
+
     fn copy_relative_path(&mut self, _: &CopyRelativePath, cx: &mut ViewContext<Self>) {
         if let Some(clipboard_text) = self
             .active_item()
@@ -1672,7 +1988,10 @@ impl Pane {
             cx,
         );
         let icon = item.tab_icon(cx);
-        let close_side = &ItemSettings::get_global(cx).close_position;
+        let is_pinned = self.is_tab_pinned(ix);
+        let item_settings = ItemSettings::get_global(cx);
+        let close_side = &item_settings.close_position;
+        let show_label = !(is_pinned && item_settings.pinned_tabs_icon_only && icon.is_some());
         let indicator = render_item_indicator(item.boxed_clone(), cx);
         let item_id = item.item_id();
         let is_first_item = ix == 0;
@@ -1748,7 +2067,19 @@ impl Pane {
                 tab.tooltip(move |cx| Tooltip::text(text.clone(), cx))
             })
             .start_slot::<Indicator>(indicator)
-            .end_slot(
+            .end_slot(if is_pinned {
+                IconButton::new("unpin tab", IconName::Pin)
+                    .shape(IconButtonShape::Square)
+                    .icon_color(Color::Muted)
+                    .size(ButtonSize::None)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(|cx| Tooltip::text("Unpin Tab", cx))
+                    .on_click(cx.listener(move |pane, _, cx| {
+                        if let Some(ix) = pane.index_for_item_id(item_id) {
+                            pane.unpin_tab_at(ix, cx);
+                        }
+                    }))
+            } else {
                 IconButton::new("close tab", IconName::Close)
                     .shape(IconButtonShape::Square)
                     .icon_color(Color::Muted)
@@ -1757,8 +2088,8 @@ impl Pane {
                     .on_click(cx.listener(move |pane, _, cx| {
                         pane.close_item_by_id(item_id, SaveIntent::Close, cx)
                             .detach_and_log_err(cx);
-                    })),
-            )
+                    }))
+            })
             .child(
                 h_flex()
                     .gap_1()
@@ -1769,7 +2100,7 @@ impl Pane {
                             Color::Muted
                         })
                     }))
-                    .child(label),
+                    .when(show_label, |this| this.child(label)),
             );
 
         let single_entry_to_resolve = {
@@ -1799,8 +2130,11 @@ impl Pane {
                             "Close Others",
                             Some(Box::new(CloseInactiveItems { save_intent: None })),
                             cx.handler_for(&pane, move |pane, cx| {
-                                pane.close_items(cx, SaveIntent::Close, |id| id != item_id)
-                                    .detach_and_log_err(cx);
+                                let pinned_item_ids = pane.pinned_item_ids();
+                                pane.close_items(cx, SaveIntent::Close, |id| {
+                                    id != item_id && !pinned_item_ids.contains(&id)
+                                })
+                                .detach_and_log_err(cx);
                             }),
                         )
                         .separator()
@@ -1840,6 +2174,54 @@ impl Pane {
                                     task.detach_and_log_err(cx)
                                 }
                             }),
+                        )
+                        .separator()
+                        .entry(
+                            if is_pinned { "Unpin Tab" } else { "Pin Tab" },
+                            Some(Box::new(TogglePinTab)),
+                            cx.handler_for(&pane, move |pane, cx| {
+                                if let Some(ix) = pane.index_for_item_id(item_id) {
+                                    if pane.is_tab_pinned(ix) {
+                                        pane.unpin_tab_at(ix, cx);
+                                    } else {
+                                        pane.pin_tab_at(ix, cx);
+                                    }
+                                }
+                            }),
+                        )
+                        .separator()
+                        .entry(
+                            "Open in Split Right",
+                            Some(Box::new(SplitRight)),
+                            cx.handler_for(&pane, move |pane, cx| {
+                                pane.split_item_by_id(item_id, SplitDirection::Right, cx);
+                            }),
+                        )
+                        .entry(
+                            "Open in Split Down",
+                            Some(Box::new(SplitDown)),
+                            cx.handler_for(&pane, move |pane, cx| {
+                                pane.split_item_by_id(item_id, SplitDirection::Down, cx);
+                            }),
+                        )
+                        .entry(
+                            "Move to New Window",
+                            Some(Box::new(MoveItemToNewWindow)),
+                            cx.handler_for(&pane, move |pane, cx| {
+                                let pane_handle = cx.view().clone();
+                                let workspace = pane.workspace.clone();
+                                cx.window_context().defer(move |cx| {
+                                    workspace
+                                        .update(cx, |workspace, cx| {
+                                            workspace.move_item_to_new_window(
+                                                &pane_handle,
+                                                item_id,
+                                                cx,
+                                            )
+                                        })
+                                        .log_err();
+                                });
+                            }),
                         );
 
                     if let Some(entry) = single_entry_to_resolve {
@@ -1848,6 +2230,8 @@ impl Pane {
                             .as_deref()
                             .and_then(|abs_path| Some(abs_path.parent()?.to_path_buf()));
 
+                        let relative_path = pane.read(cx).entry_relative_path(entry, cx);
+
                         let entry_id = entry.to_proto();
                         menu = menu
                             .separator()
@@ -1862,13 +2246,17 @@ impl Pane {
                                     }),
                                 )
                             })
-                            .entry(
-                                "Copy Relative Path",
-                                Some(Box::new(CopyRelativePath)),
-                                cx.handler_for(&pane, move |pane, cx| {
-                                    pane.copy_relative_path(&CopyRelativePath, cx);
-                                }),
-                            )
+                            .when_some(relative_path, |menu, relative_path| {
+                                menu.entry(
+                                    "Copy Relative Path",
+                                    Some(Box::new(CopyRelativePath)),
+                                    cx.handler_for(&pane, move |_, cx| {
+                                        cx.write_to_clipboard(ClipboardItem::new_string(
+                                            relative_path.to_string_lossy().to_string(),
+                                        ));
+                                    }),
+                                )
+                            })
                             .separator()
                             .entry(
                                 "Reveal In Project Panel",
@@ -1906,25 +2294,8 @@ impl Pane {
     }
 
     fn render_tab_bar(&mut self, cx: &mut ViewContext<'_, Pane>) -> impl IntoElement {
-        let navigate_backward = IconButton::new("navigate_backward", IconName::ArrowLeft)
-            .shape(IconButtonShape::Square)
-            .icon_size(IconSize::Small)
-            .on_click({
-                let view = cx.view().clone();
-                move |_, cx| view.update(cx, Self::navigate_backward)
-            })
-            .disabled(!self.can_navigate_backward())
-            .tooltip(|cx| Tooltip::for_action("Go Back", &GoBack, cx));
-
-        let navigate_forward = IconButton::new("navigate_forward", IconName::ArrowRight)
-            .shape(IconButtonShape::Square)
-            .icon_size(IconSize::Small)
-            .on_click({
-                let view = cx.view().clone();
-                move |_, cx| view.update(cx, Self::navigate_forward)
-            })
-            .disabled(!self.can_navigate_forward())
-            .tooltip(|cx| Tooltip::for_action("Go Forward", &GoForward, cx));
+        let navigate_backward = self.render_nav_history_button(NavigationMode::GoingBack, cx);
+        let navigate_forward = self.render_nav_history_button(NavigationMode::GoingForward, cx);
 
         TabBar::new("tab_bar")
             .track_scroll(self.tab_bar_scroll_handle.clone())
@@ -2234,7 +2605,7 @@ impl Render for Pane {
         }
 
         let should_display_tab_bar = self.should_display_tab_bar.clone();
-        let display_tab_bar = should_display_tab_bar(cx);
+        let display_tab_bar = !self.zen_mode && should_display_tab_bar(cx);
 
         v_flex()
             .key_context(key_context)
@@ -2260,6 +2631,7 @@ impl Render for Pane {
             .on_action(cx.listener(|pane, _: &GoBack, cx| pane.navigate_backward(cx)))
             .on_action(cx.listener(|pane, _: &GoForward, cx| pane.navigate_forward(cx)))
             .on_action(cx.listener(Pane::toggle_zoom))
+            .on_action(cx.listener(Pane::toggle_pin_tab))
             .on_action(cx.listener(|pane: &mut Pane, action: &ActivateItem, cx| {
                 pane.activate_item(action.0, true, true, cx);
             }))
@@ -2330,6 +2702,8 @@ impl Render for Pane {
                     }
                 }),
             )
+            .on_action(cx.listener(Self::copy_path))
+            .on_action(cx.listener(Self::copy_relative_path))
             .on_action(
                 cx.listener(|pane: &mut Self, action: &RevealInProjectPanel, cx| {
                     let entry_id = action
@@ -2346,6 +2720,15 @@ impl Render for Pane {
             .when(self.active_item().is_some() && display_tab_bar, |pane| {
                 pane.child(self.render_tab_bar(cx))
             })
+            .children(self.nav_history_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(AnchorCorner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
             .child({
                 let has_worktrees = self.project.read(cx).worktrees(cx).next().is_some();
                 // main content
@@ -2592,6 +2975,17 @@ impl NavHistory {
             .retain(|entry| entry.item.id() != item_id);
     }
 
+    /// The number of entries that navigating in the given mode can visit.
+    pub fn entry_count(&self, mode: NavigationMode) -> usize {
+        let state = self.0.lock();
+        match mode {
+            NavigationMode::GoingBack => state.backward_stack.len(),
+            NavigationMode::GoingForward => state.forward_stack.len(),
+            NavigationMode::ReopeningClosedItem => state.closed_stack.len(),
+            NavigationMode::Normal | NavigationMode::Disabled | NavigationMode::ClosingItem => 0,
+        }
+    }
+
     pub fn path_for_item(&self, item_id: EntityId) -> Option<(ProjectPath, Option<PathBuf>)> {
         self.0.lock().paths_by_item.get(&item_id).cloned()
     }
@@ -3121,6 +3515,134 @@ mod tests {
         assert_item_labels(&pane, [], cx);
     }
 
+    #[gpui::test]
+    async fn test_pinned_tabs(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        set_labeled_items(&pane, ["A", "B", "C", "D*"], cx);
+
+        // Pinned tabs move to the left, after the other pinned tabs.
+        pane.update(cx, |pane, cx| pane.toggle_pin_tab(&TogglePinTab, cx));
+        assert_item_labels(&pane, ["D*", "A", "B", "C"], cx);
+        pane.update(cx, |pane, cx| pane.pin_tab_at(2, cx));
+        assert_item_labels(&pane, ["D*", "B", "A", "C"], cx);
+        pane.update(cx, |pane, _| assert_eq!(pane.pinned_count(), 2));
+
+        // New items open after the pinned tabs.
+        pane.update(cx, |pane, cx| pane.activate_item(0, false, false, cx));
+        add_labeled_item(&pane, "E", false, cx);
+        assert_item_labels(&pane, ["D", "B", "E*", "A", "C"], cx);
+
+        // Closing the other items or all items keeps the pinned tabs.
+        pane.update(cx, |pane, cx| {
+            pane.close_inactive_items(&CloseInactiveItems { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        assert_item_labels(&pane, ["D", "B", "E*"], cx);
+
+        pane.update(cx, |pane, cx| {
+            pane.close_all_items(&CloseAllItems { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        assert_item_labels(&pane, ["D*", "B"], cx);
+
+        // Unpinned tabs move to the right of the remaining pinned tabs.
+        pane.update(cx, |pane, cx| pane.unpin_tab_at(0, cx));
+        assert_item_labels(&pane, ["B", "D*"], cx);
+        pane.update(cx, |pane, _| assert_eq!(pane.pinned_count(), 1));
+    }
+
+    #[gpui::test]
+    async fn test_split_item_by_id(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        let [_, item_b, _] = set_labeled_items(&pane, ["A", "B", "C*"], cx);
+        pane.update(cx, |pane, cx| {
+            pane.split_item_by_id(item_b.item_id(), SplitDirection::Right, cx)
+        });
+        cx.executor().run_until_parked();
+
+        assert_item_labels(&pane, ["A", "B*", "C"], cx);
+        let new_pane = workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.panes().len(), 2);
+            workspace.panes()[1].clone()
+        });
+        assert_item_labels(&new_pane, ["B*"], cx);
+    }
+
+    #[gpui::test]
+    async fn test_alternate_file(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        set_labeled_items(&pane, ["A", "B", "C", "D*"], cx);
+        pane.update(cx, |pane, cx| pane.activate_item(1, false, false, cx));
+        assert_item_labels(&pane, ["A", "B*", "C", "D"], cx);
+
+        // Toggles between the two most recently used items, regardless of tab order.
+        pane.update(cx, |pane, cx| pane.alternate_file(cx));
+        assert_item_labels(&pane, ["A", "B", "C", "D*"], cx);
+        pane.update(cx, |pane, cx| pane.alternate_file(cx));
+        assert_item_labels(&pane, ["A", "B*", "C", "D"], cx);
+
+        // Closing the alternate item falls back to the one used before it.
+        pane.update(cx, |pane, cx| pane.remove_item(3, false, false, cx));
+        pane.update(cx, |pane, cx| pane.alternate_file(cx));
+        assert_item_labels(&pane, ["A", "B", "C*"], cx);
+    }
+
+    #[gpui::test]
+    async fn test_navigate_history_by(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        let item = cx.new_view(|cx| TestItem::new(cx));
+        pane.update(cx, |pane, cx| {
+            pane.add_item(Box::new(item.clone()), true, true, None, cx)
+        });
+        item.update(cx, |item, cx| {
+            item.set_state("one".to_string(), cx);
+            item.set_state("two".to_string(), cx);
+            item.set_state("three".to_string(), cx);
+        });
+        pane.update(cx, |pane, cx| {
+            assert_eq!(pane.nav_history.entry_count(NavigationMode::GoingBack), 3);
+            assert_eq!(
+                pane.nav_history_menu_labels(NavigationMode::GoingBack, cx),
+                ["untitled", "untitled", "untitled"]
+            );
+            pane.navigate_history_by(NavigationMode::GoingBack, 2, cx);
+        });
+        cx.executor().run_until_parked();
+
+        item.update(cx, |item, _| assert_eq!(item.state, "one"));
+        pane.update(cx, |pane, _| {
+            assert_eq!(pane.nav_history.entry_count(NavigationMode::GoingBack), 1);
+        });
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
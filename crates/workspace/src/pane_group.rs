@@ -129,6 +129,11 @@ impl PaneGroup {
     pub(crate) fn first_pane(&self) -> View<Pane> {
         self.root.first_pane()
     }
+
+    /// The number of panes that are laid out side by side, across the widest row of the group.
+    pub(crate) fn column_count(&self) -> usize {
+        self.root.column_count()
+    }
 }
 
 #[derive(Clone)]
@@ -169,6 +174,19 @@ impl Member {
         }
     }
 
+    fn column_count(&self) -> usize {
+        match self {
+            Member::Axis(axis) => {
+                let column_counts = axis.members.iter().map(Member::column_count);
+                match axis.axis {
+                    Axis::Horizontal => column_counts.sum(),
+                    Axis::Vertical => column_counts.max().unwrap_or(1),
+                }
+            }
+            Member::Pane(_) => 1,
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn render(
         &self,
@@ -412,7 +412,12 @@ impl SerializedPane {
         }
 
         let mut items = Vec::new();
-        for item_handle in futures::future::join_all(item_tasks).await {
+        let mut pinned_count = 0;
+        for (item_handle, item) in futures::future::join_all(item_tasks)
+            .await
+            .into_iter()
+            .zip(&self.children)
+        {
             let item_handle = item_handle.log_err();
             items.push(item_handle.clone());
 
@@ -420,9 +425,16 @@ impl SerializedPane {
                 pane.update(cx, |pane, cx| {
                     pane.add_item(item_handle.clone(), true, true, None, cx);
                 })?;
+                if item.pinned {
+                    pinned_count += 1;
+                }
             }
         }
 
+        if pinned_count > 0 {
+            pane.update(cx, |pane, cx| pane.set_pinned_count(pinned_count, cx))?;
+        }
+
         if let Some(active_item_index) = active_item_index {
             pane.update(cx, |pane, cx| {
                 pane.activate_item(active_item_index, false, false, cx);
@@ -451,6 +463,7 @@ pub struct SerializedItem {
     pub item_id: ItemId,
     pub active: bool,
     pub preview: bool,
+    pub pinned: bool,
 }
 
 impl SerializedItem {
@@ -460,6 +473,7 @@ impl SerializedItem {
             item_id,
             active,
             preview,
+            pinned: false,
         }
     }
 }
@@ -472,13 +486,14 @@ impl Default for SerializedItem {
             item_id: 100000,
             active: false,
             preview: false,
+            pinned: false,
         }
     }
 }
 
 impl StaticColumnCount for SerializedItem {
     fn column_count() -> usize {
-        4
+        5
     }
 }
 impl Bind for &SerializedItem {
@@ -486,7 +501,8 @@ impl Bind for &SerializedItem {
         let next_index = statement.bind(&self.kind, start_index)?;
         let next_index = statement.bind(&self.item_id, next_index)?;
         let next_index = statement.bind(&self.active, next_index)?;
-        statement.bind(&self.preview, next_index)
+        let next_index = statement.bind(&self.preview, next_index)?;
+        statement.bind(&self.pinned, next_index)
     }
 }
 
@@ -496,12 +512,14 @@ impl Column for SerializedItem {
         let (item_id, next_index) = ItemId::column(statement, next_index)?;
         let (active, next_index) = bool::column(statement, next_index)?;
         let (preview, next_index) = bool::column(statement, next_index)?;
+        let (pinned, next_index) = Option::<bool>::column(statement, next_index)?;
         Ok((
             SerializedItem {
                 kind,
                 item_id,
                 active,
                 preview,
+                pinned: pinned.unwrap_or(false),
             },
             next_index,
         ))
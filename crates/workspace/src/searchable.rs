@@ -70,6 +70,16 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
     fn query_suggestion(&mut self, cx: &mut ViewContext<Self>) -> String;
     fn activate_match(&mut self, index: usize, matches: &[Self::Match], cx: &mut ViewContext<Self>);
     fn select_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>);
+    /// Adds a selection for the match while keeping the existing selections, for items that
+    /// support multiple selections.
+    fn add_match_to_selections(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.activate_match(index, matches, cx);
+    }
     fn replace(&mut self, _: &Self::Match, _: &SearchQuery, _: &mut ViewContext<Self>);
     fn replace_all(
         &mut self,
@@ -127,6 +137,12 @@ pub trait SearchableItemHandle: ItemHandle {
     fn query_suggestion(&self, cx: &mut WindowContext) -> String;
     fn activate_match(&self, index: usize, matches: &AnyVec<dyn Send>, cx: &mut WindowContext);
     fn select_matches(&self, matches: &AnyVec<dyn Send>, cx: &mut WindowContext);
+    fn add_match_to_selections(
+        &self,
+        index: usize,
+        matches: &AnyVec<dyn Send>,
+        cx: &mut WindowContext,
+    );
     fn replace(
         &self,
         _: any_vec::element::ElementRef<'_, dyn Send>,
@@ -160,6 +176,8 @@ pub trait SearchableItemHandle: ItemHandle {
     fn search_bar_visibility_changed(&self, visible: bool, cx: &mut WindowContext);
 
     fn toggle_filtered_search_ranges(&mut self, enabled: bool, cx: &mut WindowContext);
+
+    fn has_filtered_search_ranges(&self, cx: &mut WindowContext) -> bool;
 }
 
 impl<T: SearchableItem> SearchableItemHandle for View<T> {
@@ -205,6 +223,18 @@ impl<T: SearchableItem> SearchableItemHandle for View<T> {
         self.update(cx, |this, cx| this.select_matches(matches.as_slice(), cx));
     }
 
+    fn add_match_to_selections(
+        &self,
+        index: usize,
+        matches: &AnyVec<dyn Send>,
+        cx: &mut WindowContext,
+    ) {
+        let matches = matches.downcast_ref().unwrap();
+        self.update(cx, |this, cx| {
+            this.add_match_to_selections(index, matches.as_slice(), cx)
+        });
+    }
+
     fn match_index_for_direction(
         &self,
         matches: &AnyVec<dyn Send>,
@@ -279,6 +309,10 @@ impl<T: SearchableItem> SearchableItemHandle for View<T> {
             this.toggle_filtered_search_ranges(enabled, cx)
         });
     }
+
+    fn has_filtered_search_ranges(&self, cx: &mut WindowContext) -> bool {
+        self.update(cx, |this, _| this.has_filtered_search_ranges())
+    }
 }
 
 impl From<Box<dyn SearchableItemHandle>> for AnyView {
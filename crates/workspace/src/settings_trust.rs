@@ -0,0 +1,145 @@
+use crate::{TrustProjectSettings, Workspace};
+use collections::HashSet;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{AppContext, Global, PromptLevel, ViewContext};
+use project::{project_settings::TrustedWorktrees, WorktreeId};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt;
+
+const TRUSTED_WORKTREES_KEY: &str = "trusted_worktrees";
+const DISTRUSTED_WORKTREES_KEY: &str = "distrusted_worktrees";
+
+/// The absolute paths of the worktrees whose local settings the user chose not to trust,
+/// which aren't prompted for again until asked to with [`TrustProjectSettings`].
+#[derive(Default)]
+struct DistrustedWorktrees(HashSet<Arc<Path>>);
+
+impl Global for DistrustedWorktrees {}
+
+/// Loads the worktrees whose local settings were trusted to run commands, or not.
+pub(crate) fn init(cx: &mut AppContext) {
+    let trusted_paths = read_paths(TRUSTED_WORKTREES_KEY);
+    cx.set_global(TrustedWorktrees::new(trusted_paths));
+    let distrusted_paths = read_paths(DISTRUSTED_WORKTREES_KEY);
+    cx.set_global(DistrustedWorktrees(distrusted_paths.collect()));
+}
+
+/// Asks whether to trust the local settings of a worktree that run commands, such as
+/// external formatters, language server binaries and tasks. Unless `ask_again` is set,
+/// worktrees that the user chose not to trust aren't prompted for.
+pub(crate) fn prompt_to_trust_worktree(
+    workspace: &mut Workspace,
+    worktree_id: WorktreeId,
+    ask_again: bool,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let Some(worktree) = project.read(cx).worktree_for_id(worktree_id, cx) else {
+        return;
+    };
+    let worktree_abs_path = worktree.read(cx).abs_path();
+    if !ask_again
+        && cx
+            .try_global::<DistrustedWorktrees>()
+            .map_or(false, |distrusted| {
+                distrusted.0.contains(&worktree_abs_path)
+            })
+    {
+        return;
+    }
+    let key_paths = project.read(cx).untrusted_local_settings(worktree_id);
+    if key_paths.is_empty() {
+        return;
+    }
+    let message = format!(
+        "Do you trust the settings of {}?",
+        worktree.read(cx).root_name()
+    );
+    let detail = format!(
+        "Its local settings make Zed run commands with these settings and tasks files, which are ignored until you trust it:\n\n{}",
+        key_paths.join("\n")
+    );
+    let answer = cx.prompt(
+        PromptLevel::Warning,
+        &message,
+        Some(&detail),
+        &["Trust", "Don't Trust"],
+    );
+    cx.spawn(|_, mut cx| async move {
+        let trusted = answer.await? == 0;
+        cx.update(|cx| {
+            if trusted {
+                project.update(cx, |project, cx| project.trust_worktree(worktree_id, cx));
+            }
+            let distrusted = cx.default_global::<DistrustedWorktrees>();
+            if trusted {
+                distrusted.0.remove(&worktree_abs_path);
+            } else {
+                distrusted.0.insert(worktree_abs_path);
+            }
+            save_paths(cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Prompts to trust the local settings of the workspace's worktrees that were ignored,
+/// including the ones that the user chose not to trust before.
+pub(crate) fn trust_project_settings(
+    workspace: &mut Workspace,
+    _: &TrustProjectSettings,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let worktree_ids = workspace
+        .project()
+        .read(cx)
+        .worktrees(cx)
+        .map(|worktree| worktree.read(cx).id())
+        .collect::<Vec<_>>();
+    for worktree_id in worktree_ids {
+        prompt_to_trust_worktree(workspace, worktree_id, true, cx);
+    }
+}
+
+fn read_paths(key: &str) -> impl Iterator<Item = Arc<Path>> {
+    KEY_VALUE_STORE
+        .read_kvp(key)
+        .log_err()
+        .flatten()
+        .and_then(|paths| serde_json::from_str::<Vec<PathBuf>>(&paths).log_err())
+        .unwrap_or_default()
+        .into_iter()
+        .map(Arc::from)
+}
+
+fn save_paths(cx: &mut AppContext) {
+    let trusted_paths = cx
+        .try_global::<TrustedWorktrees>()
+        .into_iter()
+        .flat_map(|trusted| trusted.paths())
+        .map(|path| path.to_path_buf())
+        .collect::<Vec<_>>();
+    let distrusted_paths = cx
+        .try_global::<DistrustedWorktrees>()
+        .into_iter()
+        .flat_map(|distrusted| distrusted.0.iter())
+        .map(|path| path.to_path_buf())
+        .collect::<Vec<_>>();
+    let (Some(trusted_paths), Some(distrusted_paths)) = (
+        serde_json::to_string(&trusted_paths).log_err(),
+        serde_json::to_string(&distrusted_paths).log_err(),
+    ) else {
+        return;
+    };
+    db::write_and_log(cx, move || async move {
+        KEY_VALUE_STORE
+            .write_kvp(TRUSTED_WORKTREES_KEY.to_string(), trusted_paths)
+            .await?;
+        KEY_VALUE_STORE
+            .write_kvp(DISTRUSTED_WORKTREES_KEY.to_string(), distrusted_paths)
+            .await
+    });
+}
@@ -0,0 +1,158 @@
+use gpui::AppContext;
+use project::{Project, ProjectPath};
+
+/// Returns the value of a window or tab title variable, for the given project and the
+/// path of its active item.
+pub fn title_variable(
+    name: &str,
+    project: &Project,
+    project_path: Option<&ProjectPath>,
+    is_dirty: bool,
+    cx: &AppContext,
+) -> Option<String> {
+    let value = match name {
+        "project" => project
+            .worktree_root_names(cx)
+            .collect::<Vec<_>>()
+            .join(", "),
+        "file" => project_path
+            .and_then(|project_path| project_path.path.file_name())
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        "path" => project_path
+            .map(|project_path| project_path.path.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        "dirty" => if is_dirty { "● " } else { "" }.to_string(),
+        "branch" => {
+            let repository = match project_path {
+                Some(project_path) => project
+                    .worktree_for_id(project_path.worktree_id, cx)?
+                    .read(cx)
+                    .repository_for_path(&project_path.path),
+                None => project
+                    .visible_worktrees(cx)
+                    .find_map(|worktree| worktree.read(cx).root_git_entry()),
+            };
+            repository
+                .and_then(|repository| repository.branch())
+                .map(|branch| branch.to_string())
+                .unwrap_or_default()
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Expands a title template such as `"{dirty}{file} — {project} ({branch})"`.
+///
+/// Each `{name}` is replaced with the value that `variable` returns for it, or left as is
+/// when there's no such variable. Separators left dangling by empty values, as well as
+/// empty parentheses and brackets, are removed, so that `"{file} — {project}"` becomes
+/// `"{project}"` when there's no file.
+pub fn expand_title_template(template: &str, variable: impl Fn(&str) -> Option<String>) -> String {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        literal.push_str(&rest[..start]);
+        match variable(name) {
+            Some(value) => {
+                parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                parts.push(TemplatePart::Value(value));
+            }
+            None => literal.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    literal.push_str(rest);
+    parts.push(TemplatePart::Literal(literal));
+
+    for ix in 0..parts.len() {
+        if !matches!(&parts[ix], TemplatePart::Value(value) if value.is_empty()) {
+            continue;
+        }
+
+        // An empty value drops the brackets around it, and the separator before it, or
+        // after it when it comes before any other value.
+        if let (TemplatePart::Literal(before), TemplatePart::Literal(after)) =
+            (&parts[ix - 1], &parts[ix + 1])
+        {
+            if let Some(close) = [("(", ")"), ("[", "]")]
+                .into_iter()
+                .find(|(open, close)| before.ends_with(open) && after.starts_with(close))
+                .map(|(_, close)| close)
+            {
+                let before = before[..before.len() - 1].to_string();
+                let after = after[close.len()..].to_string();
+                parts[ix - 1] = TemplatePart::Literal(before);
+                parts[ix + 1] = TemplatePart::Literal(after);
+            }
+        }
+        let follows_value = parts[..ix]
+            .iter()
+            .any(|part| matches!(part, TemplatePart::Value(value) if !value.is_empty()));
+        let separator_ix = if follows_value { ix - 1 } else { ix + 1 };
+        if let TemplatePart::Literal(separator) = &parts[separator_ix] {
+            if is_separator(separator) {
+                parts[separator_ix] = TemplatePart::Literal(String::new());
+            }
+        }
+    }
+
+    let title = parts
+        .into_iter()
+        .map(|part| match part {
+            TemplatePart::Literal(text) | TemplatePart::Value(text) => text,
+        })
+        .collect::<String>();
+    title.trim().to_string()
+}
+
+enum TemplatePart {
+    Literal(String),
+    Value(String),
+}
+
+fn is_separator(text: &str) -> bool {
+    text.chars()
+        .all(|c| c.is_whitespace() || matches!(c, '-' | '—' | '–' | '|' | '·' | ',' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_title_template() {
+        let variables = |name: &str| match name {
+            "file" => Some("main.rs".to_string()),
+            "project" => Some("zed".to_string()),
+            "branch" => Some(String::new()),
+            "dirty" => Some("● ".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_title_template("{dirty}{file} — {project} ({branch})", variables),
+            "● main.rs — zed"
+        );
+        assert_eq!(
+            expand_title_template("{branch} | {project} [{unknown}]", variables),
+            "zed [{unknown}]"
+        );
+
+        let no_file = |name: &str| match name {
+            "file" | "dirty" => Some(String::new()),
+            "project" => Some("zed".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_title_template("{dirty}{file} — {project}", no_file),
+            "zed"
+        );
+        assert_eq!(expand_title_template("{file}", no_file), "");
+    }
+}
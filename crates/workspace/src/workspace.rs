@@ -1,14 +1,17 @@
 pub mod dock;
 pub mod item;
+pub mod layouts;
 mod modal_layer;
 pub mod notifications;
 pub mod pane;
 pub mod pane_group;
 mod persistence;
 pub mod searchable;
+mod settings_trust;
 pub mod shared_screen;
 mod status_bar;
 pub mod tasks;
+pub mod title_template;
 mod toolbar;
 mod workspace_settings;
 
@@ -26,7 +29,7 @@ use futures::{
         mpsc::{self, UnboundedReceiver, UnboundedSender},
         oneshot,
     },
-    future::try_join_all,
+    future::{join_all, try_join_all, LocalBoxFuture},
     Future, FutureExt, StreamExt,
 };
 use gpui::{
@@ -60,7 +63,7 @@ use project::{
 };
 use serde::Deserialize;
 use session::AppSession;
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use shared_screen::SharedScreen;
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
@@ -69,7 +72,7 @@ use sqlez::{
 use status_bar::StatusBar;
 pub use status_bar::StatusItemView;
 use std::{
-    any::TypeId,
+    any::{Any, TypeId},
     borrow::Cow,
     cell::RefCell,
     cmp,
@@ -134,6 +137,8 @@ actions!(
         CopyRelativePath,
         Feedback,
         FollowNextCollaborator,
+        GoToLastEditLocation,
+        MoveItemToNewWindow,
         NewCenterTerminal,
         NewFile,
         NewFileSplitVertical,
@@ -148,9 +153,11 @@ actions!(
         SaveWithoutFormat,
         ToggleBottomDock,
         ToggleCenteredLayout,
+        ToggleZenMode,
         ToggleLeftDock,
         ToggleRightDock,
         ToggleZoom,
+        TrustProjectSettings,
         Unfollow,
         Welcome,
     ]
@@ -316,6 +323,7 @@ pub fn init_settings(cx: &mut AppContext) {
 pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
     init_settings(cx);
     notifications::init(cx);
+    settings_trust::init(cx);
 
     cx.on_action(Workspace::close_global);
     cx.on_action(reload);
@@ -545,6 +553,44 @@ pub fn register_serializable_item<I: SerializableItem>(cx: &mut AppContext) {
         .insert(TypeId::of::<I>(), descriptor);
 }
 
+#[derive(Clone, Default, Deref, DerefMut)]
+struct PanelLoaders(Vec<PanelLoader>);
+
+type PanelLoader = Arc<
+    dyn Fn(WeakView<Workspace>, AsyncWindowContext) -> LocalBoxFuture<'static, Result<PanelAdder>>,
+>;
+
+type PanelAdder = Box<dyn FnOnce(&mut Workspace, &mut ViewContext<Workspace>)>;
+
+impl Global for PanelLoaders {}
+
+/// Registers a [Panel] for the app, so that crates can contribute panels without the
+/// workspace knowing about them. Registered panels are loaded into every workspace by
+/// [Workspace::load_registered_panels], in the order they were registered. Their icons,
+/// default positions and sizes come from their [Panel] implementations, and they can
+/// persist their own state with [dock::read_panel_state] and [dock::write_panel_state].
+pub fn register_panel<T, F>(
+    cx: &mut AppContext,
+    load: impl Fn(WeakView<Workspace>, AsyncWindowContext) -> F + 'static,
+) where
+    T: Panel,
+    F: Future<Output = Result<View<T>>> + 'static,
+{
+    cx.default_global::<PanelLoaders>()
+        .push(Arc::new(move |workspace, cx| {
+            let panel = load(workspace, cx);
+            async move {
+                let panel = panel.await?;
+                Ok(Box::new(
+                    move |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+                        workspace.add_panel(panel, cx)
+                    },
+                ) as PanelAdder)
+            }
+            .boxed_local()
+        }));
+}
+
 pub struct AppState {
     pub languages: Arc<LanguageRegistry>,
     pub client: Arc<Client>,
@@ -588,7 +634,6 @@ impl AppState {
     pub fn test(cx: &mut AppContext) -> Arc<Self> {
         use node_runtime::FakeNodeRuntime;
         use session::Session;
-        use settings::SettingsStore;
         use ui::Context as _;
 
         if !cx.has_global::<SettingsStore>() {
@@ -750,6 +795,8 @@ pub struct Workspace {
     pane_history_timestamp: Arc<AtomicUsize>,
     bounds: Bounds<Pixels>,
     centered_layout: bool,
+    zen_mode: Option<ZenMode>,
+    last_edit_location: Option<EditLocation>,
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
@@ -783,6 +830,7 @@ struct FollowerView {
 impl Workspace {
     const DEFAULT_PADDING: f32 = 0.2;
     const MAX_PADDING: f32 = 0.4;
+    const DEFAULT_ZEN_MODE_WIDTH: f32 = 0.6;
 
     pub fn new(
         workspace_id: Option<WorkspaceId>,
@@ -806,6 +854,10 @@ impl Workspace {
                     this.serialize_workspace(cx);
                 }
 
+                project::Event::WorktreeUpdatedGitRepositories => {
+                    this.update_window_title(cx);
+                }
+
                 project::Event::DisconnectedFromHost => {
                     this.update_window_edited(cx);
                     let leaders_to_unfollow =
@@ -837,6 +889,10 @@ impl Workspace {
                     )
                 }
 
+                project::Event::UntrustedLocalSettings(worktree_id) => {
+                    settings_trust::prompt_to_trust_worktree(this, *worktree_id, false, cx);
+                }
+
                 project::Event::LanguageServerPrompt(request) => {
                     struct LanguageServerPrompt;
 
@@ -982,6 +1038,7 @@ impl Workspace {
                 }));
                 cx.notify();
             }),
+            cx.observe_global::<SettingsStore>(|this, cx| this.update_window_title(cx)),
             cx.observe_window_appearance(|_, cx| {
                 let window_appearance = cx.appearance();
 
@@ -1047,6 +1104,8 @@ impl Workspace {
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             centered_layout: false,
+            zen_mode: None,
+            last_edit_location: None,
             bounds_save_task_queued: None,
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
@@ -1186,7 +1245,10 @@ impl Workspace {
 
             notify_if_database_failed(window, &mut cx);
             let opened_items = window
-                .update(&mut cx, |_workspace, cx| {
+                .update(&mut cx, |workspace, cx| {
+                    if serialized_workspace.is_none() {
+                        layouts::apply_project_layout(workspace, cx);
+                    }
                     open_items(serialized_workspace, project_paths, app_state, cx)
                 })?
                 .await
@@ -1235,6 +1297,27 @@ impl Workspace {
         });
     }
 
+    /// Loads the panels registered with [register_panel] and adds them to the workspace.
+    /// A panel that fails to load is skipped without affecting the others.
+    pub async fn load_registered_panels(
+        workspace: WeakView<Self>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<()> {
+        let loaders =
+            cx.update(|cx| cx.try_global::<PanelLoaders>().cloned().unwrap_or_default())?;
+        let panels = join_all(
+            loaders
+                .iter()
+                .map(|load| load(workspace.clone(), cx.clone())),
+        )
+        .await;
+        workspace.update(&mut cx, |workspace, cx| {
+            for add_panel in panels.into_iter().filter_map(|panel| panel.log_err()) {
+                add_panel(workspace, cx);
+            }
+        })
+    }
+
     pub fn status_bar(&self) -> &View<StatusBar> {
         &self.status_bar
     }
@@ -1413,6 +1496,56 @@ impl Workspace {
         self.navigate_history(pane, NavigationMode::GoingForward, cx)
     }
 
+    pub(crate) fn record_edit_location(&mut self, item: &dyn ItemHandle, cx: &mut WindowContext) {
+        self.last_edit_location = Some(EditLocation {
+            item: item.downgrade_item(),
+            project_path: item.project_path(cx),
+            data: item.navigation_data(cx),
+        });
+    }
+
+    /// Activates the item that was edited last, at the location of the edit, reopening
+    /// the item if it was closed.
+    pub fn go_to_last_edit_location(
+        &mut self,
+        _: &GoToLastEditLocation,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(EditLocation {
+            item,
+            project_path,
+            data,
+        }) = self.last_edit_location.take()
+        else {
+            return;
+        };
+
+        let open_item = item.upgrade().and_then(|item| {
+            let pane = self.panes_by_item.get(&item.item_id())?.upgrade()?;
+            let index = pane.read(cx).index_for_item(item.as_ref())?;
+            pane.update(cx, |pane, cx| pane.activate_item(index, true, true, cx));
+            Some(item)
+        });
+        if let Some(item) = open_item {
+            if let Some(data) = data {
+                item.navigate(data, cx);
+            }
+            self.record_edit_location(item.as_ref(), cx);
+        } else if let Some(project_path) = project_path {
+            let open_task = self.open_path(project_path, None, true, cx);
+            cx.spawn(|workspace, mut cx| async move {
+                let item = open_task.await?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    if let Some(data) = data {
+                        item.navigate(data, cx);
+                    }
+                    workspace.record_edit_location(item.as_ref(), cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
     pub fn reopen_closed_item(&mut self, cx: &mut ViewContext<Workspace>) -> Task<Result<()>> {
         self.navigate_history(
             self.active_pane().downgrade(),
@@ -2413,6 +2546,9 @@ impl Workspace {
             )
         });
         cx.subscribe(&pane, Self::handle_pane_event).detach();
+        if self.zen_mode.is_some() {
+            pane.update(cx, |pane, cx| pane.set_zen_mode(true, cx));
+        }
         self.panes.push(pane.clone());
         cx.focus_view(&pane);
         cx.emit(Event::PaneAdded(pane.clone()));
@@ -2930,9 +3066,6 @@ impl Workspace {
         self.zoomed_position = None;
         cx.emit(Event::ZoomChanged);
         self.update_active_view_for_followers(cx);
-        pane.model.update(cx, |pane, _| {
-            pane.track_alternate_file_items();
-        });
 
         cx.notify();
     }
@@ -2957,9 +3090,6 @@ impl Workspace {
             }
             pane::Event::Remove => self.remove_pane(pane, cx),
             pane::Event::ActivateItem { local } => {
-                pane.model.update(cx, |pane, _| {
-                    pane.track_alternate_file_items();
-                });
                 if *local {
                     self.unfollow_in_pane(&pane, cx);
                 }
@@ -3059,6 +3189,59 @@ impl Workspace {
         maybe_pane_handle
     }
 
+    /// Moves the item out of the pane into a new window. The new window gets its own
+    /// workspace for the same project, so the item keeps sharing its buffers and language
+    /// servers with this workspace, and its own database id, so that it's restored too.
+    pub fn move_item_to_new_window(
+        &mut self,
+        pane: &View<Pane>,
+        item_id: EntityId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let pane = pane.downgrade();
+        cx.spawn(|this, mut cx| async move {
+            let workspace_id = DB.next_id().await?;
+            this.update(&mut cx, |this, cx| {
+                let pane = pane.upgrade()?;
+                let (item_ix, item) = pane.read(cx).index_for_item_id(item_id).and_then(|ix| {
+                    let item = pane.read(cx).item_for_index(ix)?.boxed_clone();
+                    Some((ix, item))
+                })?;
+
+                let project = this.project.clone();
+                let app_state = this.app_state.clone();
+                let options = (app_state.build_window_options)(None, cx);
+                let window = cx
+                    .open_window(options, |cx| {
+                        cx.new_view(|cx| {
+                            Workspace::new(Some(workspace_id), project, app_state.clone(), cx)
+                        })
+                    })
+                    .log_err()?;
+
+                // The item is cloned from within the new window, so that the clone is bound
+                // to it.
+                let moved = window
+                    .update(cx, |workspace, cx| {
+                        let Some(clone) = item.clone_on_split(Some(workspace_id), cx) else {
+                            return false;
+                        };
+                        workspace.add_item_to_active_pane(clone, None, true, cx);
+                        true
+                    })
+                    .unwrap_or(false);
+                if moved {
+                    pane.update(cx, |pane, cx| pane.remove_item(item_ix, false, true, cx));
+                } else {
+                    window.update(cx, |_, cx| cx.remove_window()).log_err();
+                }
+                Some(())
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn split_pane_with_item(
         &mut self,
         pane_to_split: WeakView<Pane>,
@@ -3357,31 +3540,40 @@ impl Workspace {
         let project = self.project().read(cx);
         let mut title = String::new();
 
-        if let Some(path) = self.active_item(cx).and_then(|item| item.project_path(cx)) {
-            let filename = path
-                .path
-                .file_name()
-                .map(|s| s.to_string_lossy())
-                .or_else(|| {
-                    Some(Cow::Borrowed(
-                        project
-                            .worktree_for_id(path.worktree_id, cx)?
-                            .read(cx)
-                            .root_name(),
-                    ))
-                });
+        if let Some(template) = WorkspaceSettings::get_global(cx).window_title.as_deref() {
+            let active_item = self.active_item(cx);
+            let project_path = active_item.as_ref().and_then(|item| item.project_path(cx));
+            let is_dirty = active_item.map_or(false, |item| item.is_dirty(cx));
+            title = title_template::expand_title_template(template, |name| {
+                title_template::title_variable(name, project, project_path.as_ref(), is_dirty, cx)
+            });
+        } else {
+            if let Some(path) = self.active_item(cx).and_then(|item| item.project_path(cx)) {
+                let filename = path
+                    .path
+                    .file_name()
+                    .map(|s| s.to_string_lossy())
+                    .or_else(|| {
+                        Some(Cow::Borrowed(
+                            project
+                                .worktree_for_id(path.worktree_id, cx)?
+                                .read(cx)
+                                .root_name(),
+                        ))
+                    });
 
-            if let Some(filename) = filename {
-                title.push_str(filename.as_ref());
-                title.push_str(" — ");
+                if let Some(filename) = filename {
+                    title.push_str(filename.as_ref());
+                    title.push_str(" — ");
+                }
             }
-        }
 
-        for (i, name) in project.worktree_root_names(cx).enumerate() {
-            if i > 0 {
-                title.push_str(", ");
+            for (i, name) in project.worktree_root_names(cx).enumerate() {
+                if i > 0 {
+                    title.push_str(", ");
+                }
+                title.push_str(name);
             }
-            title.push_str(name);
         }
 
         if title.is_empty() {
@@ -3874,7 +4066,7 @@ impl Workspace {
                     }
                     for item in pane.items() {
                         if matches!(
-                            item.workspace_settings(cx).autosave,
+                            item.autosave_setting(cx),
                             AutosaveSetting::OnWindowChange | AutosaveSetting::OnFocusChange
                         ) {
                             Pane::autosave_item(item.as_ref(), self.project.clone(), cx)
@@ -3988,6 +4180,9 @@ impl Workspace {
                                 item_id: handle.item_id().as_u64(),
                                 active: Some(handle.item_id()) == active_item_id,
                                 preview: pane.is_active_preview_item(handle.item_id()),
+                                pinned: pane
+                                    .index_for_item_id(handle.item_id())
+                                    .map_or(false, |ix| pane.is_tab_pinned(ix)),
                             })
                         })
                         .collect::<Vec<_>>(),
@@ -4358,6 +4553,15 @@ impl Workspace {
                 }),
             )
             .on_action(cx.listener(Workspace::toggle_centered_layout))
+            .on_action(cx.listener(settings_trust::trust_project_settings))
+            .on_action(cx.listener(Workspace::toggle_zen_mode))
+            .on_action(cx.listener(Workspace::go_to_last_edit_location))
+            .on_action(cx.listener(|workspace, _: &MoveItemToNewWindow, cx| {
+                let pane = workspace.active_pane.clone();
+                if let Some(item_id) = pane.read(cx).active_item().map(|item| item.item_id()) {
+                    workspace.move_item_to_new_window(&pane, item_id, cx);
+                }
+            }))
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -4434,6 +4638,35 @@ impl Workspace {
         cx.notify();
     }
 
+    pub fn is_zen_mode(&self) -> bool {
+        self.zen_mode.is_some()
+    }
+
+    /// Hides the docks, the tab bars, the status bar and the items' decorations, and centers
+    /// the panes, or restores them when already in zen mode.
+    pub fn toggle_zen_mode(&mut self, _: &ToggleZenMode, cx: &mut ViewContext<Self>) {
+        if let Some(zen_mode) = self.zen_mode.take() {
+            for dock in zen_mode.open_docks {
+                dock.update(cx, |dock, cx| dock.set_open(true, cx));
+            }
+        } else {
+            let mut open_docks = Vec::new();
+            for dock in [&self.left_dock, &self.right_dock, &self.bottom_dock] {
+                if dock.read(cx).is_open() {
+                    dock.update(cx, |dock, cx| dock.set_open(false, cx));
+                    open_docks.push(dock.clone());
+                }
+            }
+            self.zen_mode = Some(ZenMode { open_docks });
+        }
+
+        let zen_mode = self.zen_mode.is_some();
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_zen_mode(zen_mode, cx));
+        }
+        cx.notify();
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
@@ -4638,12 +4871,29 @@ impl FocusableView for Workspace {
 #[derive(Clone, Render)]
 struct DraggedDock(DockPosition);
 
+/// The parts of the layout that zen mode hid, to restore them when leaving it.
+struct ZenMode {
+    open_docks: Vec<View<Dock>>,
+}
+
+/// The item and location of an edit, to go back to it.
+struct EditLocation {
+    item: Box<dyn WeakItemHandle>,
+    project_path: Option<ProjectPath>,
+    data: Option<Box<dyn Any + Send>>,
+}
+
 impl Render for Workspace {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let mut context = KeyContext::new_with_defaults();
         context.add("Workspace");
+        let centered_layout_settings = WorkspaceSettings::get_global(cx).centered_layout;
+        let centered_max_width = centered_layout_settings
+            .max_width
+            .filter(|max_width| *max_width > 0.0)
+            .map(|max_width| px(max_width) * self.center.column_count() as f32);
         let centered_layout = self.centered_layout
-            && self.center.panes().len() == 1
+            && (self.center.panes().len() == 1 || centered_max_width.is_some())
             && self.active_item(cx).is_some();
         let render_padding = |size| {
             (size > 0.0).then(|| {
@@ -4654,11 +4904,32 @@ impl Render for Workspace {
                     .border_color(cx.theme().colors().pane_group_border)
             })
         };
-        let paddings = if centered_layout {
-            let settings = WorkspaceSettings::get_global(cx).centered_layout;
+        let mut center_max_width = None;
+        let paddings = if self.zen_mode.is_some() {
+            let width = WorkspaceSettings::get_global(cx)
+                .zen_mode
+                .width
+                .unwrap_or(Self::DEFAULT_ZEN_MODE_WIDTH)
+                .clamp(1.0 - 2.0 * Self::MAX_PADDING, 1.0);
+            let padding = (1.0 - width) / 2.0;
+            (render_padding(padding), render_padding(padding))
+        } else if let Some(max_width) = centered_max_width.filter(|_| centered_layout) {
+            let render_flexible_padding = || {
+                div()
+                    .h_full()
+                    .flex_1()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_color(cx.theme().colors().pane_group_border)
+            };
+            center_max_width = Some(max_width);
             (
-                render_padding(Self::adjust_padding(settings.left_padding)),
-                render_padding(Self::adjust_padding(settings.right_padding)),
+                Some(render_flexible_padding()),
+                Some(render_flexible_padding()),
+            )
+        } else if centered_layout {
+            (
+                render_padding(Self::adjust_padding(centered_layout_settings.left_padding)),
+                render_padding(Self::adjust_padding(centered_layout_settings.right_padding)),
             )
         } else {
             (None, None)
@@ -4748,15 +5019,27 @@ impl Render for Workspace {
                                                 .when_some(paddings.0, |this, p| {
                                                     this.child(p.border_r_1())
                                                 })
-                                                .child(self.center.render(
-                                                    &self.project,
-                                                    &self.follower_states,
-                                                    self.active_call(),
-                                                    &self.active_pane,
-                                                    self.zoomed.as_ref(),
-                                                    &self.app_state,
-                                                    cx,
-                                                ))
+                                                .map(|this| {
+                                                    let center = self.center.render(
+                                                        &self.project,
+                                                        &self.follower_states,
+                                                        self.active_call(),
+                                                        &self.active_pane,
+                                                        self.zoomed.as_ref(),
+                                                        &self.app_state,
+                                                        cx,
+                                                    );
+                                                    match center_max_width {
+                                                        Some(max_width) => this.child(
+                                                            h_flex()
+                                                                .h_full()
+                                                                .flex_1()
+                                                                .max_w(max_width)
+                                                                .child(center),
+                                                        ),
+                                                        None => this.child(center),
+                                                    }
+                                                })
                                                 .when_some(paddings.1, |this, p| {
                                                     this.child(p.border_l_1())
                                                 }),
@@ -4796,7 +5079,9 @@ impl Render for Workspace {
                         .child(self.modal_layer.clone())
                         .children(self.render_notifications(cx)),
                 )
-                .child(self.status_bar.clone())
+                .when(self.zen_mode.is_none(), |this| {
+                    this.child(self.status_bar.clone())
+                })
                 .children(if self.project.read(cx).is_disconnected() {
                     if let Some(render) = self.render_disconnected_overlay.take() {
                         let result = render(self, cx);
@@ -6302,6 +6587,143 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_registered_panels(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            register_panel(cx, |workspace, mut cx| async move {
+                workspace.update(&mut cx, |_, cx| {
+                    cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx))
+                })
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let load_panels = workspace.update(cx, |_, cx| {
+            cx.spawn(|workspace, cx| Workspace::load_registered_panels(workspace, cx))
+        });
+        load_panels.await.unwrap();
+
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.panel::<TestPanel>(cx).is_some());
+            assert_eq!(workspace.right_dock().read(cx).panels_len(), 1);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_toggle_zen_mode(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        workspace.update(cx, |workspace, cx| {
+            let left_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(left_panel, cx);
+            let right_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(right_panel, cx);
+            workspace
+                .left_dock()
+                .update(cx, |left_dock, cx| left_dock.set_open(true, cx));
+            workspace
+                .right_dock()
+                .update(cx, |right_dock, cx| right_dock.set_open(false, cx));
+
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+            assert!(workspace.is_zen_mode());
+            assert!(!workspace.left_dock().read(cx).is_open());
+            assert!(!workspace.right_dock().read(cx).is_open());
+            assert!(workspace.active_pane().read(cx).is_zen_mode());
+
+            // Panes that are split off while in zen mode are in zen mode too.
+            let pane = workspace.active_pane().clone();
+            let new_pane = workspace.split_pane(pane, SplitDirection::Right, cx);
+            assert!(new_pane.read(cx).is_zen_mode());
+
+            // Leaving zen mode reopens the docks that were open before.
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+            assert!(!workspace.is_zen_mode());
+            assert!(workspace.left_dock().read(cx).is_open());
+            assert!(!workspace.right_dock().read(cx).is_open());
+            assert!(workspace
+                .panes()
+                .iter()
+                .all(|pane| !pane.read(cx).is_zen_mode()));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_centered_layout_column_count(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // With a max width, each column of panes gets up to that width.
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.center.column_count(), 1);
+
+            let left_pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            assert_eq!(workspace.center.column_count(), 2);
+
+            // Panes stacked on top of each other share their column.
+            workspace.split_pane(right_pane.clone(), SplitDirection::Down, cx);
+            workspace.split_pane(left_pane, SplitDirection::Down, cx);
+            assert_eq!(workspace.center.column_count(), 2);
+
+            // The widest row decides the width.
+            workspace.split_pane(right_pane, SplitDirection::Right, cx);
+            assert_eq!(workspace.center.column_count(), 3);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_move_item_to_new_window(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let item = cx.new_view(|cx| TestItem::new(cx).with_label("A"));
+        let pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+            workspace.active_pane().clone()
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.move_item_to_new_window(&pane, item.item_id(), cx)
+        });
+        cx.run_until_parked();
+
+        assert_eq!(pane.read_with(cx, |pane, _| pane.items_len()), 0);
+        let window = cx.handle();
+        let new_workspace = cx
+            .windows()
+            .into_iter()
+            .find(|new_window| *new_window != window)
+            .and_then(|new_window| new_window.downcast::<Workspace>())
+            .unwrap();
+        new_workspace
+            .update(cx, |new_workspace, cx| {
+                assert_eq!(new_workspace.project(), &project);
+                assert!(new_workspace.database_id().is_some());
+                assert_ne!(
+                    new_workspace.database_id(),
+                    workspace.read(cx).database_id()
+                );
+                let item = new_workspace
+                    .active_item_as::<TestItem>(cx)
+                    .expect("item should be moved to the new window");
+                assert_eq!(item.read(cx).label, "A");
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use collections::HashMap;
 use gpui::AppContext;
+pub use language::language_settings::AutosaveSetting;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
@@ -11,6 +12,7 @@ pub struct WorkspaceSettings {
     pub pane_split_direction_horizontal: PaneSplitDirectionHorizontal,
     pub pane_split_direction_vertical: PaneSplitDirectionVertical,
     pub centered_layout: CenteredLayoutSettings,
+    pub zen_mode: ZenModeSettings,
     pub confirm_quit: bool,
     pub show_call_status_icon: bool,
     pub autosave: AutosaveSetting,
@@ -19,6 +21,7 @@ pub struct WorkspaceSettings {
     pub when_closing_with_no_tabs: CloseWindowWhenNoItems,
     pub use_system_path_prompts: bool,
     pub command_aliases: HashMap<String, String>,
+    pub window_title: Option<String>,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -73,6 +76,8 @@ pub struct WorkspaceSettingsContent {
     pub pane_split_direction_vertical: Option<PaneSplitDirectionVertical>,
     // Centered layout related settings.
     pub centered_layout: Option<CenteredLayoutSettings>,
+    // Zen mode related settings.
+    pub zen_mode: Option<ZenModeSettings>,
     /// Whether or not to prompt the user to confirm before closing the application.
     ///
     /// Default: false
@@ -108,6 +113,11 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: true
     pub command_aliases: Option<HashMap<String, String>>,
+    /// A template for the window title, or null for the default title. Supports the
+    /// `{project}`, `{file}`, `{path}`, `{dirty}` and `{branch}` variables.
+    ///
+    /// Default: null
+    pub window_title: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -128,19 +138,6 @@ pub struct TabBarSettingsContent {
     pub show_nav_history_buttons: Option<bool>,
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum AutosaveSetting {
-    /// Disable autosave.
-    Off,
-    /// Save after inactivity period of `milliseconds`.
-    AfterDelay { milliseconds: u64 },
-    /// Autosave when focus changes.
-    OnFocusChange,
-    /// Autosave when the active window changes.
-    OnWindowChange,
-}
-
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PaneSplitDirectionHorizontal {
@@ -168,6 +165,23 @@ pub struct CenteredLayoutSettings {
     ///
     /// Default: 0.2
     pub right_padding: Option<f32>,
+    /// The maximum width of the central pane's content, in pixels, when the
+    /// centered layout is used. When set, the paddings take up whatever space
+    /// is left, instead of using the relative paddings, and split panes are
+    /// centered too, with each column getting up to this width.
+    ///
+    /// Default: null
+    pub max_width: Option<f32>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ZenModeSettings {
+    /// The relative width of the central pane from the workspace when zen mode
+    /// is used.
+    ///
+    /// Default: 0.6
+    pub width: Option<f32>,
 }
 
 impl Settings for WorkspaceSettings {
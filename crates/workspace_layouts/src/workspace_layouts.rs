@@ -0,0 +1,119 @@
+use gpui::{actions, AppContext, SharedString, ViewContext, WeakView, WindowContext};
+use picker::named_items::{NamedItems, NamedItemsModal, NamedItemsMode};
+use util::ResultExt;
+use workspace::{
+    layouts::{self, NamedLayout},
+    Workspace,
+};
+
+actions!(workspace_layouts, [SaveLayout, RestoreLayout]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &SaveLayout, cx| {
+            let layout = NamedLayout {
+                name: String::new(),
+                layout: layouts::capture_layout(workspace, cx),
+            };
+            toggle_layouts(workspace, NamedItemsMode::Save(layout), cx);
+        });
+        workspace.register_action(|workspace, _: &RestoreLayout, cx| {
+            toggle_layouts(workspace, NamedItemsMode::Open, cx);
+        });
+    })
+    .detach();
+}
+
+/// Reads the saved layouts and the project's layout in the background, and then shows
+/// them in a modal.
+fn toggle_layouts(
+    workspace: &Workspace,
+    mode: NamedItemsMode<NamedLayout>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let load_project_layout = layouts::project_layout_name(workspace, cx);
+    let load_layouts = cx
+        .background_executor()
+        .spawn(async { layouts::load_layouts() });
+    cx.spawn(|workspace, mut cx| async move {
+        let project_layout = load_project_layout.await;
+        let saved_layouts = load_layouts.await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let layouts_handler = Layouts {
+                workspace: cx.view().downgrade(),
+                project_layout,
+            };
+            workspace.toggle_modal(cx, |cx| {
+                NamedItemsModal::new(layouts_handler, mode, saved_layouts, cx)
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The saved workspace layouts, listed in a [`NamedItemsModal`] to restore one of them or
+/// to save the current layout.
+///
+/// With the secondary confirm, the restored layout also becomes the one that's restored
+/// whenever the project is opened without a previous session.
+struct Layouts {
+    workspace: WeakView<Workspace>,
+    /// The name of the layout that's restored when the project is opened.
+    project_layout: Option<String>,
+}
+
+impl Layouts {
+    fn set_project_layout(&mut self, name: Option<String>, cx: &mut WindowContext) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                layouts::set_project_layout_name(workspace, name.clone(), cx)
+            })
+            .log_err();
+        self.project_layout = name;
+    }
+}
+
+impl NamedItems for Layouts {
+    type Item = NamedLayout;
+
+    const OPEN_PLACEHOLDER: &'static str = "Restore a layout…";
+    const SAVE_PLACEHOLDER: &'static str = "Name this layout…";
+    const NO_ITEMS_TEXT: &'static str = "No saved layouts";
+    const DELETE_TOOLTIP: &'static str = "Delete layout";
+
+    fn name(layout: &NamedLayout) -> &str {
+        &layout.name
+    }
+
+    fn set_name(layout: &mut NamedLayout, name: String) {
+        layout.name = name;
+    }
+
+    fn store(&mut self, layouts: &[NamedLayout], cx: &mut WindowContext) {
+        layouts::store_layouts(layouts, cx);
+    }
+
+    fn open(&mut self, layout: &NamedLayout, secondary: bool, cx: &mut WindowContext) {
+        if secondary {
+            let project_layout =
+                (self.project_layout.as_ref() != Some(&layout.name)).then(|| layout.name.clone());
+            self.set_project_layout(project_layout, cx);
+        }
+        self.workspace
+            .update(cx, |workspace, cx| {
+                layouts::apply_layout(workspace, &layout.layout, cx).detach()
+            })
+            .log_err();
+    }
+
+    fn deleted(&mut self, layout: &NamedLayout, cx: &mut WindowContext) {
+        if self.project_layout.as_ref() == Some(&layout.name) {
+            self.set_project_layout(None, cx);
+        }
+    }
+
+    fn details(&self, layout: &NamedLayout) -> Option<SharedString> {
+        (self.project_layout.as_ref() == Some(&layout.name))
+            .then(|| "restored for this project".into())
+    }
+}
@@ -11,6 +11,7 @@ use util::paths::PathMatcher;
 pub struct WorktreeSettings {
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    pub read_only_files: PathMatcher,
 }
 
 impl WorktreeSettings {
@@ -23,6 +24,11 @@ impl WorktreeSettings {
         path.ancestors()
             .any(|ancestor| self.file_scan_exclusions.is_match(&ancestor))
     }
+
+    pub fn is_path_read_only(&self, path: &Path) -> bool {
+        path.ancestors()
+            .any(|ancestor| self.read_only_files.is_match(&ancestor))
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -45,6 +51,11 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Open the files matching these globs as read-only. Globs are matched against
+    /// both the path within the project and the absolute path of the file.
+    /// Default: [ "**/.cargo/registry", "**/.rustup/toolchains", "**/go/pkg/mod" ]
+    pub read_only_files: Option<Vec<String>>,
 }
 
 impl Settings for WorktreeSettings {
@@ -59,11 +70,14 @@ impl Settings for WorktreeSettings {
         let result: WorktreeSettingsContent = sources.json_merge()?;
         let mut file_scan_exclusions = result.file_scan_exclusions.unwrap_or_default();
         let mut private_files = result.private_files.unwrap_or_default();
+        let mut read_only_files = result.read_only_files.unwrap_or_default();
         file_scan_exclusions.sort();
         private_files.sort();
+        read_only_files.sort();
         Ok(Self {
             file_scan_exclusions: path_matchers(&file_scan_exclusions, "file_scan_exclusions")?,
             private_files: path_matchers(&private_files, "private_files")?,
+            read_only_files: path_matchers(&read_only_files, "read_only_files")?,
         })
     }
 }
@@ -233,6 +233,7 @@ fn init_ui(
     crate::zed::linux_prompts::init(cx);
 
     app_state.languages.set_theme(cx.theme().clone());
+    spellcheck::init(app_state.fs.clone(), cx);
     editor::init(cx);
     image_viewer::init(cx);
     diagnostics::init(cx);
@@ -244,11 +245,13 @@ fn init_ui(
     go_to_line::init(cx);
     file_finder::init(cx);
     tab_switcher::init(cx);
+    workspace_layouts::init(cx);
     dev_server_projects::init(app_state.client.clone(), cx);
     outline::init(cx);
     project_symbols::init(cx);
     project_panel::init(Assets, cx);
     outline_panel::init(Assets, cx);
+    hierarchy_panel::init(cx);
     tasks_ui::init(cx);
     channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
     search::init(cx);
@@ -256,7 +259,10 @@ fn init_ui(
     terminal_view::init(cx);
     journal::init(app_state.clone(), cx);
     language_selector::init(cx);
+    encoding_selector::init(cx);
+    line_ending_selector::init(cx);
     theme_selector::init(cx);
+    settings_profile_selector::init(cx);
     language_tools::init(cx);
     call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
     notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -301,7 +307,9 @@ fn init_ui(
     let fs = app_state.fs.clone();
     load_user_themes_in_background(fs.clone(), cx);
     watch_themes(fs.clone(), cx);
+    watch_icon_themes(fs.clone(), cx);
     watch_languages(fs.clone(), app_state.languages.clone(), cx);
+    languages::watch_user_languages(fs.clone(), app_state.languages.clone(), cx);
     watch_file_types(fs.clone(), cx);
 
     cx.set_menus(app_menus());
@@ -849,6 +857,7 @@ fn init_paths() -> anyhow::Result<()> {
         paths::config_dir(),
         paths::extensions_dir(),
         paths::languages_dir(),
+        paths::icon_themes_dir(),
         paths::database_dir(),
         paths::logs_dir(),
         paths::temp_dir(),
@@ -1141,19 +1150,29 @@ fn watch_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
 
         while let Some(paths) = events.next().await {
             for path in paths {
-                if fs.metadata(&path).await.ok().flatten().is_some() {
-                    if let Some(theme_registry) =
-                        cx.update(|cx| ThemeRegistry::global(cx).clone()).log_err()
-                    {
-                        if let Some(()) = theme_registry
-                            .load_user_theme(&path, fs.clone())
-                            .await
-                            .log_err()
-                        {
-                            cx.update(|cx| ThemeSettings::reload_current_theme(cx))
-                                .log_err();
-                        }
-                    }
+                if path
+                    .extension()
+                    .map_or(true, |extension| extension != "json")
+                {
+                    continue;
+                }
+                let Some(theme_registry) =
+                    cx.update(|cx| ThemeRegistry::global(cx).clone()).log_err()
+                else {
+                    continue;
+                };
+                let reload = if fs.metadata(&path).await.ok().flatten().is_some() {
+                    theme_registry
+                        .load_user_theme(&path, fs.clone())
+                        .await
+                        .log_err()
+                        .is_some()
+                } else {
+                    theme_registry.remove_user_theme(&path)
+                };
+                if reload {
+                    cx.update(|cx| ThemeSettings::reload_current_theme(cx))
+                        .log_err();
                 }
             }
         }
@@ -1161,6 +1180,67 @@ fn watch_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
     .detach()
 }
 
+/// Spawns a background task to load the user icon themes from the icon themes
+/// directory and read their icons, and to reload them when they change.
+fn watch_icon_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
+    use file_icons::FileIcons;
+    use std::time::Duration;
+
+    cx.spawn(|cx| async move {
+        let icon_themes_dir = paths::icon_themes_dir();
+        fs.create_dir(icon_themes_dir).await.log_err();
+        let (mut events, _) = fs.watch(icon_themes_dir, Duration::from_millis(100)).await;
+
+        let mut paths = match fs.read_dir(icon_themes_dir).await {
+            Ok(entries) => {
+                entries
+                    .filter_map(|entry| async move { entry.log_err() })
+                    .collect::<Vec<_>>()
+                    .await
+            }
+            Err(_) => Vec::new(),
+        };
+        loop {
+            let mut icons_to_read = Vec::new();
+            for path in paths {
+                if path
+                    .extension()
+                    .map_or(true, |extension| extension != "json")
+                {
+                    // This may be one of the icons of an icon theme.
+                    icons_to_read.push(path);
+                    continue;
+                }
+                let content = fs.load(&path).await.ok();
+                let icon_paths = cx.update(|cx| {
+                    FileIcons::update_global(cx, |file_icons, _| {
+                        file_icons.remove_icon_theme(&path);
+                        file_icons.insert_icon_theme(&path, &content?).log_err()
+                    })
+                })?;
+                icons_to_read.extend(icon_paths.into_iter().flatten());
+            }
+
+            let used_icons = cx.update(|cx| FileIcons::get(cx).icon_paths())?;
+            Assets::retain_user_icons(|path| used_icons.contains(path));
+            for icon_path in icons_to_read {
+                if used_icons.contains(&icon_path) {
+                    let content = fs.load_bytes(&icon_path).await.log_err();
+                    Assets::set_user_icon(icon_path, content);
+                }
+            }
+            cx.update(|cx| cx.refresh())?;
+
+            let Some(changed_paths) = events.next().await else {
+                break;
+            };
+            paths = changed_paths;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx)
+}
+
 #[cfg(debug_assertions)]
 fn watch_languages(fs: Arc<dyn fs::Fs>, languages: Arc<LanguageRegistry>, cx: &mut AppContext) {
     use std::time::Duration;
@@ -1212,7 +1292,7 @@ fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
         while (events.next().await).is_some() {
             cx.update(|cx| {
                 FileIcons::update_global(cx, |file_types, _cx| {
-                    *file_types = file_icons::FileIcons::new(Assets);
+                    file_types.reload_default(Assets);
                 });
             })
             .ok();
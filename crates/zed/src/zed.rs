@@ -29,8 +29,8 @@ use release_channel::{AppCommitSha, ReleaseChannel};
 use rope::Rope;
 use search::project_search::ProjectSearchBar;
 use settings::{
-    initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile, Settings,
-    SettingsStore, DEFAULT_KEYMAP_PATH,
+    initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile,
+    LoadedKeymaps, Settings, SettingsStore, DEFAULT_KEYMAP_PATH,
 };
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
 use task::static_source::{StaticSource, TrackedFile};
@@ -197,6 +197,10 @@ pub fn initialize_workspace(
             activity_indicator::ActivityIndicator::new(workspace, app_state.languages.clone(), cx);
         let active_buffer_language =
             cx.new_view(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_encoding =
+            cx.new_view(|_| encoding_selector::ActiveBufferEncoding::new(workspace));
+        let active_buffer_line_ending =
+            cx.new_view(|_| line_ending_selector::ActiveBufferLineEnding::new(workspace));
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
         let cursor_position =
             cx.new_view(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
@@ -204,6 +208,8 @@ pub fn initialize_workspace(
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
             status_bar.add_right_item(inline_completion_button, cx);
+            status_bar.add_right_item(active_buffer_line_ending, cx);
+            status_bar.add_right_item(active_buffer_encoding, cx);
             status_bar.add_right_item(active_buffer_language, cx);
             status_bar.add_right_item(vim_mode_indicator, cx);
             status_bar.add_right_item(cursor_position, cx);
@@ -287,7 +293,9 @@ pub fn initialize_workspace(
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
                 cx.focus_self();
-            })
+            })?;
+
+            Workspace::load_registered_panels(workspace_handle, cx).await
         })
         .detach();
 
@@ -784,24 +792,28 @@ fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
     keymap_content.clone().add_to_cx(cx).log_err();
+    cx.default_global::<LoadedKeymaps>().user_keymap = keymap_content.clone();
     cx.set_menus(app_menus());
     cx.set_dock_menu(vec![MenuItem::action("New Window", workspace::NewWindow)])
 }
 
 pub fn load_default_keymap(cx: &mut AppContext) {
     let base_keymap = *BaseKeymap::get_global(cx);
-    if base_keymap == BaseKeymap::None {
-        return;
-    }
-
-    KeymapFile::load_asset(DEFAULT_KEYMAP_PATH, cx).unwrap();
-    if VimModeSetting::get_global(cx).0 {
-        KeymapFile::load_asset("keymaps/vim.json", cx).unwrap();
+    let mut asset_paths = Vec::new();
+    if base_keymap != BaseKeymap::None {
+        asset_paths.push(DEFAULT_KEYMAP_PATH);
+        if VimModeSetting::get_global(cx).0 {
+            asset_paths.push("keymaps/vim.json");
+        }
+        if let Some(asset_path) = base_keymap.asset_path() {
+            asset_paths.push(asset_path);
+        }
     }
 
-    if let Some(asset_path) = base_keymap.asset_path() {
+    for asset_path in &asset_paths {
         KeymapFile::load_asset(asset_path, cx).unwrap();
     }
+    cx.default_global::<LoadedKeymaps>().asset_paths = asset_paths;
 }
 
 fn open_local_settings_file(
@@ -3387,6 +3399,7 @@ mod tests {
             reveal: RevealStrategy::Always,
             hide: HideStrategy::Never,
             shell: Shell::System,
+            problem_matcher: None,
         };
         let project = Project::test(app_state.fs.clone(), [project_root.path()], cx).await;
         let window = cx.add_window(|cx| Workspace::test_new(project, cx));